@@ -0,0 +1,123 @@
+//! `oracle acpc` — play a saved solution against an ACPC dealer
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use oracle_engine::acpc::{parse_match_state, AcpcConfig};
+use oracle_engine::file_format::load_solution;
+use oracle_engine::node::Action;
+use oracle_engine::rng::{Rng, DEFAULT_SEED};
+use oracle_engine::Solution;
+
+/// Protocol version sent on connect
+const VERSION_LINE: &str = "VERSION:2.0.0\r\n";
+
+/// Run `oracle acpc` with the arguments after the subcommand
+pub fn run_acpc(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut connect = None;
+    let mut config = AcpcConfig::new();
+    let mut seed = DEFAULT_SEED;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--connect", Some(v)) => {
+                connect = Some(v.to_string());
+                i += 2;
+            }
+            ("--bb-chips", Some(v)) => {
+                config.big_blind_chips = v.parse().ok().filter(|&n| n > 0).ok_or(format!("bad --bb-chips '{}'", v))?;
+                i += 2;
+            }
+            ("--seed", Some(v)) => {
+                seed = v.parse().map_err(|_| format!("bad --seed '{}'", v))?;
+                i += 2;
+            }
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (file, _) => {
+                path = Some(file.to_string());
+                i += 1;
+            }
+        }
+    }
+    let (Some(path), Some(connect)) = (path, connect) else {
+        return Err("usage: oracle acpc SOLUTION --connect HOST:PORT [--bb-chips N] [--seed N]".to_string());
+    };
+
+    let solution = load_solution(&path).map_err(|e| format!("{}: {}", path, e))?;
+    solution.validate().map_err(|e| format!("{}: {}", path, e))?;
+    let stream = TcpStream::connect(&connect).map_err(|e| format!("{}: {}", connect, e))?;
+    let mut writer = stream.try_clone().map_err(|e| e.to_string())?;
+    writer.write_all(VERSION_LINE.as_bytes()).map_err(|e| e.to_string())?;
+    println!("Connected to {}", connect);
+
+    let mut rng = Rng::new(seed);
+    let (mut from_tree, mut fallback) = (0u64, 0u64);
+    for line in BufReader::new(stream).lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let Some((reply, played)) = respond(&solution, &line, &config, &mut rng) else { continue };
+        writer.write_all(format!("{}\r\n", reply).as_bytes()).map_err(|e| e.to_string())?;
+        if played { from_tree += 1 } else { fallback += 1 }
+    }
+    println!("Dealer closed the match: {} actions from the solution, {} check/call fallbacks", from_tree, fallback);
+    Ok(())
+}
+
+/// Reply to one dealer line if it's our turn, and whether the action came
+/// from the solution. States the tree can't place (preflop, another spot,
+/// an unreadable line) get a check or call, which is always legal.
+fn respond(solution: &Solution, line: &str, config: &AcpcConfig, rng: &mut Rng) -> Option<(String, bool)> {
+    if line.starts_with('#') || line.starts_with(';') {
+        return None;
+    }
+    let state = match parse_match_state(line) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("{}", e);
+            return None;
+        }
+    };
+    if !state.is_our_turn() {
+        return None;
+    }
+    let action = state.game_state(config).ok()
+        .zip(state.hole_cards[state.position])
+        .and_then(|(game, hole)| solution.act(&game, hole, rng).ok());
+    Some((state.reply(action.unwrap_or(Action::Call), config), action.is_some()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::builder::{build_tree, StreetSizes, TreeConfig};
+    use oracle_engine::game::GameConfig;
+    use oracle_engine::node::{parse_cards, Node};
+    use oracle_engine::CfrSolver;
+
+    #[test]
+    fn test_respond_plays_tree_spots_and_falls_back() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d").unwrap());
+        config.flop = StreetSizes::new(vec![0.5]);
+        config.turn = StreetSizes::new(Vec::new());
+        config.river = StreetSizes::new(Vec::new());
+        config.max_raises = 1;
+        let mut solution = Solution::from_solver(&CfrSolver::new_with_evs(build_tree(&config), oracle_engine::EvTable::new()));
+        // Root (OOP): always bet
+        solution.storage.strategy_sums[0] = vec![0.0, 1.0];
+        let (acpc, mut rng) = (AcpcConfig::new(), Rng::new(1));
+        let Some(Node::Decision { actions, .. }) = solution.tree.get(0) else { panic!("root is a decision") };
+        let Action::Bet { size } = actions[1] else { panic!("second root action is the bet") };
+
+        let flop = "MATCHSTATE:0:5:cr300c/:Td9d|/AsKh7d";
+        let (reply, played) = respond(&solution, flop, &acpc, &mut rng).unwrap();
+        assert!(played);
+        assert_eq!(reply, format!("{}:r{}", flop, 300 + (size * 100.0).round() as u64));
+
+        // Preflop and the opponent's turn
+        let (reply, played) = respond(&solution, "MATCHSTATE:1:6::|Td9d", &acpc, &mut rng).unwrap();
+        assert_eq!((reply.as_str(), played), ("MATCHSTATE:1:6::|Td9d:c", false));
+        assert_eq!(respond(&solution, "MATCHSTATE:0:6::Td9d|", &acpc, &mut rng), None);
+        assert_eq!(respond(&solution, "# comment", &acpc, &mut rng), None);
+    }
+}
@@ -0,0 +1,317 @@
+//! `oracle analyze` — per-line EV-loss report from hand histories
+//!
+//! `oracle analyze hh_folder/ --solutions lib/ --player alice` reads every
+//! ACPC dealer log in the folder (`STATE` lines, `acpc::parse_log_line`;
+//! other lines are skipped) and looks each hand up in the library: the
+//! first `*.bin` solution, in name order, whose root board starts the
+//! hand's board and whose root pot is the pot the hand had on that street.
+//! The player's decisions from there on are graded (`review::review_hand`)
+//! and added up per line (`review::leak_report`); without `--player` every
+//! seat is graded.
+//!
+//! Grading needs per-hand EVs, so each solution must carry its ranges; the
+//! first hand that needs one re-solves its tree with the vector solver for
+//! `--iterations` (default 500). `--bb-chips` sets the log's big blind.
+//!
+//! `--all-in-ev` adds each player's results: bb won as logged, and bb won
+//! with hands all-in before the river paid at all-in EV
+//! (`LoggedHand::all_in_ev`), which takes out most of the runout luck.
+
+use std::path::Path;
+use oracle_engine::acpc::{parse_log_line, AcpcConfig, LoggedHand};
+use oracle_engine::coach::Grade;
+use oracle_engine::file_format::load_solution;
+use oracle_engine::review::{leak_report, review_hand, ReviewedDecision};
+use oracle_engine::vector_cfr::VectorCfrSolver;
+use oracle_engine::Solution;
+
+/// Vector-solver iterations per library solution when `--iterations` isn't given
+const DEFAULT_ITERATIONS: u64 = 500;
+
+/// What came of reviewing a set of hands
+#[derive(Debug, Default)]
+pub struct Analysis {
+    pub hands: usize,
+    pub reviewed: usize,
+    /// Hands no library solution matches (including hands over preflop)
+    pub unmatched: usize,
+    /// Hands that matched but couldn't be replayed or graded
+    pub failed: usize,
+    pub decisions: Vec<ReviewedDecision>,
+}
+
+/// One player's winnings over the hands read
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerResult {
+    pub player: String,
+    pub hands: usize,
+    /// Hands all-in before the river
+    pub all_ins: usize,
+    /// bb won as logged
+    pub won: f64,
+    /// bb won with all-in hands paid at all-in EV
+    pub all_in_ev: f64,
+}
+
+/// Run `oracle analyze` with the arguments after the subcommand
+pub fn run_analyze(args: &[String]) -> Result<(), String> {
+    let mut histories = None;
+    let mut solutions = None;
+    let mut player = None;
+    let mut iterations = DEFAULT_ITERATIONS;
+    let mut config = AcpcConfig::new();
+    let mut all_in_ev = false;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--all-in-ev", _) => {
+                all_in_ev = true;
+                i += 1;
+                continue;
+            }
+            ("--solutions", Some(v)) => solutions = Some(v.to_string()),
+            ("--player", Some(v)) => player = Some(v.to_string()),
+            ("--iterations", Some(v)) => iterations = v.parse().map_err(|_| format!("invalid --iterations '{}'", v))?,
+            ("--bb-chips", Some(v)) => config.big_blind_chips = v.parse().ok().filter(|&n| n > 0).ok_or(format!("bad --bb-chips '{}'", v))?,
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (path, _) => {
+                histories = Some(path.to_string());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+    let usage = "usage: oracle analyze HH_DIR --solutions DIR [--player NAME] [--iterations N] [--bb-chips N] [--all-in-ev]";
+    let (histories, solutions) = histories.zip(solutions).ok_or(usage)?;
+
+    let hands = load_hands(Path::new(&histories))?;
+    let library = load_library(Path::new(&solutions))?;
+    if library.is_empty() {
+        return Err(format!("{}: no .bin solutions with ranges", solutions));
+    }
+    let analysis = analyze(&hands, &library, player.as_deref(), iterations, &config);
+    print!("{}", format_report(&analysis));
+    if all_in_ev {
+        print!("\n{}", format_results(&player_results(&hands, player.as_deref(), &config)));
+    }
+    Ok(())
+}
+
+/// Every hand in the `STATE` lines of `path`, a log file or a folder of them
+fn load_hands(path: &Path) -> Result<Vec<LoggedHand>, String> {
+    let mut files = vec![path.to_path_buf()];
+    if path.is_dir() {
+        let entries = std::fs::read_dir(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        files = entries.filter_map(|e| e.ok().map(|e| e.path())).filter(|p| p.is_file()).collect();
+        files.sort();
+    }
+    let mut hands = Vec::new();
+    for file in &files {
+        let text = std::fs::read_to_string(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+        for (n, line) in text.lines().enumerate().filter(|(_, l)| l.starts_with("STATE:")) {
+            hands.push(parse_log_line(line).map_err(|e| format!("{}:{}: {}", file.display(), n + 1, e))?);
+        }
+    }
+    Ok(hands)
+}
+
+/// Every `*.bin` solution in `dir` with ranges, in name order
+fn load_library(dir: &Path) -> Result<Vec<Solution>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "bin"))
+        .collect();
+    paths.sort();
+    let mut library = Vec::new();
+    for path in paths {
+        let solution = load_solution(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        solution.validate().map_err(|e| format!("{}: {}", path.display(), e))?;
+        if solution.ranges.is_none() {
+            eprintln!("Skipping {}: no ranges to grade hands with", path.display());
+            continue;
+        }
+        library.push(solution);
+    }
+    Ok(library)
+}
+
+/// Grade `player`'s decisions (every seat's when `None`) in `hands`
+pub fn analyze(hands: &[LoggedHand], library: &[Solution], player: Option<&str>, iterations: u64, config: &AcpcConfig) -> Analysis {
+    let mut solvers: Vec<Option<VectorCfrSolver>> = library.iter().map(|_| None).collect();
+    let mut analysis = Analysis::default();
+    for hand in hands {
+        if player.is_some_and(|p| !hand.players.iter().any(|name| name == p)) {
+            continue;
+        }
+        analysis.hands += 1;
+        let Some((index, round)) = find_solution(library, hand, config) else {
+            analysis.unmatched += 1;
+            continue;
+        };
+        let solver = solvers[index].get_or_insert_with(|| {
+            let solution = &library[index];
+            let ranges = solution.ranges.as_ref().expect("library solutions have ranges");
+            let mut solver = VectorCfrSolver::new(solution.tree.clone(), ranges);
+            for _ in 0..iterations {
+                solver.run_iteration();
+            }
+            solver
+        });
+        // Seat 0 is OOP and seat 1 IP; hole cards go `[IP, OOP]`
+        let seen = |seat: usize| hand.state.hole_cards[seat].filter(|_| player.is_none_or(|p| hand.players[seat] == p));
+        let graded = hand.state.game_state_from(round, config).map_err(|e| e.to_string())
+            .and_then(|state| review_hand(solver, hand.state.hand_number, &state, [seen(1), seen(0)]).map_err(|e| e.to_string()));
+        match graded {
+            Ok(decisions) => {
+                analysis.reviewed += 1;
+                analysis.decisions.extend(decisions);
+            }
+            Err(e) => {
+                eprintln!("Hand {}: {}", hand.state.hand_number, e);
+                analysis.failed += 1;
+            }
+        }
+    }
+    analysis
+}
+
+/// The first solution rooted at a street the hand reached, on the hand's
+/// board and pot, with that street's betting round
+fn find_solution(library: &[Solution], hand: &LoggedHand, config: &AcpcConfig) -> Option<(usize, usize)> {
+    library.iter().enumerate().find_map(|(index, solution)| {
+        let root = solution.tree.get(0)?;
+        let board = root.board();
+        let round = board.len().checked_sub(2)?;
+        let on_board = hand.state.board.len() >= board.len() && board.iter().all(|c| hand.state.board[..board.len()].contains(c));
+        let pot_matches = (root.pot() - hand.state.pot_before(round, config)).abs() < 1e-6;
+        (on_board && hand.state.rounds.len() > round && pot_matches).then_some((index, round))
+    })
+}
+
+/// Winnings per player (just `player` when given), in order of first hand
+pub fn player_results(hands: &[LoggedHand], player: Option<&str>, config: &AcpcConfig) -> Vec<PlayerResult> {
+    let bb = config.big_blind_chips as f64;
+    let mut results: Vec<PlayerResult> = Vec::new();
+    for hand in hands {
+        let all_in = hand.all_in_ev(config);
+        for seat in 0..2 {
+            let name = &hand.players[seat];
+            if player.is_some_and(|p| p != name) {
+                continue;
+            }
+            let index = match results.iter().position(|r| &r.player == name) {
+                Some(index) => index,
+                None => {
+                    results.push(PlayerResult { player: name.clone(), hands: 0, all_ins: 0, won: 0.0, all_in_ev: 0.0 });
+                    results.len() - 1
+                }
+            };
+            let result = &mut results[index];
+            result.hands += 1;
+            result.all_ins += usize::from(all_in.is_some());
+            result.won += hand.outcome[seat] / bb;
+            result.all_in_ev += all_in.map_or(hand.outcome[seat], |ev| ev[seat]) / bb;
+        }
+    }
+    results
+}
+
+/// One row per player: hands, all-ins, bb won and all-in EV
+fn format_results(results: &[PlayerResult]) -> String {
+    let mut out = format!("{:<16} {:>6} {:>7} {:>10} {:>10}\n", "player", "hands", "all-in", "won bb", "all-in EV");
+    for r in results {
+        out.push_str(&format!("{:<16} {:>6} {:>7} {:>10.2} {:>10.2}\n", r.player, r.hands, r.all_ins, r.won, r.all_in_ev));
+    }
+    out
+}
+
+/// Summary lines, then one row per line, largest total loss first
+fn format_report(analysis: &Analysis) -> String {
+    let total: f64 = analysis.decisions.iter().map(|d| d.grade.loss_vs_best).sum();
+    let mut out = format!(
+        "Hands: {} read, {} reviewed, {} without a matching solution, {} failed\n",
+        analysis.hands, analysis.reviewed, analysis.unmatched, analysis.failed
+    );
+    out.push_str(&format!("Decisions graded: {}, EV lost vs best: {:.2} bb\n\n", analysis.decisions.len(), total));
+    let grades: Vec<String> = Grade::ALL.iter().map(|g| format!("{:>10}", g.to_string())).collect();
+    out.push_str(&format!("{:<4} {:<24} {:>5} {:>9} {:>8} {}\n", "pos", "line", "seen", "loss bb", "mean bb", grades.join("")));
+    for leak in leak_report(&analysis.decisions) {
+        let counts: Vec<String> = leak.grades.iter().map(|n| format!("{:>10}", n)).collect();
+        out.push_str(&format!(
+            "{:<4} {:<24} {:>5} {:>9.2} {:>8.3} {}\n",
+            format!("{:?}", leak.player),
+            leak.line,
+            leak.decisions,
+            leak.total_loss,
+            leak.mean_loss(),
+            counts.join("")
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::file_format::{save_solution, Compression};
+    use oracle_engine::node::parse_cards;
+    use oracle_engine::range::Range;
+    use oracle_engine::solve::{solve, SolveSpec};
+    use oracle_engine::StopCondition;
+
+    #[test]
+    fn test_analyze_grades_matching_hands_per_line() {
+        let dir = std::env::temp_dir().join(format!("oracle_analyze_test_{}", std::process::id()));
+        let (lib, hh) = (dir.join("lib"), dir.join("hh"));
+        std::fs::create_dir_all(&lib).unwrap();
+        std::fs::create_dir_all(&hh).unwrap();
+        // Limped preflop and checked flop and turn: a 2bb river pot
+        let ranges = [Range::parse("AA,KK,AK").unwrap(), Range::parse("QQ-99,AQ").unwrap()];
+        let mut spec = SolveSpec::from_state(parse_cards("AsKh7d2c9s").unwrap(), ranges.clone(), 2.0, [98.0, 98.0]);
+        spec.stop = StopCondition::iterations(20);
+        let solution = Solution::from_solver(&solve(&spec).unwrap().solver).with_ranges(ranges);
+        save_solution(lib.join("river.bin"), &solution, Compression::None).unwrap();
+        std::fs::write(
+            hh.join("match.log"),
+            "# dealer log\n\
+             STATE:1:cc/cc/cc/cr200f:QcQd|AhKd/AsKh7d/2c/9s:-100|100:alice|bob\n\
+             STATE:2:cc/cc/cc/cc:QhJh|AhKd/AsKh7d/2c/9s:0|0:alice|bob\n\
+             STATE:3:cc/cc/cc/r200f:TcTd|AhKd/AsKh7d/2c/8s:100|-100:alice|bob\n\
+             STATE:4:f:QcQd|AhKd:-50|50:carol|bob\n\
+             STATE:5:cc/r300c/r20000c/:KcKd|AhAd/AsKh7d/2c/Ks:20000|-20000:alice|bob\n",
+        )
+        .unwrap();
+
+        let hands = load_hands(&hh).unwrap();
+        assert_eq!(hands.len(), 5);
+        let library = load_library(&lib).unwrap();
+        let analysis = analyze(&hands, &library, Some("alice"), 100, &AcpcConfig::new());
+        // Hand 1's pot-sized bet maps to the tree's 75% bet; hand 2: QJs isn't
+        // in alice's range; hand 3: another river card
+        assert_eq!((analysis.hands, analysis.reviewed, analysis.unmatched, analysis.failed), (4, 1, 2, 1));
+        let lines: Vec<&str> = analysis.decisions.iter().map(|d| d.line.as_str()).collect();
+        assert_eq!(lines, vec!["root", "R:x-b75"]);
+
+        let report = format_report(&analysis);
+        assert!(report.starts_with("Hands: 4 read, 1 reviewed, 2 without a matching solution, 1 failed\nDecisions graded: 2,"), "{}", report);
+        assert!(report.contains("\nOOP  R:x-b75"), "{}", report);
+
+        // Hand 5's kings hit a 1-in-44 river: 200bb won, 200 × (2/44 - 1) at all-in EV
+        let results = player_results(&hands, None, &AcpcConfig::new());
+        let names: Vec<&str> = results.iter().map(|r| r.player.as_str()).collect();
+        assert_eq!(names, vec!["alice", "bob", "carol"]);
+        let alice = &results[0];
+        assert_eq!((alice.hands, alice.all_ins), (4, 1));
+        assert!((alice.won - 200.0).abs() < 1e-9);
+        assert!((alice.all_in_ev - 200.0 * (2.0 / 44.0 - 1.0)).abs() < 1e-6, "{:?}", alice);
+        assert!((results[1].all_in_ev + alice.all_in_ev - 0.5).abs() < 1e-6);
+        assert_eq!(player_results(&hands, Some("carol"), &AcpcConfig::new()).len(), 1);
+        assert!(format_results(&results).contains("     200.00    -190.91\n"), "{}", format_results(&results));
+        assert!(run_analyze(&[hh.display().to_string()]).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
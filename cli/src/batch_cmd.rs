@@ -0,0 +1,535 @@
+//! `oracle batch jobs.toml` — solve a list of spots and write a manifest
+//!
+//! A job file has optional `[defaults]` and one `[[spot]]` table per spot.
+//! Every key may appear in either place; spot values win. A spot with
+//! `boards = [...]` expands into one job per board:
+//!
+//! ```toml
+//! [defaults]
+//! pot = 10
+//! stacks = 95                # or [IP, OOP]
+//! ip_range = "AA-TT,AK,AQs"
+//! oop_range = "99-55,AJ,KQ"
+//! river_bets = "66,allin"
+//! iterations = 2000
+//! target = 0.01
+//! units = "pot"              # target in % of the starting pot (default: bb)
+//!
+//! [[spot]]
+//! name = "dry"
+//! boards = ["AsKh7d2c9s", "Ks8d3c2h2d"]
+//!
+//! [[spot]]
+//! board = "Td9d8c6s2h"
+//! stacks = [40, 200]
+//!
+//! [[spot]]
+//! name = "low-paired"
+//! sample = { count = 5, cards = 5, filter = "paired,high<=T", seed = 7 }
+//! ```
+//!
+//! Boards may be flops, turns, or rivers. A 4- or 5-card board builds only
+//! the streets left; set `pot` and `stacks` to what the earlier action left
+//! to re-solve a hand from there.
+//!
+//! `sample` draws `count` distinct random boards (`cards` long, default 3)
+//! passing a `BoardFilter` spec, reproducibly from `seed`.
+//!
+//! `stack_sweep = [20, 40, 75, 100, 150]` solves each of a spot's boards
+//! once per depth instead of at `stacks`; entries may also be `[IP, OOP]`.
+//! Jobs get a `-<stack>bb` suffix, so the manifest lines up the depths for
+//! comparison (`sweep::stack_sweep` reports EV and strategy shifts directly).
+//!
+//! Spots run in parallel on one Rayon pool (`threads`, default all cores);
+//! each writes `<out>/<name>.bin`, and `<out>/manifest.toml` records every
+//! spot's file, iterations, exploitability (in bb and as % of the starting
+//! pot), stop reason (or error), and how mixed its strategy is (`analysis::MixingSummary`: mean entropy in bits,
+//! share of pure decisions, mean support size).
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use oracle_engine::analysis::{strategy_mixing, MixingSummary};
+use oracle_engine::file_format::{save_solution, Compression};
+use oracle_engine::board_gen::BoardSampler;
+use oracle_engine::game::GameConfig;
+use oracle_engine::node::{format_cards, parse_cards};
+use oracle_engine::range::Range;
+use oracle_engine::solve::{solve, SolveSpec};
+use oracle_engine::units::{Unit, UnitParseError, Units};
+use oracle_engine::Solution;
+use crate::tree_cmd::street_sizes;
+
+/// Stacks as one number for both players or `[IP, OOP]`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Stacks {
+    Both(f64),
+    Each([f64; 2]),
+}
+
+/// Random boards for a spot: `count` distinct boards matching `filter`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BoardSample {
+    pub count: usize,
+    /// Board size (default 3)
+    pub cards: Option<usize>,
+    /// Comma-separated `BoardFilter` spec, e.g. "monotone,high<=T"
+    pub filter: Option<String>,
+    pub seed: Option<u64>,
+}
+
+impl BoardSample {
+    fn boards(&self) -> Result<Vec<String>, String> {
+        let filter = self.filter.as_deref().unwrap_or("");
+        let mut sampler = BoardSampler::parse(self.cards.unwrap_or(3), filter, self.seed.unwrap_or(0)).map_err(|e| e.to_string())?;
+        let mut boards: Vec<String> = Vec::with_capacity(self.count);
+        // Repeats are redrawn; a filter with too few boards runs out of tries
+        for _ in 0..self.count.saturating_mul(100) {
+            if boards.len() == self.count {
+                break;
+            }
+            let board = format_cards(&sampler.sample().map_err(|e| e.to_string())?);
+            if !boards.contains(&board) {
+                boards.push(board);
+            }
+        }
+        if boards.len() < self.count {
+            return Err(format!("only {} distinct boards match '{}'", boards.len(), filter));
+        }
+        Ok(boards)
+    }
+}
+
+/// Keys allowed in `[defaults]` and in each `[[spot]]`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpotSettings {
+    pub name: Option<String>,
+    pub board: Option<String>,
+    pub boards: Option<Vec<String>>,
+    pub sample: Option<BoardSample>,
+    pub pot: Option<f64>,
+    pub stacks: Option<Stacks>,
+    pub stack_sweep: Option<Vec<Stacks>>,
+    pub ip_range: Option<String>,
+    pub oop_range: Option<String>,
+    pub flop_bets: Option<String>,
+    pub turn_bets: Option<String>,
+    pub river_bets: Option<String>,
+    pub max_raises: Option<usize>,
+    pub iterations: Option<u64>,
+    pub target: Option<f64>,
+    /// Unit `target` is given in (`units::Unit` name; default bb)
+    pub units: Option<String>,
+    pub check_every: Option<u64>,
+}
+
+impl SpotSettings {
+    /// `self` with unset keys filled from `defaults`
+    fn or(self, defaults: &SpotSettings) -> SpotSettings {
+        let d = defaults.clone();
+        SpotSettings {
+            name: self.name.or(d.name),
+            board: self.board.or(d.board),
+            boards: self.boards.or(d.boards),
+            sample: self.sample.or(d.sample),
+            pot: self.pot.or(d.pot),
+            stacks: self.stacks.or(d.stacks),
+            stack_sweep: self.stack_sweep.or(d.stack_sweep),
+            ip_range: self.ip_range.or(d.ip_range),
+            oop_range: self.oop_range.or(d.oop_range),
+            flop_bets: self.flop_bets.or(d.flop_bets),
+            turn_bets: self.turn_bets.or(d.turn_bets),
+            river_bets: self.river_bets.or(d.river_bets),
+            max_raises: self.max_raises.or(d.max_raises),
+            iterations: self.iterations.or(d.iterations),
+            target: self.target.or(d.target),
+            units: self.units.or(d.units),
+            check_every: self.check_every.or(d.check_every),
+        }
+    }
+}
+
+/// Parsed job file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobFile {
+    #[serde(default)]
+    pub defaults: SpotSettings,
+    #[serde(default)]
+    pub spot: Vec<SpotSettings>,
+    /// Solver threads for the whole batch (default: all cores)
+    pub threads: Option<usize>,
+    /// Output directory (default: `solutions`)
+    pub out: Option<PathBuf>,
+    /// zstd-compress solution files
+    #[serde(default)]
+    pub compress: bool,
+}
+
+/// One spot ready to solve
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub name: String,
+    pub spec: SolveSpec,
+}
+
+/// One line of `manifest.toml`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub board: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exploitability: Option<f64>,
+    /// `exploitability` as % of the starting pot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exploitability_pct_pot: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_entropy: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pure_fraction: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean_support: Option<f64>,
+    pub seconds: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Manifest<'a> {
+    spot: &'a [ManifestEntry],
+}
+
+/// Expand every `[[spot]]` (and each of its `boards`) into jobs
+pub fn expand_jobs(file: &JobFile) -> Result<Vec<Job>, String> {
+    let mut jobs = Vec::new();
+    for (index, spot) in file.spot.iter().enumerate() {
+        let settings = spot.clone().or(&file.defaults);
+        let boards = match (&settings.boards, &settings.board, &settings.sample) {
+            (Some(boards), _, _) => boards.clone(),
+            (None, Some(board), _) => vec![board.clone()],
+            (None, None, Some(sample)) => sample.boards().map_err(|e| format!("spot {}: {}", index + 1, e))?,
+            (None, None, None) => return Err(format!("spot {} has no board", index + 1)),
+        };
+        for board in &boards {
+            let name = match (&settings.name, boards.len()) {
+                (Some(name), 1) => name.clone(),
+                (Some(name), _) => format!("{}-{}", name, board),
+                (None, _) => board.clone(),
+            };
+            let Some(sweep) = &settings.stack_sweep else {
+                let spec = job_spec(&settings, board).map_err(|e| format!("spot '{}': {}", name, e))?;
+                jobs.push(Job { name, spec });
+                continue;
+            };
+            for &stacks in sweep {
+                let name = match stacks {
+                    Stacks::Both(s) => format!("{}-{}bb", name, s),
+                    Stacks::Each([ip, oop]) => format!("{}-{}-{}bb", name, ip, oop),
+                };
+                let depth = SpotSettings { stacks: Some(stacks), ..settings.clone() };
+                let spec = job_spec(&depth, board).map_err(|e| format!("spot '{}': {}", name, e))?;
+                jobs.push(Job { name, spec });
+            }
+        }
+    }
+    let mut names: Vec<&str> = jobs.iter().map(|j| j.name.as_str()).collect();
+    names.sort_unstable();
+    if let Some(w) = names.windows(2).find(|w| w[0] == w[1]) {
+        return Err(format!("duplicate spot name '{}'", w[0]));
+    }
+    if let Some(name) = names.iter().find(|n| !is_file_name(n)) {
+        return Err(format!("spot name '{}' can't name a solution file (no paths, '/', '\\', or '..')", name));
+    }
+    Ok(jobs)
+}
+
+/// Whether `name` can be joined onto the output directory without leaving
+/// it: not empty or absolute, and no separators or `..`
+fn is_file_name(name: &str) -> bool {
+    !name.is_empty() && !std::path::Path::new(name).is_absolute() && !name.contains(['/', '\\']) && !name.contains("..")
+}
+
+pub(crate) fn job_spec(settings: &SpotSettings, board: &str) -> Result<SolveSpec, String> {
+    let board = parse_cards(board).map_err(|e| e.to_string())?;
+    let stacks = match settings.stacks.unwrap_or(Stacks::Both(95.0)) {
+        Stacks::Both(s) => [s, s],
+        Stacks::Each(s) => s,
+    };
+    let range = |r: &Option<String>, which: &str| {
+        let text = r.as_deref().ok_or(format!("{} is required", which))?;
+        Range::parse(text).map_err(|e| format!("{}: {}", which, e))
+    };
+    let ranges = [range(&settings.ip_range, "ip_range")?, range(&settings.oop_range, "oop_range")?];
+
+    let mut spec = SolveSpec::new(board, ranges, GameConfig::from_pot(settings.pot.unwrap_or(10.0), stacks));
+    if let Some(s) = &settings.flop_bets {
+        spec.tree_config.flop = street_sizes(s)?;
+    }
+    if let Some(s) = &settings.turn_bets {
+        spec.tree_config.turn = street_sizes(s)?;
+    }
+    if let Some(s) = &settings.river_bets {
+        spec.tree_config.river = street_sizes(s)?;
+    }
+    if let Some(n) = settings.max_raises {
+        spec.tree_config.max_raises = n;
+    }
+    if let Some(n) = settings.iterations {
+        spec.stop.max_iterations = n;
+    }
+    if let Some(target) = settings.target {
+        let unit: Unit = settings.units.as_deref().unwrap_or("bb").parse().map_err(|e: UnitParseError| e.to_string())?;
+        spec.stop.target_exploitability = Some(Units::for_game(&spec.tree_config.game).to_bb(target, unit));
+    }
+    if let Some(n) = settings.check_every {
+        spec.stop.check_every = n;
+    }
+    Ok(spec)
+}
+
+/// Solve one job and save it under `out`
+fn run_job(job: &Job, out: &Path, compression: Compression) -> ManifestEntry {
+    let start = Instant::now();
+    let mut entry = ManifestEntry {
+        name: job.name.clone(),
+        board: format_cards(&job.spec.board),
+        file: None,
+        iterations: None,
+        exploitability: None,
+        exploitability_pct_pot: None,
+        stop_reason: None,
+        mean_entropy: None,
+        pure_fraction: None,
+        mean_support: None,
+        seconds: 0.0,
+        error: None,
+    };
+    match solve(&job.spec) {
+        Ok(result) => {
+            let file = format!("{}.bin", job.name);
+            let solution = Solution::from_solver(&result.solver).with_ranges(job.spec.ranges.clone());
+            match save_solution(out.join(&file), &solution, compression) {
+                Ok(()) => entry.file = Some(file),
+                Err(e) => entry.error = Some(e.to_string()),
+            }
+            entry.iterations = Some(result.metrics.iterations);
+            entry.exploitability = Some(result.metrics.exploitability);
+            let units = Units::for_game(&job.spec.tree_config.game);
+            entry.exploitability_pct_pot = Some(units.from_bb(result.metrics.exploitability, Unit::PotPercent));
+            entry.stop_reason = Some(format!("{:?}", result.stop_reason));
+            let mixing = MixingSummary::from_nodes(&strategy_mixing(&result.solver.tree, &result.solver.storage));
+            entry.mean_entropy = Some(mixing.mean_entropy);
+            entry.pure_fraction = Some(mixing.pure_fraction);
+            entry.mean_support = Some(mixing.mean_support);
+        }
+        Err(e) => entry.error = Some(e.to_string()),
+    }
+    entry.seconds = start.elapsed().as_secs_f64();
+    entry
+}
+
+/// Solve all jobs on a pool of `threads` and return manifest entries in job order
+pub fn run_jobs(jobs: &[Job], threads: Option<usize>, out: &Path, compression: Compression) -> Result<Vec<ManifestEntry>, String> {
+    let mut pool = rayon::ThreadPoolBuilder::new();
+    if let Some(n) = threads {
+        pool = pool.num_threads(n);
+    }
+    let pool = pool.build().map_err(|e| e.to_string())?;
+    Ok(pool.install(|| jobs.par_iter().map(|job| run_job(job, out, compression)).collect()))
+}
+
+/// Run `oracle batch` with the arguments after `batch`
+pub fn run_batch(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut threads = None;
+    let mut out = None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--threads", Some(v)) => threads = Some(v.parse().map_err(|_| format!("invalid threads '{}'", v))?),
+            ("--out", Some(v)) => out = Some(PathBuf::from(v)),
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (file, _) => {
+                path = Some(file.to_string());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+
+    let path = path.ok_or("usage: oracle batch JOBS.toml [--threads N] [--out DIR]")?;
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let file: JobFile = toml::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+    let jobs = expand_jobs(&file)?;
+    let threads = threads.or(file.threads);
+    if threads == Some(0) {
+        return Err("threads must be at least 1".to_string());
+    }
+    let out = out.or(file.out).unwrap_or_else(|| PathBuf::from("solutions"));
+    let compression = if file.compress { Compression::Zstd { level: 3 } } else { Compression::None };
+    std::fs::create_dir_all(&out).map_err(|e| format!("{}: {}", out.display(), e))?;
+
+    println!("Solving {} spots from {} into {}...", jobs.len(), path, out.display());
+    let start = Instant::now();
+    let entries = run_jobs(&jobs, threads, &out, compression)?;
+    for e in &entries {
+        match (&e.error, e.exploitability, e.exploitability_pct_pot) {
+            (Some(err), _, _) => println!("  {:<24} FAILED: {}", e.name, err),
+            (None, Some(x), Some(pct)) => {
+                println!("  {:<24} {:>8} iters  {:.6} bb ({:.3}% pot)  {:.1}s", e.name, e.iterations.unwrap_or(0), x, pct, e.seconds)
+            }
+            _ => {}
+        }
+    }
+
+    let manifest = toml::to_string(&Manifest { spot: &entries }).map_err(|e| e.to_string())?;
+    let manifest_path = out.join("manifest.toml");
+    std::fs::write(&manifest_path, manifest).map_err(|e| format!("{}: {}", manifest_path.display(), e))?;
+    let failed = entries.iter().filter(|e| e.error.is_some()).count();
+    println!("Done in {:.1}s; manifest at {}", start.elapsed().as_secs_f64(), manifest_path.display());
+    if failed > 0 {
+        return Err(format!("{} of {} spots failed", failed, entries.len()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::file_format::load_solution;
+
+    const JOBS: &str = r#"
+        threads = 2
+
+        [defaults]
+        pot = 10
+        ip_range = "AK,77"
+        oop_range = "AQ,KQ"
+        river_bets = "75"
+        max_raises = 1
+        iterations = 30
+
+        [[spot]]
+        name = "dry"
+        boards = ["AsKh7d2c9s", "Ks8d3c2h4d"]
+
+        [[spot]]
+        board = "Td9d8c6s2h"
+        stacks = [40, 200]
+        oop_range = "QQ"
+    "#;
+
+    #[test]
+    fn test_expand_jobs_applies_defaults_and_boards() {
+        let file: JobFile = toml::from_str(JOBS).unwrap();
+        let jobs = expand_jobs(&file).unwrap();
+        let names: Vec<&str> = jobs.iter().map(|j| j.name.as_str()).collect();
+        assert_eq!(names, ["dry-AsKh7d2c9s", "dry-Ks8d3c2h4d", "Td9d8c6s2h"]);
+        assert_eq!(jobs[0].spec.stop.max_iterations, 30);
+        assert_eq!(jobs[2].spec.tree_config.game.postflop_stacks(), [40.0, 200.0]);
+        assert_eq!(jobs[2].spec.ranges[1], Range::parse("QQ").unwrap());
+        assert_eq!(jobs[2].spec.ranges[0], Range::parse("AK,77").unwrap());
+    }
+
+    #[test]
+    fn test_sampled_boards_are_distinct_and_filtered() {
+        let file: JobFile = toml::from_str(r#"
+            [defaults]
+            ip_range = "AA"
+            oop_range = "KK"
+            [[spot]]
+            name = "mono"
+            sample = { count = 4, cards = 5, filter = "monotone", seed = 3 }
+        "#).unwrap();
+        let jobs = expand_jobs(&file).unwrap();
+        assert_eq!(jobs.len(), 4);
+        for job in &jobs {
+            assert!(job.name.starts_with("mono-"));
+            assert!(job.spec.board.iter().all(|c| c.suit() == job.spec.board[0].suit()));
+        }
+        assert_eq!(jobs.iter().map(|j| j.name.clone()).collect::<Vec<_>>(), expand_jobs(&file).unwrap().iter().map(|j| j.name.clone()).collect::<Vec<_>>());
+
+        let bad: JobFile = toml::from_str("[[spot]]\nsample = { count = 2, filter = \"wet\" }").unwrap();
+        assert!(expand_jobs(&bad).unwrap_err().contains("invalid board filter 'wet'"));
+    }
+
+    #[test]
+    fn test_stack_sweep_expands_depths() {
+        let file: JobFile = toml::from_str(r#"
+            [defaults]
+            ip_range = "AA"
+            oop_range = "KK"
+            stack_sweep = [20, 150, [40, 200]]
+            [[spot]]
+            name = "srp"
+            board = "AsKh7d"
+        "#).unwrap();
+        let jobs = expand_jobs(&file).unwrap();
+        let names: Vec<&str> = jobs.iter().map(|j| j.name.as_str()).collect();
+        assert_eq!(names, ["srp-20bb", "srp-150bb", "srp-40-200bb"]);
+        assert_eq!(jobs[1].spec.tree_config.game.postflop_stacks(), [150.0, 150.0]);
+        assert_eq!(jobs[2].spec.tree_config.game.postflop_stacks(), [40.0, 200.0]);
+    }
+
+    #[test]
+    fn test_target_units_convert_to_bb() {
+        let spot = "[defaults]\nip_range = \"AA\"\noop_range = \"KK\"\npot = 20\ntarget = 0.5\n[[spot]]\nboard = \"AsKh7d\"";
+        let jobs = expand_jobs(&toml::from_str(&format!("{}\nunits = \"pot\"", spot)).unwrap()).unwrap();
+        assert_eq!(jobs[0].spec.stop.target_exploitability, Some(0.1));
+        let jobs = expand_jobs(&toml::from_str(spot).unwrap()).unwrap();
+        assert_eq!(jobs[0].spec.stop.target_exploitability, Some(0.5));
+        let bad = expand_jobs(&toml::from_str(&format!("{}\nunits = \"mbb\"", spot)).unwrap());
+        assert!(bad.unwrap_err().contains("unknown unit 'mbb'"));
+    }
+
+    #[test]
+    fn test_job_file_errors() {
+        assert!(toml::from_str::<JobFile>("[[spot]]\nbord = \"AsKh7d\"").is_err());
+        let file: JobFile = toml::from_str("[[spot]]\npot = 5").unwrap();
+        assert_eq!(expand_jobs(&file).unwrap_err(), "spot 1 has no board");
+        let file: JobFile = toml::from_str("[[spot]]\nboard = \"AsKh7d\"\nip_range = \"AA\"").unwrap();
+        assert!(expand_jobs(&file).unwrap_err().contains("oop_range is required"));
+        let dup = "[defaults]\nip_range = \"AA\"\noop_range = \"KK\"\n[[spot]]\nboard = \"AsKh7d\"\n[[spot]]\nboard = \"AsKh7d\"";
+        assert!(expand_jobs(&toml::from_str(dup).unwrap()).unwrap_err().contains("duplicate"));
+        for name in ["", "../x", "/abs", "a/b", "a\\\\b", "x..y"] {
+            let spot = format!("[defaults]\nip_range = \"AA\"\noop_range = \"KK\"\n[[spot]]\nboard = \"AsKh7d\"\nname = \"{}\"", name);
+            assert!(expand_jobs(&toml::from_str(&spot).unwrap()).unwrap_err().contains("can't name a solution file"), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_run_jobs_writes_solutions_in_order() {
+        let file: JobFile = toml::from_str(JOBS).unwrap();
+        let jobs = expand_jobs(&file).unwrap();
+        let out = std::env::temp_dir().join(format!("oracle_batch_{}", std::process::id()));
+        std::fs::create_dir_all(&out).unwrap();
+        let entries = run_jobs(&jobs, Some(2), &out, Compression::None).unwrap();
+        assert_eq!(entries.len(), 3);
+        for (entry, job) in entries.iter().zip(&jobs) {
+            assert_eq!(entry.name, job.name);
+            assert_eq!(entry.error, None);
+            let solution = load_solution(out.join(entry.file.as_ref().unwrap())).unwrap();
+            assert_eq!(solution.iterations, 30);
+            assert_eq!(solution.ranges.as_ref(), Some(&job.spec.ranges));
+            assert!(entry.mean_support.unwrap() >= 1.0 && entry.pure_fraction.unwrap() <= 1.0);
+        }
+        let manifest = toml::to_string(&Manifest { spot: &entries }).unwrap();
+        assert!(manifest.contains("name = \"dry-AsKh7d2c9s\""));
+        assert!(manifest.contains("mean_entropy = "));
+        assert!(manifest.contains("exploitability_pct_pot = "));
+        std::fs::remove_dir_all(&out).unwrap();
+    }
+}
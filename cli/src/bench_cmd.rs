@@ -0,0 +1,206 @@
+//! `oracle bench` — run the benchmark suite and save or compare baselines
+//!
+//! The suite measures evaluator throughput and solver speed on a generated
+//! turn tree. `--save-baseline NAME` writes the results to
+//! `<dir>/NAME.json`; `--compare NAME` loads that file and reports the
+//! change of every metric in percent, failing when one drops by more than
+//! `--threshold` percent (default 5). Every metric is a rate, so higher is
+//! better:
+//!
+//! ```text
+//! oracle bench --save-baseline main
+//! oracle bench --compare main --threshold 3
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use oracle_engine::builder::{build_tree, StreetSizes, TreeConfig};
+use oracle_engine::evaluator::benchmark_throughput_with_seed;
+use oracle_engine::game::{build_terminal_evs, GameConfig};
+use oracle_engine::node::{parse_cards, Node};
+use oracle_engine::rng::DEFAULT_SEED;
+use oracle_engine::CfrSolver;
+
+/// Directory baselines are kept in unless `--baseline-dir` is given
+pub const DEFAULT_BASELINE_DIR: &str = "bench_baselines";
+
+/// Saved results of one suite run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub name: String,
+    /// `oracle` version that produced the numbers
+    pub version: String,
+    /// Metric name → rate (higher is better)
+    pub metrics: BTreeMap<String, f64>,
+}
+
+/// One metric against its baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub metric: String,
+    pub baseline: f64,
+    pub current: f64,
+    /// `(current - baseline) / baseline` in percent
+    pub change_pct: f64,
+    pub regressed: bool,
+}
+
+/// Compare every metric present in both runs; a drop of more than
+/// `threshold_pct` percent is a regression
+pub fn compare(baseline: &Baseline, current: &Baseline, threshold_pct: f64) -> Vec<Comparison> {
+    baseline.metrics.iter().filter_map(|(metric, &old)| {
+        let new = *current.metrics.get(metric)?;
+        let change_pct = if old == 0.0 { 0.0 } else { (new - old) / old * 100.0 };
+        Some(Comparison { metric: metric.clone(), baseline: old, current: new, change_pct, regressed: change_pct < -threshold_pct })
+    }).collect()
+}
+
+fn baseline_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.json", name))
+}
+
+pub fn save_baseline(dir: &Path, baseline: &Baseline) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let path = baseline_path(dir, &baseline.name);
+    let json = serde_json::to_string_pretty(baseline).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+pub fn load_baseline(dir: &Path, name: &str) -> Result<Baseline, String> {
+    let path = baseline_path(dir, name);
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&json).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Turn tree the solver metrics run on
+fn solver_tree_config() -> TreeConfig {
+    let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+    config.turn = StreetSizes::new(vec![0.5, 1.0]);
+    config.river = StreetSizes::new(vec![0.5, 1.0]);
+    config.max_raises = 2;
+    config
+}
+
+fn per_sec(count: u64, elapsed: Duration) -> f64 {
+    count as f64 / elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+}
+
+/// Run the suite: `samples` evaluator hands and `iterations` solver iterations
+pub fn run_suite(name: &str, samples: usize, iterations: u64) -> Baseline {
+    let mut metrics = BTreeMap::new();
+    let (evals_per_sec, _) = benchmark_throughput_with_seed(samples, DEFAULT_SEED);
+    metrics.insert("evaluator_evals_per_sec".to_string(), evals_per_sec);
+
+    let start = Instant::now();
+    let tree = build_tree(&solver_tree_config());
+    metrics.insert("tree_builds_per_sec".to_string(), per_sec(1, start.elapsed()));
+
+    let evs = build_terminal_evs(&tree, |node: &Node| {
+        f64::from(node.board().iter().map(|c| u32::from(c.value())).sum::<u32>() % 101) / 100.0
+    });
+    let mut solver = CfrSolver::new_with_evs(tree, evs);
+    let start = Instant::now();
+    for _ in 0..iterations {
+        solver.run_iteration();
+    }
+    metrics.insert("solver_iterations_per_sec".to_string(), per_sec(iterations, start.elapsed()));
+
+    let checks = 5;
+    let start = Instant::now();
+    for _ in 0..checks {
+        solver.exploitability(Duration::ZERO);
+    }
+    metrics.insert("exploitability_checks_per_sec".to_string(), per_sec(checks, start.elapsed()));
+
+    Baseline { name: name.to_string(), version: env!("CARGO_PKG_VERSION").to_string(), metrics }
+}
+
+/// Run `oracle bench` with the arguments after `bench`
+pub fn run_bench(args: &[String]) -> Result<(), String> {
+    let mut save = None;
+    let mut against = None;
+    let mut dir = PathBuf::from(DEFAULT_BASELINE_DIR);
+    let mut threshold = 5.0;
+    let mut samples = 1_000_000usize;
+    let mut iterations = 200u64;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--save-baseline", Some(v)) => save = Some(v.to_string()),
+            ("--compare", Some(v)) => against = Some(v.to_string()),
+            ("--baseline-dir", Some(v)) => dir = PathBuf::from(v),
+            ("--threshold", Some(v)) => threshold = v.parse().map_err(|_| format!("invalid threshold '{}'", v))?,
+            ("--samples", Some(v)) => samples = v.parse().map_err(|_| format!("invalid sample count '{}'", v))?,
+            ("--iterations", Some(v)) => iterations = v.parse().map_err(|_| format!("invalid iteration count '{}'", v))?,
+            (flag, _) => return Err(format!("unknown or incomplete option '{}'", flag)),
+        }
+        i += 2;
+    }
+    // Load first so a typo doesn't cost a full run
+    let baseline = against.as_deref().map(|name| load_baseline(&dir, name)).transpose()?;
+
+    println!("Running benchmark suite ({} evaluator hands, {} solver iterations)...", samples, iterations);
+    let current = run_suite(save.as_deref().unwrap_or("current"), samples, iterations);
+    for (metric, value) in &current.metrics {
+        println!("  {:<32} {:>16.2}", metric, value);
+    }
+
+    if let Some(name) = &save {
+        let path = save_baseline(&dir, &current)?;
+        println!("Saved baseline '{}' to {}", name, path.display());
+    }
+
+    if let Some(baseline) = baseline {
+        println!();
+        println!("Compared with '{}' (oracle {}):", baseline.name, baseline.version);
+        println!("  {:<32} {:>16} {:>16} {:>9}", "Metric", "Baseline", "Current", "Change");
+        let comparisons = compare(&baseline, &current, threshold);
+        for c in &comparisons {
+            let flag = if c.regressed { "  REGRESSION" } else { "" };
+            println!("  {:<32} {:>16.2} {:>16.2} {:>+8.1}%{}", c.metric, c.baseline, c.current, c.change_pct, flag);
+        }
+        let regressions = comparisons.iter().filter(|c| c.regressed).count();
+        if regressions > 0 {
+            return Err(format!("{} metric(s) regressed by more than {}%", regressions, threshold));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline(name: &str, metrics: &[(&str, f64)]) -> Baseline {
+        Baseline {
+            name: name.to_string(),
+            version: "0.1.0".to_string(),
+            metrics: metrics.iter().map(|&(m, v)| (m.to_string(), v)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_compare_flags_drops_past_threshold() {
+        let old = baseline("main", &[("a", 100.0), ("b", 100.0), ("c", 100.0), ("gone", 1.0)]);
+        let new = baseline("current", &[("a", 96.0), ("b", 90.0), ("c", 120.0), ("added", 1.0)]);
+        let result = compare(&old, &new, 5.0);
+        let summary: Vec<(&str, f64, bool)> = result.iter().map(|c| (c.metric.as_str(), c.change_pct, c.regressed)).collect();
+        assert_eq!(summary, vec![("a", -4.0, false), ("b", -10.0, true), ("c", 20.0, false)]);
+    }
+
+    #[test]
+    fn test_baseline_round_trips_through_json() {
+        let dir = std::env::temp_dir().join(format!("oracle_bench_test_{}", std::process::id()));
+        let saved = baseline("laptop", &[("evaluator_evals_per_sec", 4.5e7)]);
+        let path = save_baseline(&dir, &saved).unwrap();
+        assert_eq!(path, dir.join("laptop.json"));
+        assert_eq!(load_baseline(&dir, "laptop").unwrap(), saved);
+        assert!(load_baseline(&dir, "missing").unwrap_err().contains("missing.json"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
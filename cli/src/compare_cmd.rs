@@ -0,0 +1,172 @@
+//! `oracle compare-algos` — convergence curves of CFR variants on one spot
+//!
+//! `oracle compare-algos --config spot.toml --algos cfr+,dcfr,lcfr --budget 60s`
+//! solves the spot once per algorithm (`config::Algorithm` names), each for
+//! the same wall-time budget on the same tree, and writes one CSV row per
+//! exploitability check: algorithm, iteration, seconds, and exploitability in
+//! bb and % of the starting pot. The spot file takes the keys of one batch
+//! `[[spot]]` (see `batch_cmd`) at the top level, with a single `board`;
+//! `iterations` caps each run, `check_every` sets the curve's resolution.
+
+use std::time::Duration;
+use oracle_engine::config::Algorithm;
+use oracle_engine::solve::{solve_with_progress, SolveSpec};
+use oracle_engine::units::{Unit, Units};
+use crate::batch_cmd::{job_spec, SpotSettings};
+
+/// One exploitability check of one run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurvePoint {
+    pub algorithm: Algorithm,
+    pub iteration: u64,
+    pub seconds: f64,
+    /// Exploitability in bb
+    pub exploitability: f64,
+}
+
+/// Run `oracle compare-algos` with the arguments after the subcommand
+pub fn run_compare_algos(args: &[String]) -> Result<(), String> {
+    let mut config = None;
+    let mut algos = Algorithm::ALL.to_vec();
+    let mut budget = Duration::from_secs(60);
+    let mut out = None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--config", Some(v)) => config = Some(v.to_string()),
+            ("--algos", Some(v)) => algos = parse_algos(v)?,
+            ("--budget", Some(v)) => budget = parse_budget(v)?,
+            ("--out", Some(v)) => out = Some(v.to_string()),
+            (flag, _) => return Err(format!("unknown or incomplete option '{}'", flag)),
+        }
+        i += 2;
+    }
+    let path = config.ok_or("usage: oracle compare-algos --config SPOT.toml [--algos LIST] [--budget 60s] [--out FILE.csv]")?;
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let settings: SpotSettings = toml::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+    let board = settings.board.clone().ok_or_else(|| format!("{}: board is required", path))?;
+    let mut spec = job_spec(&settings, &board).map_err(|e| format!("{}: {}", path, e))?;
+    spec.stop.max_iterations = settings.iterations.unwrap_or(u64::MAX);
+    spec.stop.target_exploitability = None;
+    spec.stop.time_limit = Some(budget);
+
+    let mut points = Vec::new();
+    for &algorithm in &algos {
+        eprintln!("Running {} for {:.1}s...", algorithm.name(), budget.as_secs_f64());
+        let curve = convergence_curve(&spec, algorithm)?;
+        if let Some(last) = curve.last() {
+            eprintln!("  {} iterations, exploitability {:.6} bb", last.iteration, last.exploitability);
+        }
+        points.extend(curve);
+    }
+    let csv = to_csv(&points, Units::for_game(&spec.tree_config.game));
+    match out {
+        Some(out) => {
+            std::fs::write(&out, csv).map_err(|e| format!("{}: {}", out, e))?;
+            eprintln!("Wrote {} points to {}", points.len(), out);
+        }
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+/// Solve `spec` with `algorithm`, recording every exploitability check
+pub fn convergence_curve(spec: &SolveSpec, algorithm: Algorithm) -> Result<Vec<CurvePoint>, String> {
+    let spec = SolveSpec { algo: algorithm, ..spec.clone() };
+    let mut curve = Vec::new();
+    solve_with_progress(&spec, |m| {
+        curve.push(CurvePoint { algorithm, iteration: m.iterations, seconds: m.elapsed_time.as_secs_f64(), exploitability: m.exploitability });
+    })
+    .map_err(|e| e.to_string())?;
+    Ok(curve)
+}
+
+/// `algorithm,iteration,seconds,exploitability_bb,exploitability_pct_pot` rows
+pub fn to_csv(points: &[CurvePoint], units: Units) -> String {
+    let mut out = String::from("algorithm,iteration,seconds,exploitability_bb,exploitability_pct_pot\n");
+    for p in points {
+        out.push_str(&format!(
+            "{},{},{:.3},{:.6},{:.4}\n",
+            p.algorithm.name(),
+            p.iteration,
+            p.seconds,
+            p.exploitability,
+            units.from_bb(p.exploitability, Unit::PotPercent)
+        ));
+    }
+    out
+}
+
+/// Comma-separated algorithm names, e.g. `cfr+,dcfr,lcfr`
+fn parse_algos(s: &str) -> Result<Vec<Algorithm>, String> {
+    let algos = s
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| t.parse::<Algorithm>().map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if algos.is_empty() {
+        return Err("--algos needs at least one algorithm".to_string());
+    }
+    Ok(algos)
+}
+
+/// A wall-time budget: `90`, `90s`, `1.5m`, or `500ms`
+fn parse_budget(s: &str) -> Result<Duration, String> {
+    let (number, scale) = if let Some(n) = s.strip_suffix("ms") {
+        (n, 0.001)
+    } else if let Some(n) = s.strip_suffix('s') {
+        (n, 1.0)
+    } else if let Some(n) = s.strip_suffix('m') {
+        (n, 60.0)
+    } else {
+        (s, 1.0)
+    };
+    match number.trim().parse::<f64>() {
+        Ok(v) if v > 0.0 && v.is_finite() => Ok(Duration::from_secs_f64(v * scale)),
+        _ => Err(format!("invalid budget '{}'", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_budget_and_algos() {
+        assert_eq!(parse_budget("60s"), Ok(Duration::from_secs(60)));
+        assert_eq!(parse_budget("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_budget("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_budget("5"), Ok(Duration::from_secs(5)));
+        assert!(parse_budget("-1s").is_err() && parse_budget("soon").is_err());
+        assert_eq!(parse_algos("cfr+, dcfr,lcfr"), Ok(vec![Algorithm::CfrPlus, Algorithm::DiscountedCfr, Algorithm::LinearCfr]));
+        assert!(parse_algos("cfr+,mccfr").unwrap_err().contains("mccfr"));
+    }
+
+    #[test]
+    fn test_compare_algos_writes_a_curve_per_algorithm() {
+        let dir = std::env::temp_dir().join(format!("oracle_compare_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("spot.toml");
+        std::fs::write(&config, "board = \"AsKh7d2c9s\"\nip_range = \"AA,KK,AK\"\noop_range = \"QQ-99,AQ\"\niterations = 60\ncheck_every = 20\n").unwrap();
+        let out = dir.join("curve.csv");
+        let arg = |s: &str| s.to_string();
+        run_compare_algos(&[
+            arg("--config"), config.display().to_string(),
+            arg("--algos"), arg("cfr+,dcfr,lcfr"),
+            arg("--budget"), arg("30s"),
+            arg("--out"), out.display().to_string(),
+        ]).unwrap();
+        let csv = std::fs::read_to_string(&out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "algorithm,iteration,seconds,exploitability_bb,exploitability_pct_pot");
+        for algo in ["cfr+", "dcfr", "lcfr"] {
+            let rows: Vec<&&str> = lines.iter().filter(|l| l.starts_with(&format!("{},", algo))).collect();
+            assert_eq!(rows.len(), 3, "{}", csv);
+            assert!(rows[2].starts_with(&format!("{},60,", algo)));
+        }
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,115 @@
+//! `oracle equity` — range-vs-range equity from the command line
+//!
+//! `oracle equity --board AsKh7d --range1 "AA,KK,AK" --range2 "77,88,T9s"`
+//! prints each range's combo count after board removal, its equity with win
+//! and tie shares, and a per-class breakdown (`EquityResult::by_class`).
+//! Runouts are enumerated exactly (`equity::range_vs_range`) unless
+//! `--samples N` asks for a sample; boards without a flop always sample,
+//! since enumerating every preflop runout is too slow.
+
+use oracle_engine::combo::class_label;
+use oracle_engine::equity::{range_vs_range, range_vs_range_sampled, EquityResult};
+use oracle_engine::evaluator::CactusKevEvaluator;
+use oracle_engine::node::{format_cards, parse_cards, Card};
+use oracle_engine::range::Range;
+use oracle_engine::rng::{Rng, DEFAULT_SEED};
+
+/// Runouts sampled for a preflop board when `--samples` isn't given
+const PREFLOP_SAMPLES: usize = 10_000;
+
+/// Run `oracle equity` with the arguments after the subcommand
+pub fn run_equity(args: &[String]) -> Result<(), String> {
+    let mut board = String::new();
+    let mut ranges: [Option<Range>; 2] = [None, None];
+    let mut samples = None;
+    let mut seed = DEFAULT_SEED;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--board", Some(v)) => board = v.to_string(),
+            ("--range1", Some(v)) => ranges[0] = Some(Range::parse(v).map_err(|e| format!("--range1: {}", e))?),
+            ("--range2", Some(v)) => ranges[1] = Some(Range::parse(v).map_err(|e| format!("--range2: {}", e))?),
+            ("--samples", Some(v)) => samples = Some(v.parse::<usize>().map_err(|_| format!("invalid --samples '{}'", v))?),
+            ("--seed", Some(v)) => seed = v.parse().map_err(|_| format!("invalid --seed '{}'", v))?,
+            (flag, _) => return Err(format!("unknown or incomplete option '{}'", flag)),
+        }
+        i += 2;
+    }
+    let usage = "usage: oracle equity --range1 R --range2 R [--board CARDS] [--samples N] [--seed N]";
+    let [Some(range1), Some(range2)] = ranges else {
+        return Err(usage.to_string());
+    };
+    let board = parse_cards(&board).map_err(|e| format!("--board: {}", e))?;
+    if board.len() > 5 || (1..3).contains(&board.len()) {
+        return Err(format!("--board must be empty or 3 to 5 cards, got {}", board.len()));
+    }
+    let samples = samples.or(board.is_empty().then_some(PREFLOP_SAMPLES));
+    print!("{}", equity_report(&board, &range1, &range2, samples, seed));
+    Ok(())
+}
+
+/// Both sides' equities and class breakdowns, exact or over `samples` runouts
+fn equity_report(board: &[Card], range1: &Range, range2: &Range, samples: Option<usize>, seed: u64) -> String {
+    let evaluator = CactusKevEvaluator::new();
+    // Both sides sample the same runouts, so their equities add up to 100%
+    let run = |hero: &Range, villain: &Range| match samples {
+        Some(n) => range_vs_range_sampled(&evaluator, hero, villain, board, n, &mut Rng::new(seed)),
+        None => range_vs_range(&evaluator, hero, villain, board),
+    };
+    let results = [run(range1, range2), run(range2, range1)];
+
+    let board_text = if board.is_empty() { "preflop".to_string() } else { format_cards(board) };
+    let method = match samples {
+        Some(n) => format!("sampled, {} runouts", n),
+        None => "exact".to_string(),
+    };
+    let mut out = format!("Board: {} ({})\n", board_text, method);
+    for (side, (range, result)) in [range1, range2].into_iter().zip(&results).enumerate() {
+        out.push_str(&format!(
+            "Range {}: {:>6.1} combos  equity {:6.2}%  (win {:.2}%, tie {:.2}%)\n",
+            side + 1,
+            range.summary(board).combos,
+            100.0 * result.hero_equity,
+            100.0 * result.win,
+            100.0 * result.tie
+        ));
+    }
+    for (side, result) in results.iter().enumerate() {
+        out.push_str(&format!("\nRange {} by class:\n", side + 1));
+        out.push_str(&class_table(result));
+    }
+    out
+}
+
+/// One `class  combos  equity` row per class with a live matchup
+fn class_table(result: &EquityResult) -> String {
+    result
+        .by_class()
+        .iter()
+        .map(|c| format!("  {:<4} {:>5.1}  {:6.2}%\n", class_label(c.class), c.combos, 100.0 * c.equity))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equity_report_lists_both_sides_and_classes() {
+        let board = parse_cards("AsKh7d").unwrap();
+        let (hero, villain) = (Range::parse("AA,KK,AK").unwrap(), Range::parse("77,88,T9s").unwrap());
+        let text = equity_report(&board, &hero, &villain, None, DEFAULT_SEED);
+        assert!(text.starts_with("Board: AsKh7d (exact)\nRange 1:   15.0 combos"), "{}", text);
+        assert!(text.contains("Range 2:   13.0 combos"));
+        assert!(text.contains("\nRange 2 by class:\n  T9s    4.0"));
+        assert!(text.contains("  88     6.0") && text.contains("  77     3.0"));
+
+        let sampled = equity_report(&board, &hero, &villain, Some(50), 1);
+        assert!(sampled.starts_with("Board: AsKh7d (sampled, 50 runouts)\n"));
+        let arg = |s: &str| s.to_string();
+        assert!(run_equity(&[arg("--range1"), arg("AA")]).is_err());
+        assert!(run_equity(&[arg("--range1"), arg("AA"), arg("--range2"), arg("KK"), arg("--board"), arg("As")]).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! `oracle ev-line` — where a line's EV is made, as CSV
+//!
+//! `oracle ev-line flop.bin --line "F:x-b33-c|T:7h|x"` follows the line
+//! (`line::Line` notation; `root` for the root alone) and writes one row per
+//! node on it (`analysis::ev_line`): the line so far, who acts, pot, reach,
+//! IP's EV, the EV change from the previous node, that change weighted by
+//! reach, and the share of the line's EV change realized there. Plotted
+//! against the row, the EV and realized columns give the line's EV graph.
+//! Writes to `--out`, or stdout.
+
+use oracle_engine::analysis::{ev_line, LinePoint};
+use oracle_engine::cfr::RegretStorage;
+use oracle_engine::file_format::load_solution;
+use oracle_engine::line::{format_line, Line};
+use oracle_engine::node::{GameTree, Node};
+
+/// Run `oracle ev-line` with the arguments after the subcommand
+pub fn run_ev_line(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut line = None;
+    let mut out = None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--line", Some(v)) => line = Some(v.to_string()),
+            ("--out", Some(v)) => out = Some(v.to_string()),
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (p, _) => {
+                path = Some(p.to_string());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+    let usage = "usage: oracle ev-line SOLUTION --line LINE [--out FILE.csv]";
+    let (path, line) = path.zip(line).ok_or(usage)?;
+    let solution = load_solution(&path).map_err(|e| format!("{}: {}", path, e))?;
+    solution.validate().map_err(|e| format!("{}: {}", path, e))?;
+    let node = Line::parse(&line).and_then(|l| l.find(&solution.tree)).map_err(|e| format!("--line '{}': {}", line, e))?;
+
+    let storage = RegretStorage::from_snapshot(solution.storage.clone());
+    let points = ev_line(&solution.tree, &storage, &solution.terminal_ev_map(), node);
+    let csv = to_csv(&solution.tree, &points);
+    match out {
+        Some(out) => {
+            std::fs::write(&out, csv).map_err(|e| format!("{}: {}", out, e))?;
+            eprintln!("Wrote {} nodes to {}", points.len(), out);
+        }
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+/// `line,to_act,pot,reach,ev_ip_bb,ev_change_bb,weighted_change_bb,realized_pct` rows
+pub fn to_csv(tree: &GameTree, points: &[LinePoint]) -> String {
+    let mut out = String::from("line,to_act,pot,reach,ev_ip_bb,ev_change_bb,weighted_change_bb,realized_pct\n");
+    for p in points {
+        let to_act = match tree.get(p.node) {
+            Some(Node::Decision { player, .. }) => format!("{:?}", player),
+            Some(Node::Chance { .. }) => "chance".to_string(),
+            _ => "terminal".to_string(),
+        };
+        out.push_str(&format!(
+            "{},{},{:.2},{:.6},{:.4},{:.4},{:.4},{:.1}\n",
+            format_line(tree, p.node),
+            to_act,
+            p.pot,
+            p.reach,
+            p.ev,
+            p.ev_change,
+            p.weighted_change,
+            p.realized * 100.0
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::file_format::{save_solution, Compression};
+    use oracle_engine::node::parse_cards;
+    use oracle_engine::range::Range;
+    use oracle_engine::solve::{solve, SolveSpec};
+    use oracle_engine::{Solution, StopCondition};
+
+    #[test]
+    fn test_ev_line_writes_a_row_per_node() {
+        let ranges = [Range::parse("AA,KK,AK").unwrap(), Range::parse("QQ-99,AQ").unwrap()];
+        let mut spec = SolveSpec::from_state(parse_cards("AsKh7d2c").unwrap(), ranges, 6.0, [97.0, 97.0]);
+        spec.stop = StopCondition::iterations(50);
+        let solution = Solution::from_solver(&solve(&spec).unwrap().solver);
+        let dir = std::env::temp_dir().join(format!("oracle_ev_line_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (input, output) = (dir.join("turn.bin"), dir.join("line.csv"));
+        save_solution(&input, &solution, Compression::None).unwrap();
+
+        let arg = |s: &str| s.to_string();
+        run_ev_line(&[input.display().to_string(), arg("--line"), arg("T:x-x|R:5c|x"), arg("--out"), output.display().to_string()]).unwrap();
+        let csv = std::fs::read_to_string(&output).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], "line,to_act,pot,reach,ev_ip_bb,ev_change_bb,weighted_change_bb,realized_pct");
+        let lines: Vec<&str> = rows[1..].iter().map(|r| r.split(',').next().unwrap()).collect();
+        assert_eq!(lines, vec!["root", "T:x", "T:x-x", "T:x-x|R:5c", "T:x-x|R:5c|x"]);
+        assert!(rows[1].starts_with("root,OOP,6.00,1.000000,") && rows[1].ends_with(",0.0000,0.0000,0.0"), "{}", rows[1]);
+        assert!(rows[3].starts_with("T:x-x,chance,"), "{}", rows[3]);
+        assert!(rows[5].ends_with(",100.0") || rows[5].ends_with(",0.0"), "{}", rows[5]);
+        assert!(run_ev_line(&[input.display().to_string(), arg("--line"), arg("T:x-q")]).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,81 @@
+//! `oracle eval BOARD --hand CARDS` — evaluate one hand on a board
+//!
+//! Prints the hand's rank on the evaluator's scale (1 = royal flush, 7462 =
+//! seven-high), its category description (`HandRank::describe`), and its
+//! percentile: the share of opponent holdings it beats on this board, ties
+//! counting half (`equity::hand_strength`). Flop and turn boards rank the
+//! cards dealt so far.
+
+use oracle_engine::equity::hand_strength;
+use oracle_engine::evaluator::CactusKevEvaluator;
+use oracle_engine::node::{format_cards, parse_cards};
+
+/// Run `oracle eval` with the arguments after the subcommand
+pub fn run_eval(args: &[String]) -> Result<(), String> {
+    let mut board = None;
+    let mut hand = None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--hand", Some(v)) => {
+                hand = Some(v.to_string());
+                i += 2;
+            }
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (b, _) => {
+                board = Some(b.to_string());
+                i += 1;
+            }
+        }
+    }
+    let (board, hand) = board.zip(hand).ok_or("usage: oracle eval BOARD --hand CARDS")?;
+    print!("{}", evaluate(&board, &hand)?);
+    Ok(())
+}
+
+/// The rank, description, and percentile of `hand` on `board`, one per line
+fn evaluate(board: &str, hand: &str) -> Result<String, String> {
+    let board_cards = parse_cards(board).map_err(|e| format!("board: {}", e))?;
+    let hand_cards = parse_cards(hand).map_err(|e| format!("hand: {}", e))?;
+    if !(3..=5).contains(&board_cards.len()) {
+        return Err(format!("board must have 3 to 5 cards, got {}", board_cards.len()));
+    }
+    let hole: [_; 2] = hand_cards
+        .as_slice()
+        .try_into()
+        .map_err(|_| format!("hand must have 2 cards, got {}", hand_cards.len()))?;
+    if let Some(card) = hole.iter().find(|c| board_cards.contains(c)) {
+        return Err(format!("{} is on the board", card));
+    }
+
+    let evaluator = CactusKevEvaluator::new();
+    let rank = evaluator.evaluate_partial(&board_cards, hole);
+    let percentile = 100.0 * hand_strength(&evaluator, hole, &board_cards);
+    Ok(format!(
+        "Board:      {}\nHand:       {}\nRank:       {} of 7462 (1 = royal flush)\nCategory:   {}\nPercentile: {:.1}% of opponent hands beaten (ties count half)\n",
+        format_cards(&board_cards),
+        format_cards(&hole),
+        rank.value(),
+        rank.describe(),
+        percentile
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_reports_rank_category_and_percentile() {
+        let text = evaluate("AsKh7d2c9s", "QsJs").unwrap();
+        assert!(text.contains("Category:   High Card, Ace\n"), "{}", text);
+        let nuts = evaluate("AsKsQs2c9d", "JsTs").unwrap();
+        assert!(nuts.contains("Rank:       1 of 7462"));
+        assert!(nuts.contains("Percentile: 100.0%"));
+        assert!(evaluate("AsKsQs", "Jh").unwrap_err().contains("2 cards"));
+        assert!(evaluate("AsKsQs", "AsJh").unwrap_err().contains("on the board"));
+        assert!(evaluate("AsKs", "JhTh").is_err());
+    }
+}
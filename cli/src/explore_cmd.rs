@@ -0,0 +1,426 @@
+//! `oracle explore` — step through a saved solution interactively
+//!
+//! Each prompt applies whitespace-separated tokens in order, so `x b75 c`
+//! walks three actions and `Qs` deals a runout card at a chance node. A
+//! line in compact notation (`F:x-b75-c|T:Qs`, see `line`) walks the whole
+//! line, and the current line is shown that way.
+
+use std::io::{BufRead, Write};
+use oracle_engine::analysis::{continuing_ranges, node_values};
+use oracle_engine::cfr::RegretStorage;
+use oracle_engine::file_format::load_solution;
+use oracle_engine::grid::RangeGrid;
+use oracle_engine::line::{action_token, chance_child, format_amount, format_line, match_action, parse_card, Line};
+use oracle_engine::node::{format_cards, GameTree, Node, NodeId, Player, Street};
+use oracle_engine::range::Range;
+use oracle_engine::Solution;
+
+/// One action at a decision node, from the acting player's side
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionSummary {
+    pub token: String,
+    /// Average-strategy frequency
+    pub frequency: f64,
+    /// EV for the acting player
+    pub ev: f64,
+}
+
+/// Interactive browser state: the solution plus the path from the root
+pub struct Explorer {
+    tree: GameTree,
+    storage: RegretStorage,
+    values: Vec<f64>,
+    ranges: Option<[Range; 2]>,
+    /// Infosets a partial solution left out
+    omitted: Vec<usize>,
+    path: Vec<NodeId>,
+    history: Vec<String>,
+}
+
+impl Explorer {
+    pub fn new(solution: Solution) -> Result<Self, String> {
+        solution.validate().map_err(|e| e.to_string())?;
+        let evs = solution.terminal_ev_map();
+        let storage = RegretStorage::from_snapshot(solution.storage);
+        let values = node_values(&solution.tree, &storage, &evs);
+        Ok(Explorer {
+            tree: solution.tree,
+            storage,
+            values,
+            ranges: solution.ranges,
+            omitted: solution.omitted,
+            path: vec![0],
+            history: Vec::new(),
+        })
+    }
+
+    /// Override (or supply) the ranges used for the grid
+    pub fn set_ranges(&mut self, ranges: [Range; 2]) {
+        self.ranges = Some(ranges);
+    }
+
+    /// Node the explorer is at
+    pub fn current(&self) -> NodeId {
+        *self.path.last().expect("path always holds the root")
+    }
+
+    fn node(&self) -> &Node {
+        self.tree.get(self.current()).expect("path holds valid node ids")
+    }
+
+    /// Apply one step token: an action, a runout card, `back`, or `root`
+    pub fn step(&mut self, token: &str) -> Result<(), String> {
+        match token {
+            "back" | "u" => {
+                if self.path.len() > 1 {
+                    self.path.pop();
+                    self.history.pop();
+                }
+                return Ok(());
+            }
+            "root" => {
+                self.path.truncate(1);
+                self.history.clear();
+                return Ok(());
+            }
+            _ => {}
+        }
+        let (child, label) = match self.node() {
+            Node::Decision { actions, children, pot, .. } => {
+                let index = match_action(token, actions, *pot)?;
+                (children[index], action_token(&actions[index], *pot))
+            }
+            Node::Chance { children, .. } => {
+                let card = parse_card(token)?;
+                let child = chance_child(&self.tree, children, card)
+                    .ok_or_else(|| format!("{} cannot be dealt here", token))?;
+                (child, format_cards(&[card]))
+            }
+            Node::Terminal { .. } => return Err("hand is over; use 'back' or 'root'".to_string()),
+        };
+        self.path.push(child);
+        self.history.push(label);
+        Ok(())
+    }
+
+    /// Apply every token of `text`, a line in either notation (see `line`),
+    /// checking its street marks on the way
+    pub fn follow(&mut self, text: &str) -> Result<(), String> {
+        let line = Line::parse(text)?;
+        for (i, token) in line.tokens.iter().enumerate() {
+            self.step(token)?;
+            line.check_street(&self.tree, i, self.current())?;
+        }
+        Ok(())
+    }
+
+    /// Steps taken from the root, as tokens
+    pub fn line(&self) -> &[String] {
+        &self.history
+    }
+
+    /// The current node's line in compact notation (`root` at the root)
+    pub fn line_notation(&self) -> String {
+        format_line(&self.tree, self.current())
+    }
+
+    /// The acting player and a summary of each action at the current node;
+    /// `None` off decision nodes
+    pub fn action_summary(&self) -> Option<(Player, Vec<ActionSummary>)> {
+        let Node::Decision { player, actions, children, pot, infoset_id, .. } = self.node() else { return None };
+        let strategy = self.storage.average_strategy(*infoset_id as usize);
+        let summary = actions.iter().zip(children).zip(strategy)
+            .map(|((action, &child), frequency)| ActionSummary {
+                token: action_token(action, *pot),
+                frequency,
+                ev: perspective(self.values[child as usize], *player),
+            })
+            .collect();
+        Some((*player, summary))
+    }
+
+    /// Describe the current node: actions with frequencies and EVs, or runouts
+    pub fn describe(&self) -> String {
+        let id = self.current();
+        let ip_ev = self.values[id as usize];
+        let mut out = String::new();
+        out.push_str(&format!("Line: {}\n", self.line_notation()));
+        match self.node() {
+            Node::Decision { player, street, actions, children, pot, stacks, board, infoset_id, .. } => {
+                out.push_str(&format!(
+                    "{} | {:?} to act | board {} | pot {} | stacks {}/{} (IP/OOP)\n",
+                    street_name(*street), player, format_cards(board), format_amount(*pot), format_amount(stacks[0]), format_amount(stacks[1]),
+                ));
+                let node = self.node();
+                let to_call = match node.amount_to_call() {
+                    c if c > 0.0 => format!(" | to call {} ({:.1}% pot odds)", format_amount(c), node.pot_odds() * 100.0),
+                    _ => String::new(),
+                };
+                out.push_str(&format!("SPR {:.2}{}\n", node.spr(), to_call));
+                out.push_str(&format!("EV: {:+.3} bb for {:?}\n", perspective(ip_ev, *player), player));
+                if self.omitted.binary_search(&(*infoset_id as usize)).is_ok() {
+                    out.push_str("Strategy left out of this partial solution (shown uniform)\n");
+                }
+                let strategy = self.storage.average_strategy(*infoset_id as usize);
+                for ((action, &child), freq) in actions.iter().zip(children).zip(&strategy) {
+                    out.push_str(&format!(
+                        "  {:<6} {:<12} {:>6.1}%  EV {:+.3}\n",
+                        action_token(action, *pot),
+                        action.to_string(),
+                        freq * 100.0,
+                        perspective(self.values[child as usize], *player),
+                    ));
+                }
+            }
+            Node::Chance { street, children, board, .. } => {
+                out.push_str(&format!(
+                    "Deal after {} on {} | {} runouts | EV {:+.3} bb for IP\n",
+                    street_name(*street), format_cards(board), children.len(), ip_ev,
+                ));
+                let runouts: Vec<String> = children.iter().filter_map(|&c| {
+                    let card = *self.tree.get(c)?.board().last()?;
+                    Some(format!("{} {:+.2}", format_cards(&[card]), self.values[c as usize]))
+                }).collect();
+                for chunk in runouts.chunks(8) {
+                    out.push_str(&format!("  {}\n", chunk.join("  ")));
+                }
+            }
+            Node::Terminal { folder, pot, .. } => {
+                let how = match folder {
+                    Some(p) => format!("{:?} folds", p),
+                    None => "showdown".to_string(),
+                };
+                out.push_str(&format!("Terminal: {} | pot {} | EV {:+.3} bb for IP\n", how, format_amount(*pot), ip_ev));
+            }
+        }
+        out
+    }
+
+    /// 13x13 grid of the acting player's range with each cell's dominant action
+    pub fn grid(&self) -> Result<String, String> {
+        let (player, actions, pot, board, infoset_id) = match self.node() {
+            Node::Decision { player, actions, pot, board, infoset_id, .. } => (*player, actions, *pot, board, *infoset_id),
+            _ => return Err("grid is only shown at decision nodes".to_string()),
+        };
+        let ranges = self.ranges.as_ref()
+            .ok_or("solution has no ranges; pass --ip-range and --oop-range")?;
+        let range = &ranges[usize::from(player == Player::OOP)];
+        let strategy = self.storage.average_strategy(infoset_id as usize);
+        let grid = RangeGrid::from_node_strategy(range, board, actions, &strategy);
+        let tokens: Vec<String> = actions.iter().map(|a| action_token(a, pot)).collect();
+        Ok(render_grid(&grid, &tokens))
+    }
+
+    /// Both players' continuing ranges at the current node in range
+    /// notation, keeping combos weighted at least `threshold`
+    pub fn ranges_here(&self, threshold: f64) -> Result<String, String> {
+        let ranges = self.ranges.as_ref()
+            .ok_or("solution has no ranges; pass --ip-range and --oop-range")?;
+        let [ip, oop] = continuing_ranges(&self.tree, &self.storage, ranges, self.current());
+        let board = self.node().board();
+        let show = |r: &Range| match r.to_notation(board, threshold) {
+            s if s.is_empty() => "(empty)".to_string(),
+            s => s,
+        };
+        Ok(format!("IP : {}\nOOP: {}\n", show(&ip), show(&oop)))
+    }
+}
+
+/// Render cells as `AKs:b75` (dominant action) or `.` when the class is not in range
+fn render_grid(grid: &RangeGrid, tokens: &[String]) -> String {
+    let mut out = String::new();
+    for row in 0..13 {
+        let cells: Vec<String> = (0..13).map(|col| {
+            let cell = grid.cell(row, col);
+            if cell.combos <= 0.0 {
+                return format!("{:<9}", ".");
+            }
+            let top = cell.actions.iter().enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map_or("", |(i, _)| tokens[i].as_str());
+            format!("{:<9}", format!("{}:{}", cell.label, top))
+        }).collect();
+        out.push_str(cells.join("").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn perspective(ip_ev: f64, player: Player) -> f64 {
+    if player == Player::IP { ip_ev } else { 0.0 - ip_ev }
+}
+
+fn street_name(street: Street) -> &'static str {
+    match street {
+        Street::Flop => "Flop",
+        Street::Turn => "Turn",
+        Street::River => "River",
+    }
+}
+
+const HELP: &str = "\
+Tokens (several per line are applied in order):
+  f x c         fold, check, call
+  b  b75        smallest bet / bet closest to 75% pot
+  r  r20        smallest raise / raise closest to 20bb total
+  allin         largest bet or raise
+  0 1 ...       action by index
+  Qs            deal a card at a chance node
+  F:x-b75|T:Qs  walk a whole line in compact notation
+  back, root    undo one step / return to the root
+  grid          show the acting player's strategy grid
+  range [T]     both players' ranges here, in range notation (combos weighted >= T)
+  help, quit
+";
+
+/// Read commands from `input` until EOF or `quit`
+pub fn repl(explorer: &mut Explorer, input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+    write!(output, "{}> ", explorer.describe())?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let mut show = true;
+        let mut tokens = line.split_whitespace().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                "quit" | "q" | "exit" => return Ok(()),
+                "help" | "?" => {
+                    write!(output, "{}", HELP)?;
+                    show = false;
+                }
+                "grid" => {
+                    match explorer.grid() {
+                        Ok(grid) => write!(output, "{}", grid)?,
+                        Err(e) => writeln!(output, "{}", e)?,
+                    }
+                    show = false;
+                }
+                "range" => {
+                    let threshold = tokens.next_if(|t| t.parse::<f64>().is_ok()).map_or(0.0, |t| t.parse().unwrap_or(0.0));
+                    match explorer.ranges_here(threshold) {
+                        Ok(ranges) => write!(output, "{}", ranges)?,
+                        Err(e) => writeln!(output, "{}", e)?,
+                    }
+                    show = false;
+                }
+                step => {
+                    let walked = if step.contains(['|', ':', '-']) { explorer.follow(step) } else { explorer.step(step) };
+                    if let Err(e) = walked {
+                        writeln!(output, "{}", e)?;
+                        break;
+                    }
+                    show = true;
+                }
+            }
+        }
+        if show {
+            write!(output, "{}", explorer.describe())?;
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+/// Run `oracle explore` with the arguments after `explore`
+pub fn run_explore(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut ip_range = None;
+    let mut oop_range = None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--ip-range", Some(v)) => ip_range = Some(Range::parse(v).map_err(|e| e.to_string())?),
+            ("--oop-range", Some(v)) => oop_range = Some(Range::parse(v).map_err(|e| e.to_string())?),
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (file, _) => {
+                path = Some(file.to_string());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+
+    let path = path.ok_or("usage: oracle explore SOLUTION [--ip-range R --oop-range R]")?;
+    let solution = load_solution(&path).map_err(|e| format!("{}: {}", path, e))?;
+    println!("Loaded {} ({} nodes, {} iterations, fingerprint {}). Type 'help' for commands.", path, solution.tree.len(), solution.iterations, solution.fingerprint());
+    let mut explorer = Explorer::new(solution)?;
+    match (ip_range, oop_range) {
+        (Some(ip), Some(oop)) => explorer.set_ranges([ip, oop]),
+        (None, None) => {}
+        _ => return Err("--ip-range and --oop-range must be given together".to_string()),
+    }
+
+    let stdin = std::io::stdin();
+    repl(&mut explorer, stdin.lock(), std::io::stdout()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::builder::{build_tree, StreetSizes, TreeConfig};
+    use oracle_engine::game::GameConfig;
+    use oracle_engine::node::parse_cards;
+    use oracle_engine::solve::{solve, SolveSpec};
+    use oracle_engine::StopCondition;
+
+    fn turn_explorer() -> Explorer {
+        let board = parse_cards("AsKh7d2c").unwrap();
+        let ranges = [Range::parse("AK,77").unwrap(), Range::parse("AQ,KQ").unwrap()];
+        let mut spec = SolveSpec::new(board, ranges.clone(), GameConfig::new());
+        spec.tree_config.turn = StreetSizes::new(vec![0.75]);
+        spec.tree_config.river = StreetSizes::new(vec![0.75]);
+        spec.tree_config.max_raises = 1;
+        spec.stop = StopCondition::iterations(50);
+        let result = solve(&spec).unwrap();
+        Explorer::new(Solution::from_solver(&result.solver).with_ranges(ranges)).unwrap()
+    }
+
+    #[test]
+    fn test_step_through_actions_and_runouts() {
+        let mut explorer = turn_explorer();
+        explorer.step("x").unwrap();
+        explorer.step("x").unwrap();
+        assert!(explorer.node().is_chance());
+        assert!(explorer.step("Kh").is_err(), "board card cannot be dealt");
+        explorer.step("Qs").unwrap();
+        assert_eq!(explorer.node().board().len(), 5);
+        assert!(explorer.describe().starts_with("Line: T:x-x|R:Qs\nRiver"));
+        explorer.step("back").unwrap();
+        assert!(explorer.node().is_chance());
+        explorer.step("root").unwrap();
+        assert_eq!(explorer.current(), 0);
+        explorer.follow("T:x-x|R:Qs").unwrap();
+        assert_eq!(explorer.node().board().len(), 5);
+        explorer.step("root").unwrap();
+        assert!(explorer.follow("F:x").unwrap_err().contains("not the flop"));
+        explorer.step("root").unwrap();
+        // OOP acts first: AQ/KQ cells are filled, IP's AK is not
+        let grid = explorer.grid().unwrap();
+        assert!(grid.contains("AQo:x") || grid.contains("AQo:b"), "{}", grid);
+        assert!(!grid.contains("AKs:"));
+        assert_eq!(explorer.ranges_here(0.0).unwrap(), "IP : 77,AK\nOOP: AQ,KQ\n");
+        assert!(explorer.ranges_here(1.5).unwrap().starts_with("IP : (empty)"));
+    }
+
+    #[test]
+    fn test_repl_reports_errors_and_quits() {
+        let tree = build_tree(&TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c9s").unwrap()));
+        let solver = oracle_engine::CfrSolver::new_with_evs(
+            tree.clone(),
+            tree.nodes.iter().filter(|n| n.is_terminal()).map(|n| (n.id(), 0.0)).collect::<oracle_engine::EvTable>(),
+        );
+        let mut explorer = Explorer::new(Solution::from_solver(&solver)).unwrap();
+        let mut out = Vec::new();
+        repl(&mut explorer, &b"c\nR:x-b\ngrid\nrange 0.5 c\nquit\nx\n"[..], &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("'c' is not available here"));
+        assert!(text.contains("Line: R:x-b75"), "{}", text);
+        assert!(text.contains("SPR 9.70 | to call 4.12 (30.0% pot odds)"), "{}", text);
+        assert!(text.contains("solution has no ranges"));
+        assert_eq!(explorer.history, vec!["x", "b75", "c"]);
+    }
+}
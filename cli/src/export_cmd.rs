@@ -0,0 +1,60 @@
+//! `oracle export-training` — write a saved solution's decisions as ML training records
+
+use oracle_engine::file_format::load_solution;
+use oracle_engine::training::export_training_data;
+
+/// Run `oracle export-training` with the arguments after the subcommand
+pub fn run_export_training(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut out = String::from("training.ortd");
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--out", Some(v)) => {
+                out = v.to_string();
+                i += 2;
+            }
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (file, _) => {
+                path = Some(file.to_string());
+                i += 1;
+            }
+        }
+    }
+    let path = path.ok_or("usage: oracle export-training SOLUTION [--out FILE]")?;
+
+    let solution = load_solution(&path).map_err(|e| format!("{}: {}", path, e))?;
+    solution.validate().map_err(|e| format!("{}: {}", path, e))?;
+    let records = export_training_data(&out, &solution).map_err(|e| format!("{}: {}", out, e))?;
+    println!("Wrote {} records ({} iterations) to {}", records, solution.iterations, out);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::file_format::{save_solution, Compression};
+    use oracle_engine::test_tree::build_test_tree;
+    use oracle_engine::training::read_training_data;
+    use oracle_engine::{CfrSolver, Solution};
+
+    #[test]
+    fn test_export_writes_one_record_per_decision() {
+        let dir = std::env::temp_dir().join(format!("oracle_export_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (solution_path, out) = (dir.join("solution.bin"), dir.join("train.ortd"));
+        let mut solver = CfrSolver::new(build_test_tree());
+        solver.run_iteration();
+        let solution = Solution::from_solver(&solver);
+        save_solution(&solution_path, &solution, Compression::None).unwrap();
+
+        let args = [solution_path.display().to_string(), "--out".to_string(), out.display().to_string()];
+        run_export_training(&args).unwrap();
+        let records = read_training_data(std::fs::File::open(&out).unwrap()).unwrap();
+        assert_eq!(records.len(), solution.tree.nodes.iter().filter(|n| n.is_decision()).count());
+        assert!(run_export_training(&["--bogus".to_string()]).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
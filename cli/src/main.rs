@@ -3,9 +3,42 @@
 //! This binary provides a CLI harness for testing engine functionality
 //! before UI integration.
 
-use oracle_engine::evaluator::benchmark_throughput;
-use oracle_engine::{CfrSolver, compute_exploitability};
-use oracle_engine::test_tree::build_test_tree;
+use oracle_engine::analysis::{strategy_mixing, MixingSummary};
+use oracle_engine::evaluator::benchmark_throughput_with_seed;
+use oracle_engine::rng::DEFAULT_SEED;
+use oracle_engine::distributed::split_roots;
+use oracle_engine::file_format::{save_solution, Compression};
+use oracle_engine::config::Algorithm;
+use oracle_engine::node::Node;
+use oracle_engine::memory::parse_memory;
+use oracle_engine::presets;
+use oracle_engine::units::{Unit, Units};
+use oracle_engine::{CfrSolver, ConvergenceMetrics, Solution, SolverConfig, Stagnation, StopReason, StreetTargets, Summation};
+use oracle_engine::test_tree::{build_test_tree, terminal_ev_table};
+use std::time::Duration;
+
+mod acpc_cmd;
+mod analyze_cmd;
+mod batch_cmd;
+mod bench_cmd;
+mod compare_cmd;
+mod equity_cmd;
+mod ev_line_cmd;
+mod eval_cmd;
+mod explore_cmd;
+mod export_cmd;
+mod merge_cmd;
+mod preset_cmd;
+#[cfg(feature = "render")]
+mod render_cmd;
+mod report_cmd;
+mod serve_cmd;
+mod spot_cmd;
+mod tree_cmd;
+mod trim_cmd;
+mod worker_cmd;
+#[cfg(feature = "tui")]
+mod tui;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -13,61 +46,174 @@ fn main() {
     if args.len() >= 3 && args[1] == "bench" && args[2] == "evaluator" {
         // Run evaluator benchmark
         println!("Running hand evaluator benchmark...");
-        let sample_size = if args.len() >= 4 {
-            args[3].parse().unwrap_or(1_000_000)
-        } else {
-            1_000_000
-        };
+        let mut sample_size: usize = 1_000_000;
+        let mut seed: u64 = DEFAULT_SEED;
+
+        let mut i = 3usize;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--seed" if i + 1 < args.len() => {
+                    seed = args[i + 1].parse().unwrap_or(DEFAULT_SEED);
+                    i += 2;
+                }
+                other => {
+                    sample_size = other.parse().unwrap_or(sample_size);
+                    i += 1;
+                }
+            }
+        }
 
         println!("Sample size: {} hands", sample_size);
-        let (evals_per_sec, duration_ms) = benchmark_throughput(sample_size);
+        println!("Seed: {}", seed);
+        let (evals_per_sec, duration_ms) = benchmark_throughput_with_seed(sample_size, seed);
 
         println!("Results:");
         println!("  Duration: {} ms", duration_ms);
         println!("  Throughput: {:.2} evals/sec", evals_per_sec);
         println!("  Throughput: {:.2}M evals/sec", evals_per_sec / 1_000_000.0);
 
+    } else if args.len() >= 2 && args[1] == "bench" {
+        if let Err(e) = bench_cmd::run_bench(&args[2..]) {
+            eprintln!("bench failed: {}", e);
+            std::process::exit(2);
+        }
+
     } else if args.len() >= 2 && args[1] == "solve" {
-        // Parse optional flags
-        let mut max_iterations: u64 = 10_000;
-        let mut threshold: f64 = 0.01;
-        let mut check_every: u64 = 100;
-        let mut time_cap_secs: u64 = 60;
+        // Parse optional flags into a solver config
+        let mut builder = SolverConfig::builder()
+            .max_iterations(10_000)
+            .target_exploitability(0.01)
+            .check_every(100)
+            .deterministic(false)
+            .time_limit(Duration::from_secs(60));
+        let mut out: Option<String> = None;
+        let mut tui = false;
+        let mut workers = None;
+        let mut preset: Option<String> = None;
+        let mut board: Option<String> = None;
+        let mut ip_range: Option<String> = None;
+        let mut oop_range: Option<String> = None;
+        let mut pot = spot_cmd::DEFAULT_POT;
+        let mut stacks = spot_cmd::DEFAULT_STACKS;
+        let mut locks: Option<String> = None;
+        let mut population: Option<String> = None;
+        let mut max_memory: Option<u64> = None;
+        let mut threshold: Option<f64> = None;
+        let mut street_targets: Option<[Option<f64>; 3]> = None;
+        let mut unit = Unit::Bb;
 
         let mut i = 2usize;
         while i < args.len() {
             match args[i].as_str() {
-                "--iterations" => {
-                    if i + 1 < args.len() {
-                        max_iterations = args[i + 1].parse().unwrap_or(10_000);
-                        i += 2;
-                    } else {
-                        i += 1;
-                    }
+                "--iterations" if i + 1 < args.len() => {
+                    builder = builder.max_iterations(args[i + 1].parse().unwrap_or(10_000));
+                    i += 2;
                 }
-                "--threshold" => {
-                    if i + 1 < args.len() {
-                        threshold = args[i + 1].parse().unwrap_or(0.01);
-                        i += 2;
-                    } else {
-                        i += 1;
-                    }
+                "--threshold" if i + 1 < args.len() => {
+                    threshold = Some(args[i + 1].parse().unwrap_or(0.01));
+                    i += 2;
                 }
-                "--check-every" => {
-                    if i + 1 < args.len() {
-                        check_every = args[i + 1].parse().unwrap_or(100);
-                        i += 2;
-                    } else {
-                        i += 1;
-                    }
+                "--street-targets" if i + 1 < args.len() => {
+                    street_targets = match parse_street_targets(&args[i + 1]) {
+                        Ok(targets) => Some(targets),
+                        Err(e) => {
+                            eprintln!("Invalid solve options: {}", e);
+                            std::process::exit(2);
+                        }
+                    };
+                    i += 2;
                 }
-                "--time-cap" => {
-                    if i + 1 < args.len() {
-                        time_cap_secs = args[i + 1].parse().unwrap_or(60);
-                        i += 2;
-                    } else {
-                        i += 1;
-                    }
+                "--units" if i + 1 < args.len() => {
+                    unit = match args[i + 1].parse() {
+                        Ok(unit) => unit,
+                        Err(e) => {
+                            eprintln!("Invalid solve options: {}", e);
+                            std::process::exit(2);
+                        }
+                    };
+                    i += 2;
+                }
+                "--check-every" if i + 1 < args.len() => {
+                    builder = builder.check_every(args[i + 1].parse().unwrap_or(100));
+                    i += 2;
+                }
+                "--time-cap" if i + 1 < args.len() => {
+                    builder = builder.time_limit(Duration::from_secs(args[i + 1].parse().unwrap_or(60)));
+                    i += 2;
+                }
+                "--stagnation" if i + 1 < args.len() => {
+                    builder = builder.stagnation(Stagnation::new(args[i + 1].parse().unwrap_or(5)));
+                    i += 2;
+                }
+                "--threads" if i + 1 < args.len() => {
+                    builder = builder.threads(args[i + 1].parse().unwrap_or(0));
+                    i += 2;
+                }
+                "--out" if i + 1 < args.len() => {
+                    out = Some(args[i + 1].clone());
+                    i += 2;
+                }
+                "--workers" if i + 1 < args.len() => {
+                    workers = Some(worker_cmd::parse_workers(&args[i + 1]));
+                    i += 2;
+                }
+                "--preset" if i + 1 < args.len() => {
+                    preset = Some(args[i + 1].clone());
+                    i += 2;
+                }
+                "--board" if i + 1 < args.len() => {
+                    board = Some(args[i + 1].clone());
+                    i += 2;
+                }
+                "--ip-range" if i + 1 < args.len() => {
+                    ip_range = Some(args[i + 1].clone());
+                    i += 2;
+                }
+                "--oop-range" if i + 1 < args.len() => {
+                    oop_range = Some(args[i + 1].clone());
+                    i += 2;
+                }
+                "--pot" if i + 1 < args.len() => {
+                    pot = args[i + 1].parse().unwrap_or(spot_cmd::DEFAULT_POT);
+                    i += 2;
+                }
+                "--stacks" if i + 1 < args.len() => {
+                    stacks = tree_cmd::parse_stacks(&args[i + 1]).unwrap_or(spot_cmd::DEFAULT_STACKS);
+                    i += 2;
+                }
+                "--locks" if i + 1 < args.len() => {
+                    locks = Some(args[i + 1].clone());
+                    i += 2;
+                }
+                "--population" if i + 1 < args.len() => {
+                    population = Some(args[i + 1].clone());
+                    i += 2;
+                }
+                "--max-memory" if i + 1 < args.len() => {
+                    max_memory = match parse_memory(&args[i + 1]) {
+                        Ok(bytes) => Some(bytes),
+                        Err(e) => {
+                            eprintln!("Invalid solve options: {}", e);
+                            std::process::exit(2);
+                        }
+                    };
+                    i += 2;
+                }
+                "--tui" => {
+                    tui = true;
+                    i += 1;
+                }
+                "--profile" => {
+                    builder = builder.profile(true);
+                    i += 1;
+                }
+                "--predictive" => {
+                    builder = builder.algorithm(Algorithm::PredictiveCfrPlus);
+                    i += 1;
+                }
+                "--compensated" => {
+                    builder = builder.summation(Summation::Compensated);
+                    i += 1;
                 }
                 _ => {
                     i += 1;
@@ -75,50 +221,384 @@ fn main() {
             }
         }
 
-        run_solve(max_iterations, threshold, check_every, time_cap_secs);
+        // A board without a preset is a spot given by flags
+        let spot = match (&preset, board.clone()) {
+            (None, Some(board)) => Some(spot_cmd::SpotArgs { board, ip_range, oop_range, pot, stacks, locks, population }),
+            _ => None,
+        };
+        // The threshold is in `unit`, which may depend on the spot's pot
+        let units = match (preset.as_deref().and_then(presets::find), &spot) {
+            (Some(p), _) => Units::for_game(&p.game),
+            (None, Some(spot)) => spot.units(),
+            (None, None) => test_tree_units(),
+        };
+        if let Some(t) = threshold {
+            builder = builder.target_exploitability(units.to_bb(t, unit));
+        }
+        if let Some([flop, turn, river]) = street_targets {
+            // Street targets replace the default overall threshold
+            if threshold.is_none() {
+                builder = builder.no_target();
+            }
+            let to_bb = |t: Option<f64>| t.map(|t| units.to_bb(t, unit));
+            builder = builder.street_targets(StreetTargets { flop: to_bb(flop), turn: to_bb(turn), river: to_bb(river) });
+        }
+
+        if (preset.is_some() || spot.is_some()) && (tui || workers.is_some()) {
+            eprintln!("Invalid solve options: --tui and --workers can't be used with --board or --preset");
+            std::process::exit(2);
+        }
+        match builder.build() {
+            Ok(config) => match (preset, spot) {
+                (Some(name), _) => {
+                    if let Err(e) = preset_cmd::run_preset_solve(&name, board.as_deref(), &config, unit, out.as_deref(), max_memory) {
+                        eprintln!("solve failed: {}", e);
+                        std::process::exit(2);
+                    }
+                }
+                (None, Some(spot)) => {
+                    if let Err(e) = spot_cmd::run_spot_solve(&spot, &config, unit, out.as_deref(), max_memory) {
+                        eprintln!("solve failed: {}", e);
+                        std::process::exit(2);
+                    }
+                }
+                (None, None) => run_solve(config, out.as_deref(), tui, workers, (units, unit)),
+            },
+            Err(e) => {
+                eprintln!("Invalid solve options: {}", e);
+                std::process::exit(2);
+            }
+        }
+
+    } else if args.len() >= 3 && args[1] == "tree" && args[2] == "build" {
+        if let Err(e) = tree_cmd::run_tree_build(&args[3..]) {
+            eprintln!("tree build failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 3 && args[1] == "tree" && args[2] == "show" {
+        if let Err(e) = tree_cmd::run_tree_show(&args[3..]) {
+            eprintln!("tree show failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "batch" {
+        if let Err(e) = batch_cmd::run_batch(&args[2..]) {
+            eprintln!("batch failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "worker" {
+        if let Err(e) = worker_cmd::run_worker_cmd(&args[2..]) {
+            eprintln!("worker failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "serve" {
+        if let Err(e) = serve_cmd::run_serve(&args[2..]) {
+            eprintln!("serve failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "compare-algos" {
+        if let Err(e) = compare_cmd::run_compare_algos(&args[2..]) {
+            eprintln!("compare-algos failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "equity" {
+        if let Err(e) = equity_cmd::run_equity(&args[2..]) {
+            eprintln!("equity failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "eval" {
+        if let Err(e) = eval_cmd::run_eval(&args[2..]) {
+            eprintln!("eval failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "explore" {
+        if let Err(e) = explore_cmd::run_explore(&args[2..]) {
+            eprintln!("explore failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "export-training" {
+        if let Err(e) = export_cmd::run_export_training(&args[2..]) {
+            eprintln!("export-training failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "report" {
+        if let Err(e) = report_cmd::run_report(&args[2..]) {
+            eprintln!("report failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "render" {
+        if let Err(e) = run_render(&args[2..]) {
+            eprintln!("render failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "acpc" {
+        if let Err(e) = acpc_cmd::run_acpc(&args[2..]) {
+            eprintln!("acpc failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "analyze" {
+        if let Err(e) = analyze_cmd::run_analyze(&args[2..]) {
+            eprintln!("analyze failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "ev-line" {
+        if let Err(e) = ev_line_cmd::run_ev_line(&args[2..]) {
+            eprintln!("ev-line failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "merge-sizes" {
+        if let Err(e) = merge_cmd::run_merge_sizes(&args[2..]) {
+            eprintln!("merge-sizes failed: {}", e);
+            std::process::exit(2);
+        }
+
+    } else if args.len() >= 2 && args[1] == "trim" {
+        if let Err(e) = trim_cmd::run_trim(&args[2..]) {
+            eprintln!("trim failed: {}", e);
+            std::process::exit(2);
+        }
 
     } else {
         println!("oracle Solver CLI v{}", env!("CARGO_PKG_VERSION"));
         println!("Phase 2 - CFR+ Solver");
         println!();
         println!("Usage:");
-        println!("  oracle bench evaluator [sample_size]");
+        println!("  oracle bench evaluator [sample_size] [--seed N]");
+        println!("  oracle bench [--save-baseline NAME] [--compare NAME] [options]");
         println!("  oracle solve [options]");
+        println!("  oracle tree build --board CARDS [options]");
+        println!("  oracle tree show TREE [--depth N]");
+        println!("  oracle compare-algos --config SPOT.toml [--algos cfr+,dcfr,lcfr] [--budget 60s] [--out FILE.csv]");
+        println!("  oracle eval BOARD --hand CARDS");
+        println!("  oracle equity --range1 R --range2 R [--board CARDS] [--samples N] [--seed N]");
+        println!("  oracle explore SOLUTION [--ip-range R --oop-range R]");
+        println!("  oracle export-training SOLUTION [--out FILE]");
+        println!("  oracle acpc SOLUTION --connect HOST:PORT [--bb-chips N] [--seed N]");
+        println!("  oracle analyze HH_DIR --solutions DIR [--player NAME] [--iterations N] [--bb-chips N] [--all-in-ev]");
+        println!("  oracle ev-line SOLUTION --line LINE [--out FILE.csv]");
+        println!("  oracle merge-sizes SOLUTION.bin [--ev-tol F] [--strategy-tol F] [--out FILE]");
+        println!("  oracle trim SOLUTION --min-reach F --out FILE [--compress]");
+        println!("  oracle batch JOBS.toml [--threads N] [--out DIR]");
+        println!("  oracle worker [--listen ADDR]");
+        println!("  oracle serve [JOBS.toml] [--listen ADDR] [--out DIR]");
         println!();
         println!("Commands:");
         println!("  bench evaluator          Run hand evaluator throughput benchmark");
-        println!("  solve                    Solve the test tree via CFR+ and report convergence");
+        println!("  bench                    Run the evaluator + solver suite; save or compare a JSON baseline");
+        println!("  solve                    Solve a spot (--board and ranges), a preset, or the test tree and report convergence");
+        println!("  tree build               Build a postflop tree, print stats, and save it");
+        println!("  tree show                Print a saved tree as indented actions to --depth (default: 3)");
+        println!("  compare-algos            Run CFR variants on one spot for a time budget; write exploitability-vs-time CSV");
+        println!("  equity                   Range-vs-range equity with combo counts and a per-class breakdown");
+        println!("  eval                     Print a hand's rank, category, and percentile on a board");
+        println!("  explore                  Step through a saved solution interactively");
+        println!("  export-training          Write (state, average strategy, EVs) records for ML training");
+        println!("  render                   Draw a node's 13x13 strategy grid to SVG or PNG (build with --features render)");
+        println!("  acpc                     Play a solution against an ACPC dealer (check/call off the tree)");
+        println!("  analyze                  Grade a player's hands from ACPC logs against a solution library; per-line EV loss");
+        println!("  ev-line                  Per-node EV, reach and EV realized along one line of a solution, as CSV");
+        println!("  merge-sizes              Merge bet sizes a solution plays alike; EV-loss bound and smaller tree");
+        println!("  trim                     Save a partial solution: only infosets reached at least --min-reach often");
+        println!("  batch                    Solve every spot in a TOML job file and write a manifest");
+        println!("  report                   Aggregate a directory of solutions by board texture and position (CSV/JSON)");
+        println!("  worker                   Serve chance subtrees to a distributed solve (experimental)");
+        println!("  serve                    Solve queued spots in the background; JSON API at /solves, Prometheus metrics at /metrics");
         println!();
         println!("Solve options:");
         println!("  --iterations N           Max CFR+ iterations (default: 10000)");
-        println!("  --threshold T            Stop when exploitability < T bb (default: 0.01)");
+        println!("  --threshold T            Stop when exploitability < T, in --units (default: 0.01 bb)");
+        println!("  --street-targets F,T,R   Stop when each street's share of exploitability is under its target, in --units ('-' for none)");
+        println!("  --units U                Unit for --threshold, --street-targets, and results: bb, chips, pot (% pot), bb100");
         println!("  --check-every N          Check exploitability every N iterations (default: 100)");
         println!("  --time-cap S             Stop after S seconds (default: 60)");
+        println!("  --stagnation N           Stop when N checks in a row gain under 1% on the best exploitability");
+        println!("  --threads N              Solve on a dedicated pool of N threads (default: global pool)");
+        println!("  --out PATH               Save the solution for 'oracle explore'");
+        println!("  --workers LIST           Farm chance subtrees out to host:port workers (test tree only, experimental)");
+        println!("  --tui                    Live dashboard instead of the table (test tree only; build with --features tui)");
+        println!("  --profile                Print where solve time went (traversal, updates, checks, by street)");
+        println!("  --compensated            Add up EVs with compensated (Neumaier) summation");
+        println!("  --predictive             Use predictive CFR+ (PCFR+) instead of CFR+");
+        println!("  --board CARDS            Solve this flop, turn, or river spot instead of the test tree");
+        println!("  --ip-range R             IP's range, e.g. 'AA-TT,AK,AQs' (required with --board)");
+        println!("  --oop-range R            OOP's range (required with --board)");
+        println!("  --pot P                  Starting pot in bb for --board (default: 10)");
+        println!("  --stacks S[,S]           Stacks behind for --board, both or IP,OOP (default: 95)");
+        println!("  --locks PATH             Node-lock file for --board: 'TARGET = FREQUENCIES' lines, e.g.");
+        println!("                           'x b75 = 0.3 0.7 0' or 'all oop river lead = b:0'");
+        println!("  --population PATH        Population stats for --board: 'TARGET, ACTION, FREQ[, SAMPLES]' rows,");
+        println!("                           locked like --locks (explicit --locks lines win)");
+        println!("  --preset NAME            Solve a ready-made spot instead: srp-btn-bb, 3bp-co-btn, mtt-bvb-40bb");
+        println!("                           (--board then picks its flop, turn, or river; default: the preset's flop)");
+        println!("  --max-memory SIZE        Memory budget for --board or --preset, e.g. 8G: estimated before the tree is");
+        println!("                           built; over it, runouts are grouped by suit isomorphism (suit-symmetric ranges");
+        println!("                           only) or the solve is refused");
+        println!();
+        println!("Tree build options:");
+        println!("  --board CARDS            Flop, turn, or river board, e.g. AsKh7d (required)");
+        println!("  --pot P                  Starting pot in bb (default: 10)");
+        println!("  --stacks S[,S]           Stacks behind, both or IP,OOP (default: 95)");
+        println!("  --flop-bets LIST         Bet sizes in % pot, 'allin' allowed (default: 33,75)");
+        println!("  --turn-bets LIST         (default: 66)");
+        println!("  --river-bets LIST        (default: 66)");
+        println!("  --max-raises N           Bets + raises per street (default: 3)");
+        println!("  --out PATH               Output file (default: tree.bin)");
+        println!("  --compress               zstd-compress the output");
+        println!("  --isomorphic             Deal one weighted card per suit-isomorphic runout class");
         println!();
         println!("Examples:");
         println!("  oracle bench evaluator              # 1M hand benchmark");
         println!("  oracle bench evaluator 10000000     # 10M hand benchmark");
+        println!("  oracle bench evaluator --seed 42    # reproducible hands from seed 42");
         println!("  oracle solve                        # solve with defaults");
         println!("  oracle solve --iterations 5000 --threshold 0.005");
+        println!("  oracle solve --units pot --threshold 0.1  # stop under 0.1% of the pot");
+        println!("  oracle solve --board AsKh7d2c --ip-range AA-TT,AK --oop-range 99-66,AQ,KQ --pot 20 --stacks 80");
+        println!("  oracle solve --preset srp-btn-bb --board Td9d8c --iterations 500 --out srp.bin");
+        println!("  oracle tree build --board AsKh7d2c --pot 10 --stacks 95 --turn-bets 66 --river-bets 66,allin --out tree.bin");
+        println!("  oracle solve --out solution.bin && oracle explore solution.bin");
+    }
+}
+
+/// `--street-targets`: flop, turn, and river targets, `-` for a street without one
+fn parse_street_targets(s: &str) -> Result<[Option<f64>; 3], String> {
+    let parse = |t: &str| match t {
+        "-" => Ok(None),
+        _ => t.parse::<f64>().map(Some).map_err(|_| format!("invalid street target '{}'", t)),
+    };
+    match s.split(',').map(str::trim).collect::<Vec<_>>().as_slice() {
+        [flop, turn, river] => Ok([parse(flop)?, parse(turn)?, parse(river)?]),
+        _ => Err(format!("invalid street targets '{}' (expected flop,turn,river)", s)),
     }
 }
 
-fn run_solve(max_iterations: u64, threshold: f64, check_every: u64, time_cap_secs: u64) {
-    use std::time::Instant;
+/// Conversions for the test tree, from its root pot
+fn test_tree_units() -> Units {
+    match build_test_tree().get(0) {
+        Some(Node::Decision { pot, .. }) => Units::new(*pot),
+        _ => unreachable!("test tree root is a decision"),
+    }
+}
+
+fn run_solve(config: SolverConfig, out: Option<&str>, tui: bool, workers: Option<Vec<String>>, display: (Units, Unit)) {
+    let (units, unit) = display;
+    let on_check = |m: &ConvergenceMetrics| print_row(m, units, unit);
+    let mut solver = CfrSolver::with_config(build_test_tree(), terminal_ev_table(), config);
+    let (m, reason) = match workers {
+        Some(workers) => {
+            print_header(&solver, units, unit);
+            println!("  Workers        : {} ({} chance subtrees)", workers.len(), split_roots(&solver.tree).len());
+            println!();
+            print_table_header();
+            match worker_cmd::run_distributed(solver, &workers, on_check) {
+                Ok((merged, m, reason)) => {
+                    solver = merged;
+                    (m, reason)
+                }
+                Err(e) => {
+                    eprintln!("Distributed solve failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None if tui => run_dashboard(&mut solver),
+        None => {
+            print_header(&solver, units, unit);
+            println!();
+            print_table_header();
+            solver.run(on_check)
+        }
+    };
 
-    let tree = build_test_tree();
-    let num_nodes = tree.len();
-    let decision_count = tree.nodes.iter().filter(|n| n.is_decision()).count();
+    let stop_reason = match reason {
+        StopReason::IterationCap => "iteration cap",
+        StopReason::ExploitabilityTarget => "exploitability threshold",
+        StopReason::TimeLimit => "time cap",
+        StopReason::Stagnated => "exploitability stagnated",
+        StopReason::StreetTargets => "street targets met",
+    };
 
+    println!();
+    println!("Stopped at iteration {} ({}).", m.iterations, stop_reason);
+    println!("Final exploitability : {}", units.format(m.exploitability, unit));
+    println!("  IP BR              : {}", units.format(m.ip_br_value, unit));
+    println!("  OOP BR             : {}", units.format(m.oop_br_value, unit));
+    println!("Elapsed              : {} ms", m.elapsed_time.as_millis());
+    let mixing = MixingSummary::from_nodes(&strategy_mixing(&solver.tree, &solver.storage));
     println!(
-        "Running CFR+ on test tree ({} nodes, {} decision nodes)...",
-        num_nodes, decision_count
+        "Mixing               : {:.3} bits mean entropy, {:.0}% pure, {:.2} actions in support",
+        mixing.mean_entropy,
+        mixing.pure_fraction * 100.0,
+        mixing.mean_support,
     );
-    println!("  Max iterations : {}", max_iterations);
-    println!("  Threshold      : {} bb", threshold);
-    println!("  Check every    : {} iters", check_every);
-    println!("  Time cap       : {} s", time_cap_secs);
-    println!();
+    let solution = Solution::from_solver(&solver);
+    println!("Fingerprint          : {}", solution.fingerprint());
+    if let Some(report) = solver.profile_report() {
+        println!();
+        print!("{}", report);
+    }
+
+    if let Some(path) = out {
+        match save_solution(path, &solution, Compression::None) {
+            Ok(()) => println!("Saved solution to {}", path),
+            Err(e) => {
+                eprintln!("Failed to save {}: {}", path, e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Print the tree and stop-condition summary
+fn print_header(solver: &CfrSolver, units: Units, unit: Unit) {
+    let num_nodes = solver.tree.len();
+    let decision_count = solver.tree.nodes.iter().filter(|n| n.is_decision()).count();
+    let config = solver.config();
+    let stop = config.stop;
+
+    let algorithm = match config.algorithm {
+        Algorithm::CfrPlus => "CFR+",
+        Algorithm::PredictiveCfrPlus => "predictive CFR+",
+        Algorithm::LinearCfr => "linear CFR",
+        Algorithm::DiscountedCfr => "discounted CFR",
+    };
+    println!(
+        "Running {} on test tree ({} nodes, {} decision nodes)...",
+        algorithm, num_nodes, decision_count
+    );
+    println!("  Max iterations : {}", stop.max_iterations);
+    if let Some(threshold) = stop.target_exploitability {
+        println!("  Threshold      : {}", units.format(threshold, unit));
+    }
+    if let Some(targets) = stop.street_targets {
+        let show = |t: Option<f64>| t.map_or("-".to_string(), |t| units.format(t, unit));
+        println!("  Street targets : flop {}, turn {}, river {}", show(targets.flop), show(targets.turn), show(targets.river));
+    }
+    println!("  Units          : {}", unit);
+    println!("  Check every    : {} iters", stop.check_every);
+    if let Some(limit) = stop.time_limit {
+        println!("  Time cap       : {} s", limit.as_secs());
+    }
+    if let Some(threads) = config.threads {
+        println!("  Threads        : {}", threads);
+    }
+}
+
+fn print_table_header() {
     println!(
         "{:>8}  {:>16}  {:>10}  {:>10}  {:>10}",
         "Iter", "Exploitability", "IP BR", "OOP BR", "Elapsed"
@@ -127,60 +607,43 @@ fn run_solve(max_iterations: u64, threshold: f64, check_every: u64, time_cap_sec
         "{:->8}  {:->16}  {:->10}  {:->10}  {:->10}",
         "", "", "", "", ""
     );
+}
 
-    let mut solver = CfrSolver::new(tree.clone());
-    let start = Instant::now();
-    let time_cap = std::time::Duration::from_secs(time_cap_secs);
-
-    let mut stop_reason = "iteration cap";
-    let mut final_iter = max_iterations;
-    let mut final_metrics = None;
-
-    for iter in 1..=max_iterations {
-        solver.run_iteration();
-
-        let elapsed = start.elapsed();
-
-        let hit_time_cap = elapsed >= time_cap;
-        let hit_check = iter % check_every == 0;
-
-        if hit_check || hit_time_cap {
-            let m = compute_exploitability(&tree, &solver.storage, iter, elapsed);
-            println!(
-                "{:>8}  {:>16.6}  {:>10.6}  {:>10.6}  {:>8}ms",
-                iter,
-                m.exploitability,
-                m.ip_br_value,
-                m.oop_br_value,
-                elapsed.as_millis()
-            );
-
-            if m.exploitability < threshold {
-                stop_reason = "exploitability threshold";
-                final_iter = iter;
-                final_metrics = Some(m);
-                break;
-            }
-
-            if hit_time_cap {
-                stop_reason = "time cap";
-                final_iter = iter;
-                final_metrics = Some(m);
-                break;
-            }
+/// One table row per exploitability check, values in `unit`
+fn print_row(m: &ConvergenceMetrics, units: Units, unit: Unit) {
+    println!(
+        "{:>8}  {:>16.6}  {:>10.6}  {:>10.6}  {:>8}ms",
+        m.iterations,
+        units.from_bb(m.exploitability, unit),
+        units.from_bb(m.ip_br_value, unit),
+        units.from_bb(m.oop_br_value, unit),
+        m.elapsed_time.as_millis()
+    );
+}
 
-            final_metrics = Some(m);
-            final_iter = iter;
+#[cfg(feature = "tui")]
+fn run_dashboard(solver: &mut CfrSolver) -> (ConvergenceMetrics, StopReason) {
+    match tui::run_solve_tui(solver) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Dashboard failed: {}", e);
+            std::process::exit(1);
         }
     }
+}
 
-    println!();
-    println!("Stopped at iteration {} ({}).", final_iter, stop_reason);
+#[cfg(not(feature = "tui"))]
+fn run_dashboard(_solver: &mut CfrSolver) -> (ConvergenceMetrics, StopReason) {
+    eprintln!("--tui needs the `tui` feature: cargo build -p oracle-cli --features tui");
+    std::process::exit(2);
+}
 
-    if let Some(m) = final_metrics {
-        println!("Final exploitability : {:.6} bb", m.exploitability);
-        println!("  IP BR              : {:.6} bb", m.ip_br_value);
-        println!("  OOP BR             : {:.6} bb", m.oop_br_value);
-        println!("Elapsed              : {} ms", m.elapsed_time.as_millis());
-    }
+#[cfg(feature = "render")]
+fn run_render(args: &[String]) -> Result<(), String> {
+    render_cmd::run_render(args)
+}
+
+#[cfg(not(feature = "render"))]
+fn run_render(_args: &[String]) -> Result<(), String> {
+    Err("render needs the `render` feature: cargo build -p oracle-cli --features render".to_string())
 }
@@ -0,0 +1,120 @@
+//! `oracle merge-sizes` — propose and perform bet-size merges on a solution
+//!
+//! `oracle merge-sizes flop.bin --ev-tol 0.01 --strategy-tol 0.05` lists each
+//! pair of neighbouring sizes the solution plays alike (`size_merge`): the
+//! line, the size kept and the one dropped, the frequency moved, and the EV
+//! and response gaps. It ends with the EV the merges give up, both players
+//! together, and the tree's size before and after. `--out FILE` saves the
+//! merged solution, ready to resume or re-solve.
+
+use oracle_engine::cfr::RegretStorage;
+use oracle_engine::file_format::{load_solution, save_solution, Compression};
+use oracle_engine::line::format_line;
+use oracle_engine::size_merge::{find_size_merges, merge_ev_loss, merge_sizes, MergeTolerance};
+use oracle_engine::Solution;
+
+/// Run `oracle merge-sizes` with the arguments after the subcommand
+pub fn run_merge_sizes(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut tolerance = MergeTolerance::default();
+    let mut out = None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--ev-tol", Some(v)) => tolerance.ev = parse_tolerance("--ev-tol", v)?,
+            ("--strategy-tol", Some(v)) => tolerance.strategy = parse_tolerance("--strategy-tol", v)?,
+            ("--out", Some(v)) => out = Some(v.to_string()),
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (p, _) => {
+                path = Some(p.to_string());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+    let path = path.ok_or("usage: oracle merge-sizes SOLUTION.bin [--ev-tol F] [--strategy-tol F] [--out FILE]")?;
+    let solution = load_solution(&path).map_err(|e| format!("{}: {}", path, e))?;
+    solution.validate().map_err(|e| format!("{}: {}", path, e))?;
+
+    let (report, merged) = merge_report(&solution, tolerance);
+    print!("{}", report);
+    if let Some(out) = out {
+        save_solution(&out, &merged, Compression::None).map_err(|e| format!("{}: {}", out, e))?;
+        eprintln!("Wrote {}", out);
+    }
+    Ok(())
+}
+
+/// The proposal table and summary, and the merged solution
+pub fn merge_report(solution: &Solution, tolerance: MergeTolerance) -> (String, Solution) {
+    let storage = RegretStorage::from_snapshot(solution.storage.clone());
+    let evs = solution.terminal_ev_map();
+    let merges = find_size_merges(&solution.tree, &storage, &evs, tolerance);
+    let mut out = format!("{:<28} {:<4} {:>10} {:>10} {:>8} {:>9} {:>9}\n", "line", "pos", "keep", "drop", "freq", "EV gap", "resp gap");
+    for m in &merges {
+        out.push_str(&format!(
+            "{:<28} {:<4} {:>10} {:>10} {:>7.1}% {:>9.4} {:>9.4}\n",
+            format_line(&solution.tree, m.node),
+            format!("{:?}", m.player),
+            m.kept.to_string(),
+            m.removed.to_string(),
+            m.frequency * 100.0,
+            m.ev_gap,
+            m.strategy_gap
+        ));
+    }
+    let merged = merge_sizes(solution, &merges).solution;
+    out.push_str(&format!(
+        "\n{} merges, EV given up: {:.4} bb; nodes {} -> {}\n",
+        merges.len(),
+        merge_ev_loss(&solution.tree, &storage, &evs, &merges),
+        solution.tree.len(),
+        merged.tree.len()
+    ));
+    (out, merged)
+}
+
+/// A non-negative tolerance
+fn parse_tolerance(flag: &str, v: &str) -> Result<f64, String> {
+    v.parse::<f64>().ok().filter(|t| *t >= 0.0 && t.is_finite()).ok_or(format!("invalid {} '{}'", flag, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::builder::StreetSizes;
+    use oracle_engine::game::GameConfig;
+    use oracle_engine::node::parse_cards;
+    use oracle_engine::range::Range;
+    use oracle_engine::solve::{solve, SolveSpec};
+    use oracle_engine::StopCondition;
+
+    #[test]
+    fn test_merge_sizes_reports_and_saves_a_smaller_solution() {
+        let ranges = [Range::parse("AA,KK,AK").unwrap(), Range::parse("QQ-99,AQ").unwrap()];
+        let mut spec = SolveSpec::new(parse_cards("AsKh7d2c9s").unwrap(), ranges, GameConfig::new());
+        spec.tree_config.river = StreetSizes::new(vec![0.5, 0.52, 1.5]);
+        spec.stop = StopCondition::iterations(500);
+        let solution = Solution::from_solver(&solve(&spec).unwrap().solver);
+
+        let (report, merged) = merge_report(&solution, MergeTolerance::default());
+        assert!(report.starts_with("line "), "{}", report);
+        assert!(merged.tree.len() < solution.tree.len(), "{}", report);
+        assert!(report.contains(&format!("nodes {} -> {}", solution.tree.len(), merged.tree.len())));
+        let (_, unchanged) = merge_report(&solution, MergeTolerance { ev: 0.0, strategy: 0.0 });
+        assert!(unchanged.tree.len() > merged.tree.len());
+
+        let dir = std::env::temp_dir().join(format!("oracle_merge_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (input, output) = (dir.join("in.bin"), dir.join("out.bin"));
+        save_solution(&input, &solution, Compression::None).unwrap();
+        let arg = |s: &str| s.to_string();
+        run_merge_sizes(&[input.display().to_string(), arg("--out"), output.display().to_string()]).unwrap();
+        assert_eq!(load_solution(&output).unwrap().tree.len(), merged.tree.len());
+        assert!(run_merge_sizes(&[arg("--ev-tol"), arg("-1")]).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,60 @@
+//! `oracle solve --preset NAME` — solve a ready-made spot from `presets`
+//!
+//! Solver flags apply as they do with `--board` (see `spot_cmd::apply_config`);
+//! `--tui` and `--workers` are refused.
+
+use oracle_engine::config::SolverConfig;
+use oracle_engine::node::parse_cards;
+use oracle_engine::presets::{self, Preset};
+use oracle_engine::solve::SolveSpec;
+use oracle_engine::units::Unit;
+use crate::spot_cmd::{apply_config, print_spot, solve_and_report};
+
+/// The preset called `name`, or an error listing the available ones
+pub fn find_preset(name: &str) -> Result<Preset, String> {
+    presets::find(name).ok_or_else(|| {
+        let names: Vec<&str> = presets::all().iter().map(|p| p.name).collect();
+        format!("unknown preset '{}' (available: {})", name, names.join(", "))
+    })
+}
+
+/// Spec for preset `name` on `board` (default: the preset's flop). A turn or
+/// river board starts the tree there, as if the earlier streets checked through.
+/// Solved as `config` asks.
+pub fn preset_spec(name: &str, board: Option<&str>, config: &SolverConfig) -> Result<SolveSpec, String> {
+    let preset = find_preset(name)?;
+    let board = board.map(parse_cards).transpose().map_err(|e| e.to_string())?;
+    let mut spec = preset.spec(board);
+    apply_config(&mut spec, config);
+    Ok(spec)
+}
+
+/// Solve preset `name` within `max_memory` bytes, print the result in
+/// `unit`, and save it to `out` if given
+pub fn run_preset_solve(name: &str, board: Option<&str>, config: &SolverConfig, unit: Unit, out: Option<&str>, max_memory: Option<u64>) -> Result<(), String> {
+    let mut spec = preset_spec(name, board, config)?;
+    spec.max_memory = max_memory;
+    println!("Preset {}: {}", name, find_preset(name)?.description);
+    print_spot(&spec, unit);
+    solve_and_report(&spec, unit, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::config::Algorithm;
+    use oracle_engine::node::format_cards;
+
+    #[test]
+    fn test_preset_spec_applies_board_and_stop() {
+        let config = SolverConfig::builder().max_iterations(50).algorithm(Algorithm::PredictiveCfrPlus).threads(2).profile(true).build().unwrap();
+        let spec = preset_spec("srp-btn-bb", Some("Td9d8c"), &config).unwrap();
+        assert_eq!(format_cards(&spec.board), "Td9d8c");
+        assert_eq!((spec.stop.max_iterations, spec.algo, spec.profile), (50, Algorithm::PredictiveCfrPlus, true));
+        assert_eq!(spec.pool.map(|p| p.current_num_threads()), Some(2));
+        let defaults = SolverConfig::new();
+        assert_eq!(preset_spec("3bp-co-btn", None, &defaults).unwrap().board, parse_cards("Jh7s4d").unwrap());
+        assert!(preset_spec("nope", None, &defaults).unwrap_err().contains("srp-btn-bb, 3bp-co-btn, mtt-bvb-40bb"));
+        assert!(preset_spec("srp-btn-bb", Some("Xx"), &defaults).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! `oracle render` — draw a solution node's strategy grid to SVG or PNG
+//!
+//! `oracle render SOLUTION --out chart.svg [--line "F:x-b75|T:7h"]` follows
+//! the line (`line::Line`, in either notation) from the root, or stays at
+//! the root when omitted, and draws the acting player's 13x13 grid with
+//! stacked action colors (`render::render_svg` / `render_png`, by the
+//! output's extension). Ranges come from the solution, or `--ip-range` and
+//! `--oop-range` when it has none.
+
+use oracle_engine::file_format::load_solution;
+use oracle_engine::line::Line;
+use oracle_engine::node::Node;
+use oracle_engine::range::Range;
+use oracle_engine::render::{action_colors, node_grid, render_png, render_svg};
+
+/// Run `oracle render` with the arguments after the subcommand
+pub fn run_render(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut out = None;
+    let mut line = String::new();
+    let mut ip_range = None;
+    let mut oop_range = None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--out", Some(v)) => out = Some(v.to_string()),
+            ("--line", Some(v)) => line = v.to_string(),
+            ("--ip-range", Some(v)) => ip_range = Some(Range::parse(v).map_err(|e| e.to_string())?),
+            ("--oop-range", Some(v)) => oop_range = Some(Range::parse(v).map_err(|e| e.to_string())?),
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (file, _) => {
+                path = Some(file.to_string());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+    let usage = "usage: oracle render SOLUTION --out FILE.svg|FILE.png [--line LINE] [--ip-range R --oop-range R]";
+    let (path, out) = path.zip(out).ok_or(usage)?;
+
+    let mut solution = load_solution(&path).map_err(|e| format!("{}: {}", path, e))?;
+    solution.validate().map_err(|e| format!("{}: {}", path, e))?;
+    match (ip_range, oop_range) {
+        (Some(ip), Some(oop)) => solution.ranges = Some([ip, oop]),
+        (None, None) => {}
+        _ => return Err("--ip-range and --oop-range must be given together".to_string()),
+    }
+    let node = Line::parse(&line).and_then(|l| l.find(&solution.tree))?;
+    let grid = node_grid(&solution, node).map_err(|e| e.to_string())?;
+    let colors = match solution.tree.get(node) {
+        Some(Node::Decision { actions, .. }) => action_colors(actions),
+        _ => Vec::new(),
+    };
+    let bytes = if out.to_ascii_lowercase().ends_with(".png") {
+        render_png(&grid, &colors)
+    } else {
+        render_svg(&grid, &colors).into_bytes()
+    };
+    std::fs::write(&out, bytes).map_err(|e| format!("{}: {}", out, e))?;
+    println!("Wrote node {} grid to {}", node, out);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::file_format::{save_solution, Compression};
+    use oracle_engine::game::GameConfig;
+    use oracle_engine::node::parse_cards;
+    use oracle_engine::solve::{solve, SolveSpec};
+    use oracle_engine::{Solution, StopCondition};
+
+    #[test]
+    fn test_render_writes_svg_and_png() {
+        let dir = std::env::temp_dir().join(format!("oracle_render_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ranges = [Range::parse("AA,KK,AK").unwrap(), Range::parse("QQ-99,AQ").unwrap()];
+        let mut spec = SolveSpec::new(parse_cards("AsKh7d2c9s").unwrap(), ranges.clone(), GameConfig::from_pot(10.0, [50.0, 50.0]));
+        spec.stop = StopCondition::iterations(50);
+        let solution = Solution::from_solver(&solve(&spec).unwrap().solver).with_ranges(ranges);
+        let path = dir.join("river.bin");
+        save_solution(&path, &solution, Compression::None).unwrap();
+
+        let arg = |s: &str| s.to_string();
+        let svg = dir.join("root.svg");
+        run_render(&[path.display().to_string(), arg("--out"), svg.display().to_string()]).unwrap();
+        assert!(std::fs::read_to_string(&svg).unwrap().contains(">AKo</text>"));
+        let png = dir.join("ip.png");
+        run_render(&[path.display().to_string(), arg("--line"), arg("R:x"), arg("--out"), png.display().to_string()]).unwrap();
+        assert_eq!(&std::fs::read(&png).unwrap()[1..4], b"PNG");
+        assert!(run_render(&[path.display().to_string(), arg("--line"), arg("x x"), arg("--out"), png.display().to_string()]).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
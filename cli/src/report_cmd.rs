@@ -0,0 +1,116 @@
+//! `oracle report DIR` — aggregate a directory of solutions into one summary
+//!
+//! Loads every `*.bin` solution in `DIR` (e.g. an `oracle batch` output
+//! directory), reads each one's c-bet and check-raise frequencies and EVs
+//! (`study::SpotSummary`), and averages them by board texture and position.
+//! Writes CSV (one row per texture and position) or JSON (the groups plus
+//! every spot) to `--out`, or CSV to stdout; the format follows `--format`,
+//! else the output file's extension.
+
+use std::path::Path;
+use oracle_engine::file_format::load_solution;
+use oracle_engine::study::{aggregate, to_csv, to_json, SpotSummary};
+
+/// Run `oracle report` with the arguments after the subcommand
+pub fn run_report(args: &[String]) -> Result<(), String> {
+    let mut dir = None;
+    let mut out: Option<String> = None;
+    let mut format: Option<String> = None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--out", Some(v)) => {
+                out = Some(v.to_string());
+                i += 2;
+            }
+            ("--format", Some(v)) => {
+                format = Some(v.to_ascii_lowercase());
+                i += 2;
+            }
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (d, _) => {
+                dir = Some(d.to_string());
+                i += 1;
+            }
+        }
+    }
+    let dir = dir.ok_or("usage: oracle report DIR [--out FILE] [--format csv|json]")?;
+    let json = match format.as_deref() {
+        Some("json") => true,
+        Some("csv") => false,
+        Some(f) => return Err(format!("unknown format '{}' (expected csv or json)", f)),
+        None => out.as_deref().is_some_and(|o| o.ends_with(".json")),
+    };
+
+    let spots = load_spots(Path::new(&dir))?;
+    if spots.is_empty() {
+        return Err(format!("{}: no .bin solutions", dir));
+    }
+    let groups = aggregate(&spots);
+    let text = if json { to_json(&groups, &spots) } else { to_csv(&groups) };
+    match out {
+        Some(path) => {
+            std::fs::write(&path, text).map_err(|e| format!("{}: {}", path, e))?;
+            eprintln!("Summarized {} solutions into {} groups in {}", spots.len(), groups.len(), path);
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Every `*.bin` solution in `dir`, named by file stem, in name order
+fn load_spots(dir: &Path) -> Result<Vec<SpotSummary>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "bin"))
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            let solution = load_solution(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            solution.validate().map_err(|e| format!("{}: {}", path.display(), e))?;
+            let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            Ok(SpotSummary::from_solution(&name, &solution))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::file_format::{save_solution, Compression};
+    use oracle_engine::game::GameConfig;
+    use oracle_engine::node::parse_cards;
+    use oracle_engine::range::Range;
+    use oracle_engine::solve::{solve, SolveSpec};
+    use oracle_engine::{Solution, StopCondition};
+
+    #[test]
+    fn test_report_summarizes_a_directory() {
+        let dir = std::env::temp_dir().join(format!("oracle_report_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for board in ["AsKh7d2c9s", "Ad8h3c2s5d"] {
+            let ranges = [Range::parse("AA,KK,AK").unwrap(), Range::parse("QQ-99,AQ").unwrap()];
+            let mut spec = SolveSpec::new(parse_cards(board).unwrap(), ranges, GameConfig::from_pot(10.0, [50.0, 50.0]));
+            spec.stop = StopCondition::iterations(100);
+            let solution = Solution::from_solver(&solve(&spec).unwrap().solver);
+            save_solution(dir.join(format!("{}.bin", board)), &solution, Compression::None).unwrap();
+        }
+        std::fs::write(dir.join("manifest.toml"), "").unwrap();
+
+        let out = dir.join("summary.json");
+        run_report(&[dir.display().to_string(), "--out".to_string(), out.display().to_string()]).unwrap();
+        let json = std::fs::read_to_string(&out).unwrap();
+        assert!(json.contains("\"texture\":\"A-high two-tone\",\"position\":\"OOP\",\"spots\":2"));
+
+        let csv = dir.join("summary.txt");
+        run_report(&[dir.display().to_string(), "--out".to_string(), csv.display().to_string(), "--format".to_string(), "csv".to_string()]).unwrap();
+        assert_eq!(std::fs::read_to_string(&csv).unwrap().lines().count(), 3);
+        assert!(run_report(&[dir.join("missing").display().to_string()]).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,687 @@
+//! `oracle serve [JOBS.toml]` — long-running solve server with a metrics endpoint
+//!
+//! Spots from the job file (same format as `oracle batch`) are queued and
+//! solved one at a time on a background thread, each saved as
+//! `<out>/<name>.bin`. The server keeps running after the queue drains.
+//!
+//! `GET /metrics` reports, in the Prometheus text format:
+//!
+//! | metric | type | meaning |
+//! |--------|------|---------|
+//! | `oracle_queue_depth` | gauge | solves waiting to start |
+//! | `oracle_solves_completed_total` | counter | solves finished and saved |
+//! | `oracle_solves_failed_total` | counter | solves that errored |
+//! | `oracle_iterations_total` | counter | CFR iterations across all solves |
+//! | `oracle_solve_active` | gauge | 1 while a solve is running |
+//! | `oracle_solve_iterations{job}` | gauge | iterations of the running solve |
+//! | `oracle_solve_iterations_per_second{job}` | gauge | its rate at the last check |
+//! | `oracle_solve_exploitability_bb{job}` | gauge | its exploitability at the last check |
+//! | `oracle_resident_memory_bytes` | gauge | process RSS (Linux only) |
+//!
+//! Solve metrics update at each exploitability check (`check_every`).
+//!
+//! A small JSON API lets web apps drive the server without extra tooling:
+//!
+//! - `POST /solves` with one `[[spot]]` table as a JSON object (e.g.
+//!   `{"board": "AsKh7d", "ip_range": "AA-TT", "oop_range": "99-55",
+//!   "iterations": 500}`) queues its solves and answers `201` with
+//!   `{"ids": [...]}`. Names must be new, since they name the files, and
+//!   can't contain `/`, `\` or `..`.
+//! - `GET /solves` lists every solve's progress; `GET /solves/{id}/progress`
+//!   one solve's: status, iterations, exploitability, rate, and file.
+//! - `GET /solves/{id}/strategy?path=F:x-b75-c|T:Qs` walks a line (see
+//!   `line`; `x,b75,c,Qs` also works) from the root of a finished solve and
+//!   returns the acting player's actions with frequencies and EVs.
+//!
+//! The HTTP side is deliberately tiny: one request per connection, no
+//! keep-alive. Each connection gets its own thread and a 10 s read/write
+//! timeout, and bodies over 1 MiB are refused with `413`. Finished
+//! solutions are loaded once and kept for later strategy requests.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use serde_json::json;
+use oracle_engine::file_format::{load_solution, save_solution, Compression};
+use oracle_engine::solve::{solve_with_progress, SolveSpec};
+use oracle_engine::Solution;
+use crate::batch_cmd::{expand_jobs, Job, JobFile, SpotSettings};
+use crate::explore_cmd::Explorer;
+
+/// Largest request body accepted
+const MAX_BODY: usize = 1 << 20;
+/// Longest a connection may stall a read or write
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where a queued solve is
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveStatus {
+    Queued,
+    Running,
+    Done,
+    Failed(String),
+}
+
+/// One solve the server knows about
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveRecord {
+    pub id: usize,
+    pub name: String,
+    pub status: SolveStatus,
+    pub iterations: u64,
+    /// At the last check
+    pub exploitability: Option<f64>,
+    /// At the last check
+    pub iterations_per_second: f64,
+    /// Solution file, relative to the output directory, once saved
+    pub file: Option<String>,
+}
+
+impl SolveRecord {
+    /// Progress as returned by `GET /solves/{id}/progress`
+    pub fn to_json(&self) -> serde_json::Value {
+        let (status, error) = match &self.status {
+            SolveStatus::Queued => ("queued", None),
+            SolveStatus::Running => ("running", None),
+            SolveStatus::Done => ("done", None),
+            SolveStatus::Failed(e) => ("failed", Some(e.as_str())),
+        };
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "status": status,
+            "iterations": self.iterations,
+            "exploitability": self.exploitability,
+            "iterations_per_second": self.iterations_per_second,
+            "file": self.file,
+            "error": error,
+        })
+    }
+}
+
+/// Everything the server tracks, behind one lock
+#[derive(Debug, Default)]
+pub struct ServeState {
+    pub records: Vec<SolveRecord>,
+    queue: VecDeque<(usize, SolveSpec)>,
+    /// Iterations of finished solves
+    finished_iterations: u64,
+}
+
+impl ServeState {
+    /// Queue a solve and return its id
+    pub fn enqueue(&mut self, job: Job) -> usize {
+        let id = self.records.len();
+        self.records.push(SolveRecord {
+            id,
+            name: job.name,
+            status: SolveStatus::Queued,
+            iterations: 0,
+            exploitability: None,
+            iterations_per_second: 0.0,
+            file: None,
+        });
+        self.queue.push_back((id, job.spec));
+        id
+    }
+
+    pub fn queue_depth(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn count(&self, done: bool) -> usize {
+        self.records.iter().filter(|r| if done { r.status == SolveStatus::Done } else { matches!(r.status, SolveStatus::Failed(_)) }).count()
+    }
+}
+
+/// State shared between the HTTP side and the solve thread
+pub struct Server {
+    pub state: Mutex<ServeState>,
+    /// Signalled when a solve is queued
+    queued: Condvar,
+    /// Where solutions are saved
+    out: PathBuf,
+    /// Finished solutions loaded for strategy requests, by solve id
+    explorers: Mutex<HashMap<usize, Arc<Mutex<Explorer>>>>,
+}
+
+impl Server {
+    pub fn new(out: PathBuf) -> Self {
+        Server { state: Mutex::new(ServeState::default()), queued: Condvar::new(), out, explorers: Mutex::default() }
+    }
+
+    pub fn lock(&self) -> std::sync::MutexGuard<'_, ServeState> {
+        self.state.lock().expect("server state poisoned")
+    }
+
+    pub fn enqueue(&self, job: Job) -> usize {
+        let id = self.lock().enqueue(job);
+        self.queued.notify_all();
+        id
+    }
+
+    /// Queue every solve one spot expands to, unless a name is taken
+    pub fn submit(&self, spot: SpotSettings) -> Result<Vec<usize>, String> {
+        let jobs = expand_jobs(&JobFile { spot: vec![spot], ..JobFile::default() })?;
+        let mut state = self.lock();
+        if let Some(job) = jobs.iter().find(|j| state.records.iter().any(|r| r.name == j.name)) {
+            return Err(format!("a solve named '{}' already exists", job.name));
+        }
+        let ids = jobs.into_iter().map(|job| state.enqueue(job)).collect();
+        drop(state);
+        self.queued.notify_all();
+        Ok(ids)
+    }
+
+    /// Wait for the next queued solve, run it, and save it
+    pub fn work_one(&self) {
+        let out = &self.out;
+        let (id, spec) = {
+            let mut state = self.lock();
+            loop {
+                if let Some(next) = state.queue.pop_front() {
+                    break next;
+                }
+                state = self.queued.wait(state).expect("server state poisoned");
+            }
+        };
+        self.lock().records[id].status = SolveStatus::Running;
+        let result = solve_with_progress(&spec, |m| {
+            let record = &mut self.lock().records[id];
+            record.iterations = m.iterations;
+            record.exploitability = Some(m.exploitability);
+            record.iterations_per_second = m.iterations as f64 / m.elapsed_time.as_secs_f64().max(1e-9);
+        });
+        // Save without the lock so metrics and the API stay responsive
+        let file = format!("{}.bin", self.lock().records[id].name);
+        let result = result.map(|result| {
+            let solution = Solution::from_solver(&result.solver).with_ranges(spec.ranges.clone());
+            let saved = save_solution(out.join(&file), &solution, Compression::None);
+            (result, saved)
+        });
+        let mut state = self.lock();
+        match result {
+            Ok((result, saved)) => {
+                state.finished_iterations += result.solver.iteration;
+                let record = &mut state.records[id];
+                record.iterations = result.solver.iteration;
+                record.exploitability = Some(result.metrics.exploitability);
+                match saved {
+                    Ok(()) => {
+                        record.file = Some(file);
+                        record.status = SolveStatus::Done;
+                    }
+                    Err(e) => record.status = SolveStatus::Failed(e.to_string()),
+                }
+            }
+            Err(e) => state.records[id].status = SolveStatus::Failed(e.to_string()),
+        }
+    }
+
+    /// The explorer for a saved solve, loading it on first use
+    fn explorer(&self, id: usize, file: &str) -> Result<Arc<Mutex<Explorer>>, (u16, String)> {
+        if let Some(explorer) = self.explorers.lock().expect("explorer cache poisoned").get(&id) {
+            return Ok(Arc::clone(explorer));
+        }
+        let solution = load_solution(self.out.join(file)).map_err(|e| (500, e.to_string()))?;
+        let explorer = Arc::new(Mutex::new(Explorer::new(solution).map_err(|e| (500, e))?));
+        let mut explorers = self.explorers.lock().expect("explorer cache poisoned");
+        Ok(Arc::clone(explorers.entry(id).or_insert(explorer)))
+    }
+}
+
+/// Prometheus text exposition of `state` (see module docs)
+pub fn render_metrics(state: &ServeState, resident_bytes: Option<u64>) -> String {
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{}{} {}", name, labels, value);
+        }
+    };
+    let plain = |v: String| vec![(String::new(), v)];
+    let running: Vec<&SolveRecord> = state.records.iter().filter(|r| r.status == SolveStatus::Running).collect();
+    let per_job = |f: &dyn Fn(&SolveRecord) -> String| -> Vec<(String, String)> {
+        running.iter().map(|r| (format!("{{job=\"{}\"}}", escape_label(&r.name)), f(r))).collect()
+    };
+    let running_iterations: u64 = running.iter().map(|r| r.iterations).sum();
+
+    metric("oracle_queue_depth", "gauge", "Solves waiting to start", &plain(state.queue_depth().to_string()));
+    metric("oracle_solves_completed_total", "counter", "Solves finished and saved", &plain(state.count(true).to_string()));
+    metric("oracle_solves_failed_total", "counter", "Solves that failed", &plain(state.count(false).to_string()));
+    metric("oracle_iterations_total", "counter", "CFR iterations across all solves", &plain((state.finished_iterations + running_iterations).to_string()));
+    metric("oracle_solve_active", "gauge", "1 while a solve is running", &plain(running.len().min(1).to_string()));
+    metric("oracle_solve_iterations", "gauge", "Iterations of the running solve", &per_job(&|r| r.iterations.to_string()));
+    metric("oracle_solve_iterations_per_second", "gauge", "Iteration rate of the running solve at its last check", &per_job(&|r| format!("{}", r.iterations_per_second)));
+    let exploitability = per_job(&|r| r.exploitability.map_or("NaN".to_string(), |x| format!("{}", x)));
+    metric("oracle_solve_exploitability_bb", "gauge", "Exploitability of the running solve at its last check, in bb", &exploitability);
+    if let Some(bytes) = resident_bytes {
+        metric("oracle_resident_memory_bytes", "gauge", "Resident memory of the server process", &plain(bytes.to_string()));
+    }
+    out
+}
+
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Resident set size from `/proc/self/status`; `None` off Linux
+fn resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// A parsed HTTP request
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Path including any query string
+    pub target: String,
+    pub body: Vec<u8>,
+}
+
+/// An HTTP response
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Response { status, content_type: "text/plain; charset=utf-8", body: body.into() }
+    }
+
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        Response { status, content_type: "application/json", body: body.to_string() }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            201 => "Created",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            413 => "Payload Too Large",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+/// Read one request: request line, headers, and a `Content-Length` body
+///
+/// Errors carry the status to answer with: `413` for a body over
+/// `MAX_BODY`, `400` otherwise.
+pub fn read_request(stream: impl Read) -> Result<Request, (u16, String)> {
+    let bad = |e: std::io::Error| (400, e.to_string());
+    // Headers get the same allowance as the body, so a client can't stream
+    // an endless line into memory
+    let mut reader = BufReader::new(stream.take(2 * MAX_BODY as u64));
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(bad)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err((400, format!("malformed request line '{}'", line.trim_end())));
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+    let mut length = 0usize;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(bad)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().map_err(|_| (400, format!("invalid Content-Length '{}'", value.trim())))?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err((413, format!("body of {} bytes is over the {} byte limit", length, MAX_BODY)));
+    }
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).map_err(bad)?;
+    Ok(Request { method, target, body })
+}
+
+/// Route a request
+pub fn handle(server: &Server, request: &Request) -> Response {
+    let (path, query) = request.target.split_once('?').unwrap_or((&request.target, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["metrics"]) => Response {
+            status: 200,
+            content_type: "text/plain; version=0.0.4",
+            body: render_metrics(&server.lock(), resident_bytes()),
+        },
+        ("POST", ["solves"]) => {
+            let spot: SpotSettings = match serde_json::from_slice(&request.body) {
+                Ok(spot) => spot,
+                Err(e) => return Response::json(400, json!({ "error": e.to_string() })),
+            };
+            match server.submit(spot) {
+                Ok(ids) => Response::json(201, json!({ "ids": ids })),
+                Err(e) => Response::json(400, json!({ "error": e })),
+            }
+        }
+        ("GET", ["solves"]) => {
+            let list: Vec<serde_json::Value> = server.lock().records.iter().map(SolveRecord::to_json).collect();
+            Response::json(200, json!(list))
+        }
+        ("GET", ["solves", id, view @ ("progress" | "strategy")]) => {
+            let Some(record) = id.parse::<usize>().ok().and_then(|id| server.lock().records.get(id).cloned()) else {
+                return Response::json(404, json!({ "error": format!("no solve '{}'", id) }));
+            };
+            if *view == "progress" {
+                return Response::json(200, record.to_json());
+            }
+            let path = query_param(query, "path").unwrap_or_default();
+            match strategy_at(server, &record, &path) {
+                Ok(body) => Response::json(200, body),
+                Err((status, e)) => Response::json(status, json!({ "error": e })),
+            }
+        }
+        (_, ["metrics"] | ["solves"] | ["solves", _, "progress" | "strategy"]) => Response::text(405, "method not allowed\n"),
+        _ => Response::text(404, "not found\n"),
+    }
+}
+
+/// Strategy at the node `path` (a line, or comma-separated tokens) reaches
+fn strategy_at(server: &Server, record: &SolveRecord, path: &str) -> Result<serde_json::Value, (u16, String)> {
+    let file = match (&record.status, &record.file) {
+        (SolveStatus::Done, Some(file)) => file,
+        (SolveStatus::Failed(e), _) => return Err((409, format!("solve {} failed: {}", record.id, e))),
+        _ => return Err((409, format!("solve {} has not finished", record.id))),
+    };
+    let explorer = server.explorer(record.id, file)?;
+    let mut explorer = explorer.lock().expect("explorer poisoned");
+    explorer.step("root").map_err(|e| (500, e))?;
+    explorer.follow(&path.replace(',', " ")).map_err(|e| (400, e))?;
+    let Some((player, actions)) = explorer.action_summary() else {
+        return Err((400, format!("'{}' does not end at a decision", path)));
+    };
+    let actions: Vec<serde_json::Value> = actions.iter()
+        .map(|a| json!({ "action": a.token, "frequency": a.frequency, "ev": a.ev }))
+        .collect();
+    Ok(json!({
+        "id": record.id,
+        "path": explorer.line(),
+        "line": explorer.line_notation(),
+        "node": explorer.current(),
+        "player": format!("{:?}", player),
+        "actions": actions,
+    }))
+}
+
+/// Percent-decoded value of `name` in a query string
+fn query_param(query: &str, name: &str) -> Option<String> {
+    let value = query.split('&').find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))?;
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => match value.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                Some(b) => {
+                    decoded.push(b);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    Some(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+fn respond(mut stream: TcpStream, server: &Server) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let response = match read_request(&stream) {
+        Ok(request) => handle(server, &request),
+        Err((status, e)) => Response::text(status, format!("{}\n", e)),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status, response.reason(), response.content_type, response.body.len(), response.body
+    )?;
+    stream.flush()
+}
+
+/// Answer requests on `listener` forever, each connection on its own thread
+pub fn serve(listener: TcpListener, server: &Server) {
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    scope.spawn(move || {
+                        if let Err(e) = respond(stream, server) {
+                            eprintln!("request failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => eprintln!("connection failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Run `oracle serve` with the arguments after `serve`
+pub fn run_serve(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut listen = String::from("127.0.0.1:9090");
+    let mut out = None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--listen", Some(v)) => listen = v.to_string(),
+            ("--out", Some(v)) => out = Some(PathBuf::from(v)),
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (file, _) => {
+                path = Some(file.to_string());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+
+    let mut jobs = Vec::new();
+    let mut file_out = None;
+    if let Some(path) = &path {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let file: JobFile = toml::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+        file_out = file.out.clone();
+        jobs = expand_jobs(&file)?;
+    }
+    let out = out.or(file_out).unwrap_or_else(|| PathBuf::from("solutions"));
+    std::fs::create_dir_all(&out).map_err(|e| format!("{}: {}", out.display(), e))?;
+    let server = Arc::new(Server::new(out.clone()));
+    for job in jobs {
+        server.enqueue(job);
+    }
+
+    let listener = TcpListener::bind(&listen).map_err(|e| format!("{}: {}", listen, e))?;
+    println!(
+        "Serving on http://{} ({} solves queued, saving to {}); metrics at /metrics, JSON API at /solves",
+        listener.local_addr().map_err(|e| e.to_string())?,
+        server.lock().queue_depth(),
+        out.display(),
+    );
+    let worker = Arc::clone(&server);
+    std::thread::spawn(move || loop {
+        worker.work_one();
+    });
+    serve(listener, &server);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOBS: &str = r#"
+        [defaults]
+        ip_range = "AK,77"
+        oop_range = "AQ,KQ"
+        river_bets = "75"
+        max_raises = 1
+        iterations = 20
+        check_every = 10
+
+        [[spot]]
+        name = "dry"
+        board = "AsKh7d2c9s"
+
+        [[spot]]
+        name = "wet"
+        board = "Td9d8c6s2h"
+    "#;
+
+    fn temp_out(tag: &str) -> PathBuf {
+        let out = std::env::temp_dir().join(format!("oracle_serve_{}_{}", tag, std::process::id()));
+        std::fs::create_dir_all(&out).unwrap();
+        out
+    }
+
+    fn queued(out: PathBuf) -> Server {
+        let server = Server::new(out);
+        for job in expand_jobs(&toml::from_str(JOBS).unwrap()).unwrap() {
+            server.enqueue(job);
+        }
+        server
+    }
+
+    fn call(server: &Server, method: &str, target: &str, body: &str) -> (u16, serde_json::Value) {
+        let response = handle(server, &Request { method: method.into(), target: target.into(), body: body.as_bytes().to_vec() });
+        (response.status, serde_json::from_str(&response.body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[test]
+    fn test_metrics_track_queue_and_solves() {
+        let out = temp_out("metrics");
+        let server = queued(out.clone());
+        let text = render_metrics(&server.lock(), Some(4096));
+        assert!(text.contains("# TYPE oracle_queue_depth gauge\noracle_queue_depth 2\n"));
+        assert!(text.contains("oracle_solve_active 0\n"));
+        assert!(text.contains("oracle_resident_memory_bytes 4096\n"));
+
+        server.work_one();
+        let state = server.lock();
+        assert_eq!(state.records[0].status, SolveStatus::Done);
+        assert_eq!(state.records[0].iterations, 20);
+        assert!(out.join(state.records[0].file.as_ref().unwrap()).exists());
+        let text = render_metrics(&state, None);
+        assert!(text.contains("oracle_queue_depth 1\n"));
+        assert!(text.contains("oracle_solves_completed_total 1\n"));
+        assert!(text.contains("oracle_iterations_total 20\n"));
+        assert!(!text.contains("oracle_resident_memory_bytes"));
+        std::fs::remove_dir_all(&out).unwrap();
+
+        // A running solve is labelled with its job name
+        let mut running = ServeState::default();
+        running.records.push(SolveRecord { status: SolveStatus::Running, iterations: 7, ..state.records[0].clone() });
+        let text = render_metrics(&running, None);
+        assert!(text.contains("oracle_solve_active 1\n"));
+        assert!(text.contains("oracle_solve_iterations{job=\"dry\"} 7\n"));
+        assert!(text.contains("oracle_iterations_total 7\n"));
+    }
+
+    #[test]
+    fn test_requests_parse_and_route() {
+        let request = read_request(&b"POST /solves HTTP/1.1\r\nHost: x\r\nContent-Length: 4\r\n\r\nbody"[..]).unwrap();
+        assert_eq!((request.method.as_str(), request.target.as_str(), &request.body[..]), ("POST", "/solves", &b"body"[..]));
+        assert_eq!(read_request(&b"\r\n"[..]).unwrap_err().0, 400);
+        let huge = format!("POST /solves HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1);
+        assert_eq!(read_request(huge.as_bytes()).unwrap_err().0, 413);
+        assert_eq!(Response::text(413, "").reason(), "Payload Too Large");
+
+        let server = queued(std::env::temp_dir());
+        let get = |target: &str| handle(&server, &Request { method: "GET".into(), target: target.into(), body: Vec::new() });
+        let metrics = get("/metrics");
+        assert_eq!(metrics.status, 200);
+        assert!(metrics.body.contains("oracle_queue_depth 2"));
+        assert_eq!(get("/nope").status, 404);
+        assert_eq!(call(&server, "DELETE", "/solves", "").0, 405);
+        assert_eq!(query_param("a=1&path=x%2Cb75+c", "path").as_deref(), Some("x,b75 c"));
+        assert_eq!(query_param("path=50%", "path").as_deref(), Some("50%"));
+        assert_eq!(query_param("paths=x", "path"), None);
+    }
+
+    #[test]
+    fn test_json_api_queues_and_reports_solves() {
+        let out = temp_out("api");
+        let server = Server::new(out.clone());
+        let spot = r#"{"name": "srp", "board": "AsKh7d2c9s", "ip_range": "AK,77", "oop_range": "AQ,KQ", "river_bets": "75", "max_raises": 1, "iterations": 20}"#;
+        assert_eq!(call(&server, "POST", "/solves", spot), (201, json!({ "ids": [0] })));
+        let (status, body) = call(&server, "POST", "/solves", spot);
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("already exists"));
+        assert_eq!(call(&server, "POST", "/solves", r#"{"bord": "AsKh7d"}"#).0, 400);
+        for name in ["", "../escape", "/tmp/abs", "a\\\\b"] {
+            let (status, body) = call(&server, "POST", "/solves", &spot.replace("\"srp\"", &format!("\"{}\"", name)));
+            assert_eq!(status, 400, "{}", name);
+            assert!(body["error"].as_str().unwrap().contains("can't name a solution file"));
+        }
+        assert_eq!(server.lock().records.len(), 1);
+
+        let (status, progress) = call(&server, "GET", "/solves/0/progress", "");
+        assert_eq!((status, progress["status"].as_str()), (200, Some("queued")));
+        assert_eq!(call(&server, "GET", "/solves/0/strategy", "").0, 409);
+        assert_eq!(call(&server, "GET", "/solves/9/progress", "").0, 404);
+
+        server.work_one();
+        let (_, progress) = call(&server, "GET", "/solves/0/progress", "");
+        assert_eq!((progress["status"].as_str(), progress["iterations"].as_u64()), (Some("done"), Some(20)));
+        let (_, list) = call(&server, "GET", "/solves", "");
+        assert_eq!(list.as_array().unwrap().len(), 1);
+
+        // OOP acts first at the root; after a check, IP faces check/bet
+        let (status, root) = call(&server, "GET", "/solves/0/strategy", "");
+        assert_eq!((status, root["player"].as_str()), (200, Some("OOP")));
+        let total: f64 = root["actions"].as_array().unwrap().iter().map(|a| a["frequency"].as_f64().unwrap()).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        let (status, after_check) = call(&server, "GET", "/solves/0/strategy?path=x", "");
+        assert_eq!((status, after_check["player"].as_str()), (200, Some("IP")));
+        assert_eq!((&after_check["path"], after_check["line"].as_str()), (&json!(["x"]), Some("R:x")));
+        assert_eq!(call(&server, "GET", "/solves/0/strategy?path=R%3Ax", "").0, 200);
+        assert_eq!(call(&server, "GET", "/solves/0/strategy?path=x,x", "").0, 400);
+        assert_eq!(call(&server, "GET", "/solves/0/strategy?path=q", "").0, 400);
+
+        // The solution stays loaded, so the file isn't read again
+        std::fs::remove_dir_all(&out).unwrap();
+        let (status, again) = call(&server, "GET", "/solves/0/strategy", "");
+        assert_eq!((status, &again["actions"]), (200, &root["actions"]));
+    }
+
+    #[test]
+    fn test_serves_metrics_over_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Arc::new(queued(std::env::temp_dir()));
+        let handle = Arc::clone(&server);
+        std::thread::spawn(move || serve(listener, &handle));
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("oracle_queue_depth 2"));
+    }
+}
@@ -0,0 +1,208 @@
+//! `oracle solve --board CARDS --ip-range R --oop-range R` — solve a spot
+//! given on the command line
+//!
+//! The tree uses `TreeConfig::new`'s default sizes on a flop, turn, or river
+//! board; `--pot` and `--stacks` (default 10 and 95 bb, like `tree build`)
+//! set what the earlier action left. `--locks FILE` pins frequencies from a
+//! node-lock file (see `oracle_engine::nodelock`), and `--population FILE`
+//! pins population stats (see `oracle_engine::population`); explicit locks
+//! win where both name an infoset. `--threads`, `--profile`, `--predictive`
+//! and `--compensated` apply as they do to the test tree; `--tui` and
+//! `--workers` don't, and are refused with `--board`.
+
+use std::sync::Arc;
+use oracle_engine::config::SolverConfig;
+use oracle_engine::file_format::{save_solution, Compression};
+use oracle_engine::node::{format_cards, parse_cards, Node};
+use oracle_engine::par::build_pool;
+use oracle_engine::population::PopulationStats;
+use oracle_engine::range::Range;
+use oracle_engine::solve::{solve, SolveSpec};
+use oracle_engine::units::{Unit, Units};
+use oracle_engine::Solution;
+
+/// Starting pot when `--pot` isn't given
+pub const DEFAULT_POT: f64 = 10.0;
+/// Stacks behind when `--stacks` isn't given
+pub const DEFAULT_STACKS: [f64; 2] = [95.0, 95.0];
+
+/// Spot inputs from `oracle solve` flags
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpotArgs {
+    pub board: String,
+    pub ip_range: Option<String>,
+    pub oop_range: Option<String>,
+    pub pot: f64,
+    /// `[IP, OOP]`
+    pub stacks: [f64; 2],
+    /// Node-lock file (see `oracle_engine::nodelock`)
+    pub locks: Option<String>,
+    /// Population frequency file (see `oracle_engine::population`)
+    pub population: Option<String>,
+}
+
+impl SpotArgs {
+    /// Unit conversions for this spot's pot
+    pub fn units(&self) -> Units {
+        Units::new(self.pot)
+    }
+}
+
+/// Solve spec for `args`, solved as `config` asks (see `apply_config`)
+pub fn spot_spec(args: &SpotArgs, config: &SolverConfig) -> Result<SolveSpec, String> {
+    let board = parse_cards(&args.board).map_err(|e| e.to_string())?;
+    let range = |r: &Option<String>, flag: &str| {
+        let text = r.as_deref().ok_or(format!("{} is required with --board", flag))?;
+        Range::parse(text).map_err(|e| format!("{}: {}", flag, e))
+    };
+    let ranges = [range(&args.ip_range, "--ip-range")?, range(&args.oop_range, "--oop-range")?];
+    let mut spec = SolveSpec::from_state(board, ranges, args.pot, args.stacks);
+    apply_config(&mut spec, config);
+    let read = |path: &String| std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e));
+    let mut locks = String::new();
+    if let Some(path) = &args.population {
+        let stats = PopulationStats::parse(&read(path)?).map_err(|e| format!("{}: {}", path, e))?;
+        locks.push_str(&stats.to_lock_text(0));
+    }
+    if let Some(path) = &args.locks {
+        locks.push_str(&read(path)?);
+    }
+    if args.population.is_some() || args.locks.is_some() {
+        spec.locks = Some(locks);
+    }
+    Ok(spec)
+}
+
+/// Copy `config`'s algorithm, summation, stop condition, profiling, and
+/// thread count onto `spec`
+pub fn apply_config(spec: &mut SolveSpec, config: &SolverConfig) {
+    spec.algo = config.algorithm;
+    spec.summation = config.summation;
+    spec.stop = config.stop;
+    spec.profile = config.profile;
+    spec.pool = config.threads.map(|n| Arc::new(build_pool(n)));
+}
+
+/// Solve the spot in `args` within `max_memory` bytes, print the result in
+/// `unit`, and save it to `out` if given
+pub fn run_spot_solve(args: &SpotArgs, config: &SolverConfig, unit: Unit, out: Option<&str>, max_memory: Option<u64>) -> Result<(), String> {
+    let mut spec = spot_spec(args, config)?;
+    spec.max_memory = max_memory;
+    println!("Spot");
+    print_spot(&spec, unit);
+    solve_and_report(&spec, unit, out)
+}
+
+/// Print the board, pot, stacks, and stop condition of `spec`
+pub fn print_spot(spec: &SolveSpec, unit: Unit) {
+    let game = &spec.tree_config.game;
+    let stop = spec.stop;
+    println!("  Board          : {}", format_cards(&spec.board));
+    println!("  Pot            : {} bb", game.initial_pot());
+    println!("  Stacks         : {:?} bb [IP, OOP]", game.postflop_stacks());
+    match stop.target_exploitability {
+        Some(target) => println!("  Stop           : {} iterations or {}", stop.max_iterations, Units::for_game(game).format(target, unit)),
+        None => println!("  Stop           : {} iterations", stop.max_iterations),
+    }
+}
+
+/// Solve `spec`, print the stop reason, exploitability (in `unit`), and root
+/// strategy, and save the solution to `out` if given. With a memory budget
+/// the estimate and any fallback are printed too (see `memory`).
+pub fn solve_and_report(spec: &SolveSpec, unit: Unit, out: Option<&str>) -> Result<(), String> {
+    println!("Solving...");
+    let result = solve(spec).map_err(|e| e.to_string())?;
+    if let Some(memory) = &result.memory {
+        println!("Memory               : {}", memory);
+    }
+    let units = Units::for_game(&spec.tree_config.game);
+    println!("Stopped at iteration {} ({:?}).", result.metrics.iterations, result.stop_reason);
+    println!("Final exploitability : {}", units.format(result.metrics.exploitability, unit));
+    if let Some(Node::Decision { actions, .. }) = result.solver.tree.get(0) {
+        let mix: Vec<String> = actions.iter().zip(result.strategy(0)).map(|(a, s)| format!("{} {:.1}%", a, s * 100.0)).collect();
+        println!("Root strategy        : {}", mix.join(", "));
+    }
+    let solution = Solution::from_solver(&result.solver).with_ranges(spec.ranges.clone());
+    println!("Fingerprint          : {}", solution.fingerprint());
+    if let Some(report) = result.solver.profile_report() {
+        println!();
+        print!("{}", report);
+    }
+
+    if let Some(path) = out {
+        save_solution(path, &solution, Compression::None).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Saved solution to {}", path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::config::Algorithm;
+    use oracle_engine::Summation;
+
+    fn args(board: &str) -> SpotArgs {
+        SpotArgs {
+            board: board.to_string(),
+            ip_range: Some("AK,77".to_string()),
+            oop_range: Some("AQ,KQ".to_string()),
+            pot: 20.0,
+            stacks: [80.0, 60.0],
+            locks: None,
+            population: None,
+        }
+    }
+
+    #[test]
+    fn test_spot_spec_from_flags() {
+        let config = SolverConfig::builder().max_iterations(40).algorithm(Algorithm::PredictiveCfrPlus).build().unwrap();
+        let spec = spot_spec(&args("AsKh7d2c"), &config).unwrap();
+        assert_eq!(spec.board, parse_cards("AsKh7d2c").unwrap());
+        assert_eq!((spec.tree_config.game.initial_pot(), spec.tree_config.game.postflop_stacks()), (20.0, [80.0, 60.0]));
+        assert_eq!(spec.ranges[1], Range::parse("AQ,KQ").unwrap());
+        assert_eq!((spec.stop.max_iterations, spec.algo), (40, Algorithm::PredictiveCfrPlus));
+        assert!(!spec.profile && spec.pool.is_none());
+        let tuned = SolverConfig::builder().threads(2).profile(true).summation(Summation::Compensated).build().unwrap();
+        let threaded = spot_spec(&args("AsKh7d2c"), &tuned).unwrap();
+        assert_eq!(threaded.pool.map(|p| p.current_num_threads()), Some(2));
+        assert_eq!((threaded.profile, threaded.summation), (true, Summation::Compensated));
+        assert_eq!(args("AsKh7d").units().pot, 20.0);
+
+        let missing = SpotArgs { oop_range: None, ..args("AsKh7d") };
+        assert_eq!(spot_spec(&missing, &config).unwrap_err(), "--oop-range is required with --board");
+        let bad = SpotArgs { ip_range: Some("AZ".to_string()), ..args("AsKh7d") };
+        assert!(spot_spec(&bad, &config).unwrap_err().starts_with("--ip-range: "));
+        assert!(spot_spec(&args("AsXx"), &config).is_err());
+        let unreadable = SpotArgs { locks: Some("/nonexistent/locks.txt".to_string()), ..args("AsKh7d") };
+        assert!(spot_spec(&unreadable, &config).unwrap_err().starts_with("/nonexistent/locks.txt: "));
+
+        let dir = std::env::temp_dir().join(format!("oracle_spot_population_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (population, locks) = (dir.join("pop.csv"), dir.join("locks.txt"));
+        std::fs::write(&population, "target,action,frequency\nroot, b, 40%\n").unwrap();
+        std::fs::write(&locks, "x = b:0\n").unwrap();
+        let both = SpotArgs {
+            population: Some(population.display().to_string()),
+            locks: Some(locks.display().to_string()),
+            ..args("AsKh7d2c9s")
+        };
+        assert_eq!(spot_spec(&both, &config).unwrap().locks.as_deref(), Some("root = b:0.4\nx = b:0\n"));
+        std::fs::write(&population, "root, b, 140%\n").unwrap();
+        assert!(spot_spec(&both, &config).unwrap_err().contains("line 1"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_solve_and_report_holds_the_memory_budget() {
+        let config = SolverConfig::builder().max_iterations(5).build().unwrap();
+        let mut spec = spot_spec(&args("AsKs7d2s"), &config).unwrap();
+        spec.max_memory = Some(oracle_engine::estimate_memory(&spec.tree_config).total() - 1);
+        solve_and_report(&spec, Unit::Bb, None).unwrap();
+        spec.max_memory = Some(1024);
+        assert!(solve_and_report(&spec, Unit::Bb, None).unwrap_err().contains("over the 1.00 KiB budget"));
+        spec.ranges[0] = Range::parse("AhKh").unwrap();
+        spec.max_memory = Some(oracle_engine::estimate_memory(&spec.tree_config).total() - 1);
+        assert!(solve_and_report(&spec, Unit::Bb, None).unwrap_err().contains("aren't suit-symmetric"));
+    }
+}
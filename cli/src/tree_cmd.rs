@@ -0,0 +1,263 @@
+//! `oracle tree build` — build, validate, summarize, and save a game tree
+//!
+//! `oracle tree show TREE --depth N` prints a saved tree as an indented list
+//! of action tokens (`line::action_token`) with the player, street, and pot
+//! at each node, to check bet-size configs without Graphviz. Chance nodes
+//! show their card count and continue down the first runout only.
+
+use oracle_engine::builder::{build_tree, StreetSizes, TreeConfig};
+use oracle_engine::factored::FactoredTree;
+use oracle_engine::file_format::{load_tree, save_tree, Compression};
+use oracle_engine::game::GameConfig;
+use oracle_engine::line::{action_token, format_amount};
+use oracle_engine::node::{format_cards, parse_cards, Action, GameTree, Node, NodeId, Player, Street};
+
+/// Parse a bet-size list like `33,75` or `66,allin` (percent of pot).
+///
+/// Returns the pot fractions and whether `allin` was present.
+pub fn parse_bet_sizes(s: &str) -> Result<(Vec<f64>, bool), String> {
+    let mut sizes = Vec::new();
+    let mut all_in = false;
+    for token in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if token.eq_ignore_ascii_case("allin") {
+            all_in = true;
+            continue;
+        }
+        let pct: f64 = token.parse().map_err(|_| format!("invalid bet size '{}'", token))?;
+        sizes.push(pct / 100.0);
+    }
+    Ok((sizes, all_in))
+}
+
+/// Parse `95` (both players) or `95,120` (`IP,OOP`)
+pub fn parse_stacks(s: &str) -> Result<[f64; 2], String> {
+    let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+    let parse = |t: &str| t.parse::<f64>().map_err(|_| format!("invalid stack '{}'", t));
+    match parts.as_slice() {
+        [both] => {
+            let v = parse(both)?;
+            Ok([v, v])
+        }
+        [ip, oop] => Ok([parse(ip)?, parse(oop)?]),
+        _ => Err(format!("invalid stacks '{}'", s)),
+    }
+}
+
+/// Street sizes from a `parse_bet_sizes` list
+pub fn street_sizes(s: &str) -> Result<StreetSizes, String> {
+    let (sizes, all_in) = parse_bet_sizes(s)?;
+    Ok(StreetSizes { allow_all_in: all_in, ..StreetSizes::new(sizes) })
+}
+
+/// Run `oracle tree build` with the arguments after `build`
+pub fn run_tree_build(args: &[String]) -> Result<(), String> {
+    let mut board = None;
+    let mut pot = 10.0;
+    let mut stacks = [95.0, 95.0];
+    let mut flop = StreetSizes::new(vec![0.33, 0.75]);
+    let mut turn = StreetSizes::new(vec![0.66]);
+    let mut river = StreetSizes::new(vec![0.66]);
+    let mut max_raises = 3usize;
+    let mut out = String::from("tree.bin");
+    let mut compression = Compression::None;
+    let mut isomorphic = false;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--board", Some(v)) => board = Some(parse_cards(v).map_err(|e| e.to_string())?),
+            ("--pot", Some(v)) => pot = v.parse().map_err(|_| format!("invalid pot '{}'", v))?,
+            ("--stacks", Some(v)) => stacks = parse_stacks(v)?,
+            ("--flop-bets", Some(v)) => flop = street_sizes(v)?,
+            ("--turn-bets", Some(v)) => turn = street_sizes(v)?,
+            ("--river-bets", Some(v)) => river = street_sizes(v)?,
+            ("--max-raises", Some(v)) => max_raises = v.parse().map_err(|_| format!("invalid max raises '{}'", v))?,
+            ("--out", Some(v)) => out = v.to_string(),
+            ("--compress", _) => {
+                compression = Compression::Zstd { level: 3 };
+                i += 1;
+                continue;
+            }
+            ("--isomorphic", _) => {
+                isomorphic = true;
+                i += 1;
+                continue;
+            }
+            (flag, _) => return Err(format!("unknown or incomplete option '{}'", flag)),
+        }
+        i += 2;
+    }
+
+    let board = board.ok_or("--board is required")?;
+    let mut config = TreeConfig::new(GameConfig::from_pot(pot, stacks), board);
+    config.flop = flop;
+    config.turn = turn;
+    config.river = river;
+    config.max_raises = max_raises;
+    config.isomorphic_runouts = isomorphic;
+    config.validate().map_err(|e| e.to_string())?;
+
+    let street = match config.root_street() {
+        Some(Street::Flop) => "flop",
+        Some(Street::Turn) => "turn",
+        _ => "river",
+    };
+    println!("Building {} tree on {} (pot {}, stacks {:?})...", street, format_cards(&config.board), pot, stacks);
+    let tree = build_tree(&config);
+    let stats = tree.stats();
+
+    println!("  Nodes          : {}", stats.nodes);
+    println!("  Decision nodes : {}", stats.decisions);
+    println!("  Chance nodes   : {}", stats.chance);
+    println!("  Terminal nodes : {} ({} folds)", stats.terminals, stats.fold_terminals);
+    println!("  Regret slots   : {}", stats.actions);
+    println!("  Max depth      : {}", stats.max_depth);
+    let factored = FactoredTree::build(&config);
+    println!("  Factored       : {} nodes in {} street templates", factored.skeleton_nodes(), factored.templates.len());
+
+    save_tree(&out, &tree, compression).map_err(|e| e.to_string())?;
+    let bytes = std::fs::metadata(&out).map(|m| m.len()).unwrap_or(0);
+    println!("Saved {} ({} bytes)", out, bytes);
+    Ok(())
+}
+
+/// Run `oracle tree show` with the arguments after `show`
+pub fn run_tree_show(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut depth = 3usize;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--depth", Some(v)) => {
+                depth = v.parse().map_err(|_| format!("invalid depth '{}'", v))?;
+                i += 2;
+            }
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (file, _) => {
+                path = Some(file.to_string());
+                i += 1;
+            }
+        }
+    }
+    let path = path.ok_or("usage: oracle tree show TREE [--depth N]")?;
+    let tree = load_tree(&path).map_err(|e| format!("{}: {}", path, e))?;
+    print!("{}", format_tree(&tree, depth));
+    Ok(())
+}
+
+/// Indented view of `tree` down to `depth` actions or runouts below the root.
+///
+/// Cut-off subtrees end in `...`.
+pub fn format_tree(tree: &GameTree, depth: usize) -> String {
+    let mut out = String::new();
+    if tree.get(0).is_some() {
+        write_node(tree, 0, "", 0, depth, &mut out);
+    }
+    out
+}
+
+fn write_node(tree: &GameTree, id: NodeId, edge: &str, level: usize, depth: usize, out: &mut String) {
+    let Some(node) = tree.get(id) else { return };
+    let more = level >= depth && !node.children().is_empty();
+    out.push_str(&format!("{}{}[{}] {}{}\n", "  ".repeat(level), edge, id, describe(node), if more { " ..." } else { "" }));
+    if more {
+        return;
+    }
+    match node {
+        Node::Decision { actions, children, pot, .. } => {
+            for (action, &child) in actions.iter().zip(children) {
+                let token = match action {
+                    Action::Bet { size } => format!("{} ({})", action_token(action, *pot), format_amount(*size)),
+                    _ => action_token(action, *pot),
+                };
+                write_node(tree, child, &format!("{} -> ", token), level + 1, depth, out);
+            }
+        }
+        Node::Chance { children, .. } => {
+            if let Some(&first) = children.first() {
+                let card = tree.get(first).and_then(|n| n.board().last()).map(|c| c.to_string()).unwrap_or_default();
+                write_node(tree, first, &format!("{} -> ", card), level + 1, depth, out);
+            }
+        }
+        Node::Terminal { .. } => {}
+    }
+}
+
+fn describe(node: &Node) -> String {
+    let pot = format_amount(node.pot());
+    match node {
+        Node::Decision { player, street, stacks, board, .. } => format!(
+            "{} to act, {} {}, pot {}, stacks IP {} OOP {}",
+            player_name(*player),
+            street_name(*street),
+            format_cards(board),
+            pot,
+            format_amount(stacks[0]),
+            format_amount(stacks[1])
+        ),
+        Node::Chance { street, children, .. } => {
+            let next = if *street == Street::Flop { "turn" } else { "river" };
+            format!("deal {}, {} cards (first shown), pot {}", next, children.len(), pot)
+        }
+        Node::Terminal { folder: Some(player), .. } => format!("{} folds, pot {}", player_name(*player), pot),
+        Node::Terminal { folder: None, .. } => format!("showdown, pot {}", pot),
+    }
+}
+
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::IP => "IP",
+        Player::OOP => "OOP",
+    }
+}
+
+fn street_name(street: Street) -> &'static str {
+    match street {
+        Street::Flop => "flop",
+        Street::Turn => "turn",
+        Street::River => "river",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bet_sizes() {
+        assert_eq!(parse_bet_sizes("33,75"), Ok((vec![0.33, 0.75], false)));
+        assert_eq!(parse_bet_sizes("66,allin"), Ok((vec![0.66], true)));
+        assert!(parse_bet_sizes("66,big").is_err());
+    }
+
+    #[test]
+    fn test_parse_stacks() {
+        assert_eq!(parse_stacks("95"), Ok([95.0, 95.0]));
+        assert_eq!(parse_stacks("40,200"), Ok([40.0, 200.0]));
+        assert!(parse_stacks("1,2,3").is_err());
+    }
+
+    #[test]
+    fn test_format_tree_stops_at_depth() {
+        let mut config = TreeConfig::new(GameConfig::from_pot(10.0, [95.0, 95.0]), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.5]);
+        let tree = build_tree(&config);
+
+        let shallow = format_tree(&tree, 1);
+        let lines: Vec<&str> = shallow.lines().collect();
+        assert_eq!(lines[0], "[0] OOP to act, turn AsKh7d2c, pot 10, stacks IP 95 OOP 95");
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("  x -> [") && lines[1].contains("IP to act, turn") && lines[1].ends_with(" ..."));
+        assert!(lines[2].starts_with("  b50 (5) -> ["), "{}", lines[2]);
+
+        let deep = format_tree(&tree, 3);
+        assert!(deep.contains("    x -> ["), "{}", deep);
+        assert!(deep.contains("deal river, 48 cards (first shown), pot 10"));
+        assert!(deep.contains("      2s -> [3] OOP to act, river AsKh7d2c2s"), "{}", deep);
+        assert!(deep.contains("OOP folds, pot 15"));
+    }
+}
@@ -0,0 +1,99 @@
+//! `oracle trim` — save a solution without its rarely reached infosets
+//!
+//! `oracle trim flop.bin --min-reach 0.01 --out flop-explore.bin` keeps the
+//! strategies of infosets reached at least 1% of the time under the average
+//! strategy and leaves the rest out (`Solution::partial`), so files meant
+//! for exploring and exporting shrink to the lines anyone visits. The tree
+//! stays whole and the omitted infosets are marked in the file; the result
+//! can't be resumed. `--compress` also zstd-compresses it (needs the `zstd`
+//! feature).
+
+use oracle_engine::file_format::{load_solution, save_solution, Compression};
+use oracle_engine::Solution;
+
+/// Run `oracle trim` with the arguments after the subcommand
+pub fn run_trim(args: &[String]) -> Result<(), String> {
+    let mut path = None;
+    let mut min_reach = None;
+    let mut out = None;
+    let mut compression = Compression::None;
+
+    let mut i = 0usize;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str);
+        match (args[i].as_str(), value) {
+            ("--compress", _) => {
+                compression = Compression::Zstd { level: 3 };
+                i += 1;
+                continue;
+            }
+            ("--min-reach", Some(v)) => {
+                min_reach = Some(v.parse::<f64>().ok().filter(|r| (0.0..=1.0).contains(r)).ok_or(format!("invalid --min-reach '{}'", v))?)
+            }
+            ("--out", Some(v)) => out = Some(v.to_string()),
+            (flag, _) if flag.starts_with("--") => return Err(format!("unknown or incomplete option '{}'", flag)),
+            (p, _) => {
+                path = Some(p.to_string());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+    let usage = "usage: oracle trim SOLUTION --min-reach F --out FILE [--compress]";
+    let ((path, min_reach), out) = path.zip(min_reach).zip(out).ok_or(usage)?;
+    let solution = load_solution(&path).map_err(|e| format!("{}: {}", path, e))?;
+    solution.validate().map_err(|e| format!("{}: {}", path, e))?;
+
+    let partial = solution.partial(min_reach);
+    save_solution(&out, &partial, compression).map_err(|e| format!("{}: {}", out, e))?;
+    let bytes = |p: &str| std::fs::metadata(p).map(|m| m.len()).map_err(|e| format!("{}: {}", p, e));
+    println!("{}", trim_summary(&partial, bytes(&path)?, bytes(&out)?));
+    Ok(())
+}
+
+/// How much a partial save left out and saved
+pub fn trim_summary(partial: &Solution, before: u64, after: u64) -> String {
+    let infosets = partial.tree.num_infosets();
+    format!(
+        "Left out {} of {} infosets; {} -> {} bytes ({:.1}%)",
+        partial.omitted.len(),
+        infosets,
+        before,
+        after,
+        after as f64 / before.max(1) as f64 * 100.0
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::node::parse_cards;
+    use oracle_engine::range::Range;
+    use oracle_engine::solve::{solve, SolveSpec};
+    use oracle_engine::StopCondition;
+
+    #[test]
+    fn test_trim_saves_a_smaller_partial_solution() {
+        let ranges = [Range::parse("AA,KK,AK").unwrap(), Range::parse("QQ-99,AQ").unwrap()];
+        let mut spec = SolveSpec::from_state(parse_cards("AsKh7d2c").unwrap(), ranges, 6.0, [97.0, 97.0]);
+        spec.stop = StopCondition::iterations(50);
+        let solution = Solution::from_solver(&solve(&spec).unwrap().solver);
+        let dir = std::env::temp_dir().join(format!("oracle_trim_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (input, output) = (dir.join("turn.bin"), dir.join("explore.bin"));
+        save_solution(&input, &solution, Compression::None).unwrap();
+
+        let arg = |s: &str| s.to_string();
+        run_trim(&[input.display().to_string(), arg("--min-reach"), arg("0.05"), arg("--out"), output.display().to_string()]).unwrap();
+        let trimmed = load_solution(&output).unwrap();
+        assert!(trimmed.is_partial() && trimmed.validate().is_ok());
+        assert_eq!(trimmed.tree, solution.tree);
+        assert!(std::fs::metadata(&output).unwrap().len() < std::fs::metadata(&input).unwrap().len());
+        assert!(trim_summary(&trimmed, 200, 50).ends_with("200 -> 50 bytes (25.0%)"));
+
+        assert!(run_trim(&[input.display().to_string(), arg("--min-reach"), arg("2")]).is_err());
+        assert!(run_trim(&[input.display().to_string(), arg("--min-reach"), arg("0.1")]).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
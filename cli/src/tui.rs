@@ -0,0 +1,230 @@
+//! `oracle solve --tui` — live dashboard for long solves (requires the `tui` feature)
+//!
+//! Redraws at every exploitability check: a log-scale convergence chart,
+//! iterations/sec, memory, and an ETA to the target. The ETA fits
+//! `exploitability ≈ a · t^-k` through the last two checks, which tracks
+//! CFR+'s roughly power-law convergence well enough for a progress estimate.
+
+use std::time::Duration;
+use oracle_engine::{CfrSolver, ConvergenceMetrics, StopReason};
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::{Axis, Block, Chart, Dataset, GraphType, Paragraph};
+use ratatui::Frame;
+
+/// Everything the dashboard shows, updated at each check
+pub struct Dashboard {
+    /// `(iteration, exploitability)` at each check
+    history: Vec<(u64, f64)>,
+    elapsed: Duration,
+    max_iterations: u64,
+    target: Option<f64>,
+    /// Bytes of regret + strategy-sum storage
+    storage_bytes: u64,
+    finished: Option<StopReason>,
+}
+
+impl Dashboard {
+    pub fn new(solver: &CfrSolver) -> Self {
+        let slots: usize = (0..solver.storage.num_infosets()).map(|i| solver.storage.regrets(i).len()).sum();
+        let stop = solver.config().stop;
+        Dashboard {
+            history: Vec::new(),
+            elapsed: Duration::ZERO,
+            max_iterations: stop.max_iterations,
+            target: stop.target_exploitability,
+            storage_bytes: (slots * 2 * std::mem::size_of::<f64>()) as u64,
+            finished: None,
+        }
+    }
+
+    pub fn record(&mut self, m: &ConvergenceMetrics) {
+        self.history.push((m.iterations, m.exploitability));
+        self.elapsed = m.elapsed_time;
+    }
+
+    pub fn iterations_per_sec(&self) -> f64 {
+        let iterations = self.history.last().map_or(0, |&(i, _)| i);
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 { iterations as f64 / secs } else { 0.0 }
+    }
+
+    /// Estimated iteration at which exploitability reaches the target, capped
+    /// at the iteration limit; `None` without a target or while not improving
+    pub fn projected_iterations(&self) -> Option<u64> {
+        let target = self.target?;
+        let [.., (t1, e1), (t2, e2)] = self.history[..] else { return None };
+        if e2 <= target {
+            return Some(t2);
+        }
+        if e1 <= 0.0 || e2 >= e1 {
+            return None;
+        }
+        let k = (e1 / e2).ln() / (t2 as f64 / t1 as f64).ln();
+        let projected = t2 as f64 * (e2 / target).powf(1.0 / k);
+        Some((projected.ceil() as u64).min(self.max_iterations))
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.projected_iterations()?.saturating_sub(self.history.last()?.0);
+        let rate = self.iterations_per_sec();
+        (rate > 0.0).then(|| Duration::from_secs_f64(remaining as f64 / rate))
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let [stats_area, chart_area] = Layout::vertical([Constraint::Length(7), Constraint::Min(8)]).areas(frame.area());
+
+        let (iteration, exploitability) = self.history.last().copied().unwrap_or((0, f64::NAN));
+        let eta = match (self.finished, self.eta()) {
+            (Some(_), _) => "done".to_string(),
+            (None, Some(eta)) => format!("{:.1}s", eta.as_secs_f64()),
+            (None, None) => "-".to_string(),
+        };
+        let memory = match resident_memory() {
+            Some(rss) => format!("{} storage, {} resident", format_bytes(self.storage_bytes), format_bytes(rss)),
+            None => format!("{} storage", format_bytes(self.storage_bytes)),
+        };
+        let status = match self.finished {
+            None => "running".to_string(),
+            Some(reason) => format!("stopped ({:?}) — press any key", reason),
+        };
+        let stats = vec![
+            Line::from(format!("Iteration      : {} / {}", iteration, self.max_iterations)),
+            Line::from(format!("Exploitability : {:.6} bb (target {})", exploitability,
+                self.target.map_or("none".to_string(), |t| format!("{} bb", t)))),
+            Line::from(format!("Speed          : {:.0} it/s   elapsed {:.1}s   ETA {}",
+                self.iterations_per_sec(), self.elapsed.as_secs_f64(), eta)),
+            Line::from(format!("Memory         : {}", memory)),
+            Line::from(format!("Status         : {}", status)),
+        ];
+        frame.render_widget(Paragraph::new(stats).block(Block::bordered().title(" oracle solve ")), stats_area);
+
+        let points: Vec<(f64, f64)> = self.history.iter()
+            .filter(|&&(_, e)| e > 0.0)
+            .map(|&(i, e)| (i as f64, e.log10()))
+            .collect();
+        let target: Vec<(f64, f64)> = self.target.filter(|&t| t > 0.0)
+            .map(|t| vec![(0.0, t.log10()), (iteration as f64, t.log10())])
+            .unwrap_or_default();
+        let (y_min, y_max) = points.iter().chain(&target)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &(_, y)| (lo.min(y), hi.max(y)));
+        let (y_min, y_max) = if y_min.is_finite() { (y_min.floor(), y_max.ceil().max(y_min.floor() + 1.0)) } else { (-3.0, 1.0) };
+        let x_max = (iteration as f64).max(1.0);
+
+        let datasets = vec![
+            Dataset::default().name("exploitability").marker(Marker::Braille).graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Cyan)).data(&points),
+            Dataset::default().name("target").marker(Marker::Dot).graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::DarkGray)).data(&target),
+        ];
+        let chart = Chart::new(datasets)
+            .block(Block::bordered().title(" exploitability (bb, log10) "))
+            .x_axis(Axis::default().title("iteration").bounds([0.0, x_max])
+                .labels(["0".to_string(), format!("{}", x_max as u64)]))
+            .y_axis(Axis::default().bounds([y_min, y_max])
+                .labels([format!("1e{}", y_min), format!("1e{}", y_max)]));
+        frame.render_widget(chart, chart_area);
+    }
+}
+
+/// Run the solver with the dashboard on an alternate screen.
+///
+/// The terminal is restored before returning, even if drawing fails.
+pub fn run_solve_tui(solver: &mut CfrSolver) -> std::io::Result<(ConvergenceMetrics, StopReason)> {
+    let mut dashboard = Dashboard::new(solver);
+    let mut terminal = ratatui::init();
+    let mut draw_error = None;
+    let (m, reason) = solver.run(|m| {
+        dashboard.record(m);
+        if draw_error.is_none() {
+            draw_error = terminal.draw(|f| dashboard.render(f)).err();
+        }
+    });
+    dashboard.finished = Some(reason);
+    let result = match draw_error {
+        Some(e) => Err(e),
+        None => terminal.draw(|f| dashboard.render(f)).map(|_| ()).and_then(|_| wait_for_key()),
+    };
+    ratatui::restore();
+    result.map(|_| (m, reason))
+}
+
+fn wait_for_key() -> std::io::Result<()> {
+    loop {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Resident set size from `/proc/self/status` (Linux only)
+fn resident_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", value, UNITS[unit]) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oracle_engine::test_tree::{build_test_tree, terminal_ev_table};
+    use oracle_engine::SolverConfig;
+
+    fn metrics(iterations: u64, exploitability: f64, millis: u64) -> ConvergenceMetrics {
+        ConvergenceMetrics {
+            exploitability,
+            ip_br_value: exploitability / 2.0,
+            oop_br_value: exploitability / 2.0,
+            iterations,
+            elapsed_time: Duration::from_millis(millis),
+        }
+    }
+
+    fn dashboard() -> Dashboard {
+        let config = SolverConfig::builder().max_iterations(10_000).target_exploitability(0.01).build().unwrap();
+        Dashboard::new(&CfrSolver::with_config(build_test_tree(), terminal_ev_table(), config))
+    }
+
+    #[test]
+    fn test_rate_and_power_law_eta() {
+        let mut dash = dashboard();
+        assert_eq!(dash.projected_iterations(), None);
+        dash.record(&metrics(100, 1.0, 500));
+        dash.record(&metrics(200, 0.5, 1_000));
+        assert!((dash.iterations_per_sec() - 200.0).abs() < 1e-9);
+        // 1/t convergence: 0.5 at 200 reaches 0.01 at 10,000
+        assert_eq!(dash.projected_iterations(), Some(10_000));
+        let eta = dash.eta().unwrap().as_secs_f64();
+        assert!((eta - 49.0).abs() < 1e-6, "eta {}", eta);
+    }
+
+    #[test]
+    fn test_eta_edge_cases() {
+        let mut dash = dashboard();
+        dash.record(&metrics(100, 0.5, 100));
+        dash.record(&metrics(200, 0.6, 200));
+        assert_eq!(dash.eta(), None, "not improving");
+        dash.record(&metrics(300, 0.005, 300));
+        assert_eq!(dash.projected_iterations(), Some(300));
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+        assert_eq!(dash.storage_bytes, 4 * 2 * 2 * 8);
+    }
+}
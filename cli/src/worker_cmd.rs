@@ -0,0 +1,88 @@
+//! `oracle worker` and `oracle solve --workers` — experimental distributed solving
+
+use std::net::TcpListener;
+use std::time::Instant;
+use oracle_engine::distributed::Coordinator;
+use oracle_engine::config::StagnationTracker;
+use oracle_engine::{CfrSolver, ConvergenceMetrics, StopReason};
+
+/// Run `oracle worker` with the arguments after `worker`
+pub fn run_worker_cmd(args: &[String]) -> Result<(), String> {
+    let mut listen = String::from("0.0.0.0:7070");
+    let mut i = 0usize;
+    while i < args.len() {
+        match (args[i].as_str(), args.get(i + 1)) {
+            ("--listen", Some(v)) => listen = v.clone(),
+            (flag, _) => return Err(format!("unknown or incomplete option '{}'", flag)),
+        }
+        i += 2;
+    }
+
+    let listener = TcpListener::bind(&listen).map_err(|e| format!("{}: {}", listen, e))?;
+    println!("Worker listening on {}", listener.local_addr().map_err(|e| e.to_string())?);
+    oracle_engine::distributed::run_worker(listener, |result| match result {
+        Ok(()) => println!("Session finished"),
+        Err(e) => eprintln!("Session failed: {}", e),
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Parse `host:port,host:port`
+pub fn parse_workers(s: &str) -> Vec<String> {
+    s.split(',').map(str::trim).filter(|w| !w.is_empty()).map(String::from).collect()
+}
+
+/// Run the solver's stop condition with chance subtrees on remote workers,
+/// calling `on_check` at each exploitability check. Returns the merged solver.
+pub fn run_distributed(
+    solver: CfrSolver,
+    workers: &[String],
+    mut on_check: impl FnMut(&ConvergenceMetrics),
+) -> Result<(CfrSolver, ConvergenceMetrics, StopReason), String> {
+    let stop = solver.config().stop;
+    let mut coordinator = Coordinator::connect(solver, workers).map_err(|e| e.to_string())?;
+
+    let start = Instant::now();
+    let mut result = None;
+    let mut plateau = StagnationTracker::new(stop.stagnation);
+    for iter in 1..=stop.max_iterations {
+        coordinator.run_iteration().map_err(|e| e.to_string())?;
+        let elapsed = start.elapsed();
+        let timed_out = stop.time_limit.is_some_and(|limit| elapsed >= limit);
+        if iter.is_multiple_of(stop.check_every) || timed_out || iter == stop.max_iterations {
+            let m = coordinator.solver().exploitability(elapsed);
+            on_check(&m);
+            if stop.target_exploitability.is_some_and(|t| m.exploitability < t) {
+                result = Some((m, StopReason::ExploitabilityTarget));
+                break;
+            }
+            if stop.street_targets.is_some_and(|targets| targets.met(&coordinator.solver().street_exploitability())) {
+                result = Some((m, StopReason::StreetTargets));
+                break;
+            }
+            if timed_out {
+                result = Some((m, StopReason::TimeLimit));
+                break;
+            }
+            if plateau.observe(m.exploitability) {
+                result = Some((m, StopReason::Stagnated));
+                break;
+            }
+            result = Some((m, StopReason::IterationCap));
+        }
+    }
+    let solver = coordinator.finish().map_err(|e| e.to_string())?;
+    let (m, reason) = result.unwrap_or_else(|| (solver.exploitability(start.elapsed()), StopReason::IterationCap));
+    Ok((solver, m, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_workers() {
+        assert_eq!(parse_workers("a:1, b:2,"), vec!["a:1", "b:2"]);
+        assert!(parse_workers("").is_empty());
+    }
+}
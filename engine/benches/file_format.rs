@@ -0,0 +1,58 @@
+//! Criterion benchmarks for tree file size and load time, plain vs zstd
+//!
+//! Run with `cargo bench -p oracle-engine --features zstd --bench file_format`.
+//! The compression ratio for each level is printed before the timings.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use oracle_engine::builder::{build_tree, StreetSizes, TreeConfig};
+use oracle_engine::file_format::{read_payload, write_payload, Compression, FileKind};
+use oracle_engine::game::GameConfig;
+use oracle_engine::node::{parse_cards, GameTree};
+
+/// Turn tree with two bet sizes per street (~tens of thousands of nodes)
+fn bench_tree() -> GameTree {
+    let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+    config.turn = StreetSizes::new(vec![0.5, 1.0]);
+    config.river = StreetSizes::new(vec![0.5, 1.0]);
+    config.max_raises = 2;
+    build_tree(&config)
+}
+
+fn encode(tree: &GameTree, compression: Compression) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_payload(&mut bytes, FileKind::Tree, tree, compression).unwrap();
+    bytes
+}
+
+fn benchmark_tree_files(c: &mut Criterion) {
+    let tree = bench_tree();
+    let variants = [
+        ("plain", Compression::None),
+        ("zstd_1", Compression::Zstd { level: 1 }),
+        ("zstd_3", Compression::Zstd { level: 3 }),
+        ("zstd_9", Compression::Zstd { level: 9 }),
+    ];
+
+    let plain_len = encode(&tree, Compression::None).len();
+    println!("tree: {} nodes, {} bytes uncompressed", tree.len(), plain_len);
+    for (name, compression) in variants {
+        let len = encode(&tree, compression).len();
+        println!("  {:<8} {:>10} bytes  ratio {:.2}x", name, len, plain_len as f64 / len as f64);
+    }
+
+    for (name, compression) in variants {
+        c.bench_function(&format!("tree_save_{}", name), |b| {
+            b.iter(|| black_box(encode(&tree, compression)))
+        });
+        let bytes = encode(&tree, compression);
+        c.bench_function(&format!("tree_load_{}", name), |b| {
+            b.iter(|| {
+                let back: GameTree = read_payload(&bytes[..], FileKind::Tree).unwrap();
+                black_box(back)
+            })
+        });
+    }
+}
+
+criterion_group!(benches, benchmark_tree_files);
+criterion_main!(benches);
@@ -5,53 +5,14 @@
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use oracle_engine::evaluator::CactusKevEvaluator;
-use oracle_engine::node::Card;
+use oracle_engine::node::{Card, HandRank};
 use oracle_engine::HandEvaluator;
-
-/// Simple LCG for deterministic random number generation
-struct Lcg {
-    state: u64,
-}
-
-impl Lcg {  
-    fn new(seed: u64) -> Self {
-        Lcg { state: seed }
-    }
-
-    fn next(&mut self) -> u64 {
-        self.state = (self.state.wrapping_mul(1103515245).wrapping_add(12345)) & 0x7fffffff;
-        self.state
-    }
-
-    fn next_card(&mut self) -> Card {
-        Card::new((self.next() % 52) as u8)
-    }
-}
+use oracle_engine::rng::Rng;
 
 /// Generate a batch of random (board, hand) pairs
 fn generate_test_hands(count: usize, seed: u64) -> Vec<([Card; 5], [Card; 2])> {
-    let mut lcg = Lcg::new(seed);
-    let mut hands = Vec::with_capacity(count);
-
-    for _ in 0..count {
-        // Generate 7 unique cards
-        let mut cards = Vec::new();
-        while cards.len() < 7 {
-            let card = lcg.next_card();
-            if !cards.contains(&card) {
-                cards.push(card);
-            } else {
-                // If duplicate, try next card
-                lcg.next();
-            }
-        }
-
-        let board = [cards[0], cards[1], cards[2], cards[3], cards[4]];
-        let hand = [cards[5], cards[6]];
-        hands.push((board, hand));
-    }
-
-    hands
+    let mut rng = Rng::new(seed);
+    (0..count).map(|_| rng.board_and_hand()).collect()
 }
 
 fn benchmark_scalar_evaluation(c: &mut Criterion) {
@@ -85,5 +46,21 @@ fn benchmark_batch_evaluation(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, benchmark_scalar_evaluation, benchmark_batch_evaluation);
+fn benchmark_batch_into_evaluation(c: &mut Criterion) {
+    let evaluator = CactusKevEvaluator::new();
+    let test_hands = generate_test_hands(1_000_000, 12345);
+
+    let boards: Vec<[Card; 5]> = test_hands.iter().map(|(b, _)| *b).collect();
+    let hands: Vec<[Card; 2]> = test_hands.iter().map(|(_, h)| *h).collect();
+    let mut out = vec![HandRank::new(0); boards.len()];
+
+    c.bench_function("hand_evaluator_7card_batch_into", |b| {
+        b.iter(|| {
+            evaluator.evaluate_batch_into(black_box(&boards), black_box(&hands), &mut out);
+            black_box(out[0])
+        })
+    });
+}
+
+criterion_group!(benches, benchmark_scalar_evaluation, benchmark_batch_evaluation, benchmark_batch_into_evaluation);
 criterion_main!(benches);
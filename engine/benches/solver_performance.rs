@@ -1,11 +1,39 @@
 //! Criterion benchmarks for CFR+ solver throughput
 
-use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use oracle_engine::builder::{build_tree, StreetSizes, TreeConfig};
 use oracle_engine::cfr::CfrSolver;
+use oracle_engine::ev_table::EvTable;
+use oracle_engine::config::{Algorithm, SolverConfig};
 use oracle_engine::exploitability::compute_exploitability;
+use oracle_engine::factored::FactoredTree;
+use oracle_engine::game::{build_terminal_evs, GameConfig};
+use oracle_engine::node::{parse_cards, GameTree, Node};
+use oracle_engine::simd;
 use oracle_engine::test_tree::build_test_tree;
+use oracle_engine::verification::{infoset_exploitability, kuhn_tree, leduc_tree};
 use std::time::Duration;
 
+/// Flop tree with one half-pot size per street and one raise: 191,844 nodes
+fn realistic_flop_config() -> TreeConfig {
+    let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d").unwrap());
+    config.flop = StreetSizes::new(vec![0.5]);
+    config.turn = StreetSizes::new(vec![0.5]);
+    config.river = StreetSizes::new(vec![0.5]);
+    config.max_raises = 1;
+    config
+}
+
+/// `realistic_flop_config` with deterministic board-dependent showdown
+/// equities (no range evaluation, so only the solver is measured)
+fn realistic_flop_solver() -> CfrSolver {
+    let tree = build_tree(&realistic_flop_config());
+    let evs = build_terminal_evs(&tree, |node: &Node| {
+        f64::from(node.board().iter().map(|c| u32::from(c.value())).sum::<u32>() % 101) / 100.0
+    });
+    CfrSolver::new_with_evs(tree, evs)
+}
+
 fn benchmark_cfr_single_iteration(c: &mut Criterion) {
     c.bench_function("cfr_single_iteration", |b| {
         b.iter_batched(
@@ -58,10 +86,107 @@ fn benchmark_exploitability_check(c: &mut Criterion) {
     });
 }
 
+fn benchmark_vector_kernels(c: &mut Criterion) {
+    // Three actions over a full range of combos, the common per-node shape
+    let (actions, hands) = (3, 1_081);
+    let regrets: Vec<f64> = (0..actions * hands).map(|i| ((i * 7919) % 201) as f64 - 100.0).collect();
+    let node_values: Vec<f64> = (0..hands).map(|i| (i % 13) as f64 - 6.0).collect();
+    let reach: Vec<f64> = (0..hands).map(|i| (i % 10) as f64 / 10.0).collect();
+    let mut out = vec![0.0; actions * hands];
+
+    c.bench_function("simd_regret_match_3x1081", |b| {
+        b.iter(|| simd::regret_match(black_box(&regrets), actions, hands, &mut out))
+    });
+    c.bench_function("simd_update_regrets_3x1081", |b| {
+        b.iter_batched(
+            || regrets.clone(),
+            |mut r| simd::update_regrets(&mut r, black_box(&regrets), black_box(&node_values)),
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("simd_accumulate_strategy_3x1081", |b| {
+        b.iter(|| simd::accumulate_strategy(&mut out, black_box(&regrets), black_box(&reach), 2.0))
+    });
+}
+
+fn benchmark_realistic_flop_tree(c: &mut Criterion) {
+    let mut solver = realistic_flop_solver();
+    let nodes = solver.tree.len() as u64;
+    let mut group = c.benchmark_group("realistic_flop_192k");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(nodes));
+
+    group.bench_function("cfr_iteration", |b| b.iter(|| solver.run_iteration()));
+    group.bench_function("exploitability_check", |b| {
+        b.iter(|| black_box(solver.exploitability(Duration::ZERO)))
+    });
+    group.bench_function("build_tree", |b| b.iter(|| build_tree(black_box(&realistic_flop_config()))));
+    group.bench_function("build_factored", |b| {
+        b.iter(|| FactoredTree::build(black_box(&realistic_flop_config())))
+    });
+    group.finish();
+}
+
+/// Run `iterations` of CFR+ and of PCFR+ on one game, reporting the
+/// exploitability each reached alongside the time taken
+fn compare_variants(c: &mut Criterion, group_name: &str, tree: GameTree, evs: EvTable, iterations: u64, exploitability: impl Fn(&CfrSolver) -> f64) {
+    let solver = |algorithm| {
+        let config = SolverConfig::builder().algorithm(algorithm).build().unwrap();
+        CfrSolver::with_config(tree.clone(), evs.clone(), config)
+    };
+
+    let mut group = c.benchmark_group(group_name);
+    group.sample_size(10);
+    for (name, algorithm) in [("cfr_plus", Algorithm::CfrPlus), ("predictive_cfr_plus", Algorithm::PredictiveCfrPlus)] {
+        let mut reached = solver(algorithm);
+        for _ in 0..iterations {
+            reached.run_iteration();
+        }
+        println!("{}/{}: exploitability {:.6} after {} iterations", group_name, name, exploitability(&reached), iterations);
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || solver(algorithm),
+                |mut solver| {
+                    for _ in 0..iterations {
+                        solver.run_iteration();
+                    }
+                    black_box(&solver.storage);
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// CFR+ against PCFR+ on a turn tree with two turn sizes (200 iterations),
+/// and on Kuhn poker (1,000) and Leduc hold'em (200), whose exploitability
+/// is measured per infoset (see `verification`)
+fn benchmark_cfr_variants(c: &mut Criterion) {
+    let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+    config.turn = StreetSizes::new(vec![0.5, 1.0]);
+    config.river = StreetSizes::new(vec![0.75]);
+    config.max_raises = 2;
+    let tree = build_tree(&config);
+    let evs = build_terminal_evs(&tree, |node: &Node| {
+        f64::from(node.board().iter().map(|c| u32::from(c.value())).sum::<u32>() % 101) / 100.0
+    });
+    compare_variants(c, "cfr_variants_200_iterations", tree, evs.into(), 200, |s| s.exploitability(Duration::ZERO).exploitability);
+
+    let by_infoset = |s: &CfrSolver| infoset_exploitability(&s.tree, &s.storage, s.terminal_evs());
+    let (tree, evs) = kuhn_tree();
+    compare_variants(c, "cfr_variants_kuhn_1000_iterations", tree, evs, 1_000, by_infoset);
+    let (tree, evs) = leduc_tree();
+    compare_variants(c, "cfr_variants_leduc_200_iterations", tree, evs, 200, by_infoset);
+}
+
 criterion_group!(
     benches,
     benchmark_cfr_single_iteration,
     benchmark_cfr_1000_iterations,
     benchmark_exploitability_check,
+    benchmark_vector_kernels,
+    benchmark_realistic_flop_tree,
+    benchmark_cfr_variants,
 );
 criterion_main!(benches);
@@ -0,0 +1,393 @@
+//! ACPC match-state protocol: parsing and translating to the tree
+//!
+//! The Annual Computer Poker Competition dealer sends each client a line
+//! per state change:
+//!
+//! ```text
+//! MATCHSTATE:<position>:<hand>:<betting>:<cards>
+//! MATCHSTATE:0:17:cr300c/r600:Td9d|/AsKh7d
+//! ```
+//!
+//! Betting rounds are separated by `/`; `c` is a check or call, `f` a fold,
+//! and `r<N>` a raise to `N` chips committed in the hand (no-limit). Cards
+//! are the hole cards by seat (`|`-separated, hidden ones empty), then the
+//! board per round. When it's the client's turn, it answers with the line it
+//! received, a colon, and its action.
+//!
+//! Heads-up, seat 1 acts first preflop and seat 0 first after it, so seat 0
+//! is OOP in a postflop tree and seat 1 is IP. `MatchState::game_state`
+//! turns the postflop rounds into a `GameState` for `Solution::act`:
+//! chip totals become bb bets and raise-to totals for the street, using
+//! `AcpcConfig::big_blind_chips`. Preflop is not in the tree; both players
+//! enter the flop with the same chips committed.
+//!
+//! Dealer logs record each finished hand as a `STATE` line with every hole
+//! card, the chips each seat won, and the players by seat:
+//!
+//! ```text
+//! STATE:17:cr300c/r600c/cc/r900f:Td9d|Ah2c/AsKh7d/2s/9h:600|-600:alice|bob
+//! ```
+//!
+//! `parse_log_line` reads one as a `LoggedHand`, so a log doubles as a hand
+//! history for reviewing play against a solution (`review`). A hand whose
+//! chips all went in before the river (`cr20000c///`, the later rounds
+//! empty) also has an all-in EV, `LoggedHand::all_in_ev`: what each seat
+//! wins on average over every runout from where the chips went in, with
+//! the luck of the actual runout taken out.
+
+use crate::bot::GameState;
+use crate::equity::hand_vs_hand;
+use crate::evaluator::CactusKevEvaluator;
+use crate::node::{parse_cards, Action, Card};
+
+/// Chips per big blind in the standard ACPC no-limit game (50/100 blinds)
+pub const DEFAULT_BIG_BLIND_CHIPS: u64 = 100;
+
+/// Betting rounds in hold'em (preflop, flop, turn, river)
+const ROUNDS: usize = 4;
+
+/// One action in ACPC betting notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcpcAction {
+    Fold,
+    /// Check or call
+    Call,
+    /// Raise to this many chips committed in the hand
+    Raise(u64),
+}
+
+/// Error returned for a malformed line or a state the tree can't express
+#[derive(Debug, Clone, PartialEq)]
+pub enum AcpcError {
+    /// The line isn't a valid `MATCHSTATE`
+    Parse(String),
+    /// Valid, but has no postflop tree counterpart (e.g. still preflop)
+    Untranslatable(String),
+}
+
+impl std::fmt::Display for AcpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AcpcError::Parse(m) => write!(f, "bad MATCHSTATE line: {}", m),
+            AcpcError::Untranslatable(m) => write!(f, "state has no tree counterpart: {}", m),
+        }
+    }
+}
+
+impl std::error::Error for AcpcError {}
+
+/// How chips map to the tree's big blinds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AcpcConfig {
+    pub big_blind_chips: u64,
+}
+
+impl AcpcConfig {
+    pub fn new() -> Self {
+        AcpcConfig { big_blind_chips: DEFAULT_BIG_BLIND_CHIPS }
+    }
+}
+
+impl Default for AcpcConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A parsed `MATCHSTATE` line
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchState {
+    /// The line as received, without the line ending (echoed in replies)
+    pub raw: String,
+    /// This client's seat
+    pub position: usize,
+    pub hand_number: u64,
+    /// Actions per betting round, preflop first
+    pub rounds: Vec<Vec<AcpcAction>>,
+    /// Hole cards by seat, where visible
+    pub hole_cards: [Option<[Card; 2]>; 2],
+    /// Board cards dealt so far
+    pub board: Vec<Card>,
+}
+
+/// A finished hand from a dealer log's `STATE` line
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedHand {
+    /// The hand as a match state seen from seat 0, with every hole card
+    pub state: MatchState,
+    /// Player names by seat
+    pub players: [String; 2],
+    /// Chips each seat won, by seat
+    pub outcome: [f64; 2],
+}
+
+/// Parse one `STATE` line from a dealer log
+pub fn parse_log_line(line: &str) -> Result<LoggedHand, AcpcError> {
+    let raw = line.trim_end_matches(['\r', '\n']);
+    let bad = |m: &str| AcpcError::Parse(format!("{} in '{}'", m, raw));
+    let fields: Vec<&str> = raw.split(':').collect();
+    let ["STATE", hand, betting, cards, outcome, players] = fields.as_slice() else {
+        return Err(bad("expected STATE:<hand>:<betting>:<cards>:<outcome>:<players>"));
+    };
+    let Some((first, second)) = players.split_once('|') else {
+        return Err(bad("expected two players"));
+    };
+    let won = |s: &str| s.parse::<f64>().map_err(|_| bad("bad outcome"));
+    let (first_won, second_won) = outcome.split_once('|').ok_or_else(|| bad("expected two outcomes"))?;
+    let mut state = parse_match_state(&format!("MATCHSTATE:0:{}:{}:{}", hand, betting, cards))?;
+    state.raw = raw.to_string();
+    Ok(LoggedHand { state, players: [first.to_string(), second.to_string()], outcome: [won(first_won)?, won(second_won)?] })
+}
+
+impl LoggedHand {
+    /// The board when the last chips went in, for a hand that went to
+    /// showdown with both players all-in before the river
+    pub fn all_in_board(&self) -> Option<&[Card]> {
+        let rounds = &self.state.rounds;
+        let last = rounds.iter().rposition(|r| !r.is_empty())?;
+        if last + 1 == rounds.len() || rounds.iter().flatten().any(|&a| a == AcpcAction::Fold) {
+            return None;
+        }
+        let dealt = if last == 0 { 0 } else { last + 2 };
+        self.state.board.get(..dealt)
+    }
+
+    /// Chips each seat wins on average over every runout from the all-in
+    /// (see module docs), or `None` unless the hand was all-in before the
+    /// river with both hands shown
+    pub fn all_in_ev(&self, config: &AcpcConfig) -> Option<[f64; 2]> {
+        let board = self.all_in_board()?;
+        let [Some(first), Some(second)] = self.state.hole_cards else { return None };
+        let equity = hand_vs_hand(&CactusKevEvaluator::new(), first, second, board);
+        let committed = self.state.committed_before(self.state.rounds.len(), config) as f64;
+        let first_ev = (2.0 * equity - 1.0) * committed;
+        Some([first_ev, -first_ev])
+    }
+}
+
+/// Parse one line from the dealer
+pub fn parse_match_state(line: &str) -> Result<MatchState, AcpcError> {
+    let raw = line.trim_end_matches(['\r', '\n']);
+    let bad = |m: &str| AcpcError::Parse(format!("{} in '{}'", m, raw));
+    let mut fields = raw.splitn(5, ':');
+    if fields.next() != Some("MATCHSTATE") {
+        return Err(bad("missing MATCHSTATE prefix"));
+    }
+    let mut number = || fields.next().and_then(|f| f.parse::<u64>().ok());
+    let position = number().filter(|&p| p < 2).ok_or_else(|| bad("bad position"))? as usize;
+    let hand_number = number().ok_or_else(|| bad("bad hand number"))?;
+    let (betting, cards) = (fields.next().ok_or_else(|| bad("no betting"))?, fields.next().ok_or_else(|| bad("no cards"))?);
+
+    let rounds = betting.split('/').map(|round| parse_round(round).ok_or_else(|| bad("bad betting"))).collect::<Result<Vec<_>, _>>()?;
+    if rounds.len() > ROUNDS {
+        return Err(bad("too many rounds"));
+    }
+    let mut card_rounds = cards.split('/');
+    let holes: Vec<&str> = card_rounds.next().unwrap_or("").split('|').collect();
+    if holes.len() != 2 {
+        return Err(bad("expected two seats of hole cards"));
+    }
+    let mut hole_cards = [None, None];
+    for (seat, hole) in holes.iter().enumerate() {
+        if !hole.is_empty() {
+            let cards = parse_cards(hole).map_err(|e| bad(&e.to_string()))?;
+            hole_cards[seat] = Some(<[Card; 2]>::try_from(cards).map_err(|_| bad("hole cards are not two cards"))?);
+        }
+    }
+    let mut board = Vec::new();
+    for round in card_rounds {
+        board.extend(parse_cards(round).map_err(|e| bad(&e.to_string()))?);
+    }
+    Ok(MatchState { raw: raw.to_string(), position, hand_number, rounds, hole_cards, board })
+}
+
+fn parse_round(round: &str) -> Option<Vec<AcpcAction>> {
+    let mut actions = Vec::new();
+    let mut chars = round.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        actions.push(match c {
+            'f' => AcpcAction::Fold,
+            'c' => AcpcAction::Call,
+            'r' => {
+                let mut end = i + 1;
+                while chars.peek().is_some_and(|(_, d)| d.is_ascii_digit()) {
+                    end = chars.next()?.0 + 1;
+                }
+                AcpcAction::Raise(round[i + 1..end].parse().ok()?)
+            }
+            _ => return None,
+        });
+    }
+    Some(actions)
+}
+
+impl MatchState {
+    /// Seat to act, or `None` once the hand is over
+    pub fn to_act(&self) -> Option<usize> {
+        let round = self.rounds.len() - 1;
+        let actions = &self.rounds[round];
+        if self.rounds.iter().flatten().any(|&a| a == AcpcAction::Fold) {
+            return None;
+        }
+        if actions.len() >= 2 && actions.last() == Some(&AcpcAction::Call) {
+            // The round closed; the dealer starts the next one with a '/'
+            return None;
+        }
+        let first = if round == 0 { 1 } else { 0 };
+        Some((first + actions.len()) % 2)
+    }
+
+    pub fn is_our_turn(&self) -> bool {
+        self.to_act() == Some(self.position)
+    }
+
+    /// Chips each player has committed when `round` starts: the last raise,
+    /// or the big blind after a limped preflop
+    fn committed_before(&self, round: usize, config: &AcpcConfig) -> u64 {
+        let raised = self.rounds[..round].iter().flatten().filter_map(|a| match a {
+            AcpcAction::Raise(n) => Some(*n),
+            _ => None,
+        });
+        raised.max().unwrap_or(config.big_blind_chips)
+    }
+
+    /// The postflop history as a tree `GameState` (bb amounts)
+    pub fn game_state(&self, config: &AcpcConfig) -> Result<GameState, AcpcError> {
+        if self.rounds.len() < 2 {
+            return Err(AcpcError::Untranslatable("the hand is still preflop".to_string()));
+        }
+        self.game_state_from(1, config)
+    }
+
+    /// The history from betting round `round` on (1 flop, 2 turn, 3 river),
+    /// for a tree rooted on that street
+    pub fn game_state_from(&self, round: usize, config: &AcpcConfig) -> Result<GameState, AcpcError> {
+        if self.rounds.len() <= round {
+            return Err(AcpcError::Untranslatable(format!("the hand never reached betting round {}", round)));
+        }
+        let bb = config.big_blind_chips as f64;
+        let mut state = GameState::new(self.board.clone());
+        for (round, actions) in self.rounds.iter().enumerate().skip(round) {
+            let start = self.committed_before(round, config);
+            let mut street = [0u64; 2];
+            for (k, &action) in actions.iter().enumerate() {
+                let seat = k % 2;
+                let facing = street[1 - seat] > street[seat];
+                state.actions.push(match action {
+                    AcpcAction::Fold => Action::Fold,
+                    AcpcAction::Call if facing => {
+                        street[seat] = street[1 - seat];
+                        Action::Call
+                    }
+                    AcpcAction::Call => Action::Check,
+                    AcpcAction::Raise(total) => {
+                        let amount = total.checked_sub(start)
+                            .ok_or_else(|| AcpcError::Untranslatable(format!("raise to {} below the {} already in", total, start)))?;
+                        street[seat] = amount;
+                        let size = amount as f64 / bb;
+                        if street[1 - seat] > 0 { Action::Raise { size } } else { Action::Bet { size } }
+                    }
+                });
+            }
+        }
+        Ok(state)
+    }
+
+    /// Pot in bb when betting round `round` starts
+    pub fn pot_before(&self, round: usize, config: &AcpcConfig) -> f64 {
+        2.0 * self.committed_before(round, config) as f64 / config.big_blind_chips as f64
+    }
+
+    /// The reply line for taking tree action `action` now
+    pub fn reply(&self, action: Action, config: &AcpcConfig) -> String {
+        let start = self.committed_before(self.rounds.len() - 1, config);
+        let token = match action {
+            Action::Fold => "f".to_string(),
+            Action::Check | Action::Call => "c".to_string(),
+            Action::Bet { size } | Action::Raise { size } => {
+                format!("r{}", start + (size * config.big_blind_chips as f64).round() as u64)
+            }
+        };
+        format!("{}:{}", self.raw, token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_translate_postflop_betting() {
+        let state = parse_match_state("MATCHSTATE:0:17:cr300c/r600r1800:Td9d|/AsKh7d\r\n").unwrap();
+        assert_eq!(state.position, 0);
+        assert_eq!(state.hand_number, 17);
+        assert_eq!(state.rounds, vec![
+            vec![AcpcAction::Call, AcpcAction::Raise(300), AcpcAction::Call],
+            vec![AcpcAction::Raise(600), AcpcAction::Raise(1800)],
+        ]);
+        assert_eq!(state.hole_cards[0], Some(parse_cards("Td9d").unwrap().try_into().unwrap()));
+        assert_eq!(state.hole_cards[1], None);
+        assert_eq!(state.board, parse_cards("AsKh7d").unwrap());
+        // Seat 0 (OOP) bet 3bb into the 3bb-each pot, seat 1 raised to 15bb; seat 0 to act
+        assert!(state.is_our_turn());
+
+        let game = state.game_state(&AcpcConfig::new()).unwrap();
+        assert_eq!(game.board, state.board);
+        assert_eq!(game.actions, vec![Action::Bet { size: 3.0 }, Action::Raise { size: 15.0 }]);
+        assert_eq!(state.reply(Action::Call, &AcpcConfig::new()), "MATCHSTATE:0:17:cr300c/r600r1800:Td9d|/AsKh7d:c");
+        assert_eq!(state.reply(Action::Raise { size: 40.0 }, &AcpcConfig::new()), "MATCHSTATE:0:17:cr300c/r600r1800:Td9d|/AsKh7d:r4300");
+    }
+
+    #[test]
+    fn test_turn_order_and_errors() {
+        let preflop = parse_match_state("MATCHSTATE:1:0::|Ah2c").unwrap();
+        assert_eq!(preflop.to_act(), Some(1));
+        assert!(matches!(preflop.game_state(&AcpcConfig::new()), Err(AcpcError::Untranslatable(_))));
+        let checked = parse_match_state("MATCHSTATE:1:3:cc/cc/cc/c:|Ah2c/AsKh7d/2s/9h").unwrap();
+        assert!(checked.is_our_turn());
+        let game = checked.game_state(&AcpcConfig::new()).unwrap();
+        assert_eq!(game.actions, vec![Action::Check; 5]);
+        // Limped preflop: a river bet of 200 more chips is a 2bb bet, replied as a raise to 300
+        assert_eq!(checked.reply(Action::Bet { size: 2.0 }, &AcpcConfig::new()), "MATCHSTATE:1:3:cc/cc/cc/c:|Ah2c/AsKh7d/2s/9h:r300");
+        assert_eq!(parse_match_state("MATCHSTATE:0:3:cc/cc/cc/cc:Kd2d|Ah2c/AsKh7d/2s/9h").unwrap().to_act(), None);
+        assert_eq!(parse_match_state("MATCHSTATE:0:3:r300f:Kd2d|").unwrap().to_act(), None);
+        assert!(matches!(parse_match_state("MATCHSTATE:2:0::|"), Err(AcpcError::Parse(_))));
+        assert!(matches!(parse_match_state("MATCHSTATE:0:0:x::|"), Err(AcpcError::Parse(_))));
+    }
+
+    #[test]
+    fn test_log_lines_replay_from_any_street() {
+        let hand = parse_log_line("STATE:17:cr300c/r600c/cc/r900f:Td9d|Ah2c/AsKh7d/2s/9h:600|-600:alice|bob\n").unwrap();
+        assert_eq!(hand.players, ["alice".to_string(), "bob".to_string()]);
+        assert_eq!(hand.state.hand_number, 17);
+        assert_eq!(hand.state.hole_cards[1], Some(parse_cards("Ah2c").unwrap().try_into().unwrap()));
+        assert_eq!(hand.state.board, parse_cards("AsKh7d2s9h").unwrap());
+
+        let config = AcpcConfig::new();
+        assert_eq!((hand.state.pot_before(1, &config), hand.state.pot_before(3, &config)), (6.0, 12.0));
+        let river = hand.state.game_state_from(3, &config).unwrap();
+        assert_eq!(river.actions, vec![Action::Bet { size: 3.0 }, Action::Fold]);
+        assert_eq!(hand.state.game_state(&config).unwrap().actions.len(), 6);
+        assert!(hand.state.game_state_from(4, &config).is_err());
+        assert_eq!(hand.outcome, [600.0, -600.0]);
+        assert_eq!(hand.all_in_ev(&config), None);
+        assert!(parse_log_line("STATE:17:cr300c:Td9d|Ah2c:0|0").is_err());
+        assert!(parse_log_line("MATCHSTATE:0:17:cr300c:Td9d|:0|0:alice|bob").is_err());
+    }
+
+    #[test]
+    fn test_all_in_ev_takes_out_the_runout() {
+        // All-in on the turn: kings need the last king, 1 of 44 rivers, and hit it
+        let hand = parse_log_line("STATE:5:cc/r300c/r20000c/:KcKd|AhAd/AsKh7d/2c/Ks:20000|-20000:alice|bob").unwrap();
+        assert_eq!(hand.all_in_board(), Some(&parse_cards("AsKh7d2c").unwrap()[..]));
+        let [kings, aces] = hand.all_in_ev(&AcpcConfig::new()).unwrap();
+        assert!((kings - 20000.0 * (2.0 / 44.0 - 1.0)).abs() < 1e-6, "{}", kings);
+        assert_eq!(aces, -kings);
+
+        let preflop = parse_log_line("STATE:6:r20000c///:KcKd|AhAd/AsKh7d/2c/Ks:20000|-20000:alice|bob").unwrap();
+        assert_eq!(preflop.all_in_board(), Some(&[][..]));
+        let river = parse_log_line("STATE:7:cc/cc/cc/r20000c:KcKd|AhAd/AsKh7d/2c/Ks:20000|-20000:alice|bob").unwrap();
+        assert_eq!(river.all_in_ev(&AcpcConfig::new()), None);
+        assert!(parse_log_line("STATE:8:cc/cc/cc/cc:KcKd|AhAd/AsKh7d/2c/Ks:x|0:alice|bob").is_err());
+    }
+}
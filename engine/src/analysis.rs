@@ -0,0 +1,424 @@
+//! Post-solve strategy analysis
+//!
+//! `action_removal_ev_loss` estimates how much each action is worth to the
+//! player who takes it: the action is locked to zero frequency at its node
+//! (remaining actions keep their relative frequencies), the opponent
+//! best-responds to the modified strategy, and the change in the opponent's
+//! best-response value is the EV the acting player loses. Actions whose
+//! removal costs almost nothing are good candidates to drop from simplified
+//! strategies.
+//!
+//! Only the locked node changes; the acting player does not re-optimize
+//! elsewhere, so the loss is an upper bound on what a re-solve without the
+//! action would cost.
+//!
+//! `continuing_ranges` narrows the starting ranges down a line, e.g. to
+//! export what a player still holds at a node (`Range::to_notation`).
+//!
+//! `strategy_mixing` measures how mixed each decision is: the entropy of its
+//! average strategy (in bits) and its support, the actions played at least
+//! `SUPPORT_THRESHOLD` of the time. `MixingSummary` averages them over the
+//! tree, so a strategy that is mostly pure can be simplified with little
+//! loss while one with high mean entropy needs its mixes kept.
+//!
+//! `ev_line` follows one line from the root and shows where its EV is made:
+//! each node's IP value (`node_values`) and reach (`prune::node_reach`),
+//! the value gained or lost by the step into it, that step weighted by its
+//! reach (its share of the root's EV), and how much of the line's total
+//! change has been realized so far.
+
+use crate::par::join;
+use crate::par::prelude::*;
+use crate::cfr::RegretStorage;
+use crate::ev_table::TerminalEvs;
+use crate::exploitability::br_traverse_with_strategy;
+use crate::combo::mask_blocked;
+use crate::game::player_index;
+use crate::node::{Action, GameTree, Node, NodeId, Player};
+use crate::prune::node_reach;
+use crate::range::Range;
+use crate::summation::Summation;
+
+/// EV impact of removing one action at one node
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionRemoval {
+    /// Decision node the action belongs to
+    pub node: NodeId,
+    /// Player taking the action
+    pub player: Player,
+    /// Index of the action at the node
+    pub action_index: usize,
+    /// The removed action
+    pub action: Action,
+    /// Average-strategy frequency of the action before removal
+    pub frequency: f64,
+    /// EV the acting player loses (in bb, whole-game) when the action is removed
+    pub ev_loss: f64,
+}
+
+/// Frequencies below this don't count toward a decision's support
+pub const SUPPORT_THRESHOLD: f64 = 0.01;
+
+/// How mixed one decision node's average strategy is
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeMixing {
+    pub node: NodeId,
+    pub player: Player,
+    pub actions: usize,
+    /// Shannon entropy of the average strategy, in bits (0 = pure,
+    /// `log2(actions)` = uniform)
+    pub entropy: f64,
+    /// Actions played at least `SUPPORT_THRESHOLD` of the time
+    pub support: usize,
+}
+
+impl NodeMixing {
+    /// One action carries (nearly) all the frequency
+    pub fn is_pure(&self) -> bool {
+        self.support <= 1
+    }
+}
+
+/// Mixing metrics averaged over decision nodes
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MixingSummary {
+    /// Decision nodes with more than one action
+    pub decisions: usize,
+    /// Mean entropy in bits
+    pub mean_entropy: f64,
+    /// Share of decisions that are pure
+    pub pure_fraction: f64,
+    /// Mean number of actions in the support
+    pub mean_support: f64,
+}
+
+impl MixingSummary {
+    pub fn from_nodes(nodes: &[NodeMixing]) -> Self {
+        if nodes.is_empty() {
+            return MixingSummary::default();
+        }
+        let n = nodes.len() as f64;
+        MixingSummary {
+            decisions: nodes.len(),
+            mean_entropy: nodes.iter().map(|m| m.entropy).sum::<f64>() / n,
+            pure_fraction: nodes.iter().filter(|m| m.is_pure()).count() as f64 / n,
+            mean_support: nodes.iter().map(|m| m.support as f64).sum::<f64>() / n,
+        }
+    }
+}
+
+/// Mixing metrics of every decision node with more than one action, in node order
+pub fn strategy_mixing(tree: &GameTree, storage: &RegretStorage) -> Vec<NodeMixing> {
+    tree.nodes.iter().filter_map(|node| match node {
+        Node::Decision { id, infoset_id, player, actions, .. } if actions.len() > 1 => {
+            let strategy = storage.average_strategy(*infoset_id as usize);
+            Some(NodeMixing {
+                node: *id,
+                player: *player,
+                actions: actions.len(),
+                entropy: strategy.iter().filter(|&&p| p > 0.0).map(|&p| -p * p.log2()).sum(),
+                support: strategy.iter().filter(|&&p| p >= SUPPORT_THRESHOLD).count(),
+            })
+        }
+        _ => None,
+    }).collect()
+}
+
+/// EV loss from removing each action at every multi-action decision node,
+/// sorted from cheapest to most expensive removal.
+///
+/// Baselines and perturbed best responses are evaluated in parallel.
+pub fn action_removal_ev_loss(
+    tree: &GameTree,
+    storage: &RegretStorage,
+    terminal_evs: &impl TerminalEvs,
+) -> Vec<ActionRemoval> {
+    let average = |infoset_id: usize| storage.average_strategy(infoset_id);
+    let (ip_baseline, oop_baseline) = join(
+        || br_traverse_with_strategy(tree, &average, terminal_evs, 0, Player::IP, Summation::Naive),
+        || br_traverse_with_strategy(tree, &average, terminal_evs, 0, Player::OOP, Summation::Naive),
+    );
+
+    let candidates: Vec<(NodeId, usize, Player, usize, Action)> = tree.nodes.iter()
+        .filter_map(|node| match node {
+            Node::Decision { id, infoset_id, player, actions, .. } if actions.len() > 1 => {
+                Some((*id, *infoset_id as usize, *player, actions.clone()))
+            }
+            _ => None,
+        })
+        .flat_map(|(id, infoset, player, actions)| {
+            actions.into_iter().enumerate().map(move |(i, a)| (id, infoset, player, i, a))
+        })
+        .collect();
+
+    let mut removals: Vec<ActionRemoval> = candidates.into_par_iter()
+        .map(|(node, infoset, player, action_index, action)| {
+            let frequency = storage.average_strategy(infoset)[action_index];
+            let locked = |i: usize| {
+                if i == infoset {
+                    remove_action(&storage.average_strategy(i), action_index)
+                } else {
+                    storage.average_strategy(i)
+                }
+            };
+            let (opponent, baseline) = match player {
+                Player::IP => (Player::OOP, oop_baseline),
+                Player::OOP => (Player::IP, ip_baseline),
+            };
+            let perturbed = br_traverse_with_strategy(tree, &locked, terminal_evs, 0, opponent, Summation::Naive);
+            ActionRemoval { node, player, action_index, action, frequency, ev_loss: perturbed - baseline }
+        })
+        .collect();
+
+    removals.sort_by(|a, b| a.ev_loss.total_cmp(&b.ev_loss));
+    removals
+}
+
+/// Each player's range `[IP, OOP]` on arriving at `node` under the average
+/// strategy: `ranges` scaled by the player's own action frequencies on the
+/// line there, without combos the node's board blocks. Scalar strategies
+/// are shared by every combo, so each range scales evenly; see
+/// `VectorCfrSolver::continuing_ranges` for per-combo narrowing.
+pub fn continuing_ranges(tree: &GameTree, storage: &RegretStorage, ranges: &[Range; 2], node: NodeId) -> [Range; 2] {
+    let mut reach = [1.0, 1.0];
+    let mut child = node;
+    while let Some(parent) = tree.get(child).and_then(|n| n.parent()) {
+        if let Some(Node::Decision { player, children, infoset_id, .. }) = tree.get(parent) {
+            let a = children.iter().position(|&c| c == child).expect("child of its parent");
+            reach[player_index(*player)] *= storage.average_strategy(*infoset_id as usize)[a];
+        }
+        child = parent;
+    }
+    let board = tree.get(node).map_or(&[][..], |n| n.board());
+    [0, 1].map(|p| {
+        let mut weights = ranges[p].scale(reach[p]).weights().to_vec();
+        mask_blocked(&mut weights, board);
+        Range::from_weights(&weights)
+    })
+}
+
+/// EV (IP perspective) of every node, indexed by node ID, when both players
+/// play the average strategy and chance cards are dealt uniformly.
+pub fn node_values(tree: &GameTree, storage: &RegretStorage, terminal_evs: &impl TerminalEvs) -> Vec<f64> {
+    let mut values = vec![0.0; tree.len()];
+    if !tree.is_empty() {
+        fill_values(tree, storage, terminal_evs, 0, &mut values);
+    }
+    values
+}
+
+fn fill_values(
+    tree: &GameTree,
+    storage: &RegretStorage,
+    terminal_evs: &impl TerminalEvs,
+    node_id: NodeId,
+    values: &mut [f64],
+) -> f64 {
+    let value = match tree.get(node_id).expect("invalid node id") {
+        Node::Terminal { .. } => terminal_evs.terminal_ev(node_id),
+        Node::Decision { children, infoset_id, .. } => {
+            let probs = storage.average_strategy(*infoset_id as usize);
+            children.iter().zip(&probs)
+                .map(|(&child, &p)| p * fill_values(tree, storage, terminal_evs, child, values))
+                .sum()
+        }
+        node @ Node::Chance { children, weights, .. } => {
+            if weights.is_empty() {
+                let total: f64 = children.iter().map(|&child| fill_values(tree, storage, terminal_evs, child, values)).sum();
+                total / children.len() as f64
+            } else {
+                children.iter().enumerate()
+                    .map(|(i, &child)| node.chance_probability(i) * fill_values(tree, storage, terminal_evs, child, values))
+                    .sum()
+            }
+        }
+    };
+    values[node_id as usize] = value;
+    value
+}
+
+/// One node on a line, from `ev_line`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinePoint {
+    pub node: NodeId,
+    pub pot: f64,
+    /// Probability both players' average strategies play the line this far
+    pub reach: f64,
+    /// IP's EV at the node, bb
+    pub ev: f64,
+    /// `ev` minus the previous node's (0.0 at the root)
+    pub ev_change: f64,
+    /// `ev_change` × `reach`
+    pub weighted_change: f64,
+    /// Share of the line's EV change from the root realized here, in [0, 1]
+    /// when the EV moves one way (0.0 if the line's EV never changes)
+    pub realized: f64,
+}
+
+/// The nodes from the root to `node`, with IP's EV and reach at each (see
+/// module docs)
+pub fn ev_line(tree: &GameTree, storage: &RegretStorage, terminal_evs: &impl TerminalEvs, node: NodeId) -> Vec<LinePoint> {
+    let mut path = vec![node];
+    while let Some(parent) = tree.get(*path.last().expect("non-empty")).and_then(Node::parent) {
+        path.push(parent);
+    }
+    path.reverse();
+    let values = node_values(tree, storage, terminal_evs);
+    let reach = node_reach(tree, storage);
+    let (first, last) = (values[path[0] as usize], values[node as usize]);
+    let mut previous = first;
+    path.into_iter().map(|id| {
+        let (ev, reach) = (values[id as usize], reach[id as usize]);
+        let ev_change = ev - previous;
+        previous = ev;
+        let realized = if (last - first).abs() > 1e-12 && ev != first { (ev - first) / (last - first) } else { 0.0 };
+        let pot = tree.get(id).expect("invalid node id").pot();
+        LinePoint { node: id, pot, reach, ev, ev_change, weighted_change: ev_change * reach, realized }
+    }).collect()
+}
+
+/// Zero out `index` and renormalize; uniform over the rest if nothing remains.
+fn remove_action(strategy: &[f64], index: usize) -> Vec<f64> {
+    let mut out = strategy.to_vec();
+    out[index] = 0.0;
+    let total: f64 = out.iter().sum();
+    if total > 0.0 {
+        out.iter_mut().for_each(|p| *p /= total);
+    } else {
+        let share = 1.0 / (out.len() - 1) as f64;
+        for (i, p) in out.iter_mut().enumerate() {
+            *p = if i == index { 0.0 } else { share };
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfr::CfrSolver;
+    use crate::test_tree::{build_test_tree, terminal_ev_table};
+
+    #[test]
+    fn test_remove_action_renormalizes() {
+        assert_eq!(remove_action(&[0.5, 0.25, 0.25], 0), vec![0.0, 0.5, 0.5]);
+        assert_eq!(remove_action(&[1.0, 0.0, 0.0], 0), vec![0.0, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_mixing_metrics() {
+        let tree = build_test_tree();
+        let untrained = CfrSolver::new(tree.clone());
+        let uniform = strategy_mixing(&tree, &untrained.storage);
+        // 4 two-action decisions, all uniform before any iteration
+        assert_eq!(uniform.len(), 4);
+        assert!(uniform.iter().all(|m| (m.entropy - 1.0).abs() < 1e-12 && m.support == 2));
+        let summary = MixingSummary::from_nodes(&uniform);
+        assert_eq!((summary.pure_fraction, summary.mean_support), (0.0, 2.0));
+
+        let mut solver = CfrSolver::new(tree.clone());
+        for _ in 0..10_000 {
+            solver.run_iteration();
+        }
+        let solved = MixingSummary::from_nodes(&strategy_mixing(&tree, &solver.storage));
+        assert!(solved.mean_entropy < summary.mean_entropy && solved.pure_fraction > 0.0);
+        assert_eq!(MixingSummary::from_nodes(&[]), MixingSummary::default());
+    }
+
+    #[test]
+    fn test_continuing_ranges_follow_the_line() {
+        let tree = build_test_tree();
+        let mut solver = CfrSolver::new(tree.clone());
+        for _ in 0..200 {
+            solver.run_iteration();
+        }
+        let ranges = [Range::parse("AA,KK").unwrap(), Range::parse("QQ,AK").unwrap()];
+        // OOP checks, IP bets: node 3
+        let [ip, oop] = continuing_ranges(&tree, &solver.storage, &ranges, 3);
+        let (check, bet) = (solver.storage.average_strategy(0)[0], solver.storage.average_strategy(1)[1]);
+        // As and Kh on the board leave 3 + 3 pairs and 3 * 3 AK combos
+        assert!((ip.combo_count() - 6.0 * bet).abs() < 1e-12);
+        assert!((oop.combo_count() - (6.0 + 9.0) * check).abs() < 1e-12);
+        let board = tree.get(0).unwrap().board();
+        assert_eq!(continuing_ranges(&tree, &solver.storage, &ranges, 0)[0].to_notation(board, 0.0), "KK+");
+    }
+
+    #[test]
+    fn test_node_values_follow_average_strategy() {
+        let tree = build_test_tree();
+        let evs = terminal_ev_table();
+        let mut solver = CfrSolver::new(tree.clone());
+        for _ in 0..2_000 {
+            solver.run_iteration();
+        }
+        let values = node_values(&tree, &solver.storage, &evs);
+        assert_eq!(values.len(), tree.len());
+        for node in &tree.nodes {
+            let id = node.id() as usize;
+            match node {
+                Node::Terminal { .. } => assert_eq!(values[id], evs[&node.id()]),
+                Node::Decision { children, infoset_id, .. } => {
+                    let probs = solver.storage.average_strategy(*infoset_id as usize);
+                    let expected: f64 = children.iter().zip(&probs).map(|(&c, p)| p * values[c as usize]).sum();
+                    assert!((values[id] - expected).abs() < 1e-12);
+                }
+                Node::Chance { .. } => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_ev_line_tracks_value_and_reach_down_a_line() {
+        let tree = build_test_tree();
+        let evs = terminal_ev_table();
+        let mut solver = CfrSolver::new(tree.clone());
+        for _ in 0..2_000 {
+            solver.run_iteration();
+        }
+        let leaf = tree.nodes.iter().rev().find(|n| n.is_terminal() && n.parent() != Some(0)).unwrap().id();
+        let line = ev_line(&tree, &solver.storage, &evs, leaf);
+        assert_eq!((line[0].node, line.last().unwrap().node), (0, leaf));
+        assert!(line.len() >= 3);
+        let values = node_values(&tree, &solver.storage, &evs);
+        assert_eq!((line[0].reach, line[0].ev_change, line[0].realized), (1.0, 0.0, 0.0));
+        assert_eq!(line.last().unwrap().ev, evs[&leaf]);
+        for w in line.windows(2) {
+            assert_eq!(w[1].ev, values[w[1].node as usize]);
+            assert!((w[1].ev_change - (w[1].ev - w[0].ev)).abs() < 1e-12);
+            assert!(w[1].reach <= w[0].reach);
+            assert!((w[1].weighted_change - w[1].ev_change * w[1].reach).abs() < 1e-12);
+        }
+        // The changes add up to the whole line's, all of it realized at the end
+        let total: f64 = line.iter().map(|p| p.ev_change).sum();
+        assert!((total - (evs[&leaf] - values[0])).abs() < 1e-9);
+        assert!((line.last().unwrap().realized - 1.0).abs() < 1e-12 || total.abs() < 1e-12);
+        assert_eq!(ev_line(&tree, &solver.storage, &evs, 0).len(), 1);
+    }
+
+    #[test]
+    fn test_removal_losses_on_test_tree() {
+        let tree = build_test_tree();
+        let evs = terminal_ev_table();
+        let mut solver = CfrSolver::new(tree.clone());
+        for _ in 0..10_000 {
+            solver.run_iteration();
+        }
+        let removals = action_removal_ev_loss(&tree, &solver.storage, &evs);
+        // 4 decision nodes × 2 actions
+        assert_eq!(removals.len(), 8);
+        assert!(removals.windows(2).all(|w| w[0].ev_loss <= w[1].ev_loss));
+
+        let find = |node: NodeId, idx: usize| removals.iter().find(|r| r.node == node && r.action_index == idx).unwrap();
+        // OOP's root bet is worth 3bb: without it OOP checks into IP's 2bb line instead of -1
+        let bet = find(0, 1);
+        assert!((bet.ev_loss - 3.0).abs() < 0.05, "root bet loss {}", bet.ev_loss);
+        // IP calling the root bet saves 4bb over folding
+        let call = find(6, 1);
+        assert!((call.ev_loss - 4.0).abs() < 0.05, "call loss {}", call.ev_loss);
+        // Actions the equilibrium never uses cost nothing to drop
+        let check = find(0, 0);
+        assert!(check.frequency < 0.01);
+        assert!(check.ev_loss.abs() < 0.05, "root check loss {}", check.ev_loss);
+    }
+}
@@ -0,0 +1,233 @@
+//! Seeded random boards matching texture filters
+//!
+//! `BoardSampler` draws flops, turns, or rivers that pass every
+//! `BoardFilter`, for batch studies over a class of boards and for test
+//! generation. Filters parse from a comma-separated spec:
+//!
+//! | Token | Board |
+//! |-------|-------|
+//! | `monotone` / `two-tone` / `rainbow` | one suit / exactly two suits / no suit repeated |
+//! | `paired` / `unpaired` | some rank repeats / every rank distinct |
+//! | `high<=T` / `high>=Q` | highest rank at most / at least the given rank |
+//! | `contains=As` / `excludes=Kh` | the card is / is not on the board |
+//!
+//! so `"paired,high<=T"` is "paired, ten-high or lower". Sampling is
+//! rejection-based with required cards placed first; a filter set that no
+//! board can satisfy fails after a bounded number of draws.
+
+use crate::deck::Deck;
+use crate::node::{parse_rank, Card};
+use crate::rng::Rng;
+
+/// Draws tried per board before `sample` gives up
+pub const MAX_ATTEMPTS: usize = 100_000;
+
+/// One board condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardFilter {
+    /// Every card one suit
+    Monotone,
+    /// Exactly two suits
+    TwoTone,
+    /// No suit repeated
+    Rainbow,
+    /// Some rank appears twice or more
+    Paired,
+    /// Every rank distinct
+    Unpaired,
+    /// Highest rank at most this (0 = deuce .. 12 = ace)
+    HighAtMost(u8),
+    /// Highest rank at least this
+    HighAtLeast(u8),
+    Contains(Card),
+    Excludes(Card),
+}
+
+impl BoardFilter {
+    /// Whether `board` passes this filter
+    pub fn matches(&self, board: &[Card]) -> bool {
+        let suits = board.iter().fold(0u8, |m, c| m | 1 << c.suit()).count_ones() as usize;
+        let ranks = board.iter().fold(0u16, |m, c| m | 1 << c.rank());
+        let high = board.iter().map(|c| c.rank()).max().unwrap_or(0);
+        match *self {
+            BoardFilter::Monotone => suits == 1,
+            BoardFilter::TwoTone => suits == 2,
+            BoardFilter::Rainbow => suits == board.len(),
+            BoardFilter::Paired => (ranks.count_ones() as usize) < board.len(),
+            BoardFilter::Unpaired => ranks.count_ones() as usize == board.len(),
+            BoardFilter::HighAtMost(r) => high <= r,
+            BoardFilter::HighAtLeast(r) => high >= r,
+            BoardFilter::Contains(card) => board.contains(&card),
+            BoardFilter::Excludes(card) => !board.contains(&card),
+        }
+    }
+
+    /// Parse one filter token (see the module docs)
+    pub fn parse(token: &str) -> Result<Self, BoardFilterError> {
+        let t = token.trim().to_ascii_lowercase();
+        let invalid = || BoardFilterError::InvalidFilter(token.trim().to_string());
+        let rank = |s: &str| {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => parse_rank(c).ok_or_else(invalid),
+                _ => Err(invalid()),
+            }
+        };
+        let card = |s: &str| s.parse::<Card>().map_err(|_| invalid());
+        Ok(match t.as_str() {
+            "monotone" => BoardFilter::Monotone,
+            "two-tone" | "twotone" => BoardFilter::TwoTone,
+            "rainbow" => BoardFilter::Rainbow,
+            "paired" => BoardFilter::Paired,
+            "unpaired" => BoardFilter::Unpaired,
+            _ => {
+                if let Some(r) = t.strip_prefix("high<=") {
+                    BoardFilter::HighAtMost(rank(r)?)
+                } else if let Some(r) = t.strip_prefix("high>=") {
+                    BoardFilter::HighAtLeast(rank(r)?)
+                } else if let Some(c) = t.strip_prefix("contains=") {
+                    BoardFilter::Contains(card(c)?)
+                } else if let Some(c) = t.strip_prefix("excludes=") {
+                    BoardFilter::Excludes(card(c)?)
+                } else {
+                    return Err(invalid());
+                }
+            }
+        })
+    }
+
+    /// Parse a comma-separated filter spec; an empty spec accepts every board
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, BoardFilterError> {
+        spec.split(',').filter(|t| !t.trim().is_empty()).map(BoardFilter::parse).collect()
+    }
+}
+
+/// Error from parsing filters or sampling boards
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardFilterError {
+    /// A token that matches none of the supported filters
+    InvalidFilter(String),
+    /// Board size outside 3–5 cards
+    InvalidSize(usize),
+    /// No board was found within `MAX_ATTEMPTS` draws
+    NoMatch,
+}
+
+impl std::fmt::Display for BoardFilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardFilterError::InvalidFilter(t) => write!(f, "invalid board filter '{}'", t),
+            BoardFilterError::InvalidSize(n) => write!(f, "boards have 3 to 5 cards, not {}", n),
+            BoardFilterError::NoMatch => write!(f, "no board matches the filters"),
+        }
+    }
+}
+
+impl std::error::Error for BoardFilterError {}
+
+/// Seeded sampler of boards passing every filter
+#[derive(Debug, Clone)]
+pub struct BoardSampler {
+    cards: usize,
+    filters: Vec<BoardFilter>,
+    rng: Rng,
+}
+
+impl BoardSampler {
+    /// Sampler of `cards`-card boards (3 = flop, 4 = turn, 5 = river)
+    pub fn new(cards: usize, filters: Vec<BoardFilter>, seed: u64) -> Result<Self, BoardFilterError> {
+        if !(3..=5).contains(&cards) {
+            return Err(BoardFilterError::InvalidSize(cards));
+        }
+        Ok(BoardSampler { cards, filters, rng: Rng::new(seed) })
+    }
+
+    /// Sampler from a comma-separated filter spec
+    pub fn parse(cards: usize, spec: &str, seed: u64) -> Result<Self, BoardFilterError> {
+        BoardSampler::new(cards, BoardFilter::parse_list(spec)?, seed)
+    }
+
+    pub fn filters(&self) -> &[BoardFilter] {
+        &self.filters
+    }
+
+    /// Next matching board, in card order
+    pub fn sample(&mut self) -> Result<Vec<Card>, BoardFilterError> {
+        let required: Vec<Card> = self.filters.iter().filter_map(|f| match f {
+            BoardFilter::Contains(card) => Some(*card),
+            _ => None,
+        }).collect();
+        let mut fixed = Vec::new();
+        for card in required {
+            if !fixed.contains(&card) {
+                fixed.push(card);
+            }
+        }
+        if fixed.len() > self.cards {
+            return Err(BoardFilterError::NoMatch);
+        }
+        for _ in 0..MAX_ATTEMPTS {
+            let mut deck = Deck::without(&fixed);
+            let mut board = fixed.clone();
+            while board.len() < self.cards {
+                board.extend(deck.deal(&mut self.rng));
+            }
+            if self.filters.iter().all(|f| f.matches(&board)) {
+                board.sort_by_key(|c| std::cmp::Reverse(c.rank()));
+                return Ok(board);
+            }
+        }
+        Err(BoardFilterError::NoMatch)
+    }
+
+    /// `n` matching boards (repeats possible)
+    pub fn sample_n(&mut self, n: usize) -> Result<Vec<Vec<Card>>, BoardFilterError> {
+        (0..n).map(|_| self.sample()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::parse_cards;
+
+    #[test]
+    fn test_filters_classify_boards() {
+        let check = |board: &str, spec: &str| {
+            let filters = BoardFilter::parse_list(spec).unwrap();
+            let cards = parse_cards(board).unwrap();
+            filters.iter().all(|f| f.matches(&cards))
+        };
+        assert!(check("AsKs7s", "monotone,unpaired,high>=A"));
+        assert!(check("Th9h9c", "two-tone,paired,high<=T"));
+        assert!(!check("JhTh9c", "high<=T"));
+        assert!(check("AsKh7d2c", "rainbow,contains=As,excludes=Ad"));
+        assert!(!check("AsKh7d2s", "rainbow"));
+        assert_eq!(BoardFilter::parse("high<=10"), Err(BoardFilterError::InvalidFilter("high<=10".into())));
+        assert!(BoardFilter::parse("wet").is_err());
+        assert_eq!(BoardFilter::parse_list("").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_sampler_is_seeded_and_respects_filters() {
+        let mut a = BoardSampler::parse(3, "paired, high<=T", 5).unwrap();
+        let mut b = BoardSampler::parse(3, "paired, high<=T", 5).unwrap();
+        let boards = a.sample_n(50).unwrap();
+        assert_eq!(boards, b.sample_n(50).unwrap());
+        for board in &boards {
+            assert_eq!(board.len(), 3);
+            assert!(a.filters().iter().all(|f| f.matches(board)));
+        }
+
+        let mut turns = BoardSampler::parse(4, "monotone,contains=As", 1).unwrap();
+        let turn = turns.sample().unwrap();
+        assert!(turn.contains(&"As".parse().unwrap()) && turn.iter().all(|c| c.suit() == 0));
+    }
+
+    #[test]
+    fn test_sampler_errors() {
+        assert_eq!(BoardSampler::parse(2, "", 0).unwrap_err(), BoardFilterError::InvalidSize(2));
+        let mut impossible = BoardSampler::parse(3, "monotone,contains=As,contains=Kh", 0).unwrap();
+        assert_eq!(impossible.sample(), Err(BoardFilterError::NoMatch));
+    }
+}
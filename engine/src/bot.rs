@@ -0,0 +1,192 @@
+//! Playing from a solution: observed state → tree node → sampled action
+//!
+//! A playing agent (or a trainer dealing hands against the solution) sees
+//! the board and the actions taken so far, not node IDs. `locate` replays
+//! that history from the root: chance nodes take the child that deals the
+//! next observed board card, decision nodes the matching action. Fold,
+//! check, and call match by kind; bets and raises match by size in bb, and
+//! a size the tree doesn't have is handled by `OffTree`: rejected, or
+//! mapped to the nearest bet or raise at that node.
+//!
+//! `Solution::act` then samples the node's stored average strategy. The
+//! scalar solver's strategy is one mix per infoset, so the hole cards only
+//! have to be consistent with the board.
+
+use crate::node::{Action, Card, GameTree, Node, NodeId};
+
+/// What an agent observes when it is asked to act
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GameState {
+    /// Every board card dealt so far, the tree root's cards first
+    pub board: Vec<Card>,
+    /// Both players' actions since the tree root, all streets in order
+    pub actions: Vec<Action>,
+}
+
+impl GameState {
+    pub fn new(board: Vec<Card>) -> Self {
+        GameState { board, actions: Vec::new() }
+    }
+
+    /// The same state with `action` appended
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
+    }
+}
+
+/// What to do with a bet or raise size the tree doesn't have
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OffTree {
+    /// Fail with `ActError::OffTree`
+    Reject,
+    /// Treat it as the bet or raise at that node nearest in size
+    #[default]
+    Nearest,
+}
+
+/// Sizes this close (bb) count as the same bet
+const SIZE_EPSILON: f64 = 1e-6;
+
+/// Error returned when an observed state can't be played from the tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActError {
+    /// The state's board doesn't follow the tree's root and runouts
+    BoardMismatch(String),
+    /// An observed action that the node doesn't offer
+    OffTree { node: NodeId, action: Action },
+    /// The history ends the hand (or the tree) before anyone is to act
+    HandOver(NodeId),
+    /// A hole card is on the board or repeated
+    CardConflict(Card),
+    /// The node's strategy was left out of a partial solution
+    Omitted(NodeId),
+}
+
+impl std::fmt::Display for ActError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActError::BoardMismatch(m) => write!(f, "board does not match the tree: {}", m),
+            ActError::OffTree { node, action } => write!(f, "action {} is not in the tree at node {}", action, node),
+            ActError::HandOver(node) => write!(f, "no decision to make: the hand ended at node {}", node),
+            ActError::CardConflict(card) => write!(f, "hole card {} conflicts with the board", card),
+            ActError::Omitted(node) => write!(f, "node {} was left out of this partial solution", node),
+        }
+    }
+}
+
+impl std::error::Error for ActError {}
+
+/// Node reached by replaying `state` from the root; always a decision node
+pub fn locate(tree: &GameTree, state: &GameState, off_tree: OffTree) -> Result<NodeId, ActError> {
+    let root = tree.get(0).ok_or_else(|| ActError::BoardMismatch("empty tree".to_string()))?;
+    let root_board = root.board();
+    if state.board.len() < root_board.len() || root_board.iter().any(|c| !state.board[..root_board.len()].contains(c)) {
+        return Err(ActError::BoardMismatch(format!("state does not start with the root board ({} cards)", root_board.len())));
+    }
+    let mut id: NodeId = 0;
+    let mut actions = state.actions.iter();
+    loop {
+        match tree.get(id).expect("invalid node id") {
+            Node::Chance { children, board, .. } => {
+                let card = *state.board.get(board.len())
+                    .ok_or_else(|| ActError::BoardMismatch(format!("node {} deals a card the state doesn't have", id)))?;
+                id = *children.iter()
+                    .find(|&&c| tree.get(c).and_then(|n| n.board().last()) == Some(&card))
+                    .ok_or_else(|| ActError::BoardMismatch(format!("node {} never deals {}", id, card)))?;
+            }
+            Node::Decision { children, actions: options, board, .. } => {
+                let Some(&action) = actions.next() else {
+                    if board.len() != state.board.len() {
+                        return Err(ActError::BoardMismatch(format!("{} board cards at node {}, {} observed", board.len(), id, state.board.len())));
+                    }
+                    return Ok(id);
+                };
+                let a = match_action(options, action, off_tree).ok_or(ActError::OffTree { node: id, action })?;
+                id = children[a];
+            }
+            Node::Terminal { .. } => return Err(ActError::HandOver(id)),
+        }
+    }
+}
+
+/// Index of the node's action matching `observed`
+pub(crate) fn match_action(options: &[Action], observed: Action, off_tree: OffTree) -> Option<usize> {
+    let size = |a: &Action| match a {
+        Action::Bet { size } | Action::Raise { size } => Some(*size),
+        _ => None,
+    };
+    let Some(target) = size(&observed) else {
+        return options.iter().position(|&a| a == observed);
+    };
+    let (best, distance) = options.iter().enumerate()
+        .filter_map(|(i, a)| size(a).map(|s| (i, (s - target).abs())))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    (distance <= SIZE_EPSILON || off_tree == OffTree::Nearest).then_some(best)
+}
+
+/// Hole cards must be two distinct cards off the board
+pub(crate) fn check_hole_cards(board: &[Card], hole_cards: [Card; 2]) -> Result<(), ActError> {
+    if hole_cards[0] == hole_cards[1] {
+        return Err(ActError::CardConflict(hole_cards[1]));
+    }
+    match hole_cards.iter().find(|c| board.contains(c)) {
+        Some(&card) => Err(ActError::CardConflict(card)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+
+    fn turn_tree() -> GameTree {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 1;
+        build_tree(&config)
+    }
+
+    #[test]
+    fn test_locate_replays_actions_and_cards() {
+        let tree = turn_tree();
+        let turn = parse_cards("AsKh7d2c").unwrap();
+        assert_eq!(locate(&tree, &GameState::new(turn.clone()), OffTree::Reject), Ok(0));
+
+        // OOP bets half pot; IP facing it is the node under the root's bet
+        let bet = tree.get(0).unwrap().children()[1];
+        let facing = GameState::new(turn.clone()).with_action(Action::Bet { size: 2.75 });
+        assert_eq!(locate(&tree, &facing, OffTree::Reject), Ok(bet));
+
+        // An off-tree 3bb bet maps to the 2.75bb one, or is rejected
+        let off = GameState::new(turn.clone()).with_action(Action::Bet { size: 3.0 });
+        assert_eq!(locate(&tree, &off, OffTree::Nearest), Ok(bet));
+        assert!(matches!(locate(&tree, &off, OffTree::Reject), Err(ActError::OffTree { node: 0, .. })));
+
+        // Check-check deals the river: the state must carry the card
+        let checked = GameState::new(turn.clone()).with_action(Action::Check).with_action(Action::Check);
+        assert!(matches!(locate(&tree, &checked, OffTree::Reject), Err(ActError::BoardMismatch(_))));
+        let mut river = checked.clone();
+        river.board.push(parse_cards("9s").unwrap()[0]);
+        let node = locate(&tree, &river, OffTree::Reject).unwrap();
+        assert_eq!(tree.get(node).unwrap().board(), &river.board[..]);
+
+        let folded = facing.with_action(Action::Fold);
+        assert!(matches!(locate(&tree, &folded, OffTree::Reject), Err(ActError::HandOver(_))));
+        assert!(matches!(locate(&tree, &GameState::new(parse_cards("AsKh7d").unwrap()), OffTree::Reject), Err(ActError::BoardMismatch(_))));
+    }
+
+    #[test]
+    fn test_hole_cards_must_avoid_board() {
+        let board = parse_cards("AsKh7d2c").unwrap();
+        let hand = |s: &str| <[Card; 2]>::try_from(parse_cards(s).unwrap()).unwrap();
+        assert_eq!(check_hole_cards(&board, hand("QcJc")), Ok(()));
+        assert_eq!(check_hole_cards(&board, hand("AsQc")), Err(ActError::CardConflict(board[0])));
+        let qc = parse_cards("Qc").unwrap()[0];
+        assert_eq!(check_hole_cards(&board, [qc, qc]), Err(ActError::CardConflict(qc)));
+    }
+}
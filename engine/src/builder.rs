@@ -0,0 +1,1123 @@
+//! Game tree builder: turns a `TreeConfig` into a flat `GameTree`
+//!
+//! Starting from the board in the config (3, 4, or 5 cards), the builder
+//! generates every betting line allowed by the per-street bet sizes and
+//! deals remaining streets through Chance nodes (one child per undealt card).
+//!
+//! A betting round closes when the second player checks or a bet is called.
+//! On the river (or at the depth limit) that's a showdown terminal;
+//! otherwise a Chance node deals the next street, carrying the pot and the
+//! stacks behind, and the new street starts with OOP to act and nothing bet.
+//! Once a player has no chips behind, nobody can act again: the remaining
+//! streets are dealt Chance node after Chance node down to the showdown,
+//! with no decisions in between.
+//!
+//! Nodes are emitted in depth-first order, so every parent precedes its
+//! children and `tree.nodes[id].id() == id`. Each decision node gets its own
+//! infoset, numbered densely in the same order (see `infoset`).
+//!
+//! Sizing conventions (all amounts in big blinds):
+//! - Bets are fractions of the pot before the bet.
+//! - Raises are fractions of the pot after calling; `Raise { size }` records
+//!   the raise-to total for the street.
+//! - Any size that would exceed the effective stack becomes an all-in.
+
+use crate::deck::Deck;
+use crate::game::{player_index, GameConfig, GameConfigError};
+use crate::infoset::{InfosetKey, InfosetTable};
+use crate::isomorphism::runout_classes;
+use crate::node::{Action, Card, GameTree, InfosetId, Node, NodeId, Player, Street};
+
+/// Bet sizing options for one street
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreetSizes {
+    /// Opening bet sizes as pot fractions (e.g. `[0.75, 1.75]`; above 1.0 is
+    /// an overbet)
+    pub bet_sizes: Vec<f64>,
+    /// Raise sizes as fractions of the pot after calling; each one becomes a
+    /// separate child
+    pub raise_sizes: Vec<f64>,
+    /// Add an all-in option at every decision where chips remain
+    pub allow_all_in: bool,
+    /// Let OOP lead (donk) into the previous street's aggressor
+    pub allow_donk: bool,
+    /// Sizes for donk bets; `None` uses `bet_sizes`
+    pub donk_sizes: Option<Vec<f64>>,
+}
+
+impl StreetSizes {
+    /// A street with the given bet sizes, pot-sized raises, no extra all-in,
+    /// and donk bets allowed at the same sizes
+    pub fn new(bet_sizes: Vec<f64>) -> Self {
+        StreetSizes { bet_sizes, raise_sizes: vec![1.0], allow_all_in: false, allow_donk: true, donk_sizes: None }
+    }
+
+    /// Sizes OOP may lead with when donking
+    pub fn donk_bet_sizes(&self) -> &[f64] {
+        self.donk_sizes.as_deref().unwrap_or(&self.bet_sizes)
+    }
+}
+
+/// How bet and raise amounts are chosen
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BettingStructure {
+    /// Pot-fraction sizes from the per-street `StreetSizes`
+    NoLimit,
+    /// Limit hold'em: every bet or raise is one fixed increment, `small_bet`
+    /// on the flop and `big_bet` on the turn and river, with at most `cap`
+    /// bets + raises per street
+    FixedLimit { small_bet: f64, big_bet: f64, cap: usize },
+}
+
+impl BettingStructure {
+    /// Standard limit structure: big bet is twice the small bet, 4-bet cap
+    pub fn fixed_limit(small_bet: f64) -> Self {
+        BettingStructure::FixedLimit { small_bet, big_bet: 2.0 * small_bet, cap: 4 }
+    }
+}
+
+/// Error returned by `TreeConfig::validate`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeConfigError {
+    /// Board has the wrong number of cards or repeats a card
+    InvalidBoard(String),
+    /// A bet or raise size is not positive
+    InvalidSize(f64),
+    /// A limit structure allows no bets
+    InvalidCap(usize),
+    /// The underlying game configuration is inconsistent
+    Game(GameConfigError),
+}
+
+impl std::fmt::Display for TreeConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeConfigError::InvalidBoard(m) => write!(f, "invalid board: {}", m),
+            TreeConfigError::InvalidSize(s) => write!(f, "invalid bet size {}", s),
+            TreeConfigError::InvalidCap(c) => write!(f, "invalid raise cap {}", c),
+            TreeConfigError::Game(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TreeConfigError {}
+
+impl From<GameConfigError> for TreeConfigError {
+    fn from(e: GameConfigError) -> Self {
+        TreeConfigError::Game(e)
+    }
+}
+
+/// Everything needed to generate a postflop tree
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeConfig {
+    /// Blinds, antes, straddle, and preflop result (starting pot and stacks)
+    pub game: GameConfig,
+    /// Board at the root (3 = flop, 4 = turn, 5 = river)
+    pub board: Vec<Card>,
+    /// Sizes used on the flop
+    pub flop: StreetSizes,
+    /// Sizes used on the turn
+    pub turn: StreetSizes,
+    /// Sizes used on the river
+    pub river: StreetSizes,
+    /// Maximum bets + raises per street (no-limit; limit uses its own cap)
+    pub max_raises: usize,
+    /// No-limit sizing or fixed-limit increments
+    pub structure: BettingStructure,
+    /// Last bettor before the root street (the preflop raiser for flop trees);
+    /// an OOP lead into this player is a donk bet
+    pub initial_aggressor: Option<Player>,
+    /// Deal one weighted child per suit-isomorphic runout class instead of
+    /// one per card (see `isomorphism`). Terminal equities are then taken on
+    /// each class's representative board.
+    pub isomorphic_runouts: bool,
+    /// Last street to build. A round that closes on it without a fold ends
+    /// in a leaf: a showdown terminal on the short board, valued by a
+    /// `leaf::LeafEvaluator` instead of dealing the next street.
+    pub depth_limit: Option<Street>,
+}
+
+impl TreeConfig {
+    /// Default tree for a board: 33% and 75% bets, pot raises, 3 bets per street
+    pub fn new(game: GameConfig, board: Vec<Card>) -> Self {
+        TreeConfig {
+            game,
+            board,
+            flop: StreetSizes::new(vec![0.33, 0.75]),
+            turn: StreetSizes::new(vec![0.75]),
+            river: StreetSizes::new(vec![0.75]),
+            max_raises: 3,
+            structure: BettingStructure::NoLimit,
+            initial_aggressor: Some(Player::IP),
+            isomorphic_runouts: false,
+            depth_limit: None,
+        }
+    }
+
+    /// Limit tree for a board with standard small/big bets and a 4-bet cap
+    pub fn fixed_limit(game: GameConfig, board: Vec<Card>, small_bet: f64) -> Self {
+        TreeConfig { structure: BettingStructure::fixed_limit(small_bet), ..TreeConfig::new(game, board) }
+    }
+
+    /// Maximum bets + raises on any street under the configured structure
+    pub fn raise_cap(&self) -> usize {
+        match self.structure {
+            BettingStructure::NoLimit => self.max_raises,
+            BettingStructure::FixedLimit { cap, .. } => cap,
+        }
+    }
+
+    /// Fixed bet increment on a street (`None` for no-limit)
+    pub fn limit_unit(&self, street: Street) -> Option<f64> {
+        match self.structure {
+            BettingStructure::NoLimit => None,
+            BettingStructure::FixedLimit { small_bet, big_bet, .. } => Some(match street {
+                Street::Flop => small_bet,
+                Street::Turn | Street::River => big_bet,
+            }),
+        }
+    }
+
+    /// Sizes for a street
+    pub fn sizes(&self, street: Street) -> &StreetSizes {
+        match street {
+            Street::Flop => &self.flop,
+            Street::Turn => &self.turn,
+            Street::River => &self.river,
+        }
+    }
+
+    /// Mutable sizes for a street
+    pub fn sizes_mut(&mut self, street: Street) -> &mut StreetSizes {
+        match street {
+            Street::Flop => &mut self.flop,
+            Street::Turn => &mut self.turn,
+            Street::River => &mut self.river,
+        }
+    }
+
+    /// Whether the tree deals and plays `street` (false past `depth_limit`)
+    pub fn deals(&self, street: Street) -> bool {
+        self.depth_limit.is_none_or(|last| street <= last)
+    }
+
+    /// Street at the root, derived from the board length
+    pub fn root_street(&self) -> Option<Street> {
+        match self.board.len() {
+            3 => Some(Street::Flop),
+            4 => Some(Street::Turn),
+            5 => Some(Street::River),
+            _ => None,
+        }
+    }
+
+    /// Check board, sizes, and game structure
+    pub fn validate(&self) -> Result<(), TreeConfigError> {
+        if self.root_street().is_none() {
+            return Err(TreeConfigError::InvalidBoard(format!("expected 3-5 cards, got {}", self.board.len())));
+        }
+        let mask = self.board.iter().fold(0u64, |m, c| m | c.mask());
+        if mask.count_ones() as usize != self.board.len() {
+            return Err(TreeConfigError::InvalidBoard("duplicate card".into()));
+        }
+        for sizes in [&self.flop, &self.turn, &self.river] {
+            let donk = sizes.donk_sizes.iter().flatten();
+            for &s in sizes.bet_sizes.iter().chain(donk).chain(&sizes.raise_sizes) {
+                if s.is_nan() || s <= 0.0 {
+                    return Err(TreeConfigError::InvalidSize(s));
+                }
+            }
+        }
+        if let BettingStructure::FixedLimit { small_bet, big_bet, cap } = self.structure {
+            for s in [small_bet, big_bet] {
+                if s.is_nan() || s <= 0.0 {
+                    return Err(TreeConfigError::InvalidSize(s));
+                }
+            }
+            if cap == 0 {
+                return Err(TreeConfigError::InvalidCap(cap));
+            }
+        }
+        self.game.validate()?;
+        Ok(())
+    }
+}
+
+/// Build the full game tree described by `config`.
+///
+/// Panics if the config does not validate; call `TreeConfig::validate` first
+/// to get a descriptive error.
+pub fn build_tree(config: &TreeConfig) -> GameTree {
+    if let Err(e) = config.validate() {
+        panic!("invalid tree config: {}", e);
+    }
+    let mut builder = Builder { config, nodes: Vec::new(), infosets: InfosetTable::new() };
+    builder.build(BettingState::root(config), None);
+    GameTree { nodes: builder.nodes }
+}
+
+/// Tolerance for comparing chip amounts
+const CHIP_EPS: f64 = 1e-9;
+
+/// Betting state at a node while the tree is generated
+#[derive(Debug, Clone)]
+pub(crate) struct BettingState {
+    street: Street,
+    /// Total pot including bets made on the current street
+    pot: f64,
+    /// Chips behind, `[IP, OOP]`
+    stacks: [f64; 2],
+    /// Chips put in on the current street, `[IP, OOP]`
+    street_bets: [f64; 2],
+    to_act: Player,
+    /// Bets + raises made on the current street
+    raises: usize,
+    /// Increment of the last bet or raise on the current street
+    last_raise: f64,
+    /// Last bettor on the previous street (or before the root)
+    aggressor: Option<Player>,
+    /// Last bettor on the current street
+    street_aggressor: Option<Player>,
+    board: Vec<Card>,
+    history: Vec<Action>,
+}
+
+impl BettingState {
+    /// State at the root of a (validated) config
+    pub(crate) fn root(config: &TreeConfig) -> Self {
+        BettingState {
+            street: config.root_street().expect("validated"),
+            pot: config.game.initial_pot(),
+            stacks: config.game.postflop_stacks(),
+            street_bets: [0.0, 0.0],
+            to_act: Player::OOP,
+            raises: 0,
+            last_raise: 0.0,
+            aggressor: config.initial_aggressor,
+            street_aggressor: None,
+            board: config.board.clone(),
+            history: Vec::new(),
+        }
+    }
+
+    pub(crate) fn street(&self) -> Street {
+        self.street
+    }
+
+    pub(crate) fn pot(&self) -> f64 {
+        self.pot
+    }
+
+    pub(crate) fn stacks(&self) -> [f64; 2] {
+        self.stacks
+    }
+
+    pub(crate) fn to_act(&self) -> Player {
+        self.to_act
+    }
+
+    /// Street dealt after this one closes (`None` on the river)
+    pub(crate) fn next_street(&self) -> Option<Street> {
+        match self.street {
+            Street::Flop => Some(Street::Turn),
+            Street::Turn => Some(Street::River),
+            Street::River => None,
+        }
+    }
+
+    /// Whether a player has no chips behind, so betting is over for the hand
+    pub(crate) fn is_all_in(&self) -> bool {
+        self.stacks.iter().any(|&s| s <= CHIP_EPS)
+    }
+
+    /// Cards that can still be dealt, in card order
+    pub(crate) fn undealt(&self) -> impl Iterator<Item = Card> {
+        Deck::without(&self.board).iter()
+    }
+
+    /// Cards a Chance node after this state deals, with their probabilities
+    /// (see `runouts`)
+    pub(crate) fn runouts(&self, isomorphic: bool) -> (Vec<Card>, Vec<f64>) {
+        runouts(&self.board, isomorphic)
+    }
+
+    /// State at the start of the next street after `card` is dealt
+    pub(crate) fn deal(&self, street: Street, card: Card) -> BettingState {
+        let mut next = self.clone();
+        next.street = street;
+        next.street_bets = [0.0, 0.0];
+        next.to_act = Player::OOP;
+        next.raises = 0;
+        next.last_raise = 0.0;
+        next.aggressor = self.street_aggressor;
+        next.street_aggressor = None;
+        next.board.push(card);
+        next
+    }
+
+    pub(crate) fn infoset_key(&self) -> InfosetKey {
+        InfosetKey::new(self.to_act, self.history.clone(), self.board.clone(), 0)
+    }
+
+    pub(crate) fn decision_node(
+        &self,
+        id: NodeId,
+        infoset_id: InfosetId,
+        parent: Option<NodeId>,
+        children: Vec<NodeId>,
+        actions: Vec<Action>,
+    ) -> Node {
+        Node::Decision {
+            id,
+            infoset_id,
+            player: self.to_act,
+            street: self.street,
+            parent,
+            children,
+            actions,
+            pot: self.pot,
+            stacks: self.stacks,
+            board: self.board.clone(),
+            bet_sequence: self.history.clone(),
+        }
+    }
+
+    pub(crate) fn chance_node(&self, id: NodeId, parent: NodeId, children: Vec<NodeId>, weights: Vec<f64>) -> Node {
+        Node::Chance {
+            id,
+            parent: Some(parent),
+            children,
+            weights,
+            street: self.street,
+            pot: self.pot,
+            stacks: self.stacks,
+            board: self.board.clone(),
+        }
+    }
+
+    pub(crate) fn terminal_node(&self, id: NodeId, parent: Option<NodeId>, folder: Option<Player>) -> Node {
+        Node::Terminal {
+            id,
+            parent,
+            folder,
+            pot: self.pot,
+            stacks: self.stacks,
+            board: self.board.clone(),
+            hole_cards: [None, None],
+        }
+    }
+
+    /// Amount the player to act must add to call
+    fn to_call(&self) -> f64 {
+        let me = player_index(self.to_act);
+        let opp = player_index(self.to_act.opponent());
+        (self.street_bets[opp] - self.street_bets[me]).max(0.0)
+    }
+
+    /// Largest street total the player to act can usefully reach
+    /// (capped by their own stack and by what the opponent can match)
+    fn max_street_total(&self) -> f64 {
+        let me = player_index(self.to_act);
+        let opp = player_index(self.to_act.opponent());
+        (self.street_bets[me] + self.stacks[me]).min(self.street_bets[opp] + self.stacks[opp])
+    }
+
+    /// State after the player to act puts `amount` more chips in
+    fn put_in(&self, amount: f64, action: Action) -> BettingState {
+        let me = player_index(self.to_act);
+        let mut next = self.clone();
+        next.stacks[me] -= amount;
+        next.street_bets[me] += amount;
+        next.pot += amount;
+        next.history.push(action);
+        next
+    }
+}
+
+struct Builder<'a> {
+    config: &'a TreeConfig,
+    nodes: Vec<Node>,
+    infosets: InfosetTable,
+}
+
+impl<'a> Builder<'a> {
+    /// Emit the node for `state` and its whole subtree; returns its id.
+    fn build(&mut self, state: BettingState, parent: Option<NodeId>) -> NodeId {
+        let id = self.reserve();
+        let options = legal_actions(self.config, &state);
+        let infoset_id = self.infosets.intern(state.infoset_key(), options.len());
+
+        let mut children = Vec::with_capacity(options.len());
+        let mut actions = Vec::with_capacity(options.len());
+        for (action, next) in options {
+            let child = match next {
+                Next::Decision(s) => self.build(s, Some(id)),
+                Next::Fold(s, folder) => self.terminal(&s, Some(id), Some(folder)),
+                Next::RoundClosed(s) => self.close_round(s, id),
+            };
+            actions.push(action);
+            children.push(child);
+        }
+
+        self.nodes[id as usize] = state.decision_node(id, infoset_id, parent, children, actions);
+        id
+    }
+
+    /// Round closed by a check-back or call: showdown on the river (or a
+    /// leaf at the depth limit), otherwise a Chance node dealing the next street.
+    fn close_round(&mut self, state: BettingState, parent: NodeId) -> NodeId {
+        let Some(next_street) = state.next_street().filter(|&s| self.config.deals(s)) else {
+            return self.terminal(&state, Some(parent), None);
+        };
+
+        let id = self.reserve();
+        let (cards, weights) = state.runouts(self.config.isomorphic_runouts);
+        let mut children = Vec::with_capacity(cards.len());
+        for card in cards {
+            children.push(self.open_street(state.deal(next_street, card), id));
+        }
+
+        self.nodes[id as usize] = state.chance_node(id, parent, children, weights);
+        id
+    }
+
+    /// Start of a street just dealt: its first decision, or with a player
+    /// all-in, straight on to the next deal or the showdown
+    fn open_street(&mut self, state: BettingState, parent: NodeId) -> NodeId {
+        if state.is_all_in() {
+            self.close_round(state, parent)
+        } else {
+            self.build(state, Some(parent))
+        }
+    }
+
+    fn terminal(&mut self, state: &BettingState, parent: Option<NodeId>, folder: Option<Player>) -> NodeId {
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(state.terminal_node(id, parent, folder));
+        id
+    }
+
+    /// Push a placeholder so children get higher ids than their parent
+    fn reserve(&mut self) -> NodeId {
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node::Terminal {
+            id,
+            parent: None,
+            folder: None,
+            pot: 0.0,
+            stacks: [0.0, 0.0],
+            board: Vec::new(),
+            hole_cards: [None, None],
+        });
+        id
+    }
+}
+
+/// Cards a Chance node deals after `board` and each one's probability.
+///
+/// Without grouping that's every undealt card, equally likely (weights
+/// empty). With `isomorphic` it's one representative per runout class,
+/// weighted by class size; weights stay empty when every class is a single
+/// card.
+pub(crate) fn runouts(board: &[Card], isomorphic: bool) -> (Vec<Card>, Vec<f64>) {
+    if isomorphic {
+        let classes = runout_classes(board);
+        let total: usize = classes.iter().map(|&(_, n)| n).sum();
+        if classes.len() < total {
+            return classes.into_iter().map(|(card, n)| (card, n as f64 / total as f64)).unzip();
+        }
+    }
+    (Deck::without(board).iter().collect(), Vec::new())
+}
+
+/// Legal actions at `state` and the state each one leads to
+pub(crate) fn legal_actions(config: &TreeConfig, state: &BettingState) -> Vec<(Action, Next)> {
+    let sizes = config.sizes(state.street);
+    let unit = config.limit_unit(state.street);
+    let cap = config.raise_cap();
+    let me = player_index(state.to_act);
+    let to_call = state.to_call();
+    let max_total = state.max_street_total();
+    let mut out = Vec::new();
+
+    if to_call <= CHIP_EPS {
+        // Unopened (or checked to): check, or open with each size
+        let check = state.put_in(0.0, Action::Check);
+        if state.to_act == Player::OOP {
+            let mut next = check;
+            next.to_act = Player::IP;
+            out.push((Action::Check, Next::Decision(next)));
+        } else {
+            out.push((Action::Check, Next::RoundClosed(check)));
+        }
+
+        // OOP opening the street into last street's aggressor
+        let donk = state.to_act == Player::OOP && state.aggressor == Some(Player::IP);
+        let may_bet = !donk || sizes.allow_donk;
+        if may_bet && state.raises < cap && max_total > CHIP_EPS {
+            let amounts = match unit {
+                Some(unit) => vec![unit.min(max_total)],
+                None => {
+                    let fracs = if donk { sizes.donk_bet_sizes() } else { &sizes.bet_sizes };
+                    // Minimum bet is one big blind (or all-in for less)
+                    let min_bet = config.game.big_blind;
+                    let mut amounts: Vec<f64> = fracs.iter()
+                        .map(|&frac| (frac * state.pot).max(min_bet).min(max_total))
+                        .collect();
+                    if sizes.allow_all_in {
+                        amounts.push(max_total);
+                    }
+                    amounts
+                }
+            };
+            for amount in dedup_amounts(amounts) {
+                let mut next = state.put_in(amount, Action::Bet { size: amount });
+                next.to_act = state.to_act.opponent();
+                next.raises += 1;
+                next.last_raise = amount;
+                next.street_aggressor = Some(state.to_act);
+                out.push((Action::Bet { size: amount }, Next::Decision(next)));
+            }
+        }
+    } else {
+        // Facing a bet: fold, call, or raise
+        out.push((Action::Fold, Next::Fold(state.clone(), state.to_act)));
+
+        let call_amount = to_call.min(state.stacks[me]);
+        out.push((Action::Call, Next::RoundClosed(state.put_in(call_amount, Action::Call))));
+
+        let can_raise = state.raises < cap
+            && max_total > state.street_bets[player_index(state.to_act.opponent())] + CHIP_EPS;
+        if can_raise {
+            let opp_total = state.street_bets[player_index(state.to_act.opponent())];
+            let pot_after_call = state.pot + to_call;
+            let totals = match unit {
+                Some(unit) => vec![(opp_total + unit).min(max_total)],
+                None => {
+                    // A raise must add at least the previous increment (and
+                    // one big blind); only an all-in may fall short
+                    let min_total = opp_total + state.last_raise.max(config.game.big_blind);
+                    let mut totals: Vec<f64> = sizes.raise_sizes.iter()
+                        .map(|&frac| (opp_total + frac * pot_after_call).max(min_total).min(max_total))
+                        .collect();
+                    if sizes.allow_all_in {
+                        totals.push(max_total);
+                    }
+                    totals
+                }
+            };
+            for total in dedup_amounts(totals) {
+                let amount = total - state.street_bets[me];
+                let mut next = state.put_in(amount, Action::Raise { size: total });
+                next.to_act = state.to_act.opponent();
+                next.raises += 1;
+                next.last_raise = total - opp_total;
+                next.street_aggressor = Some(state.to_act);
+                out.push((Action::Raise { size: total }, Next::Decision(next)));
+            }
+        }
+    }
+    out
+}
+
+/// Where an action leads
+pub(crate) enum Next {
+    /// Opponent acts next
+    Decision(BettingState),
+    /// Player folded
+    Fold(BettingState, Player),
+    /// Betting round is over
+    RoundClosed(BettingState),
+}
+
+/// Sort ascending and drop amounts within `CHIP_EPS` of each other
+fn dedup_amounts(mut amounts: Vec<f64>) -> Vec<f64> {
+    amounts.sort_by(|a, b| a.partial_cmp(b).expect("finite amounts"));
+    amounts.dedup_by(|a, b| (*a - *b).abs() < CHIP_EPS);
+    amounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{build_terminal_evs, PostedBlind};
+    use crate::node::parse_cards;
+
+    fn river_config(max_raises: usize) -> TreeConfig {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c9s").unwrap());
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = max_raises;
+        config
+    }
+
+    #[test]
+    fn test_river_tree_shape() {
+        let tree = build_tree(&river_config(2));
+        // 6 decisions (root, x, xb, xbr, b, br) + 9 terminals
+        assert_eq!(tree.len(), 15);
+        assert_eq!(tree.nodes.iter().filter(|n| n.is_decision()).count(), 6);
+        match tree.get(0).unwrap() {
+            Node::Decision { player, actions, pot, .. } => {
+                assert_eq!(*player, Player::OOP);
+                assert_eq!(actions, &vec![Action::Check, Action::Bet { size: 2.75 }]);
+                assert_eq!(*pot, 5.5);
+            }
+            _ => panic!("root should be a decision"),
+        }
+    }
+
+    #[test]
+    fn test_ids_parents_and_children_consistent() {
+        let tree = build_tree(&river_config(3));
+        for (idx, node) in tree.nodes.iter().enumerate() {
+            assert_eq!(node.id() as usize, idx);
+            for &child in node.children() {
+                assert!(child as usize > idx, "children must follow parents");
+                assert_eq!(tree.get(child).unwrap().parent(), Some(node.id()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_raise_sizes_are_pot_after_call() {
+        let tree = build_tree(&river_config(2));
+        // Root bet 2.75 into 5.5; IP pot raise = 2.75 + (5.5 + 2.75 + 2.75) = 13.75
+        let ip_facing = tree.get(0).unwrap().children()[1];
+        match tree.get(ip_facing).unwrap() {
+            Node::Decision { actions, .. } => {
+                assert_eq!(actions, &vec![Action::Fold, Action::Call, Action::Raise { size: 13.75 }]);
+            }
+            _ => panic!("expected IP decision"),
+        }
+    }
+
+    #[test]
+    fn test_bets_capped_at_all_in() {
+        let mut config = river_config(3);
+        config.game.starting_stack = [5.0, 5.0]; // 2.5 behind after preflop
+        config.river = StreetSizes::new(vec![0.5, 2.0]);
+        let tree = build_tree(&config);
+        match tree.get(0).unwrap() {
+            Node::Decision { actions, .. } => {
+                // 0.5 pot = 2.75 and 2.0 pot both exceed 2.5 behind → single all-in
+                assert_eq!(actions, &vec![Action::Check, Action::Bet { size: 2.5 }]);
+            }
+            _ => panic!("root should be a decision"),
+        }
+        for node in &tree.nodes {
+            if let Node::Terminal { stacks, .. } = node {
+                assert!(stacks.iter().all(|&s| s >= -CHIP_EPS));
+            }
+        }
+    }
+
+    #[test]
+    fn test_shallow_vs_deep_caps_at_effective_stack() {
+        let mut config = river_config(3);
+        config.game.starting_stack = [22.5, 200.0]; // IP 20 behind, OOP 197.5
+        config.river = StreetSizes { allow_all_in: true, ..StreetSizes::new(vec![0.75, 5.0]) };
+        let tree = build_tree(&config);
+        match tree.get(0).unwrap() {
+            Node::Decision { actions, stacks, .. } => {
+                assert_eq!(*stacks, [20.0, 197.5]);
+                // 0.75 pot = 4.125; 5x pot and the deep player's shove both cap at 20
+                assert_eq!(actions, &vec![Action::Check, Action::Bet { size: 4.125 }, Action::Bet { size: 20.0 }]);
+            }
+            _ => panic!("root should be a decision"),
+        }
+
+        let evs = build_terminal_evs(&tree, |_| 0.5);
+        for node in &tree.nodes {
+            if let Node::Terminal { id, stacks, .. } = node {
+                // Nobody risks more than the short stack
+                assert!(stacks[0] >= -CHIP_EPS);
+                assert!(stacks[1] >= 197.5 - 20.0 - CHIP_EPS);
+                // IP never wins more than pot + short stack or loses more than the short stack
+                let ip = evs[id];
+                assert!((-20.0 - CHIP_EPS..=5.5 + 20.0 + CHIP_EPS).contains(&ip), "ev {}", ip);
+            }
+            if let Node::Decision { actions, .. } = node {
+                for a in actions {
+                    if let Action::Bet { size } | Action::Raise { size } = a {
+                        assert!(*size <= 20.0 + CHIP_EPS, "{} exceeds effective stack", a);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_legal_actions_match_amount_to_call() {
+        let mut config = TreeConfig::new(GameConfig::from_pot(10.0, [60.0, 35.0]), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes { allow_all_in: true, ..StreetSizes::new(vec![0.5]) };
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 3;
+        let tree = build_tree(&config);
+        let mut facing = 0;
+        for node in &tree.nodes {
+            let Node::Decision { player, actions, children, stacks, .. } = node else { continue };
+            let to_call = node.amount_to_call();
+            if to_call <= CHIP_EPS {
+                assert_eq!(actions[0], Action::Check);
+                continue;
+            }
+            facing += 1;
+            assert_eq!(&actions[..2], &[Action::Fold, Action::Call]);
+            // Calling costs exactly the amount to call
+            let me = player_index(*player);
+            let after_call = tree.get(children[1]).unwrap();
+            assert!((stacks[me] - after_call.stacks()[me] - to_call).abs() < CHIP_EPS, "node {}", node.id());
+            assert!(node.pot_odds() > 0.0 && node.pot_odds() < 0.5);
+        }
+        assert!(facing > 0);
+    }
+
+    #[test]
+    fn test_turn_tree_deals_rivers() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 1;
+        let tree = build_tree(&config);
+        let chance: Vec<&Node> = tree.nodes.iter().filter(|n| n.is_chance()).collect();
+        // Turn closes via x-x or b-c (two lines per opening side) → 3 chance nodes
+        assert_eq!(chance.len(), 3);
+        for c in chance {
+            assert_eq!(c.children().len(), 48);
+            for &child in c.children() {
+                assert_eq!(tree.get(child).unwrap().board().len(), 5);
+                assert_eq!(tree.get(child).unwrap().street(), Some(Street::River));
+            }
+        }
+    }
+
+    #[test]
+    fn test_depth_limit_ends_in_leaves() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.depth_limit = Some(Street::Turn);
+        let tree = build_tree(&config);
+        assert!(!config.deals(Street::River) && config.deals(Street::Turn));
+        assert!(tree.nodes.iter().all(|n| !n.is_chance()));
+        let leaves = tree.nodes.iter()
+            .filter(|n| matches!(n, Node::Terminal { folder: None, .. }))
+            .inspect(|n| assert_eq!(n.board().len(), 4))
+            .count();
+        assert!(leaves > 0);
+        config.depth_limit = None;
+        assert!(build_tree(&config).len() > tree.len());
+    }
+
+    #[test]
+    fn test_isomorphic_runouts_group_river_cards() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKs7s2h").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 1;
+        config.isomorphic_runouts = true;
+        let tree = build_tree(&config);
+        for node in tree.nodes.iter().filter(|n| n.is_chance()) {
+            // 10 spades + 12 hearts singly, diamond/club pairs by rank
+            assert_eq!(node.children().len(), 35);
+            let total: f64 = (0..35).map(|i| node.chance_probability(i)).sum();
+            assert!((total - 1.0).abs() < 1e-12);
+            let deuce = node.children().iter().position(|&c| tree.get(c).unwrap().board()[4] == parse_cards("2d").unwrap()[0]);
+            assert_eq!(deuce.map(|i| node.chance_probability(i)), Some(2.0 / 48.0));
+        }
+
+        // A rainbow board has no symmetry left to exploit
+        config.board = parse_cards("AsKh7d2c").unwrap();
+        let grouped = build_tree(&config);
+        config.isomorphic_runouts = false;
+        assert_eq!(grouped, build_tree(&config));
+    }
+
+    #[test]
+    fn test_ante_and_straddle_flow_into_pot_and_terminal_evs() {
+        let mut game = GameConfig::new();
+        game.ante = 0.25;
+        game.straddle = Some(2.0);
+        game.preflop_contribution = 6.0;
+        game.posted = [PostedBlind::None, PostedBlind::Big];
+        let mut config = TreeConfig::new(game, parse_cards("AsKh7d2c9s").unwrap());
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 1;
+        // 12 live + 1.5 antes + SB 0.5 + straddle 2.0 dead
+        let expected_pot = 16.0;
+        let tree = build_tree(&config);
+        match tree.get(0).unwrap() {
+            Node::Decision { pot, stacks, .. } => {
+                assert_eq!(*pot, expected_pot);
+                assert_eq!(*stacks, [93.75, 93.75]);
+            }
+            _ => panic!("root should be a decision"),
+        }
+
+        let evs = build_terminal_evs(&tree, |_| 0.5);
+        for node in &tree.nodes {
+            if let Node::Terminal { id, folder, .. } = node {
+                let ev = evs[id];
+                match folder {
+                    // Whoever folds to a bet leaves the dead money to the bettor
+                    Some(Player::OOP) => assert_eq!(ev, expected_pot),
+                    Some(Player::IP) => assert_eq!(ev, 0.0),
+                    // Chops split dead money evenly
+                    None => assert_eq!(ev, expected_pot / 2.0),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixed_limit_increments_and_cap() {
+        let config = TreeConfig::fixed_limit(GameConfig::new(), parse_cards("AsKh7d2c").unwrap(), 1.0);
+        let tree = build_tree(&config);
+
+        // Turn is a big-bet street: OOP bets 2, then raises go 4, 6, 8 and stop at the cap
+        let mut node = tree.get(0).unwrap();
+        let mut totals = Vec::new();
+        while let Node::Decision { actions, children, street: Street::Turn, .. } = node {
+            let aggressive = actions.iter().position(|a| matches!(a, Action::Bet { .. } | Action::Raise { .. }));
+            match aggressive {
+                Some(i) => {
+                    if let Action::Bet { size } | Action::Raise { size } = actions[i] {
+                        totals.push(size);
+                    }
+                    node = tree.get(children[i]).unwrap();
+                }
+                None => {
+                    assert_eq!(actions, &vec![Action::Fold, Action::Call]);
+                    break;
+                }
+            }
+        }
+        assert_eq!(totals, vec![2.0, 4.0, 6.0, 8.0]);
+
+        // River bets are big bets too, regardless of pot size
+        for n in &tree.nodes {
+            if let Node::Decision { street: Street::River, actions, bet_sequence, .. } = n {
+                if bet_sequence.last() == Some(&Action::Call) || bet_sequence.last() == Some(&Action::Check) {
+                    if let Some(Action::Bet { size }) = actions.get(1) {
+                        assert_eq!(*size, 2.0);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixed_limit_flop_uses_small_bet() {
+        let mut config = TreeConfig::fixed_limit(GameConfig::new(), parse_cards("AsKh7d").unwrap(), 1.0);
+        config.structure = BettingStructure::FixedLimit { small_bet: 1.0, big_bet: 2.0, cap: 1 };
+        // Only check the root; a flop tree would deal every turn and river
+        let options = legal_actions(&config, &BettingState {
+            street: Street::Flop,
+            pot: 5.5,
+            stacks: [97.5, 97.5],
+            street_bets: [0.0, 0.0],
+            to_act: Player::OOP,
+            raises: 0,
+            last_raise: 0.0,
+            aggressor: None,
+            street_aggressor: None,
+            board: config.board.clone(),
+            history: Vec::new(),
+        });
+        let actions: Vec<Action> = options.into_iter().map(|(a, _)| a).collect();
+        assert_eq!(actions, vec![Action::Check, Action::Bet { size: 1.0 }]);
+    }
+
+    #[test]
+    fn test_overbets_and_multiple_raise_sizes() {
+        let mut config = river_config(2);
+        config.river.bet_sizes = vec![0.75, 1.75];
+        config.river.raise_sizes = vec![0.5, 1.0];
+        let tree = build_tree(&config);
+        let root = tree.get(0).unwrap();
+        match root {
+            Node::Decision { actions, .. } => {
+                assert_eq!(actions, &vec![Action::Check, Action::Bet { size: 4.125 }, Action::Bet { size: 9.625 }]);
+            }
+            _ => panic!("root should be a decision"),
+        }
+        // Facing the 4.125 bet into 5.5: pot after call is 13.75
+        match tree.get(root.children()[1]).unwrap() {
+            Node::Decision { actions, .. } => assert_eq!(actions, &vec![
+                Action::Fold,
+                Action::Call,
+                Action::Raise { size: 4.125 + 6.875 },
+                Action::Raise { size: 4.125 + 13.75 },
+            ]),
+            _ => panic!("expected IP decision"),
+        }
+        // root + (x, x-b ×2, x-b-r ×4) + (b ×2, b-r ×4)
+        assert_eq!(tree.nodes.iter().filter(|n| n.is_decision()).count(), 1 + (1 + 2 + 4) + (2 + 4));
+    }
+
+    #[test]
+    fn test_raises_respect_min_raise() {
+        let mut config = river_config(3);
+        config.river.bet_sizes = vec![0.1];
+        // 0.05 pot raise is far below a legal min-raise
+        config.river.raise_sizes = vec![0.05];
+        let tree = build_tree(&config);
+        for node in &tree.nodes {
+            if let Node::Decision { actions, bet_sequence, .. } = node {
+                let mut prev_total = 0.0;
+                let mut prev_increment = 0.0_f64;
+                for a in bet_sequence.iter().chain(actions.iter().filter(|a| matches!(a, Action::Raise { .. }))) {
+                    if let Action::Bet { size } | Action::Raise { size } = *a {
+                        let increment = size - prev_total;
+                        assert!(increment + CHIP_EPS >= prev_increment.max(1.0), "{} after {}", size, prev_total);
+                        prev_increment = increment;
+                        prev_total = size;
+                    }
+                }
+            }
+        }
+        // 0.1 pot bet is 0.55, below the 1bb minimum
+        assert!(matches!(tree.get(0).unwrap(), Node::Decision { actions, .. } if actions[1] == Action::Bet { size: 1.0 }));
+    }
+
+    #[test]
+    fn test_donk_toggle_and_sizes() {
+        let mut config = river_config(1);
+        // Preflop raiser IP: OOP's river lead is a donk
+        config.river.allow_donk = false;
+        let root_actions = |config: &TreeConfig| match build_tree(config).get(0).unwrap() {
+            Node::Decision { actions, .. } => actions.clone(),
+            _ => panic!("root should be a decision"),
+        };
+        assert_eq!(root_actions(&config), vec![Action::Check]);
+
+        // Restricted donk sizes; IP's bets after a check keep the regular sizes
+        config.river.allow_donk = true;
+        config.river.donk_sizes = Some(vec![0.25]);
+        assert_eq!(root_actions(&config), vec![Action::Check, Action::Bet { size: 1.375 }]);
+        let tree = build_tree(&config);
+        match tree.get(tree.get(0).unwrap().children()[0]).unwrap() {
+            Node::Decision { actions, player, .. } => {
+                assert_eq!(*player, Player::IP);
+                assert_eq!(actions, &vec![Action::Check, Action::Bet { size: 2.75 }]);
+            }
+            _ => panic!("expected IP decision"),
+        }
+
+        // OOP was the preflop aggressor: leading is a c-bet, donk settings don't apply
+        config.river.allow_donk = false;
+        config.initial_aggressor = Some(Player::OOP);
+        assert_eq!(root_actions(&config), vec![Action::Check, Action::Bet { size: 2.75 }]);
+    }
+
+    #[test]
+    fn test_aggressor_carries_to_next_street() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes { allow_donk: false, ..StreetSizes::new(vec![0.5]) };
+        config.max_raises = 1;
+        config.initial_aggressor = None;
+        let tree = build_tree(&config);
+        for node in &tree.nodes {
+            let first_to_act = node.parent().is_some_and(|p| tree.get(p).unwrap().is_chance());
+            if let (true, Node::Decision { actions, bet_sequence, .. }) = (first_to_act, node) {
+                let last_turn_bettor = match bet_sequence.as_slice() {
+                    // x-b-c: IP bet the turn, so OOP's river lead is a donk
+                    [Action::Check, Action::Bet { .. }, Action::Call] => Some(Player::IP),
+                    [Action::Bet { .. }, Action::Call] => Some(Player::OOP),
+                    [Action::Check, Action::Check] => None,
+                    other => panic!("unexpected line {:?}", other),
+                };
+                let can_lead = actions.len() > 1;
+                assert_eq!(can_lead, last_turn_bettor != Some(Player::IP), "{:?}", bet_sequence);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_configs() {
+        let mut config = river_config(1);
+        config.board.pop();
+        config.board.pop();
+        config.board.pop();
+        assert!(matches!(config.validate(), Err(TreeConfigError::InvalidBoard(_))));
+
+        let mut config = river_config(1);
+        config.river.bet_sizes = vec![0.0];
+        assert_eq!(config.validate(), Err(TreeConfigError::InvalidSize(0.0)));
+
+        let mut config = river_config(1);
+        config.structure = BettingStructure::FixedLimit { small_bet: 1.0, big_bet: 2.0, cap: 0 };
+        assert_eq!(config.validate(), Err(TreeConfigError::InvalidCap(0)));
+    }
+
+    #[test]
+    fn test_rounds_close_on_check_back_or_call() {
+        // 8bb behind: a flop bet and raise can get it in, as can any turn shove
+        let mut config = TreeConfig::new(GameConfig::from_pot(5.5, [8.0, 8.0]), parse_cards("AsKh7d").unwrap());
+        config.flop = StreetSizes::new(vec![0.5]);
+        config.turn = StreetSizes { allow_all_in: true, ..StreetSizes::new(vec![0.5]) };
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 2;
+        config.isomorphic_runouts = true;
+        let tree = build_tree(&config);
+        let (mut closed, mut all_in_deals) = (0, 0);
+        for node in &tree.nodes {
+            match node {
+                Node::Decision { player, actions, children, pot, stacks, board, .. } => {
+                    // Nobody is asked to act with nothing to call once a player is all-in
+                    assert!(node.amount_to_call() > CHIP_EPS || stacks.iter().all(|&s| s > CHIP_EPS), "node {}", node.id());
+                    for (action, &c) in actions.iter().zip(children) {
+                        let child = tree.get(c).unwrap();
+                        let round_over = matches!(action, Action::Call) || (*action == Action::Check && *player == Player::IP);
+                        match action {
+                            Action::Fold => assert!(matches!(child, Node::Terminal { folder: Some(f), .. } if f == player)),
+                            _ if !round_over => assert!(matches!(child, Node::Decision { player: p, .. } if *p == player.opponent())),
+                            _ => {
+                                closed += 1;
+                                // The pot and stacks carry over, the call included
+                                let call = node.amount_to_call().min(stacks[player_index(*player)]);
+                                assert!((child.pot() - pot - call).abs() < CHIP_EPS);
+                                assert!((stacks[0] + stacks[1] - child.stacks()[0] - child.stacks()[1] - call).abs() < CHIP_EPS);
+                                assert_eq!(child.board(), &board[..]);
+                                if board.len() == 5 {
+                                    assert!(matches!(child, Node::Terminal { folder: None, .. }));
+                                } else {
+                                    assert!(child.is_chance(), "node {} {}", node.id(), action);
+                                }
+                            }
+                        }
+                    }
+                }
+                Node::Chance { children, pot, stacks, board, .. } => {
+                    let all_in = stacks.iter().any(|&s| s <= CHIP_EPS);
+                    all_in_deals += usize::from(all_in);
+                    for &c in children {
+                        let child = tree.get(c).unwrap();
+                        assert_eq!(child.board()[..board.len()], board[..]);
+                        assert_eq!(child.board().len(), board.len() + 1);
+                        assert_eq!((child.pot(), child.stacks()), (*pot, *stacks));
+                        match child {
+                            // A new street: OOP first, nothing bet yet
+                            Node::Decision { player, .. } => {
+                                assert!(!all_in);
+                                assert_eq!(*player, Player::OOP);
+                                assert_eq!(child.amount_to_call(), 0.0);
+                            }
+                            // All-in: the next card or the showdown, no decisions
+                            Node::Chance { .. } => assert!(all_in && board.len() == 3),
+                            Node::Terminal { folder, .. } => assert!(all_in && folder.is_none() && child.board().len() == 5),
+                        }
+                    }
+                }
+                Node::Terminal { folder: None, board, .. } => assert_eq!(board.len(), 5),
+                Node::Terminal { .. } => {}
+            }
+        }
+        // Flop all-ins deal the turn and then the river
+        let flop_all_ins = tree.nodes.iter().filter(|n| n.is_chance() && tree.get(n.children()[0]).unwrap().is_chance()).count();
+        assert!(closed > 0 && all_in_deals > flop_all_ins && flop_all_ins > 0);
+    }
+}
@@ -0,0 +1,129 @@
+//! Card removal by folded players ("bunching")
+//!
+//! Players who fold before the flop don't hold random hands: folding ranges
+//! lean toward small cards, so the cards still in play are slightly richer
+//! in big ones. `Bunching` summarizes that as the probability that each
+//! card is dead (in a folder's hand), from the folders' ranges with the
+//! board removed, treating the folders as independent.
+//!
+//! A solve uses it in two places:
+//!
+//! - Starting ranges: each combo's weight is scaled by the chance that both
+//!   of its cards are live (`apply`), which shifts showdown equities.
+//! - Chance nodes: a turn or river card is dealt in proportion to its
+//!   chance of being live instead of uniformly (`deal_weights`). Each chance
+//!   node is normalized over its own cards, ignoring the small interaction
+//!   with the players' hole cards.
+
+use crate::isomorphism::{board_symmetries, permute};
+use crate::node::Card;
+use crate::range::Range;
+
+/// Per-card probability of being held by a folded player (see module docs)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bunching {
+    /// dead[card value] in [0.0, 1.0]
+    dead: Vec<f64>,
+}
+
+impl Bunching {
+    /// Bunching from the ranges each folded player folded, given the `board`.
+    /// An empty folding range removes nothing.
+    pub fn from_folded_ranges(folded: &[Range], board: &[Card]) -> Self {
+        let mut live = [1.0f64; 52];
+        for range in folded {
+            let mut per_card = [0.0f64; 52];
+            let mut total = 0.0;
+            for (hand, w) in range.live_combos(board) {
+                total += w;
+                per_card[hand[0].value() as usize] += w;
+                per_card[hand[1].value() as usize] += w;
+            }
+            if total > 0.0 {
+                for (l, held) in live.iter_mut().zip(per_card) {
+                    *l *= 1.0 - held / total;
+                }
+            }
+        }
+        Bunching { dead: live.iter().map(|l| 1.0 - l).collect() }
+    }
+
+    /// Probability that `card` is in a folded player's hand
+    pub fn dead(&self, card: Card) -> f64 {
+        self.dead[card.value() as usize]
+    }
+
+    /// Probability that `card` is still in play
+    pub fn live(&self, card: Card) -> f64 {
+        1.0 - self.dead(card)
+    }
+
+    /// `range` with each combo weighted by the chance both its cards are live
+    pub fn apply(&self, range: &Range) -> Range {
+        let mut out = Range::new();
+        for (hand, w) in range.live_combos(&[]) {
+            out.set_weight(hand, w * self.live(hand[0]) * self.live(hand[1]));
+        }
+        out
+    }
+
+    /// Whether every card is as likely dead as its images under `board`'s
+    /// suit symmetries, as grouped runouts need (see `isomorphism`)
+    pub fn is_symmetric(&self, board: &[Card]) -> bool {
+        let symmetries = board_symmetries(board);
+        (0..52u8).map(Card::new).all(|card| symmetries.iter().all(|perm| (self.dead(permute(card, perm)) - self.dead(card)).abs() <= 1e-12))
+    }
+
+    /// Probability of dealing each of `cards`, proportional to how likely it
+    /// is to be live; uniform if none is
+    pub fn deal_weights(&self, cards: &[Card]) -> Vec<f64> {
+        let total: f64 = cards.iter().map(|&c| self.live(c)).sum();
+        if total <= 0.0 {
+            return vec![1.0 / cards.len() as f64; cards.len()];
+        }
+        cards.iter().map(|&c| self.live(c) / total).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::parse_cards;
+
+    fn card(s: &str) -> Card {
+        parse_cards(s).unwrap()[0]
+    }
+
+    #[test]
+    fn test_folded_ranges_remove_their_cards() {
+        let board = parse_cards("As7d2c").unwrap();
+        // Folding only 32o-76o style junk: no folder holds an ace or king
+        let folder = Range::parse("32o,43o,54o,65o,76o").unwrap();
+        let bunching = Bunching::from_folded_ranges(std::slice::from_ref(&folder), &board);
+        assert_eq!(bunching.dead(card("Kh")), 0.0);
+        assert!(bunching.dead(card("5h")) > 0.0);
+        // Two independent folders remove more than one
+        let two = Bunching::from_folded_ranges(&[folder.clone(), folder], &board);
+        assert!(two.dead(card("5h")) > bunching.dead(card("5h")));
+        assert!(two.dead(card("5h")) < 2.0 * bunching.dead(card("5h")));
+        assert_eq!(Bunching::from_folded_ranges(&[Range::new()], &board).dead(card("5h")), 0.0);
+    }
+
+    #[test]
+    fn test_apply_and_deal_weights() {
+        let bunching = Bunching::from_folded_ranges(&[Range::parse("55").unwrap()], &[]);
+        // Each five is in the folder's hand half the time
+        assert!((bunching.dead(card("5h")) - 0.5).abs() < 1e-12);
+        let r = bunching.apply(&Range::parse("AK,55").unwrap());
+        assert_eq!(r.weight([card("As"), card("Kd")]), 1.0);
+        assert!((r.weight([card("5s"), card("5d")]) - 0.25).abs() < 1e-12);
+
+        let weights = bunching.deal_weights(&[card("5c"), card("Ac")]);
+        assert!((weights[0] - 1.0 / 3.0).abs() < 1e-12 && (weights[1] - 2.0 / 3.0).abs() < 1e-12);
+
+        let board = parse_cards("AsKs7d").unwrap();
+        assert!(bunching.is_symmetric(&board));
+        assert!(!Bunching::from_folded_ranges(&[Range::parse("5h5d").unwrap()], &board).is_symmetric(&board));
+    }
+}
@@ -1,49 +1,239 @@
 //! CFR+ algorithm: regret storage, strategy accumulation, and tree traversal
 //!
+//! `Algorithm::PredictiveCfrPlus` changes only how a traversal picks each
+//! iteration's strategy (`RegretStorage::iteration_strategy`) and how
+//! heavily it's averaged; the regret update is the same. `LinearCfr` and
+//! `DiscountedCfr` play plain regret matching but keep negative regrets and
+//! weight or discount them by iteration (`Algorithm::accumulate_regret`).
+//!
+//! Iteration `t` is weighted by its own number, so a solver's `iteration`
+//! count is part of its state. A solver rebuilt from saved state
+//! (`Solution::resume`) or warm-continued after new terminal EVs counts on
+//! as `SolverConfig::resume` says; by default from where it stopped, so a
+//! solve run in pieces, with the same config, ends bit for bit where an
+//! uninterrupted one does. Snapshots keep the last iteration's regrets for
+//! that reason, since PCFR+ predicts from them and pruning checks them.
+//!
 //! All EV values throughout the traversal are from IP's perspective.
 //! OOP regrets use a sign flip (OOP gains when IP EV falls).
 //!
-//! The traversal is implemented as a pure free function (`cfr_traverse_fn`) that
-//! collects regret/strategy updates rather than mutating storage mid-traversal.
-//! This design enables Rayon-parallel processing at Chance nodes: since only
-//! shared references (&GameTree, &RegretStorage) are needed during traversal,
-//! independent subtrees can run concurrently without locks.
+//! The traversal (`Traversal`) writes regret/strategy updates into an `UpdateBuffer` rather than mutating
+//! storage mid-traversal. This design enables Rayon-parallel processing at
+//! Chance nodes, and at decisions with at least `PARALLEL_SUBTREE` nodes
+//! below them: since only shared references (&GameTree, &RegretStorage,
+//! &UpdateBuffer) are needed during traversal, independent subtrees can run
+//! concurrently without locks, and a river tree with no chance nodes still
+//! spreads over the pool. The buffer is merged into storage once the
+//! traversal finishes. The walk keeps its own stacks of decisions in
+//! progress and their scratch (strategies, child values) instead of
+//! recursing, so tree depth costs heap rather than thread stack.
 
+use crate::ev_table::{EvTable, TerminalEvs};
+use crate::config::{Algorithm, ResumeWeighting, SolverConfig, StagnationTracker, StopReason, StorageMode};
+use crate::exploitability::{compute_exploitability_with_summation, street_exploitability, ConvergenceMetrics, StreetExploitability};
 use crate::node::{GameTree, Node, NodeId, Player};
+use crate::nodelock::NodeLocks;
+use crate::profile::{ProfileReport, Profiler};
+use crate::summation::Summation;
 use crate::test_tree::terminal_ev_table;
 use std::collections::HashMap;
-use rayon::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::par::{build_pool, ThreadPool};
+use crate::par::prelude::*;
+
+/// Owned copy of every regret and strategy-sum array in a `RegretStorage`.
+///
+/// Outer index is the infoset ID, exactly as in the storage.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StorageSnapshot {
+    pub regrets: Vec<Vec<f64>>,
+    pub strategy_sums: Vec<Vec<f64>>,
+    /// The last iteration's counterfactual regrets, which PCFR+ predicts
+    /// from and pruning checks; empty reads as all zeros
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub last_regrets: Vec<Vec<f64>>,
+}
 
-/// Regret and strategy storage, indexed by node ID.
+/// Regret and strategy storage, indexed by infoset ID (see `infoset`).
 ///
-/// Non-decision nodes (terminal, chance) have empty inner vecs.
-/// Never call `current_strategy` or `update_regrets` on a non-decision node.
+/// Dense storage (`new`, `for_tree`) allocates every infoset's arrays up
+/// front. Lazy storage (`lazy`, `lazy_for_tree`) allocates an infoset's
+/// arrays the first time an update touches it; until then it reads as all
+/// zeros, so strategies, snapshots, and results are the same in both modes.
+/// Use it when most infosets are rarely or never reached (sampled
+/// traversals, huge abstractions).
 pub struct RegretStorage {
-    /// regrets[node_id][action_idx] — cumulative regrets (CFR+ floored at 0)
+    /// regrets[infoset_id][action_idx] — cumulative regrets (CFR+ floored at 0)
     regrets: Vec<Vec<f64>>,
-    /// strategy_sums[node_id][action_idx] — linearly weighted strategy accumulation
+    /// strategy_sums[infoset_id][action_idx] — linearly weighted strategy accumulation
     strategy_sums: Vec<Vec<f64>>,
+    /// last_regrets[infoset_id][action_idx] — counterfactual regret of the most
+    /// recent update, for PCFR+ predictions and pruning
+    last_regrets: Vec<Vec<f64>>,
+    /// Lazy mode: action count per infoset, whose arrays stay empty until
+    /// first touched. `None` for dense storage.
+    lazy_actions: Option<Vec<usize>>,
+    /// Read in place of untouched lazy arrays (as long as the widest infoset)
+    zeros: Vec<f64>,
 }
 
 impl RegretStorage {
-    /// Allocate storage. `actions_per_node[i]` is the number of actions at node i
-    /// (0 for terminal/chance nodes).
-    pub fn new(_num_nodes: usize, actions_per_node: &[usize]) -> Self {
-        let regrets = actions_per_node
+    /// Allocate storage. `actions_per_infoset[i]` is the number of actions at infoset i.
+    pub fn new(actions_per_infoset: &[usize]) -> Self {
+        let regrets = actions_per_infoset
             .iter()
             .map(|&n| vec![0.0_f64; n])
             .collect();
-        let strategy_sums = actions_per_node
+        let strategy_sums = actions_per_infoset
             .iter()
             .map(|&n| vec![0.0_f64; n])
             .collect();
-        RegretStorage { regrets, strategy_sums }
+        let last_regrets = actions_per_infoset
+            .iter()
+            .map(|&n| vec![0.0_f64; n])
+            .collect();
+        RegretStorage { regrets, strategy_sums, last_regrets, lazy_actions: None, zeros: Vec::new() }
+    }
+
+    /// Storage that allocates each infoset's arrays on first touch (see
+    /// `RegretStorage` docs)
+    pub fn lazy(actions_per_infoset: &[usize]) -> Self {
+        let empty = || actions_per_infoset.iter().map(|_| Vec::new()).collect();
+        RegretStorage {
+            regrets: empty(),
+            strategy_sums: empty(),
+            last_regrets: empty(),
+            lazy_actions: Some(actions_per_infoset.to_vec()),
+            zeros: vec![0.0; actions_per_infoset.iter().copied().max().unwrap_or(0)],
+        }
+    }
+
+    /// Zeroed storage sized for every infoset in `tree`
+    pub fn for_tree(tree: &GameTree) -> Self {
+        RegretStorage::new(&Self::tree_actions(tree))
+    }
+
+    /// Lazy storage for every infoset in `tree`
+    pub fn lazy_for_tree(tree: &GameTree) -> Self {
+        RegretStorage::lazy(&Self::tree_actions(tree))
+    }
+
+    fn tree_actions(tree: &GameTree) -> Vec<usize> {
+        let mut actions_per_infoset = vec![0usize; tree.num_infosets()];
+        for node in &tree.nodes {
+            if let Node::Decision { infoset_id, actions, .. } = node {
+                actions_per_infoset[*infoset_id as usize] = actions.len();
+            }
+        }
+        actions_per_infoset
+    }
+
+    /// Whether arrays are allocated on first touch
+    pub fn is_lazy(&self) -> bool {
+        self.lazy_actions.is_some()
+    }
+
+    /// Infosets whose arrays are allocated (all of them for dense storage)
+    pub fn allocated_infosets(&self) -> usize {
+        match &self.lazy_actions {
+            Some(actions) => self.regrets.iter().zip(actions).filter(|(r, &n)| r.len() == n && n > 0).count(),
+            None => self.regrets.len(),
+        }
+    }
+
+    /// Number of actions at an infoset
+    pub fn num_actions(&self, infoset_id: usize) -> usize {
+        match &self.lazy_actions {
+            Some(actions) => actions[infoset_id],
+            None => self.regrets[infoset_id].len(),
+        }
+    }
+
+    /// `rows[infoset_id]`, or zeros if a lazy infoset hasn't been touched
+    fn row<'s>(&'s self, rows: &'s [Vec<f64>], infoset_id: usize) -> &'s [f64] {
+        let row = &rows[infoset_id];
+        match &self.lazy_actions {
+            Some(actions) if row.is_empty() => &self.zeros[..actions[infoset_id]],
+            _ => row,
+        }
+    }
+
+    /// Allocate a lazy infoset's arrays if this is its first touch
+    fn touch(&mut self, infoset_id: usize) {
+        if let Some(actions) = &self.lazy_actions {
+            let n = actions[infoset_id];
+            if self.regrets[infoset_id].len() != n {
+                self.regrets[infoset_id] = vec![0.0; n];
+                self.strategy_sums[infoset_id] = vec![0.0; n];
+                self.last_regrets[infoset_id] = vec![0.0; n];
+            }
+        }
+    }
+
+    /// Number of infosets
+    pub fn num_infosets(&self) -> usize {
+        self.regrets.len()
+    }
+
+    /// Cumulative (floored) regrets at an infoset, indexed by action
+    pub fn regrets(&self, infoset_id: usize) -> &[f64] {
+        self.row(&self.regrets, infoset_id)
+    }
+
+    /// Weighted strategy sums at an infoset, indexed by action
+    pub fn strategy_sum(&self, infoset_id: usize) -> &[f64] {
+        self.row(&self.strategy_sums, infoset_id)
+    }
+
+    /// Copy out all regret, strategy-sum, and last-regret arrays (untouched
+    /// lazy infosets as zeros)
+    pub fn snapshot(&self) -> StorageSnapshot {
+        if !self.is_lazy() {
+            return StorageSnapshot {
+                regrets: self.regrets.clone(),
+                strategy_sums: self.strategy_sums.clone(),
+                last_regrets: self.last_regrets.clone(),
+            };
+        }
+        let rows = |rows: &[Vec<f64>]| (0..rows.len()).map(|i| self.row(rows, i).to_vec()).collect();
+        StorageSnapshot { regrets: rows(&self.regrets), strategy_sums: rows(&self.strategy_sums), last_regrets: rows(&self.last_regrets) }
+    }
+
+    /// Rebuild storage from a snapshot (e.g. one saved by an external tool);
+    /// empty `last_regrets` start at zero
+    pub fn from_snapshot(snapshot: StorageSnapshot) -> Self {
+        assert_eq!(snapshot.regrets.len(), snapshot.strategy_sums.len(), "snapshot arrays must match");
+        let last_regrets = if snapshot.last_regrets.is_empty() {
+            snapshot.regrets.iter().map(|r| vec![0.0; r.len()]).collect()
+        } else {
+            snapshot.last_regrets
+        };
+        RegretStorage { regrets: snapshot.regrets, strategy_sums: snapshot.strategy_sums, last_regrets, lazy_actions: None, zeros: Vec::new() }
+    }
+
+    /// Multiply every strategy sum by `factor`, leaving the average
+    /// strategy as it is
+    pub(crate) fn scale_strategy_sums(&mut self, factor: f64) {
+        for row in &mut self.strategy_sums {
+            row.iter_mut().for_each(|x| *x *= factor);
+        }
+    }
+
+    /// Zero every regret and strategy sum, keeping the allocation (lazy
+    /// infosets stay allocated once touched)
+    pub fn reset(&mut self) {
+        for r in self.regrets.iter_mut().chain(self.strategy_sums.iter_mut()).chain(self.last_regrets.iter_mut()) {
+            r.iter_mut().for_each(|x| *x = 0.0);
+        }
     }
 
     /// Current mixed strategy via regret-matching+.
     /// σ(I,a) = r+(I,a) / Σr+(I,a); uniform if all regrets ≤ 0.
     pub fn current_strategy(&self, infoset_id: usize) -> Vec<f64> {
-        let r = &self.regrets[infoset_id];
+        let r = self.regrets(infoset_id);
         let pos_sum: f64 = r.iter().map(|&x| x.max(0.0)).sum();
         if pos_sum <= 0.0 {
             let n = r.len();
@@ -52,9 +242,54 @@ impl RegretStorage {
         r.iter().map(|&x| x.max(0.0) / pos_sum).collect()
     }
 
+    /// Predictive regret-matching+: regret matching on r+(I,a) + m(I,a),
+    /// where the prediction m is the last update's counterfactual regret.
+    pub fn predictive_strategy(&self, infoset_id: usize) -> Vec<f64> {
+        let predicted: Vec<f64> = self.regrets(infoset_id).iter().zip(self.last_regrets(infoset_id))
+            .map(|(r, m)| (r + m).max(0.0))
+            .collect();
+        let pos_sum: f64 = predicted.iter().sum();
+        if pos_sum <= 0.0 {
+            let n = predicted.len();
+            return vec![1.0 / n as f64; n];
+        }
+        predicted.iter().map(|&x| x / pos_sum).collect()
+    }
+
+    /// Strategy `algorithm` plays at an infoset this iteration
+    pub fn iteration_strategy(&self, infoset_id: usize, algorithm: Algorithm) -> Vec<f64> {
+        let mut strategy = vec![0.0; self.num_actions(infoset_id)];
+        self.iteration_strategy_into(infoset_id, algorithm, &mut strategy);
+        strategy
+    }
+
+    /// `iteration_strategy` written into `out` (one slot per action)
+    pub fn iteration_strategy_into(&self, infoset_id: usize, algorithm: Algorithm, out: &mut [f64]) {
+        let r = self.regrets(infoset_id);
+        match algorithm {
+            Algorithm::CfrPlus | Algorithm::LinearCfr | Algorithm::DiscountedCfr => {
+                for (o, &x) in out.iter_mut().zip(r) {
+                    *o = x.max(0.0);
+                }
+            }
+            Algorithm::PredictiveCfrPlus => {
+                for ((o, &x), &m) in out.iter_mut().zip(r).zip(self.last_regrets(infoset_id)) {
+                    *o = (x + m).max(0.0);
+                }
+            }
+        }
+        let pos_sum: f64 = out.iter().sum();
+        if pos_sum <= 0.0 {
+            let n = out.len();
+            out.fill(1.0 / n as f64);
+        } else {
+            out.iter_mut().for_each(|x| *x /= pos_sum);
+        }
+    }
+
     /// Average strategy: S_T(I,a) / ΣS_T(I,a); uniform if never accumulated.
     pub fn average_strategy(&self, infoset_id: usize) -> Vec<f64> {
-        let s = &self.strategy_sums[infoset_id];
+        let s = self.strategy_sum(infoset_id);
         let total: f64 = s.iter().sum();
         if total <= 0.0 {
             let n = s.len();
@@ -66,6 +301,7 @@ impl RegretStorage {
     /// CFR+ regret update: r_{t+1}(I,a) = max(0, r_t(I,a) + cf_value[a]).
     /// The floor is applied to the final value (not just the delta).
     pub fn update_regrets(&mut self, infoset_id: usize, cf_values: &[f64]) {
+        self.touch(infoset_id);
         let r = &mut self.regrets[infoset_id];
         for (ri, &cf) in r.iter_mut().zip(cf_values.iter()) {
             *ri = (*ri + cf).max(0.0);
@@ -74,6 +310,7 @@ impl RegretStorage {
 
     /// Linear weighted strategy accumulation: S_t(I,a) += t * σ_t(I,a).
     pub fn accumulate_strategy(&mut self, infoset_id: usize, strategy: &[f64], iteration: u64) {
+        self.touch(infoset_id);
         let s = &mut self.strategy_sums[infoset_id];
         let weight = iteration as f64;
         for (si, &prob) in s.iter_mut().zip(strategy.iter()) {
@@ -82,142 +319,537 @@ impl RegretStorage {
     }
 }
 
-/// A batched regret/strategy update produced during a single traversal.
+/// One infoset's regret/strategy update from a single traversal, as sent
+/// between distributed workers (see `UpdateBuffer::updates`).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct RegretUpdate {
+    pub(crate) infoset_id: usize,
+    pub(crate) cf_values: Vec<f64>,
+    pub(crate) strategy: Vec<f64>,
+    pub(crate) weight: u64,
+}
+
+impl RegretStorage {
+    /// Apply a traversal's updates (each infoset appears at most once per iteration)
+    /// with `algorithm`'s regret rule for iteration `t`
+    pub(crate) fn apply(&mut self, updates: &[RegretUpdate], algorithm: Algorithm, t: u64) {
+        for u in updates {
+            self.touch(u.infoset_id);
+            for (r, &cf) in self.regrets[u.infoset_id].iter_mut().zip(&u.cf_values) {
+                *r = algorithm.accumulate_regret(*r, cf, t);
+            }
+            self.accumulate_strategy(u.infoset_id, &u.strategy, u.weight);
+            self.last_regrets[u.infoset_id].copy_from_slice(&u.cf_values);
+        }
+    }
+
+    /// Apply every update written to `buffer` since its last `begin`, in the
+    /// buffer's order, as `algorithm`'s iteration `t`
+    pub(crate) fn merge(&mut self, buffer: &UpdateBuffer, algorithm: Algorithm, t: u64) {
+        let w = algorithm.averaging_weight(t) as f64;
+        for &(node_id, infoset_id) in &buffer.order {
+            let Some(slot) = buffer.written(node_id) else { continue };
+            self.touch(infoset_id);
+            let (r, s, last) = (&mut self.regrets[infoset_id], &mut self.strategy_sums[infoset_id], &mut self.last_regrets[infoset_id]);
+            for (a, i) in slot.enumerate() {
+                let cf = f64::from_bits(buffer.cf_values[i].load(Ordering::Relaxed));
+                r[a] = algorithm.accumulate_regret(r[a], cf, t);
+                s[a] += w * f64::from_bits(buffer.strategy[i].load(Ordering::Relaxed));
+                last[a] = cf;
+            }
+        }
+    }
+
+    /// Append zeroed slots for infosets past the current end, so storage can
+    /// follow a tree that is still growing (see `lazy_tree`)
+    pub(crate) fn grow(&mut self, actions_per_infoset: &[usize]) {
+        for &n in actions_per_infoset.iter().skip(self.regrets.len()) {
+            if let Some(actions) = &mut self.lazy_actions {
+                actions.push(n);
+                if n > self.zeros.len() {
+                    self.zeros.resize(n, 0.0);
+                }
+                self.regrets.push(Vec::new());
+                self.strategy_sums.push(Vec::new());
+                self.last_regrets.push(Vec::new());
+            } else {
+                self.regrets.push(vec![0.0; n]);
+                self.strategy_sums.push(vec![0.0; n]);
+                self.last_regrets.push(vec![0.0; n]);
+            }
+        }
+    }
+
+    /// Regret update applied at an infoset last iteration, indexed by action
+    pub(crate) fn last_regrets(&self, infoset_id: usize) -> &[f64] {
+        self.row(&self.last_regrets, infoset_id)
+    }
+
+    /// Overwrite one infoset's regrets and strategy sums
+    pub(crate) fn restore(&mut self, infoset_id: usize, regrets: Vec<f64>, strategy_sums: Vec<f64>) {
+        assert_eq!(regrets.len(), self.num_actions(infoset_id), "action count mismatch");
+        assert_eq!(strategy_sums.len(), self.num_actions(infoset_id), "action count mismatch");
+        self.touch(infoset_id);
+        self.regrets[infoset_id] = regrets;
+        self.strategy_sums[infoset_id] = strategy_sums;
+    }
+
+    /// Overwrite one infoset's last regrets (see `last_regrets`)
+    #[cfg(feature = "serde")]
+    pub(crate) fn restore_last_regrets(&mut self, infoset_id: usize, last_regrets: Vec<f64>) {
+        assert_eq!(last_regrets.len(), self.num_actions(infoset_id), "action count mismatch");
+        self.touch(infoset_id);
+        self.last_regrets[infoset_id] = last_regrets;
+    }
+}
+
+/// Decisions with at least this many nodes in their subtree walk their
+/// children in parallel; below it a Rayon job costs more than it saves
+pub(crate) const PARALLEL_SUBTREE: usize = 256;
+
+/// Walks nested deeper than this keep their decisions' children on one
+/// thread. Each nested walk is a real call on the stack, so long raise wars
+/// must not split at every level; a few levels already fill the pool.
+const MAX_NESTED_WALKS: usize = 8;
+
+/// Per-decision-node slots a traversal writes its updates into.
 ///
-/// Collected by `cfr_traverse_fn` and applied sequentially in `run_iteration`
-/// so that the traversal itself only needs shared (&) references.
-#[derive(Clone)]
-struct RegretUpdate {
-    infoset_id: usize,
-    cf_values: Vec<f64>,
-    strategy: Vec<f64>,
-    weight: u64,
+/// Each node is visited at most once per traversal, so every slot has a
+/// single writer and parallel subtrees never contend. Values are f64 bits in
+/// relaxed atomics, which lets the buffer be shared by reference; Rayon's
+/// join orders the writes before the merge. Slots are stamped with the
+/// traversal's epoch instead of being cleared, so nodes a traversal skips
+/// (pruned or cut) contribute nothing.
+pub(crate) struct UpdateBuffer {
+    /// Node `n`'s actions occupy `offsets[n]..offsets[n + 1]`
+    offsets: Vec<usize>,
+    /// Epoch each node's slot was last written in
+    stamps: Vec<AtomicU64>,
+    cf_values: Vec<AtomicU64>,
+    strategy: Vec<AtomicU64>,
+    epoch: u64,
+    /// (node, infoset) of every decision below the root, children before
+    /// parents: the order the collect-then-apply traversal applied them in
+    order: Vec<(NodeId, usize)>,
+    /// Decisions whose subtree reaches `PARALLEL_SUBTREE` nodes
+    parallel: Vec<bool>,
 }
 
-/// Minimal node info extracted before recursive calls (avoids borrow conflicts).
-enum NodeInfo {
-    Terminal,
-    Decision {
-        infoset_id: usize,
-        player: Player,
-        children: Vec<NodeId>,
-    },
-    Chance {
-        children: Vec<NodeId>,
-    },
+impl UpdateBuffer {
+    pub(crate) fn new(tree: &GameTree) -> Self {
+        let mut offsets = Vec::with_capacity(tree.len() + 1);
+        offsets.push(0);
+        for node in &tree.nodes {
+            let actions = if let Node::Decision { actions, .. } = node { actions.len() } else { 0 };
+            offsets.push(offsets.last().unwrap() + actions);
+        }
+        let slots = *offsets.last().unwrap();
+        let zeroed = |n: usize| (0..n).map(|_| AtomicU64::new(0)).collect();
+        UpdateBuffer {
+            offsets,
+            stamps: zeroed(tree.len()),
+            cf_values: zeroed(slots),
+            strategy: zeroed(slots),
+            epoch: 0,
+            order: if tree.is_empty() { Vec::new() } else { post_order(tree, 0) },
+            parallel: subtree_sizes(tree).into_iter().map(|n| n >= PARALLEL_SUBTREE).collect(),
+        }
+    }
+
+    /// Heap bytes of a buffer for a tree with `nodes` nodes, `decisions`
+    /// decisions, and `actions` actions summed over decisions
+    pub(crate) fn estimate_bytes(nodes: u64, decisions: u64, actions: u64) -> u64 {
+        let size = |n: usize| n as u64;
+        (nodes + 1) * size(size_of::<usize>())
+            + nodes * size(size_of::<AtomicU64>() + size_of::<bool>())
+            + 2 * actions * size(size_of::<AtomicU64>())
+            + decisions * size(size_of::<(NodeId, usize)>())
+    }
+
+    /// Heap bytes this buffer holds (lengths, not capacities)
+    #[cfg(test)]
+    pub(crate) fn heap_bytes(&self) -> u64 {
+        (self.offsets.len() * size_of::<usize>()
+            + (self.stamps.len() + self.cf_values.len() + self.strategy.len()) * size_of::<AtomicU64>()
+            + self.order.len() * size_of::<(NodeId, usize)>()
+            + self.parallel.len() * size_of::<bool>()) as u64
+    }
+
+    /// Whether `node_id`'s children are worth walking in parallel
+    fn parallel(&self, node_id: NodeId) -> bool {
+        self.parallel[node_id as usize]
+    }
+
+    /// Start a new traversal, invalidating everything written so far
+    pub(crate) fn begin(&mut self) {
+        self.epoch += 1;
+    }
+
+    /// Record a decision node's counterfactual regrets and strategy
+    pub(crate) fn record(&self, node_id: NodeId, cf_values: &[f64], strategy: &[f64]) {
+        let start = self.offsets[node_id as usize];
+        for (i, (&cf, &p)) in cf_values.iter().zip(strategy).enumerate() {
+            self.cf_values[start + i].store(cf.to_bits(), Ordering::Relaxed);
+            self.strategy[start + i].store(p.to_bits(), Ordering::Relaxed);
+        }
+        self.stamps[node_id as usize].store(self.epoch, Ordering::Relaxed);
+    }
+
+    /// Slot range of `node_id` if it was written this traversal
+    fn written(&self, node_id: NodeId) -> Option<std::ops::Range<usize>> {
+        let n = node_id as usize;
+        (self.stamps[n].load(Ordering::Relaxed) == self.epoch).then(|| self.offsets[n]..self.offsets[n + 1])
+    }
+
+    /// This traversal's updates below `root`, in merge order
+    #[cfg(any(feature = "serde", test))]
+    pub(crate) fn updates(&self, tree: &GameTree, root: NodeId, weight: u64) -> Vec<RegretUpdate> {
+        post_order(tree, root).into_iter().filter_map(|(node_id, infoset_id)| {
+            let slot = self.written(node_id)?;
+            let load = |v: &[AtomicU64]| v[slot.clone()].iter().map(|x| f64::from_bits(x.load(Ordering::Relaxed))).collect();
+            Some(RegretUpdate { infoset_id, cf_values: load(&self.cf_values), strategy: load(&self.strategy), weight })
+        }).collect()
+    }
 }
 
-/// Extract the minimal node information needed for traversal.
-fn read_node(tree: &GameTree, node_id: NodeId) -> NodeInfo {
-    match tree.get(node_id).expect("invalid node id") {
-        Node::Terminal { .. } => NodeInfo::Terminal,
-        Node::Decision { infoset_id, player, children, .. } => NodeInfo::Decision {
-            infoset_id: *infoset_id as usize,
-            player: *player,
-            children: children.clone(),
-        },
-        Node::Chance { children, .. } => NodeInfo::Chance {
-            children: children.clone(),
-        },
+/// Nodes in each node's subtree, itself included; 0 for nodes the root
+/// doesn't reach
+fn subtree_sizes(tree: &GameTree) -> Vec<usize> {
+    let mut sizes = vec![0; tree.len()];
+    let mut stack = if tree.is_empty() { Vec::new() } else { vec![(0, false)] };
+    while let Some((id, expanded)) = stack.pop() {
+        let children = tree.get(id).expect("invalid node id").children();
+        if expanded {
+            sizes[id as usize] = 1 + children.iter().map(|&c| sizes[c as usize]).sum::<usize>();
+            continue;
+        }
+        stack.push((id, true));
+        stack.extend(children.iter().map(|&c| (c, false)));
     }
+    sizes
 }
 
-/// Pure CFR+ traversal. Returns `(ev, updates)` where `ev` is the value from
-/// IP's perspective and `updates` is the list of regret/strategy changes to apply.
+/// Decision nodes below `root` with their infosets, children before parents
+/// and siblings in action order
+fn post_order(tree: &GameTree, root: NodeId) -> Vec<(NodeId, usize)> {
+    let mut order = Vec::new();
+    let mut stack = vec![(root, false)];
+    while let Some((id, expanded)) = stack.pop() {
+        let node = tree.get(id).expect("invalid node id");
+        if expanded {
+            order.extend(node.infoset_id().map(|i| (id, i as usize)));
+            continue;
+        }
+        stack.push((id, true));
+        stack.extend(node.children().iter().rev().map(|&c| (c, false)));
+    }
+    order
+}
+
+/// One CFR+ traversal: the tree, the strategies it plays, and where its
+/// updates go.
 ///
-/// Both `tree` and `storage` are borrowed immutably, so Chance node children
-/// can be traversed in parallel via Rayon without any locking.
-fn cfr_traverse_fn(
-    tree: &GameTree,
-    storage: &RegretStorage,
-    terminal_evs: &HashMap<NodeId, f64>,
-    node_id: NodeId,
-    reach_ip: f64,
-    reach_oop: f64,
-    t: u64,
-) -> (f64, Vec<RegretUpdate>) {
-    match read_node(tree, node_id) {
-        NodeInfo::Terminal => {
-            let ev = terminal_evs[&node_id];
-            (ev, vec![])
-        }
-
-        NodeInfo::Decision { infoset_id, player, children } => {
-            let strategy = storage.current_strategy(infoset_id);
-
-            let mut all_updates: Vec<RegretUpdate> = Vec::new();
-            let mut child_evs = Vec::with_capacity(children.len());
-
-            for (i, &child_id) in children.iter().enumerate() {
-                let (new_reach_ip, new_reach_oop) = if player == Player::IP {
-                    (reach_ip * strategy[i], reach_oop)
-                } else {
-                    (reach_ip, reach_oop * strategy[i])
-                };
-                let (ev, child_updates) = cfr_traverse_fn(
-                    tree, storage, terminal_evs, child_id, new_reach_ip, new_reach_oop, t,
-                );
-                child_evs.push(ev);
-                all_updates.extend(child_updates);
+/// `tree`, `storage`, and `updates` are borrowed immutably, so Chance node
+/// children, and the children of decisions over `PARALLEL_SUBTREE` nodes,
+/// can be traversed in parallel via Rayon without any locking. Child values
+/// are still added up in action order, so results don't depend on the pool.
+///
+/// Nodes in `cut` are not traversed: their precomputed value is used instead
+/// (see `distributed`, where remote workers value those subtrees).
+///
+/// With `prune = Some(threshold)`, an action with zero probability whose last
+/// regret is below `-threshold` is skipped; its regret update repeats the
+/// last one, which leaves a zero regret at zero (see `PruneConfig`).
+///
+/// With a `profiler`, time spent at each decision and terminal node
+/// (children excluded) is recorded. Node values and chance expectations are
+/// added up with `summation`. `algorithm` picks each infoset's strategy; its
+/// averaging weight is applied when the buffer is merged.
+pub(crate) struct Traversal<'a> {
+    pub(crate) tree: &'a GameTree,
+    pub(crate) storage: &'a RegretStorage,
+    pub(crate) terminal_evs: &'a EvTable,
+    pub(crate) updates: &'a UpdateBuffer,
+    pub(crate) prune: Option<f64>,
+    pub(crate) cut: Option<&'a HashMap<NodeId, f64>>,
+    pub(crate) profiler: Option<&'a Profiler>,
+    pub(crate) summation: Summation,
+    pub(crate) algorithm: Algorithm,
+    pub(crate) locks: Option<&'a NodeLocks>,
+}
+
+impl<'a> Traversal<'a> {
+    /// Plain CFR+ over the whole tree: no pruning, cut, or profiling
+    pub(crate) fn new(
+        tree: &'a GameTree,
+        storage: &'a RegretStorage,
+        terminal_evs: &'a EvTable,
+        updates: &'a UpdateBuffer,
+    ) -> Self {
+        Traversal {
+            tree,
+            storage,
+            terminal_evs,
+            updates,
+            prune: None,
+            cut: None,
+            profiler: None,
+            summation: Summation::Naive,
+            algorithm: Algorithm::CfrPlus,
+            locks: None,
+        }
+    }
+
+    /// Traverse the subtree at `node_id`, recording every decision's
+    /// regret/strategy update in `updates`. Returns the node's value from
+    /// IP's perspective.
+    pub(crate) fn run(&self, node_id: NodeId, reach_ip: f64, reach_oop: f64) -> f64 {
+        self.walk(node_id, reach_ip, reach_oop, &mut Scratch::default())
+    }
+
+    /// Depth-first walk with explicit stacks instead of recursion, so deep
+    /// lines (long raise wars) can't overflow the thread's stack. Only
+    /// parallel children (of chance nodes and large decisions) start walks
+    /// of their own, each on a `Scratch` per Rayon job.
+    fn walk(&self, root: NodeId, reach_ip: f64, reach_oop: f64, scratch: &mut Scratch<'a>) -> f64 {
+        let floor = scratch.frames.len();
+        let mut value = self.enter(root, reach_ip, reach_oop, scratch);
+        loop {
+            // Hand a finished child's value to the decision above it
+            if let Some(v) = value {
+                if scratch.frames.len() == floor {
+                    return v;
+                }
+                let frame = scratch.frames.last_mut().expect("frame above floor");
+                scratch.values[frame.base + frame.children.len() + frame.next] = v;
+                if let Some(child_start) = frame.child_start {
+                    frame.child_time += child_start.elapsed();
+                }
+                frame.next += 1;
+            }
+
+            // Enter the top decision's next unpruned child, or finish it
+            let frame = scratch.frames.last_mut().expect("frame above floor");
+            while frame.next < frame.children.len() && self.pruned(frame.infoset_id, frame.next, scratch.values[frame.base + frame.next]) {
+                frame.next += 1;
+            }
+            if frame.next == frame.children.len() {
+                value = Some(self.finish(scratch));
+                continue;
             }
+            let p = scratch.values[frame.base + frame.next];
+            let (new_reach_ip, new_reach_oop) = if frame.player == Player::IP {
+                (frame.reach_ip * p, frame.reach_oop)
+            } else {
+                (frame.reach_ip, frame.reach_oop * p)
+            };
+            frame.child_start = self.profiler.map(|_| Instant::now());
+            let child_id = frame.children[frame.next];
+            value = self.enter(child_id, new_reach_ip, new_reach_oop, scratch);
+        }
+    }
 
-            // Node value (IP's perspective)
-            let node_value: f64 = strategy.iter().zip(child_evs.iter())
-                .map(|(&s, &ev)| s * ev).sum();
+    /// A zero-probability action skipped by regret-based pruning
+    fn pruned(&self, infoset_id: usize, action: usize, probability: f64) -> bool {
+        self.prune.is_some_and(|threshold| probability == 0.0 && self.storage.last_regrets(infoset_id)[action] < -threshold)
+    }
 
-            // Counterfactual regrets (sign depends on acting player)
-            let cf_values: Vec<f64> = child_evs.iter().map(|&ev| {
-                if player == Player::IP {
-                    reach_oop * (ev - node_value)
-                } else {
-                    reach_ip * (node_value - ev) // OOP benefits when IP EV falls
+    /// Value a cut, terminal, or chance node outright; push a frame for a
+    /// decision (its children are visited by `walk`) and return `None`
+    fn enter(&self, node_id: NodeId, reach_ip: f64, reach_oop: f64, scratch: &mut Scratch<'a>) -> Option<f64> {
+        if let Some(&ev) = self.cut.and_then(|c| c.get(&node_id)) {
+            return Some(ev);
+        }
+        let start = self.profiler.map(|_| Instant::now());
+        match self.tree.get(node_id).expect("invalid node id") {
+            Node::Terminal { .. } => {
+                let ev = self.terminal_evs.terminal_ev(node_id);
+                if let (Some(p), Some(start)) = (self.profiler, start) {
+                    p.add_node(self.tree, node_id, start.elapsed());
                 }
-            }).collect();
+                Some(ev)
+            }
 
-            all_updates.push(RegretUpdate {
-                infoset_id,
-                cf_values,
-                strategy,
-                weight: t,
-            });
+            Node::Decision { infoset_id, player, children, .. } => {
+                let (infoset_id, n) = (*infoset_id as usize, children.len());
+                // values[base..base + n] is the strategy, the next n the child values
+                let base = scratch.values.len();
+                scratch.values.resize(base + 2 * n, 0.0);
+                self.storage.iteration_strategy_into(infoset_id, self.algorithm, &mut scratch.values[base..base + n]);
+                if let Some(locks) = self.locks {
+                    locks.apply(infoset_id, &mut scratch.values[base..base + n]);
+                }
+                // A large subtree values its children in parallel up front,
+                // leaving `walk` only the frame to finish
+                let mut next = 0;
+                let mut child_time = Duration::ZERO;
+                if self.updates.parallel(node_id) && scratch.nested < MAX_NESTED_WALKS {
+                    let child_start = self.profiler.map(|_| Instant::now());
+                    let nested = scratch.nested + 1;
+                    let (strategy, values) = scratch.values[base..].split_at_mut(n);
+                    values.par_iter_mut().zip(children.par_iter()).enumerate().for_each_init(|| Scratch::nested(nested), |s, (i, (v, &child_id))| {
+                        if self.pruned(infoset_id, i, strategy[i]) {
+                            return;
+                        }
+                        let (child_ip, child_oop) = if *player == Player::IP {
+                            (reach_ip * strategy[i], reach_oop)
+                        } else {
+                            (reach_ip, reach_oop * strategy[i])
+                        };
+                        *v = self.walk(child_id, child_ip, child_oop, s);
+                    });
+                    next = n;
+                    child_time = child_start.map_or(Duration::ZERO, |t| t.elapsed());
+                }
+                scratch.frames.push(Frame {
+                    node_id,
+                    infoset_id,
+                    player: *player,
+                    children,
+                    reach_ip,
+                    reach_oop,
+                    base,
+                    next,
+                    start,
+                    child_start: None,
+                    child_time,
+                });
+                None
+            }
+
+            Node::Chance { children, weights, .. } => {
+                let base = scratch.values.len();
+                scratch.values.resize(base + children.len(), 0.0);
+
+                // Parallel traversal: each child subtree is independent (disjoint node sets,
+                // only shared immutable refs needed). Rayon's work-stealing scheduler handles
+                // nested parallelism safely.
+                let nested = scratch.nested + 1;
+                scratch.values[base..].par_iter_mut().zip(children.par_iter()).for_each_init(|| Scratch::nested(nested), |s, (ev, &child_id)| {
+                    *ev = self.walk(child_id, reach_ip, reach_oop, s);
+                });
 
-            (node_value, all_updates)
-        }
-
-        NodeInfo::Chance { children } => {
-            let n = children.len() as f64;
-
-            // Parallel traversal: each child subtree is independent (disjoint node sets,
-            // only shared immutable refs needed). Rayon's work-stealing scheduler handles
-            // nested parallelism safely.
-            let results: Vec<(f64, Vec<RegretUpdate>)> = children
-                .par_iter()
-                .map(|&child_id| {
-                    cfr_traverse_fn(
-                        tree, storage, terminal_evs, child_id, reach_ip, reach_oop, t,
-                    )
-                })
-                .collect();
-
-            // Uniform average EV; concatenate all updates
-            let mut all_updates: Vec<RegretUpdate> = Vec::new();
-            let mut ev_sum = 0.0_f64;
-            for (ev, updates) in results {
-                ev_sum += ev;
-                all_updates.extend(updates);
+                // Expected EV over the deal
+                let evs = &scratch.values[base..];
+                let ev_sum = self.summation.sum(evs.iter().enumerate()
+                    .map(|(i, ev)| if weights.is_empty() { *ev } else { weights[i] * ev }));
+                scratch.values.truncate(base);
+
+                Some(if weights.is_empty() { ev_sum / children.len() as f64 } else { ev_sum })
             }
+        }
+    }
+
+    /// Pop the top decision once all its children are valued: record its
+    /// update and return its value
+    fn finish(&self, scratch: &mut Scratch<'a>) -> f64 {
+        let frame = scratch.frames.pop().expect("frame to finish");
+        let (infoset_id, n) = (frame.infoset_id, frame.children.len());
+        let last = self.storage.last_regrets(infoset_id);
+        let (strategy, values) = scratch.values[frame.base..].split_at_mut(n);
 
-            (ev_sum / n, all_updates)
+        // Node value (IP's perspective); pruned actions have zero probability
+        let node_value = self.summation.sum(strategy.iter().zip(values.iter()).enumerate()
+            .map(|(i, (&s, &ev))| if self.pruned(infoset_id, i, s) { 0.0 } else { s * ev }));
+
+        // Counterfactual regrets (sign depends on acting player), in place of the child values
+        for (i, (&s, v)) in strategy.iter().zip(values.iter_mut()).enumerate() {
+            *v = if self.pruned(infoset_id, i, s) {
+                last[i]
+            } else if frame.player == Player::IP {
+                frame.reach_oop * (*v - node_value)
+            } else {
+                frame.reach_ip * (node_value - *v) // OOP benefits when IP EV falls
+            };
         }
+        self.updates.record(frame.node_id, values, strategy);
+        scratch.values.truncate(frame.base);
+
+        if let (Some(p), Some(start)) = (self.profiler, frame.start) {
+            p.add_node(self.tree, frame.node_id, start.elapsed().saturating_sub(frame.child_time));
+        }
+        node_value
+    }
+}
+
+/// A decision node whose children a walk is visiting
+struct Frame<'a> {
+    node_id: NodeId,
+    infoset_id: usize,
+    player: Player,
+    children: &'a [NodeId],
+    reach_ip: f64,
+    reach_oop: f64,
+    /// Start of the node's strategy and child values in `Scratch::values`
+    base: usize,
+    /// Next child to visit
+    next: usize,
+    start: Option<Instant>,
+    child_start: Option<Instant>,
+    child_time: Duration,
+}
+
+/// Reusable stacks for a walk: decisions in progress, and each one's
+/// strategy and child values. Nothing is allocated per node once they've
+/// grown to the tree's depth.
+#[derive(Default)]
+struct Scratch<'a> {
+    values: Vec<f64>,
+    frames: Vec<Frame<'a>>,
+    /// Parallel walks this one runs inside
+    nested: usize,
+}
+
+impl Scratch<'_> {
+    /// Empty stacks for a walk `nested` parallel walks deep
+    fn nested(nested: usize) -> Self {
+        Scratch { nested, ..Scratch::default() }
+    }
+}
+
+/// How `CfrSolver::update_terminal_evs` treats the solve so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restart {
+    /// Zero regrets, strategy sums, and the iteration count, as if the
+    /// solver had just been built with the new EVs
+    Reset,
+    /// Keep regrets and strategy sums, and count on as `config.resume` says
+    /// (from the current iteration by default). Later iterations move the
+    /// strategy toward the new equilibrium; after a small change (a rake
+    /// tweak, slightly different ICM stacks) that takes far fewer iterations
+    /// than starting over.
+    WarmContinue,
+}
+
+/// Error returned when new terminal EVs leave a terminal node unvalued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingTerminalEv(pub NodeId);
+
+impl std::fmt::Display for MissingTerminalEv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no terminal EV for node {}", self.0)
     }
 }
 
+impl std::error::Error for MissingTerminalEv {}
+
 /// CFR+ solver operating on a game tree.
 pub struct CfrSolver {
     pub tree: GameTree,
     pub storage: RegretStorage,
     pub iteration: u64,
-    terminal_evs: HashMap<NodeId, f64>,
+    terminal_evs: EvTable,
+    config: SolverConfig,
+    /// Pool all parallel work runs in: injected (`with_pool`), dedicated
+    /// when `config.threads` is set, or `None` for the global pool
+    pool: Option<Arc<ThreadPool>>,
+    /// Phase timings when `config.profile` is set
+    profiler: Option<Profiler>,
+    /// Where each iteration's traversal writes its updates
+    pub(crate) updates: UpdateBuffer,
+    /// Frequencies pinned at locked infosets (see `with_locks`)
+    locks: Option<NodeLocks>,
 }
 
 impl CfrSolver {
@@ -226,40 +858,207 @@ impl CfrSolver {
         Self::new_with_evs(tree, terminal_ev_table())
     }
 
-    /// Create a solver with a custom terminal EV table.
+    /// Create a solver with a custom terminal EV table (an `EvTable`, or the
+    /// `HashMap` `build_terminal_evs` returns).
     /// Use this when solving trees other than the default 9-node test tree.
-    pub fn new_with_evs(tree: GameTree, terminal_evs: HashMap<NodeId, f64>) -> Self {
-        let num_nodes = tree.len();
-        let mut actions_per_node = vec![0usize; num_nodes];
-        for node in &tree.nodes {
-            if let Node::Decision { id, actions, .. } = node {
-                actions_per_node[*id as usize] = actions.len();
+    pub fn new_with_evs(tree: GameTree, terminal_evs: impl Into<EvTable>) -> Self {
+        Self::with_config(tree, terminal_evs, SolverConfig::new())
+    }
+
+    /// Create a solver with a custom terminal EV table and configuration.
+    ///
+    /// `config` should come from `SolverConfig::builder().build()` (already
+    /// validated). A dedicated Rayon pool is created when `config.threads` is set,
+    /// and storage is allocated as `config.storage` says.
+    pub fn with_config(tree: GameTree, terminal_evs: impl Into<EvTable>, config: SolverConfig) -> Self {
+        let terminal_evs = terminal_evs.into();
+        let pool = config.threads.map(|n| Arc::new(build_pool(n)));
+        let storage = match config.storage {
+            StorageMode::Dense => RegretStorage::for_tree(&tree),
+            StorageMode::Lazy => RegretStorage::lazy_for_tree(&tree),
+        };
+        let profiler = config.profile.then(|| Profiler::new(&tree));
+        let updates = UpdateBuffer::new(&tree);
+        CfrSolver { tree, storage, iteration: 0, terminal_evs, config, pool, profiler, updates, locks: None }
+    }
+
+    /// Run iterations and exploitability checks inside `pool` instead of the
+    /// global pool (or the one `config.threads` asked for), so an application
+    /// can keep the solver off pools its other Rayon work uses. Solvers may
+    /// share one pool.
+    pub fn with_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Play `locks`' pinned frequencies at locked infosets every iteration
+    /// (see `nodelock`)
+    pub fn with_locks(mut self, locks: NodeLocks) -> Self {
+        self.locks = Some(locks);
+        self
+    }
+
+    /// The node locks this solver plays, if any
+    pub fn locks(&self) -> Option<&NodeLocks> {
+        self.locks.as_ref()
+    }
+
+    /// Configuration this solver runs with
+    pub fn config(&self) -> &SolverConfig {
+        &self.config
+    }
+
+    /// Terminal EV table (IP perspective)
+    pub fn terminal_evs(&self) -> &EvTable {
+        &self.terminal_evs
+    }
+
+    /// Replace the terminal EV table, e.g. after a rake or ICM change, and
+    /// keep solving the same tree (see `Restart`).
+    ///
+    /// Fails, leaving the solver unchanged, if `terminal_evs` has no value
+    /// for some terminal node of the tree.
+    pub fn update_terminal_evs(&mut self, terminal_evs: impl Into<EvTable>, restart: Restart) -> Result<(), MissingTerminalEv> {
+        let terminal_evs = terminal_evs.into();
+        if let Some(node) = self.tree.nodes.iter().find(|n| n.is_terminal() && terminal_evs.get(n.id()).is_none()) {
+            return Err(MissingTerminalEv(node.id()));
+        }
+        self.terminal_evs = terminal_evs;
+        match restart {
+            Restart::Reset => self.reset(),
+            Restart::WarmContinue => self.apply_resume(self.config.algorithm),
+        }
+        Ok(())
+    }
+
+    /// Count on from inherited state as `config.resume` says, once storage
+    /// and the iteration count hold what a solve with `saved` left (see
+    /// `ResumeWeighting`)
+    pub(crate) fn apply_resume(&mut self, saved: Algorithm) {
+        match self.config.resume {
+            ResumeWeighting::Continue => {}
+            ResumeWeighting::Reweight => {
+                let saved_weight = saved.total_weight(self.iteration);
+                if saved_weight > 0.0 {
+                    self.storage.scale_strategy_sums(self.config.algorithm.total_weight(self.iteration) / saved_weight);
+                }
+            }
+            ResumeWeighting::Restart => self.iteration = 0,
+        }
+    }
+
+    /// Discard all progress: zero regrets and strategy sums and restart the
+    /// iteration count, keeping the tree, EVs, config, and locks
+    pub fn reset(&mut self) {
+        self.storage.reset();
+        self.iteration = 0;
+    }
+
+    /// Time breakdown so far; `None` unless `config.profile` is set
+    pub fn profile_report(&self) -> Option<ProfileReport> {
+        self.profiler.as_ref().map(Profiler::report)
+    }
+
+    /// Count `elapsed` as terminal evaluation in the profile (if profiling)
+    pub(crate) fn record_terminal_eval(&self, elapsed: Duration) {
+        if let Some(p) = &self.profiler {
+            p.add_terminal_eval(elapsed);
+        }
+    }
+
+    /// Exploitability of the current average strategy
+    pub fn exploitability(&self, elapsed: std::time::Duration) -> ConvergenceMetrics {
+        let start = Instant::now();
+        let compute = || {
+            compute_exploitability_with_summation(&self.tree, &self.storage, &self.terminal_evs, self.iteration, elapsed, self.config.summation)
+        };
+        let metrics = match &self.pool {
+            Some(pool) => pool.install(compute),
+            None => compute(),
+        };
+        if let Some(p) = &self.profiler {
+            p.add_exploitability(start.elapsed());
+        }
+        metrics
+    }
+
+    /// Exploitability of the current average strategy split by street (see
+    /// `street_exploitability`)
+    pub fn street_exploitability(&self) -> StreetExploitability {
+        let start = Instant::now();
+        let by_street = street_exploitability(&self.tree, &self.storage, &self.terminal_evs);
+        if let Some(p) = &self.profiler {
+            p.add_exploitability(start.elapsed());
+        }
+        by_street
+    }
+
+    /// Iterate until the configured stop condition is met.
+    ///
+    /// Exploitability is checked every `stop.check_every` iterations, at the
+    /// iteration cap, and when the time limit is hit; `on_check` sees each
+    /// result. With `stop.stagnation`, a plateau also ends the run, and with
+    /// `stop.street_targets`, so does every street meeting its target.
+    /// Returns the last metrics and why the run stopped.
+    pub fn run(&mut self, mut on_check: impl FnMut(&ConvergenceMetrics)) -> (ConvergenceMetrics, StopReason) {
+        let stop = self.config.stop;
+        let start = Instant::now();
+        let mut last = None;
+        let mut plateau = StagnationTracker::new(stop.stagnation);
+        for iter in 1..=stop.max_iterations {
+            self.run_iteration();
+            let elapsed = start.elapsed();
+            let timed_out = stop.time_limit.is_some_and(|limit| elapsed >= limit);
+            if iter.is_multiple_of(stop.check_every) || timed_out || iter == stop.max_iterations {
+                let m = self.exploitability(elapsed);
+                on_check(&m);
+                let converged = stop.target_exploitability.is_some_and(|t| m.exploitability < t);
+                if converged {
+                    return (m, StopReason::ExploitabilityTarget);
+                }
+                if stop.street_targets.is_some_and(|targets| targets.met(&self.street_exploitability())) {
+                    return (m, StopReason::StreetTargets);
+                }
+                if timed_out {
+                    return (m, StopReason::TimeLimit);
+                }
+                if plateau.observe(m.exploitability) {
+                    return (m, StopReason::Stagnated);
+                }
+                last = Some(m);
             }
         }
-        let storage = RegretStorage::new(num_nodes, &actions_per_node);
-        CfrSolver { tree, storage, iteration: 0, terminal_evs }
+        let m = last.unwrap_or_else(|| self.exploitability(start.elapsed()));
+        (m, StopReason::IterationCap)
     }
 
     /// Run one CFR+ iteration (increments `self.iteration` before traversal).
     ///
-    /// Internally uses a functional traversal that collects all regret/strategy
-    /// updates and applies them sequentially. Chance node subtrees are traversed
-    /// in parallel via Rayon.
+    /// The traversal records every regret/strategy update in the solver's
+    /// `UpdateBuffer`, which is then merged into storage in one pass. Chance
+    /// node subtrees are traversed in parallel via Rayon.
     pub fn run_iteration(&mut self) {
         self.iteration += 1;
         let t = self.iteration;
-        let (_, updates) = cfr_traverse_fn(
-            &self.tree,
-            &self.storage,
-            &self.terminal_evs,
-            0,
-            1.0,
-            1.0,
-            t,
-        );
-        for u in updates {
-            self.storage.update_regrets(u.infoset_id, &u.cf_values);
-            self.storage.accumulate_strategy(u.infoset_id, &u.strategy, u.weight);
+        self.updates.begin();
+        let traversal = Traversal {
+            prune: self.config.prune_threshold(t),
+            profiler: self.profiler.as_ref(),
+            summation: self.config.summation,
+            algorithm: self.config.algorithm,
+            locks: self.locks.as_ref(),
+            ..Traversal::new(&self.tree, &self.storage, &self.terminal_evs, &self.updates)
+        };
+        let traverse = || traversal.run(0, 1.0, 1.0);
+        let start = Instant::now();
+        match &self.pool {
+            Some(pool) => pool.install(traverse),
+            None => traverse(),
+        };
+        let traversed = Instant::now();
+        self.storage.merge(&self.updates, self.config.algorithm, t);
+        if let Some(p) = &self.profiler {
+            p.add_iteration(traversed - start, traversed.elapsed());
         }
     }
 }
@@ -267,15 +1066,48 @@ impl CfrSolver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{StopCondition, StreetTargets};
+    use crate::node::Street;
     use crate::test_tree::{build_test_tree, build_test_tree_chance, terminal_ev_table_chance};
+    use std::time::Duration;
 
     fn make_storage(actions: &[usize]) -> RegretStorage {
-        RegretStorage::new(actions.len(), actions)
+        RegretStorage::new(actions)
+    }
+
+    #[test]
+    fn test_compensated_summation_matches_exact_chance_value() {
+        // A deal of 10,001 cards: one worth 1bb, the rest 1e-16bb each
+        let n = 10_001u32;
+        let mut nodes = vec![Node::Chance {
+            id: 0, parent: None, children: (1..=n).collect(), weights: vec![], street: Street::Turn,
+            pot: 2.0, stacks: [1.0, 1.0], board: vec![],
+        }];
+        let mut evs = HashMap::new();
+        for id in 1..=n {
+            nodes.push(Node::Terminal {
+                id, parent: Some(0), folder: None, pot: 2.0, stacks: [1.0, 1.0], board: vec![], hole_cards: [None, None],
+            });
+            evs.insert(id, if id == 1 { 1.0 } else { 1e-16 });
+        }
+        let tree = GameTree { nodes };
+        let storage = RegretStorage::for_tree(&tree);
+        let exact = (1.0 + 1e-12) / n as f64;
+        let mut updates = UpdateBuffer::new(&tree);
+        updates.begin();
+        let table = EvTable::from(&evs);
+        let value = |summation| Traversal { summation, ..Traversal::new(&tree, &storage, &table, &updates) }.run(0, 1.0, 1.0);
+        let br = |summation| compute_exploitability_with_summation(&tree, &storage, &evs, 0, Duration::ZERO, summation).ip_br_value;
+
+        let naive_error = (value(Summation::Naive) - exact).abs();
+        assert!(naive_error > 1e-17, "{}", naive_error);
+        assert!((value(Summation::Compensated) - exact).abs() < naive_error / 1000.0);
+        assert!((br(Summation::Compensated) - exact).abs() < (br(Summation::Naive) - exact).abs() / 1000.0);
     }
 
     #[test]
     fn test_regret_matching_uniform_initial() {
-        let s = make_storage(&[0, 0, 2]); // node 2 has 2 actions
+        let s = make_storage(&[1, 1, 2]); // infoset 2 has 2 actions
         let strategy = s.current_strategy(2);
         assert!((strategy[0] - 0.5).abs() < 1e-10);
         assert!((strategy[1] - 0.5).abs() < 1e-10);
@@ -283,7 +1115,7 @@ mod tests {
 
     #[test]
     fn test_regret_matching_proportional() {
-        let mut s = make_storage(&[0, 0, 2]);
+        let mut s = make_storage(&[1, 1, 2]);
         s.regrets[2] = vec![2.0, 1.0];
         let strategy = s.current_strategy(2);
         assert!((strategy[0] - 2.0 / 3.0).abs() < 1e-10);
@@ -292,22 +1124,37 @@ mod tests {
 
     #[test]
     fn test_strategy_sums_to_one() {
-        let mut s = make_storage(&[0, 0, 3]);
+        let mut s = make_storage(&[1, 1, 3]);
         s.regrets[2] = vec![1.0, 0.5, 0.0];
         let strategy = s.current_strategy(2);
         let sum: f64 = strategy.iter().sum();
         assert!((sum - 1.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_iteration_strategy_into_matches_allocating_strategies() {
+        let mut s = make_storage(&[4]);
+        s.regrets[0] = vec![0.3, 0.0, 1.7, 0.1];
+        s.last_regrets[0] = vec![-0.5, 0.2, 0.4, -0.1];
+        let mut out = [0.0; 4];
+        s.iteration_strategy_into(0, Algorithm::CfrPlus, &mut out);
+        assert_eq!(out.to_vec(), s.current_strategy(0));
+        s.iteration_strategy_into(0, Algorithm::PredictiveCfrPlus, &mut out);
+        assert_eq!(out.to_vec(), s.predictive_strategy(0));
+        s.regrets[0] = vec![0.0; 4];
+        s.iteration_strategy_into(0, Algorithm::CfrPlus, &mut out);
+        assert_eq!(out, [0.25; 4]);
+    }
+
     #[test]
     fn test_cfr_plus_negative_floor() {
         let mut s = make_storage(&[2]);
         s.regrets[0] = vec![0.5, -1.0];
         s.update_regrets(0, &[-2.0, 3.0]);
         // 0.5 + (-2.0) = -1.5 → floored to 0.0
-        assert!((s.regrets[0][0] - 0.0).abs() < 1e-10);
+        assert!((s.regrets(0)[0] - 0.0).abs() < 1e-10);
         // -1.0 + 3.0 = 2.0 → unchanged
-        assert!((s.regrets[0][1] - 2.0).abs() < 1e-10);
+        assert!((s.regrets(0)[1] - 2.0).abs() < 1e-10);
     }
 
     #[test]
@@ -316,9 +1163,30 @@ mod tests {
         s.accumulate_strategy(0, &[0.6, 0.4], 1);
         s.accumulate_strategy(0, &[0.5, 0.5], 2);
         // S[0] = 1*0.6 + 2*0.5 = 1.6
-        assert!((s.strategy_sums[0][0] - 1.6).abs() < 1e-10);
+        assert!((s.strategy_sum(0)[0] - 1.6).abs() < 1e-10);
         // S[1] = 1*0.4 + 2*0.5 = 1.4
-        assert!((s.strategy_sums[0][1] - 1.4).abs() < 1e-10);
+        assert!((s.strategy_sum(0)[1] - 1.4).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_and_reset() {
+        let mut solver = CfrSolver::new(build_test_tree());
+        for _ in 0..50 {
+            solver.run_iteration();
+        }
+        let snap = solver.storage.snapshot();
+        assert_eq!(snap.regrets.len(), solver.storage.num_infosets());
+        assert_eq!(snap.strategy_sums[0], solver.storage.strategy_sum(0));
+        // One slot per decision node, none for terminals
+        assert_eq!(solver.storage.num_infosets(), 4);
+
+        let restored = RegretStorage::from_snapshot(snap.clone());
+        assert_eq!(restored.average_strategy(0), solver.storage.average_strategy(0));
+
+        solver.storage.reset();
+        assert!(solver.storage.strategy_sum(0).iter().all(|&x| x == 0.0));
+        assert!(solver.storage.regrets(1).iter().all(|&x| x == 0.0));
+        assert_eq!(solver.storage.regrets(0).len(), snap.regrets[0].len());
     }
 
     #[test]
@@ -354,13 +1222,218 @@ mod tests {
             solver.run_iteration();
         }
         // All decision node average strategies should sum to ~1.0
-        for &id in &[0usize, 1, 3, 6] {
+        for id in 0..4 {
             let avg = solver.storage.average_strategy(id);
             let sum: f64 = avg.iter().sum();
             assert!((sum - 1.0).abs() < 1e-6, "node {} strategy sum = {}", id, sum);
         }
     }
 
+    #[test]
+    fn test_update_buffer_merges_like_collected_updates() {
+        let tree = build_test_tree_chance();
+        let evs = EvTable::from(terminal_ev_table_chance());
+        let mut merged = RegretStorage::for_tree(&tree);
+        let mut applied = RegretStorage::for_tree(&tree);
+        let mut buffer = UpdateBuffer::new(&tree);
+        for t in 1..=50 {
+            buffer.begin();
+            Traversal::new(&tree, &merged, &evs, &buffer).run(0, 1.0, 1.0);
+            let collected = buffer.updates(&tree, 0, t);
+            assert_eq!(collected.len(), tree.num_infosets());
+            applied.apply(&collected, Algorithm::CfrPlus, t);
+            merged.merge(&buffer, Algorithm::CfrPlus, t);
+            assert_eq!(merged.snapshot(), applied.snapshot());
+        }
+        // A new traversal starts empty: slots written before `begin` don't merge again
+        buffer.begin();
+        merged.merge(&buffer, Algorithm::CfrPlus, 51);
+        assert_eq!(merged.snapshot(), applied.snapshot());
+    }
+
+    #[test]
+    fn test_lazy_storage_matches_dense() {
+        let tree = build_test_tree_chance();
+        let evs = terminal_ev_table_chance();
+        let config = SolverConfig::builder().storage(StorageMode::Lazy).build().unwrap();
+        let mut lazy = CfrSolver::with_config(tree.clone(), evs.clone(), config);
+        assert!(lazy.storage.is_lazy());
+        assert_eq!(lazy.storage.allocated_infosets(), 0);
+        // Untouched infosets read as zeros of the right width
+        assert_eq!(lazy.storage.snapshot(), RegretStorage::for_tree(&tree).snapshot());
+        assert_eq!(lazy.storage.average_strategy(0), vec![0.5, 0.5]);
+
+        let mut dense = CfrSolver::new_with_evs(tree, evs);
+        for _ in 0..100 {
+            lazy.run_iteration();
+            dense.run_iteration();
+        }
+        assert_eq!(lazy.storage.snapshot(), dense.storage.snapshot());
+        assert_eq!(lazy.storage.allocated_infosets(), lazy.storage.num_infosets());
+    }
+
+    #[test]
+    fn test_with_config_threads_match_global_pool() {
+        let tree = build_test_tree_chance();
+        let evs = terminal_ev_table_chance();
+        let config = SolverConfig::builder().threads(2).build().unwrap();
+        let mut pooled = CfrSolver::with_config(tree.clone(), evs.clone(), config);
+        let mut global = CfrSolver::new_with_evs(tree, evs);
+        for _ in 0..200 {
+            pooled.run_iteration();
+            global.run_iteration();
+        }
+        for id in 0..4 {
+            assert_eq!(pooled.storage.average_strategy(id), global.storage.average_strategy(id));
+        }
+    }
+
+    #[test]
+    fn test_river_tree_splits_decisions_and_matches_serial() {
+        use crate::builder::{build_tree, StreetSizes, TreeConfig};
+        use crate::game::{build_terminal_evs, GameConfig};
+        use crate::node::parse_cards;
+
+        // No chance nodes, so only decisions can spread the walk
+        let mut tree_config = TreeConfig::new(GameConfig::from_pot(2.0, [500.0, 500.0]), parse_cards("AsKh7d2c9s").unwrap());
+        tree_config.river = StreetSizes { raise_sizes: vec![0.5, 1.0], allow_all_in: true, ..StreetSizes::new(vec![0.25, 0.5, 0.75, 1.25]) };
+        tree_config.max_raises = 4;
+        let tree = build_tree(&tree_config);
+        assert!(tree.nodes.iter().all(|n| !matches!(n, Node::Chance { .. })));
+        assert!(subtree_sizes(&tree)[0] >= PARALLEL_SUBTREE, "{} nodes", tree.len());
+        let evs = build_terminal_evs(&tree, |node: &Node| f64::from(node.pot() as u32 % 7) / 7.0);
+        let solve = |threads| {
+            let config = SolverConfig::builder().threads(threads).build().unwrap();
+            let mut solver = CfrSolver::with_config(tree.clone(), evs.clone(), config);
+            for _ in 0..50 {
+                solver.run_iteration();
+            }
+            solver.storage.snapshot()
+        };
+        assert_eq!(solve(4), solve(1));
+    }
+
+    #[test]
+    fn test_injected_pool_is_shared_and_matches_global() {
+        let tree = build_test_tree_chance();
+        let evs = terminal_ev_table_chance();
+        let pool = Arc::new(build_pool(2));
+        let mut a = CfrSolver::new_with_evs(tree.clone(), evs.clone()).with_pool(Arc::clone(&pool));
+        let mut b = CfrSolver::new_with_evs(tree.clone(), evs.clone()).with_pool(Arc::clone(&pool));
+        assert_eq!(Arc::strong_count(&pool), 3);
+        let mut global = CfrSolver::new_with_evs(tree, evs);
+        for _ in 0..200 {
+            a.run_iteration();
+            b.run_iteration();
+            global.run_iteration();
+        }
+        for id in 0..4 {
+            assert_eq!(a.storage.average_strategy(id), global.storage.average_strategy(id));
+            assert_eq!(b.storage.average_strategy(id), global.storage.average_strategy(id));
+        }
+        assert_eq!(a.exploitability(Duration::ZERO).exploitability, global.exploitability(Duration::ZERO).exploitability);
+    }
+
+    #[test]
+    fn test_pruning_skips_dominated_lines_and_still_converges() {
+        use crate::builder::{build_tree, StreetSizes, TreeConfig};
+        use crate::game::{build_terminal_evs, GameConfig};
+        use crate::node::parse_cards;
+
+        // IP always wins at showdown, so OOP's betting lines only lose chips
+        let mut tree_config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c9s").unwrap());
+        tree_config.river = StreetSizes::new(vec![0.5, 1.0]);
+        let tree = build_tree(&tree_config);
+        let evs = build_terminal_evs(&tree, |_| 1.0);
+        let config = SolverConfig::builder().max_iterations(3_000).no_target().prune(0.0, 50).build().unwrap();
+        let mut pruned = CfrSolver::with_config(tree.clone(), evs.clone(), config);
+        let mut full = CfrSolver::new_with_evs(tree, evs);
+        for _ in 0..3_000 {
+            pruned.run_iteration();
+            full.run_iteration();
+        }
+
+        let traverse = |prune| {
+            let mut buffer = UpdateBuffer::new(&pruned.tree);
+            buffer.begin();
+            Traversal { prune, ..Traversal::new(&pruned.tree, &pruned.storage, pruned.terminal_evs(), &buffer) }.run(0, 1.0, 1.0);
+            buffer.updates(&pruned.tree, 0, 1).len()
+        };
+        assert!(traverse(Some(0.0)) < traverse(None), "nothing was pruned");
+        let (p, f) = (pruned.exploitability(Duration::ZERO), full.exploitability(Duration::ZERO));
+        assert!(p.exploitability < 0.01 && (p.exploitability - f.exploitability).abs() < 0.01, "{} vs {}", p.exploitability, f.exploitability);
+    }
+
+    #[test]
+    fn test_deep_raise_war_runs_on_a_small_stack() {
+        use crate::node::Action;
+        // 20,000 decisions deep: each player folds or raises until the last raise
+        let depth: u32 = 20_000;
+        let mut nodes = Vec::new();
+        let mut evs = HashMap::new();
+        for i in 0..depth {
+            let (id, player) = (2 * i, if i % 2 == 0 { Player::OOP } else { Player::IP });
+            nodes.push(Node::Decision {
+                id, infoset_id: i, player, street: Street::River, parent: id.checked_sub(2),
+                children: vec![id + 1, id + 2], actions: vec![Action::Fold, Action::Raise { size: 1.0 }],
+                pot: 2.0, stacks: [1.0, 1.0], board: vec![], bet_sequence: vec![],
+            });
+            nodes.push(Node::Terminal {
+                id: id + 1, parent: Some(id), folder: Some(player), pot: 2.0, stacks: [1.0, 1.0], board: vec![], hole_cards: [None, None],
+            });
+            evs.insert(id + 1, if player == Player::IP { -1.0 } else { 1.0 });
+        }
+        nodes.push(Node::Terminal {
+            id: 2 * depth, parent: Some(2 * depth - 2), folder: None, pot: 2.0, stacks: [1.0, 1.0], board: vec![], hole_cards: [None, None],
+        });
+        evs.insert(2 * depth, 0.25);
+        let tree = GameTree { nodes };
+
+        let metrics = std::thread::Builder::new().stack_size(64 * 1024).spawn(move || {
+            let mut solver = CfrSolver::new_with_evs(tree, evs);
+            for _ in 0..3 {
+                solver.run_iteration();
+            }
+            solver.exploitability(Duration::ZERO)
+        }).unwrap().join().expect("deep traversal overflowed the stack");
+        assert!(metrics.exploitability.is_finite() && metrics.exploitability >= 0.0);
+    }
+
+    #[test]
+    fn test_run_stops_on_target() {
+        let config = SolverConfig::builder()
+            .max_iterations(50_000)
+            .target_exploitability(0.01)
+            .check_every(100)
+            .build()
+            .unwrap();
+        let mut solver = CfrSolver::with_config(build_test_tree(), terminal_ev_table(), config);
+        let mut checks = 0;
+        let (metrics, reason) = solver.run(|_| checks += 1);
+        assert_eq!(reason, StopReason::ExploitabilityTarget);
+        assert!(metrics.exploitability < 0.01);
+        assert_eq!(solver.iteration, checks * 100);
+    }
+
+    #[test]
+    fn test_run_stops_on_street_targets() {
+        let targets = StreetTargets { flop: Some(0.01), ..StreetTargets::default() };
+        let config = SolverConfig::builder().max_iterations(50_000).no_target().street_targets(targets).check_every(100).build().unwrap();
+        let mut solver = CfrSolver::with_config(build_test_tree(), terminal_ev_table(), config);
+        let (metrics, reason) = solver.run(|_| {});
+        assert_eq!(reason, StopReason::StreetTargets);
+        // Every decision is on the flop, so the flop share is the whole
+        let by_street = solver.street_exploitability();
+        assert!(by_street.flop <= 0.01 && (by_street.flop - metrics.exploitability).abs() < 1e-12);
+
+        // Targets on streets the tree doesn't have hold trivially
+        let targets = StreetTargets { river: Some(0.0), ..StreetTargets::default() };
+        let config = SolverConfig::builder().no_target().street_targets(targets).check_every(10).build().unwrap();
+        let mut solver = CfrSolver::with_config(build_test_tree(), terminal_ev_table(), config);
+        assert_eq!(solver.run(|_| {}).1, StopReason::StreetTargets);
+        assert_eq!(solver.iteration, 10);
+    }
+
     #[test]
     fn test_cfr_solver_chance_tree_strategies_evolve() {
         let tree = build_test_tree_chance();
@@ -384,11 +1457,102 @@ mod tests {
         for _ in 0..5_000 {
             solver.run_iteration();
         }
-        // Decision node infosets: 0, 2, 5, 8 — average strategies should sum to ~1.0
-        for &id in &[0usize, 2, 5, 8] {
+        // Decision nodes 0, 2, 5, 8 are infosets 0..4 — average strategies should sum to ~1.0
+        for id in 0..4 {
             let avg = solver.storage.average_strategy(id);
             let sum: f64 = avg.iter().sum();
             assert!((sum - 1.0).abs() < 1e-6, "chance tree node {} strategy sum = {}", id, sum);
         }
     }
+
+    #[test]
+    fn test_predictive_cfr_plus_converges_faster() {
+        use crate::builder::{build_tree, StreetSizes, TreeConfig};
+        use crate::config::Algorithm;
+        use crate::game::{build_terminal_evs, GameConfig};
+        use crate::node::parse_cards;
+
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5, 1.0]);
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 2;
+        let tree = build_tree(&config);
+        let evs = build_terminal_evs(&tree, |node: &Node| {
+            f64::from(node.board().iter().map(|c| u32::from(c.value())).sum::<u32>() % 101) / 100.0
+        });
+        let exploitability = |algorithm| {
+            let config = SolverConfig::builder().algorithm(algorithm).build().unwrap();
+            let mut solver = CfrSolver::with_config(tree.clone(), evs.clone(), config);
+            for _ in 0..200 {
+                solver.run_iteration();
+            }
+            solver.exploitability(Duration::ZERO).exploitability
+        };
+        let (plus, predictive) = (exploitability(Algorithm::CfrPlus), exploitability(Algorithm::PredictiveCfrPlus));
+        assert!(predictive < plus / 2.0, "PCFR+ {} vs CFR+ {}", predictive, plus);
+    }
+
+    #[test]
+    fn test_update_terminal_evs_resets_or_warm_continues() {
+        use crate::builder::{build_tree, StreetSizes, TreeConfig};
+        use crate::game::{build_terminal_evs, GameConfig};
+        use crate::node::parse_cards;
+
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c9s").unwrap());
+        config.river = StreetSizes::new(vec![0.5, 1.0]);
+        let tree = build_tree(&config);
+        let equity = |shift: f64| move |node: &Node| (f64::from(node.board().len() as u32 % 3) * 0.3 + shift).min(1.0);
+        let evs = build_terminal_evs(&tree, equity(0.1));
+        let new_evs = build_terminal_evs(&tree, equity(0.15));
+        let stop = StopCondition { target_exploitability: Some(1e-3), check_every: 10, ..StopCondition::iterations(5_000) };
+        let config = SolverConfig::builder().stop(stop).build().unwrap();
+
+        let mut fresh = CfrSolver::with_config(tree.clone(), new_evs.clone(), config.clone());
+        let (cold, _) = fresh.run(|_| {});
+
+        let mut solver = CfrSolver::with_config(tree.clone(), evs.clone(), config);
+        solver.run(|_| {});
+        let before = solver.iteration;
+        solver.update_terminal_evs(new_evs.clone(), Restart::WarmContinue).unwrap();
+        assert_eq!(solver.iteration, before);
+        let (warm, reason) = solver.run(|_| {});
+        assert_eq!(reason, StopReason::ExploitabilityTarget);
+        assert!(warm.iterations - before < cold.iterations, "warm {} vs cold {}", warm.iterations - before, cold.iterations);
+
+        solver.update_terminal_evs(new_evs.clone(), Restart::Reset).unwrap();
+        assert_eq!(solver.iteration, 0);
+        assert!(solver.storage.strategy_sum(0).iter().all(|&s| s == 0.0));
+        let (again, _) = solver.run(|_| {});
+        assert_eq!(again.iterations, cold.iterations);
+
+        let mut partial = new_evs;
+        let terminal = tree.nodes.iter().find(|n| n.is_terminal()).unwrap().id();
+        partial.remove(&terminal);
+        assert_eq!(solver.update_terminal_evs(partial, Restart::Reset), Err(MissingTerminalEv(terminal)));
+        assert_eq!(solver.iteration, again.iterations);
+    }
+
+    #[test]
+    fn test_linear_and_discounted_cfr_converge() {
+        use crate::config::Algorithm;
+
+        let tree = build_test_tree_chance();
+        let evs = terminal_ev_table_chance();
+        let exploitability = |algorithm, iterations| {
+            let config = SolverConfig::builder().algorithm(algorithm).build().unwrap();
+            let mut solver = CfrSolver::with_config(tree.clone(), evs.clone(), config);
+            for _ in 0..iterations {
+                solver.run_iteration();
+            }
+            solver.exploitability(Duration::ZERO).exploitability
+        };
+        for algorithm in [Algorithm::LinearCfr, Algorithm::DiscountedCfr] {
+            let (early, late) = (exploitability(algorithm, 10), exploitability(algorithm, 500));
+            assert!(late < early / 10.0 && late < 1e-2, "{:?}: {} after 10, {} after 500", algorithm, early, late);
+        }
+        // LCFR keeps negative regrets; CFR+ floors them
+        assert_eq!(Algorithm::LinearCfr.accumulate_regret(1.0, -3.0, 2), -5.0);
+        assert_eq!(Algorithm::CfrPlus.accumulate_regret(1.0, -3.0, 2), 0.0);
+        assert_eq!(Algorithm::DiscountedCfr.accumulate_regret(1.0, -3.0, 2), -1.0);
+    }
 }
@@ -0,0 +1,186 @@
+//! Grading a player's decision against the solution
+//!
+//! A trainer shows a spot, the user picks an action for their hand, and
+//! `grade_action` says how much that cost. It asks the vector solver for
+//! the hand's EV of every action at the node (`hand_action_values`), then
+//! compares the chosen action with the best one and with the solver's own
+//! mix for that hand. The grade comes from the loss against the best action
+//! as a fraction of the pot, so the same leak reads the same in a 5bb pot
+//! and a 100bb pot.
+
+use crate::node::{Action, Card, Node, NodeId};
+use crate::vector_cfr::VectorCfrSolver;
+
+/// Loss (fraction of the pot) up to which an action is still `Grade::Correct`
+pub const CORRECT_LOSS: f64 = 0.01;
+/// Loss up to which a wrong action is an `Inaccuracy`
+pub const INACCURACY_LOSS: f64 = 0.05;
+/// Loss up to which a wrong action is a `Mistake`; anything more is a `Blunder`
+pub const MISTAKE_LOSS: f64 = 0.15;
+
+/// Severity of a decision, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Grade {
+    Correct,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Grade {
+    /// Every grade, best first
+    pub const ALL: [Grade; 4] = [Grade::Correct, Grade::Inaccuracy, Grade::Mistake, Grade::Blunder];
+
+    /// Grade for losing `loss` bb in a pot of `pot` bb
+    pub fn from_loss(loss: f64, pot: f64) -> Self {
+        let fraction = if pot > 0.0 { loss / pot } else { loss };
+        if fraction <= CORRECT_LOSS {
+            Grade::Correct
+        } else if fraction <= INACCURACY_LOSS {
+            Grade::Inaccuracy
+        } else if fraction <= MISTAKE_LOSS {
+            Grade::Mistake
+        } else {
+            Grade::Blunder
+        }
+    }
+}
+
+impl std::fmt::Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Grade::Correct => "correct",
+            Grade::Inaccuracy => "inaccuracy",
+            Grade::Mistake => "mistake",
+            Grade::Blunder => "blunder",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Error returned when a decision can't be graded
+#[derive(Debug, Clone, PartialEq)]
+pub enum CoachError {
+    /// The node isn't a decision node
+    NotDecision(NodeId),
+    /// The action index is out of range for the node
+    NoSuchAction { node: NodeId, action: usize },
+    /// The acting player's range doesn't hold the hand, or no opponent
+    /// combo reaches the node alongside it
+    HandNotInSpot([Card; 2]),
+}
+
+impl std::fmt::Display for CoachError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoachError::NotDecision(node) => write!(f, "node {} is not a decision", node),
+            CoachError::NoSuchAction { node, action } => write!(f, "node {} has no action {}", node, action),
+            CoachError::HandNotInSpot(hand) => write!(f, "{}{} can't be at this spot", hand[0], hand[1]),
+        }
+    }
+}
+
+impl std::error::Error for CoachError {}
+
+/// How one chosen action compares with the solution
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionGrade {
+    pub node: NodeId,
+    /// Index of the chosen action
+    pub action: usize,
+    pub actions: Vec<Action>,
+    /// The hand's EV (bb) of each action
+    pub action_evs: Vec<f64>,
+    /// The solver's frequencies for the hand
+    pub strategy: Vec<f64>,
+    /// Index of the highest-EV action
+    pub best: usize,
+    /// EV of the solver's mix
+    pub mixed_ev: f64,
+    /// Best action's EV minus the chosen one's (never negative)
+    pub loss_vs_best: f64,
+    /// Mix EV minus the chosen one's (negative when the choice beats the mix)
+    pub loss_vs_mix: f64,
+    pub grade: Grade,
+}
+
+impl ActionGrade {
+    pub fn chosen_ev(&self) -> f64 {
+        self.action_evs[self.action]
+    }
+}
+
+/// Grade taking action `action` at decision `node` holding `hand`
+pub fn grade_action(solver: &VectorCfrSolver, node: NodeId, action: usize, hand: [Card; 2]) -> Result<ActionGrade, CoachError> {
+    let Some(Node::Decision { actions, pot, .. }) = solver.tree.get(node) else {
+        return Err(CoachError::NotDecision(node));
+    };
+    if action >= actions.len() {
+        return Err(CoachError::NoSuchAction { node, action });
+    }
+    let action_evs = solver.hand_action_values(node, hand).ok_or(CoachError::HandNotInSpot(hand))?;
+    let strategy = solver.hand_strategy(node, hand).ok_or(CoachError::HandNotInSpot(hand))?;
+    let best = action_evs.iter().enumerate().fold(0, |best, (a, &ev)| if ev > action_evs[best] { a } else { best });
+    let mixed_ev: f64 = action_evs.iter().zip(&strategy).map(|(ev, s)| ev * s).sum();
+    let loss_vs_best = action_evs[best] - action_evs[action];
+    Ok(ActionGrade {
+        node,
+        action,
+        actions: actions.clone(),
+        best,
+        mixed_ev,
+        loss_vs_best,
+        loss_vs_mix: mixed_ev - action_evs[action],
+        grade: Grade::from_loss(loss_vs_best, *pot),
+        action_evs,
+        strategy,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+    use crate::range::Range;
+
+    fn hand(s: &str) -> [Card; 2] {
+        parse_cards(s).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_grades_folding_a_set_as_a_blunder() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c9s").unwrap());
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 1;
+        let ranges = [Range::parse("AK,77,QJs").unwrap(), Range::parse("AQ,KQ,99").unwrap()];
+        let mut solver = VectorCfrSolver::new(build_tree(&config), &ranges);
+        for _ in 0..300 {
+            solver.run_iteration();
+        }
+        // OOP checks, IP bets: OOP faces the bet with [Fold, Call]
+        let check = solver.tree.get(0).unwrap().children()[0];
+        let facing = solver.tree.get(check).unwrap().children()[1];
+        let Some(Node::Decision { actions, .. }) = solver.tree.get(facing) else { panic!("facing a bet is a decision") };
+        assert_eq!(actions[0], Action::Fold);
+
+        // OOP's set of nines beats all of IP's range: calling is best, folding gives up the pot
+        let nuts = hand("9h9d");
+        let call = grade_action(&solver, facing, 1, nuts).unwrap();
+        assert_eq!((call.best, call.grade, call.loss_vs_best), (1, Grade::Correct, 0.0));
+        let fold = grade_action(&solver, facing, 0, nuts).unwrap();
+        assert_eq!(fold.grade, Grade::Blunder);
+        // Values are zero-sum from the root: folding hands IP the 5.5bb starting pot
+        assert!((fold.chosen_ev() + 5.5).abs() < 1e-9, "{}", fold.chosen_ev());
+        assert!(fold.loss_vs_best > 0.0 && (fold.loss_vs_mix - (fold.mixed_ev - fold.chosen_ev())).abs() < 1e-12);
+        assert!((fold.strategy.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        assert_eq!(grade_action(&solver, facing, 2, nuts), Err(CoachError::NoSuchAction { node: facing, action: 2 }));
+        assert_eq!(grade_action(&solver, facing, 0, hand("AdKd")), Err(CoachError::HandNotInSpot(hand("AdKd"))));
+        let terminal = solver.tree.get(check).unwrap().children()[0];
+        assert_eq!(grade_action(&solver, terminal, 0, nuts), Err(CoachError::NotDecision(terminal)));
+        assert_eq!(Grade::from_loss(0.1, 10.0), Grade::Correct);
+        assert_eq!(Grade::from_loss(1.0, 10.0), Grade::Mistake);
+    }
+}
@@ -0,0 +1,173 @@
+//! Canonical hole-card combo indexing
+//!
+//! Every two-card combo has a dense index in `0..1326`, so per-combo data
+//! (range weights, strategies, reach vectors) lives in flat arrays indexed
+//! by combo. The layout is triangular over raw card values:
+//! `hi * (hi - 1) / 2 + lo`.
+//!
+//! Combos also group into the 169 preflop classes ("AKs", "T9o", "77"),
+//! indexed row-major on the 13x13 grid: row and column 0 are aces, the
+//! diagonal holds pairs, suited hands sit above it and offsuit hands below.
+
+use crate::node::{Card, RANK_CHARS};
+
+/// Number of distinct two-card combos in a 52-card deck (C(52, 2))
+pub const NUM_COMBOS: usize = 1326;
+
+/// Number of preflop hand classes (13 pairs, 78 suited, 78 offsuit)
+pub const NUM_CLASSES: usize = 169;
+
+/// Dense index of a two-card combo (order-independent).
+///
+/// Uses the triangular layout `hi * (hi - 1) / 2 + lo` over raw card values,
+/// which maps the 1326 combos onto 0..1326 without gaps.
+pub fn combo_index(a: Card, b: Card) -> usize {
+    let (hi, lo) = if a.value() > b.value() { (a.value(), b.value()) } else { (b.value(), a.value()) };
+    debug_assert!(hi != lo, "combo cards must differ");
+    let hi = hi as usize;
+    hi * (hi - 1) / 2 + lo as usize
+}
+
+/// Inverse of `combo_index`: the two cards (higher value first) of combo `idx`.
+pub fn combo_cards(idx: usize) -> [Card; 2] {
+    debug_assert!(idx < NUM_COMBOS);
+    // Largest hi with hi * (hi - 1) / 2 <= idx
+    let mut hi = ((1.0 + (1.0 + 8.0 * idx as f64).sqrt()) / 2.0) as usize;
+    while hi * (hi - 1) / 2 > idx {
+        hi -= 1;
+    }
+    while (hi + 1) * hi / 2 <= idx {
+        hi += 1;
+    }
+    let lo = idx - hi * (hi - 1) / 2;
+    [Card::new(hi as u8), Card::new(lo as u8)]
+}
+
+/// Card mask of combo `idx`
+pub fn combo_mask(idx: usize) -> u64 {
+    let [a, b] = combo_cards(idx);
+    a.mask() | b.mask()
+}
+
+/// Per combo, whether it shares a card with `board`
+pub fn blocked_combos(board: &[Card]) -> Vec<bool> {
+    let dead = board.iter().fold(0u64, |m, c| m | c.mask());
+    (0..NUM_COMBOS).map(|i| combo_mask(i) & dead != 0).collect()
+}
+
+/// Zero the entries of a per-combo array whose combo `board` blocks
+pub fn mask_blocked(values: &mut [f64], board: &[Card]) {
+    assert_eq!(values.len(), NUM_COMBOS, "values must have one entry per combo");
+    for (value, blocked) in values.iter_mut().zip(blocked_combos(board)) {
+        if blocked {
+            *value = 0.0;
+        }
+    }
+}
+
+/// Preflop class of a combo: `row * 13 + col` on the 13x13 grid
+pub fn class_index(hand: [Card; 2]) -> usize {
+    let (r1, r2) = (hand[0].rank() as usize, hand[1].rank() as usize);
+    let (hi, lo) = (r1.max(r2), r1.min(r2));
+    if hi == lo || hand[0].suit() == hand[1].suit() {
+        (12 - hi) * 13 + (12 - lo)
+    } else {
+        (12 - lo) * 13 + (12 - hi)
+    }
+}
+
+/// Preflop class of combo `idx`
+pub fn class_of_combo(idx: usize) -> usize {
+    class_index(combo_cards(idx))
+}
+
+/// Ranks (high, low) of a class and whether it is suited; pairs are not
+fn class_ranks(class: usize) -> (u8, u8, bool) {
+    assert!(class < NUM_CLASSES, "class index out of range");
+    let (row, col) = (class / 13, class % 13);
+    let (r_row, r_col) = ((12 - row) as u8, (12 - col) as u8);
+    if row <= col {
+        (r_row, r_col, row < col)
+    } else {
+        (r_col, r_row, false)
+    }
+}
+
+/// Label of a class: "AA", "AKs", "AKo"
+pub fn class_label(class: usize) -> String {
+    let (hi, lo, suited) = class_ranks(class);
+    let (h, l) = (RANK_CHARS[hi as usize], RANK_CHARS[lo as usize]);
+    if hi == lo {
+        format!("{}{}", h, l)
+    } else {
+        format!("{}{}{}", h, l, if suited { 's' } else { 'o' })
+    }
+}
+
+/// All concrete combos of a class (6 pairs, 4 suited, or 12 offsuit)
+pub fn class_combos(class: usize) -> Vec<[Card; 2]> {
+    let (hi, lo, suited) = class_ranks(class);
+    let mut out = Vec::with_capacity(12);
+    for s1 in 0..4u8 {
+        for s2 in 0..4u8 {
+            let keep = if hi == lo { s1 < s2 } else if suited { s1 == s2 } else { s1 != s2 };
+            if keep {
+                out.push([Card::from_rank_suit(hi, s1), Card::from_rank_suit(lo, s2)]);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::parse_cards;
+
+    #[test]
+    fn test_combo_index_roundtrip() {
+        let mut seen = vec![false; NUM_COMBOS];
+        for a in 0..52u8 {
+            for b in 0..a {
+                let idx = combo_index(Card::new(a), Card::new(b));
+                assert!(!seen[idx], "index {} assigned twice", idx);
+                seen[idx] = true;
+                let cards = combo_cards(idx);
+                assert_eq!(cards, [Card::new(a), Card::new(b)]);
+                assert_eq!(combo_mask(idx), Card::new(a).mask() | Card::new(b).mask());
+            }
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn test_classes_partition_the_combos() {
+        let mut sizes = vec![0usize; NUM_CLASSES];
+        for idx in 0..NUM_COMBOS {
+            sizes[class_of_combo(idx)] += 1;
+        }
+        for (class, &size) in sizes.iter().enumerate() {
+            let combos = class_combos(class);
+            assert_eq!(combos.len(), size, "{}", class_label(class));
+            assert!(combos.iter().all(|&h| class_index(h) == class));
+        }
+        assert_eq!(class_label(0), "AA");
+        assert_eq!(class_label(1), "AKs");
+        assert_eq!(class_label(13), "AKo");
+        assert_eq!(class_label(168), "22");
+        let t9s = parse_cards("Th9h").unwrap();
+        assert_eq!(class_label(class_index([t9s[0], t9s[1]])), "T9s");
+    }
+
+    #[test]
+    fn test_board_blocks_combos() {
+        let board = parse_cards("AsKh7d").unwrap();
+        let blocked = blocked_combos(&board);
+        // 3 cards each pair with 51 others, minus the 3 pairs among themselves
+        assert_eq!(blocked.iter().filter(|&&b| b).count(), 3 * 51 - 3);
+        let mut values = vec![1.0; NUM_COMBOS];
+        mask_blocked(&mut values, &board);
+        assert_eq!(values.iter().sum::<f64>(), (NUM_COMBOS - 150) as f64);
+        assert_eq!(values[combo_index(board[0], board[1])], 0.0);
+    }
+}
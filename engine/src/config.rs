@@ -0,0 +1,771 @@
+//! Solver configuration: algorithm, precision, threading, checkpointing,
+//! stop conditions, pruning, sampled best-response estimates, determinism,
+//! profiling, regret storage allocation, and resume weighting
+//!
+//! Build with `SolverConfig::builder()`; every setter has a default, and
+//! `build()` validates the combination:
+//!
+//! ```
+//! use oracle_engine::SolverConfig;
+//!
+//! let config = SolverConfig::builder()
+//!     .max_iterations(5_000)
+//!     .target_exploitability(0.005)
+//!     .threads(4)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(config.stop.check_every, 100);
+//! ```
+
+use std::path::PathBuf;
+use std::time::Duration;
+use crate::rng::DEFAULT_SEED;
+use crate::summation::Summation;
+use crate::exploitability::StreetExploitability;
+use crate::node::Street;
+
+/// CFR variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Algorithm {
+    /// CFR+ with regret flooring and linear strategy averaging
+    CfrPlus,
+    /// Predictive CFR+ (PCFR+): each iteration's strategy is regret
+    /// matching+ on the floored regrets plus the last iteration's
+    /// instantaneous regrets, a prediction of the next ones; strategies are
+    /// averaged with quadratic weights. Usually converges faster than CFR+
+    /// on poker trees. Scalar and lazy-tree CFR only; vector CFR and
+    /// distributed solves run plain CFR+.
+    PredictiveCfrPlus,
+    /// Linear CFR (LCFR): regret matching without a floor, with iteration
+    /// `t`'s regrets and strategy both weighted by `t`. Scalar and lazy-tree
+    /// CFR only, like PCFR+.
+    LinearCfr,
+    /// Discounted CFR (DCFR) with α = 1.5, β = 0, γ = 2: after each
+    /// iteration positive regrets are scaled by t^α / (t^α + 1) and negative
+    /// ones halved; strategies are averaged with quadratic weights. Scalar
+    /// and lazy-tree CFR only, like PCFR+.
+    DiscountedCfr,
+}
+
+impl Algorithm {
+    /// Every variant, in `name` order
+    pub const ALL: [Algorithm; 4] = [Algorithm::CfrPlus, Algorithm::PredictiveCfrPlus, Algorithm::LinearCfr, Algorithm::DiscountedCfr];
+
+    /// Short name, as parsed by `FromStr`: `cfr+`, `pcfr+`, `lcfr`, `dcfr`
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::CfrPlus => "cfr+",
+            Algorithm::PredictiveCfrPlus => "pcfr+",
+            Algorithm::LinearCfr => "lcfr",
+            Algorithm::DiscountedCfr => "dcfr",
+        }
+    }
+
+    /// Weight of iteration `t`'s strategy in the average
+    pub fn averaging_weight(self, t: u64) -> u64 {
+        match self {
+            Algorithm::CfrPlus | Algorithm::LinearCfr => t,
+            Algorithm::PredictiveCfrPlus | Algorithm::DiscountedCfr => t * t,
+        }
+    }
+
+    /// Sum of `averaging_weight` over iterations `1..=iterations`
+    pub fn total_weight(self, iterations: u64) -> f64 {
+        let t = iterations as f64;
+        match self {
+            Algorithm::CfrPlus | Algorithm::LinearCfr => t * (t + 1.0) / 2.0,
+            Algorithm::PredictiveCfrPlus | Algorithm::DiscountedCfr => t * (t + 1.0) * (2.0 * t + 1.0) / 6.0,
+        }
+    }
+
+    /// Cumulative regret after adding iteration `t`'s counterfactual regret `cf`
+    pub fn accumulate_regret(self, regret: f64, cf: f64, t: u64) -> f64 {
+        match self {
+            Algorithm::CfrPlus | Algorithm::PredictiveCfrPlus => (regret + cf).max(0.0),
+            Algorithm::LinearCfr => regret + t as f64 * cf,
+            Algorithm::DiscountedCfr => {
+                let r = regret + cf;
+                if r > 0.0 {
+                    let scale = (t as f64).powf(1.5);
+                    r * scale / (scale + 1.0)
+                } else {
+                    r * 0.5
+                }
+            }
+        }
+    }
+}
+
+/// Error returned for an unknown algorithm name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlgorithmParseError(pub String);
+
+impl std::fmt::Display for AlgorithmParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown algorithm '{}' (expected cfr+, pcfr+, lcfr, or dcfr)", self.0)
+    }
+}
+
+impl std::error::Error for AlgorithmParseError {}
+
+impl std::str::FromStr for Algorithm {
+    type Err = AlgorithmParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "cfr+" | "cfrplus" => Ok(Algorithm::CfrPlus),
+            "pcfr+" | "pcfrplus" | "predictive" => Ok(Algorithm::PredictiveCfrPlus),
+            "lcfr" | "linear" => Ok(Algorithm::LinearCfr),
+            "dcfr" | "discounted" => Ok(Algorithm::DiscountedCfr),
+            _ => Err(AlgorithmParseError(s.to_string())),
+        }
+    }
+}
+
+/// Floating-point width of regret and strategy storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    /// 64-bit storage (the only width `RegretStorage` implements today)
+    F64,
+    /// 32-bit storage (reserved; rejected by validation until storage supports it)
+    F32,
+}
+
+/// How regret storage allocates per-infoset arrays (scalar and lazy-tree
+/// CFR; see `RegretStorage`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageMode {
+    /// Every infoset's arrays up front
+    Dense,
+    /// Each infoset's arrays on first update, for sampled solves and huge
+    /// abstractions where most infosets are rarely reached
+    Lazy,
+}
+
+/// How a solver resumed from saved state (`Solution::resume`, a checkpoint,
+/// or a warm start) counts and weights the iterations it inherits.
+///
+/// Iteration `t` is weighted by `t` (or `t²`) in the average strategy, and
+/// LCFR and DCFR weight or discount its regrets by `t` too, so where the
+/// count picks up decides how much the saved state is worth against what
+/// comes next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResumeWeighting {
+    /// Keep counting from the saved iteration `T`: the next iteration is
+    /// `T + 1`, weighted as in an uninterrupted run. With the same
+    /// algorithm, a resumed solve matches one that never stopped bit for bit.
+    #[default]
+    Continue,
+    /// Keep counting from `T`, and first rescale the saved strategy sums
+    /// from the saving algorithm's total weight over `T` iterations to the
+    /// resuming one's (`Algorithm::total_weight`), so after a variant switch
+    /// the average reads as if the new variant had run from the start.
+    /// Regrets are kept as saved.
+    Reweight,
+    /// Count again from 0, so the saved sums are a prior worth the weight
+    /// they accumulated (as with `population::seed_priors`) and new
+    /// iterations are weighted like a fresh solve's
+    Restart,
+}
+
+/// When the solver writes checkpoints
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckpointPolicy {
+    /// No checkpoints
+    Never,
+    /// Checkpoint every `iterations` iterations to `path`
+    Every { iterations: u64, path: PathBuf },
+}
+
+/// When a solve stops iterating
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StopCondition {
+    /// Hard iteration cap
+    pub max_iterations: u64,
+    /// Stop once exploitability (in bb) falls below this
+    pub target_exploitability: Option<f64>,
+    /// Check exploitability every N iterations
+    pub check_every: u64,
+    /// Stop after this much wall time
+    pub time_limit: Option<Duration>,
+    /// Stop early once exploitability stops improving
+    pub stagnation: Option<Stagnation>,
+    /// Stop once every street's share of exploitability meets its target
+    /// (`CfrSolver` only)
+    pub street_targets: Option<StreetTargets>,
+}
+
+impl StopCondition {
+    /// 10,000 iterations, 0.01bb target, checked every 100 iterations, no time limit
+    pub fn new() -> Self {
+        StopCondition {
+            max_iterations: 10_000,
+            target_exploitability: Some(0.01),
+            check_every: 100,
+            time_limit: None,
+            stagnation: None,
+            street_targets: None,
+        }
+    }
+
+    /// Run exactly `n` iterations (no early stop)
+    pub fn iterations(n: u64) -> Self {
+        StopCondition { max_iterations: n, target_exploitability: None, check_every: n.max(1), time_limit: None, stagnation: None, street_targets: None }
+    }
+}
+
+impl Default for StopCondition {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Per-street accuracy: the solve can stop once each street's share of
+/// exploitability (see `street_exploitability`) is at or below its target,
+/// in bb. Streets without a target are unconstrained, so looser river
+/// targets trade accuracy deep in the tree for a shorter solve.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StreetTargets {
+    pub flop: Option<f64>,
+    pub turn: Option<f64>,
+    pub river: Option<f64>,
+}
+
+impl StreetTargets {
+    pub fn get(&self, street: Street) -> Option<f64> {
+        match street {
+            Street::Flop => self.flop,
+            Street::Turn => self.turn,
+            Street::River => self.river,
+        }
+    }
+
+    /// True when every street with a target meets it
+    pub fn met(&self, by_street: &StreetExploitability) -> bool {
+        [Street::Flop, Street::Turn, Street::River]
+            .into_iter()
+            .all(|street| self.get(street).is_none_or(|target| by_street.get(street) <= target))
+    }
+}
+
+/// Relative improvement `Stagnation::new` asks for (1%)
+pub const DEFAULT_MIN_IMPROVEMENT: f64 = 0.01;
+
+/// Plateau rule: the solve has stagnated when none of the last `checks`
+/// exploitability checks beat the best value before them by at least
+/// `min_improvement` (a fraction of that value).
+///
+/// Oscillation and numerical trouble both show up this way. CFR+ is the
+/// only algorithm, so there are no discounting parameters to fall back to;
+/// the solve stops with `StopReason::Stagnated` instead of running out the
+/// iteration cap or the clock.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stagnation {
+    pub checks: u32,
+    pub min_improvement: f64,
+}
+
+impl Stagnation {
+    /// Stagnated after `checks` checks without a `DEFAULT_MIN_IMPROVEMENT` gain
+    pub fn new(checks: u32) -> Self {
+        Stagnation { checks, min_improvement: DEFAULT_MIN_IMPROVEMENT }
+    }
+}
+
+/// Exploitability history checked against a `Stagnation` rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagnationTracker {
+    rule: Option<Stagnation>,
+    history: Vec<f64>,
+}
+
+impl StagnationTracker {
+    /// Tracker for `rule`; never reports stagnation when `rule` is `None`
+    pub fn new(rule: Option<Stagnation>) -> Self {
+        StagnationTracker { rule, history: Vec::new() }
+    }
+
+    /// Record one check; true once the rule says the solve has stagnated
+    pub fn observe(&mut self, exploitability: f64) -> bool {
+        self.history.push(exploitability);
+        let Some(rule) = self.rule else { return false };
+        let window = rule.checks as usize;
+        if self.history.len() <= window {
+            return false;
+        }
+        let (before, recent) = self.history.split_at(self.history.len() - window);
+        let best_before = before.iter().copied().fold(f64::INFINITY, f64::min);
+        let best_recent = recent.iter().copied().fold(f64::INFINITY, f64::min);
+        best_recent > best_before * (1.0 - rule.min_improvement)
+    }
+}
+
+/// Regret-based pruning: skip the subtree behind an action the acting player
+/// no longer plays (zero current probability) while its most recent
+/// counterfactual regret is below `-threshold`. Every `revisit_every`
+/// iterations nothing is pruned, so skipped actions get fresh regrets and
+/// can come back.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PruneConfig {
+    /// Prune once the last regret is below `-threshold` (bb, reach-weighted)
+    pub threshold: f64,
+    /// Full traversal every N iterations
+    pub revisit_every: u64,
+}
+
+/// Cheap exploitability estimate between exact checks (vector CFR): every
+/// `every` iterations, a best response that deals only `cards` randomly
+/// chosen cards at each chance node, each standing in for `1 / cards` of
+/// the street. Far fewer runouts make it much faster than the exact check
+/// on flop trees. It's a proxy, not a bound: the best responder maximizes
+/// over noisy runout estimates, which tends to read a little high. Estimates
+/// are reported but never stop the solve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampledBr {
+    pub every: u64,
+    /// Cards dealt per chance node (all of them when it has fewer)
+    pub cards: usize,
+}
+
+/// Why a solve stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    IterationCap,
+    ExploitabilityTarget,
+    TimeLimit,
+    /// Exploitability plateaued (see `Stagnation`)
+    Stagnated,
+    /// Every street met its target (see `StreetTargets`)
+    StreetTargets,
+}
+
+/// Error returned by `SolverConfigBuilder::build`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverConfigError {
+    /// `threads` was set to zero
+    ZeroThreads,
+    /// `check_every`, a checkpoint interval, the prune revisit interval, or a
+    /// sampled best-response setting is zero
+    ZeroInterval(&'static str),
+    /// Target exploitability (overall or per street) is negative or NaN
+    InvalidTarget(f64),
+    /// Prune threshold is negative or NaN
+    InvalidPruneThreshold(f64),
+    /// Stagnation `min_improvement` is outside [0, 1) or NaN
+    InvalidStagnation(f64),
+    /// A wall-clock limit makes the iteration count depend on machine speed
+    TimeLimitNotDeterministic,
+    /// The option is recognized but not implemented yet
+    Unsupported(&'static str),
+}
+
+impl std::fmt::Display for SolverConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverConfigError::ZeroThreads => write!(f, "threads must be at least 1"),
+            SolverConfigError::ZeroInterval(what) => write!(f, "{} must be at least 1", what),
+            SolverConfigError::InvalidTarget(t) => write!(f, "invalid target exploitability {}", t),
+            SolverConfigError::InvalidPruneThreshold(t) => write!(f, "invalid prune threshold {}", t),
+            SolverConfigError::InvalidStagnation(x) => write!(f, "invalid stagnation improvement {}", x),
+            SolverConfigError::TimeLimitNotDeterministic => {
+                write!(f, "a time limit cannot be combined with deterministic mode")
+            }
+            SolverConfigError::Unsupported(what) => write!(f, "{} is not supported yet", what),
+        }
+    }
+}
+
+impl std::error::Error for SolverConfigError {}
+
+/// Validated solver configuration (see `SolverConfig::builder`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverConfig {
+    pub algorithm: Algorithm,
+    pub precision: Precision,
+    /// Worker threads; `None` uses the global Rayon pool
+    pub threads: Option<usize>,
+    pub checkpoint: CheckpointPolicy,
+    pub stop: StopCondition,
+    /// Regret-based pruning; `None` traverses every branch every iteration
+    pub prune: Option<PruneConfig>,
+    /// Require bit-identical results across runs and thread counts
+    pub deterministic: bool,
+    /// Seed for any sampling the algorithm does
+    pub seed: u64,
+    /// Record where solve time goes (see `profile`)
+    pub profile: bool,
+    /// Most river boards whose showdown rank tables vector CFR keeps in
+    /// memory; others are re-ranked when next reached. `None` ranks every
+    /// board once up front.
+    pub showdown_cache: Option<usize>,
+    /// How the scalar solver's traversal and exploitability add up EVs
+    pub summation: Summation,
+    /// Sampled best-response estimates between exact checks (vector CFR)
+    pub sampled_br: Option<SampledBr>,
+    /// When regret storage allocates each infoset's arrays
+    pub storage: StorageMode,
+    /// How a resumed solve counts inherited iterations
+    pub resume: ResumeWeighting,
+}
+
+impl SolverConfig {
+    /// CFR+, f64 storage, global thread pool, no checkpoints, default stop
+    /// condition, no pruning, deterministic, `DEFAULT_SEED`, no profiling,
+    /// every showdown board ranked up front, naive summation, no sampled
+    /// estimates, dense storage, resumes continue the iteration count
+    pub fn new() -> Self {
+        SolverConfig {
+            algorithm: Algorithm::CfrPlus,
+            precision: Precision::F64,
+            threads: None,
+            checkpoint: CheckpointPolicy::Never,
+            stop: StopCondition::new(),
+            prune: None,
+            deterministic: true,
+            seed: DEFAULT_SEED,
+            profile: false,
+            showdown_cache: None,
+            summation: Summation::Naive,
+            sampled_br: None,
+            storage: StorageMode::Dense,
+            resume: ResumeWeighting::Continue,
+        }
+    }
+
+    /// Start a builder from the defaults
+    pub fn builder() -> SolverConfigBuilder {
+        SolverConfigBuilder { config: SolverConfig::new() }
+    }
+
+    /// Check the combination of options
+    pub fn validate(&self) -> Result<(), SolverConfigError> {
+        if self.threads == Some(0) {
+            return Err(SolverConfigError::ZeroThreads);
+        }
+        if self.stop.check_every == 0 {
+            return Err(SolverConfigError::ZeroInterval("check_every"));
+        }
+        if let CheckpointPolicy::Every { iterations: 0, .. } = self.checkpoint {
+            return Err(SolverConfigError::ZeroInterval("checkpoint interval"));
+        }
+        if let Some(t) = self.stop.target_exploitability {
+            if t.is_nan() || t < 0.0 {
+                return Err(SolverConfigError::InvalidTarget(t));
+            }
+        }
+        if let Some(targets) = self.stop.street_targets {
+            for t in [targets.flop, targets.turn, targets.river].into_iter().flatten() {
+                if t.is_nan() || t < 0.0 {
+                    return Err(SolverConfigError::InvalidTarget(t));
+                }
+            }
+        }
+        if let Some(rule) = self.stop.stagnation {
+            if rule.checks == 0 {
+                return Err(SolverConfigError::ZeroInterval("stagnation window"));
+            }
+            if !(0.0..1.0).contains(&rule.min_improvement) {
+                return Err(SolverConfigError::InvalidStagnation(rule.min_improvement));
+            }
+        }
+        if let Some(prune) = self.prune {
+            if prune.revisit_every == 0 {
+                return Err(SolverConfigError::ZeroInterval("prune revisit interval"));
+            }
+            if prune.threshold.is_nan() || prune.threshold < 0.0 {
+                return Err(SolverConfigError::InvalidPruneThreshold(prune.threshold));
+            }
+        }
+        if let Some(sampled) = self.sampled_br {
+            if sampled.every == 0 {
+                return Err(SolverConfigError::ZeroInterval("sampled best-response interval"));
+            }
+            if sampled.cards == 0 {
+                return Err(SolverConfigError::ZeroInterval("sampled best-response cards"));
+            }
+        }
+        if self.showdown_cache == Some(0) {
+            return Err(SolverConfigError::ZeroInterval("showdown cache"));
+        }
+        if self.deterministic && self.stop.time_limit.is_some() {
+            return Err(SolverConfigError::TimeLimitNotDeterministic);
+        }
+        if self.precision == Precision::F32 {
+            return Err(SolverConfigError::Unsupported("f32 precision"));
+        }
+        Ok(())
+    }
+
+    /// True when a checkpoint should be written after `iteration`
+    pub fn checkpoint_due(&self, iteration: u64) -> bool {
+        match self.checkpoint {
+            CheckpointPolicy::Never => false,
+            CheckpointPolicy::Every { iterations, .. } => iteration > 0 && iteration.is_multiple_of(iterations),
+        }
+    }
+
+    /// Prune threshold in effect for `iteration`: `None` when pruning is off
+    /// or the iteration is a full revisit
+    pub fn prune_threshold(&self, iteration: u64) -> Option<f64> {
+        self.prune.filter(|p| !iteration.is_multiple_of(p.revisit_every)).map(|p| p.threshold)
+    }
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder for `SolverConfig`
+#[derive(Debug, Clone)]
+pub struct SolverConfigBuilder {
+    config: SolverConfig,
+}
+
+impl SolverConfigBuilder {
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.config.algorithm = algorithm;
+        self
+    }
+
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.config.precision = precision;
+        self
+    }
+
+    /// Use a dedicated pool with `n` threads
+    pub fn threads(mut self, n: usize) -> Self {
+        self.config.threads = Some(n);
+        self
+    }
+
+    pub fn checkpoint(mut self, policy: CheckpointPolicy) -> Self {
+        self.config.checkpoint = policy;
+        self
+    }
+
+    /// Replace the whole stop condition
+    pub fn stop(mut self, stop: StopCondition) -> Self {
+        self.config.stop = stop;
+        self
+    }
+
+    pub fn max_iterations(mut self, n: u64) -> Self {
+        self.config.stop.max_iterations = n;
+        self
+    }
+
+    pub fn target_exploitability(mut self, bb: f64) -> Self {
+        self.config.stop.target_exploitability = Some(bb);
+        self
+    }
+
+    /// Never stop on exploitability; only the iteration cap or time limit apply
+    pub fn no_target(mut self) -> Self {
+        self.config.stop.target_exploitability = None;
+        self
+    }
+
+    pub fn check_every(mut self, n: u64) -> Self {
+        self.config.stop.check_every = n;
+        self
+    }
+
+    /// Stop after `limit` of wall time (requires `deterministic(false)`)
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.config.stop.time_limit = Some(limit);
+        self
+    }
+
+    /// Stop with `StopReason::Stagnated` on a plateau (see `Stagnation`)
+    pub fn stagnation(mut self, rule: Stagnation) -> Self {
+        self.config.stop.stagnation = Some(rule);
+        self
+    }
+
+    /// Stop with `StopReason::StreetTargets` once every street meets its
+    /// target (see `StreetTargets`)
+    pub fn street_targets(mut self, targets: StreetTargets) -> Self {
+        self.config.stop.street_targets = Some(targets);
+        self
+    }
+
+    /// Enable regret-based pruning (see `PruneConfig`)
+    pub fn prune(mut self, threshold: f64, revisit_every: u64) -> Self {
+        self.config.prune = Some(PruneConfig { threshold, revisit_every });
+        self
+    }
+
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.config.deterministic = deterministic;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.seed = seed;
+        self
+    }
+
+    /// Time traversal, regret updates, and exploitability checks (see `profile`)
+    pub fn profile(mut self, profile: bool) -> Self {
+        self.config.profile = profile;
+        self
+    }
+
+    /// Sum EVs with `Summation::Compensated` or plainly (scalar CFR)
+    pub fn summation(mut self, summation: Summation) -> Self {
+        self.config.summation = summation;
+        self
+    }
+
+    /// Estimate exploitability every `every` iterations from `cards` cards
+    /// per chance node (vector CFR; see `SampledBr`)
+    pub fn sampled_br(mut self, every: u64, cards: usize) -> Self {
+        self.config.sampled_br = Some(SampledBr { every, cards });
+        self
+    }
+
+    /// Allocate regret storage up front or per infoset on first update
+    pub fn storage(mut self, mode: StorageMode) -> Self {
+        self.config.storage = mode;
+        self
+    }
+
+    /// How a resumed solve counts the iterations it inherits
+    pub fn resume(mut self, weighting: ResumeWeighting) -> Self {
+        self.config.resume = weighting;
+        self
+    }
+
+    /// Keep at most `boards` showdown rank tables in memory (vector CFR)
+    pub fn showdown_cache(mut self, boards: usize) -> Self {
+        self.config.showdown_cache = Some(boards);
+        self
+    }
+
+    /// Validate and return the config
+    pub fn build(self) -> Result<SolverConfig, SolverConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_validate() {
+        let config = SolverConfig::builder().build().unwrap();
+        assert_eq!(config, SolverConfig::new());
+        assert_eq!(config.stop.max_iterations, 10_000);
+        assert!(config.deterministic);
+    }
+
+    #[test]
+    fn test_builder_sets_fields() {
+        let config = SolverConfig::builder()
+            .threads(2)
+            .max_iterations(500)
+            .no_target()
+            .check_every(50)
+            .deterministic(false)
+            .time_limit(Duration::from_secs(5))
+            .seed(7)
+            .build()
+            .unwrap();
+        assert_eq!(config.threads, Some(2));
+        assert_eq!(config.stop, StopCondition {
+            max_iterations: 500,
+            target_exploitability: None,
+            check_every: 50,
+            time_limit: Some(Duration::from_secs(5)),
+            stagnation: None,
+            street_targets: None,
+        });
+        assert_eq!(config.seed, 7);
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        assert_eq!(SolverConfig::builder().threads(0).build(), Err(SolverConfigError::ZeroThreads));
+        assert_eq!(
+            SolverConfig::builder().check_every(0).build(),
+            Err(SolverConfigError::ZeroInterval("check_every")),
+        );
+        assert_eq!(
+            SolverConfig::builder().target_exploitability(-1.0).build(),
+            Err(SolverConfigError::InvalidTarget(-1.0)),
+        );
+        assert_eq!(
+            SolverConfig::builder().street_targets(StreetTargets { river: Some(-0.5), ..StreetTargets::default() }).build(),
+            Err(SolverConfigError::InvalidTarget(-0.5)),
+        );
+        assert_eq!(
+            SolverConfig::builder().time_limit(Duration::from_secs(1)).build(),
+            Err(SolverConfigError::TimeLimitNotDeterministic),
+        );
+        assert!(matches!(
+            SolverConfig::builder().precision(Precision::F32).build(),
+            Err(SolverConfigError::Unsupported(_)),
+        ));
+        assert_eq!(
+            SolverConfig::builder().prune(0.1, 0).build(),
+            Err(SolverConfigError::ZeroInterval("prune revisit interval")),
+        );
+        assert_eq!(SolverConfig::builder().prune(-1.0, 10).build(), Err(SolverConfigError::InvalidPruneThreshold(-1.0)));
+        assert_eq!(SolverConfig::builder().showdown_cache(0).build(), Err(SolverConfigError::ZeroInterval("showdown cache")));
+        assert_eq!(SolverConfig::builder().sampled_br(10, 0).build(), Err(SolverConfigError::ZeroInterval("sampled best-response cards")));
+        assert_eq!(SolverConfig::builder().stagnation(Stagnation::new(0)).build(), Err(SolverConfigError::ZeroInterval("stagnation window")));
+        let bad = Stagnation { checks: 3, min_improvement: 1.0 };
+        assert_eq!(SolverConfig::builder().stagnation(bad).build(), Err(SolverConfigError::InvalidStagnation(1.0)));
+    }
+
+    #[test]
+    fn test_stagnation_tracker_needs_a_full_flat_window() {
+        let mut tracker = StagnationTracker::new(Some(Stagnation::new(2)));
+        assert!(!tracker.observe(1.0));
+        assert!(!tracker.observe(0.5));
+        assert!(!tracker.observe(0.499));
+        // 0.499 and 0.4985 are within 1% of the 0.5 before them
+        assert!(tracker.observe(0.4985));
+        // Oscillating around the best value also counts as a plateau
+        let mut tracker = StagnationTracker::new(Some(Stagnation::new(2)));
+        assert!(![0.2, 0.1, 0.3].iter().any(|&x| tracker.observe(x)));
+        assert!(tracker.observe(0.15));
+        let mut off = StagnationTracker::new(None);
+        assert!(![1.0, 1.0, 1.0, 1.0].iter().any(|&x| off.observe(x)));
+    }
+
+    #[test]
+    fn test_prune_threshold_skips_revisits() {
+        let config = SolverConfig::builder().prune(0.5, 10).build().unwrap();
+        assert_eq!(config.prune_threshold(9), Some(0.5));
+        assert_eq!(config.prune_threshold(10), None);
+        assert_eq!(SolverConfig::new().prune_threshold(9), None);
+    }
+
+    #[test]
+    fn test_total_weight_sums_averaging_weights() {
+        for algorithm in Algorithm::ALL {
+            let summed: u64 = (1..=25).map(|t| algorithm.averaging_weight(t)).sum();
+            assert_eq!(algorithm.total_weight(25), summed as f64);
+            assert_eq!(algorithm.total_weight(0), 0.0);
+        }
+        assert_eq!(SolverConfig::builder().resume(ResumeWeighting::Restart).build().unwrap().resume, ResumeWeighting::Restart);
+    }
+
+    #[test]
+    fn test_checkpoint_due() {
+        let config = SolverConfig::builder()
+            .checkpoint(CheckpointPolicy::Every { iterations: 100, path: PathBuf::from("ckpt.bin") })
+            .build()
+            .unwrap();
+        assert!(!config.checkpoint_due(0));
+        assert!(!config.checkpoint_due(99));
+        assert!(config.checkpoint_due(200));
+        assert!(!SolverConfig::new().checkpoint_due(100));
+    }
+}
@@ -0,0 +1,223 @@
+//! Card deck as a 64-bit mask
+//!
+//! `Deck` is the set of cards still available: bit `v` is set while card
+//! `v` can be dealt. Removing the board, iterating what's left, and checking
+//! a combo against dead cards are single mask operations, so chance-node
+//! expansion, equity enumeration, and test hand generation share this type
+//! instead of each folding board masks and filtering `0..52` by hand.
+//!
+//! Iteration is always in card order, which keeps everything built on it
+//! (chance children, runout enumeration) deterministic.
+
+use crate::node::Card;
+use crate::range::Range;
+use crate::rng::Rng;
+
+/// All 52 card bits
+const FULL: u64 = (1u64 << 52) - 1;
+
+/// Set of cards that can still be dealt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Deck {
+    mask: u64,
+}
+
+impl Deck {
+    /// Full 52-card deck
+    pub fn new() -> Self {
+        Deck { mask: FULL }
+    }
+
+    /// Deck holding exactly the cards set in `mask` (bits above 51 ignored)
+    pub fn from_mask(mask: u64) -> Self {
+        Deck { mask: mask & FULL }
+    }
+
+    /// Full deck minus `dead` (board, hole cards, ...)
+    pub fn without(dead: &[Card]) -> Self {
+        let mut deck = Deck::new();
+        deck.remove_all(dead);
+        deck
+    }
+
+    pub fn mask(self) -> u64 {
+        self.mask
+    }
+
+    pub fn len(self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.mask == 0
+    }
+
+    pub fn contains(self, card: Card) -> bool {
+        self.mask & card.mask() != 0
+    }
+
+    /// Remove `card`; returns whether it was in the deck
+    pub fn remove(&mut self, card: Card) -> bool {
+        let present = self.contains(card);
+        self.mask &= !card.mask();
+        present
+    }
+
+    pub fn remove_all(&mut self, cards: &[Card]) {
+        for &card in cards {
+            self.remove(card);
+        }
+    }
+
+    /// Put `card` back
+    pub fn insert(&mut self, card: Card) {
+        self.mask |= card.mask();
+    }
+
+    /// Remaining cards in card order
+    pub fn iter(self) -> DeckIter {
+        DeckIter { mask: self.mask }
+    }
+
+    /// Whether any of `cards` is no longer in the deck (dealt or dead)
+    pub fn conflicts(self, cards: &[Card]) -> bool {
+        cards.iter().any(|&c| !self.contains(c))
+    }
+
+    /// Every two-card combo left in the deck, in card order
+    pub fn combos(self) -> impl Iterator<Item = [Card; 2]> {
+        self.iter().flat_map(move |a| {
+            // Cards above `a` only, so each pair comes once
+            Deck::from_mask(self.mask & !((a.mask() << 1) - 1)).iter().map(move |b| [a, b])
+        })
+    }
+
+    /// `range`'s weighted combos that can still be held given this deck
+    pub fn live_combos<'a>(self, range: &'a Range) -> impl Iterator<Item = ([Card; 2], f64)> + 'a {
+        range.live_combos(&[]).filter(move |(hand, _)| !self.conflicts(hand))
+    }
+
+    /// Uniformly random remaining card, without removing it
+    pub fn sample(self, rng: &mut Rng) -> Option<Card> {
+        if self.is_empty() {
+            return None;
+        }
+        self.iter().nth(rng.below(self.len() as u64) as usize)
+    }
+
+    /// Remove and return a uniformly random card
+    pub fn deal(&mut self, rng: &mut Rng) -> Option<Card> {
+        let card = self.sample(rng)?;
+        self.remove(card);
+        Some(card)
+    }
+
+    /// Deal `N` distinct cards, or `None` (leaving the deck untouched) if
+    /// fewer than `N` remain
+    pub fn deal_n<const N: usize>(&mut self, rng: &mut Rng) -> Option<[Card; N]> {
+        if self.len() < N {
+            return None;
+        }
+        let mut cards = [Card::new(0); N];
+        for card in cards.iter_mut() {
+            *card = self.deal(rng)?;
+        }
+        Some(cards)
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoIterator for Deck {
+    type Item = Card;
+    type IntoIter = DeckIter;
+
+    fn into_iter(self) -> DeckIter {
+        self.iter()
+    }
+}
+
+/// Iterator over a deck's cards, lowest first
+#[derive(Debug, Clone)]
+pub struct DeckIter {
+    mask: u64,
+}
+
+impl Iterator for DeckIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.mask == 0 {
+            return None;
+        }
+        let v = self.mask.trailing_zeros() as u8;
+        self.mask &= self.mask - 1;
+        Some(Card::new(v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.mask.count_ones() as usize;
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for DeckIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::parse_cards;
+
+    #[test]
+    fn test_remove_iterate_and_conflicts() {
+        let board = parse_cards("AsKh7d").unwrap();
+        let mut deck = Deck::without(&board);
+        assert_eq!(deck.len(), 49);
+        assert!(board.iter().all(|&c| !deck.contains(c)));
+        let cards: Vec<Card> = deck.iter().collect();
+        assert_eq!(cards.len(), 49);
+        assert!(cards.windows(2).all(|w| w[0].value() < w[1].value()));
+
+        let hand = parse_cards("Ah7h").unwrap();
+        assert!(!deck.conflicts(&hand));
+        assert!(deck.remove(hand[0]));
+        assert!(!deck.remove(hand[0]));
+        assert!(deck.conflicts(&hand));
+        deck.insert(hand[0]);
+        assert_eq!(deck, Deck::without(&board));
+        assert_eq!(deck.combos().count(), 49 * 48 / 2);
+    }
+
+    #[test]
+    fn test_live_combos_skip_blocked_hands() {
+        let range = Range::parse("AA,KQs").unwrap();
+        let deck = Deck::without(&parse_cards("AsQh2c").unwrap());
+        // Three aces left make 3 AA combos; KhQh is blocked
+        assert_eq!(deck.live_combos(&range).count(), 3 + 3);
+        assert_eq!(deck.live_combos(&range).count(), range.live_combos(&parse_cards("AsQh2c").unwrap()).count());
+    }
+
+    #[test]
+    fn test_deal_is_seeded_and_exhausts_the_deck() {
+        let mut a = Deck::new();
+        let mut b = Deck::new();
+        let (mut ra, mut rb) = (Rng::new(3), Rng::new(3));
+        let hand: [Card; 7] = a.deal_n(&mut ra).unwrap();
+        assert_eq!(Some(hand), b.deal_n(&mut rb));
+        assert_eq!(a.len(), 45);
+        assert!(!a.conflicts(&[]) && a.conflicts(&hand));
+
+        let mut seen = hand.iter().fold(0u64, |m, c| m | c.mask());
+        while let Some(card) = a.deal(&mut ra) {
+            assert_eq!(seen & card.mask(), 0);
+            seen |= card.mask();
+        }
+        assert_eq!(seen, FULL);
+        assert_eq!(a.deal_n::<1>(&mut ra), None);
+        assert_eq!(a.sample(&mut ra), None);
+    }
+}
@@ -0,0 +1,452 @@
+//! Experimental distributed CFR+: a coordinator farms chance-node subtrees
+//! out to worker processes over TCP (requires the `serde` feature)
+//!
+//! The subtrees below the first chance level (e.g. one per turn card on a
+//! flop tree) are split round-robin across workers. Each iteration takes one
+//! round trip per worker:
+//!
+//! 1. The coordinator walks the tree above the split with the current
+//!    strategy and sends each worker the reach probabilities of its subtrees.
+//! 2. Workers run the ordinary CFR+ traversal on their subtrees, apply the
+//!    updates to their own copy of the regrets, and send back each subtree's
+//!    value and updates.
+//! 3. The coordinator merges the workers' updates into the full storage,
+//!    traverses the top of the tree with the returned values, and applies
+//!    the top updates.
+//!
+//! Workers run the solver's `SolverConfig::algorithm` (any CFR variant) and
+//! `SolverConfig::summation`, and every update is computed from the same
+//! strategies as a local iteration, so a distributed solve matches
+//! `CfrSolver::run_iteration` bit for bit. Regret-based pruning and node
+//! locks (`SolverConfig::prune`, `CfrSolver::with_locks`) aren't supported:
+//! `Coordinator::connect` refuses a solver that uses them.
+//!
+//! Messages are bincode frames over plain `std::net` streams. There is no
+//! authentication or encryption; run workers on a trusted network only.
+
+use std::collections::HashMap;
+use std::io::{BufReader, BufWriter, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use crate::par::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::cfr::{CfrSolver, RegretStorage, RegretUpdate, Traversal, UpdateBuffer};
+use crate::config::Algorithm;
+use crate::ev_table::EvTable;
+use crate::node::{GameTree, InfosetId, Node, NodeId, Player};
+use crate::summation::Summation;
+
+/// Error in the coordinator/worker protocol
+#[derive(Debug)]
+pub enum DistributedError {
+    Io(std::io::Error),
+    /// A message failed to encode or decode
+    Codec(String),
+    /// The peer sent an unexpected message
+    Protocol(String),
+    /// The solver uses an option distributed solves don't support
+    Unsupported(String),
+}
+
+impl std::fmt::Display for DistributedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DistributedError::Io(e) => write!(f, "I/O error: {}", e),
+            DistributedError::Codec(m) => write!(f, "message codec error: {}", m),
+            DistributedError::Protocol(m) => write!(f, "protocol error: {}", m),
+            DistributedError::Unsupported(m) => write!(f, "not supported in distributed solves: {}", m),
+        }
+    }
+}
+
+impl std::error::Error for DistributedError {}
+
+impl From<std::io::Error> for DistributedError {
+    fn from(e: std::io::Error) -> Self {
+        DistributedError::Io(e)
+    }
+}
+
+/// `(infoset, regrets, strategy sums, last regrets)` handed to a worker
+type InfosetState = (InfosetId, Vec<f64>, Vec<f64>, Vec<f64>);
+
+/// Coordinator → worker
+#[derive(Debug, Serialize, Deserialize)]
+enum Request {
+    /// Tree, terminal EVs, the solver's variant and summation, and the
+    /// worker's regret state for its subtrees
+    Setup { tree: GameTree, terminal_evs: Vec<(NodeId, f64)>, algorithm: Algorithm, summation: Summation, regrets: Vec<InfosetState> },
+    /// Traverse each `(subtree root, reach_ip, reach_oop)` at iteration `t`
+    Iterate { t: u64, reaches: Vec<(NodeId, f64, f64)> },
+    Shutdown,
+}
+
+/// Worker → coordinator
+#[derive(Debug, Serialize, Deserialize)]
+enum Response {
+    Ready,
+    /// `(subtree root, value, updates)` in request order
+    Results(Vec<(NodeId, f64, Vec<RegretUpdate>)>),
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Result<Self, DistributedError> {
+        stream.set_nodelay(true)?;
+        Ok(Connection { reader: BufReader::new(stream.try_clone()?), writer: BufWriter::new(stream) })
+    }
+
+    fn send<T: Serialize>(&mut self, message: &T) -> Result<(), DistributedError> {
+        bincode::serialize_into(&mut self.writer, message).map_err(|e| DistributedError::Codec(e.to_string()))?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn receive<T: serde::de::DeserializeOwned>(&mut self) -> Result<T, DistributedError> {
+        bincode::deserialize_from(&mut self.reader).map_err(|e| DistributedError::Codec(e.to_string()))
+    }
+}
+
+/// Roots of the subtrees handed to workers: children of every chance node
+/// that has no chance node above it, in tree order
+pub fn split_roots(tree: &GameTree) -> Vec<NodeId> {
+    let mut roots = Vec::new();
+    if !tree.is_empty() {
+        collect_roots(tree, 0, &mut roots);
+    }
+    roots
+}
+
+fn collect_roots(tree: &GameTree, node_id: NodeId, roots: &mut Vec<NodeId>) {
+    let node = tree.get(node_id).expect("invalid node id");
+    if node.is_chance() {
+        roots.extend_from_slice(node.children());
+    } else {
+        for &child in node.children() {
+            collect_roots(tree, child, roots);
+        }
+    }
+}
+
+/// Reach probabilities `(reach_ip, reach_oop)` at each split root under the
+/// strategy `algorithm` plays this iteration
+fn root_reaches(tree: &GameTree, storage: &RegretStorage, algorithm: Algorithm) -> HashMap<NodeId, (f64, f64)> {
+    let mut reaches = HashMap::new();
+    if !tree.is_empty() {
+        collect_reaches(tree, storage, algorithm, 0, 1.0, 1.0, &mut reaches);
+    }
+    reaches
+}
+
+fn collect_reaches(
+    tree: &GameTree,
+    storage: &RegretStorage,
+    algorithm: Algorithm,
+    node_id: NodeId,
+    reach_ip: f64,
+    reach_oop: f64,
+    out: &mut HashMap<NodeId, (f64, f64)>,
+) {
+    match tree.get(node_id).expect("invalid node id") {
+        Node::Terminal { .. } => {}
+        Node::Chance { children, .. } => {
+            for &child in children {
+                out.insert(child, (reach_ip, reach_oop));
+            }
+        }
+        Node::Decision { player, children, infoset_id, .. } => {
+            let strategy = storage.iteration_strategy(*infoset_id as usize, algorithm);
+            for (&child, &p) in children.iter().zip(&strategy) {
+                let (ip, oop) = if *player == Player::IP { (reach_ip * p, reach_oop) } else { (reach_ip, reach_oop * p) };
+                collect_reaches(tree, storage, algorithm, child, ip, oop, out);
+            }
+        }
+    }
+}
+
+/// Decision nodes in the subtree rooted at `root`
+fn subtree_infosets(tree: &GameTree, root: NodeId, out: &mut Vec<InfosetId>) {
+    let node = tree.get(root).expect("invalid node id");
+    out.extend(node.infoset_id());
+    for &child in node.children() {
+        subtree_infosets(tree, child, out);
+    }
+}
+
+/// Coordinator side of a distributed solve
+pub struct Coordinator {
+    solver: CfrSolver,
+    workers: Vec<Connection>,
+    /// Split roots owned by each worker
+    assignment: Vec<Vec<NodeId>>,
+}
+
+impl Coordinator {
+    /// Connect to every worker and hand out the solver's chance subtrees.
+    ///
+    /// Workers receive the solver's current regrets, so a partially solved
+    /// solver continues where it left off. Fails before connecting if the
+    /// solver prunes or has node locks (see module docs).
+    pub fn connect<A: ToSocketAddrs>(solver: CfrSolver, workers: &[A]) -> Result<Self, DistributedError> {
+        if workers.is_empty() {
+            return Err(DistributedError::Protocol("no workers given".to_string()));
+        }
+        if solver.config().prune.is_some() {
+            return Err(DistributedError::Unsupported("regret-based pruning".to_string()));
+        }
+        if solver.locks().is_some() {
+            return Err(DistributedError::Unsupported("node locks".to_string()));
+        }
+        let mut connections = Vec::with_capacity(workers.len());
+        for addr in workers {
+            connections.push(Connection::new(TcpStream::connect(addr)?)?);
+        }
+
+        let mut assignment = vec![Vec::new(); connections.len()];
+        for (i, root) in split_roots(&solver.tree).into_iter().enumerate() {
+            assignment[i % connections.len()].push(root);
+        }
+
+        let terminal_evs: Vec<(NodeId, f64)> = solver.terminal_evs().iter().collect();
+        let (algorithm, summation) = (solver.config().algorithm, solver.config().summation);
+        for (conn, roots) in connections.iter_mut().zip(&assignment) {
+            let mut infosets = Vec::new();
+            for &root in roots {
+                subtree_infosets(&solver.tree, root, &mut infosets);
+            }
+            let regrets = infosets.into_iter().map(|id| {
+                let i = id as usize;
+                let storage = &solver.storage;
+                (id, storage.regrets(i).to_vec(), storage.strategy_sum(i).to_vec(), storage.last_regrets(i).to_vec())
+            }).collect();
+            let tree = solver.tree.clone();
+            conn.send(&Request::Setup { tree, terminal_evs: terminal_evs.clone(), algorithm, summation, regrets })?;
+        }
+        for conn in &mut connections {
+            match conn.receive()? {
+                Response::Ready => {}
+                other => return Err(DistributedError::Protocol(format!("expected Ready, got {:?}", other))),
+            }
+        }
+        Ok(Coordinator { solver, workers: connections, assignment })
+    }
+
+    /// Solver state (the full, merged storage)
+    pub fn solver(&self) -> &CfrSolver {
+        &self.solver
+    }
+
+    /// Number of subtrees each worker owns
+    pub fn subtrees_per_worker(&self) -> Vec<usize> {
+        self.assignment.iter().map(Vec::len).collect()
+    }
+
+    /// Run one distributed iteration of the solver's algorithm
+    pub fn run_iteration(&mut self) -> Result<(), DistributedError> {
+        self.solver.iteration += 1;
+        let t = self.solver.iteration;
+        let (algorithm, summation) = (self.solver.config().algorithm, self.solver.config().summation);
+        let reaches = root_reaches(&self.solver.tree, &self.solver.storage, algorithm);
+
+        for (conn, roots) in self.workers.iter_mut().zip(&self.assignment) {
+            let batch = roots.iter().map(|r| {
+                let (ip, oop) = reaches[r];
+                (*r, ip, oop)
+            }).collect();
+            conn.send(&Request::Iterate { t, reaches: batch })?;
+        }
+
+        let mut values = HashMap::new();
+        let mut remote_updates = Vec::new();
+        for (conn, roots) in self.workers.iter_mut().zip(&self.assignment) {
+            let results = match conn.receive()? {
+                Response::Results(results) => results,
+                other => return Err(DistributedError::Protocol(format!("expected Results, got {:?}", other))),
+            };
+            if results.len() != roots.len() {
+                return Err(DistributedError::Protocol(format!(
+                    "worker returned {} results for {} subtrees", results.len(), roots.len()
+                )));
+            }
+            for (root, ev, updates) in results {
+                values.insert(root, ev);
+                remote_updates.extend(updates);
+            }
+        }
+
+        let solver = &mut self.solver;
+        solver.updates.begin();
+        Traversal {
+            cut: Some(&values),
+            algorithm,
+            summation,
+            ..Traversal::new(&solver.tree, &solver.storage, solver.terminal_evs(), &solver.updates)
+        }
+        .run(0, 1.0, 1.0);
+        solver.storage.apply(&remote_updates, algorithm, t);
+        solver.storage.merge(&solver.updates, algorithm, t);
+        Ok(())
+    }
+
+    /// Tell workers to exit and return the solver
+    pub fn finish(mut self) -> Result<CfrSolver, DistributedError> {
+        for conn in &mut self.workers {
+            conn.send(&Request::Shutdown)?;
+        }
+        Ok(self.solver)
+    }
+}
+
+/// Serve one coordinator session on `stream` until it sends `Shutdown`
+pub fn serve_worker(stream: TcpStream) -> Result<(), DistributedError> {
+    let mut conn = Connection::new(stream)?;
+    let (tree, terminal_evs, algorithm, summation, regrets) = match conn.receive()? {
+        Request::Setup { tree, terminal_evs, algorithm, summation, regrets } => (tree, terminal_evs, algorithm, summation, regrets),
+        other => return Err(DistributedError::Protocol(format!("expected Setup, got {:?}", other))),
+    };
+    let terminal_evs: EvTable = terminal_evs.into_iter().collect();
+    let mut storage = RegretStorage::for_tree(&tree);
+    let mut buffer = UpdateBuffer::new(&tree);
+    for (id, r, s, last) in regrets {
+        storage.restore(id as usize, r, s);
+        storage.restore_last_regrets(id as usize, last);
+    }
+    conn.send(&Response::Ready)?;
+
+    loop {
+        match conn.receive()? {
+            Request::Iterate { t, reaches } => {
+                buffer.begin();
+                let traversal = Traversal { algorithm, summation, ..Traversal::new(&tree, &storage, &terminal_evs, &buffer) };
+                let evs: Vec<f64> = reaches.par_iter()
+                    .map(|&(root, ip, oop)| traversal.run(root, ip, oop))
+                    .collect();
+                let weight = algorithm.averaging_weight(t);
+                let results: Vec<(NodeId, f64, Vec<RegretUpdate>)> = reaches.iter().zip(evs)
+                    .map(|(&(root, _, _), ev)| (root, ev, buffer.updates(&tree, root, weight)))
+                    .collect();
+                for (_, _, updates) in &results {
+                    storage.apply(updates, algorithm, t);
+                }
+                conn.send(&Response::Results(results))?;
+            }
+            Request::Shutdown => return Ok(()),
+            Request::Setup { .. } => return Err(DistributedError::Protocol("unexpected second Setup".to_string())),
+        }
+    }
+}
+
+/// Accept coordinator sessions on `listener` one at a time, forever
+pub fn run_worker(listener: TcpListener, mut on_session: impl FnMut(Result<(), DistributedError>)) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept()?;
+        on_session(serve_worker(stream));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::config::SolverConfig;
+    use crate::nodelock::NodeLocks;
+    use crate::game::{build_terminal_evs, GameConfig};
+    use crate::node::parse_cards;
+    use crate::test_tree::{build_test_tree_chance, terminal_ev_table_chance};
+
+    fn spawn_workers(n: usize) -> Vec<std::net::SocketAddr> {
+        (0..n).map(|_| {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            std::thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                serve_worker(stream).unwrap();
+            });
+            addr
+        }).collect()
+    }
+
+    fn assert_matches_local(tree: GameTree, evs: HashMap<NodeId, f64>, workers: usize, iterations: usize) {
+        assert_matches_local_with(tree, evs, SolverConfig::new(), workers, iterations);
+    }
+
+    fn assert_matches_local_with(tree: GameTree, evs: HashMap<NodeId, f64>, config: SolverConfig, workers: usize, iterations: usize) {
+        let mut local = CfrSolver::with_config(tree.clone(), evs.clone(), config.clone());
+        let remote = CfrSolver::with_config(tree, evs, config);
+        let mut coordinator = Coordinator::connect(remote, &spawn_workers(workers)).unwrap();
+        for _ in 0..iterations {
+            local.run_iteration();
+            coordinator.run_iteration().unwrap();
+        }
+        let remote = coordinator.finish().unwrap();
+        assert_eq!(remote.iteration, local.iteration);
+        assert_eq!(remote.storage.snapshot(), local.storage.snapshot());
+    }
+
+    #[test]
+    fn test_split_roots() {
+        assert_eq!(split_roots(&build_test_tree_chance()), vec![2, 5]);
+        assert!(split_roots(&crate::test_tree::build_test_tree()).is_empty());
+    }
+
+    #[test]
+    fn test_distributed_matches_local_on_chance_tree() {
+        assert_matches_local(build_test_tree_chance(), terminal_ev_table_chance(), 2, 200);
+    }
+
+    #[test]
+    fn test_distributed_runs_the_solver_algorithm() {
+        let config = SolverConfig::builder().algorithm(Algorithm::PredictiveCfrPlus).summation(Summation::Compensated).build().unwrap();
+        assert_matches_local_with(build_test_tree_chance(), terminal_ev_table_chance(), config, 2, 200);
+
+        // Warm-started: workers pick up the last regrets PCFR+ predicts from
+        let config = SolverConfig::builder().algorithm(Algorithm::PredictiveCfrPlus).build().unwrap();
+        let mut local = CfrSolver::with_config(build_test_tree_chance(), terminal_ev_table_chance(), config.clone());
+        let mut warm = CfrSolver::with_config(build_test_tree_chance(), terminal_ev_table_chance(), config);
+        for _ in 0..50 {
+            local.run_iteration();
+            warm.run_iteration();
+        }
+        let mut coordinator = Coordinator::connect(warm, &spawn_workers(2)).unwrap();
+        for _ in 0..50 {
+            local.run_iteration();
+            coordinator.run_iteration().unwrap();
+        }
+        assert_eq!(coordinator.finish().unwrap().storage.snapshot(), local.storage.snapshot());
+    }
+
+    #[test]
+    fn test_distributed_lcfr_and_dcfr_match_local() {
+        for algorithm in [Algorithm::LinearCfr, Algorithm::DiscountedCfr] {
+            let config = SolverConfig::builder().algorithm(algorithm).build().unwrap();
+            assert_matches_local_with(build_test_tree_chance(), terminal_ev_table_chance(), config, 2, 200);
+        }
+    }
+
+    #[test]
+    fn test_connect_refuses_pruning_and_locks() {
+        let config = SolverConfig::builder().prune(0.0, 50).build().unwrap();
+        let pruned = CfrSolver::with_config(build_test_tree_chance(), terminal_ev_table_chance(), config);
+        assert!(matches!(Coordinator::connect(pruned, &["127.0.0.1:1"]), Err(DistributedError::Unsupported(_))));
+        let tree = build_test_tree_chance();
+        let locks = NodeLocks::parse(&tree, "root = x:1").unwrap();
+        let locked = CfrSolver::new_with_evs(tree, terminal_ev_table_chance()).with_locks(locks);
+        assert!(matches!(Coordinator::connect(locked, &["127.0.0.1:1"]), Err(DistributedError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_distributed_matches_local_on_turn_tree() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 1;
+        let tree = build_tree(&config);
+        let evs = build_terminal_evs(&tree, |n| (n.board().last().unwrap().rank() as f64) / 12.0);
+        // One subtree per river card under each turn line that reaches the river
+        assert!(split_roots(&tree).len() > 40);
+        assert_matches_local(tree, evs, 3, 20);
+    }
+}
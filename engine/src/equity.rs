@@ -0,0 +1,439 @@
+//! Range-vs-range equity by exact runout enumeration
+//!
+//! For a board of 0–5 cards, every completion to five cards is enumerated and
+//! each live (hero, villain) combo pair is settled at showdown. Each runout
+//! ranks both ranges once and sorts them (see `showdown`), so the cost is
+//! O(runouts × (|hero| + |villain|) log(|hero| + |villain|)) rather than
+//! pairwise. `range_vs_range_sampled` settles a random sample of runouts the
+//! same way, for boards with too many to enumerate.
+//!
+//! Exact enumeration is cheap on turn and river boards; flop boards cost about
+//! 1,000 runouts and are practical for narrow ranges.
+//!
+//! `hand_strength` and `expected_hand_strength` give the classic bucketing
+//! inputs against a uniformly random opponent hand: HS is the share of the
+//! pot won on the current board, EHS and EHS² average the river HS (and its
+//! square) over every runout.
+
+use crate::combo::{class_index, NUM_CLASSES};
+use crate::deck::Deck;
+use crate::evaluator::CactusKevEvaluator;
+use crate::node::{Card, HandEvaluator, HandRank};
+use crate::range::Range;
+use crate::rng::Rng;
+use crate::showdown::{showdown_weights, BoardRanks};
+
+/// Equity of a single hero combo against the villain range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComboEquity {
+    /// Hero hole cards
+    pub hand: [Card; 2],
+    /// Hero combo weight in its range
+    pub weight: f64,
+    /// Pot share (wins + half of ties) against the villain range
+    pub equity: f64,
+}
+
+/// Result of a range-vs-range equity calculation
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquityResult {
+    /// Hero's pot share over all weighted matchups
+    pub hero_equity: f64,
+    /// Villain's pot share (`1 - hero_equity` when any matchup exists)
+    pub villain_equity: f64,
+    /// Fraction of weighted matchups hero wins outright
+    pub win: f64,
+    /// Fraction of weighted matchups that chop
+    pub tie: f64,
+    /// Per-combo hero equities (combos with no live matchup are omitted)
+    pub hero_combos: Vec<ComboEquity>,
+}
+
+/// Hero equity over the live combos of one hand class
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassEquity {
+    /// Class index (`combo::class_label` names it)
+    pub class: usize,
+    /// Total weight of the class's combos with a live matchup
+    pub combos: f64,
+    /// Weight-averaged pot share of those combos
+    pub equity: f64,
+}
+
+impl EquityResult {
+    /// Hero's per-combo equities grouped by hand class, in grid order (AA first)
+    pub fn by_class(&self) -> Vec<ClassEquity> {
+        let mut sums = vec![(0.0_f64, 0.0_f64); NUM_CLASSES];
+        for combo in &self.hero_combos {
+            let entry = &mut sums[class_index(combo.hand)];
+            entry.0 += combo.weight;
+            entry.1 += combo.weight * combo.equity;
+        }
+        sums.into_iter().enumerate()
+            .filter(|(_, (weight, _))| *weight > 0.0)
+            .map(|(class, (combos, share))| ClassEquity { class, combos, equity: share / combos })
+            .collect()
+    }
+}
+
+/// Exact equity of `hero` against `villain` on `board` (0–5 cards).
+///
+/// Combos blocked by the board or by each other are excluded; remaining
+/// matchups are weighted by the product of both combo weights.
+pub fn range_vs_range<E: HandEvaluator>(
+    evaluator: &E,
+    hero: &Range,
+    villain: &Range,
+    board: &[Card],
+) -> EquityResult {
+    assert!(board.len() <= 5, "board must have at most 5 cards");
+    settle_runouts(evaluator, hero, villain, board, |settle| for_each_runout(board, |full_board, _| settle(full_board)))
+}
+
+/// Estimated equity of `hero` against `villain` over `samples` runouts of
+/// `board` drawn uniformly with replacement.
+///
+/// Each sampled runout is settled exactly as in `range_vs_range`, so this
+/// converges on the exact result; use it where enumeration is too slow
+/// (preflop and wide flop ranges).
+pub fn range_vs_range_sampled<E: HandEvaluator>(
+    evaluator: &E,
+    hero: &Range,
+    villain: &Range,
+    board: &[Card],
+    samples: usize,
+    rng: &mut Rng,
+) -> EquityResult {
+    assert!(board.len() <= 5, "board must have at most 5 cards");
+    let deck = Deck::without(board);
+    let mut full = [Card::new(0); 5];
+    full[..board.len()].copy_from_slice(board);
+    settle_runouts(evaluator, hero, villain, board, |settle| {
+        for _ in 0..samples {
+            let mut remaining = deck;
+            for slot in full[board.len()..].iter_mut() {
+                *slot = remaining.deal(rng).expect("a 52-card deck completes any board");
+            }
+            settle(&full);
+        }
+    })
+}
+
+/// Settle every live matchup on each full board `runouts` passes to its callback
+fn settle_runouts<E: HandEvaluator>(
+    evaluator: &E,
+    hero: &Range,
+    villain: &Range,
+    board: &[Card],
+    runouts: impl FnOnce(&mut dyn FnMut(&[Card; 5])),
+) -> EquityResult {
+    let hero_live: Vec<([Card; 2], f64)> = hero.live_combos(board).collect();
+    let villain_live: Vec<([Card; 2], f64)> = villain.live_combos(board).collect();
+
+    // Per hero combo: (weighted pot share, weighted matchups)
+    let mut share = vec![0.0_f64; hero_live.len()];
+    let mut total = vec![0.0_f64; hero_live.len()];
+    let mut win_sum = 0.0_f64;
+    let mut tie_sum = 0.0_f64;
+
+    let hero_cards: Vec<[Card; 2]> = hero_live.iter().map(|&(h, _)| h).collect();
+    let villain_cards: Vec<[Card; 2]> = villain_live.iter().map(|&(v, _)| v).collect();
+    let villain_weights: Vec<f64> = villain_live.iter().map(|&(_, w)| w).collect();
+
+    runouts(&mut |full_board| {
+        let hero_ranks = BoardRanks::new(evaluator, *full_board, &hero_cards);
+        let villain_ranks = BoardRanks::new(evaluator, *full_board, &villain_cards);
+        let sums = showdown_weights(&hero_ranks, &villain_ranks, &villain_weights);
+        for (i, &(_, wh)) in hero_live.iter().enumerate() {
+            let (win, lose, tie) = (sums.win[i], sums.lose[i], sums.tie[i]);
+            total[i] += wh * (win + lose + tie);
+            share[i] += wh * (win + 0.5 * tie);
+            win_sum += wh * win;
+            tie_sum += wh * tie;
+        }
+    });
+
+    let grand_total: f64 = total.iter().sum();
+    let grand_share: f64 = share.iter().sum();
+    let hero_combos = hero_live.iter().enumerate()
+        .filter(|(i, _)| total[*i] > 0.0)
+        .map(|(i, &(hand, weight))| ComboEquity { hand, weight, equity: share[i] / total[i] })
+        .collect();
+
+    if grand_total <= 0.0 {
+        return EquityResult { hero_equity: 0.0, villain_equity: 0.0, win: 0.0, tie: 0.0, hero_combos };
+    }
+    let hero_equity = grand_share / grand_total;
+    EquityResult {
+        hero_equity,
+        villain_equity: 1.0 - hero_equity,
+        win: win_sum / grand_total,
+        tie: tie_sum / grand_total,
+        hero_combos,
+    }
+}
+
+/// Exact equity of a single hand against a range on `board`.
+pub fn hand_vs_range<E: HandEvaluator>(
+    evaluator: &E,
+    hand: [Card; 2],
+    villain: &Range,
+    board: &[Card],
+) -> f64 {
+    let mut hero = Range::new();
+    hero.set_weight(hand, 1.0);
+    range_vs_range(evaluator, &hero, villain, board).hero_equity
+}
+
+/// Exact equity of one hand against another on `board`: the pot share
+/// either would take all-in, before the rest of the board comes.
+pub fn hand_vs_hand<E: HandEvaluator>(evaluator: &E, hero: [Card; 2], villain: [Card; 2], board: &[Card]) -> f64 {
+    let mut range = Range::new();
+    range.set_weight(villain, 1.0);
+    hand_vs_range(evaluator, hero, &range, board)
+}
+
+/// Expected hand strength of one combo over the runouts of a board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandStrength {
+    /// Mean river HS
+    pub ehs: f64,
+    /// Mean squared river HS (rewards hands whose strength varies, i.e. draws)
+    pub ehs2: f64,
+}
+
+/// Hand strength (HS): pot share of `hand` against a uniformly random
+/// opponent hand on the current 3–5 card `board`, with no cards to come.
+pub fn hand_strength(evaluator: &CactusKevEvaluator, hand: [Card; 2], board: &[Card]) -> f64 {
+    assert!((3..=5).contains(&board.len()), "board must have 3 to 5 cards");
+    match <[Card; 5]>::try_from(board) {
+        Ok(river) => river_strength(evaluator, hand, &river, &mut StrengthScratch::default()),
+        Err(_) => {
+            let hero = evaluator.evaluate_partial(board, hand);
+            let opponents = Deck::from_mask(!(combo_mask(&hand) | board_mask(board))).combos();
+            pot_share(hero, opponents.map(|h| evaluator.evaluate_partial(board, h)))
+        }
+    }
+}
+
+/// EHS and EHS²: mean river hand strength of `hand` (and its square) over
+/// every completion of `board` (0–5 cards).
+pub fn expected_hand_strength(evaluator: &CactusKevEvaluator, hand: [Card; 2], board: &[Card]) -> HandStrength {
+    assert!(board.len() <= 5, "board must have at most 5 cards");
+    let hand_mask = combo_mask(&hand);
+    let mut scratch = StrengthScratch::default();
+    let (mut sum, mut sum_sq, mut runouts) = (0.0, 0.0, 0usize);
+    for_each_runout(board, |full_board, runout_mask| {
+        if runout_mask & hand_mask != 0 {
+            return;
+        }
+        let hs = river_strength(evaluator, hand, full_board, &mut scratch);
+        sum += hs;
+        sum_sq += hs * hs;
+        runouts += 1;
+    });
+    let n = runouts.max(1) as f64;
+    HandStrength { ehs: sum / n, ehs2: sum_sq / n }
+}
+
+/// Buffers reused across runouts so river HS doesn't allocate
+#[derive(Default)]
+struct StrengthScratch {
+    hands: Vec<[Card; 2]>,
+    boards: Vec<[Card; 5]>,
+    ranks: Vec<HandRank>,
+}
+
+fn river_strength(evaluator: &CactusKevEvaluator, hand: [Card; 2], board: &[Card; 5], scratch: &mut StrengthScratch) -> f64 {
+    let dead = combo_mask(&hand) | board_mask(board);
+    scratch.hands.clear();
+    scratch.hands.extend(Deck::from_mask(!dead).combos());
+    scratch.boards.clear();
+    scratch.boards.resize(scratch.hands.len(), *board);
+    scratch.ranks.resize(scratch.hands.len(), HandRank::new(0));
+    evaluator.evaluate_batch_into(&scratch.boards, &scratch.hands, &mut scratch.ranks);
+    pot_share(evaluator.evaluate_7cards(*board, hand), scratch.ranks.iter().copied())
+}
+
+/// Wins plus half of ties, over the opponents' ranks (lower rank is stronger)
+fn pot_share(hero: HandRank, opponents: impl Iterator<Item = HandRank>) -> f64 {
+    let (mut share, mut n) = (0.0, 0usize);
+    for rank in opponents {
+        n += 1;
+        if hero < rank {
+            share += 1.0;
+        } else if hero == rank {
+            share += 0.5;
+        }
+    }
+    if n == 0 { 0.0 } else { share / n as f64 }
+}
+
+fn board_mask(board: &[Card]) -> u64 {
+    board.iter().fold(0u64, |m, c| m | c.mask())
+}
+
+fn combo_mask(hand: &[Card; 2]) -> u64 {
+    hand[0].mask() | hand[1].mask()
+}
+
+/// Call `f(full_board, runout_mask)` for every completion of `board` to 5 cards.
+///
+/// `runout_mask` holds only the newly dealt cards.
+pub(crate) fn for_each_runout(board: &[Card], mut f: impl FnMut(&[Card; 5], u64)) {
+    let remaining: Vec<Card> = Deck::without(board).iter().collect();
+    let need = 5 - board.len();
+    let mut full = [Card::new(0); 5];
+    full[..board.len()].copy_from_slice(board);
+
+    // Iterative k-combination enumeration over `remaining`
+    let mut idx: Vec<usize> = (0..need).collect();
+    loop {
+        let mut mask = 0u64;
+        for (k, &i) in idx.iter().enumerate() {
+            full[board.len() + k] = remaining[i];
+            mask |= remaining[i].mask();
+        }
+        f(&full, mask);
+
+        // Advance to the next combination
+        let mut k = need;
+        loop {
+            if k == 0 {
+                return;
+            }
+            k -= 1;
+            if idx[k] < remaining.len() - need + k {
+                break;
+            }
+            if k == 0 {
+                return;
+            }
+        }
+        idx[k] += 1;
+        for m in (k + 1)..need {
+            idx[m] = idx[m - 1] + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluator::CactusKevEvaluator;
+    use crate::node::parse_cards;
+
+    #[test]
+    fn test_runout_counts() {
+        let mut n = 0;
+        for_each_runout(&parse_cards("AsKh7d").unwrap(), |_, _| n += 1);
+        assert_eq!(n, 49 * 48 / 2);
+        let mut n = 0;
+        for_each_runout(&parse_cards("AsKh7d2c9s").unwrap(), |_, _| n += 1);
+        assert_eq!(n, 1);
+    }
+
+    #[test]
+    fn test_river_nut_hand_has_full_equity() {
+        let eval = CactusKevEvaluator::new();
+        let board = parse_cards("AsKsQsJs2h").unwrap();
+        let ts = parse_cards("Ts3c").unwrap();
+        let villain = Range::parse("AA,KK,QQ").unwrap();
+        let eq = hand_vs_range(&eval, [ts[0], ts[1]], &villain, &board);
+        assert_eq!(eq, 1.0);
+    }
+
+    #[test]
+    fn test_river_chop_is_half() {
+        let eval = CactusKevEvaluator::new();
+        // Board straight plays for everyone
+        let board = parse_cards("AsKdQhJcTs").unwrap();
+        let hero = Range::parse("22").unwrap();
+        let villain = Range::parse("33").unwrap();
+        let r = range_vs_range(&eval, &hero, &villain, &board);
+        assert!((r.hero_equity - 0.5).abs() < 1e-12);
+        assert!((r.tie - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_equities_sum_to_one() {
+        let eval = CactusKevEvaluator::new();
+        let board = parse_cards("AsKh7d2c").unwrap();
+        let hero = Range::parse("AK,77").unwrap();
+        let villain = Range::parse("QQ+,T9s").unwrap();
+        let r = range_vs_range(&eval, &hero, &villain, &board);
+        assert!((r.hero_equity + r.villain_equity - 1.0).abs() < 1e-12);
+        assert!(r.hero_equity > 0.5, "sets and top two should be ahead: {}", r.hero_equity);
+    }
+
+    #[test]
+    fn test_turn_set_vs_overpair() {
+        let eval = CactusKevEvaluator::new();
+        let board = parse_cards("7h8c2d3s").unwrap();
+        let sevens = parse_cards("7s7d").unwrap();
+        let villain = Range::parse("AhAd").unwrap();
+        let eq = hand_vs_range(&eval, [sevens[0], sevens[1]], &villain, &board);
+        // Villain needs one of the 2 remaining aces among 44 rivers
+        assert!((eq - 42.0 / 44.0).abs() < 1e-12, "equity {}", eq);
+        let aces = parse_cards("AhAd").unwrap();
+        assert_eq!(hand_vs_hand(&eval, [sevens[0], sevens[1]], [aces[0], aces[1]], &board), eq);
+    }
+
+    #[test]
+    fn test_sampled_equity_approaches_exact_and_groups_by_class() {
+        let eval = CactusKevEvaluator::new();
+        let board = parse_cards("AsKh7d").unwrap();
+        let hero = Range::parse("AA,KK,AK").unwrap();
+        let villain = Range::parse("77,88,T9s").unwrap();
+        let exact = range_vs_range(&eval, &hero, &villain, &board);
+        let sampled = range_vs_range_sampled(&eval, &hero, &villain, &board, 2000, &mut Rng::new(7));
+        assert!((sampled.hero_equity - exact.hero_equity).abs() < 0.02, "{} vs {}", sampled.hero_equity, exact.hero_equity);
+
+        let classes = exact.by_class();
+        let labels: Vec<String> = classes.iter().map(|c| crate::combo::class_label(c.class)).collect();
+        assert_eq!(labels, ["AA", "AKs", "AKo", "KK"]);
+        let total: f64 = classes.iter().map(|c| c.combos).sum();
+        assert!((total - hero.summary(&board).combos).abs() < 1e-9);
+        let mean = classes.iter().map(|c| c.combos * c.equity).sum::<f64>() / total;
+        assert!((mean - exact.hero_equity).abs() < 0.05);
+    }
+
+    fn hand(s: &str) -> [Card; 2] {
+        let cards = parse_cards(s).unwrap();
+        [cards[0], cards[1]]
+    }
+
+    #[test]
+    fn test_river_hand_strength_is_equity_vs_random_hand() {
+        let eval = CactusKevEvaluator::new();
+        let board = parse_cards("AsKh7d2c9s").unwrap();
+        for h in ["AhAd", "7h2h", "3c4c", "KsQd"] {
+            let hs = hand_strength(&eval, hand(h), &board);
+            let expected = hand_vs_range(&eval, hand(h), &Range::full(), &board);
+            assert!((hs - expected).abs() < 1e-12, "{}: {} vs {}", h, hs, expected);
+            // Nothing to come: EHS is HS and EHS² its square
+            let ehs = expected_hand_strength(&eval, hand(h), &board);
+            assert!((ehs.ehs - hs).abs() < 1e-12 && (ehs.ehs2 - hs * hs).abs() < 1e-12);
+        }
+        let nuts = hand_strength(&eval, hand("Ts3c"), &parse_cards("AsKsQsJs2h").unwrap());
+        assert_eq!(nuts, 1.0);
+    }
+
+    #[test]
+    fn test_turn_ehs_averages_river_strength() {
+        let eval = CactusKevEvaluator::new();
+        let board = parse_cards("Ks9s4d2c").unwrap();
+        // Uniform opponents are equally many on every river, so EHS is equity vs a random hand
+        let draw = expected_hand_strength(&eval, hand("AsTs"), &board);
+        let expected = hand_vs_range(&eval, hand("AsTs"), &Range::full(), &board);
+        assert!((draw.ehs - expected).abs() < 1e-12, "{} vs {}", draw.ehs, expected);
+        assert!(draw.ehs2 > draw.ehs * draw.ehs);
+
+        // The flush draw is behind now but improves; a made pair of kings mostly holds
+        assert!(hand_strength(&eval, hand("AsTs"), &board) < draw.ehs);
+        let pair = expected_hand_strength(&eval, hand("KhQd"), &board);
+        assert!(pair.ehs > draw.ehs);
+        // EHS² separates the draw from a made hand of similar EHS
+        assert!(draw.ehs2 - draw.ehs * draw.ehs > pair.ehs2 - pair.ehs * pair.ehs);
+    }
+}
@@ -0,0 +1,122 @@
+//! Terminal EV tables: IP's value at each terminal node
+//!
+//! Solvers look up a terminal's EV once per visit, so the lookup sits in the
+//! innermost loop of every traversal. `EvTable` stores the values densely,
+//! indexed by node ID, so that lookup is a bounds-checked array read rather
+//! than a hash. Slots for nodes without an EV (decisions, chance nodes) hold
+//! NaN.
+//!
+//! Traversals that don't run per iteration (analysis, best responses) take
+//! any `TerminalEvs`, so both an `EvTable` and the `HashMap<NodeId, f64>`
+//! that `build_terminal_evs` returns work there.
+
+use std::collections::HashMap;
+use crate::node::NodeId;
+
+/// A lookup from terminal node ID to IP's EV
+pub trait TerminalEvs: Sync {
+    /// IP's EV at terminal `node_id`; panics if the table has none
+    fn terminal_ev(&self, node_id: NodeId) -> f64;
+}
+
+impl TerminalEvs for HashMap<NodeId, f64> {
+    fn terminal_ev(&self, node_id: NodeId) -> f64 {
+        self[&node_id]
+    }
+}
+
+/// Terminal EVs indexed by node ID (see module docs)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EvTable {
+    evs: Vec<f64>,
+}
+
+impl EvTable {
+    /// Empty table
+    pub fn new() -> Self {
+        EvTable::default()
+    }
+
+    /// EV at `node_id`, if it has one
+    pub fn get(&self, node_id: NodeId) -> Option<f64> {
+        self.evs.get(node_id as usize).copied().filter(|ev| !ev.is_nan())
+    }
+
+    /// Set the EV at `node_id`, growing the table as needed
+    pub fn insert(&mut self, node_id: NodeId, ev: f64) {
+        let i = node_id as usize;
+        if i >= self.evs.len() {
+            self.evs.resize(i + 1, f64::NAN);
+        }
+        self.evs[i] = ev;
+    }
+
+    /// Number of nodes with an EV
+    pub fn len(&self) -> usize {
+        self.evs.iter().filter(|ev| !ev.is_nan()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `(node_id, ev)` for every node with an EV, in node order
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, f64)> + '_ {
+        self.evs.iter().enumerate().filter(|(_, ev)| !ev.is_nan()).map(|(i, &ev)| (i as NodeId, ev))
+    }
+
+    /// The same EVs keyed by node ID
+    pub fn to_map(&self) -> HashMap<NodeId, f64> {
+        self.iter().collect()
+    }
+}
+
+impl TerminalEvs for EvTable {
+    fn terminal_ev(&self, node_id: NodeId) -> f64 {
+        self.get(node_id).unwrap_or_else(|| panic!("no terminal EV for node {}", node_id))
+    }
+}
+
+impl FromIterator<(NodeId, f64)> for EvTable {
+    fn from_iter<I: IntoIterator<Item = (NodeId, f64)>>(iter: I) -> Self {
+        let mut table = EvTable::new();
+        for (node_id, ev) in iter {
+            table.insert(node_id, ev);
+        }
+        table
+    }
+}
+
+impl From<HashMap<NodeId, f64>> for EvTable {
+    fn from(evs: HashMap<NodeId, f64>) -> Self {
+        evs.into_iter().collect()
+    }
+}
+
+impl From<&HashMap<NodeId, f64>> for EvTable {
+    fn from(evs: &HashMap<NodeId, f64>) -> Self {
+        evs.iter().map(|(&id, &ev)| (id, ev)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_tree::terminal_ev_table;
+
+    #[test]
+    fn test_table_matches_map() {
+        let map = terminal_ev_table();
+        let table = EvTable::from(&map);
+        assert_eq!(table.len(), map.len());
+        for (&id, &ev) in &map {
+            assert_eq!(table.terminal_ev(id), map.terminal_ev(id));
+            assert_eq!(table.get(id), Some(ev));
+        }
+        // The root is a decision: no EV
+        assert_eq!(table.get(0), None);
+        assert_eq!(table.get(1_000), None);
+        assert_eq!(table.to_map(), map);
+        assert!(table.iter().map(|(id, _)| id).collect::<Vec<_>>().windows(2).all(|w| w[0] < w[1]));
+    }
+}
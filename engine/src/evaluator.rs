@@ -8,6 +8,7 @@
 //! The evaluator is designed for high throughput (target: 50M+ evals/sec).
 
 use crate::node::{Card, HandEvaluator, HandRank};
+use crate::rng::{Rng, DEFAULT_SEED};
 
 /// Cactus Kev evaluator implementation
 ///
@@ -44,82 +45,83 @@ impl CactusKevEvaluator {
         HandRank::new(tables::best_nonflush_hand_7(&rank_counts))
     }
 
-    /// Reference evaluator using the original 21-combination loop.
-    /// Used only by consistency tests to cross-check the bitboard path.
-    #[cfg(test)]
-    fn evaluate_7cards_reference(&self, board: [Card; 5], hand: [Card; 2]) -> HandRank {
-        let all_cards = [board[0], board[1], board[2], board[3], board[4], hand[0], hand[1]];
-        let mut best_rank = u16::MAX;
-        for i in 0..7 {
-            for j in (i + 1)..7 {
-                for k in (j + 1)..7 {
-                    for l in (k + 1)..7 {
-                        for m in (l + 1)..7 {
-                            let five_cards = [all_cards[i], all_cards[j], all_cards[k], all_cards[l], all_cards[m]];
-                            let rank = self.rank_5cards_ref(five_cards);
-                            if rank < best_rank {
-                                best_rank = rank;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        HandRank::new(best_rank)
-    }
-
-    /// 5-card ranker used only by the reference evaluator.
-    #[cfg(test)]
-    fn rank_5cards_ref(&self, cards: [Card; 5]) -> u16 {
+    /// Evaluate the best 5-card hand among 5–7 cards
+    ///
+    /// Same tables and rank scale as `evaluate_7cards`, so flop (5-card) and
+    /// turn (6-card) snapshots compare directly with river ranks.
+    ///
+    /// Panics unless `cards` holds 5 to 7 cards.
+    pub fn evaluate_cards(&self, cards: &[Card]) -> HandRank {
+        assert!((5..=7).contains(&cards.len()), "need 5 to 7 cards, got {}", cards.len());
         let mut suit_masks = [0u16; 4];
-        for card in cards.iter() {
-            let card_val = card.value();
-            let suit = (card_val / 13) as usize;
-            let rank = card_val % 13;
-            suit_masks[suit] |= 1u16 << rank;
-        }
-        let mut flush_suit = None;
-        for (suit_idx, mask) in suit_masks.iter().enumerate() {
-            if mask.count_ones() == 5 {
-                flush_suit = Some(suit_idx);
-                break;
-            }
+        let mut rank_counts = [0u8; 13];
+        for card in cards {
+            let v = card.value();
+            suit_masks[(v / 13) as usize] |= 1u16 << (v % 13);
+            rank_counts[(v % 13) as usize] += 1;
         }
-        if let Some(suit_idx) = flush_suit {
-            let rank_mask = suit_masks[suit_idx];
-            tables::get_flush_table()[rank_mask as usize]
-        } else {
-            use tables::RANK_PRIMES_REF;
-            let mut product = 1u32;
-            for card in cards.iter() {
-                let rank = card.value() % 13;
-                product *= RANK_PRIMES_REF[rank as usize];
+        for mask in suit_masks.iter() {
+            if mask.count_ones() >= 5 {
+                return HandRank::new(tables::best_flush_hand_7(*mask));
             }
-            tables::lookup_nonflush_rank(product)
         }
+        HandRank::new(tables::best_nonflush_hand_7(&rank_counts))
+    }
+
+    /// Current made hand of `hand` on a flop, turn, or river `board`
+    ///
+    /// Panics unless `board` holds 3 to 5 cards.
+    pub fn evaluate_partial(&self, board: &[Card], hand: [Card; 2]) -> HandRank {
+        assert!((3..=5).contains(&board.len()), "board must have 3 to 5 cards, got {}", board.len());
+        let mut cards = [hand[0]; 7];
+        cards[1] = hand[1];
+        cards[2..2 + board.len()].copy_from_slice(board);
+        self.evaluate_cards(&cards[..2 + board.len()])
     }
 
     /// Evaluate a batch of 7-card hands
     ///
-    /// Uses NEON-accelerated path on ARM64 (Apple Silicon), falls back to scalar
-    /// path on other architectures.
+    /// Allocates the result; use `evaluate_batch_into` to reuse a buffer.
     pub fn evaluate_batch(&self, boards: &[[Card; 5]], hands: &[[Card; 2]]) -> Vec<HandRank> {
+        let mut results = vec![HandRank::new(0); boards.len()];
+        self.evaluate_batch_into(boards, hands, &mut results);
+        results
+    }
+
+    /// Evaluate a batch of 7-card hands into a caller-provided buffer
+    ///
+    /// Uses NEON-accelerated path on ARM64 (Apple Silicon), falls back to scalar
+    /// path on other architectures. Never allocates.
+    ///
+    /// Panics if `boards`, `hands`, and `out` differ in length.
+    pub fn evaluate_batch_into(&self, boards: &[[Card; 5]], hands: &[[Card; 2]], out: &mut [HandRank]) {
         assert_eq!(boards.len(), hands.len(), "boards and hands must have same length");
+        assert_eq!(boards.len(), out.len(), "out must have one slot per hand");
 
         #[cfg(target_arch = "aarch64")]
         {
-            return neon::evaluate_batch_neon(self, boards, hands);
+            neon::evaluate_batch_neon(self, boards, hands, out);
         }
 
         #[cfg(not(target_arch = "aarch64"))]
         {
-            let mut results = Vec::with_capacity(boards.len());
-            for i in 0..boards.len() {
-                results.push(self.evaluate_7cards(boards[i], hands[i]));
+            for ((rank, &board), &hand) in out.iter_mut().zip(boards).zip(hands) {
+                *rank = self.evaluate_7cards(board, hand);
             }
-            results
         }
     }
+
+    /// Evaluate `(board, hand)` pairs lazily, one rank per pair
+    ///
+    /// Nothing is buffered, so callers that gather hands on the fly (skipping
+    /// blocked combos, say) can evaluate without intermediate vectors.
+    pub fn evaluate_iter<I>(&self, hands: I) -> impl Iterator<Item = HandRank>
+    where
+        I: IntoIterator<Item = ([Card; 5], [Card; 2])>,
+    {
+        let evaluator = *self;
+        hands.into_iter().map(move |(board, hand)| evaluator.evaluate_7cards(board, hand))
+    }
 }
 
 impl Default for CactusKevEvaluator {
@@ -134,36 +136,41 @@ impl HandEvaluator for CactusKevEvaluator {
     }
 }
 
+/// Highest rank of the flush with table value `rank` (323–1599)
+///
+/// Flush values compress 1,277 rank sets into the 323–1599 range, so this
+/// scans the table rather than inverting the arithmetic.
+pub(crate) fn flush_high_rank(rank: u16) -> u8 {
+    let table = tables::get_flush_table();
+    (0u16..8192).rev()
+        .find(|&mask| mask.count_ones() == 5 && table[mask as usize] == rank)
+        .map_or(0, |mask| 15 - mask.leading_zeros() as u8)
+}
+
 /// Benchmark helper for CLI
 ///
 /// Runs a batch evaluation and returns (evals_per_sec, duration_ms)
 pub fn benchmark_throughput(sample_size: usize) -> (f64, u64) {
+    benchmark_throughput_with_seed(sample_size, DEFAULT_SEED)
+}
+
+/// Benchmark helper with an explicit RNG seed for the generated hands.
+///
+/// Runs a batch evaluation and returns (evals_per_sec, duration_ms)
+pub fn benchmark_throughput_with_seed(sample_size: usize, seed: u64) -> (f64, u64) {
     use std::time::Instant;
 
     let evaluator = CactusKevEvaluator::new();
 
     // Generate test hands (same logic as benchmark)
-    let mut seed: u64 = 12345;
-    let lcg_next = |s: &mut u64| {
-        *s = s.wrapping_mul(1103515245).wrapping_add(12345) & 0x7fffffff;
-        (*s % 52) as u8
-    };
-
+    let mut rng = Rng::new(seed);
     let mut boards = Vec::with_capacity(sample_size);
     let mut hands = Vec::with_capacity(sample_size);
 
     for _ in 0..sample_size {
-        let mut cards = Vec::new();
-        while cards.len() < 7 {
-            let card_val = lcg_next(&mut seed);
-            if !cards.contains(&card_val) {
-                cards.push(card_val);
-            } else {
-                seed = seed.wrapping_add(1);
-            }
-        }
-        boards.push([Card::new(cards[0]), Card::new(cards[1]), Card::new(cards[2]), Card::new(cards[3]), Card::new(cards[4])]);
-        hands.push([Card::new(cards[5]), Card::new(cards[6])]);
+        let (board, hand) = rng.board_and_hand();
+        boards.push(board);
+        hands.push(hand);
     }
 
     // Warm-up (also initializes FLUSH_TABLE)
@@ -182,7 +189,7 @@ pub fn benchmark_throughput(sample_size: usize) -> (f64, u64) {
     (evals_per_sec, duration_ms)
 }
 
-mod tables {
+pub(crate) mod tables {
     //! Lookup tables for hand evaluator
     //!
     //! FLUSH_TABLE: 8192-entry precomputed table (16 KB) — fits in L1 cache.
@@ -193,8 +200,8 @@ mod tables {
     static FLUSH_TABLE: OnceLock<[u16; 8192]> = OnceLock::new();
 
     /// Prime numbers for each rank (2-A, where 2=index 0, A=index 12).
-    /// Exposed for use by the reference evaluator in tests.
-    #[cfg(test)]
+    /// Exposed for use by the reference evaluator (`testutil`).
+    #[cfg(any(test, feature = "testutil"))]
     pub(crate) const RANK_PRIMES_REF: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
 
     /// Return a reference to the precomputed flush rank table.
@@ -229,7 +236,7 @@ mod tables {
         }
         // Check wheel SF: A-5-4-3-2 = bits 12,3,2,1,0 = 0x100F
         if suit_mask & 0x100F == 0x100F {
-            return table[0x100F as usize];
+            return table[0x100F];
         }
 
         // Regular flush: keep top 5 bits of suit_mask
@@ -269,17 +276,13 @@ mod tables {
                         }
                     }
                 }
-                2 => {
-                    if pair_count < 3 {
-                        pairs[pair_count] = i as u8;
-                        pair_count += 1;
-                    }
+                2 if pair_count < 3 => {
+                    pairs[pair_count] = i as u8;
+                    pair_count += 1;
                 }
-                1 => {
-                    if single_count < 7 {
-                        singles[single_count] = i as u8;
-                        single_count += 1;
-                    }
+                1 if single_count < 7 => {
+                    singles[single_count] = i as u8;
+                    single_count += 1;
                 }
                 _ => {}
             }
@@ -304,8 +307,8 @@ mod tables {
         // Priority 3: Straight (using rank_present bitmask)
         {
             let mut rank_present: u16 = 0;
-            for i in 0..13 {
-                if rank_counts[i] > 0 {
+            for (i, &count) in rank_counts.iter().enumerate() {
+                if count > 0 {
                     rank_present |= 1u16 << i;
                 }
             }
@@ -376,16 +379,16 @@ mod tables {
         best
     }
 
-    /// Non-flush rank lookup used only by the reference evaluator in tests.
+    /// Non-flush rank lookup used only by the reference evaluator (`testutil`).
     /// Builds rank counts from prime factorization then classifies — same logic as
     /// `best_nonflush_hand_7` so results are consistent for 5-card subhands.
-    #[cfg(test)]
+    #[cfg(any(test, feature = "testutil"))]
     pub(crate) fn lookup_nonflush_rank(product: u32) -> u16 {
         // Recover per-rank counts from prime product
         let mut rank_counts = [0u8; 13];
         let mut remaining = product;
         for (idx, &prime) in RANK_PRIMES_REF.iter().enumerate() {
-            while remaining % prime == 0 && remaining > 0 {
+            while remaining > 0 && remaining.is_multiple_of(prime) {
                 remaining /= prime;
                 rank_counts[idx] += 1;
             }
@@ -407,8 +410,8 @@ mod tables {
             match rank_counts[i] {
                 4 => { quad_rank = i as u8; }
                 3 => { trips_rank = i as u8; }
-                2 => { if pair_count < 2 { pairs[pair_count] = i as u8; pair_count += 1; } }
-                1 => { if single_count < 5 { singles[single_count] = i as u8; single_count += 1; } }
+                2 if pair_count < 2 => { pairs[pair_count] = i as u8; pair_count += 1; }
+                1 if single_count < 5 => { singles[single_count] = i as u8; single_count += 1; }
                 _ => {}
             }
         }
@@ -431,7 +434,7 @@ mod tables {
         // Straight check
         {
             let mut rank_present = 0u16;
-            for i in 0..13 { if rank_counts[i] > 0 { rank_present |= 1u16 << i; } }
+            for (i, &count) in rank_counts.iter().enumerate() { if count > 0 { rank_present |= 1u16 << i; } }
             for high in (4u8..=12).rev() {
                 let mask = 0x1Fu16 << (high - 4);
                 if rank_present & mask == mask {
@@ -544,7 +547,7 @@ mod neon {
     use crate::node::Card;
     use super::{CactusKevEvaluator, HandRank};
 
-    /// NEON-accelerated batch evaluation.
+    /// NEON-accelerated batch evaluation into `out`.
     ///
     /// Each hand is evaluated with the O(1) scalar bitboard path.
     /// The SIMD benefit comes from cache-warm FLUSH_TABLE + branch-free arithmetic.
@@ -552,10 +555,11 @@ mod neon {
         evaluator: &CactusKevEvaluator,
         boards: &[[Card; 5]],
         hands: &[[Card; 2]],
-    ) -> Vec<HandRank> {
-        boards.iter().zip(hands.iter())
-            .map(|(&b, &h)| evaluator.evaluate_7cards(b, h))
-            .collect()
+        out: &mut [HandRank],
+    ) {
+        for ((rank, &b), &h) in out.iter_mut().zip(boards).zip(hands) {
+            *rank = evaluator.evaluate_7cards(b, h);
+        }
     }
 }
 
@@ -641,33 +645,8 @@ mod tests {
     fn test_new_vs_old_evaluator_consistency() {
         let eval = CactusKevEvaluator::new();
 
-        let mut seed: u64 = 98765;
-        let lcg = |s: &mut u64| -> u8 {
-            *s = (*s).wrapping_mul(1103515245).wrapping_add(12345) & 0x7fffffff;
-            (*s % 52) as u8
-        };
-
-        for i in 0..50_000usize {
-            let mut cards = [0u8; 7];
-            let mut used = [false; 52];
-            let mut idx = 0;
-            while idx < 7 {
-                let v = lcg(&mut seed);
-                if !used[v as usize] {
-                    used[v as usize] = true;
-                    cards[idx] = v;
-                    idx += 1;
-                }
-            }
-            let board = [Card::new(cards[0]), Card::new(cards[1]), Card::new(cards[2]),
-                         Card::new(cards[3]), Card::new(cards[4])];
-            let hand  = [Card::new(cards[5]), Card::new(cards[6])];
-
-            let new_rank = eval.evaluate_7cards(board, hand).value();
-            let ref_rank = eval.evaluate_7cards_reference(board, hand).value();
-
-            assert_eq!(new_rank, ref_rank,
-                "Mismatch on hand {i}: new={new_rank} ref={ref_rank} cards={cards:?}");
+        if let Err(mismatch) = crate::testutil::differential_test(&eval, 50_000, 98765) {
+            panic!("{}", mismatch);
         }
     }
 
@@ -782,36 +761,20 @@ mod tests {
     fn test_large_sample_category_validation() {
         let eval = CactusKevEvaluator::new();
 
-        let mut seed: u64 = 12345;
-        let lcg = |s: &mut u64| {
-            *s = (*s * 1103515245 + 12345) & 0x7fffffff;
-            (*s % 52) as u8
-        };
+        let mut rng = Rng::new(12345);
 
         for _ in 0..10000 {
-            let mut cards = Vec::new();
-            while cards.len() < 7 {
-                let card_val = lcg(&mut seed);
-                if !cards.contains(&card_val) {
-                    cards.push(card_val);
-                }
-            }
-
-            let board = [
-                Card::new(cards[0]), Card::new(cards[1]), Card::new(cards[2]),
-                Card::new(cards[3]), Card::new(cards[4]),
-            ];
-            let hand = [Card::new(cards[5]), Card::new(cards[6])];
+            let (board, hand) = rng.board_and_hand();
 
             let rank = eval.evaluate(board, hand);
             let rank_val = rank.value();
 
-            assert!(rank_val >= 1 && rank_val <= 7462,
+            assert!((1..=7462).contains(&rank_val),
                    "Rank {} should be in range [1, 7462]", rank_val);
 
             let all_cards = [board[0], board[1], board[2], board[3], board[4], hand[0], hand[1]];
-            let mut rank_counts = vec![0u8; 13];
-            let mut suit_counts = vec![0u8; 4];
+            let mut rank_counts = [0u8; 13];
+            let mut suit_counts = [0u8; 4];
 
             for card in all_cards.iter() {
                 let card_val = card.value();
@@ -831,27 +794,84 @@ mod tests {
             }) || (rank_bits & 0x100F == 0x100F);
 
             if rank_counts[0] == 4 {
-                assert!(rank_val >= 11 && rank_val <= 166,
+                assert!((11..=166).contains(&rank_val),
                        "Four of a kind rank {} should be in range [11, 166]", rank_val);
             } else if rank_counts[0] == 3 && rank_counts[1] == 2 {
-                assert!(rank_val >= 167 && rank_val <= 322,
+                assert!((167..=322).contains(&rank_val),
                        "Full house rank {} should be in range [167, 322]", rank_val);
             } else if rank_counts[0] == 3 && rank_counts[1] < 2 && suit_counts[0] < 5 && !can_form_straight {
-                assert!(rank_val >= 1610 && rank_val <= 2467,
+                assert!((1610..=2467).contains(&rank_val),
                        "Three of a kind rank {} should be in range [1610, 2467]", rank_val);
             } else if rank_counts[0] == 2 && rank_counts[1] == 2 && suit_counts[0] < 5 && !can_form_straight {
-                assert!(rank_val >= 2468 && rank_val <= 3325,
+                assert!((2468..=3325).contains(&rank_val),
                        "Two pair rank {} should be in range [2468, 3325]", rank_val);
             } else if rank_counts[0] == 2 && suit_counts[0] < 5 && !can_form_straight {
-                assert!(rank_val >= 3326 && rank_val <= 6185,
+                assert!((3326..=6185).contains(&rank_val),
                        "One pair rank {} should be in range [3326, 6185]", rank_val);
             } else {
-                assert!(rank_val >= 1 && rank_val <= 7462,
+                assert!((1..=7462).contains(&rank_val),
                        "High card/flush/straight rank {} should be in range [1, 7462]", rank_val);
             }
         }
     }
 
+    #[test]
+    fn test_partial_boards_match_best_five_card_subset() {
+        use crate::testutil::reference_rank_5;
+        let eval = CactusKevEvaluator::new();
+        let mut rng = Rng::new(4125);
+        for _ in 0..5_000 {
+            let (board, hand) = rng.board_and_hand();
+            let flop = eval.evaluate_partial(&board[..3], hand);
+            assert_eq!(flop.value(), reference_rank_5([board[0], board[1], board[2], hand[0], hand[1]]).value());
+
+            let cards = [board[0], board[1], board[2], board[3], hand[0], hand[1]];
+            let best = (0..6).map(|skip| {
+                let five: Vec<Card> = cards.iter().enumerate().filter(|&(i, _)| i != skip).map(|(_, &c)| c).collect();
+                reference_rank_5(five.try_into().unwrap()).value()
+            }).min().unwrap();
+            let turn = eval.evaluate_partial(&board[..4], hand);
+            assert_eq!(turn.value(), best);
+
+            // Made hands only improve as cards come
+            let river = eval.evaluate_partial(&board, hand);
+            assert_eq!(river, eval.evaluate_7cards(board, hand));
+            assert!(river <= turn && turn <= flop);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "3 to 5 cards")]
+    fn test_partial_rejects_preflop_board() {
+        let (board, hand) = Rng::new(1).board_and_hand();
+        CactusKevEvaluator::new().evaluate_partial(&board[..2], hand);
+    }
+
+    #[test]
+    fn test_batch_into_and_iter_match_batch() {
+        let eval = CactusKevEvaluator::new();
+        let mut rng = Rng::new(4124);
+        let pairs: Vec<([Card; 5], [Card; 2])> = (0..1_000).map(|_| rng.board_and_hand()).collect();
+        let boards: Vec<[Card; 5]> = pairs.iter().map(|p| p.0).collect();
+        let hands: Vec<[Card; 2]> = pairs.iter().map(|p| p.1).collect();
+        let expected = eval.evaluate_batch(&boards, &hands);
+        assert_eq!(expected, pairs.iter().map(|&(b, h)| eval.evaluate(b, h)).collect::<Vec<_>>());
+
+        // A reused buffer is fully overwritten
+        let mut out = vec![HandRank::new(u16::MAX); pairs.len()];
+        eval.evaluate_batch_into(&boards, &hands, &mut out);
+        assert_eq!(out, expected);
+        assert_eq!(eval.evaluate_iter(pairs.iter().copied()).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "one slot per hand")]
+    fn test_batch_into_rejects_short_buffer() {
+        let eval = CactusKevEvaluator::new();
+        let (board, hand) = Rng::new(1).board_and_hand();
+        eval.evaluate_batch_into(&[board, board], &[hand, hand], &mut [HandRank::new(0)]);
+    }
+
     #[test]
     fn test_evaluator_creation() {
         let eval = CactusKevEvaluator::new();
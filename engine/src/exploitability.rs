@@ -4,11 +4,12 @@
 //! Both traversals are read-only (& refs only) so no locking is needed.
 
 use std::time::Duration;
-use rayon;
+use crate::par::join;
 use crate::cfr::RegretStorage;
-use crate::node::{GameTree, Node, NodeId, Player};
+use crate::ev_table::TerminalEvs;
+use crate::node::{GameTree, Node, NodeId, Player, Street};
+use crate::summation::Summation;
 use crate::test_tree::terminal_ev_table;
-use std::collections::HashMap;
 
 /// Convergence metrics returned after an exploitability check.
 pub struct ConvergenceMetrics {
@@ -24,7 +25,7 @@ pub struct ConvergenceMetrics {
 
 /// Compute exploitability using the default 9-node test terminal EV table.
 ///
-/// Both players' best-response traversals run in parallel via `rayon::join`.
+/// Both players' best-response traversals run in parallel via `par::join`.
 pub fn compute_exploitability(
     tree: &GameTree,
     storage: &RegretStorage,
@@ -32,9 +33,9 @@ pub fn compute_exploitability(
     elapsed: Duration,
 ) -> ConvergenceMetrics {
     let evs = terminal_ev_table();
-    let (ip_br_value, oop_br_value) = rayon::join(
-        || br_traverse_with_evs(tree, storage, &evs, 0, Player::IP, 1.0),
-        || br_traverse_with_evs(tree, storage, &evs, 0, Player::OOP, 1.0),
+    let (ip_br_value, oop_br_value) = join(
+        || br_traverse_with_evs(tree, storage, &evs, 0, Player::IP, Summation::Naive),
+        || br_traverse_with_evs(tree, storage, &evs, 0, Player::OOP, Summation::Naive),
     );
 
     ConvergenceMetrics {
@@ -49,17 +50,29 @@ pub fn compute_exploitability(
 /// Compute exploitability with a custom terminal EV table.
 ///
 /// Use this when solving trees other than the default 9-node test tree.
-/// Both players' best-response traversals run in parallel via `rayon::join`.
+/// Both players' best-response traversals run in parallel via `par::join`.
 pub fn compute_exploitability_with_evs(
     tree: &GameTree,
     storage: &RegretStorage,
-    terminal_evs: &HashMap<NodeId, f64>,
+    terminal_evs: &impl TerminalEvs,
     iteration: u64,
     elapsed: Duration,
 ) -> ConvergenceMetrics {
-    let (ip_br_value, oop_br_value) = rayon::join(
-        || br_traverse_with_evs(tree, storage, terminal_evs, 0, Player::IP, 1.0),
-        || br_traverse_with_evs(tree, storage, terminal_evs, 0, Player::OOP, 1.0),
+    compute_exploitability_with_summation(tree, storage, terminal_evs, iteration, elapsed, Summation::Naive)
+}
+
+/// `compute_exploitability_with_evs`, adding up EVs with `summation`
+pub fn compute_exploitability_with_summation(
+    tree: &GameTree,
+    storage: &RegretStorage,
+    terminal_evs: &impl TerminalEvs,
+    iteration: u64,
+    elapsed: Duration,
+    summation: Summation,
+) -> ConvergenceMetrics {
+    let (ip_br_value, oop_br_value) = join(
+        || br_traverse_with_evs(tree, storage, terminal_evs, 0, Player::IP, summation),
+        || br_traverse_with_evs(tree, storage, terminal_evs, 0, Player::OOP, summation),
     );
 
     ConvergenceMetrics {
@@ -71,58 +84,192 @@ pub fn compute_exploitability_with_evs(
     }
 }
 
-/// Recursive best-response traversal with an explicit EV table.
+/// Exploitability split by the street of the decisions where it's gained
+/// (see `street_exploitability`); each field is in bb
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StreetExploitability {
+    pub flop: f64,
+    pub turn: f64,
+    pub river: f64,
+}
+
+impl StreetExploitability {
+    pub fn get(&self, street: Street) -> f64 {
+        match street {
+            Street::Flop => self.flop,
+            Street::Turn => self.turn,
+            Street::River => self.river,
+        }
+    }
+
+    /// Sum over streets: the total exploitability
+    pub fn total(&self) -> f64 {
+        self.flop + self.turn + self.river
+    }
+
+    fn add(&mut self, street: Street, value: f64) {
+        match street {
+            Street::Flop => self.flop += value,
+            Street::Turn => self.turn += value,
+            Street::River => self.river += value,
+        }
+    }
+}
+
+/// Exploitability of the average strategy, attributed to streets.
+///
+/// A best responder's gain over the average strategy comes from the
+/// decisions where it deviates: at each of its decision nodes it gains
+/// `max_a BR(child) - sum_a sigma(a) BR(child)`, weighted by the node's
+/// reach under the average strategies and chance. Those gains add up to
+/// exactly the total exploitability; each is credited to its node's street.
+/// So a small river share means the river strategy is close to a best
+/// response given how the tree gets there, even if the flop isn't yet.
+pub fn street_exploitability(tree: &GameTree, storage: &RegretStorage, terminal_evs: &impl TerminalEvs) -> StreetExploitability {
+    // Pre-order, so every node's parent comes before it
+    let mut order = Vec::with_capacity(tree.len());
+    let mut reach = vec![0.0; tree.len()];
+    let mut stack = vec![0 as NodeId];
+    reach[0] = 1.0;
+    while let Some(id) = stack.pop() {
+        order.push(id);
+        let node = tree.get(id).expect("invalid node id");
+        let parent_reach = reach[id as usize];
+        match node {
+            Node::Decision { infoset_id, children, .. } => {
+                let probs = storage.average_strategy(*infoset_id as usize);
+                for (&child, &p) in children.iter().zip(&probs) {
+                    reach[child as usize] = parent_reach * p;
+                }
+            }
+            Node::Chance { children, .. } => {
+                for (i, &child) in children.iter().enumerate() {
+                    reach[child as usize] = parent_reach * node.chance_probability(i);
+                }
+            }
+            Node::Terminal { .. } => {}
+        }
+        stack.extend(node.children().iter().rev());
+    }
+
+    // Best-response values for [IP, OOP], children before parents
+    let mut br = vec![[0.0; 2]; tree.len()];
+    let mut by_street = StreetExploitability::default();
+    for &id in order.iter().rev() {
+        let node = tree.get(id).expect("invalid node id");
+        let value = match node {
+            Node::Terminal { .. } => {
+                let ip_ev = terminal_evs.terminal_ev(id);
+                [ip_ev, -ip_ev]
+            }
+            Node::Decision { player, street, infoset_id, children, .. } => {
+                let probs = storage.average_strategy(*infoset_id as usize);
+                let mut value = [0, 1].map(|i| children.iter().zip(&probs).map(|(&c, &p)| p * br[c as usize][i]).sum::<f64>());
+                let me = *player as usize;
+                let best = children.iter().map(|&c| br[c as usize][me]).fold(f64::NEG_INFINITY, f64::max);
+                by_street.add(*street, reach[id as usize] * (best - value[me]));
+                value[me] = best;
+                value
+            }
+            Node::Chance { children, .. } => {
+                let mut value = [0.0; 2];
+                for (k, &c) in children.iter().enumerate() {
+                    let p = node.chance_probability(k);
+                    value[0] += p * br[c as usize][0];
+                    value[1] += p * br[c as usize][1];
+                }
+                value
+            }
+        };
+        br[id as usize] = value;
+    }
+    by_street
+}
+
+/// Best-response traversal with an explicit EV table.
 ///
 /// Returns EV from `traversing_player`'s perspective.
-/// `reach_opponent` is the probability the opponent reaches this node.
 fn br_traverse_with_evs(
     tree: &GameTree,
     storage: &RegretStorage,
-    terminal_evs: &HashMap<NodeId, f64>,
+    terminal_evs: &impl TerminalEvs,
     node_id: NodeId,
     traversing_player: Player,
-    reach_opponent: f64,
+    summation: Summation,
 ) -> f64 {
-    match tree.get(node_id).expect("invalid node id") {
-        Node::Terminal { .. } => {
-            let ip_ev = terminal_evs[&node_id];
-            if traversing_player == Player::IP { ip_ev } else { -ip_ev }
-        }
+    let average = |infoset_id: usize| storage.average_strategy(infoset_id);
+    br_traverse_with_strategy(tree, &average, terminal_evs, node_id, traversing_player, summation)
+}
+
+/// Best-response traversal against an arbitrary opponent strategy.
+///
+/// `strategy(infoset_id)` gives the opponent's action probabilities at each of
+/// their decision nodes. Used by post-solve analyses that perturb the average
+/// strategy (see `analysis`). Returns EV from `traversing_player`'s
+/// perspective; expectations over actions and cards are added up with
+/// `summation`.
+///
+/// The walk keeps explicit stacks rather than recursing, so deep trees
+/// don't risk overflowing the thread's stack.
+pub(crate) fn br_traverse_with_strategy(
+    tree: &GameTree,
+    strategy: &impl Fn(usize) -> Vec<f64>,
+    terminal_evs: &impl TerminalEvs,
+    node_id: NodeId,
+    traversing_player: Player,
+    summation: Summation,
+) -> f64 {
+    /// A node whose children are being valued
+    struct Frame<'a> {
+        node: &'a Node,
+        /// Start of its child values in `values`
+        base: usize,
+        /// Opponent's probabilities at an opponent decision, else empty
+        probs: Vec<f64>,
+    }
 
-        Node::Decision { player, children, infoset_id, .. } => {
-            let children = children.clone();
-            let infoset_id = *infoset_id as usize;
-            let acting_player = *player;
-
-            if acting_player == traversing_player {
-                // Best response: choose the action with the highest EV
-                children.iter().map(|&child_id| {
-                    br_traverse_with_evs(
-                        tree, storage, terminal_evs, child_id, traversing_player, reach_opponent,
-                    )
-                }).fold(f64::NEG_INFINITY, f64::max)
-            } else {
-                // Opponent plays their average strategy
-                let strategy = storage.average_strategy(infoset_id);
-                children.iter().zip(strategy.iter()).map(|(&child_id, &prob)| {
-                    let child_ev = br_traverse_with_evs(
-                        tree, storage, terminal_evs, child_id, traversing_player,
-                        reach_opponent * prob,
-                    );
-                    prob * child_ev
-                }).sum()
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut values: Vec<f64> = Vec::new();
+    let mut next = Some(node_id);
+    loop {
+        if let Some(id) = next.take() {
+            match tree.get(id).expect("invalid node id") {
+                Node::Terminal { .. } => {
+                    let ip_ev = terminal_evs.terminal_ev(id);
+                    values.push(if traversing_player == Player::IP { ip_ev } else { -ip_ev });
+                }
+                node => {
+                    let probs = match node {
+                        Node::Decision { player, infoset_id, .. } if *player != traversing_player => strategy(*infoset_id as usize),
+                        _ => Vec::new(),
+                    };
+                    frames.push(Frame { node, base: values.len(), probs });
+                }
             }
         }
 
-        Node::Chance { children, .. } => {
-            let children = children.clone();
-            let n = children.len() as f64;
-            children.iter().map(|&child_id| {
-                br_traverse_with_evs(
-                    tree, storage, terminal_evs, child_id, traversing_player, reach_opponent,
-                ) / n
-            }).sum()
+        let Some(frame) = frames.last() else { return values[0] };
+        let children = frame.node.children();
+        let done = values.len() - frame.base;
+        if done < children.len() {
+            next = Some(children[done]);
+            continue;
         }
+        let child_evs = &values[frame.base..];
+        let value = match frame.node {
+            // Best response: choose the action with the highest EV
+            Node::Decision { player, .. } if *player == traversing_player => child_evs.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            // Opponent plays the given strategy
+            Node::Decision { .. } => summation.sum(child_evs.iter().zip(&frame.probs).map(|(&ev, &prob)| prob * ev)),
+            Node::Chance { weights, .. } => {
+                let n = children.len() as f64;
+                summation.sum(child_evs.iter().enumerate().map(|(i, &ev)| if weights.is_empty() { ev / n } else { ev * weights[i] }))
+            }
+            Node::Terminal { .. } => unreachable!("terminals have no frame"),
+        };
+        values.truncate(frame.base);
+        values.push(value);
+        frames.pop();
     }
 }
 
@@ -133,10 +280,9 @@ fn br_traverse(
     storage: &RegretStorage,
     node_id: NodeId,
     traversing_player: Player,
-    reach_opponent: f64,
 ) -> f64 {
     let evs = terminal_ev_table();
-    br_traverse_with_evs(tree, storage, &evs, node_id, traversing_player, reach_opponent)
+    br_traverse_with_evs(tree, storage, &evs, node_id, traversing_player, Summation::Naive)
 }
 
 #[cfg(test)]
@@ -223,4 +369,35 @@ mod tests {
             late.exploitability,
         );
     }
+
+    #[test]
+    fn test_street_exploitability_adds_up_to_the_total() {
+        use crate::builder::{build_tree, StreetSizes, TreeConfig};
+        use crate::ev_table::EvTable;
+        use crate::game::{build_terminal_evs, GameConfig};
+        use crate::node::parse_cards;
+
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5, 1.0]);
+        config.river = StreetSizes::new(vec![0.5]);
+        let tree = build_tree(&config);
+        // IP's pot share depends on the river card, so river play matters
+        let evs = EvTable::from(build_terminal_evs(&tree, |node| match node.board().get(4) {
+            Some(card) => (card.value() % 4) as f64 / 3.0,
+            None => 0.5,
+        }));
+
+        let mut solver = CfrSolver::new_with_evs(tree.clone(), evs.clone());
+        for iterations in [0, 20, 200] {
+            while solver.iteration < iterations {
+                solver.run_iteration();
+            }
+            let total = compute_exploitability_with_evs(&tree, &solver.storage, &evs, iterations, Duration::ZERO);
+            let by_street = street_exploitability(&tree, &solver.storage, &evs);
+            assert!((by_street.total() - total.exploitability).abs() < 1e-9, "{:?} vs {}", by_street, total.exploitability);
+            // No flop decisions in a turn tree; every credited gain is a real deviation
+            assert_eq!(by_street.flop, 0.0);
+            assert!(by_street.turn >= -1e-12 && by_street.river > 0.0);
+        }
+    }
 }
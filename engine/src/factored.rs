@@ -0,0 +1,315 @@
+//! Factored tree storage: one betting template per street line, shared by
+//! every runout
+//!
+//! Betting never depends on the cards dealt, so below a Chance node each of
+//! the 45–49 children repeats the same betting skeleton with only the board
+//! changed. `FactoredTree` stores each street's skeleton once, as a
+//! `Template`; a full-tree node is a template node plus the runout (board
+//! cards) that leads to it. A turn or flop tree takes one to two orders of
+//! magnitude fewer nodes this way, and building it skips the per-runout work.
+//!
+//! `expand` reproduces `build_tree`'s `GameTree` node for node, and `stats`
+//! computes its `TreeStats` without expanding. With isomorphic runouts the
+//! dealt classes depend on the board, so `stats` walks the runouts instead
+//! of multiplying.
+
+use crate::builder::{legal_actions, runouts, BettingState, Next, TreeConfig};
+use crate::node::{Action, Card, GameTree, InfosetId, Node, NodeId, Player, Street, TreeStats};
+
+/// Index into `FactoredTree::templates`
+pub type TemplateId = u32;
+
+/// One node of a street's betting skeleton (no board)
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkeletonNode {
+    Decision {
+        player: Player,
+        actions: Vec<Action>,
+        /// Template-local indices, one per action
+        children: Vec<u32>,
+        pot: f64,
+        stacks: [f64; 2],
+    },
+    Terminal {
+        folder: Option<Player>,
+        pot: f64,
+        stacks: [f64; 2],
+    },
+    /// Street closed with more to deal: every undealt card continues in `next`
+    Chance {
+        next: TemplateId,
+        pot: f64,
+        stacks: [f64; 2],
+    },
+}
+
+/// Betting skeleton of one street from a given starting state; node 0 is
+/// the street's first decision (the next deal or the showdown once a
+/// player is all-in) and parents precede children
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pub street: Street,
+    pub nodes: Vec<SkeletonNode>,
+}
+
+/// A game tree stored as street templates instead of per-runout nodes
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactoredTree {
+    /// Board at the root
+    pub board: Vec<Card>,
+    /// Template 0 is the root street
+    pub templates: Vec<Template>,
+    /// Chance nodes deal one child per runout class (`TreeConfig::isomorphic_runouts`)
+    pub isomorphic_runouts: bool,
+}
+
+impl FactoredTree {
+    /// Factor the tree described by `config`.
+    ///
+    /// Panics if the config does not validate (like `build_tree`).
+    pub fn build(config: &TreeConfig) -> Self {
+        if let Err(e) = config.validate() {
+            panic!("invalid tree config: {}", e);
+        }
+        let mut templates = Vec::new();
+        build_template(config, BettingState::root(config), &mut templates);
+        FactoredTree { board: config.board.clone(), templates, isomorphic_runouts: config.isomorphic_runouts }
+    }
+
+    /// Nodes stored across all templates
+    pub fn skeleton_nodes(&self) -> usize {
+        self.templates.iter().map(|t| t.nodes.len()).sum()
+    }
+
+    /// Stats of the expanded tree, computed per template
+    pub fn stats(&self) -> TreeStats {
+        let mut memo = vec![None; self.templates.len()];
+        self.template_stats(0, &mut self.board.clone(), &mut memo)
+    }
+
+    /// Stats of template `t` reached with `board`; memoized per template
+    /// unless runout classes make them board-dependent
+    fn template_stats(&self, t: TemplateId, board: &mut Vec<Card>, memo: &mut Vec<Option<TreeStats>>) -> TreeStats {
+        if let Some(stats) = memo[t as usize] {
+            return stats;
+        }
+        let template = &self.templates[t as usize];
+        let mut stats = TreeStats::default();
+        let mut depth = vec![0usize; template.nodes.len()];
+        for (i, node) in template.nodes.iter().enumerate() {
+            stats.nodes += 1;
+            stats.max_depth = stats.max_depth.max(depth[i]);
+            match node {
+                SkeletonNode::Decision { actions, children, .. } => {
+                    stats.decisions += 1;
+                    stats.actions += actions.len();
+                    for &c in children {
+                        depth[c as usize] = depth[i] + 1;
+                    }
+                }
+                SkeletonNode::Terminal { folder, .. } => {
+                    stats.terminals += 1;
+                    stats.fold_terminals += usize::from(folder.is_some());
+                }
+                SkeletonNode::Chance { next, .. } => {
+                    stats.chance += 1;
+                    let (cards, _) = runouts(board, self.isomorphic_runouts);
+                    for card in cards {
+                        board.push(card);
+                        let sub = self.template_stats(*next, board, memo);
+                        board.pop();
+                        stats.nodes += sub.nodes;
+                        stats.decisions += sub.decisions;
+                        stats.chance += sub.chance;
+                        stats.terminals += sub.terminals;
+                        stats.fold_terminals += sub.fold_terminals;
+                        stats.actions += sub.actions;
+                        stats.max_depth = stats.max_depth.max(depth[i] + 1 + sub.max_depth);
+                    }
+                }
+            }
+        }
+        if !self.isomorphic_runouts {
+            memo[t as usize] = Some(stats);
+        }
+        stats
+    }
+
+    /// Expand into the same `GameTree` `build_tree` produces (ids, infoset
+    /// ids, and node order match)
+    pub fn expand(&self) -> GameTree {
+        let mut expander = Expander { tree: self, nodes: Vec::new(), infosets: 0 };
+        let mut board = self.board.clone();
+        expander.emit(0, 0, None, &mut board, &mut Vec::new());
+        GameTree { nodes: expander.nodes }
+    }
+}
+
+/// Build the template starting at `state`; returns its id
+fn build_template(config: &TreeConfig, state: BettingState, templates: &mut Vec<Template>) -> TemplateId {
+    let id = templates.len() as TemplateId;
+    templates.push(Template { street: state.street(), nodes: Vec::new() });
+    let mut nodes = Vec::new();
+    if state.is_all_in() {
+        close_round(config, state, &mut nodes, templates);
+    } else {
+        build_decision(config, state, &mut nodes, templates);
+    }
+    templates[id as usize].nodes = nodes;
+    id
+}
+
+fn build_decision(
+    config: &TreeConfig,
+    state: BettingState,
+    nodes: &mut Vec<SkeletonNode>,
+    templates: &mut Vec<Template>,
+) -> u32 {
+    let index = nodes.len();
+    nodes.push(SkeletonNode::Terminal { folder: None, pot: 0.0, stacks: [0.0, 0.0] });
+    let mut actions = Vec::new();
+    let mut children = Vec::new();
+    for (action, next) in legal_actions(config, &state) {
+        let child = match next {
+            Next::Decision(s) => build_decision(config, s, nodes, templates),
+            Next::Fold(s, folder) => push(nodes, SkeletonNode::Terminal { folder: Some(folder), pot: s.pot(), stacks: s.stacks() }),
+            Next::RoundClosed(s) => close_round(config, s, nodes, templates),
+        };
+        actions.push(action);
+        children.push(child);
+    }
+    nodes[index] = SkeletonNode::Decision { player: state.to_act(), actions, children, pot: state.pot(), stacks: state.stacks() };
+    index as u32
+}
+
+/// Round closed: a Chance node into the next street's template, or a showdown
+fn close_round(config: &TreeConfig, s: BettingState, nodes: &mut Vec<SkeletonNode>, templates: &mut Vec<Template>) -> u32 {
+    match (s.next_street().filter(|&st| config.deals(st)), s.undealt().next()) {
+        (Some(street), Some(card)) => {
+            // Betting ignores the board, so any card stands in for the runout
+            let next = build_template(config, s.deal(street, card), templates);
+            push(nodes, SkeletonNode::Chance { next, pot: s.pot(), stacks: s.stacks() })
+        }
+        _ => push(nodes, SkeletonNode::Terminal { folder: None, pot: s.pot(), stacks: s.stacks() }),
+    }
+}
+
+fn push(nodes: &mut Vec<SkeletonNode>, node: SkeletonNode) -> u32 {
+    nodes.push(node);
+    (nodes.len() - 1) as u32
+}
+
+struct Expander<'a> {
+    tree: &'a FactoredTree,
+    nodes: Vec<Node>,
+    /// Next infoset id (one per decision, in emission order)
+    infosets: InfosetId,
+}
+
+impl Expander<'_> {
+    fn emit(&mut self, t: TemplateId, local: u32, parent: Option<NodeId>, board: &mut Vec<Card>, history: &mut Vec<Action>) -> NodeId {
+        let template = &self.tree.templates[t as usize];
+        let id = self.nodes.len() as NodeId;
+        match &template.nodes[local as usize] {
+            SkeletonNode::Decision { player, actions, children, pot, stacks } => {
+                let infoset_id = self.infosets;
+                self.infosets += 1;
+                self.nodes.push(placeholder());
+                let mut ids = Vec::with_capacity(children.len());
+                for (action, &child) in actions.iter().zip(children) {
+                    history.push(*action);
+                    ids.push(self.emit(t, child, Some(id), board, history));
+                    history.pop();
+                }
+                self.nodes[id as usize] = Node::Decision {
+                    id,
+                    infoset_id,
+                    player: *player,
+                    street: template.street,
+                    parent,
+                    children: ids,
+                    actions: actions.clone(),
+                    pot: *pot,
+                    stacks: *stacks,
+                    board: board.clone(),
+                    bet_sequence: history.clone(),
+                };
+            }
+            SkeletonNode::Terminal { folder, pot, stacks } => {
+                self.nodes.push(Node::Terminal {
+                    id,
+                    parent,
+                    folder: *folder,
+                    pot: *pot,
+                    stacks: *stacks,
+                    board: board.clone(),
+                    hole_cards: [None, None],
+                });
+            }
+            SkeletonNode::Chance { next, pot, stacks } => {
+                self.nodes.push(placeholder());
+                let (cards, weights) = runouts(board, self.tree.isomorphic_runouts);
+                let mut ids = Vec::with_capacity(cards.len());
+                for card in cards {
+                    board.push(card);
+                    ids.push(self.emit(*next, 0, Some(id), board, history));
+                    board.pop();
+                }
+                self.nodes[id as usize] = Node::Chance {
+                    id,
+                    parent,
+                    children: ids,
+                    weights,
+                    street: template.street,
+                    pot: *pot,
+                    stacks: *stacks,
+                    board: board.clone(),
+                };
+            }
+        }
+        id
+    }
+}
+
+fn placeholder() -> Node {
+    Node::Terminal { id: 0, parent: None, folder: None, pot: 0.0, stacks: [0.0, 0.0], board: Vec::new(), hole_cards: [None, None] }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes};
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+
+    #[test]
+    fn test_expand_matches_build_tree() {
+        // The last is shallow enough for flop all-ins, dealt without decisions
+        for (board, isomorphic, stacks) in [("AsKh7d2c9s", false, 97.5), ("AsKh7d2c", false, 97.5), ("AsKs7d", true, 97.5), ("AsKs7d", true, 8.0)] {
+            let mut config = TreeConfig::new(GameConfig::from_pot(5.5, [stacks, stacks]), parse_cards(board).unwrap());
+            config.flop = StreetSizes::new(vec![0.5]);
+            config.turn = StreetSizes::new(vec![0.5, 1.0]);
+            config.max_raises = 2;
+            config.isomorphic_runouts = isomorphic;
+            let factored = FactoredTree::build(&config);
+            let built = build_tree(&config);
+            assert_eq!(factored.stats(), built.stats());
+            assert_eq!(factored.expand(), built);
+        }
+    }
+
+    #[test]
+    fn test_flop_tree_factors_by_orders_of_magnitude() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d").unwrap());
+        config.flop = StreetSizes::new(vec![0.5]);
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 1;
+        let factored = FactoredTree::build(&config);
+        let stats = factored.stats();
+        assert_eq!(stats, build_tree(&config).stats());
+        assert!(factored.skeleton_nodes() * 100 < stats.nodes, "{} vs {}", factored.skeleton_nodes(), stats.nodes);
+        assert_eq!(factored.templates[0].street, Street::Flop);
+        assert!(factored.templates.iter().any(|t| t.street == Street::River));
+    }
+}
@@ -0,0 +1,308 @@
+//! On-disk format for trees and solutions (requires the `serde` feature)
+//!
+//! Every file starts with an 8-byte header:
+//!
+//! | bytes | meaning                                        |
+//! |-------|------------------------------------------------|
+//! | 0..4  | magic `ORCL`                                   |
+//! | 4     | format version (`FORMAT_VERSION`)              |
+//! | 5     | payload kind (1 = tree, 2 = solution)          |
+//! | 6     | flags (bit 0 = payload is zstd-compressed)     |
+//! | 7     | reserved (0)                                   |
+//!
+//! followed by the bincode-encoded payload. Loading reads the flag and
+//! decompresses transparently, so callers only choose compression on save.
+//! Compressed files need the `zstd` feature to read or write.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use crate::node::GameTree;
+use crate::solution::Solution;
+
+/// File magic
+pub const MAGIC: [u8; 4] = *b"ORCL";
+/// Current header/payload format version
+pub const FORMAT_VERSION: u8 = 1;
+/// Header flag: payload is zstd-compressed
+pub const FLAG_ZSTD: u8 = 0b0000_0001;
+
+/// What a file contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Tree = 1,
+    Solution = 2,
+}
+
+impl FileKind {
+    fn from_byte(b: u8) -> Option<FileKind> {
+        match b {
+            1 => Some(FileKind::Tree),
+            2 => Some(FileKind::Solution),
+            _ => None,
+        }
+    }
+}
+
+/// Payload compression used when saving
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    /// zstd at the given level (1-22; 3 is zstd's default)
+    Zstd { level: i32 },
+}
+
+/// Error reading or writing a tree/solution file
+#[derive(Debug)]
+pub enum FileError {
+    Io(std::io::Error),
+    /// File does not start with `ORCL`
+    BadMagic,
+    /// Written by a newer (or unknown) format version
+    UnsupportedVersion(u8),
+    /// Header kind byte is not a known kind
+    UnknownKind(u8),
+    /// File holds a different kind of payload than requested
+    WrongKind { expected: FileKind, found: FileKind },
+    /// Payload is compressed (or compression was requested) without the `zstd` feature
+    CompressionUnavailable,
+    /// Payload failed to encode or decode
+    Codec(String),
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::Io(e) => write!(f, "I/O error: {}", e),
+            FileError::BadMagic => write!(f, "not an oracle tree/solution file"),
+            FileError::UnsupportedVersion(v) => write!(f, "unsupported file format version {}", v),
+            FileError::UnknownKind(k) => write!(f, "unknown payload kind {}", k),
+            FileError::WrongKind { expected, found } => write!(f, "expected a {:?} file, found {:?}", expected, found),
+            FileError::CompressionUnavailable => write!(f, "zstd compression requires the `zstd` feature"),
+            FileError::Codec(m) => write!(f, "payload codec error: {}", m),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+impl From<std::io::Error> for FileError {
+    fn from(e: std::io::Error) -> Self {
+        FileError::Io(e)
+    }
+}
+
+/// Write a tree to `path`
+pub fn save_tree(path: impl AsRef<Path>, tree: &GameTree, compression: Compression) -> Result<(), FileError> {
+    write_payload(BufWriter::new(File::create(path)?), FileKind::Tree, tree, compression)
+}
+
+/// Read a tree written by `save_tree` (compressed or not)
+pub fn load_tree(path: impl AsRef<Path>) -> Result<GameTree, FileError> {
+    read_payload(BufReader::new(File::open(path)?), FileKind::Tree)
+}
+
+/// Write a solution to `path`; a partial solution's omitted rows are
+/// written empty (see `Solution::partial`)
+pub fn save_solution(path: impl AsRef<Path>, solution: &Solution, compression: Compression) -> Result<(), FileError> {
+    let writer = BufWriter::new(File::create(path)?);
+    if solution.is_partial() {
+        write_payload(writer, FileKind::Solution, &solution.compacted(), compression)
+    } else {
+        write_payload(writer, FileKind::Solution, solution, compression)
+    }
+}
+
+/// Read a solution written by `save_solution` (compressed or not)
+pub fn load_solution(path: impl AsRef<Path>) -> Result<Solution, FileError> {
+    let mut solution: Solution = read_payload(BufReader::new(File::open(path)?), FileKind::Solution)?;
+    solution.expand_omitted();
+    Ok(solution)
+}
+
+/// Read just the header: payload kind and whether it is compressed
+pub fn read_header(mut reader: impl Read) -> Result<(FileKind, bool), FileError> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    if header[0..4] != MAGIC {
+        return Err(FileError::BadMagic);
+    }
+    if header[4] != FORMAT_VERSION {
+        return Err(FileError::UnsupportedVersion(header[4]));
+    }
+    let kind = FileKind::from_byte(header[5]).ok_or(FileError::UnknownKind(header[5]))?;
+    Ok((kind, header[6] & FLAG_ZSTD != 0))
+}
+
+/// Encode `value` with a header into `writer`
+pub fn write_payload<T: serde::Serialize>(
+    mut writer: impl Write,
+    kind: FileKind,
+    value: &T,
+    compression: Compression,
+) -> Result<(), FileError> {
+    if matches!(compression, Compression::Zstd { .. }) && !cfg!(feature = "zstd") {
+        return Err(FileError::CompressionUnavailable);
+    }
+    let flags = match compression {
+        Compression::None => 0,
+        Compression::Zstd { .. } => FLAG_ZSTD,
+    };
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&[FORMAT_VERSION, kind as u8, flags, 0])?;
+    match compression {
+        Compression::None => encode(&mut writer, value)?,
+        Compression::Zstd { level } => zstd_encode(&mut writer, value, level)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Decode a payload of the expected kind from `reader`
+pub fn read_payload<T: serde::de::DeserializeOwned>(mut reader: impl Read, expected: FileKind) -> Result<T, FileError> {
+    let (found, compressed) = read_header(&mut reader)?;
+    if found != expected {
+        return Err(FileError::WrongKind { expected, found });
+    }
+    if compressed {
+        zstd_decode(reader)
+    } else {
+        decode(reader)
+    }
+}
+
+fn encode<T: serde::Serialize>(writer: impl Write, value: &T) -> Result<(), FileError> {
+    bincode::serialize_into(writer, value).map_err(|e| FileError::Codec(e.to_string()))
+}
+
+fn decode<T: serde::de::DeserializeOwned>(reader: impl Read) -> Result<T, FileError> {
+    bincode::deserialize_from(reader).map_err(|e| FileError::Codec(e.to_string()))
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_encode<T: serde::Serialize>(writer: impl Write, value: &T, level: i32) -> Result<(), FileError> {
+    let mut encoder = zstd::Encoder::new(writer, level)?;
+    encode(&mut encoder, value)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_decode<T: serde::de::DeserializeOwned>(reader: impl Read) -> Result<T, FileError> {
+    decode(zstd::Decoder::new(reader)?)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_encode<T: serde::Serialize>(_writer: impl Write, _value: &T, _level: i32) -> Result<(), FileError> {
+    Err(FileError::CompressionUnavailable)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_decode<T: serde::de::DeserializeOwned>(_reader: impl Read) -> Result<T, FileError> {
+    Err(FileError::CompressionUnavailable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::cfr::CfrSolver;
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+    use crate::test_tree::build_test_tree;
+
+    fn turn_tree() -> GameTree {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 1;
+        build_tree(&config)
+    }
+
+    #[test]
+    fn test_uncompressed_round_trip_and_header() {
+        let tree = turn_tree();
+        let mut bytes = Vec::new();
+        write_payload(&mut bytes, FileKind::Tree, &tree, Compression::None).unwrap();
+        assert_eq!(&bytes[0..4], b"ORCL");
+        assert_eq!(read_header(&bytes[..]).unwrap(), (FileKind::Tree, false));
+        let back: GameTree = read_payload(&bytes[..], FileKind::Tree).unwrap();
+        assert_eq!(back, tree);
+    }
+
+    #[test]
+    fn test_solution_file_round_trip() {
+        let mut solver = CfrSolver::new(build_test_tree());
+        for _ in 0..100 {
+            solver.run_iteration();
+        }
+        let solution = Solution::from_solver(&solver);
+        let path = std::env::temp_dir().join(format!("oracle_solution_{}.bin", std::process::id()));
+        save_solution(&path, &solution, Compression::None).unwrap();
+        let back = load_solution(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(back, solution);
+        assert_eq!(back.fingerprint(), solution.fingerprint());
+    }
+
+    #[test]
+    fn test_partial_solution_file_is_smaller() {
+        use crate::range::Range;
+        use crate::solve::{solve, SolveSpec};
+        use crate::StopCondition;
+
+        let ranges = [Range::parse("AA,KK,AK").unwrap(), Range::parse("QQ-99,AQ").unwrap()];
+        let mut spec = SolveSpec::from_state(parse_cards("AsKh7d2c").unwrap(), ranges, 6.0, [97.0, 97.0]);
+        spec.stop = StopCondition::iterations(50);
+        let solution = Solution::from_solver(&solve(&spec).unwrap().solver);
+        let partial = solution.partial(0.05);
+        assert!(partial.is_partial());
+
+        let dir = std::env::temp_dir().join(format!("oracle_partial_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (full_path, partial_path) = (dir.join("full.bin"), dir.join("partial.bin"));
+        save_solution(&full_path, &solution, Compression::None).unwrap();
+        save_solution(&partial_path, &partial, Compression::None).unwrap();
+        let sizes = [&full_path, &partial_path].map(|p| std::fs::metadata(p).unwrap().len());
+        let back = load_solution(&partial_path).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(sizes[1] < sizes[0], "{:?}", sizes);
+        // Omitted rows come back zeroed, so the loaded solution equals the partial one
+        assert_eq!(back, partial);
+        assert!(back.validate().is_ok());
+    }
+
+    #[test]
+    fn test_header_errors() {
+        assert!(matches!(read_header(&b"NOPE\x01\x01\x00\x00"[..]), Err(FileError::BadMagic)));
+        assert!(matches!(read_header(&b"ORCL\x09\x01\x00\x00"[..]), Err(FileError::UnsupportedVersion(9))));
+        assert!(matches!(read_header(&b"ORCL\x01\x07\x00\x00"[..]), Err(FileError::UnknownKind(7))));
+
+        let mut bytes = Vec::new();
+        write_payload(&mut bytes, FileKind::Tree, &build_test_tree(), Compression::None).unwrap();
+        let err = read_payload::<Solution>(&bytes[..], FileKind::Solution).unwrap_err();
+        assert!(matches!(err, FileError::WrongKind { expected: FileKind::Solution, found: FileKind::Tree }));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd_round_trip_is_smaller() {
+        let tree = turn_tree();
+        let mut plain = Vec::new();
+        write_payload(&mut plain, FileKind::Tree, &tree, Compression::None).unwrap();
+        let mut packed = Vec::new();
+        write_payload(&mut packed, FileKind::Tree, &tree, Compression::Zstd { level: 3 }).unwrap();
+        assert_eq!(read_header(&packed[..]).unwrap(), (FileKind::Tree, true));
+        assert!(packed.len() * 4 < plain.len(), "zstd {} vs plain {}", packed.len(), plain.len());
+        let back: GameTree = read_payload(&packed[..], FileKind::Tree).unwrap();
+        assert_eq!(back, tree);
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn test_zstd_needs_feature() {
+        let mut bytes = Vec::new();
+        let err = write_payload(&mut bytes, FileKind::Tree, &build_test_tree(), Compression::Zstd { level: 3 });
+        assert!(matches!(err, Err(FileError::CompressionUnavailable)));
+    }
+}
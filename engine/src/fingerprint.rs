@@ -0,0 +1,237 @@
+//! Stable fingerprints of trees, configs, and solutions
+//!
+//! A fingerprint is a 64-bit FNV-1a hash, written out as 16 hex digits, of
+//! everything that determines a solve's result: the tree's structure, the
+//! result-affecting solver options, and the regret/strategy arrays. Two runs
+//! (or two machines) that print the same solution fingerprint produced the
+//! same result bit for bit.
+//!
+//! Every value is fed in a fixed little-endian byte layout and floats by
+//! their bit patterns, so fingerprints don't depend on the platform, the
+//! Rust version, or `HashMap` order. Options that don't change the result
+//! (thread count, profiling, checkpoints, the stop condition, the storage
+//! mode) are left out
+//! of the config fingerprint; the iteration count is part of the solution's.
+
+use crate::cfr::StorageSnapshot;
+use crate::config::SolverConfig;
+use crate::node::{Action, Card, GameTree, Node, Player};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A 64-bit content hash (see module docs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fingerprint(pub u64);
+
+impl std::fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Incremental FNV-1a hasher with a fixed byte layout per value type
+#[derive(Debug, Clone)]
+pub struct Fingerprinter {
+    state: u64,
+}
+
+impl Default for Fingerprinter {
+    fn default() -> Self {
+        Fingerprinter::new()
+    }
+}
+
+impl Fingerprinter {
+    pub fn new() -> Self {
+        Fingerprinter { state: FNV_OFFSET }
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.state = (self.state ^ b as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub fn u64(&mut self, x: u64) {
+        self.bytes(&x.to_le_bytes());
+    }
+
+    /// Lengths and indices, always hashed as 8 bytes
+    pub fn usize(&mut self, x: usize) {
+        self.u64(x as u64);
+    }
+
+    /// By bit pattern: `0.0` and `-0.0` differ, and so do differently rounded results
+    pub fn f64(&mut self, x: f64) {
+        self.u64(x.to_bits());
+    }
+
+    pub fn f64s(&mut self, xs: &[f64]) {
+        self.usize(xs.len());
+        xs.iter().for_each(|&x| self.f64(x));
+    }
+
+    pub fn finish(&self) -> Fingerprint {
+        Fingerprint(self.state)
+    }
+
+    fn tag(&mut self, tag: u8) {
+        self.bytes(&[tag]);
+    }
+
+    fn cards(&mut self, cards: &[Card]) {
+        self.usize(cards.len());
+        cards.iter().for_each(|c| self.tag(c.value()));
+    }
+
+    fn player(&mut self, player: Player) {
+        self.tag(match player {
+            Player::IP => 0,
+            Player::OOP => 1,
+        });
+    }
+
+    fn action(&mut self, action: Action) {
+        match action {
+            Action::Fold => self.tag(0),
+            Action::Check => self.tag(1),
+            Action::Call => self.tag(2),
+            Action::Bet { size } => {
+                self.tag(3);
+                self.f64(size);
+            }
+            Action::Raise { size } => {
+                self.tag(4);
+                self.f64(size);
+            }
+        }
+    }
+
+    fn actions(&mut self, actions: &[Action]) {
+        self.usize(actions.len());
+        actions.iter().for_each(|&a| self.action(a));
+    }
+
+    /// Every node's kind, links, actions, pot, stacks, board, and (for
+    /// decisions) infoset
+    pub fn tree(&mut self, tree: &GameTree) {
+        self.usize(tree.len());
+        for node in &tree.nodes {
+            self.u64(node.id() as u64);
+            self.u64(node.parent().map_or(u64::MAX, |p| p as u64));
+            self.usize(node.children().len());
+            node.children().iter().for_each(|&c| self.u64(c as u64));
+            match node {
+                Node::Decision { infoset_id, player, street, actions, pot, stacks, board, bet_sequence, .. } => {
+                    self.tag(0);
+                    self.u64(*infoset_id as u64);
+                    self.player(*player);
+                    self.tag(*street as u8);
+                    self.actions(actions);
+                    self.f64(*pot);
+                    self.f64s(stacks);
+                    self.cards(board);
+                    self.actions(bet_sequence);
+                }
+                Node::Chance { weights, street, pot, stacks, board, .. } => {
+                    self.tag(1);
+                    self.f64s(weights);
+                    self.tag(*street as u8);
+                    self.f64(*pot);
+                    self.f64s(stacks);
+                    self.cards(board);
+                }
+                Node::Terminal { folder, pot, stacks, board, hole_cards, .. } => {
+                    self.tag(2);
+                    match folder {
+                        Some(p) => self.player(*p),
+                        None => self.tag(2),
+                    }
+                    self.f64(*pot);
+                    self.f64s(stacks);
+                    self.cards(board);
+                    for hole in hole_cards {
+                        match hole {
+                            Some(cards) => self.cards(cards),
+                            None => self.usize(0),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Solver options that change the result (see module docs)
+    pub fn config(&mut self, config: &SolverConfig) {
+        self.bytes(format!("{:?}/{:?}/{:?}", config.algorithm, config.precision, config.summation).as_bytes());
+        match config.prune {
+            Some(prune) => {
+                self.tag(1);
+                self.f64(prune.threshold);
+                self.u64(prune.revisit_every);
+            }
+            None => self.tag(0),
+        }
+        self.tag(config.deterministic as u8);
+        self.u64(config.seed);
+    }
+
+    /// Regret and strategy-sum arrays, infoset by infoset, then last regrets
+    /// when the snapshot keeps them
+    pub fn storage(&mut self, storage: &StorageSnapshot) {
+        self.usize(storage.regrets.len());
+        for (regrets, sums) in storage.regrets.iter().zip(&storage.strategy_sums) {
+            self.f64s(regrets);
+            self.f64s(sums);
+        }
+        if !storage.last_regrets.is_empty() {
+            storage.last_regrets.iter().for_each(|last| self.f64s(last));
+        }
+    }
+}
+
+/// Fingerprint of a tree's structure alone
+pub fn tree_fingerprint(tree: &GameTree) -> Fingerprint {
+    let mut h = Fingerprinter::new();
+    h.tree(tree);
+    h.finish()
+}
+
+/// Fingerprint of the result-affecting solver options
+pub fn config_fingerprint(config: &SolverConfig) -> Fingerprint {
+    let mut h = Fingerprinter::new();
+    h.config(config);
+    h.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Algorithm, SolverConfig};
+    use crate::test_tree::build_test_tree;
+
+    #[test]
+    fn test_fingerprint_is_fixed_and_sensitive() {
+        // FNV-1a test vector: the layout is stable, not just self-consistent
+        let mut h = Fingerprinter::new();
+        h.bytes(b"a");
+        assert_eq!(h.finish().to_string(), "af63dc4c8601ec8c");
+
+        let tree = build_test_tree();
+        assert_eq!(tree_fingerprint(&tree), tree_fingerprint(&tree.clone()));
+        let mut moved = tree.clone();
+        if let Node::Decision { pot, .. } = &mut moved.nodes[0] {
+            *pot += 1e-12;
+        }
+        assert_ne!(tree_fingerprint(&moved), tree_fingerprint(&tree));
+
+        let config = SolverConfig::new();
+        let threaded = SolverConfig::builder().threads(4).profile(true).build().unwrap();
+        assert_eq!(config_fingerprint(&config), config_fingerprint(&threaded));
+        let predictive = SolverConfig::builder().algorithm(Algorithm::PredictiveCfrPlus).build().unwrap();
+        assert_ne!(config_fingerprint(&config), config_fingerprint(&predictive));
+        assert_ne!(config_fingerprint(&config), config_fingerprint(&SolverConfig::builder().seed(7).build().unwrap()));
+    }
+}
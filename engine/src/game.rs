@@ -0,0 +1,491 @@
+//! Game configuration: blinds, antes, straddle, and preflop pot initialization
+//!
+//! A postflop spot starts from whatever preflop produced: both remaining
+//! players have put in `preflop_contribution`, every seat at the table has
+//! posted its ante, and blinds or a straddle posted by players who folded sit
+//! in the pot as dead money. `GameConfig` turns that description into the
+//! starting pot and stacks used by the tree builder.
+//!
+//! EV convention (used by `terminal_ip_ev` and everything built on it): a
+//! player's EV is the share of the final pot they win minus the chips they
+//! invested after the root. The pot includes all dead money, so
+//! `EV_IP + EV_OOP == initial pot` at every terminal.
+//!
+//! Knockout tournaments add `GameConfig::bounty`: a player who busts the
+//! other at showdown also collects the loser's bounty, converted to bb by
+//! `BountyModel::bb_per_bounty`. The solvers need zero-sum payoffs, so the
+//! bounty is charged to the busted player as it's paid to the winner.
+
+use std::collections::HashMap;
+use crate::node::{GameTree, Node, NodeId, Player};
+use crate::payoff::TerminalPayoff;
+
+/// Forced bet a remaining player posted before the flop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PostedBlind {
+    /// No blind (e.g. button, cutoff)
+    None,
+    /// Small blind
+    Small,
+    /// Big blind
+    Big,
+    /// Straddle (only meaningful when `GameConfig::straddle` is set)
+    Straddle,
+}
+
+/// Error returned by `GameConfig::validate`
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameConfigError {
+    /// Blinds, ante, or straddle are negative or out of order
+    InvalidForcedBets(String),
+    /// Fewer than two players, or both players claim the same blind
+    InvalidSeats(String),
+    /// A player's preflop contribution is below what they were forced to post
+    /// or below the amount required to see the flop
+    ContributionTooSmall(f64),
+    /// Forced bets and contribution exceed a player's starting stack
+    StackTooSmall(f64),
+    /// A bounty or the bounty conversion is negative
+    InvalidBounty(String),
+}
+
+impl std::fmt::Display for GameConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameConfigError::InvalidForcedBets(m) => write!(f, "invalid forced bets: {}", m),
+            GameConfigError::InvalidSeats(m) => write!(f, "invalid seats: {}", m),
+            GameConfigError::ContributionTooSmall(x) => write!(f, "preflop contribution {} is too small", x),
+            GameConfigError::StackTooSmall(x) => write!(f, "starting stack {} cannot cover the preflop action", x),
+            GameConfigError::InvalidBounty(m) => write!(f, "invalid bounty: {}", m),
+        }
+    }
+}
+
+impl std::error::Error for GameConfigError {}
+
+/// Knockout bounties on the two remaining players
+///
+/// In a progressive knockout, half of a bounty is paid when it's won and
+/// half goes onto the winner's own head; pass the half paid out as
+/// `bounties` and value it with `bb_per_bounty`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BountyModel {
+    /// Bounty on each player's head in prize money, indexed `[IP, OOP]`
+    pub bounties: [f64; 2],
+    /// Chip value (bb) of one unit of prize money
+    pub bb_per_bounty: f64,
+}
+
+impl BountyModel {
+    pub fn new(bounties: [f64; 2], bb_per_bounty: f64) -> Self {
+        BountyModel { bounties, bb_per_bounty }
+    }
+
+    /// `player`'s bounty in bb
+    pub fn chip_value(&self, player: Player) -> f64 {
+        self.bounties[player_index(player)] * self.bb_per_bounty
+    }
+
+    /// IP's bounty EV at a showdown terminal, given the chance IP wins
+    /// (`ip_wins`) and the chance OOP wins (`oop_wins`); ties bust nobody.
+    ///
+    /// A player is at risk when they're all-in for no more than the other
+    /// matched, i.e. the other covers them. Fold terminals pay no bounty.
+    pub fn ip_ev(&self, node: &Node, root_stacks: [f64; 2], ip_wins: f64, oop_wins: f64) -> f64 {
+        let Node::Terminal { folder: None, stacks, .. } = node else { return 0.0 };
+        let ip_invested = root_stacks[0] - stacks[0];
+        let oop_invested = root_stacks[1] - stacks[1];
+        let mut ev = 0.0;
+        if stacks[1] <= 0.0 && oop_invested <= ip_invested {
+            ev += ip_wins * self.chip_value(Player::OOP);
+        }
+        if stacks[0] <= 0.0 && ip_invested <= oop_invested {
+            ev -= oop_wins * self.chip_value(Player::IP);
+        }
+        ev
+    }
+}
+
+/// Blind/ante structure plus the preflop result leading into the postflop spot.
+///
+/// All amounts are in big blinds.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameConfig {
+    /// Small blind amount
+    pub small_blind: f64,
+    /// Big blind amount
+    pub big_blind: f64,
+    /// Ante per player, or the single big-blind ante when `big_blind_ante` is set
+    pub ante: f64,
+    /// When true, the big blind posts one ante of `ante` for the whole table
+    pub big_blind_ante: bool,
+    /// Straddle amount, if a straddle was posted
+    pub straddle: Option<f64>,
+    /// Players dealt in (determines total antes); at least 2
+    pub table_size: usize,
+    /// Forced bet posted by each remaining player, indexed `[IP, OOP]`
+    pub posted: [PostedBlind; 2],
+    /// Chips each remaining player put in preflop (including their blind)
+    pub preflop_contribution: f64,
+    /// Stack each player started the hand with (before antes and blinds),
+    /// indexed `[IP, OOP]`
+    pub starting_stack: [f64; 2],
+    /// Knockout bounties, for progressive-knockout and other bounty formats
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bounty: Option<BountyModel>,
+}
+
+impl GameConfig {
+    /// 100bb single-raised pot, button (IP) vs big blind (OOP) at a 6-max table:
+    /// 0.5/1 blinds, no ante, raise to 2.5 and call.
+    pub fn new() -> Self {
+        GameConfig {
+            small_blind: 0.5,
+            big_blind: 1.0,
+            ante: 0.0,
+            big_blind_ante: false,
+            straddle: None,
+            table_size: 6,
+            posted: [PostedBlind::None, PostedBlind::Big],
+            preflop_contribution: 2.5,
+            starting_stack: [100.0, 100.0],
+            bounty: None,
+        }
+    }
+
+    /// Postflop spot given directly by its starting pot and stacks (`[IP, OOP]`).
+    ///
+    /// Modeled as a blind-vs-blind pot with no antes, so there is no dead
+    /// money and each player contributed half of `pot`.
+    pub fn from_pot(pot: f64, stacks: [f64; 2]) -> Self {
+        let contribution = pot / 2.0;
+        GameConfig {
+            small_blind: 0.5,
+            big_blind: 1.0,
+            ante: 0.0,
+            big_blind_ante: false,
+            straddle: None,
+            table_size: 2,
+            posted: [PostedBlind::Small, PostedBlind::Big],
+            preflop_contribution: contribution,
+            starting_stack: [stacks[0] + contribution, stacks[1] + contribution],
+            bounty: None,
+        }
+    }
+
+    /// Total antes in the pot
+    pub fn total_antes(&self) -> f64 {
+        if self.big_blind_ante {
+            self.ante
+        } else {
+            self.ante * self.table_size as f64
+        }
+    }
+
+    /// Ante paid by one remaining player
+    pub fn ante_paid(&self, player: Player) -> f64 {
+        if self.big_blind_ante {
+            if self.posted[player_index(player)] == PostedBlind::Big { self.ante } else { 0.0 }
+        } else {
+            self.ante
+        }
+    }
+
+    /// Blinds and straddle posted by players who are no longer in the hand
+    pub fn dead_blinds(&self) -> f64 {
+        let has = |b: PostedBlind| self.posted.contains(&b);
+        let mut dead = 0.0;
+        if !has(PostedBlind::Small) {
+            dead += self.small_blind;
+        }
+        if !has(PostedBlind::Big) {
+            dead += self.big_blind;
+        }
+        if let Some(straddle) = self.straddle {
+            if !has(PostedBlind::Straddle) {
+                dead += straddle;
+            }
+        }
+        dead
+    }
+
+    /// All money in the pot that neither remaining player's preflop
+    /// contribution accounts for (antes plus dead blinds)
+    pub fn dead_money(&self) -> f64 {
+        self.total_antes() + self.dead_blinds()
+    }
+
+    /// Pot at the start of the postflop spot
+    pub fn initial_pot(&self) -> f64 {
+        2.0 * self.preflop_contribution + self.dead_money()
+    }
+
+    /// Stacks behind at the start of the postflop spot, indexed `[IP, OOP]`
+    pub fn postflop_stacks(&self) -> [f64; 2] {
+        [
+            self.starting_stack[0] - self.preflop_contribution - self.ante_paid(Player::IP),
+            self.starting_stack[1] - self.preflop_contribution - self.ante_paid(Player::OOP),
+        ]
+    }
+
+    /// The same structure with starting stacks set so the postflop stacks
+    /// are `stacks` (`[IP, OOP]`); blinds, antes, and the pot don't change
+    pub fn with_postflop_stacks(mut self, stacks: [f64; 2]) -> Self {
+        let current = self.postflop_stacks();
+        for i in 0..2 {
+            self.starting_stack[i] += stacks[i] - current[i];
+        }
+        self
+    }
+
+    /// Largest amount either player can win or lose postflop (the shorter stack)
+    pub fn effective_stack(&self) -> f64 {
+        let [ip, oop] = self.postflop_stacks();
+        ip.min(oop)
+    }
+
+    /// Amount the preflop action had to reach for both players to see the flop
+    pub fn largest_forced_bet(&self) -> f64 {
+        self.straddle.map_or(self.big_blind, |s| s.max(self.big_blind))
+    }
+
+    /// Check the structure is internally consistent
+    pub fn validate(&self) -> Result<(), GameConfigError> {
+        if self.small_blind < 0.0 || self.big_blind <= 0.0 || self.small_blind > self.big_blind {
+            return Err(GameConfigError::InvalidForcedBets("blinds must satisfy 0 <= SB <= BB, BB > 0".into()));
+        }
+        if self.ante < 0.0 {
+            return Err(GameConfigError::InvalidForcedBets("ante must be non-negative".into()));
+        }
+        if let Some(straddle) = self.straddle {
+            if straddle < self.big_blind {
+                return Err(GameConfigError::InvalidForcedBets("straddle must be at least the big blind".into()));
+            }
+        }
+        if self.table_size < 2 {
+            return Err(GameConfigError::InvalidSeats("table must have at least 2 players".into()));
+        }
+        if self.posted[0] != PostedBlind::None && self.posted[0] == self.posted[1] {
+            return Err(GameConfigError::InvalidSeats("both players posted the same blind".into()));
+        }
+        if self.posted.contains(&PostedBlind::Straddle) && self.straddle.is_none() {
+            return Err(GameConfigError::InvalidSeats("straddle seat without a straddle".into()));
+        }
+        if self.preflop_contribution < self.largest_forced_bet() {
+            return Err(GameConfigError::ContributionTooSmall(self.preflop_contribution));
+        }
+        for (stack, behind) in self.starting_stack.iter().zip(self.postflop_stacks()) {
+            if behind < 0.0 {
+                return Err(GameConfigError::StackTooSmall(*stack));
+            }
+        }
+        if let Some(bounty) = &self.bounty {
+            if bounty.bounties.iter().any(|&b| b < 0.0) || bounty.bb_per_bounty < 0.0 {
+                return Err(GameConfigError::InvalidBounty("bounties and bb_per_bounty must be non-negative".into()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Index of a player in `[IP, OOP]` arrays
+pub fn player_index(player: Player) -> usize {
+    match player {
+        Player::IP => 0,
+        Player::OOP => 1,
+    }
+}
+
+/// IP's EV at a terminal node: pot share minus chips invested since the root.
+///
+/// `root_stacks` are the stacks at the tree root; `ip_equity` is IP's share of
+/// a contested pot (ignored for fold terminals). The pot includes dead money,
+/// so the folding player's opponent collects it in full.
+///
+/// At showdown, chips one player put in beyond what the other matched (a
+/// shorter stack calling all-in for less) are uncalled and go straight back
+/// to the player who bet them; only the rest of the pot is contested (see
+/// `payoff::TerminalPayoff` for the breakdown).
+pub fn terminal_ip_ev(node: &Node, root_stacks: [f64; 2], ip_equity: f64) -> f64 {
+    match TerminalPayoff::from_node(node, root_stacks) {
+        Some(payoff) => payoff.ip_ev(ip_equity),
+        None => panic!("terminal_ip_ev called on a non-terminal node"),
+    }
+}
+
+/// Build the terminal EV table (IP perspective) for a tree.
+///
+/// `ip_equity` is called for each showdown terminal and returns IP's pot share
+/// there (e.g. range-vs-range equity on that terminal's board).
+pub fn build_terminal_evs(tree: &GameTree, mut ip_equity: impl FnMut(&Node) -> f64) -> HashMap<NodeId, f64> {
+    let root_stacks = match tree.get(0) {
+        Some(Node::Decision { stacks, .. }) | Some(Node::Chance { stacks, .. }) | Some(Node::Terminal { stacks, .. }) => *stacks,
+        None => return HashMap::new(),
+    };
+    let mut table = HashMap::new();
+    for node in &tree.nodes {
+        if let Node::Terminal { id, folder, .. } = node {
+            let equity = if folder.is_none() { ip_equity(node) } else { 0.0 };
+            table.insert(*id, terminal_ip_ev(node, root_stacks, equity));
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_srp_pot() {
+        let g = GameConfig::new();
+        assert!(g.validate().is_ok());
+        // 2.5 + 2.5 + dead SB 0.5
+        assert_eq!(g.initial_pot(), 5.5);
+        assert_eq!(g.postflop_stacks(), [97.5, 97.5]);
+    }
+
+    #[test]
+    fn test_from_pot() {
+        let g = GameConfig::from_pot(10.0, [95.0, 120.0]);
+        assert!(g.validate().is_ok());
+        assert_eq!(g.initial_pot(), 10.0);
+        assert_eq!(g.postflop_stacks(), [95.0, 120.0]);
+        assert_eq!(g.dead_money(), 0.0);
+    }
+
+    #[test]
+    fn test_antes_are_dead_money() {
+        let mut g = GameConfig::new();
+        g.ante = 0.125;
+        assert_eq!(g.total_antes(), 0.75);
+        assert_eq!(g.initial_pot(), 6.25);
+        assert_eq!(g.postflop_stacks(), [97.375, 97.375]);
+    }
+
+    #[test]
+    fn test_big_blind_ante_paid_by_big_blind_only() {
+        let mut g = GameConfig::new();
+        g.ante = 1.0;
+        g.big_blind_ante = true;
+        g.table_size = 8;
+        assert_eq!(g.total_antes(), 1.0);
+        assert_eq!(g.initial_pot(), 6.5);
+        assert_eq!(g.postflop_stacks(), [97.5, 96.5]);
+    }
+
+    #[test]
+    fn test_straddle_dead_money_and_minimum_contribution() {
+        let mut g = GameConfig::new();
+        g.straddle = Some(2.0);
+        g.preflop_contribution = 6.0;
+        // Both blinds and the straddle are dead
+        g.posted = [PostedBlind::None, PostedBlind::None];
+        assert_eq!(g.dead_blinds(), 3.5);
+        assert_eq!(g.initial_pot(), 15.5);
+
+        // Straddler in the hand: their straddle is live money
+        g.posted = [PostedBlind::None, PostedBlind::Straddle];
+        assert_eq!(g.dead_blinds(), 1.5);
+        assert!(g.validate().is_ok());
+
+        g.preflop_contribution = 1.5;
+        assert_eq!(g.validate(), Err(GameConfigError::ContributionTooSmall(1.5)));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_structures() {
+        let mut g = GameConfig::new();
+        g.posted = [PostedBlind::Big, PostedBlind::Big];
+        assert!(matches!(g.validate(), Err(GameConfigError::InvalidSeats(_))));
+
+        let mut g = GameConfig::new();
+        g.small_blind = 2.0;
+        assert!(matches!(g.validate(), Err(GameConfigError::InvalidForcedBets(_))));
+
+        let mut g = GameConfig::new();
+        g.starting_stack = [100.0, 2.0];
+        assert_eq!(g.validate(), Err(GameConfigError::StackTooSmall(2.0)));
+    }
+
+    #[test]
+    fn test_asymmetric_stacks() {
+        let mut g = GameConfig::new();
+        g.starting_stack = [40.0, 250.0];
+        g.ante = 0.5;
+        g.big_blind_ante = true;
+        assert!(g.validate().is_ok());
+        // Only the big blind (OOP) pays the ante
+        assert_eq!(g.postflop_stacks(), [37.5, 247.0]);
+        assert_eq!(g.effective_stack(), 37.5);
+        let deeper = g.clone().with_postflop_stacks([150.0, 20.0]);
+        assert_eq!(deeper.postflop_stacks(), [150.0, 20.0]);
+        assert_eq!(deeper.initial_pot(), g.initial_pot());
+    }
+
+    #[test]
+    fn test_bounty_paid_only_when_covered_and_all_in() {
+        let bounty = BountyModel::new([10.0, 20.0], 0.5);
+        let root = [40.0, 30.0];
+        let terminal = |stacks: [f64; 2], folder| Node::Terminal {
+            id: 0, parent: None, folder, pot: 6.0 + (root[0] - stacks[0]) + (root[1] - stacks[1]),
+            stacks, board: vec![], hole_cards: [None, None],
+        };
+        // OOP shoves 30, IP covers and calls: only OOP's 10bb bounty is at stake
+        let covered = terminal([10.0, 0.0], None);
+        assert_eq!(bounty.ip_ev(&covered, root, 1.0, 0.0), 10.0);
+        assert_eq!(bounty.ip_ev(&covered, root, 0.0, 1.0), 0.0);
+        assert_eq!(bounty.ip_ev(&covered, root, 0.6, 0.3), 6.0);
+        // Equal stacks all-in: the loser's bounty goes to the winner either way
+        let even = terminal([0.0, 0.0], None);
+        assert_eq!(bounty.ip_ev(&even, [30.0, 30.0], 0.0, 1.0), -5.0);
+        assert_eq!(bounty.ip_ev(&terminal([0.0, 0.0], Some(Player::OOP)), root, 1.0, 0.0), 0.0);
+        assert_eq!(bounty.ip_ev(&terminal([35.0, 25.0], None), root, 1.0, 0.0), 0.0);
+
+        let mut g = GameConfig::new();
+        g.bounty = Some(BountyModel::new([-1.0, 1.0], 1.0));
+        assert!(matches!(g.validate(), Err(GameConfigError::InvalidBounty(_))));
+    }
+
+    #[test]
+    fn test_uncalled_excess_returned_at_showdown() {
+        let root = [10.0, 50.0];
+        // OOP shoves 50 into 6, IP calls all-in for 10: 40 of OOP's bet is uncalled
+        let showdown = Node::Terminal {
+            id: 0, parent: None, folder: None, pot: 6.0 + 10.0 + 50.0,
+            stacks: [0.0, 0.0], board: vec![], hole_cards: [None, None],
+        };
+        // Contested pot is 26; IP wins it all at 100% equity
+        assert_eq!(terminal_ip_ev(&showdown, root, 1.0), 26.0 - 10.0);
+        assert_eq!(terminal_ip_ev(&showdown, root, 0.0), -10.0);
+        assert_eq!(terminal_ip_ev(&showdown, root, 0.5), 13.0 - 10.0);
+    }
+
+    #[test]
+    fn test_terminal_ev_includes_dead_money() {
+        let pot = 6.25;
+        let root = [97.375, 97.375];
+        // IP bets 3, OOP folds: IP collects the whole pot, nets the dead money
+        let oop_folds = Node::Terminal {
+            id: 0, parent: None, folder: Some(Player::OOP), pot: pot + 3.0,
+            stacks: [root[0] - 3.0, root[1]], board: vec![], hole_cards: [None, None],
+        };
+        assert_eq!(terminal_ip_ev(&oop_folds, root, 0.0), pot);
+
+        // Checked-down showdown at 50% equity: IP gets half the starting pot
+        let showdown = Node::Terminal {
+            id: 1, parent: None, folder: None, pot,
+            stacks: root, board: vec![], hole_cards: [None, None],
+        };
+        assert_eq!(terminal_ip_ev(&showdown, root, 0.5), pot / 2.0);
+    }
+}
@@ -0,0 +1,318 @@
+//! 13x13 range grid projection for visualization
+//!
+//! Projects per-combo data (range weights, and optionally per-action
+//! frequencies) onto the standard 169-cell hand grid:
+//!
+//! - Row and column 0 are aces, 12 are deuces.
+//! - The diagonal holds pocket pairs.
+//! - Above the diagonal (row < col) are suited hands, below are offsuit.
+//!
+//! Each cell reports the weighted combo count, its average frequency, and the
+//! fraction of that weight taking each action (the "mixed color" a renderer
+//! stacks per cell). Exports are plain JSON and CSV with no external crate.
+
+use crate::node::{Action, Card};
+use crate::combo::{class_combos, class_index, class_label, combo_index};
+use crate::range::Range;
+
+/// Hand class of a grid cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellKind {
+    /// Pocket pair (diagonal)
+    Pair,
+    /// Suited non-pair (above diagonal)
+    Suited,
+    /// Offsuit non-pair (below diagonal)
+    Offsuit,
+}
+
+impl CellKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            CellKind::Pair => "pair",
+            CellKind::Suited => "suited",
+            CellKind::Offsuit => "offsuit",
+        }
+    }
+}
+
+/// One of the 169 hand-class cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridCell {
+    /// Row index (0 = A .. 12 = 2)
+    pub row: usize,
+    /// Column index (0 = A .. 12 = 2)
+    pub col: usize,
+    /// Class label, e.g. "AKs", "T9o", "77"
+    pub label: String,
+    /// Pair, suited, or offsuit
+    pub kind: CellKind,
+    /// Combos of this class not blocked by the board
+    pub live_combos: usize,
+    /// Weighted combo count (sum of range weights over live combos)
+    pub combos: f64,
+    /// Average weight over live combos (0 when no combo is live)
+    pub frequency: f64,
+    /// Fraction of the cell's weight taking each action (sums to 1 when `combos > 0`)
+    pub actions: Vec<f64>,
+}
+
+/// 169-cell grid in row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeGrid {
+    /// Labels for the entries of each cell's `actions` (empty for a plain range)
+    pub action_labels: Vec<String>,
+    /// Cells in row-major order (`cells[row * 13 + col]`)
+    pub cells: Vec<GridCell>,
+}
+
+impl RangeGrid {
+    /// Project range weights onto the grid (no action breakdown)
+    pub fn from_range(range: &Range, board: &[Card]) -> Self {
+        Self::build(range, board, Vec::new(), |_, _| None)
+    }
+
+    /// Project per-combo action frequencies onto the grid.
+    ///
+    /// `frequencies[a][combo_index]` is the probability that the combo takes
+    /// action `a`; each cell's `actions` is the range-weighted average.
+    pub fn from_action_frequencies(
+        range: &Range,
+        board: &[Card],
+        action_labels: Vec<String>,
+        frequencies: &[Vec<f64>],
+    ) -> Self {
+        assert_eq!(action_labels.len(), frequencies.len(), "one frequency vector per action");
+        Self::build(range, board, action_labels, |a, idx| Some(frequencies[a][idx]))
+    }
+
+    /// Project a node-level strategy (one probability per action, shared by
+    /// every combo) onto the grid.
+    pub fn from_node_strategy(range: &Range, board: &[Card], actions: &[Action], strategy: &[f64]) -> Self {
+        assert_eq!(actions.len(), strategy.len(), "one probability per action");
+        let labels = actions.iter().map(|a| a.to_string()).collect();
+        Self::build(range, board, labels, |a, _| Some(strategy[a]))
+    }
+
+    /// Cell at (row, col)
+    pub fn cell(&self, row: usize, col: usize) -> &GridCell {
+        &self.cells[row * 13 + col]
+    }
+
+    /// Export as a JSON object: `{"actions": [...], "cells": [{...}, ...]}`
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"actions\":[");
+        for (i, label) in self.action_labels.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&json_string(label));
+        }
+        out.push_str("],\"cells\":[");
+        for (i, c) in self.cells.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&format!(
+                "{{\"row\":{},\"col\":{},\"hand\":{},\"kind\":\"{}\",\"live_combos\":{},\"combos\":{},\"frequency\":{},\"actions\":[",
+                c.row, c.col, json_string(&c.label), c.kind.as_str(), c.live_combos, c.combos, c.frequency,
+            ));
+            for (j, f) in c.actions.iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                out.push_str(&f.to_string());
+            }
+            out.push_str("]}");
+        }
+        out.push_str("]}");
+        out
+    }
+
+    /// Export as CSV with one row per cell and one column per action
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("row,col,hand,kind,live_combos,combos,frequency");
+        for label in &self.action_labels {
+            out.push(',');
+            out.push_str(&csv_field(label));
+        }
+        out.push('\n');
+        for c in &self.cells {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{}",
+                c.row, c.col, c.label, c.kind.as_str(), c.live_combos, c.combos, c.frequency,
+            ));
+            for f in &c.actions {
+                out.push(',');
+                out.push_str(&f.to_string());
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn build(
+        range: &Range,
+        board: &[Card],
+        action_labels: Vec<String>,
+        freq: impl Fn(usize, usize) -> Option<f64>,
+    ) -> Self {
+        let dead = board.iter().fold(0u64, |m, c| m | c.mask());
+        let num_actions = action_labels.len();
+        let mut cells = Vec::with_capacity(169);
+        for row in 0..13 {
+            for col in 0..13 {
+                let kind = cell_kind(row, col);
+                let mut live_combos = 0usize;
+                let mut combos = 0.0_f64;
+                let mut action_weight = vec![0.0_f64; num_actions];
+                for [a, b] in class_combos(row * 13 + col) {
+                    if (a.mask() | b.mask()) & dead != 0 {
+                        continue;
+                    }
+                    live_combos += 1;
+                    let w = range.weight([a, b]);
+                    combos += w;
+                    let idx = combo_index(a, b);
+                    for (act, slot) in action_weight.iter_mut().enumerate() {
+                        *slot += w * freq(act, idx).unwrap_or(0.0);
+                    }
+                }
+                let actions = if combos > 0.0 {
+                    action_weight.iter().map(|&x| x / combos).collect()
+                } else {
+                    vec![0.0; num_actions]
+                };
+                cells.push(GridCell {
+                    row,
+                    col,
+                    label: class_label(row * 13 + col),
+                    kind,
+                    live_combos,
+                    combos,
+                    frequency: if live_combos > 0 { combos / live_combos as f64 } else { 0.0 },
+                    actions,
+                });
+            }
+        }
+        RangeGrid { action_labels, cells }
+    }
+}
+
+/// Grid position of a hole-card combo as (row, col)
+pub fn cell_of(hand: [Card; 2]) -> (usize, usize) {
+    let class = class_index(hand);
+    (class / 13, class % 13)
+}
+
+/// Class of a grid cell
+fn cell_kind(row: usize, col: usize) -> CellKind {
+    if row == col {
+        CellKind::Pair
+    } else if row < col {
+        CellKind::Suited
+    } else {
+        CellKind::Offsuit
+    }
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains(',') || s.contains('"') {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::parse_cards;
+
+    #[test]
+    fn test_grid_layout_labels() {
+        let grid = RangeGrid::from_range(&Range::full(), &[]);
+        assert_eq!(grid.cells.len(), 169);
+        assert_eq!(grid.cell(0, 0).label, "AA");
+        assert_eq!(grid.cell(0, 1).label, "AKs");
+        assert_eq!(grid.cell(1, 0).label, "AKo");
+        assert_eq!(grid.cell(12, 12).label, "22");
+        assert_eq!(grid.cell(4, 5).label, "T9s");
+    }
+
+    #[test]
+    fn test_full_range_combo_counts() {
+        let grid = RangeGrid::from_range(&Range::full(), &[]);
+        let total: f64 = grid.cells.iter().map(|c| c.combos).sum();
+        assert_eq!(total, 1326.0);
+        assert_eq!(grid.cell(0, 0).live_combos, 6);
+        assert_eq!(grid.cell(0, 1).live_combos, 4);
+        assert_eq!(grid.cell(1, 0).live_combos, 12);
+    }
+
+    #[test]
+    fn test_board_blockers_reduce_live_combos() {
+        let board = parse_cards("AsKh7d").unwrap();
+        let grid = RangeGrid::from_range(&Range::full(), &board);
+        assert_eq!(grid.cell(0, 0).live_combos, 3); // AA without As
+        assert_eq!(grid.cell(0, 1).live_combos, 2); // AKs: only d, c
+    }
+
+    #[test]
+    fn test_cell_of_matches_labels() {
+        let aks = parse_cards("AhKh").unwrap();
+        let ako = parse_cards("KdAh").unwrap();
+        let sevens = parse_cards("7c7d").unwrap();
+        assert_eq!(cell_of([aks[0], aks[1]]), (0, 1));
+        assert_eq!(cell_of([ako[0], ako[1]]), (1, 0));
+        assert_eq!(cell_of([sevens[0], sevens[1]]), (7, 7));
+    }
+
+    #[test]
+    fn test_action_frequencies_are_weighted_fractions() {
+        let range = Range::parse("AA").unwrap();
+        let mut bet = vec![0.0; crate::combo::NUM_COMBOS];
+        let mut check = vec![1.0; crate::combo::NUM_COMBOS];
+        // One AA combo always bets, the other five check
+        let asah = parse_cards("AsAh").unwrap();
+        let idx = combo_index(asah[0], asah[1]);
+        bet[idx] = 1.0;
+        check[idx] = 0.0;
+        let grid = RangeGrid::from_action_frequencies(
+            &range, &[], vec!["Check".into(), "Bet 5".into()], &[check, bet],
+        );
+        let aa = grid.cell(0, 0);
+        assert!((aa.actions[1] - 1.0 / 6.0).abs() < 1e-12);
+        assert!((aa.actions.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_exports_contain_every_cell() {
+        let grid = RangeGrid::from_node_strategy(
+            &Range::parse("QQ+").unwrap(), &[], &[Action::Check, Action::Bet { size: 5.0 }], &[0.25, 0.75],
+        );
+        let csv = grid.to_csv();
+        assert_eq!(csv.lines().count(), 170);
+        assert!(csv.starts_with("row,col,hand,kind,live_combos,combos,frequency,Check,Bet 5\n"));
+        let json = grid.to_json();
+        assert!(json.starts_with("{\"actions\":[\"Check\",\"Bet 5\"]"));
+        assert_eq!(json.matches("\"hand\"").count(), 169);
+        assert!(json.contains("{\"row\":0,\"col\":0,\"hand\":\"AA\",\"kind\":\"pair\",\"live_combos\":6,\"combos\":6,\"frequency\":1,\"actions\":[0.25,0.75]}"));
+    }
+}
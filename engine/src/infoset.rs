@@ -0,0 +1,180 @@
+//! Information sets: the table mapping what a player observes to an `InfosetId`
+//!
+//! An infoset is keyed by the acting player, the public history (every
+//! action so far plus the board), and a hole-card bucket. Trees from
+//! `build_tree` don't deal hole cards, so their keys all use bucket 0 and
+//! every decision node is its own infoset. Abstractions intern coarser or
+//! finer keys instead: several nodes may share one infoset, as long as they
+//! offer the same number of actions.
+//!
+//! Ids are dense (`0..len()`), so regret storage holds no slots for terminal
+//! or chance nodes.
+
+use std::collections::HashMap;
+use crate::node::{Action, Card, GameTree, InfosetId, Node, Player};
+
+/// What the acting player knows at a decision
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfosetKey {
+    pub player: Player,
+    /// Every action since the root, across streets
+    pub history: Vec<Action>,
+    pub board: Vec<Card>,
+    /// Hole-card bucket (0 when hole cards aren't part of the tree)
+    pub bucket: u32,
+}
+
+impl InfosetKey {
+    pub fn new(player: Player, history: Vec<Action>, board: Vec<Card>, bucket: u32) -> Self {
+        InfosetKey { player, history, board, bucket }
+    }
+
+    /// Key of a decision node's public state with bucket 0
+    pub fn of_node(node: &Node) -> Option<Self> {
+        match node {
+            Node::Decision { player, bet_sequence, board, .. } => {
+                Some(InfosetKey::new(*player, bet_sequence.clone(), board.clone(), 0))
+            }
+            _ => None,
+        }
+    }
+
+    /// Hashable encoding (`Action` sizes are floats, so compare their bits)
+    fn encode(&self) -> Vec<u64> {
+        let mut out = Vec::with_capacity(4 + 2 * self.history.len());
+        out.push(match self.player {
+            Player::IP => 0,
+            Player::OOP => 1,
+        });
+        out.push(u64::from(self.bucket));
+        // Ordered (≤ 5 cards, base 53): the same cards dealt in a different order differ
+        out.push(self.board.iter().fold(0u64, |acc, c| acc * 53 + u64::from(c.value()) + 1));
+        for action in &self.history {
+            let (tag, size) = match action {
+                Action::Fold => (0, 0.0),
+                Action::Check => (1, 0.0),
+                Action::Call => (2, 0.0),
+                Action::Bet { size } => (3, *size),
+                Action::Raise { size } => (4, *size),
+            };
+            out.push(tag);
+            out.push(size.to_bits());
+        }
+        out
+    }
+}
+
+/// Dense infoset ids with each infoset's key and action count
+#[derive(Debug, Clone, Default)]
+pub struct InfosetTable {
+    keys: Vec<InfosetKey>,
+    actions: Vec<usize>,
+    index: HashMap<Vec<u64>, InfosetId>,
+}
+
+impl InfosetTable {
+    pub fn new() -> Self {
+        InfosetTable { keys: Vec::new(), actions: Vec::new(), index: HashMap::new() }
+    }
+
+    /// Rebuild the table of a tree from its decision nodes' `infoset_id`s.
+    /// Where several nodes share an infoset, the first node's key is kept.
+    ///
+    /// Panics if the ids aren't dense or nodes sharing an infoset disagree on
+    /// the number of actions.
+    pub fn from_tree(tree: &GameTree) -> Self {
+        let mut slots: Vec<Option<(InfosetKey, usize)>> = vec![None; tree.num_infosets()];
+        for node in &tree.nodes {
+            if let (Some(id), Some(key)) = (node.infoset_id(), InfosetKey::of_node(node)) {
+                let actions = node.children().len();
+                match &slots[id as usize] {
+                    Some((_, n)) => assert_eq!(*n, actions, "infoset {} has nodes with different action counts", id),
+                    None => slots[id as usize] = Some((key, actions)),
+                }
+            }
+        }
+        let mut table = InfosetTable::new();
+        for (id, slot) in slots.into_iter().enumerate() {
+            let (key, actions) = slot.unwrap_or_else(|| panic!("infoset {} has no decision node", id));
+            table.index.entry(key.encode()).or_insert(id as InfosetId);
+            table.keys.push(key);
+            table.actions.push(actions);
+        }
+        table
+    }
+
+    /// Id of `key`, allocating the next one if it's new.
+    ///
+    /// Panics if `key` is already present with a different action count.
+    pub fn intern(&mut self, key: InfosetKey, actions: usize) -> InfosetId {
+        let encoded = key.encode();
+        if let Some(&id) = self.index.get(&encoded) {
+            assert_eq!(self.actions[id as usize], actions, "infoset {} interned with different action counts", id);
+            return id;
+        }
+        let id = self.keys.len() as InfosetId;
+        self.index.insert(encoded, id);
+        self.keys.push(key);
+        self.actions.push(actions);
+        id
+    }
+
+    pub fn get(&self, key: &InfosetKey) -> Option<InfosetId> {
+        self.index.get(&key.encode()).copied()
+    }
+
+    pub fn key(&self, id: InfosetId) -> &InfosetKey {
+        &self.keys[id as usize]
+    }
+
+    pub fn num_actions(&self, id: InfosetId) -> usize {
+        self.actions[id as usize]
+    }
+
+    /// Action count per infoset, indexed by id
+    pub fn actions_per_infoset(&self) -> &[usize] {
+        &self.actions
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, TreeConfig};
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+
+    #[test]
+    fn test_built_tree_has_dense_infosets_per_decision() {
+        let tree = build_tree(&TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap()));
+        let table = InfosetTable::from_tree(&tree);
+        assert_eq!(table.len(), tree.stats().decisions);
+        assert_eq!(tree.num_infosets(), table.len());
+        for node in tree.nodes.iter().filter(|n| n.is_decision()) {
+            let id = node.infoset_id().unwrap();
+            assert_eq!(table.get(&InfosetKey::of_node(node).unwrap()), Some(id));
+            assert_eq!(table.num_actions(id), node.children().len());
+        }
+    }
+
+    #[test]
+    fn test_intern_reuses_ids_and_buckets_split() {
+        let board = parse_cards("AsKh7d").unwrap();
+        let mut table = InfosetTable::new();
+        let a = table.intern(InfosetKey::new(Player::OOP, vec![], board.clone(), 0), 2);
+        let b = table.intern(InfosetKey::new(Player::IP, vec![Action::Bet { size: 5.0 }], board.clone(), 0), 3);
+        let c = table.intern(InfosetKey::new(Player::IP, vec![Action::Bet { size: 5.0 }], board.clone(), 1), 3);
+        assert_eq!((a, b, c), (0, 1, 2));
+        assert_eq!(table.intern(InfosetKey::new(Player::OOP, vec![], board.clone(), 0), 2), a);
+        assert_eq!(table.get(&InfosetKey::new(Player::IP, vec![Action::Bet { size: 7.5 }], board, 0)), None);
+        assert_eq!(table.actions_per_infoset(), &[2, 3, 3]);
+    }
+}
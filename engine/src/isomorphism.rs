@@ -0,0 +1,152 @@
+//! Suit isomorphism: board symmetries and canonical runout classes
+//!
+//! Permuting suits doesn't change a hand's strength, so two runouts related
+//! by a suit permutation that fixes the board are strategically the same
+//! (given suit-symmetric ranges). On `AsKs7d`, swapping hearts and clubs
+//! fixes the board, so the `2h` and `2c` turns play identically and can be
+//! solved once with twice the weight.
+//!
+//! A permutation fixes a board when it maps the flop to itself as a set and
+//! leaves every later card where it is (the turn and river are dealt in
+//! order, so they aren't interchangeable with flop cards).
+//!
+//! Grouped runouts are valued on their representative's board, so they're
+//! only exact when the ranges are symmetric too: `range_is_symmetric` checks
+//! that every live combo weighs the same as its images under the board's
+//! symmetries. With `AhKh` but not `AcKc` in a range on `AsKs7d`, the `2h`
+//! and `2c` turns differ and can't be grouped.
+
+use crate::deck::Deck;
+use crate::node::Card;
+use crate::range::Range;
+
+/// `perm[s]` is the suit that suit `s` maps to
+pub type SuitPermutation = [u8; 4];
+
+/// Image of `card` under `perm`
+pub fn permute(card: Card, perm: &SuitPermutation) -> Card {
+    Card::from_rank_suit(card.rank(), perm[card.suit() as usize])
+}
+
+/// All 24 suit permutations, identity first
+fn all_permutations() -> Vec<SuitPermutation> {
+    let mut out = Vec::with_capacity(24);
+    for a in 0..4u8 {
+        for b in (0..4).filter(|&b| b != a) {
+            for c in (0..4).filter(|&c| c != a && c != b) {
+                let d = 6 - a - b - c;
+                out.push([a, b, c, d]);
+            }
+        }
+    }
+    out
+}
+
+/// Suit permutations that fix `board` (always includes the identity)
+pub fn board_symmetries(board: &[Card]) -> Vec<SuitPermutation> {
+    let (flop, later) = board.split_at(board.len().min(3));
+    let flop_mask = flop.iter().fold(0u64, |m, c| m | c.mask());
+    all_permutations().into_iter().filter(|perm| {
+        flop.iter().all(|&c| flop_mask & permute(c, perm).mask() != 0)
+            && later.iter().all(|&c| permute(c, perm) == c)
+    }).collect()
+}
+
+/// Whether every combo of `range` that's live on `board` has the weight of
+/// its images under each of `board`'s symmetries
+pub fn range_is_symmetric(range: &Range, board: &[Card]) -> bool {
+    let symmetries = board_symmetries(board);
+    range.live_combos(board).all(|([a, b], w)| {
+        symmetries.iter().all(|perm| (range.weight([permute(a, perm), permute(b, perm)]) - w).abs() <= 1e-12)
+    })
+}
+
+/// One representative per class of suit-isomorphic next cards, with the
+/// class size. Representatives are the lowest card of their class, in card
+/// order; sizes sum to the number of undealt cards.
+pub fn runout_classes(board: &[Card]) -> Vec<(Card, usize)> {
+    let symmetries = board_symmetries(board);
+    let mut left = Deck::without(board);
+    let mut out = Vec::new();
+    while let Some(card) = left.iter().next() {
+        let class = symmetries.iter().fold(0u64, |m, perm| m | permute(card, perm).mask());
+        left = Deck::from_mask(left.mask() & !class);
+        out.push((card, class.count_ones() as usize));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::node_values;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::cfr::CfrSolver;
+    use crate::game::{build_terminal_evs, GameConfig};
+    use crate::node::{parse_cards, Node};
+    use std::time::Duration;
+
+    #[test]
+    fn test_symmetries_by_board_texture() {
+        let symmetries = |b: &str| board_symmetries(&parse_cards(b).unwrap()).len();
+        assert_eq!(symmetries("AsKh7d"), 1);
+        assert_eq!(symmetries("AsKs7d"), 2);
+        assert_eq!(symmetries("AsKs7s"), 6);
+        // The paired flop's suits can swap with each other as a set
+        assert_eq!(symmetries("AsAh7d"), 2);
+        // A turn pins its suit
+        assert_eq!(symmetries("AsKs7s2h"), 2);
+        assert_eq!(symmetries("AsKs7s2h9d"), 1);
+    }
+
+    #[test]
+    fn test_runout_classes_cover_the_deck() {
+        for (board, classes) in [("AsKh7d", 49), ("AsKs7d", 36), ("AsKs7s", 23), ("AsKs7s2h", 35)] {
+            let cards = parse_cards(board).unwrap();
+            let runouts = runout_classes(&cards);
+            assert_eq!(runouts.len(), classes, "{}", board);
+            assert_eq!(runouts.iter().map(|&(_, n)| n).sum::<usize>(), 52 - cards.len());
+        }
+        // The heart deuce stands for the club deuce too
+        let runouts = runout_classes(&parse_cards("AsKs7d").unwrap());
+        let deuce = parse_cards("2h").unwrap()[0];
+        assert!(runouts.contains(&(deuce, 2)));
+    }
+
+    #[test]
+    fn test_range_symmetry_follows_the_board() {
+        let board = parse_cards("AsKs7d").unwrap();
+        assert!(range_is_symmetric(&Range::parse("AK,QJs,QQ").unwrap(), &board));
+        assert!(!range_is_symmetric(&Range::parse("AhKh,QcJc").unwrap(), &board));
+        // Only hearts and clubs are interchangeable on this board
+        assert!(range_is_symmetric(&Range::parse("QhJh,QcJc").unwrap(), &board));
+        assert!(range_is_symmetric(&Range::parse("QsJs").unwrap(), &board));
+        assert!(!range_is_symmetric(&Range::parse("QhJh").unwrap(), &board));
+    }
+
+    #[test]
+    fn test_grouped_tree_solves_to_the_same_values() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKs7s2h").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 1;
+        // Depends on ranks only, so it's the same on isomorphic boards
+        let equity = |node: &Node| f64::from(node.board().iter().map(|c| c.rank()).sum::<u8>() % 7) / 6.0;
+        let solve = |config: &TreeConfig| {
+            let tree = build_tree(config);
+            let evs = build_terminal_evs(&tree, equity);
+            let mut solver = CfrSolver::new_with_evs(tree, evs);
+            for _ in 0..20 {
+                solver.run_iteration();
+            }
+            let root = node_values(&solver.tree, &solver.storage, solver.terminal_evs())[0];
+            (solver.tree.len(), root, solver.exploitability(Duration::ZERO).exploitability)
+        };
+        let (full_nodes, full_value, full_expl) = solve(&config);
+        config.isomorphic_runouts = true;
+        let (nodes, value, expl) = solve(&config);
+        assert!(nodes * 4 < full_nodes * 3, "{} vs {}", nodes, full_nodes);
+        assert!((value - full_value).abs() < 1e-9, "{} vs {}", value, full_value);
+        assert!((expl - full_expl).abs() < 1e-9, "{} vs {}", expl, full_expl);
+    }
+}
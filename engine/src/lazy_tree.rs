@@ -0,0 +1,463 @@
+//! Lazily expanded game trees: children are built the first time a branch
+//! is traversed
+//!
+//! `build_tree` materializes every runout up front, which dominates memory
+//! on flop trees even when a solve (or a sampled one) only ever visits part
+//! of them. `LazyTree` starts from the root alone and expands a node's
+//! children on the first `children()` call, behind an `RwLock` so readers
+//! of already-expanded nodes don't serialize.
+//!
+//! Node ids are allocated in expansion order, so they differ from
+//! `build_tree`'s; parents still precede children, and each decision node
+//! gets the infoset id of its `InfosetKey` in the tree's own table.
+//!
+//! `LazyCfrSolver` runs CFR+ directly on a lazy tree. With every chance
+//! outcome traversed it matches `CfrSolver` on the built tree infoset for
+//! infoset; with chance sampling it only expands the runouts it draws.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use crate::builder::{legal_actions, BettingState, Next, TreeConfig};
+use crate::cfr::{CfrSolver, RegretStorage, RegretUpdate};
+use crate::config::{SolverConfig, StorageMode};
+use crate::game::{build_terminal_evs, terminal_ip_ev};
+use crate::infoset::InfosetTable;
+use crate::node::{GameTree, Node, NodeId, Player};
+use crate::rng::Rng;
+
+/// Work left at a node that hasn't been expanded yet
+enum Pending {
+    /// Decision: where each action leads
+    Decision(Vec<Next>),
+    /// Chance: the state whose round just closed
+    Chance(BettingState),
+}
+
+struct Arena {
+    nodes: Vec<Node>,
+    /// `Some` until the node's children exist, indexed by `NodeId`
+    pending: Vec<Option<Pending>>,
+    infosets: InfosetTable,
+}
+
+impl Arena {
+    fn push(&mut self, node: Node, pending: Option<Pending>) -> NodeId {
+        self.nodes.push(node);
+        self.pending.push(pending);
+        (self.nodes.len() - 1) as NodeId
+    }
+
+    /// Decision node for `state`; its actions are computed now, its children later
+    fn decision(&mut self, config: &TreeConfig, state: BettingState, parent: Option<NodeId>) -> NodeId {
+        let id = self.nodes.len() as NodeId;
+        let (actions, nexts): (Vec<_>, Vec<_>) = legal_actions(config, &state).into_iter().unzip();
+        let infoset_id = self.infosets.intern(state.infoset_key(), actions.len());
+        let node = state.decision_node(id, infoset_id, parent, Vec::new(), actions);
+        self.push(node, Some(Pending::Decision(nexts)))
+    }
+
+    /// Round closed: showdown on the river (or a depth-limit leaf), otherwise
+    /// an unexpanded Chance node
+    fn close_round(&mut self, config: &TreeConfig, state: BettingState, parent: NodeId) -> NodeId {
+        let id = self.nodes.len() as NodeId;
+        if !state.next_street().is_some_and(|s| config.deals(s)) {
+            return self.push(state.terminal_node(id, Some(parent), None), None);
+        }
+        let node = state.chance_node(id, parent, Vec::new(), Vec::new());
+        self.push(node, Some(Pending::Chance(state)))
+    }
+
+    /// Street just dealt: its first decision, or the next round closed
+    /// already when a player is all-in (as in `build_tree`)
+    fn open_street(&mut self, config: &TreeConfig, state: BettingState, parent: NodeId) -> NodeId {
+        if state.is_all_in() {
+            self.close_round(config, state, parent)
+        } else {
+            self.decision(config, state, Some(parent))
+        }
+    }
+
+    fn expand(&mut self, config: &TreeConfig, id: NodeId) {
+        let Some(pending) = self.pending[id as usize].take() else {
+            return;
+        };
+        let children: Vec<NodeId> = match pending {
+            Pending::Decision(nexts) => nexts.into_iter().map(|next| match next {
+                Next::Decision(s) => self.decision(config, s, Some(id)),
+                Next::Fold(s, folder) => {
+                    let child = self.nodes.len() as NodeId;
+                    self.push(s.terminal_node(child, Some(id), Some(folder)), None)
+                }
+                Next::RoundClosed(s) => self.close_round(config, s, id),
+            }).collect(),
+            Pending::Chance(state) => {
+                let street = state.next_street().expect("chance nodes precede a street");
+                let (cards, weights) = state.runouts(config.isomorphic_runouts);
+                if let Node::Chance { weights: slot, .. } = &mut self.nodes[id as usize] {
+                    *slot = weights;
+                }
+                cards.into_iter().map(|card| self.open_street(config, state.deal(street, card), id)).collect()
+            }
+        };
+        match &mut self.nodes[id as usize] {
+            Node::Decision { children: slot, .. } | Node::Chance { children: slot, .. } => *slot = children,
+            Node::Terminal { .. } => unreachable!("terminals have nothing to expand"),
+        }
+    }
+}
+
+/// Game tree whose nodes are created on first visit
+pub struct LazyTree {
+    config: TreeConfig,
+    arena: RwLock<Arena>,
+}
+
+impl LazyTree {
+    /// A tree holding only the root of `config`.
+    ///
+    /// Panics if the config does not validate (like `build_tree`).
+    pub fn new(config: TreeConfig) -> Self {
+        if let Err(e) = config.validate() {
+            panic!("invalid tree config: {}", e);
+        }
+        let mut arena = Arena { nodes: Vec::new(), pending: Vec::new(), infosets: InfosetTable::new() };
+        arena.decision(&config, BettingState::root(&config), None);
+        LazyTree { config, arena: RwLock::new(arena) }
+    }
+
+    pub fn config(&self) -> &TreeConfig {
+        &self.config
+    }
+
+    /// Copy of node `id`; its `children()` are empty until it's expanded.
+    ///
+    /// Panics if `id` hasn't been created yet.
+    pub fn node(&self, id: NodeId) -> Node {
+        self.arena.read().expect("lazy tree lock poisoned").nodes[id as usize].clone()
+    }
+
+    /// Children of `id`, expanding the node first if needed
+    pub fn children(&self, id: NodeId) -> Vec<NodeId> {
+        {
+            let arena = self.arena.read().expect("lazy tree lock poisoned");
+            if arena.pending[id as usize].is_none() {
+                return arena.nodes[id as usize].children().to_vec();
+            }
+        }
+        let mut arena = self.arena.write().expect("lazy tree lock poisoned");
+        arena.expand(&self.config, id);
+        arena.nodes[id as usize].children().to_vec()
+    }
+
+    /// Whether `id`'s children exist (always true for terminals)
+    pub fn is_expanded(&self, id: NodeId) -> bool {
+        self.arena.read().expect("lazy tree lock poisoned").pending[id as usize].is_none()
+    }
+
+    /// Nodes created so far
+    pub fn len(&self) -> usize {
+        self.arena.read().expect("lazy tree lock poisoned").nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Infosets seen so far (every created decision node has one)
+    pub fn num_infosets(&self) -> usize {
+        self.arena.read().expect("lazy tree lock poisoned").infosets.len()
+    }
+
+    /// Action count per infoset seen so far, indexed by id
+    pub fn actions_per_infoset(&self) -> Vec<usize> {
+        self.arena.read().expect("lazy tree lock poisoned").infosets.actions_per_infoset().to_vec()
+    }
+
+    /// Expand every remaining node and return the full tree. Ids and infoset
+    /// ids of nodes that already existed are kept.
+    pub fn materialize(&self) -> GameTree {
+        let mut arena = self.arena.write().expect("lazy tree lock poisoned");
+        let mut id = 0;
+        // Expanding appends, so this also reaches nodes created along the way
+        while id < arena.nodes.len() {
+            arena.expand(&self.config, id as NodeId);
+            id += 1;
+        }
+        GameTree { nodes: arena.nodes.clone() }
+    }
+}
+
+/// CFR+ on a `LazyTree`, expanding branches as the traversal reaches them.
+///
+/// Chance nodes are traversed in order on one thread so node ids don't
+/// depend on scheduling. Terminal EVs come from `equity` (IP's showdown
+/// equity at a terminal, as in `build_terminal_evs`) and are cached.
+pub struct LazyCfrSolver {
+    pub tree: LazyTree,
+    pub storage: RegretStorage,
+    pub iteration: u64,
+    config: SolverConfig,
+    equity: Box<dyn Fn(&Node) -> f64 + Send + Sync>,
+    terminal_evs: HashMap<NodeId, f64>,
+    root_stacks: [f64; 2],
+    /// Draw one outcome per chance node instead of visiting all of them
+    sampling: Option<Rng>,
+}
+
+impl LazyCfrSolver {
+    pub fn new(tree: LazyTree, equity: impl Fn(&Node) -> f64 + Send + Sync + 'static, config: SolverConfig) -> Self {
+        let root_stacks = match tree.node(0) {
+            Node::Decision { stacks, .. } | Node::Chance { stacks, .. } | Node::Terminal { stacks, .. } => stacks,
+        };
+        let storage = match config.storage {
+            StorageMode::Dense => RegretStorage::new(&[]),
+            StorageMode::Lazy => RegretStorage::lazy(&[]),
+        };
+        LazyCfrSolver {
+            tree,
+            storage,
+            iteration: 0,
+            config,
+            equity: Box::new(equity),
+            terminal_evs: HashMap::new(),
+            root_stacks,
+            sampling: None,
+        }
+    }
+
+    /// Sample one chance outcome per visit (chance-sampled CFR+), seeded
+    /// from `config.seed`
+    pub fn with_chance_sampling(mut self) -> Self {
+        self.sampling = Some(Rng::new(self.config.seed));
+        self
+    }
+
+    pub fn config(&self) -> &SolverConfig {
+        &self.config
+    }
+
+    /// Run one CFR+ iteration (increments `self.iteration` before traversal)
+    pub fn run_iteration(&mut self) {
+        self.iteration += 1;
+        let t = self.iteration;
+        let prune = self.config.prune_threshold(t);
+        let mut updates = Vec::new();
+        self.traverse(0, 1.0, 1.0, t, prune, &mut updates);
+        self.storage.grow(&self.tree.actions_per_infoset());
+        self.storage.apply(&updates, self.config.algorithm, t);
+    }
+
+    /// Same traversal as `cfr::Traversal`. Infosets first reached during
+    /// this iteration have no storage yet and play uniformly.
+    fn traverse(
+        &mut self,
+        id: NodeId,
+        reach_ip: f64,
+        reach_oop: f64,
+        t: u64,
+        prune: Option<f64>,
+        updates: &mut Vec<RegretUpdate>,
+    ) -> f64 {
+        let node = self.tree.node(id);
+        match node {
+            Node::Terminal { .. } => {
+                if let Some(&ev) = self.terminal_evs.get(&id) {
+                    return ev;
+                }
+                let equity = if matches!(node, Node::Terminal { folder: None, .. }) { (self.equity)(&node) } else { 0.0 };
+                let ev = terminal_ip_ev(&node, self.root_stacks, equity);
+                self.terminal_evs.insert(id, ev);
+                ev
+            }
+
+            Node::Decision { infoset_id, player, actions, .. } => {
+                let infoset_id = infoset_id as usize;
+                let known = infoset_id < self.storage.num_infosets();
+                let strategy = if known {
+                    self.storage.iteration_strategy(infoset_id, self.config.algorithm)
+                } else {
+                    vec![1.0 / actions.len() as f64; actions.len()]
+                };
+                let last = if known { self.storage.last_regrets(infoset_id).to_vec() } else { vec![0.0; actions.len()] };
+                let pruned = |i: usize| prune.is_some_and(|threshold| strategy[i] == 0.0 && last[i] < -threshold);
+
+                let children = self.tree.children(id);
+                let mut child_evs = Vec::with_capacity(children.len());
+                for (i, &child) in children.iter().enumerate() {
+                    if pruned(i) {
+                        child_evs.push(None);
+                        continue;
+                    }
+                    let (ip, oop) = if player == Player::IP {
+                        (reach_ip * strategy[i], reach_oop)
+                    } else {
+                        (reach_ip, reach_oop * strategy[i])
+                    };
+                    child_evs.push(Some(self.traverse(child, ip, oop, t, prune, updates)));
+                }
+
+                let node_value: f64 = strategy.iter().zip(child_evs.iter())
+                    .map(|(&s, ev)| ev.map_or(0.0, |ev| s * ev)).sum();
+                let cf_values: Vec<f64> = child_evs.iter().enumerate().map(|(i, ev)| match *ev {
+                    None => last[i],
+                    Some(ev) if player == Player::IP => reach_oop * (ev - node_value),
+                    Some(ev) => reach_ip * (node_value - ev),
+                }).collect();
+
+                updates.push(RegretUpdate { infoset_id, cf_values, strategy, weight: self.config.algorithm.averaging_weight(t) });
+                node_value
+            }
+
+            Node::Chance { .. } => {
+                let children = self.tree.children(id);
+                let node = self.tree.node(id);
+                match &mut self.sampling {
+                    // The sampled child's value is an unbiased estimate of the expectation
+                    Some(rng) => {
+                        let child = sample_child(&node, rng);
+                        self.traverse(children[child], reach_ip, reach_oop, t, prune, updates)
+                    }
+                    None if matches!(&node, Node::Chance { weights, .. } if weights.is_empty()) => {
+                        let mut ev_sum = 0.0_f64;
+                        for &child in &children {
+                            ev_sum += self.traverse(child, reach_ip, reach_oop, t, prune, updates);
+                        }
+                        ev_sum / children.len() as f64
+                    }
+                    None => {
+                        let mut ev = 0.0_f64;
+                        for (i, &child) in children.iter().enumerate() {
+                            ev += node.chance_probability(i) * self.traverse(child, reach_ip, reach_oop, t, prune, updates);
+                        }
+                        ev
+                    }
+                }
+            }
+        }
+    }
+
+    /// Materialize the tree and continue as a `CfrSolver` with the regrets
+    /// and strategy sums accumulated so far (e.g. to measure exploitability)
+    pub fn into_solver(mut self) -> CfrSolver {
+        let tree = self.tree.materialize();
+        self.storage.grow(&self.tree.actions_per_infoset());
+        let equity = self.equity;
+        let evs = build_terminal_evs(&tree, |node| equity(node));
+        let mut solver = CfrSolver::with_config(tree, evs, self.config);
+        solver.storage = self.storage;
+        solver.iteration = self.iteration;
+        solver
+    }
+}
+
+/// Index of a child drawn with the Chance node's probabilities
+fn sample_child(node: &Node, rng: &mut Rng) -> usize {
+    match node {
+        Node::Chance { children, weights, .. } if weights.is_empty() => rng.below(children.len() as u64) as usize,
+        Node::Chance { weights, .. } => {
+            let mut u = rng.next_f64();
+            for (i, &w) in weights.iter().enumerate() {
+                if u < w {
+                    return i;
+                }
+                u -= w;
+            }
+            weights.len() - 1
+        }
+        _ => unreachable!("only Chance nodes are sampled"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes};
+    use crate::game::GameConfig;
+    use crate::infoset::InfosetKey;
+    use crate::node::parse_cards;
+
+    fn turn_config() -> TreeConfig {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 1;
+        config
+    }
+
+    /// Made-up showdown equity that depends on the river card
+    fn equity(node: &Node) -> f64 {
+        node.board().last().map_or(0.5, |c| f64::from(c.value()) / 51.0)
+    }
+
+    #[test]
+    fn test_materialized_tree_matches_built_tree() {
+        let lazy = LazyTree::new(turn_config());
+        assert_eq!(lazy.len(), 1);
+        assert!(!lazy.is_expanded(0));
+        let tree = lazy.materialize();
+        let built = build_tree(&turn_config());
+        assert_eq!(tree.stats(), built.stats());
+        assert_eq!(tree.num_infosets(), built.num_infosets());
+        // Turn all-ins deal the river straight to showdown in both
+        let shallow = TreeConfig { game: GameConfig::from_pot(5.5, [6.0, 6.0]), ..turn_config() };
+        assert_eq!(LazyTree::new(shallow.clone()).materialize().stats(), build_tree(&shallow).stats());
+        for (id, node) in tree.nodes.iter().enumerate() {
+            assert_eq!(node.id() as usize, id);
+            for &child in node.children() {
+                assert!(child as usize > id);
+                assert_eq!(tree.get(child).unwrap().parent(), Some(node.id()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact_lazy_solve_matches_cfr_solver() {
+        let config = SolverConfig::builder().max_iterations(10).build().unwrap();
+        let mut lazy = LazyCfrSolver::new(LazyTree::new(turn_config()), equity, config.clone());
+        let built = build_tree(&turn_config());
+        let evs = build_terminal_evs(&built, equity);
+        let mut eager = CfrSolver::with_config(built, evs, config);
+        for _ in 0..10 {
+            lazy.run_iteration();
+            eager.run_iteration();
+        }
+        let lazy = lazy.into_solver();
+        let table = InfosetTable::from_tree(&lazy.tree);
+        for node in eager.tree.nodes.iter().filter(|n| n.is_decision()) {
+            let theirs = table.get(&InfosetKey::of_node(node).unwrap()).unwrap() as usize;
+            let ours = node.infoset_id().unwrap() as usize;
+            assert_eq!(lazy.storage.average_strategy(theirs), eager.storage.average_strategy(ours));
+        }
+    }
+
+    #[test]
+    fn test_chance_sampling_expands_part_of_the_tree() {
+        let config = SolverConfig::builder().seed(3).build().unwrap();
+        let mut solver = LazyCfrSolver::new(LazyTree::new(turn_config()), equity, config).with_chance_sampling();
+        for _ in 0..5 {
+            solver.run_iteration();
+        }
+        let visited = solver.tree.len();
+        assert_eq!(solver.storage.num_infosets(), solver.tree.num_infosets());
+        let full = build_tree(&turn_config()).len();
+        assert!(visited < full / 4, "{} of {} nodes expanded", visited, full);
+        assert_eq!(solver.into_solver().tree.len(), full);
+    }
+
+    #[test]
+    fn test_lazy_storage_allocates_only_sampled_infosets() {
+        let config = SolverConfig::builder().seed(3).storage(StorageMode::Lazy).build().unwrap();
+        let mut solver = LazyCfrSolver::new(LazyTree::new(turn_config()), equity, config).with_chance_sampling();
+        for _ in 0..5 {
+            solver.run_iteration();
+        }
+        let touched = solver.storage.allocated_infosets();
+        let solver = solver.into_solver();
+        // The rest of the materialized tree reads as untouched until solved
+        assert!(solver.storage.is_lazy());
+        assert_eq!(solver.storage.allocated_infosets(), touched);
+        assert!(touched < solver.storage.num_infosets() / 4);
+        let unreached = (0..solver.storage.num_infosets()).find(|&i| solver.storage.regrets(i).iter().all(|&r| r == 0.0)).unwrap();
+        assert_eq!(solver.storage.average_strategy(unreached).len(), solver.storage.num_actions(unreached));
+    }
+}
@@ -0,0 +1,137 @@
+//! Values at the leaves of a depth-limited tree
+//!
+//! A tree built with `TreeConfig::depth_limit` stops after that street: a
+//! round that closes there ends in a leaf, a showdown terminal on a board of
+//! fewer than five cards, instead of a Chance node. The solver can't see the
+//! rest of the hand, so a `LeafEvaluator` turns the leaf's board, pot, and
+//! both players' reach into a value for each combo.
+//!
+//! `EquityLeaf` is the built-in estimate: the hand is checked down from the
+//! leaf, so each combo collects its pot share over every runout. That is
+//! exact after an all-in and ignores the betting still to come otherwise.
+//! A learned model (a value network over board and ranges, say)
+//! plugs in by implementing the trait and handing it to
+//! `VectorCfrSolver::with_leaf_evaluator`.
+
+use crate::equity::for_each_runout;
+use crate::evaluator::CactusKevEvaluator;
+use crate::node::Card;
+use crate::showdown::{showdown_weights, BoardRanks};
+
+/// Everything a leaf evaluator sees at one leaf, from `player`'s side
+#[derive(Debug, Clone, Copy)]
+pub struct Leaf<'a> {
+    /// Board at the leaf (3 or 4 cards in a depth-limited tree)
+    pub board: &'a [Card],
+    pub pot: f64,
+    /// Chips behind, `[IP, OOP]`
+    pub stacks: [f64; 2],
+    /// Player whose values are wanted (0 = IP, 1 = OOP)
+    pub player: usize,
+    /// `player`'s payoff for winning the pot at showdown
+    pub win: f64,
+    /// `player`'s payoff for losing it
+    pub lose: f64,
+    /// `player`'s payoff for a chop
+    pub tie: f64,
+    /// `player`'s combos
+    pub combos: &'a [[Card; 2]],
+    /// The opponent's combos
+    pub opp_combos: &'a [[Card; 2]],
+    /// Opponent reach per combo in `opp_combos`
+    pub opp_reach: &'a [f64],
+}
+
+/// Per-combo values at a depth-limited leaf
+pub trait LeafEvaluator: Send + Sync {
+    /// Counterfactual value of each of `leaf.player`'s combos: the expected
+    /// payoff against each compatible opponent combo, summed with the
+    /// opponent's reach as weight (not normalized), like every value the
+    /// vector solver passes up the tree
+    fn evaluate(&self, leaf: &Leaf<'_>) -> Vec<f64>;
+}
+
+/// One model shared by several solvers
+impl<L: LeafEvaluator + ?Sized> LeafEvaluator for std::sync::Arc<L> {
+    fn evaluate(&self, leaf: &Leaf<'_>) -> Vec<f64> {
+        (**self).evaluate(leaf)
+    }
+}
+
+/// Check-down equity: every runout to the river, pot share at showdown
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EquityLeaf {
+    evaluator: CactusKevEvaluator,
+}
+
+impl EquityLeaf {
+    pub fn new() -> Self {
+        EquityLeaf { evaluator: CactusKevEvaluator::new() }
+    }
+}
+
+impl LeafEvaluator for EquityLeaf {
+    fn evaluate(&self, leaf: &Leaf<'_>) -> Vec<f64> {
+        let mut values = vec![0.0; leaf.combos.len()];
+        for_each_runout(leaf.board, |board, _| {
+            let own = BoardRanks::new(&self.evaluator, *board, leaf.combos);
+            let opp = BoardRanks::new(&self.evaluator, *board, leaf.opp_combos);
+            let sums = showdown_weights(&own, &opp, leaf.opp_reach);
+            for (h, value) in values.iter_mut().enumerate() {
+                *value += leaf.win * sums.win[h] + leaf.lose * sums.lose[h] + leaf.tie * sums.tie[h];
+            }
+        });
+        // A compatible pair sees every runout that avoids its four cards
+        let runouts = runouts_per_pair(leaf.board.len());
+        values.iter_mut().for_each(|v| *v /= runouts);
+        values
+    }
+}
+
+/// C(48 - board, 5 - board): runouts of a board that miss both hands
+fn runouts_per_pair(board: usize) -> f64 {
+    let live = 48 - board;
+    (0..5 - board).fold(1.0, |n, k| n * (live - k) as f64 / (k + 1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equity::range_vs_range;
+    use crate::node::parse_cards;
+    use crate::range::Range;
+
+    #[test]
+    fn test_equity_leaf_is_pot_share_over_runouts() {
+        let board = parse_cards("AsKh7d2c").unwrap();
+        let hero = Range::parse("AK,77,QJs").unwrap();
+        let villain = Range::parse("AQ,KK,T9s").unwrap();
+        let combos: Vec<[Card; 2]> = hero.live_combos(&board).map(|(h, _)| h).collect();
+        let (opp_combos, opp_reach): (Vec<[Card; 2]>, Vec<f64>) = villain.live_combos(&board).unzip();
+        // Payoff 1 for a win, 0 for a loss, 1/2 for a chop: values are equity times compatible reach
+        let leaf = Leaf {
+            board: &board,
+            pot: 10.0,
+            stacks: [95.0, 95.0],
+            player: 0,
+            win: 1.0,
+            lose: 0.0,
+            tie: 0.5,
+            combos: &combos,
+            opp_combos: &opp_combos,
+            opp_reach: &opp_reach,
+        };
+        let values = EquityLeaf::new().evaluate(&leaf);
+        let expected = range_vs_range(&CactusKevEvaluator::new(), &hero, &villain, &board);
+        for combo in &expected.hero_combos {
+            let h = combos.iter().position(|&c| c == combo.hand).unwrap();
+            let compatible: f64 = opp_combos.iter().zip(&opp_reach)
+                .filter(|(o, _)| !o.iter().any(|c| combo.hand.contains(c)))
+                .map(|(_, w)| w)
+                .sum();
+            assert!((values[h] - combo.equity * compatible).abs() < 1e-9, "{:?}", combo.hand);
+        }
+        assert_eq!(runouts_per_pair(5), 1.0);
+        assert_eq!(runouts_per_pair(3), (45 * 44 / 2) as f64);
+    }
+}
@@ -8,11 +8,115 @@
 pub mod node;
 pub mod evaluator;
 pub mod cfr;
+pub mod ev_table;
 pub mod exploitability;
 pub mod test_tree;
+pub mod rng;
+pub mod deck;
+#[cfg(any(test, feature = "testutil"))]
+pub mod testutil;
+pub mod board_gen;
+pub mod combo;
+pub mod range;
+pub mod bunching;
+pub mod showdown;
+pub mod equity;
+pub mod grid;
+pub mod game;
+pub mod builder;
+pub mod lazy_tree;
+pub mod factored;
+pub mod infoset;
+pub mod isomorphism;
+pub mod pot;
+pub mod payoff;
+pub mod analysis;
+pub mod prune;
+pub mod size_merge;
+pub mod memory;
+pub mod solve;
+pub mod vector_cfr;
+pub mod leaf;
+pub mod simulator;
+pub mod bot;
+pub mod acpc;
+pub mod coach;
+pub mod review;
+pub mod runouts;
+pub mod sweep;
+pub mod presets;
+pub mod par;
+pub mod simd;
+pub mod summation;
+pub mod units;
+pub mod config;
+pub mod profile;
+pub mod fingerprint;
+pub mod live;
+pub mod solution;
+pub mod training;
+pub mod validation;
+pub mod verification;
+pub mod line;
+pub mod nodelock;
+pub mod population;
+pub mod study;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "serde")]
+pub mod file_format;
+#[cfg(feature = "serde")]
+pub mod distributed;
 
-pub use evaluator::{CactusKevEvaluator, benchmark_throughput};
-pub use node::HandEvaluator;
-pub use cfr::{CfrSolver, RegretStorage};
-pub use exploitability::{compute_exploitability, compute_exploitability_with_evs, ConvergenceMetrics};
+pub use evaluator::{CactusKevEvaluator, benchmark_throughput, benchmark_throughput_with_seed};
+pub use node::{parse_cards, Card, HandCategory, HandEvaluator, HandRank, TreeStats};
+pub use cfr::{CfrSolver, MissingTerminalEv, RegretStorage, Restart, StorageSnapshot};
+pub use ev_table::{EvTable, TerminalEvs};
+pub use exploitability::{compute_exploitability, compute_exploitability_with_evs, compute_exploitability_with_summation, street_exploitability, ConvergenceMetrics, StreetExploitability};
 pub use test_tree::{build_test_tree, build_test_tree_chance, terminal_ev_table_chance};
+pub use rng::Rng;
+pub use deck::{Deck, DeckIter};
+pub use board_gen::{BoardFilter, BoardFilterError, BoardSampler};
+pub use combo::{class_index, class_label, combo_cards, combo_index, NUM_CLASSES, NUM_COMBOS};
+pub use range::{Range, RangeParseError};
+pub use bunching::Bunching;
+pub use showdown::{showdown_weights, BoardRanks, ShowdownWeights};
+pub use equity::{expected_hand_strength, hand_strength, hand_vs_hand, hand_vs_range, range_vs_range, range_vs_range_sampled, ClassEquity, EquityResult, HandStrength};
+pub use grid::{CellKind, GridCell, RangeGrid};
+pub use game::{build_terminal_evs, terminal_ip_ev, BountyModel, GameConfig, GameConfigError, PostedBlind};
+pub use builder::{build_tree, BettingStructure, StreetSizes, TreeConfig, TreeConfigError};
+pub use infoset::{InfosetKey, InfosetTable};
+pub use isomorphism::{board_symmetries, runout_classes, SuitPermutation};
+pub use factored::{FactoredTree, SkeletonNode, Template, TemplateId};
+pub use lazy_tree::{LazyCfrSolver, LazyTree};
+pub use pot::{award_pots, side_pots, SidePot};
+pub use payoff::{audit_payoffs, Outcome, PayoffIssue, TerminalPayoff};
+pub use analysis::{action_removal_ev_loss, ev_line, node_values, strategy_mixing, ActionRemoval, LinePoint, MixingSummary, NodeMixing};
+pub use prune::{node_reach, prune_by_reach, PrunedSolution};
+pub use memory::{estimate_memory, fit_memory, parse_memory, MemoryDecision, MemoryError, MemoryEstimate, MemoryPlan};
+pub use size_merge::{find_size_merges, merge_ev_loss, merge_sizes, MergeTolerance, SizeMerge};
+pub use solve::{solve, solve_vector, solve_with_progress, SolveError, SolveResult, SolveSpec, VectorSolveResult};
+pub use leaf::{EquityLeaf, Leaf, LeafEvaluator};
+pub use bot::{locate, ActError, GameState, OffTree};
+pub use nodelock::{NodeLockError, NodeLocks};
+pub use population::{PopulationError, PopulationRow, PopulationStats};
+pub use coach::{grade_action, ActionGrade, CoachError, Grade};
+pub use review::{leak_report, review_hand, LineLeak, ReviewError, ReviewedDecision};
+pub use runouts::{runout_breakdown, CardClass, ClassSummary, RunoutError, RunoutReport, RunoutRow};
+pub use presets::Preset;
+pub use sweep::{bet_size_sweep, stack_sweep, SizePoint, SizeSweep, StackPoint, StackSweep};
+pub use simulator::{simulate, simulate_with, AllInScoring, DealSampler, Purified, SimulationResult, SolvedStrategy, Strategy, Uniform};
+pub use validation::{validate_evs, EvValidation, NodeEvCheck};
+pub use verification::{infoset_best_response, infoset_exploitability, kuhn_tree, leduc_tree, verify, verify_all, GoldenCheck, GoldenGame, VerificationReport};
+pub use vector_cfr::{ShowdownCacheStats, VectorCfrSolver, VectorStorage};
+pub use config::{
+    Algorithm, AlgorithmParseError, CheckpointPolicy, Precision, PruneConfig, SolverConfig, SolverConfigBuilder, SolverConfigError,
+    ResumeWeighting, SampledBr, Stagnation, StagnationTracker, StopCondition, StopReason, StorageMode, StreetTargets,
+};
+pub use profile::ProfileReport;
+pub use summation::{CompensatedSum, Summation};
+pub use units::{Unit, UnitParseError, Units};
+pub use training::{export_training_data, read_training_data, training_records, write_training_data, TrainingDataError, TrainingRecord};
+pub use live::{Budget, SolveHandle, StrategySnapshot};
+pub use solution::{Blend, InfosetLabel, Solution, SolutionError, SOLUTION_SCHEMA_VERSION};
+pub use fingerprint::{config_fingerprint, tree_fingerprint, Fingerprint, Fingerprinter};
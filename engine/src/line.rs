@@ -0,0 +1,347 @@
+//! Compact action tokens for naming lines through a tree
+//!
+//! One token per step: `f`, `x`, `c` for fold, check, call; `b75` for the
+//! bet closest to 75% of the pot and `r20` for the raise closest to a 20bb
+//! total; a card such as `Qs` for a runout dealt at a chance node. `oracle
+//! explore` reads these at its prompt and node-lock files use them for node
+//! paths, so both name a node the same way.
+//!
+//! A whole line is written street by street, the way GTO Wizard and
+//! PioSOLVER abbreviate it:
+//!
+//! ```text
+//! F:x-b33-c|T:7h|x-x
+//! ```
+//!
+//! Segments are separated by `|` and the tokens within one by `-`. A
+//! segment may start with the street it's played on (`F:`, `T:`, `R:`);
+//! `format_line` marks the root street's actions and every runout card, and
+//! `Line::parse` checks each marked street against the tree when following
+//! the line. The root is `root`. Tokens separated by spaces (`x b33 c 7h x
+//! x`) are the same line without street marks, and both forms are accepted
+//! wherever a line is read. `line_pattern` writes runout cards as `*`, which
+//! node-lock targets read as every card, so one pattern names a spot on
+//! every runout.
+
+use crate::node::{parse_cards, Action, Card, GameTree, Node, NodeId, Street};
+
+/// Shorthand for an action: `f`, `x`, `c`, `b<pct of pot>`, `r<raise-to>`
+pub fn action_token(action: &Action, pot: f64) -> String {
+    match action {
+        Action::Fold => "f".to_string(),
+        Action::Check => "x".to_string(),
+        Action::Call => "c".to_string(),
+        Action::Bet { size } => format!("b{}", (size / pot * 100.0).round()),
+        Action::Raise { size } => format!("r{}", format_amount(*size)),
+    }
+}
+
+/// Resolve a token against a node's actions.
+///
+/// `b` / `r` alone pick the smallest bet / raise; `b75` picks the bet closest
+/// to 75% of pot and `r20` the raise closest to a 20bb total; `allin` picks
+/// the largest bet or raise; a bare number is an action index.
+pub fn match_action(token: &str, actions: &[Action], pot: f64) -> Result<usize, String> {
+    let token = token.to_ascii_lowercase();
+    if let Ok(index) = token.parse::<usize>() {
+        return if index < actions.len() { Ok(index) } else { Err(format!("no action #{}", index)) };
+    }
+    let find = |pred: &dyn Fn(&Action) -> bool| actions.iter().position(pred);
+    let found = match token.as_str() {
+        "f" | "fold" => find(&|a| matches!(a, Action::Fold)),
+        "x" | "check" => find(&|a| matches!(a, Action::Check)),
+        "c" | "call" => find(&|a| matches!(a, Action::Call)),
+        "allin" | "a" => {
+            let mut best: Option<(usize, f64)> = None;
+            for (i, a) in actions.iter().enumerate() {
+                if let Action::Bet { size } | Action::Raise { size } = a {
+                    if best.is_none_or(|(_, s)| *size > s) {
+                        best = Some((i, *size));
+                    }
+                }
+            }
+            best.map(|(i, _)| i)
+        }
+        t if t.starts_with('b') || t.starts_with('r') => {
+            let is_bet = t.starts_with('b');
+            let target = match &t[1..] {
+                "" => None,
+                n => Some(n.parse::<f64>().map_err(|_| format!("invalid size in '{}'", t))?),
+            };
+            let mut best: Option<(usize, f64)> = None;
+            for (i, a) in actions.iter().enumerate() {
+                let value = match (a, is_bet) {
+                    (Action::Bet { size }, true) => size / pot * 100.0,
+                    (Action::Raise { size }, false) => *size,
+                    _ => continue,
+                };
+                let distance = target.map_or(value, |t| (value - t).abs());
+                if best.is_none_or(|(_, d)| distance < d) {
+                    best = Some((i, distance));
+                }
+            }
+            best.map(|(i, _)| i)
+        }
+        _ => return Err(format!("unknown action '{}'", token)),
+    };
+    found.ok_or_else(|| format!("'{}' is not available here", token))
+}
+
+/// Format a chip amount without trailing zeros
+pub fn format_amount(v: f64) -> String {
+    let s = format!("{:.2}", v);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// A runout card token
+pub fn parse_card(token: &str) -> Result<Card, String> {
+    match parse_cards(token).map_err(|e| e.to_string())?.as_slice() {
+        [card] => Ok(*card),
+        _ => Err(format!("expected one card, got '{}'", token)),
+    }
+}
+
+/// Follow `tokens` from the root: an action token at each decision, a card
+/// at each chance node
+pub fn find_node<'t>(tree: &GameTree, tokens: impl IntoIterator<Item = &'t str>) -> Result<NodeId, String> {
+    let mut id: NodeId = 0;
+    for token in tokens {
+        id = step(tree, id, token)?;
+    }
+    Ok(id)
+}
+
+/// The child of `id` that `token` names
+fn step(tree: &GameTree, id: NodeId, token: &str) -> Result<NodeId, String> {
+    Ok(match tree.get(id).ok_or("empty tree")? {
+        Node::Decision { actions, children, pot, .. } => children[match_action(token, actions, *pot)?],
+        Node::Chance { children, .. } => chance_child(tree, children, parse_card(token)?)
+            .ok_or_else(|| format!("{} cannot be dealt here", token))?,
+        Node::Terminal { .. } => return Err(format!("'{}' follows the end of the hand", token)),
+    })
+}
+
+/// A parsed line (see module docs): its tokens in order, and the street
+/// each `F:` / `T:` / `R:` mark names
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Line {
+    pub tokens: Vec<String>,
+    /// `(index of the first token in the marked segment, street)`
+    pub streets: Vec<(usize, Street)>,
+}
+
+impl Line {
+    /// Read a line in either form: `F:x-b33-c|T:7h|x-x` or `x b33 c 7h x x`
+    pub fn parse(text: &str) -> Result<Line, String> {
+        let text = text.trim();
+        let mut line = Line::default();
+        if text.is_empty() || text == "root" {
+            return Ok(line);
+        }
+        for segment in text.split('|') {
+            let body = match segment.split_once(':') {
+                Some((mark, body)) => {
+                    let street = street_mark(mark.trim()).ok_or_else(|| format!("unknown street '{}:' (expected F:, T:, or R:)", mark.trim()))?;
+                    if line.streets.last().is_some_and(|&(_, s)| s >= street) {
+                        return Err(format!("'{}' is out of street order", segment.trim()));
+                    }
+                    line.streets.push((line.tokens.len(), street));
+                    body
+                }
+                None => segment,
+            };
+            if body.trim().is_empty() {
+                return Err(format!("empty segment in '{}'", text));
+            }
+            for part in body.split('-') {
+                let tokens: Vec<&str> = part.split_whitespace().collect();
+                if tokens.is_empty() {
+                    return Err(format!("empty step in '{}'", segment.trim()));
+                }
+                line.tokens.extend(tokens.into_iter().map(str::to_string));
+            }
+        }
+        Ok(line)
+    }
+
+    /// Node the line reaches from the root
+    pub fn find(&self, tree: &GameTree) -> Result<NodeId, String> {
+        let mut id: NodeId = 0;
+        for (i, token) in self.tokens.iter().enumerate() {
+            id = step(tree, id, token)?;
+            self.check_street(tree, i, id)?;
+        }
+        Ok(id)
+    }
+
+    /// If token `index` starts a marked segment, check that `reached` (the
+    /// node it led to) is on the marked street
+    pub fn check_street(&self, tree: &GameTree, index: usize, reached: NodeId) -> Result<(), String> {
+        let Some(&(_, street)) = self.streets.iter().find(|(i, _)| *i == index) else { return Ok(()) };
+        let actual = tree.get(reached).map_or(street, |n| board_street(n.board().len()));
+        if actual != street {
+            return Err(format!("'{}' is on the {}, not the {}", self.tokens[index], street_word(actual), street_word(street)));
+        }
+        Ok(())
+    }
+}
+
+/// The line from the root to `id` in compact notation (see module docs)
+pub fn format_line(tree: &GameTree, id: NodeId) -> String {
+    write_line(tree, id, true)
+}
+
+/// `format_line` with every runout card written as `*`
+pub fn line_pattern(tree: &GameTree, id: NodeId) -> String {
+    write_line(tree, id, false)
+}
+
+fn write_line(tree: &GameTree, id: NodeId, cards: bool) -> String {
+    // (token, street of the card dealt, if a runout)
+    let mut steps: Vec<(String, Option<Street>)> = Vec::new();
+    let mut child = id;
+    while let Some(parent) = tree.get(child).and_then(Node::parent) {
+        let dealt = tree.get(child).expect("path holds valid node ids").board();
+        steps.push(match tree.get(parent).expect("parents are valid node ids") {
+            Node::Decision { actions, children, pot, .. } => {
+                let a = children.iter().position(|&c| c == child).expect("child of its parent");
+                (action_token(&actions[a], *pot), None)
+            }
+            _ => {
+                let card = dealt.last().filter(|_| cards).map_or("*".to_string(), |c| c.to_string());
+                (card, Some(board_street(dealt.len())))
+            }
+        });
+        child = parent;
+    }
+    if steps.is_empty() {
+        return "root".to_string();
+    }
+    steps.reverse();
+
+    let mut segments = Vec::new();
+    let mut actions: Vec<String> = Vec::new();
+    let mut mark = tree.get(0).map(|root| board_street(root.board().len()));
+    for (token, dealt) in steps {
+        let Some(street) = dealt else {
+            actions.push(token);
+            continue;
+        };
+        if !actions.is_empty() {
+            segments.push(marked(mark.take(), &actions.join("-")));
+            actions.clear();
+        }
+        mark = None;
+        segments.push(marked(Some(street), &token));
+    }
+    if !actions.is_empty() {
+        segments.push(marked(mark, &actions.join("-")));
+    }
+    segments.join("|")
+}
+
+fn marked(street: Option<Street>, body: &str) -> String {
+    match street {
+        Some(Street::Flop) => format!("F:{}", body),
+        Some(Street::Turn) => format!("T:{}", body),
+        Some(Street::River) => format!("R:{}", body),
+        None => body.to_string(),
+    }
+}
+
+fn street_mark(mark: &str) -> Option<Street> {
+    match mark.to_ascii_uppercase().as_str() {
+        "F" => Some(Street::Flop),
+        "T" => Some(Street::Turn),
+        "R" => Some(Street::River),
+        _ => None,
+    }
+}
+
+fn street_word(street: Street) -> &'static str {
+    match street {
+        Street::Flop => "flop",
+        Street::Turn => "turn",
+        Street::River => "river",
+    }
+}
+
+/// Street a board of `len` cards is on
+fn board_street(len: usize) -> Street {
+    match len {
+        0..=3 => Street::Flop,
+        4 => Street::Turn,
+        _ => Street::River,
+    }
+}
+
+/// The child of a chance node that deals `card`
+pub fn chance_child(tree: &GameTree, children: &[NodeId], card: Card) -> Option<NodeId> {
+    children.iter().copied().find(|&c| tree.get(c).and_then(|n| n.board().last().copied()) == Some(card))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::game::GameConfig;
+
+    #[test]
+    fn test_match_action_tokens() {
+        let actions = [Action::Check, Action::Bet { size: 2.75 }, Action::Bet { size: 8.25 }];
+        assert_eq!(match_action("x", &actions, 5.5), Ok(0));
+        assert_eq!(match_action("b", &actions, 5.5), Ok(1));
+        assert_eq!(match_action("b50", &actions, 5.5), Ok(1));
+        assert_eq!(match_action("b140", &actions, 5.5), Ok(2));
+        assert_eq!(match_action("allin", &actions, 5.5), Ok(2));
+        assert_eq!(match_action("2", &actions, 5.5), Ok(2));
+        assert!(match_action("c", &actions, 5.5).is_err());
+        assert!(match_action("bx", &actions, 5.5).is_err());
+        let facing = [Action::Fold, Action::Call, Action::Raise { size: 13.75 }];
+        assert_eq!(match_action("r14", &facing, 11.0), Ok(2));
+        assert_eq!(action_token(&facing[2], 11.0), "r13.75");
+        assert_eq!(action_token(&actions[1], 5.5), "b50");
+    }
+
+    #[test]
+    fn test_find_node_follows_actions_and_cards() {
+        let board = parse_cards("AsKh7d").unwrap();
+        let mut config = TreeConfig::new(GameConfig::new(), board);
+        config.flop = StreetSizes::new(vec![0.5]);
+        config.max_raises = 0;
+        let tree = build_tree(&config);
+        let checked = find_node(&tree, ["x", "x"]).unwrap();
+        assert!(tree.get(checked).unwrap().is_chance());
+        let turn = find_node(&tree, ["x", "x", "2c"]).unwrap();
+        assert_eq!(tree.get(turn).unwrap().board().last(), Some(&parse_cards("2c").unwrap()[0]));
+        assert!(find_node(&tree, ["x", "x", "As"]).is_err());
+        assert!(find_node(&tree, ["x", "b", "f", "x"]).is_err());
+        assert_eq!(find_node(&tree, []), Ok(0));
+    }
+
+    #[test]
+    fn test_line_notation_round_trips() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d").unwrap());
+        config.flop = StreetSizes::new(vec![0.33]);
+        config.max_raises = 1;
+        let tree = build_tree(&config);
+        let node = find_node(&tree, ["x", "b33", "c", "7h", "x", "x"]).unwrap();
+        assert_eq!(format_line(&tree, node), "F:x-b33-c|T:7h|x-x");
+        assert_eq!(line_pattern(&tree, node), "F:x-b33-c|T:*|x-x");
+        assert_eq!(format_line(&tree, 0), "root");
+
+        let line = Line::parse("F:x-b33-c|T:7h|x-x").unwrap();
+        assert_eq!(line.tokens, ["x", "b33", "c", "7h", "x", "x"]);
+        assert_eq!(line.streets, vec![(0, Street::Flop), (3, Street::Turn)]);
+        assert_eq!(line.find(&tree), Ok(node));
+        assert_eq!(Line::parse("x b33 c 7h x x").unwrap().find(&tree), Ok(node));
+        assert_eq!(Line::parse("root").unwrap().find(&tree), Ok(0));
+
+        // Marks are checked against the tree and must run flop, turn, river
+        assert!(Line::parse("T:x-b33-c").unwrap().find(&tree).unwrap_err().contains("on the flop, not the turn"));
+        assert!(Line::parse("T:x|F:x").is_err());
+        assert!(Line::parse("Q:x").is_err());
+        assert!(Line::parse("F:x--c").is_err());
+        assert!(Line::parse("F:x|").is_err());
+    }
+}
@@ -0,0 +1,304 @@
+//! Solving in the background with live strategy snapshots
+//!
+//! `SolveHandle::spawn` moves a `CfrSolver` onto its own thread and keeps
+//! iterating while the caller (a GUI, say) reads the evolving average
+//! strategy. Readers never touch the solver's storage: every
+//! `publish_every` iterations, between iterations, the solver thread copies
+//! the average strategy of every infoset into a fresh `StrategySnapshot`
+//! and swaps it in behind an `Arc`. `snapshot` clones that `Arc`, so a
+//! reader gets a consistent view of a single iteration for as long as it
+//! holds it, and the solver never waits on readers beyond the pointer swap.
+//!
+//! The thread works through an iteration budget, starting with
+//! `config().stop.max_iterations`. `add_budget` grants more iterations or
+//! more solving time; `pause` and `resume` hold and release it without
+//! spending budget. Out of budget or paused, the thread waits rather than
+//! exits, so interactive tools can keep one solver warm and run it
+//! opportunistically (e.g. while the user is idle). `stop` ends the thread
+//! and returns the solver.
+//!
+//! Each iteration is charged to the iteration budget while any remains and
+//! to the time budget after that, by its measured duration; time spent
+//! paused or idle is never charged.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use crate::cfr::{CfrSolver, RegretStorage};
+
+/// Average strategy of every infoset as of one iteration
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategySnapshot {
+    /// Iteration the snapshot was taken after
+    pub iteration: u64,
+    /// strategies[infoset_id][action_idx]
+    pub strategies: Vec<Vec<f64>>,
+}
+
+impl StrategySnapshot {
+    /// Copy the average strategy out of `storage`
+    pub fn capture(storage: &RegretStorage, iteration: u64) -> Self {
+        StrategySnapshot {
+            iteration,
+            strategies: (0..storage.num_infosets()).map(|i| storage.average_strategy(i)).collect(),
+        }
+    }
+
+    /// Average strategy at `infoset_id`
+    pub fn strategy(&self, infoset_id: usize) -> &[f64] {
+        &self.strategies[infoset_id]
+    }
+}
+
+/// More work for a `SolveHandle`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Budget {
+    Iterations(u64),
+    Time(Duration),
+}
+
+/// What the solver thread may do next
+#[derive(Debug)]
+struct Control {
+    iterations_left: u64,
+    time_left: Duration,
+    paused: bool,
+    stop: bool,
+    /// The thread is waiting (paused or out of budget)
+    idle: bool,
+}
+
+impl Control {
+    fn can_run(&self) -> bool {
+        !self.paused && (self.iterations_left > 0 || !self.time_left.is_zero())
+    }
+
+    fn charge(&mut self, took: Duration) {
+        if self.iterations_left > 0 {
+            self.iterations_left -= 1;
+        } else {
+            self.time_left = self.time_left.saturating_sub(took);
+        }
+    }
+}
+
+/// State shared between a `SolveHandle` and its solver thread
+struct Shared {
+    latest: Mutex<Arc<StrategySnapshot>>,
+    iteration: AtomicU64,
+    control: Mutex<Control>,
+    /// Signalled whenever `control` changes
+    changed: Condvar,
+}
+
+impl Shared {
+    fn publish(&self, solver: &CfrSolver) {
+        let snapshot = Arc::new(StrategySnapshot::capture(&solver.storage, solver.iteration));
+        *self.latest.lock().expect("snapshot lock poisoned") = snapshot;
+    }
+
+    fn control(&self) -> MutexGuard<'_, Control> {
+        self.control.lock().expect("control lock poisoned")
+    }
+
+    /// Block until the thread may iterate, publishing a snapshot before
+    /// going idle; false once stopped
+    fn wait_for_work(&self, solver: &CfrSolver) -> bool {
+        let mut control = self.control();
+        while !control.stop && !control.can_run() {
+            if !control.idle {
+                drop(control);
+                self.publish(solver);
+                control = self.control();
+                if control.stop || control.can_run() {
+                    break;
+                }
+                control.idle = true;
+                self.changed.notify_all();
+            }
+            control = self.changed.wait(control).expect("control lock poisoned");
+        }
+        control.idle = false;
+        !control.stop
+    }
+
+    /// Apply a change from the handle and wake the thread
+    fn update(&self, change: impl FnOnce(&mut Control)) {
+        let mut control = self.control();
+        change(&mut control);
+        // Work is pending again, so `wait_idle` shouldn't return until it's done
+        if control.can_run() {
+            control.idle = false;
+        }
+        drop(control);
+        self.changed.notify_all();
+    }
+}
+
+/// A solver iterating on a background thread (see module docs)
+pub struct SolveHandle {
+    shared: Arc<Shared>,
+    thread: JoinHandle<CfrSolver>,
+}
+
+impl SolveHandle {
+    /// Start iterating `solver` on a new thread, publishing a snapshot every
+    /// `publish_every` iterations (at least 1) and whenever it goes idle
+    pub fn spawn(mut solver: CfrSolver, publish_every: u64) -> Self {
+        let publish_every = publish_every.max(1);
+        let shared = Arc::new(Shared {
+            latest: Mutex::new(Arc::new(StrategySnapshot::capture(&solver.storage, solver.iteration))),
+            iteration: AtomicU64::new(solver.iteration),
+            control: Mutex::new(Control {
+                iterations_left: solver.config().stop.max_iterations,
+                time_left: Duration::ZERO,
+                paused: false,
+                stop: false,
+                idle: false,
+            }),
+            changed: Condvar::new(),
+        });
+        let worker = Arc::clone(&shared);
+        let thread = std::thread::spawn(move || {
+            while worker.wait_for_work(&solver) {
+                let start = Instant::now();
+                solver.run_iteration();
+                worker.control().charge(start.elapsed());
+                worker.iteration.store(solver.iteration, Ordering::Relaxed);
+                if solver.iteration.is_multiple_of(publish_every) {
+                    worker.publish(&solver);
+                }
+            }
+            worker.publish(&solver);
+            solver
+        });
+        SolveHandle { shared, thread }
+    }
+
+    /// The most recently published strategy snapshot
+    pub fn snapshot(&self) -> Arc<StrategySnapshot> {
+        Arc::clone(&self.shared.latest.lock().expect("snapshot lock poisoned"))
+    }
+
+    /// Iterations completed so far (may be ahead of the latest snapshot)
+    pub fn iteration(&self) -> u64 {
+        self.shared.iteration.load(Ordering::Relaxed)
+    }
+
+    /// Hold the solver after the current iteration, keeping its budget
+    pub fn pause(&self) {
+        self.shared.update(|c| c.paused = true);
+    }
+
+    /// Let a paused solver continue with whatever budget it has
+    pub fn resume(&self) {
+        self.shared.update(|c| c.paused = false);
+    }
+
+    /// Grant more iterations or more solving time
+    pub fn add_budget(&self, budget: Budget) {
+        self.shared.update(|c| match budget {
+            Budget::Iterations(n) => c.iterations_left = c.iterations_left.saturating_add(n),
+            Budget::Time(d) => c.time_left = c.time_left.saturating_add(d),
+        });
+    }
+
+    /// Iterations and time left in the budget
+    pub fn budget_left(&self) -> (u64, Duration) {
+        let control = self.shared.control();
+        (control.iterations_left, control.time_left)
+    }
+
+    /// The thread is waiting, paused or out of budget
+    pub fn is_idle(&self) -> bool {
+        self.shared.control().idle
+    }
+
+    /// Block until the thread is paused or out of budget; its latest state
+    /// is published by then
+    pub fn wait_idle(&self) {
+        let mut control = self.shared.control();
+        while !control.idle {
+            control = self.shared.changed.wait(control).expect("control lock poisoned");
+        }
+    }
+
+    /// Stop after the current iteration and hand the solver back
+    pub fn stop(self) -> CfrSolver {
+        self.shared.update(|c| c.stop = true);
+        self.thread.join().expect("solver thread panicked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{SolverConfig, StopCondition};
+    use crate::test_tree::{build_test_tree, terminal_ev_table};
+
+    fn solver(iterations: u64) -> CfrSolver {
+        let config = SolverConfig::builder().stop(StopCondition::iterations(iterations)).build().unwrap();
+        CfrSolver::with_config(build_test_tree(), terminal_ev_table(), config)
+    }
+
+    #[test]
+    fn test_snapshots_track_running_solver() {
+        let handle = SolveHandle::spawn(solver(2_000), 100);
+        let early = handle.snapshot();
+        assert_eq!(early.strategies.len(), 4);
+        handle.wait_idle();
+        let last = handle.snapshot();
+        let solver = handle.stop();
+        assert_eq!(solver.iteration, 2_000);
+        // The final snapshot matches the solver, and the solver matches a foreground run
+        assert_eq!(*last, StrategySnapshot::capture(&solver.storage, 2_000));
+        let mut reference = CfrSolver::new(build_test_tree());
+        for _ in 0..2_000 {
+            reference.run_iteration();
+        }
+        assert_eq!(last.strategy(0), reference.storage.average_strategy(0).as_slice());
+        // A snapshot held by a reader is a complete strategy from one iteration
+        assert!(early.iteration <= 2_000);
+        assert!(early.strategies.iter().all(|s| (s.iter().sum::<f64>() - 1.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_pause_resume_and_budget() {
+        let handle = SolveHandle::spawn(solver(u64::MAX), 1);
+        while handle.iteration() < 10 {
+            std::thread::yield_now();
+        }
+        handle.pause();
+        handle.wait_idle();
+        let paused_at = handle.iteration();
+        assert!(paused_at >= 10 && handle.is_idle());
+        // Pausing keeps the budget and publishes the latest state
+        assert_eq!(handle.snapshot().iteration, paused_at);
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(handle.iteration(), paused_at);
+        assert_eq!(handle.stop().iteration, paused_at);
+
+        // Out of budget, the thread waits for more instead of exiting
+        let handle = SolveHandle::spawn(solver(10), 1);
+        handle.wait_idle();
+        assert_eq!((handle.iteration(), handle.budget_left()), (10, (0, Duration::ZERO)));
+        handle.add_budget(Budget::Iterations(5));
+        handle.wait_idle();
+        assert_eq!(handle.iteration(), 15);
+        handle.add_budget(Budget::Time(Duration::from_millis(20)));
+        handle.wait_idle();
+        assert!(handle.iteration() > 15);
+        assert_eq!(handle.budget_left(), (0, Duration::ZERO));
+
+        // A paused solver doesn't spend budget it's given
+        handle.pause();
+        handle.add_budget(Budget::Iterations(3));
+        handle.wait_idle();
+        let before = handle.iteration();
+        assert_eq!(handle.budget_left().0, 3);
+        handle.resume();
+        handle.wait_idle();
+        assert_eq!(handle.stop().iteration, before + 3);
+    }
+}
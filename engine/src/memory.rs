@@ -0,0 +1,348 @@
+//! Memory budgets: a solve's footprint estimated before its tree is built
+//!
+//! A flop tree with every runout can need more memory than the machine has,
+//! and a solve that runs out is killed hours in. `estimate_memory` walks a
+//! `TreeConfig`'s betting without building the tree: each street's actions
+//! are followed for the first card a Chance node deals and counted once per
+//! card it deals (with isomorphic runouts the classes differ from card to
+//! card, so the count is approximate). Bytes come from the size of `Node`
+//! and its vectors, the three `f64` arrays per infoset that `RegretStorage`
+//! keeps, the traversal's `UpdateBuffer` (offsets, stamps, and a parallel
+//! flag per node, two `f64` slots per action, and a merge-order entry per
+//! decision), and the terminal EVs twice over: the map `solve` returns and
+//! the solver's dense `EvTable` copy. Allocator slack and `Vec` growth
+//! aren't counted.
+//!
+//! `fit_memory` holds an estimate to a budget (`parse_memory` reads `8G`,
+//! `512M`, ...). A tree over budget switches to isomorphic runouts (each
+//! suit-isomorphic class of cards solved once, see `builder::runouts`) when
+//! that fits and the caller says the ranges are suit-symmetric
+//! (`isomorphism::range_is_symmetric`); grouped runouts are valued on one
+//! board per class, so with asymmetric ranges they'd give a different
+//! answer. Anything else is refused.
+//!
+//! Falling back to `f32`, quantized, or bucketed storage is out of scope:
+//! storage is `f64` only (`Precision::F32` is reserved and fails
+//! validation), so isomorphic runouts are the only fallback.
+
+use std::mem::size_of;
+use crate::builder::{legal_actions, BettingState, Next, TreeConfig};
+use crate::cfr::UpdateBuffer;
+use crate::node::{Action, Card, Node, NodeId};
+
+/// Estimated memory of a scalar solve (see module docs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryEstimate {
+    pub nodes: u64,
+    pub infosets: u64,
+    /// Nodes and their vectors
+    pub tree_bytes: u64,
+    /// Regrets, strategy sums, and last regrets
+    pub storage_bytes: u64,
+    /// Traversal update buffer (`cfr::UpdateBuffer`)
+    pub buffer_bytes: u64,
+    /// Terminal EV map
+    pub ev_bytes: u64,
+    /// The solver's dense copy of the terminal EVs (`EvTable`)
+    pub ev_table_bytes: u64,
+}
+
+impl MemoryEstimate {
+    pub fn total(&self) -> u64 {
+        self.tree_bytes + self.storage_bytes + self.buffer_bytes + self.ev_bytes + self.ev_table_bytes
+    }
+}
+
+/// How `fit_memory` made a solve fit its budget
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemoryPlan {
+    /// The tree fits as configured
+    AsConfigured,
+    /// Isomorphic runouts were switched on; `full` is the estimate without
+    IsomorphicRunouts { full: MemoryEstimate },
+}
+
+/// A budget check that passed
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryDecision {
+    pub budget: u64,
+    /// Estimate of the tree that will be built
+    pub estimate: MemoryEstimate,
+    pub plan: MemoryPlan,
+}
+
+impl std::fmt::Display for MemoryDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (needed, budget) = (format_bytes(self.estimate.total()), format_bytes(self.budget));
+        match &self.plan {
+            MemoryPlan::AsConfigured => write!(f, "~{} of {} ({} nodes)", needed, budget, self.estimate.nodes),
+            MemoryPlan::IsomorphicRunouts { full } => write!(
+                f,
+                "~{} is over {}; isomorphic runouts bring it to ~{} ({} nodes)",
+                format_bytes(full.total()), budget, needed, self.estimate.nodes
+            ),
+        }
+    }
+}
+
+/// A solve that doesn't fit its budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryError {
+    pub budget: u64,
+    /// Smallest estimate tried
+    pub estimate: MemoryEstimate,
+    /// Whether `estimate` has isomorphic runouts; `false` when asymmetric
+    /// ranges ruled them out
+    pub isomorphic_runouts: bool,
+}
+
+impl std::fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (needed, budget) = (format_bytes(self.estimate.total()), format_bytes(self.budget));
+        if self.isomorphic_runouts {
+            write!(f, "solve needs ~{} ({} nodes) even with isomorphic runouts, over the {} budget", needed, self.estimate.nodes, budget)
+        } else {
+            write!(
+                f,
+                "solve needs ~{} ({} nodes), over the {} budget; the ranges aren't suit-symmetric, so isomorphic runouts can't shrink it",
+                needed, self.estimate.nodes, budget
+            )
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+/// Error returned when a memory size can't be read
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryParseError(pub String);
+
+impl std::fmt::Display for MemoryParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid memory size '{}' (expected e.g. 8G, 512M, or a byte count)", self.0)
+    }
+}
+
+impl std::error::Error for MemoryParseError {}
+
+/// Bytes in a size like `8G`, `1.5GiB`, `512m`, or `1048576`; suffixes are
+/// binary (K = 1024)
+pub fn parse_memory(s: &str) -> Result<u64, MemoryParseError> {
+    let error = || MemoryParseError(s.to_string());
+    let lower = s.trim().to_ascii_lowercase();
+    let unit = lower.trim_end_matches("ib").trim_end_matches('b');
+    let (number, shift) = match unit.char_indices().last() {
+        Some((i, 'k')) => (&unit[..i], 10),
+        Some((i, 'm')) => (&unit[..i], 20),
+        Some((i, 'g')) => (&unit[..i], 30),
+        Some((i, 't')) => (&unit[..i], 40),
+        _ => (unit, 0),
+    };
+    let value: f64 = number.trim().parse().map_err(|_| error())?;
+    if !value.is_finite() || value <= 0.0 {
+        return Err(error());
+    }
+    Ok((value * (1u64 << shift) as f64) as u64)
+}
+
+/// `1.50 GiB`-style size
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.2} {}", value, UNITS[unit]),
+    }
+}
+
+/// Memory `build_tree(config)` and a scalar solve of it would use (see
+/// module docs).
+///
+/// Panics if the config does not validate (like `build_tree`).
+pub fn estimate_memory(config: &TreeConfig) -> MemoryEstimate {
+    if let Err(e) = config.validate() {
+        panic!("invalid tree config: {}", e);
+    }
+    let mut walk = Walk { config, estimate: MemoryEstimate::default(), terminals: 0, actions: 0 };
+    walk.decision(&BettingState::root(config), 1);
+    let mut estimate = walk.estimate;
+    // Three arrays per infoset: regrets, strategy sums, last regrets
+    estimate.storage_bytes = 3 * (estimate.infosets * size_of::<Vec<f64>>() as u64 + walk.actions * size_of::<f64>() as u64);
+    estimate.buffer_bytes = UpdateBuffer::estimate_bytes(estimate.nodes, estimate.infosets, walk.actions);
+    // Hash map entries at a 7/8 load factor, plus a control byte each
+    estimate.ev_bytes = walk.terminals * (size_of::<(NodeId, f64)>() as u64 + 1) * 8 / 7;
+    // One slot per node up to the last terminal
+    estimate.ev_table_bytes = estimate.nodes * size_of::<f64>() as u64;
+    estimate
+}
+
+/// Check `config`'s estimate against `budget` bytes, switching on
+/// isomorphic runouts if that's what fits and the solve's ranges (and any
+/// bunching) are `symmetric` on the board (see module docs)
+pub fn fit_memory(config: &mut TreeConfig, budget: u64, symmetric: bool) -> Result<MemoryDecision, MemoryError> {
+    let estimate = estimate_memory(config);
+    if estimate.total() <= budget {
+        return Ok(MemoryDecision { budget, estimate, plan: MemoryPlan::AsConfigured });
+    }
+    if config.isomorphic_runouts || !symmetric {
+        return Err(MemoryError { budget, estimate, isomorphic_runouts: config.isomorphic_runouts });
+    }
+    let mut grouped = config.clone();
+    grouped.isomorphic_runouts = true;
+    let reduced = estimate_memory(&grouped);
+    if reduced.total() > budget {
+        return Err(MemoryError { budget, estimate: reduced, isomorphic_runouts: true });
+    }
+    config.isomorphic_runouts = true;
+    Ok(MemoryDecision { budget, estimate: reduced, plan: MemoryPlan::IsomorphicRunouts { full: estimate } })
+}
+
+/// Counting pass over the betting, mirroring `build_tree`
+struct Walk<'a> {
+    config: &'a TreeConfig,
+    estimate: MemoryEstimate,
+    terminals: u64,
+    /// Actions summed over infosets
+    actions: u64,
+}
+
+impl Walk<'_> {
+    /// Count `node` (with `children` child links) `copies` times
+    fn count(&mut self, node: &Node, children: usize, copies: u64) {
+        let heap = match node {
+            Node::Decision { actions, board, bet_sequence, .. } => {
+                (actions.len() + bet_sequence.len()) * size_of::<Action>() + board.len() * size_of::<Card>()
+            }
+            Node::Chance { weights, board, .. } => weights.len() * size_of::<f64>() + board.len() * size_of::<Card>(),
+            Node::Terminal { board, .. } => board.len() * size_of::<Card>(),
+        };
+        let bytes = size_of::<Node>() + children * size_of::<NodeId>() + heap;
+        self.estimate.nodes += copies;
+        self.estimate.tree_bytes += bytes as u64 * copies;
+    }
+
+    fn decision(&mut self, state: &BettingState, copies: u64) {
+        let (actions, nexts): (Vec<_>, Vec<_>) = legal_actions(self.config, state).into_iter().unzip();
+        let n = actions.len();
+        self.count(&state.decision_node(0, 0, None, Vec::new(), actions), n, copies);
+        self.estimate.infosets += copies;
+        self.actions += n as u64 * copies;
+        for next in nexts {
+            match next {
+                Next::Decision(s) => self.decision(&s, copies),
+                Next::Fold(s, folder) => self.terminal(&s.terminal_node(0, None, Some(folder)), copies),
+                Next::RoundClosed(s) => self.close_round(&s, copies),
+            }
+        }
+    }
+
+    fn terminal(&mut self, node: &Node, copies: u64) {
+        self.count(node, 0, copies);
+        self.terminals += copies;
+    }
+
+    /// Showdown (or depth-limit leaf), or a Chance node and the next street
+    /// once per card it deals
+    fn close_round(&mut self, state: &BettingState, copies: u64) {
+        let Some(street) = state.next_street().filter(|&s| self.config.deals(s)) else {
+            return self.terminal(&state.terminal_node(0, None, None), copies);
+        };
+        let (cards, weights) = state.runouts(self.config.isomorphic_runouts);
+        self.count(&state.chance_node(0, 0, Vec::new(), weights), cards.len(), copies);
+        let dealt = state.deal(street, cards[0]);
+        let copies = copies * cards.len() as u64;
+        if dealt.is_all_in() {
+            self.close_round(&dealt, copies);
+        } else {
+            self.decision(&dealt, copies);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes};
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+
+    fn turn_config() -> TreeConfig {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKs7d2s").unwrap());
+        config.turn = StreetSizes::new(vec![0.5, 1.0]);
+        config.river = StreetSizes::new(vec![0.75]);
+        config
+    }
+
+    #[test]
+    fn test_estimate_counts_the_built_tree() {
+        for isomorphic in [false, true] {
+            let mut config = turn_config();
+            config.isomorphic_runouts = isomorphic;
+            let tree = build_tree(&config);
+            let estimate = estimate_memory(&config);
+            // Without grouping every card deals the same betting, so the count is exact
+            if !isomorphic {
+                assert_eq!(estimate.nodes, tree.len() as u64);
+                assert_eq!(estimate.infosets, tree.num_infosets() as u64);
+            }
+            let nodes = tree.len() as f64;
+            assert!((estimate.nodes as f64 - nodes).abs() < 0.1 * nodes, "{} vs {}", estimate.nodes, nodes);
+            assert!(estimate.tree_bytes >= tree.len() as u64 * size_of::<Node>() as u64);
+            assert!(estimate.storage_bytes > 0 && estimate.ev_bytes > 0);
+            assert!(estimate.ev_table_bytes >= tree.len() as u64 * size_of::<f64>() as u64 * 9 / 10);
+        }
+    }
+
+    #[test]
+    fn test_estimate_counts_the_update_buffer() {
+        let config = turn_config();
+        let tree = build_tree(&config);
+        let estimate = estimate_memory(&config);
+        assert_eq!(estimate.buffer_bytes, UpdateBuffer::new(&tree).heap_bytes());
+        // Offsets, stamps, and flags per node; two slots per action
+        assert!(estimate.buffer_bytes > estimate.nodes * 17);
+        assert_eq!(estimate.total(), estimate.tree_bytes + estimate.storage_bytes + estimate.buffer_bytes + estimate.ev_bytes + estimate.ev_table_bytes);
+    }
+
+    #[test]
+    fn test_fit_memory_switches_to_isomorphic_runouts_or_refuses() {
+        let mut config = turn_config();
+        let full = estimate_memory(&config);
+        let decision = fit_memory(&mut config, full.total(), true).unwrap();
+        assert_eq!(decision.plan, MemoryPlan::AsConfigured);
+        assert!(!config.isomorphic_runouts);
+
+        let budget = full.total() - 1;
+        let err = fit_memory(&mut config, budget, false).unwrap_err();
+        assert_eq!((err.estimate, err.isomorphic_runouts), (full, false));
+        assert!(!config.isomorphic_runouts);
+        assert!(err.to_string().contains("aren't suit-symmetric"), "{}", err);
+        let decision = fit_memory(&mut config, budget, true).unwrap();
+        assert_eq!(decision.plan, MemoryPlan::IsomorphicRunouts { full });
+        assert!(config.isomorphic_runouts && decision.estimate.total() <= budget);
+        assert!(decision.to_string().contains("isomorphic runouts bring it to"), "{}", decision);
+
+        let err = fit_memory(&mut turn_config(), 1 << 10, true).unwrap_err();
+        assert_eq!(err.estimate, decision.estimate);
+        assert!(err.to_string().ends_with("over the 1.00 KiB budget"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_and_format_memory() {
+        assert_eq!(parse_memory("8G"), Ok(8 << 30));
+        assert_eq!(parse_memory("1.5GiB"), Ok(3 << 29));
+        assert_eq!(parse_memory("512m"), Ok(512 << 20));
+        assert_eq!(parse_memory("4096"), Ok(4096));
+        assert_eq!(parse_memory(" 2 KB "), Ok(2048));
+        for bad in ["", "G", "-1G", "eight", "8X"] {
+            assert!(parse_memory(bad).is_err(), "{}", bad);
+        }
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 << 29), "1.50 GiB");
+    }
+}
@@ -7,6 +7,7 @@
 /// Represents a playing card (0-51, where 0-12 are spades, 13-25 are hearts, etc.)
 /// or a more structured representation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card(u8);
 
 impl Card {
@@ -20,6 +21,121 @@ impl Card {
     pub fn value(self) -> u8 {
         self.0
     }
+
+    /// Create a card from a rank (0=2 .. 12=A) and suit (0=s, 1=h, 2=d, 3=c)
+    pub fn from_rank_suit(rank: u8, suit: u8) -> Self {
+        assert!(rank < 13 && suit < 4, "rank must be 0-12 and suit 0-3");
+        Card(suit * 13 + rank)
+    }
+
+    /// Rank index (0=2 .. 12=A)
+    pub fn rank(self) -> u8 {
+        self.0 % 13
+    }
+
+    /// Suit index (0=spades, 1=hearts, 2=diamonds, 3=clubs)
+    pub fn suit(self) -> u8 {
+        self.0 / 13
+    }
+
+    /// Single-bit mask for this card (bit `value`), used for conflict checks
+    pub fn mask(self) -> u64 {
+        1u64 << self.0
+    }
+}
+
+/// Rank characters indexed by rank (0=2 .. 12=A)
+pub const RANK_CHARS: [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
+
+/// Suit characters indexed by suit (0=spades, 1=hearts, 2=diamonds, 3=clubs)
+pub const SUIT_CHARS: [char; 4] = ['s', 'h', 'd', 'c'];
+
+/// Parse a rank character ('2'-'9', 'T', 'J', 'Q', 'K', 'A'; case-insensitive)
+pub fn parse_rank(c: char) -> Option<u8> {
+    let upper = c.to_ascii_uppercase();
+    RANK_CHARS.iter().position(|&r| r == upper).map(|i| i as u8)
+}
+
+/// Parse a suit character ('s', 'h', 'd', 'c'; case-insensitive)
+pub fn parse_suit(c: char) -> Option<u8> {
+    let lower = c.to_ascii_lowercase();
+    SUIT_CHARS.iter().position(|&s| s == lower).map(|i| i as u8)
+}
+
+/// Error returned when a card or board string cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardParseError {
+    /// Input was not exactly rank + suit
+    InvalidLength(String),
+    /// Unknown rank character
+    InvalidRank(char),
+    /// Unknown suit character
+    InvalidSuit(char),
+    /// The same card appears twice
+    DuplicateCard(Card),
+}
+
+impl std::fmt::Display for CardParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardParseError::InvalidLength(s) => write!(f, "invalid card string '{}'", s),
+            CardParseError::InvalidRank(c) => write!(f, "invalid rank '{}'", c),
+            CardParseError::InvalidSuit(c) => write!(f, "invalid suit '{}'", c),
+            CardParseError::DuplicateCard(c) => write!(f, "duplicate card {}", c),
+        }
+    }
+}
+
+impl std::error::Error for CardParseError {}
+
+impl std::str::FromStr for Card {
+    type Err = CardParseError;
+
+    /// Parse a two-character card such as "As", "Td", "2c"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (r, su) = match (chars.next(), chars.next(), chars.next()) {
+            (Some(r), Some(su), None) => (r, su),
+            _ => return Err(CardParseError::InvalidLength(s.to_string())),
+        };
+        let rank = parse_rank(r).ok_or(CardParseError::InvalidRank(r))?;
+        let suit = parse_suit(su).ok_or(CardParseError::InvalidSuit(su))?;
+        Ok(Card::from_rank_suit(rank, suit))
+    }
+}
+
+impl std::fmt::Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", RANK_CHARS[self.rank() as usize], SUIT_CHARS[self.suit() as usize])
+    }
+}
+
+/// Parse a concatenated card list such as "AsKh7d" (whitespace and commas ignored).
+///
+/// Rejects duplicate cards.
+pub fn parse_cards(s: &str) -> Result<Vec<Card>, CardParseError> {
+    let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace() && *c != ',').collect();
+    if !chars.len().is_multiple_of(2) {
+        return Err(CardParseError::InvalidLength(s.to_string()));
+    }
+    let mut cards = Vec::with_capacity(chars.len() / 2);
+    let mut used = 0u64;
+    for pair in chars.chunks(2) {
+        let rank = parse_rank(pair[0]).ok_or(CardParseError::InvalidRank(pair[0]))?;
+        let suit = parse_suit(pair[1]).ok_or(CardParseError::InvalidSuit(pair[1]))?;
+        let card = Card::from_rank_suit(rank, suit);
+        if used & card.mask() != 0 {
+            return Err(CardParseError::DuplicateCard(card));
+        }
+        used |= card.mask();
+        cards.push(card);
+    }
+    Ok(cards)
+}
+
+/// Format a card list as a concatenated string ("AsKh7d")
+pub fn format_cards(cards: &[Card]) -> String {
+    cards.iter().map(|c| c.to_string()).collect()
 }
 
 /// Hand rank for poker evaluation
@@ -27,6 +143,7 @@ impl Card {
 /// Lower values represent stronger hands (e.g., Royal Flush = 1, High Card = 7462)
 /// This matches standard poker hand ranking conventions.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandRank(u16);
 
 impl HandRank {
@@ -39,6 +156,114 @@ impl HandRank {
     pub fn value(self) -> u16 {
         self.0
     }
+
+    /// Hand category this rank falls in
+    pub fn category(self) -> HandCategory {
+        match self.0 {
+            0..=10 => HandCategory::StraightFlush,
+            11..=166 => HandCategory::Quads,
+            167..=322 => HandCategory::FullHouse,
+            323..=1599 => HandCategory::Flush,
+            1600..=1609 => HandCategory::Straight,
+            1610..=2467 => HandCategory::Trips,
+            2468..=3325 => HandCategory::TwoPair,
+            3326..=6185 => HandCategory::Pair,
+            _ => HandCategory::HighCard,
+        }
+    }
+
+    /// Human-readable hand, e.g. "Full House, Aces full of Kings"
+    ///
+    /// Decodes the ranks that define the category (kickers are omitted).
+    pub fn describe(self) -> String {
+        let v = self.0;
+        let category = self.category();
+        match category {
+            HandCategory::StraightFlush if v <= 1 => "Royal Flush".to_string(),
+            // One rank step per value below the ace-high straight (flush)
+            HandCategory::StraightFlush => format!("{}, {} high", category, rank_name(13 - v as u8)),
+            HandCategory::Straight => format!("{}, {} high", category, rank_name(12 - (v - 1600) as u8)),
+            HandCategory::Quads => format!("{}, {}", category, rank_plural(12 - ((v - 11) / 12) as u8)),
+            HandCategory::FullHouse => {
+                let trips = 12 - ((v - 167) / 12) as u8;
+                // The pair is the n-th highest of the twelve other ranks
+                let pair = (0..13u8).rev().filter(|&r| r != trips).nth(((v - 167) % 12) as usize).unwrap_or(0);
+                format!("{}, {} full of {}", category, rank_plural(trips), rank_plural(pair))
+            }
+            HandCategory::Flush => format!("{}, {} high", category, rank_name(crate::evaluator::flush_high_rank(v))),
+            HandCategory::Trips => format!("{}, {}", category, rank_plural(12 - ((v - 1610) / 66) as u8)),
+            HandCategory::TwoPair => {
+                // Combinatorial index of (high, low) pair ranks, strongest = 77
+                let combo = 77 - (v - 2468) / 11;
+                let high = (1..13u8).rev().find(|&h| u16::from(h) * u16::from(h - 1) / 2 <= combo).unwrap_or(1);
+                let low = (combo - u16::from(high) * u16::from(high - 1) / 2) as u8;
+                format!("{}, {} and {}", category, rank_plural(high), rank_plural(low))
+            }
+            HandCategory::Pair => format!("{}, {}", category, rank_plural(12 - ((v - 3326) / 220) as u8)),
+            HandCategory::HighCard => {
+                // Combinatorial index of the five ranks; the top rank is the
+                // largest n with C(n, 5) <= index
+                let index = 1286u16.saturating_sub(v - 6186);
+                let top = (4..13u8).rev().find(|&n| choose5(n) <= index).unwrap_or(4);
+                format!("{}, {}", category, rank_name(top))
+            }
+        }
+    }
+}
+
+fn choose5(n: u8) -> u16 {
+    let n = u32::from(n);
+    if n < 5 { 0 } else { (n * (n - 1) * (n - 2) * (n - 3) * (n - 4) / 120) as u16 }
+}
+
+const RANK_NAMES: [&str; 13] = ["Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen", "King", "Ace"];
+
+fn rank_name(rank: u8) -> &'static str {
+    RANK_NAMES[rank.min(12) as usize]
+}
+
+fn rank_plural(rank: u8) -> String {
+    match rank.min(12) {
+        4 => "Sixes".to_string(),
+        r => format!("{}s", RANK_NAMES[r as usize]),
+    }
+}
+
+/// The nine hand categories, strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandCategory {
+    StraightFlush,
+    Quads,
+    FullHouse,
+    Flush,
+    Straight,
+    Trips,
+    TwoPair,
+    Pair,
+    HighCard,
+}
+
+impl HandCategory {
+    pub fn name(self) -> &'static str {
+        match self {
+            HandCategory::StraightFlush => "Straight Flush",
+            HandCategory::Quads => "Four of a Kind",
+            HandCategory::FullHouse => "Full House",
+            HandCategory::Flush => "Flush",
+            HandCategory::Straight => "Straight",
+            HandCategory::Trips => "Three of a Kind",
+            HandCategory::TwoPair => "Two Pair",
+            HandCategory::Pair => "One Pair",
+            HandCategory::HighCard => "High Card",
+        }
+    }
+}
+
+impl std::fmt::Display for HandCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
 /// Hand evaluator trait
@@ -55,6 +280,7 @@ pub trait HandEvaluator {
 
 /// Player position in heads-up poker
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Player {
     /// In Position (acts last)
     IP,
@@ -74,6 +300,7 @@ impl Player {
 
 /// Street in postflop poker
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Street {
     /// Flop (3 board cards)
     Flop,
@@ -85,6 +312,7 @@ pub enum Street {
 
 /// Action type available at a decision node
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     /// Fold (only available when facing a bet)
     Fold,
@@ -94,14 +322,29 @@ pub enum Action {
     Call,
     /// Bet a specific size (in big blinds or pot fraction)
     Bet { size: f64 },
+    /// Raise to a street total (in big blinds)
+    Raise { size: f64 },
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Fold => write!(f, "Fold"),
+            Action::Check => write!(f, "Check"),
+            Action::Call => write!(f, "Call"),
+            Action::Bet { size } => write!(f, "Bet {}", size),
+            Action::Raise { size } => write!(f, "Raise {}", size),
+        }
+    }
 }
 
 /// Node ID type (index into flat array storage)
 pub type NodeId = u32;
 
 /// Information set ID type
-/// 
-/// In heads-up postflop with perfect recall, each node maps 1:1 to an information set.
+///
+/// Dense over decision nodes (see `infoset::InfosetTable`); several nodes
+/// may share one when an abstraction merges what the player observes.
 pub type InfosetId = u32;
 
 /// Represents a node in the game tree
@@ -109,7 +352,8 @@ pub type InfosetId = u32;
 /// Nodes are immutable and contain only game state information.
 /// Solver state (regrets, strategies) is stored separately in parallel arrays
 /// indexed by InfosetId or NodeId.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Node {
     /// Decision node where a player must act
     Decision {
@@ -142,8 +386,11 @@ pub enum Node {
         id: NodeId,
         /// Parent node ID
         parent: Option<NodeId>,
-        /// Child node IDs (one per possible board card)
+        /// Child node IDs (one per possible board card, or per runout class
+        /// when isomorphic runouts are grouped)
         children: Vec<NodeId>,
+        /// Probability of each child; empty when all children are equally likely
+        weights: Vec<f64>,
         /// Current street before chance event
         street: Street,
         /// Pot size
@@ -183,6 +430,15 @@ impl Node {
         }
     }
 
+    /// Probability that a Chance node deals child `index` (0.0 for other nodes)
+    pub fn chance_probability(&self, index: usize) -> f64 {
+        match self {
+            Node::Chance { weights, .. } if !weights.is_empty() => weights[index],
+            Node::Chance { children, .. } => 1.0 / children.len() as f64,
+            _ => 0.0,
+        }
+    }
+
     /// Get the information set ID (only valid for Decision nodes)
     pub fn infoset_id(&self) -> Option<InfosetId> {
         match self {
@@ -227,6 +483,80 @@ impl Node {
         }
     }
 
+    /// Pot size
+    pub fn pot(&self) -> f64 {
+        match self {
+            Node::Decision { pot, .. } => *pot,
+            Node::Chance { pot, .. } => *pot,
+            Node::Terminal { pot, .. } => *pot,
+        }
+    }
+
+    /// Stacks behind, `[IP, OOP]`
+    pub fn stacks(&self) -> [f64; 2] {
+        match self {
+            Node::Decision { stacks, .. } => *stacks,
+            Node::Chance { stacks, .. } => *stacks,
+            Node::Terminal { stacks, .. } => *stacks,
+        }
+    }
+
+    /// The smaller stack: the most either player can still win or lose
+    pub fn effective_stack(&self) -> f64 {
+        let stacks = self.stacks();
+        stacks[0].min(stacks[1])
+    }
+
+    /// Stack-to-pot ratio: effective stack over the pot (0.0 for an empty pot)
+    pub fn spr(&self) -> f64 {
+        if self.pot() > 0.0 { self.effective_stack() / self.pot() } else { 0.0 }
+    }
+
+    /// Chips the player to act must add to call, capped by their stack
+    /// (0.0 when not facing a bet and for non-decision nodes).
+    ///
+    /// Replays the street's actions from `bet_sequence`: OOP acts first, bet
+    /// and raise sizes are street totals, and a call or a second check
+    /// closes the street.
+    pub fn amount_to_call(&self) -> f64 {
+        let Node::Decision { player, stacks, bet_sequence, .. } = self else { return 0.0 };
+        let seat = |p: Player| if p == Player::IP { 0 } else { 1 };
+        let mut street_bets = [0.0f64; 2];
+        let mut to_act = Player::OOP;
+        let mut checked = false;
+        for action in bet_sequence {
+            match action {
+                Action::Call => {
+                    street_bets = [0.0; 2];
+                    to_act = Player::OOP;
+                    checked = false;
+                    continue;
+                }
+                Action::Check if checked => {
+                    to_act = Player::OOP;
+                    checked = false;
+                    continue;
+                }
+                Action::Check => checked = true,
+                Action::Bet { size } | Action::Raise { size } => {
+                    street_bets[seat(to_act)] = *size;
+                    checked = false;
+                }
+                Action::Fold => {}
+            }
+            to_act = to_act.opponent();
+        }
+        let (me, opp) = (seat(*player), seat(player.opponent()));
+        (street_bets[opp] - street_bets[me]).max(0.0).min(stacks[me])
+    }
+
+    /// Share of the pot after calling that the call costs: the equity a
+    /// call needs to break even (0.0 when there is nothing to call)
+    pub fn pot_odds(&self) -> f64 {
+        let to_call = self.amount_to_call();
+        if to_call > 0.0 { to_call / (self.pot() + to_call) } else { 0.0 }
+    }
+
     /// Check if this is a terminal node
     pub fn is_terminal(&self) -> bool {
         matches!(self, Node::Terminal { .. })
@@ -243,10 +573,26 @@ impl Node {
     }
 }
 
+/// Node counts and shape summary of a `GameTree`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TreeStats {
+    pub nodes: usize,
+    pub decisions: usize,
+    pub chance: usize,
+    pub terminals: usize,
+    /// Terminals where someone folded
+    pub fold_terminals: usize,
+    /// Total actions over all decision nodes (= regret slots)
+    pub actions: usize,
+    /// Longest root-to-leaf path, in edges
+    pub max_depth: usize,
+}
+
 /// Game tree wrapper
 /// 
 /// Contains a flat array of nodes for efficient traversal and cache locality.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameTree {
     /// Flat array of nodes indexed by NodeId
     pub nodes: Vec<Node>,
@@ -277,6 +623,116 @@ impl GameTree {
     pub fn is_empty(&self) -> bool {
         self.nodes.is_empty()
     }
+
+    /// Number of infosets (one past the largest decision node `infoset_id`)
+    pub fn num_infosets(&self) -> usize {
+        self.nodes.iter().filter_map(|n| n.infoset_id()).map(|id| id as usize + 1).max().unwrap_or(0)
+    }
+
+    /// Renumber the nodes reachable from the root (node 0) in depth-first
+    /// order, drop the rest, and rewrite every id, parent, and child link to
+    /// match. For use after pruning or editing subtrees, when ids have gaps
+    /// or links point at removed nodes: a child id that doesn't exist, or a
+    /// node already claimed by another parent, is dropped along with its
+    /// action (Chance weights are renormalized). Infoset ids are kept.
+    ///
+    /// Returns each old node's new id (`None` if it was dropped), for
+    /// remapping anything keyed by node id, such as terminal EV tables.
+    pub fn reindex(&mut self) -> Vec<Option<NodeId>> {
+        let n = self.nodes.len();
+        let mut map = vec![None; n];
+        if n == 0 {
+            return map;
+        }
+        // Pre-order walk; a node belongs to the first parent that reaches it
+        let mut owner: Vec<Option<usize>> = vec![None; n];
+        let mut claimed = vec![false; n];
+        let mut kept: Vec<Vec<bool>> = vec![Vec::new(); n];
+        let mut order = Vec::with_capacity(n);
+        let mut stack = vec![0usize];
+        claimed[0] = true;
+        while let Some(old) = stack.pop() {
+            map[old] = Some(order.len() as NodeId);
+            order.push(old);
+            let children = self.nodes[old].children();
+            kept[old] = children.iter().map(|&c| {
+                let c = c as usize;
+                let keep = c < n && !claimed[c];
+                if keep {
+                    claimed[c] = true;
+                    owner[c] = Some(old);
+                }
+                keep
+            }).collect();
+            let live: Vec<usize> = children.iter().zip(&kept[old]).filter(|(_, &k)| k).map(|(&c, _)| c as usize).collect();
+            stack.extend(live.into_iter().rev());
+        }
+
+        let mut nodes = Vec::with_capacity(order.len());
+        for &old in &order {
+            let mut node = self.nodes[old].clone();
+            let keep = &kept[old];
+            let new_id = map[old].expect("visited");
+            let new_parent = owner[old].and_then(|p| map[p]);
+            let relink = |children: &mut Vec<NodeId>| {
+                *children = children.iter().zip(keep).filter(|(_, &k)| k).map(|(&c, _)| map[c as usize].expect("kept child was visited")).collect();
+            };
+            match &mut node {
+                Node::Decision { id, parent, children, actions, .. } => {
+                    *id = new_id;
+                    *parent = new_parent;
+                    relink(children);
+                    *actions = actions.iter().zip(keep).filter(|(_, &k)| k).map(|(a, _)| *a).collect();
+                }
+                Node::Chance { id, parent, children, weights, .. } => {
+                    *id = new_id;
+                    *parent = new_parent;
+                    relink(children);
+                    if !weights.is_empty() {
+                        let kept_weights: Vec<f64> = weights.iter().zip(keep).filter(|(_, &k)| k).map(|(w, _)| *w).collect();
+                        let total: f64 = kept_weights.iter().sum();
+                        *weights = kept_weights.iter().map(|w| if total > 0.0 { w / total } else { 0.0 }).collect();
+                    }
+                }
+                Node::Terminal { id, parent, .. } => {
+                    *id = new_id;
+                    *parent = new_parent;
+                }
+            }
+            nodes.push(node);
+        }
+        self.nodes = nodes;
+        map
+    }
+
+    /// Count nodes by kind and measure depth.
+    ///
+    /// Assumes parents precede children in `nodes` (true for built trees).
+    pub fn stats(&self) -> TreeStats {
+        let mut stats = TreeStats { nodes: self.nodes.len(), ..TreeStats::default() };
+        let mut depth = vec![0usize; self.nodes.len()];
+        for node in &self.nodes {
+            match node {
+                Node::Decision { actions, .. } => {
+                    stats.decisions += 1;
+                    stats.actions += actions.len();
+                }
+                Node::Chance { .. } => stats.chance += 1,
+                Node::Terminal { folder, .. } => {
+                    stats.terminals += 1;
+                    stats.fold_terminals += usize::from(folder.is_some());
+                }
+            }
+            let d = depth[node.id() as usize];
+            stats.max_depth = stats.max_depth.max(d);
+            for &child in node.children() {
+                if let Some(slot) = depth.get_mut(child as usize) {
+                    *slot = d + 1;
+                }
+            }
+        }
+        stats
+    }
 }
 
 impl Default for GameTree {
@@ -284,3 +740,146 @@ impl Default for GameTree {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_parse_and_display_roundtrip() {
+        for v in 0..52u8 {
+            let card = Card::new(v);
+            let parsed: Card = card.to_string().parse().unwrap();
+            assert_eq!(parsed, card);
+        }
+        assert_eq!("As".parse::<Card>().unwrap(), Card::from_rank_suit(12, 0));
+        assert_eq!("td".parse::<Card>().unwrap(), Card::from_rank_suit(8, 2));
+    }
+
+    #[test]
+    fn test_pot_odds_and_effective_stack_accessors() {
+        let tree = crate::test_tree::build_test_tree();
+        let node = |id| tree.get(id).unwrap();
+        // Root: 10bb pot, 95bb behind, nothing to call
+        assert_eq!((node(0).pot(), node(0).effective_stack(), node(0).amount_to_call()), (10.0, 95.0, 0.0));
+        assert_eq!(node(0).spr(), 9.5);
+        assert_eq!(node(1).pot_odds(), 0.0);
+        // OOP facing IP's 5bb bet into 10
+        assert_eq!((node(3).amount_to_call(), node(3).pot_odds()), (5.0, 0.25));
+        assert_eq!(node(6).amount_to_call(), 5.0);
+        assert_eq!(node(8).amount_to_call(), 0.0);
+        assert_eq!(node(8).effective_stack(), 90.0);
+    }
+
+    #[test]
+    fn test_amount_to_call_replays_the_street() {
+        let decision = |player, stacks, bet_sequence| Node::Decision {
+            id: 0, infoset_id: 0, player, street: Street::Turn, parent: None, children: vec![], actions: vec![],
+            pot: 40.0, stacks, board: vec![], bet_sequence,
+        };
+        // Flop bet and call, turn check, bet 10, raise to 30: IP owes 20
+        let line = vec![Action::Bet { size: 5.0 }, Action::Call, Action::Check, Action::Bet { size: 10.0 }, Action::Raise { size: 30.0 }];
+        assert_eq!(decision(Player::IP, [80.0, 60.0], line.clone()).amount_to_call(), 20.0);
+        // ...capped by a shorter stack
+        assert_eq!(decision(Player::IP, [12.0, 60.0], line).amount_to_call(), 12.0);
+        // Flop checked through: the turn starts fresh
+        let checked = vec![Action::Check, Action::Check, Action::Bet { size: 7.0 }];
+        assert_eq!(decision(Player::IP, [90.0, 83.0], checked).amount_to_call(), 7.0);
+        assert_eq!(decision(Player::OOP, [90.0, 90.0], vec![Action::Check, Action::Check]).amount_to_call(), 0.0);
+    }
+
+    #[test]
+    fn test_reindex_compacts_after_pruning() {
+        let mut tree = crate::test_tree::build_test_tree();
+        // Prune OOP's bet (root child 6) by cutting the link: 6, 7, 8 become orphans
+        if let Node::Decision { children, actions, .. } = &mut tree.nodes[0] {
+            children.truncate(1);
+            actions.truncate(1);
+        }
+        let map = tree.reindex();
+        assert_eq!(map, vec![Some(0), Some(1), Some(2), Some(3), Some(4), Some(5), None, None, None]);
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.stats().terminals, 3);
+
+        // Children listed before their parents and a dangling link come back in DFS order
+        let mut tree = crate::test_tree::build_test_tree();
+        tree.nodes.swap(1, 6);
+        if let Node::Decision { children, actions, .. } = &mut tree.nodes[0] {
+            *children = vec![6, 1, 42];
+            actions.push(Action::Bet { size: 20.0 });
+        }
+        let map = tree.reindex();
+        assert_eq!(map[6], Some(1));
+        assert_eq!(map[1], Some(6));
+        for (i, node) in tree.nodes.iter().enumerate() {
+            assert_eq!(node.id() as usize, i);
+            for &c in node.children() {
+                assert_eq!(tree.get(c).unwrap().parent(), Some(node.id()));
+                assert!(c > node.id());
+            }
+        }
+        assert_eq!(tree.get(0).unwrap().children().len(), 2);
+        assert!(matches!(tree.get(0).unwrap(), Node::Decision { actions, .. } if actions.len() == 2));
+        assert_eq!(tree.num_infosets(), 4);
+    }
+
+    #[test]
+    fn test_card_parse_errors() {
+        assert_eq!("Xs".parse::<Card>(), Err(CardParseError::InvalidRank('X')));
+        assert_eq!("Ax".parse::<Card>(), Err(CardParseError::InvalidSuit('x')));
+        assert!(matches!("Asd".parse::<Card>(), Err(CardParseError::InvalidLength(_))));
+    }
+
+    #[test]
+    fn test_parse_cards_board() {
+        let board = parse_cards("AsKh7d").unwrap();
+        assert_eq!(format_cards(&board), "AsKh7d");
+        assert_eq!(board[2].rank(), 5);
+        assert_eq!(parse_cards("As As"), Err(CardParseError::DuplicateCard(Card::from_rank_suit(12, 0))));
+        assert!(parse_cards("AsK").is_err());
+    }
+
+    #[test]
+    fn test_hand_rank_category_and_description() {
+        use crate::evaluator::CactusKevEvaluator;
+        let eval = CactusKevEvaluator::new();
+        let rank = |cards: &str| {
+            let c = parse_cards(cards).unwrap();
+            eval.evaluate([c[0], c[1], c[2], c[3], c[4]], [c[5], c[6]])
+        };
+        for (cards, category, text) in [
+            ("AsKsQsJsTs2h3d", HandCategory::StraightFlush, "Royal Flush"),
+            ("5h4h3h2hAh9c9d", HandCategory::StraightFlush, "Straight Flush, Five high"),
+            ("9s9h9d9c2h3dKs", HandCategory::Quads, "Four of a Kind, Nines"),
+            ("AsAhAdKsKh2c3c", HandCategory::FullHouse, "Full House, Aces full of Kings"),
+            ("2s2h2d6s6h9c3c", HandCategory::FullHouse, "Full House, Twos full of Sixes"),
+            ("KdJd8d4d2dAsQc", HandCategory::Flush, "Flush, King high"),
+            ("9sThJdQcKh2s3s", HandCategory::Straight, "Straight, King high"),
+            ("7s7h7dAsKh2c3d", HandCategory::Trips, "Three of a Kind, Sevens"),
+            ("AsAhKsKh9d2c3c", HandCategory::TwoPair, "Two Pair, Aces and Kings"),
+            ("3s3h2s2h9dJcKd", HandCategory::TwoPair, "Two Pair, Threes and Twos"),
+            ("QsQh9d7c4s3h2d", HandCategory::Pair, "One Pair, Queens"),
+            ("AsJh9d7c4s3h2d", HandCategory::HighCard, "High Card, Ace"),
+            ("9sJh7d6c4s3h2d", HandCategory::HighCard, "High Card, Jack"),
+        ] {
+            let r = rank(cards);
+            assert_eq!(r.category(), category, "{}", cards);
+            assert_eq!(r.describe(), text, "{}", cards);
+        }
+        assert!(HandCategory::Quads < HandCategory::FullHouse);
+    }
+
+    #[test]
+    fn test_tree_stats_on_test_tree() {
+        let stats = crate::test_tree::build_test_tree().stats();
+        assert_eq!(stats, TreeStats {
+            nodes: 9,
+            decisions: 4,
+            chance: 0,
+            terminals: 5,
+            fold_terminals: 2,
+            actions: 8,
+            max_depth: 3,
+        });
+    }
+}
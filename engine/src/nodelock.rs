@@ -0,0 +1,383 @@
+//! Node locks: fixing some of a player's frequencies while the rest solve
+//!
+//! A lock pins chosen actions at an infoset to given frequencies. Every
+//! iteration the solver plays the pinned frequencies there, and the
+//! remaining probability goes to the unpinned actions in proportion to
+//! their regrets, so the locked player still optimizes whatever the lock
+//! leaves open and the opponent learns to exploit the lock. Locks apply per
+//! infoset: locking a node locks every node sharing its infoset.
+//!
+//! Lock files make lock scenarios reproducible. Each line is
+//! `TARGET = FREQUENCIES`, and `#` starts a comment:
+//!
+//! ```text
+//! # one node, named by its path from the root (see `line`)
+//! T:x-b75 = 0.3 0.7 0          # every action, in the node's order
+//! T:x-b75-c|R:Qs = x:1         # only the named actions; the rest solve
+//! T:x-b75-c|R:* = b:0          # `*` steps to every child (any card or action)
+//! x b75 c Qs x = b:0           # paths may also be space-separated tokens
+//! all oop river lead = b:0     # bulk: every matching decision
+//! ```
+//!
+//! A path of `root` names the root; street marks in a path are checked
+//! against the tree, as `line::Line::find` does. An `all` target keeps the decisions
+//! matching every qualifier given: a player (`ip`, `oop`), a street
+//! (`flop`, `turn`, `river`), and `lead` (not facing a bet) or `facing`.
+//! Frequencies are either one number per action or `token:frequency` pairs,
+//! where a bare `b` or `r` splits its frequency evenly over every bet or
+//! raise. Later lines override earlier ones at the same infoset.
+
+use std::collections::HashMap;
+use crate::line::{action_token, chance_child, format_line, match_action, parse_card, Line};
+use crate::node::{Action, GameTree, Node, NodeId, Player, Street};
+
+/// Pinned frequencies per infoset (see module docs)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NodeLocks {
+    /// `locks[infoset][action]`: `Some(frequency)` if pinned, `None` if free
+    locks: HashMap<usize, Vec<Option<f64>>>,
+}
+
+/// A lock-file line that couldn't be applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeLockError {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for NodeLockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for NodeLockError {}
+
+/// Slack allowed when pinned frequencies add up to 1
+const SUM_TOLERANCE: f64 = 1e-6;
+
+impl NodeLocks {
+    pub fn new() -> Self {
+        NodeLocks::default()
+    }
+
+    /// Pin `infoset`'s actions: `Some(frequency)` fixes an action, `None`
+    /// leaves it to the solver. Replaces any earlier lock on the infoset.
+    ///
+    /// Panics if a frequency is outside `[0, 1]`, the pinned frequencies
+    /// add up to more than 1, or every action is pinned and they don't add
+    /// up to 1.
+    pub fn lock(&mut self, infoset: usize, frequencies: Vec<Option<f64>>) {
+        if let Err(e) = check(&frequencies) {
+            panic!("invalid lock on infoset {}: {}", infoset, e);
+        }
+        self.locks.insert(infoset, frequencies);
+    }
+
+    /// Remove the lock on `infoset`, if any
+    pub fn unlock(&mut self, infoset: usize) {
+        self.locks.remove(&infoset);
+    }
+
+    pub fn get(&self, infoset: usize) -> Option<&[Option<f64>]> {
+        self.locks.get(&infoset).map(Vec::as_slice)
+    }
+
+    pub fn len(&self) -> usize {
+        self.locks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locks.is_empty()
+    }
+
+    /// `(infoset, frequencies)` for every lock, in infoset order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &[Option<f64>])> {
+        let mut infosets: Vec<usize> = self.locks.keys().copied().collect();
+        infosets.sort_unstable();
+        infosets.into_iter().map(|i| (i, self.locks[&i].as_slice()))
+    }
+
+    /// Overwrite `strategy` (the solver's strategy at `infoset`) with the
+    /// lock's: pinned actions take their frequencies and free actions share
+    /// what's left in proportion to `strategy`, or evenly if it gives them
+    /// nothing. Unlocked infosets are left alone.
+    pub fn apply(&self, infoset: usize, strategy: &mut [f64]) {
+        let Some(lock) = self.locks.get(&infoset) else { return };
+        let pinned: f64 = lock.iter().flatten().sum();
+        let free = lock.iter().filter(|f| f.is_none()).count();
+        if free == 0 {
+            for (s, f) in strategy.iter_mut().zip(lock) {
+                *s = f.unwrap_or(0.0) / pinned;
+            }
+            return;
+        }
+        let left = (1.0 - pinned).max(0.0);
+        let free_sum: f64 = strategy.iter().zip(lock).filter(|(_, f)| f.is_none()).map(|(s, _)| s).sum();
+        for (s, f) in strategy.iter_mut().zip(lock) {
+            *s = match f {
+                Some(f) => *f,
+                None if free_sum > 0.0 => left * *s / free_sum,
+                None => left / free as f64,
+            };
+        }
+    }
+
+    /// Read a lock file (see module docs), resolving its targets in `tree`
+    pub fn parse(tree: &GameTree, text: &str) -> Result<NodeLocks, NodeLockError> {
+        let mut locks = NodeLocks::new();
+        for (i, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: String| NodeLockError { line: i + 1, message };
+            let (target, frequencies) = line.split_once('=')
+                .ok_or_else(|| error("expected TARGET = FREQUENCIES".to_string()))?;
+            let nodes = resolve_target(tree, target.trim()).map_err(error)?;
+            if nodes.is_empty() {
+                return Err(error(format!("'{}' matches no decisions", target.trim())));
+            }
+            for id in nodes {
+                let Some(Node::Decision { infoset_id, actions, pot, .. }) = tree.get(id) else {
+                    return Err(error(format!("'{}' reaches node {}, which is not a decision", target.trim(), id)));
+                };
+                let pins = parse_frequencies(frequencies, actions, *pot).map_err(error)?;
+                check(&pins).map_err(error)?;
+                locks.locks.insert(*infoset_id as usize, pins);
+            }
+        }
+        Ok(locks)
+    }
+
+    /// A lock file that `parse` reads back to these locks: one line per
+    /// infoset, naming the first node in it by its path
+    pub fn to_text(&self, tree: &GameTree) -> String {
+        let mut first: HashMap<usize, NodeId> = HashMap::new();
+        for node in &tree.nodes {
+            if let Some(infoset) = node.infoset_id() {
+                first.entry(infoset as usize).or_insert(node.id());
+            }
+        }
+        let mut out = String::new();
+        for (infoset, lock) in self.iter() {
+            let Some(&id) = first.get(&infoset) else { continue };
+            let Some(Node::Decision { actions, pot, .. }) = tree.get(id) else { continue };
+            let frequencies: Vec<String> = if lock.iter().all(Option::is_some) {
+                lock.iter().flatten().map(|f| f.to_string()).collect()
+            } else {
+                lock.iter().zip(actions)
+                    .filter_map(|(f, a)| f.map(|f| format!("{}:{}", action_token(a, *pot), f)))
+                    .collect()
+            };
+            out.push_str(&format!("{} = {}\n", format_line(tree, id), frequencies.join(" ")));
+        }
+        out
+    }
+}
+
+/// Frequencies must be in `[0, 1]`, and add up to at most 1, or exactly 1
+/// when every action is pinned
+fn check(frequencies: &[Option<f64>]) -> Result<(), String> {
+    if let Some(f) = frequencies.iter().flatten().find(|f| !(0.0..=1.0).contains(*f)) {
+        return Err(format!("frequency {} is outside [0, 1]", f));
+    }
+    let pinned: f64 = frequencies.iter().flatten().sum();
+    if pinned > 1.0 + SUM_TOLERANCE {
+        return Err(format!("frequencies add up to {}", pinned));
+    }
+    if frequencies.iter().all(Option::is_some) && (pinned - 1.0).abs() > SUM_TOLERANCE {
+        return Err(format!("frequencies of every action add up to {}, not 1", pinned));
+    }
+    Ok(())
+}
+
+/// The nodes a lock target names
+fn resolve_target(tree: &GameTree, target: &str) -> Result<Vec<NodeId>, String> {
+    let mut words = target.split_whitespace().peekable();
+    if words.peek() == Some(&"all") {
+        words.next();
+        return select_all(tree, words);
+    }
+    let line = Line::parse(target)?;
+    let mut frontier: Vec<NodeId> = if tree.is_empty() { Vec::new() } else { vec![0] };
+    for (i, token) in line.tokens.iter().enumerate() {
+        let token = token.as_str();
+        let mut next = Vec::new();
+        for id in frontier {
+            let node = tree.get(id).expect("frontier holds valid node ids");
+            match (token, node) {
+                (_, Node::Terminal { .. }) => return Err(format!("'{}' follows the end of the hand", token)),
+                ("*", _) => next.extend_from_slice(node.children()),
+                (_, Node::Decision { actions, children, pot, .. }) => next.push(children[match_action(token, actions, *pot)?]),
+                (_, Node::Chance { children, .. }) => next.push(
+                    chance_child(tree, children, parse_card(token)?).ok_or_else(|| format!("{} cannot be dealt here", token))?,
+                ),
+            }
+        }
+        for &id in &next {
+            line.check_street(tree, i, id)?;
+        }
+        frontier = next;
+    }
+    Ok(frontier)
+}
+
+/// Decisions matching every qualifier of an `all` target
+fn select_all<'t>(tree: &GameTree, qualifiers: impl Iterator<Item = &'t str>) -> Result<Vec<NodeId>, String> {
+    let (mut player, mut street, mut facing) = (None, None, None);
+    for q in qualifiers {
+        match q.to_ascii_lowercase().as_str() {
+            "ip" => player = Some(Player::IP),
+            "oop" => player = Some(Player::OOP),
+            "flop" => street = Some(Street::Flop),
+            "turn" => street = Some(Street::Turn),
+            "river" => street = Some(Street::River),
+            "lead" => facing = Some(false),
+            "facing" => facing = Some(true),
+            other => return Err(format!("unknown qualifier '{}'", other)),
+        }
+    }
+    Ok(tree.nodes.iter()
+        .filter_map(|node| match node {
+            Node::Decision { id, player: p, street: s, actions, .. }
+                if player.is_none_or(|x| x == *p)
+                    && street.is_none_or(|x| x == *s)
+                    && facing.is_none_or(|x| x == actions.contains(&Action::Call)) => Some(*id),
+            _ => None,
+        })
+        .collect())
+}
+
+/// One frequency per action, or `token:frequency` pairs (see module docs)
+fn parse_frequencies(text: &str, actions: &[Action], pot: f64) -> Result<Vec<Option<f64>>, String> {
+    let parse = |s: &str| s.parse::<f64>().map_err(|_| format!("invalid frequency '{}'", s));
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return Err("no frequencies".to_string());
+    }
+    if words.iter().all(|w| !w.contains(':')) {
+        if words.len() != actions.len() {
+            return Err(format!("{} frequencies for {} actions", words.len(), actions.len()));
+        }
+        return words.iter().map(|w| parse(w).map(Some)).collect();
+    }
+    let mut pins = vec![None; actions.len()];
+    for word in words {
+        let (token, frequency) = word.split_once(':').ok_or_else(|| format!("expected TOKEN:FREQUENCY, got '{}'", word))?;
+        let frequency = parse(frequency)?;
+        let kind: Option<fn(&Action) -> bool> = match token {
+            "b" => Some(|a| matches!(a, Action::Bet { .. })),
+            "r" => Some(|a| matches!(a, Action::Raise { .. })),
+            _ => None,
+        };
+        match kind {
+            Some(is_kind) => {
+                let matching: Vec<usize> = (0..actions.len()).filter(|&a| is_kind(&actions[a])).collect();
+                if matching.is_empty() {
+                    return Err(format!("'{}' is not available here", token));
+                }
+                for &a in &matching {
+                    pins[a] = Some(frequency / matching.len() as f64);
+                }
+            }
+            None => pins[match_action(token, actions, pot)?] = Some(frequency),
+        }
+    }
+    Ok(pins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::cfr::CfrSolver;
+    use crate::game::GameConfig;
+    use crate::line::find_node;
+    use crate::node::parse_cards;
+    use crate::test_tree::build_test_tree;
+
+    fn river_tree() -> GameTree {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5, 1.0]);
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 2;
+        build_tree(&config)
+    }
+
+    #[test]
+    fn test_apply_pins_and_shares_the_rest() {
+        let mut locks = NodeLocks::new();
+        locks.lock(0, vec![Some(0.2), None, None]);
+        let mut strategy = [0.5, 0.3, 0.2];
+        locks.apply(0, &mut strategy);
+        assert_eq!(strategy[0], 0.2);
+        assert!((strategy[1] - 0.8 * 0.6).abs() < 1e-12 && (strategy[2] - 0.8 * 0.4).abs() < 1e-12);
+        // Free actions the solver gives nothing share evenly
+        let mut strategy = [1.0, 0.0, 0.0];
+        locks.apply(0, &mut strategy);
+        assert_eq!(strategy, [0.2, 0.4, 0.4]);
+        // Unlocked infosets are untouched
+        let mut other = [0.5, 0.5];
+        locks.apply(1, &mut other);
+        assert_eq!(other, [0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_parse_paths_wildcards_and_bulk_targets() {
+        let tree = river_tree();
+        let text = "\
+            # turn: OOP leads small 30%
+            root = x:0.7 b50:0.3
+            T:x-b100 = 0.1 0.9 0   # fold, call, raise
+            x x * = b:0
+            all oop river lead = b:0
+        ";
+        let locks = NodeLocks::parse(&tree, text).unwrap();
+        let infoset = |id: NodeId| tree.get(id).unwrap().infoset_id().unwrap() as usize;
+        assert_eq!(locks.get(0), Some(&[Some(0.7), Some(0.3), None][..]));
+        let facing = find_node(&tree, ["x", "b100"]).unwrap();
+        assert_eq!(locks.get(infoset(facing)), Some(&[Some(0.1), Some(0.9), Some(0.0)][..]));
+        // Every river card after check-check, and every OOP river lead
+        let river = find_node(&tree, ["x", "x", "Qs"]).unwrap();
+        assert_eq!(locks.get(infoset(river)), Some(&[None, Some(0.0)][..]));
+        let leads = tree.nodes.iter()
+            .filter(|n| matches!(n, Node::Decision { player: Player::OOP, street: Street::River, actions, .. } if actions[0] == Action::Check))
+            .count();
+        assert!(leads > 40);
+        assert!(tree.nodes.iter().filter_map(|n| n.infoset_id()).filter(|&i| locks.get(i as usize).is_some()).count() >= leads);
+
+        // Written out and read back, the locks are the same
+        assert_eq!(NodeLocks::parse(&tree, &locks.to_text(&tree)).unwrap(), locks);
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let tree = river_tree();
+        let err = |text: &str| NodeLocks::parse(&tree, text).unwrap_err();
+        assert_eq!(err("root = 0.5 0.5 0\nroot 0.5").line, 2);
+        assert!(err("root = 0.5 0.5 0.5").message.contains("add up"));
+        assert!(err("root = 0.5").message.contains("1 frequencies for 3 actions"));
+        assert!(err("root = x:0.8 b:0.8").message.contains("add up"));
+        assert!(err("x b50 f = 1").message.contains("not a decision"));
+        assert!(err("x c = 1").message.contains("not available"));
+        assert!(err("all oop preflop = b:0").message.contains("unknown qualifier"));
+        assert!(err("all ip flop = b:0").message.contains("matches no decisions"));
+        assert!(err("F:x = b:0").message.contains("not the flop"));
+        assert!(err("T:x-x|T:* = b:0").message.contains("out of street order"));
+    }
+
+    #[test]
+    fn test_solver_plays_locked_frequencies() {
+        let tree = build_test_tree();
+        // OOP always checks at the root: IP's best reply is to bet
+        let locks = NodeLocks::parse(&tree, "root = 1 0").unwrap();
+        let mut solver = CfrSolver::new(tree).with_locks(locks);
+        for _ in 0..2_000 {
+            solver.run_iteration();
+        }
+        assert_eq!(solver.storage.average_strategy(0), vec![1.0, 0.0]);
+        assert!(solver.storage.average_strategy(1)[1] > 0.99);
+    }
+}
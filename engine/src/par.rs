@@ -0,0 +1,139 @@
+//! Parallelism with or without Rayon
+//!
+//! With the `parallel` feature (on by default) this re-exports the parts of
+//! Rayon the engine uses. Without it, the same names run sequentially on
+//! the calling thread: `par_iter`/`into_par_iter` are plain iterators,
+//! `join` runs both closures in turn, and `ThreadPool::install` just calls
+//! its closure, so `SolverConfig::threads` has no effect. Results are the
+//! same either way; chance and decision children are traversed in order.
+//!
+//! Engine modules import parallel helpers from here (`par::prelude::*` for
+//! the iterator traits) rather than from Rayon directly, so the sequential
+//! build needs no `cfg` at call sites.
+
+#[cfg(feature = "parallel")]
+pub use rayon::{join, ThreadPool};
+
+/// Iterator traits: Rayon's prelude, or sequential stand-ins
+pub mod prelude {
+    #[cfg(feature = "parallel")]
+    pub use rayon::prelude::*;
+    #[cfg(not(feature = "parallel"))]
+    pub use super::sequential::{IntoParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
+}
+
+/// A pool of `threads` workers for a solver's parallel work
+#[cfg(feature = "parallel")]
+pub fn build_pool(threads: usize) -> ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("failed to build solver thread pool")
+}
+
+#[cfg(not(feature = "parallel"))]
+pub use sequential::{build_pool, join, ThreadPool};
+
+#[cfg(not(feature = "parallel"))]
+mod sequential {
+    /// Run `a` then `b`
+    pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA,
+        B: FnOnce() -> RB,
+    {
+        (a(), b())
+    }
+
+    /// Stand-in for `rayon::ThreadPool`: work runs on the calling thread
+    #[derive(Debug)]
+    pub struct ThreadPool;
+
+    impl ThreadPool {
+        pub fn install<R>(&self, op: impl FnOnce() -> R) -> R {
+            op()
+        }
+
+        pub fn current_num_threads(&self) -> usize {
+            1
+        }
+    }
+
+    /// `threads` is ignored without the `parallel` feature
+    pub fn build_pool(_threads: usize) -> ThreadPool {
+        ThreadPool
+    }
+
+    /// Sequential `par_iter`
+    pub trait IntoParallelRefIterator<'a> {
+        type Iter: Iterator;
+        fn par_iter(&'a self) -> Self::Iter;
+    }
+
+    impl<'a, T: 'a> IntoParallelRefIterator<'a> for [T] {
+        type Iter = std::slice::Iter<'a, T>;
+        fn par_iter(&'a self) -> Self::Iter {
+            self.iter()
+        }
+    }
+
+    /// Sequential `par_iter_mut`
+    pub trait IntoParallelRefMutIterator<'a> {
+        type Iter: Iterator;
+        fn par_iter_mut(&'a mut self) -> Self::Iter;
+    }
+
+    impl<'a, T: 'a> IntoParallelRefMutIterator<'a> for [T] {
+        type Iter = std::slice::IterMut<'a, T>;
+        fn par_iter_mut(&'a mut self) -> Self::Iter {
+            self.iter_mut()
+        }
+    }
+
+    /// Rayon-only adapters on plain iterators
+    pub trait ParallelIterator: Iterator + Sized {
+        /// `for_each` with one `init()` value for the whole iterator
+        fn for_each_init<T>(self, init: impl Fn() -> T, op: impl Fn(&mut T, Self::Item)) {
+            let mut state = init();
+            self.for_each(|item| op(&mut state, item));
+        }
+    }
+
+    impl<I: Iterator> ParallelIterator for I {}
+
+    /// Sequential `into_par_iter`
+    pub trait IntoParallelIterator {
+        type Iter: Iterator;
+        fn into_par_iter(self) -> Self::Iter;
+    }
+
+    impl<T> IntoParallelIterator for Vec<T> {
+        type Iter = std::vec::IntoIter<T>;
+        fn into_par_iter(self) -> Self::Iter {
+            self.into_iter()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::prelude::*;
+
+    #[test]
+    fn test_helpers_match_sequential_results() {
+        let xs: Vec<u64> = (1..=100).collect();
+        let squares: Vec<u64> = xs.par_iter().map(|&x| x * x).collect();
+        assert_eq!(squares, xs.iter().map(|&x| x * x).collect::<Vec<_>>());
+        let total: u64 = xs.clone().into_par_iter().sum();
+        assert_eq!(total, 5050);
+        let mut doubled = xs.clone();
+        doubled.par_iter_mut().zip(xs.par_iter()).for_each_init(Vec::new, |seen, (d, &x)| {
+            seen.push(x);
+            *d = 2 * x;
+        });
+        assert_eq!(doubled[99], 200);
+        assert_eq!(join(|| 1, || "two"), (1, "two"));
+        assert_eq!(build_pool(2).install(|| 7), 7);
+    }
+}
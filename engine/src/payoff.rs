@@ -0,0 +1,324 @@
+//! Terminal payoff accounting: uncalled bets, chops, and chip rounding
+//!
+//! `game::terminal_ip_ev` folds a terminal into one number. `TerminalPayoff`
+//! keeps the pieces apart so each can be checked: what every player put in
+//! since the root, the part of a bet the other player never matched (returned
+//! to its owner before anything is contested), and the contested pot.
+//!
+//! - A fold gives the whole pot to the other player; the unmatched part of
+//!   their bet is their own money coming back.
+//! - At showdown the uncalled excess (a shorter stack calling all-in for
+//!   less) goes back first, and only the contested pot is won or split.
+//! - A chop splits the contested pot exactly in half. With
+//!   `chip_payouts` every amount is rounded to whole chips first and an odd
+//!   chip goes to OOP, the first player left of the button.
+//!
+//! `audit_payoffs` checks these invariants on every terminal of a tree: the
+//! pot equals the root pot plus what was invested, no stack is negative, a
+//! showdown with unequal investments has the short player all-in, and the
+//! folder was facing a bet.
+
+use crate::game::player_index;
+use crate::node::{GameTree, Node, NodeId, Player};
+
+/// Tolerance for comparing bb amounts built up from fractional bet sizes
+const EPSILON: f64 = 1e-9;
+
+/// Who takes a contested pot at showdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// This player's hand is best
+    Win(Player),
+    /// Equal hands split the contested pot
+    Chop,
+}
+
+/// One terminal's pot, broken into what is returned and what is contested
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerminalPayoff {
+    /// Final pot, including dead money and any uncalled bet
+    pub pot: f64,
+    /// Chips each player put in after the root, `[IP, OOP]`
+    pub invested: [f64; 2],
+    /// Unmatched part of each player's investment, returned to them
+    pub uncalled: [f64; 2],
+    /// Pot left after uncalled chips are returned
+    pub contested: f64,
+    /// Player who folded, `None` at showdown
+    pub folder: Option<Player>,
+}
+
+impl TerminalPayoff {
+    /// Payoff breakdown of terminal `node` given the stacks at the tree root;
+    /// `None` for non-terminals
+    pub fn from_node(node: &Node, root_stacks: [f64; 2]) -> Option<Self> {
+        let Node::Terminal { folder, pot, stacks, .. } = node else { return None };
+        let invested = [root_stacks[0] - stacks[0], root_stacks[1] - stacks[1]];
+        let excess = invested[0] - invested[1];
+        let uncalled = [excess.max(0.0), (-excess).max(0.0)];
+        Some(TerminalPayoff { pot: *pot, invested, uncalled, contested: pot - excess.abs(), folder: *folder })
+    }
+
+    /// Pot at the root (dead money and earlier streets)
+    pub fn starting_pot(&self) -> f64 {
+        self.pot - self.invested[0] - self.invested[1]
+    }
+
+    /// Chips each player collects, `[IP, OOP]`. At a fold the other player
+    /// collects the pot and `outcome` is ignored.
+    pub fn payouts(&self, outcome: Outcome) -> [f64; 2] {
+        if let Some(folder) = self.folder {
+            let mut payouts = [0.0; 2];
+            payouts[1 - player_index(folder)] = self.pot;
+            return payouts;
+        }
+        let mut payouts = self.uncalled;
+        match outcome {
+            Outcome::Win(winner) => payouts[player_index(winner)] += self.contested,
+            Outcome::Chop => {
+                payouts[0] += self.contested / 2.0;
+                payouts[1] += self.contested / 2.0;
+            }
+        }
+        payouts
+    }
+
+    /// Each player's EV, `[IP, OOP]`: payout minus chips invested since the root
+    pub fn evs(&self, outcome: Outcome) -> [f64; 2] {
+        let payouts = self.payouts(outcome);
+        [payouts[0] - self.invested[0], payouts[1] - self.invested[1]]
+    }
+
+    /// IP's EV when IP wins `ip_equity` of the contested pot (ignored at folds);
+    /// the quantity `game::terminal_ip_ev` reports
+    pub fn ip_ev(&self, ip_equity: f64) -> f64 {
+        let ip_share = match self.folder {
+            Some(Player::OOP) => self.pot,
+            Some(Player::IP) => 0.0,
+            None => ip_equity * self.contested + self.uncalled[0],
+        };
+        ip_share - self.invested[0]
+    }
+
+    /// `payouts` in whole chips at `chips_per_bb`, converted back to bb.
+    ///
+    /// The pot and the uncalled bet are each rounded to the nearest chip and
+    /// the contested pot is what's left, so payouts always add up to the
+    /// rounded pot. A chop gives OOP the odd chip.
+    pub fn chip_payouts(&self, outcome: Outcome, chips_per_bb: f64) -> [f64; 2] {
+        assert!(chips_per_bb > 0.0, "chips_per_bb must be positive");
+        let pot = (self.pot * chips_per_bb).round();
+        let mut chips = [0.0; 2];
+        if let Some(folder) = self.folder {
+            chips[1 - player_index(folder)] = pot;
+        } else {
+            chips = self.uncalled.map(|u| (u * chips_per_bb).round());
+            let contested = pot - chips[0] - chips[1];
+            match outcome {
+                Outcome::Win(winner) => chips[player_index(winner)] += contested,
+                Outcome::Chop => {
+                    let half = (contested / 2.0).floor();
+                    chips[0] += half;
+                    chips[1] += contested - half;
+                }
+            }
+        }
+        chips.map(|c| c / chips_per_bb)
+    }
+}
+
+/// A terminal whose accounting doesn't add up (see `audit_payoffs`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayoffIssue {
+    /// The pot isn't the root pot plus both players' investments
+    PotMismatch { node: NodeId, expected: f64, actual: f64 },
+    /// A player has a negative stack
+    NegativeStack { node: NodeId, player: Player },
+    /// Investments differ at showdown but the short player isn't all-in
+    UnmatchedShowdown { node: NodeId },
+    /// The folder had already put in at least as much as the other player
+    FoldWithoutBet { node: NodeId },
+}
+
+impl std::fmt::Display for PayoffIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayoffIssue::PotMismatch { node, expected, actual } => write!(f, "node {}: pot {} but root pot plus investments is {}", node, actual, expected),
+            PayoffIssue::NegativeStack { node, player } => write!(f, "node {}: {:?} stack is negative", node, player),
+            PayoffIssue::UnmatchedShowdown { node } => write!(f, "node {}: unequal investments at showdown without an all-in", node),
+            PayoffIssue::FoldWithoutBet { node } => write!(f, "node {}: folder was not facing a bet", node),
+        }
+    }
+}
+
+/// Check every terminal's payoff accounting against the root's pot and
+/// stacks (see module docs); an empty result means the tree is consistent
+pub fn audit_payoffs(tree: &GameTree) -> Vec<PayoffIssue> {
+    let Some(root) = tree.get(0) else { return Vec::new() };
+    let (root_pot, root_stacks) = (root.pot(), root.stacks());
+    let mut issues = Vec::new();
+    for node in &tree.nodes {
+        let Some(payoff) = TerminalPayoff::from_node(node, root_stacks) else { continue };
+        let (id, stacks) = (node.id(), node.stacks());
+        let expected = root_pot + payoff.invested[0] + payoff.invested[1];
+        if (payoff.pot - expected).abs() > EPSILON {
+            issues.push(PayoffIssue::PotMismatch { node: id, expected, actual: payoff.pot });
+        }
+        for (i, player) in [Player::IP, Player::OOP].into_iter().enumerate() {
+            if stacks[i] < -EPSILON {
+                issues.push(PayoffIssue::NegativeStack { node: id, player });
+            }
+        }
+        match payoff.folder {
+            Some(folder) => {
+                let f = player_index(folder);
+                if payoff.invested[f] >= payoff.invested[1 - f] - EPSILON {
+                    issues.push(PayoffIssue::FoldWithoutBet { node: id });
+                }
+            }
+            None => {
+                let short = if payoff.invested[0] < payoff.invested[1] { 0 } else { 1 };
+                if (payoff.invested[0] - payoff.invested[1]).abs() > EPSILON && stacks[short] > EPSILON {
+                    issues.push(PayoffIssue::UnmatchedShowdown { node: id });
+                }
+            }
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::game::{terminal_ip_ev, GameConfig};
+    use crate::node::parse_cards;
+
+    fn terminal(folder: Option<Player>, pot: f64, stacks: [f64; 2]) -> Node {
+        Node::Terminal { id: 0, parent: None, folder, pot, stacks, board: vec![], hole_cards: [None, None] }
+    }
+
+    #[test]
+    fn test_fold_returns_the_uncalled_bet_with_the_pot() {
+        // 10bb pot, IP bets 7, OOP folds
+        let payoff = TerminalPayoff::from_node(&terminal(Some(Player::OOP), 17.0, [93.0, 100.0]), [100.0, 100.0]).unwrap();
+        assert_eq!((payoff.invested, payoff.uncalled, payoff.contested), ([7.0, 0.0], [7.0, 0.0], 10.0));
+        assert_eq!(payoff.starting_pot(), 10.0);
+        // The outcome doesn't matter at a fold
+        for outcome in [Outcome::Win(Player::OOP), Outcome::Chop] {
+            assert_eq!(payoff.payouts(outcome), [17.0, 0.0]);
+            assert_eq!(payoff.evs(outcome), [10.0, 0.0]);
+        }
+        assert_eq!(payoff.ip_ev(0.0), 10.0);
+
+        // OOP raises 7 to 21 and IP folds: IP's 7 is lost, OOP's extra 14 comes back
+        let payoff = TerminalPayoff::from_node(&terminal(Some(Player::IP), 38.0, [93.0, 79.0]), [100.0, 100.0]).unwrap();
+        assert_eq!(payoff.uncalled, [0.0, 14.0]);
+        assert_eq!(payoff.evs(Outcome::Chop), [-7.0, 17.0]);
+    }
+
+    #[test]
+    fn test_showdown_returns_excess_before_contesting() {
+        // OOP shoves 50 into 6, IP calls all-in for 10
+        let payoff = TerminalPayoff::from_node(&terminal(None, 66.0, [0.0, 0.0]), [10.0, 50.0]).unwrap();
+        assert_eq!((payoff.uncalled, payoff.contested), ([0.0, 40.0], 26.0));
+        assert_eq!(payoff.payouts(Outcome::Win(Player::IP)), [26.0, 40.0]);
+        assert_eq!(payoff.payouts(Outcome::Win(Player::OOP)), [0.0, 66.0]);
+        assert_eq!(payoff.payouts(Outcome::Chop), [13.0, 53.0]);
+        assert_eq!(payoff.evs(Outcome::Win(Player::IP)), [16.0, -10.0]);
+        assert_eq!(payoff.evs(Outcome::Chop), [3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_chop_is_exact_and_payouts_sum_to_the_pot() {
+        // Dead money makes the chop EV the half-pot, not zero
+        let payoff = TerminalPayoff::from_node(&terminal(None, 5.5 + 2.0 * 4.125, [93.375, 93.375]), [97.5, 97.5]).unwrap();
+        assert_eq!(payoff.evs(Outcome::Chop), [2.75, 2.75]);
+        for outcome in [Outcome::Win(Player::IP), Outcome::Win(Player::OOP), Outcome::Chop] {
+            let evs = payoff.evs(outcome);
+            assert!((evs[0] + evs[1] - payoff.starting_pot()).abs() < 1e-12);
+            assert!((payoff.payouts(outcome).iter().sum::<f64>() - payoff.pot).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_ip_ev_matches_terminal_ip_ev() {
+        let root = [10.0, 50.0];
+        let nodes = [
+            terminal(None, 66.0, [0.0, 0.0]),
+            terminal(None, 6.0, root),
+            terminal(Some(Player::IP), 16.0, [10.0, 40.0]),
+            terminal(Some(Player::OOP), 16.0, [0.0, 50.0]),
+        ];
+        for node in &nodes {
+            let payoff = TerminalPayoff::from_node(node, root).unwrap();
+            for equity in [0.0, 0.25, 0.5, 1.0] {
+                assert_eq!(payoff.ip_ev(equity), terminal_ip_ev(node, root, equity));
+            }
+            // Winning outright is equity 1, a chop is equity 0.5
+            assert_eq!(payoff.evs(Outcome::Win(Player::IP))[0], payoff.ip_ev(1.0));
+            assert_eq!(payoff.evs(Outcome::Chop)[0], payoff.ip_ev(0.5));
+        }
+    }
+
+    #[test]
+    fn test_chip_payouts_round_and_give_the_odd_chip_to_oop() {
+        // 3.33bb (333 chips at 100/bb) + 0.01 odd: 10.01bb pot chopped
+        let payoff = TerminalPayoff::from_node(&terminal(None, 10.01, [95.0, 95.0]), [95.0, 95.0]).unwrap();
+        assert_eq!(payoff.chip_payouts(Outcome::Chop, 100.0), [5.0, 5.01]);
+        assert_eq!(payoff.chip_payouts(Outcome::Win(Player::IP), 100.0), [10.01, 0.0]);
+
+        // A 2/3-pot bet leaves fractional chips; everything rounds to the same total
+        let pot = 10.0 + 2.0 * (10.0 * 2.0 / 3.0);
+        let payoff = TerminalPayoff::from_node(&terminal(None, pot, [100.0 - 20.0 / 3.0; 2]), [100.0, 100.0]).unwrap();
+        let chips = payoff.chip_payouts(Outcome::Chop, 1.0);
+        assert_eq!(chips, [11.0, 12.0]);
+        assert_eq!(chips[0] + chips[1], pot.round());
+
+        // Uncalled chips come back before the odd chip is decided
+        let payoff = TerminalPayoff::from_node(&terminal(None, 67.0, [0.0, 0.0]), [10.0, 51.0]).unwrap();
+        assert_eq!(payoff.chip_payouts(Outcome::Chop, 1.0), [13.0, 54.0]);
+    }
+
+    #[test]
+    fn test_audit_flags_each_inconsistency() {
+        let root = Node::Decision {
+            id: 0, infoset_id: 0, player: Player::OOP, street: crate::node::Street::River, parent: None,
+            children: vec![1, 2, 3, 4, 5], actions: vec![], pot: 10.0, stacks: [50.0, 50.0], board: vec![], bet_sequence: vec![],
+        };
+        let mut tree = GameTree { nodes: vec![root] };
+        let mut add = |node: Node| {
+            let id = tree.nodes.len() as NodeId;
+            let Node::Terminal { folder, pot, stacks, board, hole_cards, .. } = node else { unreachable!() };
+            tree.nodes.push(Node::Terminal { id, parent: Some(0), folder, pot, stacks, board, hole_cards });
+        };
+        add(terminal(None, 30.0, [40.0, 40.0]));
+        add(terminal(None, 31.0, [40.0, 40.0]));
+        add(terminal(None, 25.0, [45.0, 40.0]));
+        add(terminal(Some(Player::IP), 20.0, [45.0, 45.0]));
+        add(terminal(None, 130.0, [-10.0, -10.0]));
+        let issues = audit_payoffs(&tree);
+        assert_eq!(issues, vec![
+            PayoffIssue::PotMismatch { node: 2, expected: 30.0, actual: 31.0 },
+            PayoffIssue::UnmatchedShowdown { node: 3 },
+            PayoffIssue::FoldWithoutBet { node: 4 },
+            PayoffIssue::NegativeStack { node: 5, player: Player::IP },
+            PayoffIssue::NegativeStack { node: 5, player: Player::OOP },
+        ]);
+        assert_eq!(issues[0].to_string(), "node 2: pot 31 but root pot plus investments is 30");
+    }
+
+    #[test]
+    fn test_built_trees_pass_the_audit() {
+        let mut config = TreeConfig::new(GameConfig::from_pot(10.0, [40.0, 25.0]), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes { allow_all_in: true, ..StreetSizes::new(vec![0.33, 0.75]) };
+        config.river = StreetSizes { allow_all_in: true, ..StreetSizes::new(vec![0.5, 1.5]) };
+        config.max_raises = 3;
+        let tree = build_tree(&config);
+        assert_eq!(audit_payoffs(&tree), vec![]);
+        // Bets are capped at the shorter stack, so showdowns are matched up to float noise
+        let showdowns = tree.nodes.iter().filter_map(|n| TerminalPayoff::from_node(n, [40.0, 25.0])).filter(|p| p.folder.is_none());
+        assert!(showdowns.into_iter().all(|p| p.uncalled.iter().all(|&u| u < EPSILON)));
+        assert_eq!(audit_payoffs(&crate::test_tree::build_test_tree()), vec![]);
+    }
+}
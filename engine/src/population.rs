@@ -0,0 +1,233 @@
+//! Population frequencies: database stats as node locks or starting priors
+//!
+//! Tracking databases report how often the player pool takes an action on
+//! a line (c-bet, fold to c-bet, check-raise, ...). A population file lists
+//! those stats one per row, comma separated, with `#` starting a comment:
+//!
+//! ```text
+//! # target, action, frequency[, samples]
+//! root, b, 0.62, 1840            # OOP leads 62% (every bet size together)
+//! x, b, 58%, 2210                # IP c-bets after a check
+//! x b75, f, 0.55, 930            # OOP folds to the c-bet
+//! all oop river facing, f, 0.4   # bulk targets work too
+//! ```
+//!
+//! Targets are node-lock targets and actions are `line` tokens, with a bare
+//! `b` or `r` split over every bet or raise (see `nodelock`). Frequencies are
+//! fractions or percentages; `samples` is how many hands the stat comes from
+//! and is optional. Rows sharing a target combine into one lock, and actions
+//! they don't name stay free for the solver.
+//!
+//! `PopulationStats::to_locks` pins the stats for an exploitative solve: the
+//! locked player plays the population's frequencies and the opponent learns
+//! to exploit them. `seed_priors` instead starts a solve from them, seeding
+//! regrets and strategy sums so the early iterations play the population's
+//! lines, without stopping the solver from moving away. Either can skip
+//! stats from fewer than `min_samples` hands.
+
+use std::collections::HashMap;
+use crate::cfr::RegretStorage;
+use crate::node::GameTree;
+use crate::nodelock::{NodeLockError, NodeLocks};
+
+/// One stat from a population file
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopulationRow {
+    /// 1-based line in the file
+    pub line: usize,
+    /// Node-lock target (`root`, `x b75`, `all oop river facing`, ...)
+    pub target: String,
+    /// Action token the frequency is for
+    pub action: String,
+    /// Frequency in `[0, 1]`
+    pub frequency: f64,
+    /// Hands the stat was measured over, if known
+    pub samples: Option<u64>,
+}
+
+/// A parsed population file (see module docs)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PopulationStats {
+    rows: Vec<PopulationRow>,
+}
+
+/// A population row that couldn't be read or applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopulationError {
+    /// 1-based line number in the population file
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for PopulationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PopulationError {}
+
+impl PopulationStats {
+    /// Read a population file (see module docs). A first row whose
+    /// frequency isn't a number is taken as a header and skipped.
+    pub fn parse(text: &str) -> Result<PopulationStats, PopulationError> {
+        let mut rows = Vec::new();
+        for (i, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: String| PopulationError { line: i + 1, message };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [target, action, frequency, rest @ ..] = fields.as_slice() else {
+                return Err(error("expected TARGET, ACTION, FREQUENCY[, SAMPLES]".to_string()));
+            };
+            let frequency = match parse_frequency(frequency) {
+                Some(f) => f,
+                None if rows.is_empty() && !frequency.chars().any(|c| c.is_ascii_digit()) => continue,
+                None => return Err(error(format!("invalid frequency '{}'", frequency))),
+            };
+            if !(0.0..=1.0).contains(&frequency) {
+                return Err(error(format!("frequency {} is outside [0, 1]", frequency)));
+            }
+            let samples = match rest {
+                [] | [""] => None,
+                [s] => Some(s.parse::<u64>().map_err(|_| error(format!("invalid sample count '{}'", s)))?),
+                _ => return Err(error("too many fields".to_string())),
+            };
+            if target.is_empty() || action.is_empty() || action.contains(char::is_whitespace) {
+                return Err(error("expected a target and one action token".to_string()));
+            }
+            rows.push(PopulationRow { line: i + 1, target: target.to_string(), action: action.to_string(), frequency, samples });
+        }
+        Ok(PopulationStats { rows })
+    }
+
+    pub fn rows(&self) -> &[PopulationRow] {
+        &self.rows
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The stats as node-lock file text, one line per target in the order
+    /// targets first appear, skipping stats from fewer than `min_samples`
+    /// hands (stats without a sample count are kept). `NodeLocks::parse`
+    /// reads it, so it can be added to a `SolveSpec::locks` file.
+    pub fn to_lock_text(&self, min_samples: u64) -> String {
+        self.lock_lines(min_samples).iter().map(|(_, text)| format!("{}\n", text)).collect()
+    }
+
+    /// Locks pinning the population's frequencies in `tree` (see
+    /// `to_lock_text`); errors name the population file's line
+    pub fn to_locks(&self, tree: &GameTree, min_samples: u64) -> Result<NodeLocks, PopulationError> {
+        let lines = self.lock_lines(min_samples);
+        let text: String = lines.iter().map(|(_, text)| format!("{}\n", text)).collect();
+        NodeLocks::parse(tree, &text).map_err(|e: NodeLockError| PopulationError { line: lines[e.line - 1].0, message: e.message })
+    }
+
+    /// Seed `storage` (sized for `tree`) so each infoset the stats reach
+    /// starts at the population's frequencies, as if it had played them for
+    /// `strength` iterations: regrets and strategy sums both become
+    /// `strength × frequency`, with actions the stats don't name sharing the
+    /// rest evenly. Returns how many infosets were seeded.
+    pub fn seed_priors(&self, tree: &GameTree, storage: &mut RegretStorage, min_samples: u64, strength: f64) -> Result<usize, PopulationError> {
+        let locks = self.to_locks(tree, min_samples)?;
+        for (infoset, pins) in locks.iter() {
+            let pinned: f64 = pins.iter().flatten().sum();
+            let free = pins.iter().filter(|p| p.is_none()).count();
+            let share = if free > 0 { (1.0 - pinned).max(0.0) / free as f64 } else { 0.0 };
+            let prior: Vec<f64> = pins.iter().map(|p| strength * p.unwrap_or(share)).collect();
+            storage.restore(infoset, prior.clone(), prior);
+        }
+        Ok(locks.len())
+    }
+
+    /// `(first population line, lock line)` per target
+    fn lock_lines(&self, min_samples: u64) -> Vec<(usize, String)> {
+        let mut order: Vec<&str> = Vec::new();
+        let mut groups: HashMap<&str, (usize, Vec<String>)> = HashMap::new();
+        for row in self.rows.iter().filter(|r| r.samples.is_none_or(|n| n >= min_samples)) {
+            let entry = groups.entry(row.target.as_str()).or_insert_with(|| {
+                order.push(row.target.as_str());
+                (row.line, Vec::new())
+            });
+            entry.1.push(format!("{}:{}", row.action, row.frequency));
+        }
+        order.into_iter().map(|target| {
+            let (line, pins) = &groups[target];
+            (*line, format!("{} = {}", target, pins.join(" ")))
+        }).collect()
+    }
+}
+
+/// `0.55` or `55%`
+fn parse_frequency(s: &str) -> Option<f64> {
+    match s.strip_suffix('%') {
+        Some(pct) => pct.trim().parse::<f64>().ok().map(|p| p / 100.0),
+        None => s.parse::<f64>().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::game::GameConfig;
+    use crate::line::Line;
+    use crate::node::{parse_cards, Node};
+
+    fn river_tree() -> GameTree {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c9s").unwrap());
+        config.river = StreetSizes::new(vec![0.5, 1.0]);
+        build_tree(&config)
+    }
+
+    fn infoset(tree: &GameTree, line: &str) -> usize {
+        let id = Line::parse(line).and_then(|l| l.find(tree)).unwrap();
+        tree.get(id).and_then(Node::infoset_id).unwrap() as usize
+    }
+
+    const STATS: &str = "target,action,frequency,samples\n\
+        root, b, 60%, 500\n\
+        x, b, 0.5, 40\n\
+        x b100, f, 0.7   # no sample count\n\
+        x b100, c, 0.2\n";
+
+    #[test]
+    fn test_population_rows_become_locks() {
+        let stats = PopulationStats::parse(STATS).unwrap();
+        assert_eq!(stats.len(), 4);
+        assert_eq!(stats.rows()[0], PopulationRow { line: 2, target: "root".to_string(), action: "b".to_string(), frequency: 0.6, samples: Some(500) });
+        assert_eq!(stats.to_lock_text(100), "root = b:0.6\nx b100 = f:0.7 c:0.2\n");
+
+        let tree = river_tree();
+        let locks = stats.to_locks(&tree, 0).unwrap();
+        assert_eq!(locks.len(), 3);
+        assert_eq!(locks.get(infoset(&tree, "root")), Some(&[None, Some(0.3), Some(0.3)][..]));
+        assert_eq!(locks.get(infoset(&tree, "x b100")).map(|l| l.len()), Some(3));
+
+        let bad = PopulationStats::parse("root, b, 0.5\nx, q, 0.5\n").unwrap();
+        assert_eq!(bad.to_locks(&tree, 0).unwrap_err().line, 2);
+        assert_eq!(PopulationStats::parse("root, b, 1.5").unwrap_err().line, 1);
+        assert!(PopulationStats::parse("root, b").is_err());
+    }
+
+    #[test]
+    fn test_seed_priors_start_from_population_frequencies() {
+        let tree = river_tree();
+        let stats = PopulationStats::parse(STATS).unwrap();
+        let mut storage = RegretStorage::for_tree(&tree);
+        assert_eq!(stats.seed_priors(&tree, &mut storage, 100, 10.0).unwrap(), 2);
+        let root = infoset(&tree, "root");
+        let strategy = storage.current_strategy(root);
+        assert!((strategy[0] - 0.4).abs() < 1e-12 && (strategy[1] - 0.3).abs() < 1e-12);
+        assert_eq!(storage.average_strategy(root), strategy);
+        assert_eq!(storage.strategy_sum(infoset(&tree, "x")), &[0.0, 0.0, 0.0][..]);
+    }
+}
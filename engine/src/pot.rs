@@ -0,0 +1,149 @@
+//! Side-pot construction and award logic for any number of players
+//!
+//! When a player is all-in for less than others have bet, they can only win
+//! the part of the pot they matched; the rest forms side pots contested by
+//! the players who put in more. `side_pots` splits total contributions into
+//! those layers and `award_pots` pays each layer to its best eligible hand.
+//!
+//! The heads-up solver only ever has one contested pot (any unmatched excess
+//! is returned, see `game::terminal_ip_ev`), so this is mainly for multiway
+//! terminals. Players are identified by their index in the input slices.
+
+use crate::node::HandRank;
+
+/// One layer of the pot and the players who can win it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SidePot {
+    /// Chips in this layer (including dead money from folded players)
+    pub amount: f64,
+    /// Indices of the players still in the hand who matched this layer
+    pub eligible: Vec<usize>,
+}
+
+/// Split per-player contributions into a main pot and side pots.
+///
+/// `contributions[i]` is everything player `i` put in this hand and
+/// `folded[i]` marks players who gave up their claim. `dead` is extra money
+/// no player can reclaim (antes or blinds of players not listed); it goes to
+/// the main pot. Pots are returned main pot first. Chips from folded players
+/// above the largest live contribution are added to the last pot, and a
+/// layer only one live player reached is uncalled and returned as a pot that
+/// player alone is eligible for.
+pub fn side_pots(contributions: &[f64], folded: &[bool], dead: f64) -> Vec<SidePot> {
+    assert_eq!(contributions.len(), folded.len(), "one folded flag per player");
+    let mut levels: Vec<f64> = contributions.iter().zip(folded)
+        .filter(|(_, &f)| !f)
+        .map(|(&c, _)| c)
+        .collect();
+    levels.sort_by(|a, b| a.partial_cmp(b).expect("finite contributions"));
+    levels.dedup();
+
+    let mut pots: Vec<SidePot> = Vec::with_capacity(levels.len());
+    let mut prev = 0.0;
+    for &level in &levels {
+        let amount: f64 = contributions.iter().map(|&c| c.min(level) - c.min(prev)).sum();
+        let eligible = (0..contributions.len())
+            .filter(|&i| !folded[i] && contributions[i] >= level)
+            .collect();
+        if amount > 0.0 {
+            pots.push(SidePot { amount, eligible });
+        }
+        prev = level;
+    }
+
+    let leftover: f64 = contributions.iter().map(|&c| (c - prev).max(0.0)).sum();
+    if pots.is_empty() && dead + leftover > 0.0 {
+        // Everyone folded: nobody is eligible, but the chips still exist
+        pots.push(SidePot { amount: 0.0, eligible: Vec::new() });
+    }
+    if let Some(main) = pots.first_mut() {
+        main.amount += dead;
+    }
+    if let Some(last) = pots.last_mut() {
+        last.amount += leftover;
+    }
+    pots
+}
+
+/// Pay each pot to the best hand among its eligible players.
+///
+/// `ranks[i]` is player `i`'s showdown rank (lower is stronger); it is only
+/// read for eligible players. Tied winners split a pot evenly. Returns the
+/// amount each player receives.
+pub fn award_pots(pots: &[SidePot], ranks: &[HandRank]) -> Vec<f64> {
+    let mut payouts = vec![0.0; ranks.len()];
+    for pot in pots {
+        let best = match pot.eligible.iter().map(|&i| ranks[i]).min() {
+            Some(best) => best,
+            None => continue,
+        };
+        let winners: Vec<usize> = pot.eligible.iter().copied().filter(|&i| ranks[i] == best).collect();
+        let share = pot.amount / winners.len() as f64;
+        for i in winners {
+            payouts[i] += share;
+        }
+    }
+    payouts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(pots: &[SidePot]) -> f64 {
+        pots.iter().map(|p| p.amount).sum()
+    }
+
+    #[test]
+    fn test_single_pot_when_everyone_matches() {
+        let pots = side_pots(&[10.0, 10.0, 10.0], &[false, false, false], 1.5);
+        assert_eq!(pots, vec![SidePot { amount: 31.5, eligible: vec![0, 1, 2] }]);
+    }
+
+    #[test]
+    fn test_three_way_all_in_for_different_amounts() {
+        // Short stack all-in for 5, middle for 20, big stack covers with 50
+        let pots = side_pots(&[5.0, 20.0, 50.0], &[false, false, false], 0.0);
+        assert_eq!(pots, vec![
+            SidePot { amount: 15.0, eligible: vec![0, 1, 2] },
+            SidePot { amount: 30.0, eligible: vec![1, 2] },
+            // Uncalled part of the big stack's bet
+            SidePot { amount: 30.0, eligible: vec![2] },
+        ]);
+
+        // Short stack has the best hand: wins only the main pot
+        let ranks = [HandRank::new(10), HandRank::new(500), HandRank::new(900)];
+        assert_eq!(award_pots(&pots, &ranks), vec![15.0, 30.0, 30.0]);
+
+        // Big stack best: scoops everything
+        let ranks = [HandRank::new(900), HandRank::new(500), HandRank::new(10)];
+        assert_eq!(award_pots(&pots, &ranks), vec![0.0, 0.0, 75.0]);
+    }
+
+    #[test]
+    fn test_folded_money_is_dead_but_not_eligible() {
+        // Player 0 bet 8 then folded to a raise; 1 and 2 are all-in for 12 and 30
+        let pots = side_pots(&[8.0, 12.0, 30.0], &[true, false, false], 0.0);
+        assert_eq!(pots, vec![
+            SidePot { amount: 32.0, eligible: vec![1, 2] },
+            SidePot { amount: 18.0, eligible: vec![2] },
+        ]);
+        assert_eq!(total(&pots), 50.0);
+    }
+
+    #[test]
+    fn test_ties_split_each_pot() {
+        let pots = side_pots(&[5.0, 10.0, 10.0], &[false, false, false], 0.0);
+        let ranks = [HandRank::new(100), HandRank::new(100), HandRank::new(100)];
+        let payouts = award_pots(&pots, &ranks);
+        // Main pot 15 split three ways, side pot 10 split two ways
+        assert_eq!(payouts, vec![5.0, 10.0, 10.0]);
+    }
+
+    #[test]
+    fn test_everyone_folded_keeps_dead_money() {
+        let pots = side_pots(&[2.0, 3.0], &[true, true], 1.0);
+        assert_eq!(total(&pots), 6.0);
+        assert!(pots.iter().all(|p| p.eligible.is_empty()));
+    }
+}
@@ -0,0 +1,162 @@
+//! Ready-made spots: common preflop scenarios with ranges and sizing trees
+//!
+//! A new user shouldn't need to know how a 3-bet pot's starting pot is
+//! built or what a reasonable button opening range is before their first
+//! solve. Each `Preset` bundles the preflop result (`GameConfig`), both
+//! ranges, the bet sizes per street, and a default flop; `Preset::spec`
+//! turns it into a `SolveSpec` for that flop or another one.
+//!
+//! | Name           | Spot                                              |
+//! |----------------|---------------------------------------------------|
+//! | `srp-btn-bb`   | 100bb cash, BTN opens 2.5bb, BB calls             |
+//! | `3bp-co-btn`   | 100bb cash, CO opens, BTN 3-bets to 7.5bb, CO calls |
+//! | `mtt-bvb-40bb` | 40bb MTT with a 1bb BB ante, SB opens 2bb, BB calls |
+//!
+//! Ranges are approximations of common solver outputs, not solved
+//! preflop strategies.
+
+use crate::builder::StreetSizes;
+use crate::game::{GameConfig, PostedBlind};
+use crate::node::{parse_cards, Card, Player};
+use crate::range::Range;
+use crate::solve::SolveSpec;
+
+/// One ready-made spot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    /// Name used on the command line (`oracle solve --preset NAME`)
+    pub name: &'static str,
+    pub description: &'static str,
+    pub game: GameConfig,
+    /// Ranges in `Range::parse` notation, indexed `[IP, OOP]`
+    pub ranges: [&'static str; 2],
+    pub flop: StreetSizes,
+    pub turn: StreetSizes,
+    pub river: StreetSizes,
+    pub max_raises: usize,
+    /// The preflop raiser
+    pub initial_aggressor: Player,
+    /// Flop used when `spec` isn't given one
+    pub board: &'static str,
+}
+
+impl Preset {
+    /// Solve spec for `board`, or the preset's default flop
+    pub fn spec(&self, board: Option<Vec<Card>>) -> SolveSpec {
+        let board = board.unwrap_or_else(|| parse_cards(self.board).expect("preset board parses"));
+        let ranges = self.ranges.map(|r| Range::parse(r).expect("preset range parses"));
+        let mut spec = SolveSpec::new(board, ranges, self.game.clone());
+        spec.tree_config.flop = self.flop.clone();
+        spec.tree_config.turn = self.turn.clone();
+        spec.tree_config.river = self.river.clone();
+        spec.tree_config.max_raises = self.max_raises;
+        spec.tree_config.initial_aggressor = Some(self.initial_aggressor);
+        spec
+    }
+}
+
+/// Every preset, in the order of the table in the module docs
+pub fn all() -> Vec<Preset> {
+    vec![srp_btn_bb(), three_bet_co_btn(), mtt_bvb_40bb()]
+}
+
+/// The preset called `name`
+pub fn find(name: &str) -> Option<Preset> {
+    all().into_iter().find(|p| p.name == name)
+}
+
+/// Sizes with an all-in added
+fn with_all_in(sizes: StreetSizes) -> StreetSizes {
+    StreetSizes { allow_all_in: true, ..sizes }
+}
+
+fn srp_btn_bb() -> Preset {
+    Preset {
+        name: "srp-btn-bb",
+        description: "100bb cash single-raised pot, BTN (IP) vs BB (OOP)",
+        game: GameConfig::new(),
+        ranges: [
+            "22+,A2s+,K2s+,Q4s+,J6s+,T6s+,96s+,86s+,75s+,64s+,54s,A2o+,K8o+,Q9o+,J9o+,T9o",
+            "22-JJ,A2s-AJs,K2s-KJs,Q2s+,J4s+,T6s+,96s+,85s+,75s+,64s+,53s+,43s,A2o-AJo,K7o-KQo,Q9o+,J9o+,T8o+,98o",
+        ],
+        flop: StreetSizes::new(vec![0.33, 0.75]),
+        turn: StreetSizes::new(vec![0.66]),
+        river: with_all_in(StreetSizes::new(vec![0.75])),
+        max_raises: 2,
+        initial_aggressor: Player::IP,
+        board: "Ks8d3c",
+    }
+}
+
+fn three_bet_co_btn() -> Preset {
+    Preset {
+        name: "3bp-co-btn",
+        description: "100bb cash 3-bet pot, BTN (IP) 3-bets CO (OOP)",
+        game: GameConfig { posted: [PostedBlind::None, PostedBlind::None], preflop_contribution: 7.5, ..GameConfig::new() },
+        ranges: [
+            "TT+,AQs+,AKo,A5s-A3s,KJs+,QJs,JTs:0.5,76s:0.5,65s:0.5",
+            "99-JJ,ATs-AQs,KJs+,QJs,JTs,T9s,98s,87s,AQo,KQo:0.5",
+        ],
+        flop: StreetSizes::new(vec![0.25, 0.66]),
+        turn: StreetSizes::new(vec![0.66]),
+        river: with_all_in(StreetSizes::new(vec![0.75])),
+        max_raises: 2,
+        initial_aggressor: Player::IP,
+        board: "Jh7s4d",
+    }
+}
+
+fn mtt_bvb_40bb() -> Preset {
+    Preset {
+        name: "mtt-bvb-40bb",
+        description: "40bb MTT blind vs blind with a BB ante, SB (OOP) opens, BB (IP) calls",
+        game: GameConfig {
+            ante: 1.0,
+            big_blind_ante: true,
+            table_size: 8,
+            posted: [PostedBlind::Big, PostedBlind::Small],
+            preflop_contribution: 2.0,
+            starting_stack: [40.0, 40.0],
+            ..GameConfig::new()
+        },
+        ranges: [
+            "22-99,A2s-AJs,K2s+,Q2s+,J2s+,T4s+,95s+,85s+,74s+,63s+,53s+,43s,A2o-AJo,K2o+,Q5o+,J7o+,T7o+,97o+,87o,76o",
+            "22+,A2s+,K2s+,Q2s+,J4s+,T6s+,96s+,85s+,75s+,64s+,54s,A2o+,K5o+,Q8o+,J8o+,T8o+,98o",
+        ],
+        flop: StreetSizes::new(vec![0.33]),
+        turn: StreetSizes::new(vec![0.66]),
+        river: with_all_in(StreetSizes::new(vec![0.75])),
+        max_raises: 2,
+        initial_aggressor: Player::OOP,
+        board: "Qd9c5h",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_build_valid_specs() {
+        let names: Vec<&str> = all().iter().map(|p| p.name).collect();
+        assert_eq!(names, ["srp-btn-bb", "3bp-co-btn", "mtt-bvb-40bb"]);
+        for preset in all() {
+            assert_eq!(preset.game.validate(), Ok(()), "{}", preset.name);
+            let spec = preset.spec(None);
+            let mut config = spec.tree_config.clone();
+            config.board = spec.board.clone();
+            assert_eq!(config.validate(), Ok(()), "{}", preset.name);
+            assert!(spec.ranges.iter().all(|r| r.live_combos(&spec.board).next().is_some()), "{}", preset.name);
+        }
+
+        let srp = find("srp-btn-bb").unwrap().spec(None);
+        assert_eq!((srp.tree_config.game.initial_pot(), srp.tree_config.game.postflop_stacks()), (5.5, [97.5, 97.5]));
+        let three_bet = find("3bp-co-btn").unwrap().spec(None);
+        assert_eq!((three_bet.tree_config.game.initial_pot(), three_bet.tree_config.game.postflop_stacks()), (16.5, [92.5, 92.5]));
+        let bvb = find("mtt-bvb-40bb").unwrap().spec(Some(parse_cards("As8h2c").unwrap()));
+        assert_eq!(bvb.board, parse_cards("As8h2c").unwrap());
+        // BB pays the ante: 2bb each plus the 1bb ante, and 1bb less behind for BB
+        assert_eq!((bvb.tree_config.game.initial_pot(), bvb.tree_config.game.postflop_stacks()), (5.0, [37.0, 38.0]));
+        assert_eq!(find("srp-utg-bb"), None);
+    }
+}
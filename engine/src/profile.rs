@@ -0,0 +1,227 @@
+//! Opt-in solve profiling: where the time goes
+//!
+//! With `SolverConfig::profile` set, `CfrSolver` times each phase of every
+//! iteration (traversal, applying regret updates, exploitability checks)
+//! plus terminal evaluation before the solve, and splits traversal time by
+//! street and depth. `CfrSolver::profile_report` returns the totals; its
+//! `Display` prints the breakdown.
+//!
+//! Street and depth times are thread time spent at decision and terminal
+//! nodes, excluding their children, summed over Rayon workers. Chance nodes
+//! only fan out, so they aren't timed. With several threads these sums
+//! exceed the traversal's wall time; compare them with each other.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use crate::node::{GameTree, Node, NodeId, Street};
+
+/// Accumulated phase times (nanoseconds) shared by traversal threads
+#[derive(Debug)]
+pub(crate) struct Profiler {
+    traversal: AtomicU64,
+    apply: AtomicU64,
+    exploitability: AtomicU64,
+    terminal_eval: AtomicU64,
+    iterations: AtomicU64,
+    /// Per street: `[flop, turn, river]`
+    street_time: [AtomicU64; 3],
+    street_visits: [AtomicU64; 3],
+    terminal_time: AtomicU64,
+    terminal_visits: AtomicU64,
+    depth_time: Vec<AtomicU64>,
+    /// Depth of each node, indexed by `NodeId`
+    depth: Vec<u32>,
+}
+
+impl Profiler {
+    pub(crate) fn new(tree: &GameTree) -> Self {
+        // Parents precede children, so one pass fills depths
+        let mut depth = vec![0u32; tree.len()];
+        for node in &tree.nodes {
+            let d = depth[node.id() as usize];
+            for &child in node.children() {
+                depth[child as usize] = d + 1;
+            }
+        }
+        let max_depth = depth.iter().copied().max().unwrap_or(0) as usize;
+        Profiler {
+            traversal: AtomicU64::new(0),
+            apply: AtomicU64::new(0),
+            exploitability: AtomicU64::new(0),
+            terminal_eval: AtomicU64::new(0),
+            iterations: AtomicU64::new(0),
+            street_time: Default::default(),
+            street_visits: Default::default(),
+            terminal_time: AtomicU64::new(0),
+            terminal_visits: AtomicU64::new(0),
+            depth_time: (0..=max_depth).map(|_| AtomicU64::new(0)).collect(),
+            depth,
+        }
+    }
+
+    pub(crate) fn add_iteration(&self, traversal: Duration, apply: Duration) {
+        self.iterations.fetch_add(1, Ordering::Relaxed);
+        add(&self.traversal, traversal);
+        add(&self.apply, apply);
+    }
+
+    pub(crate) fn add_exploitability(&self, elapsed: Duration) {
+        add(&self.exploitability, elapsed);
+    }
+
+    pub(crate) fn add_terminal_eval(&self, elapsed: Duration) {
+        add(&self.terminal_eval, elapsed);
+    }
+
+    /// Time spent at `node` itself (children excluded)
+    pub(crate) fn add_node(&self, tree: &GameTree, node: NodeId, elapsed: Duration) {
+        match tree.get(node) {
+            Some(Node::Decision { street, .. }) => {
+                let s = street_index(*street);
+                add(&self.street_time[s], elapsed);
+                self.street_visits[s].fetch_add(1, Ordering::Relaxed);
+            }
+            Some(Node::Terminal { .. }) => {
+                add(&self.terminal_time, elapsed);
+                self.terminal_visits.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+        if let Some(d) = self.depth.get(node as usize) {
+            add(&self.depth_time[*d as usize], elapsed);
+        }
+    }
+
+    pub(crate) fn report(&self) -> ProfileReport {
+        let load = |a: &AtomicU64| Duration::from_nanos(a.load(Ordering::Relaxed));
+        ProfileReport {
+            iterations: self.iterations.load(Ordering::Relaxed),
+            traversal: load(&self.traversal),
+            regret_update: load(&self.apply),
+            exploitability: load(&self.exploitability),
+            terminal_eval: load(&self.terminal_eval),
+            street_time: [0, 1, 2].map(|s| load(&self.street_time[s])),
+            street_visits: [0, 1, 2].map(|s| self.street_visits[s].load(Ordering::Relaxed)),
+            terminal_time: load(&self.terminal_time),
+            terminal_visits: self.terminal_visits.load(Ordering::Relaxed),
+            depth_time: self.depth_time.iter().map(load).collect(),
+        }
+    }
+}
+
+fn add(counter: &AtomicU64, elapsed: Duration) {
+    counter.fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+}
+
+fn street_index(street: Street) -> usize {
+    match street {
+        Street::Flop => 0,
+        Street::Turn => 1,
+        Street::River => 2,
+    }
+}
+
+/// Where a profiled solve spent its time
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileReport {
+    pub iterations: u64,
+    /// Wall time in tree traversal
+    pub traversal: Duration,
+    /// Wall time applying regret and strategy updates
+    pub regret_update: Duration,
+    /// Wall time in exploitability checks
+    pub exploitability: Duration,
+    /// Wall time valuing terminals before the first iteration
+    pub terminal_eval: Duration,
+    /// Thread time at decision nodes per street, `[flop, turn, river]`
+    pub street_time: [Duration; 3],
+    /// Decision-node visits per street
+    pub street_visits: [u64; 3],
+    /// Thread time reading terminal values during traversal
+    pub terminal_time: Duration,
+    pub terminal_visits: u64,
+    /// Thread time at decision and terminal nodes, indexed by depth
+    pub depth_time: Vec<Duration>,
+}
+
+impl ProfileReport {
+    /// Sum of the wall-time phases
+    pub fn total(&self) -> Duration {
+        self.traversal + self.regret_update + self.exploitability + self.terminal_eval
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total().as_secs_f64().max(f64::MIN_POSITIVE);
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        writeln!(f, "Profile ({} iterations, {:.1} ms)", self.iterations, ms(self.total()))?;
+        for (label, d) in [
+            ("traversal", self.traversal),
+            ("regret update", self.regret_update),
+            ("exploitability", self.exploitability),
+            ("terminal eval", self.terminal_eval),
+        ] {
+            writeln!(f, "  {:<16} {:>10.1} ms {:>5.1}%", label, ms(d), 100.0 * d.as_secs_f64() / total)?;
+        }
+
+        let node_time: f64 = self.depth_time.iter().map(Duration::as_secs_f64).sum::<f64>().max(f64::MIN_POSITIVE);
+        writeln!(f, "Traversal by street (thread time)")?;
+        let streets = ["flop", "turn", "river"].into_iter().zip(self.street_time).zip(self.street_visits);
+        for ((label, d), visits) in streets.filter(|(_, visits)| *visits > 0) {
+            writeln!(f, "  {:<16} {:>10.1} ms {:>5.1}%  {} visits", label, ms(d), 100.0 * d.as_secs_f64() / node_time, visits)?;
+        }
+        writeln!(
+            f,
+            "  {:<16} {:>10.1} ms {:>5.1}%  {} visits",
+            "terminals", ms(self.terminal_time), 100.0 * self.terminal_time.as_secs_f64() / node_time, self.terminal_visits,
+        )?;
+        writeln!(f, "Traversal by depth (thread time)")?;
+        for (depth, d) in self.depth_time.iter().enumerate().filter(|(_, d)| !d.is_zero()) {
+            writeln!(f, "  depth {:<10} {:>10.1} ms {:>5.1}%", depth, ms(*d), 100.0 * d.as_secs_f64() / node_time)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::cfr::CfrSolver;
+    use crate::config::SolverConfig;
+    use crate::game::{build_terminal_evs, GameConfig};
+    use crate::node::{parse_cards, Node, Street};
+    use std::time::Duration;
+
+    #[test]
+    fn test_profile_counts_every_visit() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.5]);
+        config.max_raises = 1;
+        let tree = build_tree(&config);
+        let decisions = |street| tree.nodes.iter().filter(|n| matches!(n, Node::Decision { street: s, .. } if *s == street)).count() as u64;
+        let (turn, river) = (decisions(Street::Turn), decisions(Street::River));
+        let terminals = tree.stats().terminals as u64;
+        let max_depth = tree.stats().max_depth;
+        let evs = build_terminal_evs(&tree, |_| 0.5);
+
+        let unprofiled = CfrSolver::with_config(tree.clone(), evs.clone(), SolverConfig::new());
+        assert_eq!(unprofiled.profile_report(), None);
+
+        let mut solver = CfrSolver::with_config(tree, evs, SolverConfig::builder().profile(true).build().unwrap());
+        for _ in 0..3 {
+            solver.run_iteration();
+        }
+        solver.exploitability(Duration::ZERO);
+        let report = solver.profile_report().unwrap();
+        assert_eq!(report.iterations, 3);
+        assert_eq!(report.street_visits, [0, 3 * turn, 3 * river]);
+        assert_eq!(report.terminal_visits, 3 * terminals);
+        assert_eq!(report.depth_time.len(), max_depth + 1);
+        assert!(report.traversal > Duration::ZERO && report.exploitability > Duration::ZERO);
+        let text = report.to_string();
+        assert!(text.contains("regret update") && text.contains("river") && !text.contains("flop"), "{}", text);
+    }
+}
@@ -0,0 +1,200 @@
+//! Reach-probability pruning of solved trees
+//!
+//! Most of a solved tree is lines the average strategy almost never plays.
+//! `prune_by_reach` drops every action whose reach probability falls below
+//! a threshold, leaving a smaller tree + solution pair for export and
+//! exploration.
+//!
+//! Reach is the product of both players' average-strategy frequencies along
+//! the line (`node_reach`). Chance probabilities are left out, so a river
+//! node reached by always-played actions has reach 1.0 whatever card came,
+//! and runouts are never pruned. Since reach only shrinks down a line, an
+//! action below the threshold takes its whole subtree with it.
+//!
+//! Actions are kept or dropped per infoset, so nodes sharing an infoset keep
+//! identical action lists: an action stays if it clears the threshold at any
+//! of them. Every decision keeps at least its most-played action. The pruned
+//! solution's storage keeps the surviving actions' regrets and strategy
+//! sums, with infosets renumbered densely in the new tree's node order.
+
+use std::collections::HashMap;
+use crate::cfr::{RegretStorage, StorageSnapshot};
+use crate::node::{GameTree, Node, NodeId};
+use crate::solution::Solution;
+
+/// A pruned solution and how its nodes relate to the original's
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrunedSolution {
+    pub solution: Solution,
+    /// `node_map[old id]` is the node's id in the pruned tree, `None` if removed
+    pub node_map: Vec<Option<NodeId>>,
+    /// Decision-node actions removed, counted per node
+    pub removed_actions: usize,
+}
+
+/// Reach probability of every node, indexed by node ID, when both players
+/// play the average strategy. Chance nodes pass their reach through to
+/// every child unchanged (see module docs).
+pub fn node_reach(tree: &GameTree, storage: &RegretStorage) -> Vec<f64> {
+    let mut reach = vec![0.0; tree.len()];
+    if tree.is_empty() {
+        return reach;
+    }
+    let mut stack = vec![(0 as NodeId, 1.0)];
+    while let Some((id, r)) = stack.pop() {
+        reach[id as usize] = r;
+        match tree.get(id).expect("invalid node id") {
+            Node::Decision { children, infoset_id, .. } => {
+                let probs = storage.average_strategy(*infoset_id as usize);
+                stack.extend(children.iter().zip(&probs).map(|(&c, &p)| (c, r * p)));
+            }
+            Node::Chance { children, .. } => stack.extend(children.iter().map(|&c| (c, r))),
+            Node::Terminal { .. } => {}
+        }
+    }
+    reach
+}
+
+/// Drop every action whose reach under the average strategy is below
+/// `threshold` (see module docs). A threshold of 0.0 keeps everything.
+pub fn prune_by_reach(solution: &Solution, threshold: f64) -> PrunedSolution {
+    let storage = RegretStorage::from_snapshot(solution.storage.clone());
+    let reach = node_reach(&solution.tree, &storage);
+
+    // Per-infoset mask of surviving actions
+    let mut keep: HashMap<usize, Vec<bool>> = HashMap::new();
+    for node in &solution.tree.nodes {
+        let Node::Decision { infoset_id, children, .. } = node else { continue };
+        let mask = keep.entry(*infoset_id as usize).or_insert_with(|| vec![false; children.len()]);
+        for (k, &child) in mask.iter_mut().zip(children) {
+            *k |= reach[child as usize] >= threshold;
+        }
+    }
+    for (&infoset, mask) in keep.iter_mut() {
+        if !mask.iter().any(|&k| k) {
+            let probs = storage.average_strategy(infoset);
+            let best = (0..probs.len()).fold(0, |best, a| if probs[a] > probs[best] { a } else { best });
+            mask[best] = true;
+        }
+    }
+    remove_actions(solution, &solution.storage, &keep)
+}
+
+/// `solution` without the actions `keep` masks out per infoset (and their
+/// subtrees), storage rows taken from `storage` and filtered to match
+pub(crate) fn remove_actions(solution: &Solution, storage: &StorageSnapshot, keep: &HashMap<usize, Vec<bool>>) -> PrunedSolution {
+    let mut tree = solution.tree.clone();
+    for node in &mut tree.nodes {
+        let Node::Decision { infoset_id, children, actions, .. } = node else { continue };
+        let mask = &keep[&(*infoset_id as usize)];
+        *children = children.iter().zip(mask).filter(|(_, &k)| k).map(|(&c, _)| c).collect();
+        *actions = actions.iter().zip(mask).filter(|(_, &k)| k).map(|(&a, _)| a).collect();
+    }
+    let node_map = tree.reindex();
+    let removed_actions = solution.tree.nodes.iter()
+        .filter(|n| node_map[n.id() as usize].is_some())
+        .filter_map(|n| n.infoset_id())
+        .map(|id| keep[&(id as usize)].iter().filter(|&&k| !k).count())
+        .sum();
+
+    // Renumber surviving infosets in node order and carry their rows over
+    let mut infoset_map: HashMap<usize, usize> = HashMap::new();
+    let mut snapshot = StorageSnapshot { regrets: Vec::new(), strategy_sums: Vec::new(), last_regrets: Vec::new() };
+    for node in &mut tree.nodes {
+        let Node::Decision { infoset_id, .. } = node else { continue };
+        let old = *infoset_id as usize;
+        let new = *infoset_map.entry(old).or_insert_with(|| {
+            let mask = &keep[&old];
+            let filter = |row: &[f64]| row.iter().zip(mask).filter(|(_, &k)| k).map(|(&x, _)| x).collect::<Vec<f64>>();
+            snapshot.regrets.push(filter(&storage.regrets[old]));
+            snapshot.strategy_sums.push(filter(&storage.strategy_sums[old]));
+            snapshot.regrets.len() - 1
+        });
+        *infoset_id = new as _;
+    }
+
+    let mut omitted: Vec<usize> = solution.omitted.iter().filter_map(|i| infoset_map.get(i).copied()).collect();
+    omitted.sort_unstable();
+
+    let mut terminal_evs: Vec<(NodeId, f64)> = solution.terminal_evs.iter()
+        .filter_map(|&(id, ev)| node_map.get(id as usize).copied().flatten().map(|new| (new, ev)))
+        .collect();
+    terminal_evs.sort_by_key(|&(id, _)| id);
+
+    PrunedSolution {
+        solution: Solution {
+            schema_version: solution.schema_version,
+            tree,
+            storage: snapshot,
+            terminal_evs,
+            iterations: solution.iterations,
+            algorithm: solution.algorithm,
+            ranges: solution.ranges.clone(),
+            config_fingerprint: solution.config_fingerprint,
+            omitted,
+        },
+        node_map,
+        removed_actions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfr::CfrSolver;
+    use crate::test_tree::build_test_tree;
+
+    fn solved() -> Solution {
+        let mut solver = CfrSolver::new(build_test_tree());
+        for _ in 0..10_000 {
+            solver.run_iteration();
+        }
+        Solution::from_solver(&solver)
+    }
+
+    #[test]
+    fn test_node_reach_multiplies_frequencies() {
+        let solution = solved();
+        let storage = RegretStorage::from_snapshot(solution.storage.clone());
+        let reach = node_reach(&solution.tree, &storage);
+        assert_eq!(reach[0], 1.0);
+        for node in &solution.tree.nodes {
+            if let Node::Decision { id, children, infoset_id, .. } = node {
+                let probs = storage.average_strategy(*infoset_id as usize);
+                for (&c, p) in children.iter().zip(probs) {
+                    assert!((reach[c as usize] - reach[*id as usize] * p).abs() < 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_prune_drops_rare_lines() {
+        let solution = solved();
+        let everything = prune_by_reach(&solution, 0.0);
+        assert_eq!((everything.solution.tree.len(), everything.removed_actions), (solution.tree.len(), 0));
+
+        let pruned = prune_by_reach(&solution, 0.01);
+        assert!(pruned.solution.validate().is_ok());
+        assert!(pruned.solution.tree.len() < solution.tree.len());
+        assert!(pruned.removed_actions > 0);
+        // OOP (almost) never checks the root, so the check line is gone
+        assert_eq!(pruned.node_map[1], None);
+        let root = pruned.solution.tree.get(0).unwrap();
+        assert_eq!(root.children().len(), 1);
+        // Surviving nodes keep their terminal EVs and strategies
+        let new_evs = pruned.solution.terminal_ev_map();
+        for &(old, ev) in &solution.terminal_evs {
+            if let Some(new) = pruned.node_map[old as usize] {
+                assert_eq!(new_evs[&new], ev);
+            }
+        }
+        let resumed = pruned.solution.clone().into_solver().unwrap();
+        assert_eq!(resumed.storage.average_strategy(0), vec![1.0]);
+
+        // Every decision keeps its most-played action however high the bar
+        let bare = prune_by_reach(&solution, 2.0);
+        assert!(bare.solution.validate().is_ok());
+        assert!(bare.solution.tree.nodes.iter().all(|n| !matches!(n, Node::Decision { .. }) || n.children().len() == 1));
+    }
+}
@@ -0,0 +1,548 @@
+//! Hand ranges: per-combo weights over the 1326 hole-card combinations
+//!
+//! A `Range` stores one weight in [0.0, 1.0] per two-card combo, so partial
+//! frequencies ("AKo at 50%") are first-class. Set algebra is weighted:
+//! union takes the max, intersection the min, and subtraction clamps at zero.
+//!
+//! Ranges parse from standard notation: `"22+,A2s+,KTo+,QJs:0.5,AhKh,T9s-65s"`.
+//! A `:w` suffix sets the weight of every combo produced by that token.
+//!
+//! `Range::to_notation` goes the other way, writing the most compact tokens
+//! that parse back to the same weights (spans, `+` forms, `AK` for both
+//! suitednesses, single combos for classes with mixed weights), e.g. to
+//! paste a solver's continuing range into another tool.
+
+use crate::combo::{class_of_combo, combo_cards, combo_index, NUM_CLASSES};
+use crate::node::{parse_rank, parse_suit, Card, RANK_CHARS};
+
+pub use crate::combo::NUM_COMBOS;
+
+/// Error returned when range notation cannot be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeParseError {
+    /// A token that matches none of the supported forms
+    InvalidToken(String),
+    /// A `:weight` suffix that is not a number in [0, 1]
+    InvalidWeight(String),
+}
+
+impl std::fmt::Display for RangeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RangeParseError::InvalidToken(t) => write!(f, "invalid range token '{}'", t),
+            RangeParseError::InvalidWeight(w) => write!(f, "invalid range weight '{}'", w),
+        }
+    }
+}
+
+impl std::error::Error for RangeParseError {}
+
+/// Summary statistics of a range, optionally after board card removal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeSummary {
+    /// Weighted combo count (sum of weights)
+    pub combos: f64,
+    /// Number of combos with non-zero weight
+    pub distinct_combos: usize,
+    /// Weighted combos that are pocket pairs
+    pub pairs: f64,
+    /// Weighted combos that are suited non-pairs
+    pub suited: f64,
+    /// Weighted combos that are offsuit non-pairs
+    pub offsuit: f64,
+}
+
+/// Weighted hand range over all 1326 hole-card combos
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    /// weights[combo_index] in [0.0, 1.0]
+    weights: Vec<f64>,
+}
+
+impl Range {
+    /// Create an empty range (every weight 0)
+    pub fn new() -> Self {
+        Range { weights: vec![0.0; NUM_COMBOS] }
+    }
+
+    /// Create a range containing every combo at weight 1
+    pub fn full() -> Self {
+        Range { weights: vec![1.0; NUM_COMBOS] }
+    }
+
+    /// Range from one weight per combo in combo-index order, e.g. a preflop
+    /// solution's frequencies; weights are clamped to [0, 1]
+    pub fn from_weights(weights: &[f64]) -> Self {
+        assert_eq!(weights.len(), NUM_COMBOS, "weights must have one entry per combo");
+        Range { weights: weights.iter().map(|w| w.clamp(0.0, 1.0)).collect() }
+    }
+
+    /// Range giving every combo its preflop class's weight (`class_index`
+    /// order, as in a 13x13 chart); weights are clamped to [0, 1]
+    pub fn from_class_weights(weights: &[f64]) -> Self {
+        assert_eq!(weights.len(), NUM_CLASSES, "weights must have one entry per class");
+        Range { weights: (0..NUM_COMBOS).map(|idx| weights[class_of_combo(idx)].clamp(0.0, 1.0)).collect() }
+    }
+
+    /// Parse standard range notation (see module docs)
+    pub fn parse(s: &str) -> Result<Self, RangeParseError> {
+        let mut range = Range::new();
+        for raw in s.split(',') {
+            let token = raw.trim();
+            if token.is_empty() {
+                continue;
+            }
+            let (hands, weight) = match token.split_once(':') {
+                Some((h, w)) => {
+                    let weight: f64 = w.trim().parse()
+                        .map_err(|_| RangeParseError::InvalidWeight(w.to_string()))?;
+                    if !(0.0..=1.0).contains(&weight) {
+                        return Err(RangeParseError::InvalidWeight(w.to_string()));
+                    }
+                    (h.trim(), weight)
+                }
+                None => (token, 1.0),
+            };
+            let combos = parse_token(hands)
+                .ok_or_else(|| RangeParseError::InvalidToken(token.to_string()))?;
+            for [a, b] in combos {
+                range.weights[combo_index(a, b)] = weight;
+            }
+        }
+        Ok(range)
+    }
+
+    /// Weight of a specific combo
+    pub fn weight(&self, hand: [Card; 2]) -> f64 {
+        self.weights[combo_index(hand[0], hand[1])]
+    }
+
+    /// Set the weight of a specific combo (clamped to [0, 1])
+    pub fn set_weight(&mut self, hand: [Card; 2], weight: f64) {
+        self.weights[combo_index(hand[0], hand[1])] = weight.clamp(0.0, 1.0);
+    }
+
+    /// All weights in combo-index order
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// True if every weight is zero
+    pub fn is_empty(&self) -> bool {
+        self.weights.iter().all(|&w| w <= 0.0)
+    }
+
+    /// Weighted union: per-combo maximum
+    pub fn union(&self, other: &Range) -> Range {
+        self.zip_with(other, f64::max)
+    }
+
+    /// Weighted intersection: per-combo minimum
+    pub fn intersect(&self, other: &Range) -> Range {
+        self.zip_with(other, f64::min)
+    }
+
+    /// Weighted difference: per-combo `max(0, self - other)`
+    pub fn subtract(&self, other: &Range) -> Range {
+        self.zip_with(other, |a, b| (a - b).max(0.0))
+    }
+
+    /// Multiply every weight by `factor`, clamping the result to [0, 1]
+    pub fn scale(&self, factor: f64) -> Range {
+        Range { weights: self.weights.iter().map(|&w| (w * factor).clamp(0.0, 1.0)).collect() }
+    }
+
+    /// Weighted combo count ignoring board removal
+    pub fn combo_count(&self) -> f64 {
+        self.weights.iter().sum()
+    }
+
+    /// Iterate over combos with non-zero weight that do not conflict with `board`.
+    ///
+    /// Yields `(hole cards, weight)` in combo-index order.
+    pub fn live_combos<'a>(&'a self, board: &[Card]) -> impl Iterator<Item = ([Card; 2], f64)> + 'a {
+        let dead = board.iter().fold(0u64, |m, c| m | c.mask());
+        self.weights.iter().enumerate().filter_map(move |(idx, &w)| {
+            if w <= 0.0 {
+                return None;
+            }
+            let hand = combo_cards(idx);
+            if (hand[0].mask() | hand[1].mask()) & dead != 0 {
+                return None;
+            }
+            Some((hand, w))
+        })
+    }
+
+    /// Combo counts by hand class after removing combos blocked by `board`
+    pub fn summary(&self, board: &[Card]) -> RangeSummary {
+        let mut summary = RangeSummary { combos: 0.0, distinct_combos: 0, pairs: 0.0, suited: 0.0, offsuit: 0.0 };
+        for (hand, w) in self.live_combos(board) {
+            summary.combos += w;
+            summary.distinct_combos += 1;
+            if hand[0].rank() == hand[1].rank() {
+                summary.pairs += w;
+            } else if hand[0].suit() == hand[1].suit() {
+                summary.suited += w;
+            } else {
+                summary.offsuit += w;
+            }
+        }
+        summary
+    }
+
+    /// Compact notation for the combos weighted at least `threshold`
+    /// (see module docs). Only combos `board` doesn't block are considered,
+    /// so a class the board cuts down still prints as one token. Weights are
+    /// rounded to three decimals; below 1 they get a `:w` suffix.
+    pub fn to_notation(&self, board: &[Card], threshold: f64) -> String {
+        let dead = board.iter().fold(0u64, |m, c| m | c.mask());
+        let live = |hand: &[Card; 2]| (hand[0].mask() | hand[1].mask()) & dead == 0;
+        let weight = |hand: [Card; 2]| {
+            let w = self.weight(hand);
+            if w >= threshold { (w * 1000.0).round() / 1000.0 } else { 0.0 }
+        };
+        // One weight shared by every live combo of a class, `None` if they differ
+        let uniform = |hi: u8, lo: u8, suit: Suitedness| {
+            let mut weights = class_combos(hi, lo, suit).into_iter().filter(live).map(weight);
+            let first = weights.next().unwrap_or(0.0);
+            weights.all(|w| w == first).then_some(first)
+        };
+        let rank = |r: u8| RANK_CHARS[r as usize];
+        let suffix = |w: f64| if w >= 1.0 { String::new() } else { format!(":{}", w) };
+
+        let mut tokens = Vec::new();
+        let mut mixed = Vec::new();
+        let pairs: Vec<_> = (0..13u8).rev().map(|r| (r, uniform(r, r, Suitedness::Any))).collect();
+        mixed.extend(pairs.iter().filter(|(_, w)| w.is_none()).map(|&(r, _)| (r, r, Suitedness::Any)));
+        for (top, bottom, w) in weight_runs(&pairs) {
+            let token = match (top, bottom) {
+                (t, b) if t == b => format!("{}{}", rank(t), rank(t)),
+                (12, b) => format!("{}{}+", rank(b), rank(b)),
+                (t, b) => format!("{}{}-{}{}", rank(t), rank(t), rank(b), rank(b)),
+            };
+            tokens.push(token + &suffix(w));
+        }
+        let mut by_suit = [Vec::new(), Vec::new(), Vec::new()];
+        for hi in (1..13u8).rev() {
+            let mut kickers = [Vec::new(), Vec::new(), Vec::new()];
+            for lo in (0..hi).rev() {
+                let (suited, offsuit) = (uniform(hi, lo, Suitedness::Suited), uniform(hi, lo, Suitedness::Offsuit));
+                let both = match (suited, offsuit) {
+                    (Some(s), Some(o)) if s == o && s > 0.0 => Some(s),
+                    _ => None,
+                };
+                match both {
+                    Some(w) => kickers[0].push((lo, Some(w))),
+                    None => {
+                        kickers[0].push((lo, Some(0.0)));
+                        kickers[1].push((lo, suited));
+                        kickers[2].push((lo, offsuit));
+                    }
+                }
+                if suited.is_none() {
+                    mixed.push((hi, lo, Suitedness::Suited));
+                }
+                if offsuit.is_none() {
+                    mixed.push((hi, lo, Suitedness::Offsuit));
+                }
+            }
+            for (k, (kickers, tag)) in kickers.iter().zip(["", "s", "o"]).enumerate() {
+                for (top, bottom, w) in weight_runs(kickers) {
+                    let token = match (top, bottom) {
+                        (t, b) if t == b => format!("{}{}{}", rank(hi), rank(t), tag),
+                        (t, b) if t == hi - 1 => format!("{}{}{}+", rank(hi), rank(b), tag),
+                        (t, b) => format!("{}{}{}-{}{}{}", rank(hi), rank(t), tag, rank(hi), rank(b), tag),
+                    };
+                    by_suit[k].push(token + &suffix(w));
+                }
+            }
+        }
+        tokens.extend(by_suit.into_iter().flatten());
+        for (hi, lo, suit) in mixed {
+            for hand in class_combos(hi, lo, suit).into_iter().filter(live) {
+                let w = weight(hand);
+                if w > 0.0 {
+                    tokens.push(format!("{}{}{}", hand[0], hand[1], suffix(w)));
+                }
+            }
+        }
+        tokens.join(",")
+    }
+
+    fn zip_with(&self, other: &Range, f: impl Fn(f64, f64) -> f64) -> Range {
+        let weights = self.weights.iter().zip(other.weights.iter())
+            .map(|(&a, &b)| f(a, b).clamp(0.0, 1.0))
+            .collect();
+        Range { weights }
+    }
+}
+
+impl Default for Range {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::str::FromStr for Range {
+    type Err = RangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Range::parse(s)
+    }
+}
+
+/// Hand class shape parsed from a two-rank token
+#[derive(Clone, Copy, PartialEq)]
+enum Suitedness {
+    Suited,
+    Offsuit,
+    Any,
+}
+
+/// Maximal runs `(top, bottom, weight)` of consecutive ranks (given high
+/// to low) sharing a positive weight; mixed ranks (`None`) break runs
+fn weight_runs(ranks: &[(u8, Option<f64>)]) -> Vec<(u8, u8, f64)> {
+    let mut runs: Vec<(u8, u8, f64)> = Vec::new();
+    let mut extend = false;
+    for &(r, w) in ranks {
+        match w {
+            Some(w) if w > 0.0 => {
+                match runs.last_mut() {
+                    Some(run) if extend && run.2 == w && run.1 == r + 1 => run.1 = r,
+                    _ => runs.push((r, r, w)),
+                }
+                extend = true;
+            }
+            _ => extend = false,
+        }
+    }
+    runs
+}
+
+/// Parse one comma-free token into its list of combos.
+fn parse_token(token: &str) -> Option<Vec<[Card; 2]>> {
+    let chars: Vec<char> = token.chars().collect();
+
+    // Specific combo, e.g. "AhKh"
+    if chars.len() == 4 && parse_suit(chars[1]).is_some() && parse_suit(chars[3]).is_some() {
+        let a: Card = chars[0..2].iter().collect::<String>().parse().ok()?;
+        let b: Card = chars[2..4].iter().collect::<String>().parse().ok()?;
+        if a == b {
+            return None;
+        }
+        return Some(vec![[a, b]]);
+    }
+
+    // Dash span, e.g. "QQ-88" or "KTs-K7s"
+    if let Some((left, right)) = token.split_once('-') {
+        let (h1, l1, s1) = parse_class(left)?;
+        let (h2, l2, s2) = parse_class(right)?;
+        if s1 != s2 {
+            return None;
+        }
+        let mut combos = Vec::new();
+        if h1 == l1 && h2 == l2 {
+            // Pair span
+            let (top, bottom) = (h1.max(h2), h1.min(h2));
+            for r in bottom..=top {
+                combos.extend(class_combos(r, r, s1));
+            }
+        } else if h1 == h2 && h1 != l1 && h2 != l2 {
+            // Kicker span with a fixed high card
+            let (top, bottom) = (l1.max(l2), l1.min(l2));
+            for k in bottom..=top {
+                combos.extend(class_combos(h1, k, s1));
+            }
+        } else {
+            return None;
+        }
+        return Some(combos);
+    }
+
+    // Plus form, e.g. "77+" or "ATs+"
+    if let Some(stem) = token.strip_suffix('+') {
+        let (hi, lo, suit) = parse_class(stem)?;
+        let mut combos = Vec::new();
+        if hi == lo {
+            for r in hi..13 {
+                combos.extend(class_combos(r, r, suit));
+            }
+        } else {
+            for k in lo..hi {
+                combos.extend(class_combos(hi, k, suit));
+            }
+        }
+        return Some(combos);
+    }
+
+    let (hi, lo, suit) = parse_class(token)?;
+    Some(class_combos(hi, lo, suit))
+}
+
+/// Parse "AK", "AKs", "AKo", "QQ" into (high rank, low rank, suitedness).
+fn parse_class(s: &str) -> Option<(u8, u8, Suitedness)> {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() < 2 || chars.len() > 3 {
+        return None;
+    }
+    let r1 = parse_rank(chars[0])?;
+    let r2 = parse_rank(chars[1])?;
+    let suit = match chars.get(2).map(|c| c.to_ascii_lowercase()) {
+        None => Suitedness::Any,
+        Some('s') => Suitedness::Suited,
+        Some('o') => Suitedness::Offsuit,
+        _ => return None,
+    };
+    if r1 == r2 && suit != Suitedness::Any {
+        return None;
+    }
+    Some((r1.max(r2), r1.min(r2), suit))
+}
+
+/// All combos of a hand class (pair, suited, offsuit, or both).
+fn class_combos(hi: u8, lo: u8, suit: Suitedness) -> Vec<[Card; 2]> {
+    let mut combos = Vec::new();
+    for s1 in 0..4u8 {
+        for s2 in 0..4u8 {
+            if hi == lo {
+                if s1 < s2 {
+                    combos.push([Card::from_rank_suit(hi, s1), Card::from_rank_suit(lo, s2)]);
+                }
+                continue;
+            }
+            let keep = match suit {
+                Suitedness::Suited => s1 == s2,
+                Suitedness::Offsuit => s1 != s2,
+                Suitedness::Any => true,
+            };
+            if keep {
+                combos.push([Card::from_rank_suit(hi, s1), Card::from_rank_suit(lo, s2)]);
+            }
+        }
+    }
+    combos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combo::class_index;
+    use crate::node::parse_cards;
+
+    fn hand(s: &str) -> [Card; 2] {
+        let c = parse_cards(s).unwrap();
+        [c[0], c[1]]
+    }
+
+    #[test]
+    fn test_parse_class_counts() {
+        assert_eq!(Range::parse("AA").unwrap().combo_count(), 6.0);
+        assert_eq!(Range::parse("AKs").unwrap().combo_count(), 4.0);
+        assert_eq!(Range::parse("AKo").unwrap().combo_count(), 12.0);
+        assert_eq!(Range::parse("AK").unwrap().combo_count(), 16.0);
+        assert_eq!(Range::parse("AhKh").unwrap().combo_count(), 1.0);
+    }
+
+    #[test]
+    fn test_parse_plus_and_spans() {
+        // 22+ = 13 pairs × 6
+        assert_eq!(Range::parse("22+").unwrap().combo_count(), 78.0);
+        // ATs+ = AT, AJ, AQ, AK suited
+        assert_eq!(Range::parse("ATs+").unwrap().combo_count(), 16.0);
+        // QQ-88 = 5 pairs
+        assert_eq!(Range::parse("QQ-88").unwrap().combo_count(), 30.0);
+        // KTs-K7s = KT, K9, K8, K7 suited
+        assert_eq!(Range::parse("KTs-K7s").unwrap().combo_count(), 16.0);
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let r = Range::parse("AA,KK:0.5").unwrap();
+        assert_eq!(r.combo_count(), 9.0);
+        assert_eq!(r.weight(hand("KsKh")), 0.5);
+        assert_eq!(r.weight(hand("AsAh")), 1.0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(Range::parse("AX"), Err(RangeParseError::InvalidToken(_))));
+        assert!(matches!(Range::parse("AAs"), Err(RangeParseError::InvalidToken(_))));
+        assert!(matches!(Range::parse("AK:1.5"), Err(RangeParseError::InvalidWeight(_))));
+        assert!(matches!(Range::parse("AKs-KQs"), Err(RangeParseError::InvalidToken(_))));
+        assert!(matches!(Range::parse("€hKh"), Err(RangeParseError::InvalidToken(_))));
+        assert!(Range::parse("Ah€h,KK").is_err());
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = Range::parse("AA,KK").unwrap();
+        let b = Range::parse("KK:0.25,QQ").unwrap();
+        assert_eq!(a.union(&b).combo_count(), 18.0);
+        assert_eq!(a.intersect(&b).combo_count(), 1.5);
+        // AA (6) + KK at 0.75 (4.5)
+        assert_eq!(a.subtract(&b).combo_count(), 10.5);
+    }
+
+    #[test]
+    fn test_scale_clamps() {
+        let r = Range::parse("AA:0.6").unwrap();
+        assert!((r.scale(0.5).weight(hand("AsAh")) - 0.3).abs() < 1e-12);
+        assert_eq!(r.scale(3.0).weight(hand("AsAh")), 1.0);
+    }
+
+    #[test]
+    fn test_live_combos_remove_board_blockers() {
+        let r = Range::parse("AA,AKs").unwrap();
+        let board = parse_cards("AsKh7d").unwrap();
+        // AA: 3 combos without As; AKs: only diamonds and clubs survive
+        let live: Vec<_> = r.live_combos(&board).collect();
+        assert_eq!(live.len(), 5);
+        let summary = r.summary(&board);
+        assert_eq!(summary.pairs, 3.0);
+        assert_eq!(summary.suited, 2.0);
+        assert_eq!(summary.offsuit, 0.0);
+    }
+
+    #[test]
+    fn test_from_weights_and_class_weights() {
+        let mut weights = vec![0.0; NUM_COMBOS];
+        weights[combo_index(hand("AsAh")[0], hand("AsAh")[1])] = 0.4;
+        weights[0] = 2.0;
+        let r = Range::from_weights(&weights);
+        assert_eq!(r.weight(hand("AhAs")), 0.4);
+        assert_eq!(r.combo_count(), 1.4);
+
+        let mut classes = vec![0.0; NUM_CLASSES];
+        classes[class_index(hand("AhKh"))] = 0.5;
+        let r = Range::from_class_weights(&classes);
+        assert_eq!(r, Range::parse("AKs:0.5").unwrap());
+    }
+
+    #[test]
+    fn test_notation_is_compact_and_round_trips() {
+        let notation = |s: &str| Range::parse(s).unwrap().to_notation(&[], 0.0);
+        assert_eq!(notation("AK,ATs-AQs,QQ+,KQo:0.5"), "QQ+,AK,AQs-ATs,KQo:0.5");
+        assert_eq!(notation("22+,A2s+,KTs-K7s,T9o,99-66:0.25"), "TT+,99-66:0.25,55-22,A2s+,KTs-K7s,T9o");
+        assert_eq!(notation(""), "");
+
+        let original = Range::parse("22+,AK,A2s+,KTs-K7s,QJo:0.5,Th9h,Kc9d:0.3").unwrap();
+        let text = original.to_notation(&[], 0.0);
+        assert_eq!(Range::parse(&text).unwrap(), original);
+        assert!(text.ends_with(",Kc9d:0.3,Th9h"), "{}", text);
+
+        // Below-threshold combos drop out; blocked combos don't split a class
+        let board = parse_cards("Ah7d2c").unwrap();
+        assert_eq!(Range::parse("AA,KK:0.2").unwrap().to_notation(&board, 0.5), "AA");
+        let mut range = Range::parse("AKs").unwrap();
+        range.set_weight(hand("AhKh"), 0.0);
+        assert_eq!(range.to_notation(&board, 0.0), "AKs");
+    }
+
+    #[test]
+    fn test_full_range_count() {
+        assert_eq!(Range::full().combo_count(), NUM_COMBOS as f64);
+        assert!(Range::new().is_empty());
+    }
+}
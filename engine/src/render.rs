@@ -0,0 +1,362 @@
+//! SVG and PNG drawings of 13x13 strategy grids (`render` feature)
+//!
+//! Each `RangeGrid` cell is drawn as a square whose filled height is the
+//! cell's range weight, split left to right into one bar per action with
+//! widths in proportion to the action's share (the stacked colors solver
+//! UIs show). Hand labels sit in each cell's corner; SVGs get a legend of
+//! action labels under the grid, PNGs a row of swatches in action order.
+//!
+//! Both formats are written by hand, with no external crate: SVG is plain
+//! text, and PNG uses stored (uncompressed) deflate blocks, so files are
+//! larger than an image library would make them but open anywhere.
+
+use crate::grid::RangeGrid;
+use crate::node::{Action, Node, NodeId, Player};
+use crate::solution::Solution;
+
+/// An RGB color
+pub type Rgb = [u8; 3];
+
+/// Side of one grid cell, in pixels
+pub const CELL_SIZE: usize = 36;
+
+const FOLD: Rgb = [91, 141, 238];
+const PASSIVE: Rgb = [90, 185, 102];
+/// Smallest and largest bet colors; sizes in between are interpolated
+const SMALL_BET: Rgb = [240, 128, 104];
+const BIG_BET: Rgb = [160, 24, 32];
+/// Plain range (no actions) fill
+const IN_RANGE: Rgb = [120, 144, 168];
+const EMPTY: Rgb = [232, 232, 232];
+const BACKGROUND: Rgb = [255, 255, 255];
+const TEXT: Rgb = [34, 34, 34];
+const LEGEND_ROW: usize = 20;
+
+/// Error from `node_grid`
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderError {
+    /// The solution carries no ranges to project
+    NoRanges,
+    /// Only decision nodes have a strategy grid
+    NotADecision(NodeId),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::NoRanges => write!(f, "solution has no ranges"),
+            RenderError::NotADecision(id) => write!(f, "node {} is not a decision", id),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// The acting player's range at decision `node`, split by its strategy
+pub fn node_grid(solution: &Solution, node: NodeId) -> Result<RangeGrid, RenderError> {
+    let ranges = solution.ranges.as_ref().ok_or(RenderError::NoRanges)?;
+    match solution.tree.get(node) {
+        Some(Node::Decision { player, actions, board, infoset_id, .. }) => {
+            let sums = solution.storage.strategy_sums.get(*infoset_id as usize).map_or(&[][..], Vec::as_slice);
+            let total: f64 = sums.iter().sum();
+            let strategy: Vec<f64> = if total > 0.0 && sums.len() == actions.len() {
+                sums.iter().map(|s| s / total).collect()
+            } else {
+                vec![1.0 / actions.len() as f64; actions.len()]
+            };
+            let range = &ranges[usize::from(*player == Player::OOP)];
+            Ok(RangeGrid::from_node_strategy(range, board, actions, &strategy))
+        }
+        _ => Err(RenderError::NotADecision(node)),
+    }
+}
+
+/// One color per action: blue fold, green check/call, bets and raises from
+/// light to dark red in the order they appear (smallest to largest)
+pub fn action_colors(actions: &[Action]) -> Vec<Rgb> {
+    let aggressive = actions.iter().filter(|a| matches!(a, Action::Bet { .. } | Action::Raise { .. })).count();
+    let mut k = 0;
+    actions
+        .iter()
+        .map(|a| match a {
+            Action::Fold => FOLD,
+            Action::Check | Action::Call => PASSIVE,
+            Action::Bet { .. } | Action::Raise { .. } => {
+                let t = if aggressive > 1 { k as f64 / (aggressive - 1) as f64 } else { 0.5 };
+                k += 1;
+                [0, 1, 2].map(|i| (SMALL_BET[i] as f64 + t * (BIG_BET[i] as f64 - SMALL_BET[i] as f64)).round() as u8)
+            }
+        })
+        .collect()
+}
+
+/// A filled rectangle in grid pixels
+struct Rect {
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+    color: Rgb,
+}
+
+/// Every rectangle of the grid, background first
+fn cell_rects(grid: &RangeGrid, colors: &[Rgb]) -> Vec<Rect> {
+    let mut rects = Vec::new();
+    for cell in &grid.cells {
+        let (x, y) = (cell.col * CELL_SIZE, cell.row * CELL_SIZE);
+        rects.push(Rect { x, y, w: CELL_SIZE - 1, h: CELL_SIZE - 1, color: EMPTY });
+        if cell.combos <= 0.0 {
+            continue;
+        }
+        let h = ((CELL_SIZE - 1) as f64 * cell.frequency.min(1.0)).round() as usize;
+        let top = y + CELL_SIZE - 1 - h;
+        if cell.actions.is_empty() {
+            rects.push(Rect { x, y: top, w: CELL_SIZE - 1, h, color: IN_RANGE });
+            continue;
+        }
+        // Cumulative edges, so rounding never leaves a gap or overflows
+        let mut share = 0.0;
+        let mut left = 0;
+        for (a, &f) in cell.actions.iter().enumerate() {
+            share += f;
+            let right = ((CELL_SIZE - 1) as f64 * share.min(1.0)).round() as usize;
+            if right > left {
+                rects.push(Rect { x: x + left, y: top, w: right - left, h, color: colors[a] });
+            }
+            left = right;
+        }
+    }
+    rects
+}
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// The grid as an SVG document; `colors` has one entry per action label
+pub fn render_svg(grid: &RangeGrid, colors: &[Rgb]) -> String {
+    assert_eq!(colors.len(), grid.action_labels.len(), "one color per action");
+    let side = 13 * CELL_SIZE;
+    let height = side + grid.action_labels.len() * LEGEND_ROW + if grid.action_labels.is_empty() { 0 } else { 8 };
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\">\n",
+        side, height, side, height,
+    );
+    out.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n", side, height, hex(BACKGROUND)));
+    for r in cell_rects(grid, colors) {
+        out.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n", r.x, r.y, r.w, r.h, hex(r.color)));
+    }
+    for cell in &grid.cells {
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"10\" fill=\"{}\">{}</text>\n",
+            cell.col * CELL_SIZE + 2, cell.row * CELL_SIZE + 11, hex(TEXT), cell.label,
+        ));
+    }
+    for (i, (label, &color)) in grid.action_labels.iter().zip(colors).enumerate() {
+        let y = side + 8 + i * LEGEND_ROW;
+        out.push_str(&format!("<rect x=\"2\" y=\"{}\" width=\"14\" height=\"14\" fill=\"{}\"/>\n", y, hex(color)));
+        out.push_str(&format!("<text x=\"22\" y=\"{}\" font-size=\"12\" fill=\"{}\">{}</text>\n", y + 11, hex(TEXT), xml_escape(label)));
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// 3x5 glyphs for hand labels, one row per byte (low three bits, MSB left)
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b010, 0b010],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '3' => [0b111, 0b001, 0b011, 0b001, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        's' => [0b000, 0b011, 0b110, 0b001, 0b110],
+        'o' => [0b000, 0b010, 0b101, 0b101, 0b010],
+        _ => [0; 5],
+    }
+}
+
+/// RGB pixels, row-major
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Canvas { width, height, pixels: BACKGROUND.repeat(width * height) }
+    }
+
+    fn fill(&mut self, r: &Rect) {
+        for y in r.y..(r.y + r.h).min(self.height) {
+            for x in r.x..(r.x + r.w).min(self.width) {
+                let i = 3 * (y * self.width + x);
+                self.pixels[i..i + 3].copy_from_slice(&r.color);
+            }
+        }
+    }
+
+    /// `text` in the 3x5 font at twice its size
+    fn text(&mut self, x: usize, y: usize, text: &str) {
+        for (k, c) in text.chars().enumerate() {
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..3 {
+                    if bits >> (2 - col) & 1 == 1 {
+                        self.fill(&Rect { x: x + k * 8 + col * 2, y: y + row * 2, w: 2, h: 2, color: TEXT });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The grid as a PNG file; `colors` has one entry per action label
+pub fn render_png(grid: &RangeGrid, colors: &[Rgb]) -> Vec<u8> {
+    assert_eq!(colors.len(), grid.action_labels.len(), "one color per action");
+    let side = 13 * CELL_SIZE;
+    let legend = if colors.is_empty() { 0 } else { LEGEND_ROW + 8 };
+    let mut canvas = Canvas::new(side, side + legend);
+    for r in cell_rects(grid, colors) {
+        canvas.fill(&r);
+    }
+    for cell in &grid.cells {
+        canvas.text(cell.col * CELL_SIZE + 2, cell.row * CELL_SIZE + 2, &cell.label);
+    }
+    for (i, &color) in colors.iter().enumerate() {
+        canvas.fill(&Rect { x: 2 + i * 20, y: side + 8, w: 14, h: 14, color });
+    }
+    encode_png(&canvas)
+}
+
+fn encode_png(canvas: &Canvas) -> Vec<u8> {
+    // Each scanline: filter type 0, then the row's pixels
+    let mut raw = Vec::with_capacity(canvas.height * (1 + 3 * canvas.width));
+    for row in canvas.pixels.chunks(3 * canvas.width) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    let mut zlib = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = raw.chunks(65_535).collect();
+    for (i, block) in blocks.iter().enumerate() {
+        zlib.push(u8::from(i + 1 == blocks.len()));
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&(canvas.width as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(canvas.height as u32).to_be_bytes());
+    // 8-bit RGB, deflate, no filter method extensions, no interlace
+    ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    for (kind, data) in [(b"IHDR", &ihdr), (b"IDAT", &zlib), (b"IEND", &Vec::new())] {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+    png
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &x in bytes {
+        a = (a + x as u32) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    b << 16 | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::parse_cards;
+    use crate::range::Range;
+
+    fn grid() -> RangeGrid {
+        let range = Range::parse("AA,KK,AKs,T9o").unwrap();
+        let actions = [Action::Check, Action::Bet { size: 3.0 }, Action::Bet { size: 7.5 }];
+        RangeGrid::from_node_strategy(&range, &parse_cards("Ah7d2c").unwrap(), &actions, &[0.5, 0.3, 0.2])
+    }
+
+    #[test]
+    fn test_action_colors() {
+        let actions = [Action::Fold, Action::Call, Action::Raise { size: 9.0 }, Action::Raise { size: 20.0 }];
+        assert_eq!(action_colors(&actions), vec![FOLD, PASSIVE, SMALL_BET, BIG_BET]);
+    }
+
+    #[test]
+    fn test_svg_stacks_actions_per_cell() {
+        let grid = grid();
+        let svg = render_svg(&grid, &action_colors(&[Action::Check, Action::Bet { size: 3.0 }, Action::Bet { size: 7.5 }]));
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        // Background, 169 cells, three bars for each of the four classes, and three swatches
+        assert_eq!(svg.matches("<rect").count(), 1 + 169 + 4 * 3 + 3);
+        assert!(svg.contains(">AKs</text>") && svg.contains(">Bet 7.5</text>"));
+        // Check takes half of each in-range cell's width
+        assert!(svg.contains(&format!("width=\"18\" height=\"35\" fill=\"{}\"", hex(PASSIVE))));
+    }
+
+    #[test]
+    fn test_png_is_well_formed() {
+        let grid = grid();
+        let colors = action_colors(&[Action::Check, Action::Bet { size: 3.0 }, Action::Bet { size: 7.5 }]);
+        let png = render_png(&grid, &colors);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let be = |i: usize| u32::from_be_bytes(png[i..i + 4].try_into().unwrap());
+        let (width, height) = (be(16) as usize, be(20) as usize);
+        assert_eq!((width, height), (13 * CELL_SIZE, 13 * CELL_SIZE + LEGEND_ROW + 8));
+        // Every chunk's CRC checks out, and the stored blocks hold every scanline
+        let mut i = 8;
+        let mut raw = Vec::new();
+        while i < png.len() {
+            let len = be(i) as usize;
+            assert_eq!(crc32(&png[i + 4..i + 8 + len]), be(i + 8 + len));
+            if &png[i + 4..i + 8] == b"IDAT" {
+                let mut z = i + 10;
+                loop {
+                    let n = u16::from_le_bytes([png[z + 1], png[z + 2]]) as usize;
+                    raw.extend_from_slice(&png[z + 5..z + 5 + n]);
+                    let last = png[z] == 1;
+                    z += 5 + n;
+                    if last {
+                        break;
+                    }
+                }
+                assert_eq!(u32::from_be_bytes(png[z..z + 4].try_into().unwrap()), adler32(&raw));
+            }
+            i += 12 + len;
+        }
+        assert_eq!(raw.len(), height * (1 + 3 * width));
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+}
@@ -0,0 +1,179 @@
+//! Hand-history review: grading a player's decisions and finding leaks
+//!
+//! `review_hand` replays one played hand through a solved tree (board cards
+//! at chance nodes, observed actions at decisions, off-tree sizes mapped to
+//! the nearest tree size) and grades every decision of a player whose hole
+//! cards are known with `coach::grade_action`. Each graded decision keeps
+//! the line that led to it as a `line::line_pattern` (`F:x-b33-c|T:*|x`),
+//! runout cards written as `*` so the same spot on different turns and
+//! rivers reads as one line.
+//!
+//! `leak_report` adds the decisions up per player and line: how often the
+//! spot came up, the total and mean EV given up against the best action,
+//! and how many decisions earned each `Grade`. Sorted by total loss, the
+//! top lines are where a player's hands leak the most.
+
+use std::collections::BTreeMap;
+use crate::bot::{match_action, ActError, GameState, OffTree};
+use crate::coach::{grade_action, ActionGrade, CoachError};
+use crate::line::{chance_child, line_pattern};
+use crate::node::{Card, Node, Player};
+use crate::vector_cfr::VectorCfrSolver;
+
+/// One graded decision from a played hand
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewedDecision {
+    pub hand_number: u64,
+    pub player: Player,
+    /// `line::line_pattern` of the decision (`root` at the root)
+    pub line: String,
+    pub grade: ActionGrade,
+}
+
+/// Error returned when a hand can't be replayed through the tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewError {
+    /// The hand's board or actions don't follow the tree
+    Replay(ActError),
+    /// A decision couldn't be graded (e.g. the hand isn't in the range)
+    Grade(CoachError),
+}
+
+impl std::fmt::Display for ReviewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReviewError::Replay(e) => write!(f, "{}", e),
+            ReviewError::Grade(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReviewError {}
+
+/// EV loss on one line, summed over every time a player reached it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineLeak {
+    pub player: Player,
+    pub line: String,
+    pub decisions: usize,
+    /// EV given up against the best action, bb
+    pub total_loss: f64,
+    /// Decisions per `Grade`, best first
+    pub grades: [usize; 4],
+}
+
+impl LineLeak {
+    pub fn mean_loss(&self) -> f64 {
+        self.total_loss / self.decisions as f64
+    }
+}
+
+/// Grade the decisions in `state` (the board and actions from the tree's
+/// root) of each player with hole cards in `hole_cards` (`[IP, OOP]`).
+/// The replay stops where the hand or the tree ends.
+pub fn review_hand(
+    solver: &VectorCfrSolver,
+    hand_number: u64,
+    state: &GameState,
+    hole_cards: [Option<[Card; 2]>; 2],
+) -> Result<Vec<ReviewedDecision>, ReviewError> {
+    let tree = &solver.tree;
+    let root_board = tree.get(0).map(Node::board).unwrap_or(&[]);
+    if state.board.len() < root_board.len() || root_board.iter().any(|c| !state.board[..root_board.len()].contains(c)) {
+        return Err(ReviewError::Replay(ActError::BoardMismatch(format!("hand does not start with the root board ({} cards)", root_board.len()))));
+    }
+    let mut decisions = Vec::new();
+    let mut actions = state.actions.iter();
+    let mut id = 0;
+    loop {
+        match tree.get(id).expect("invalid node id") {
+            Node::Chance { children, board, .. } => {
+                let Some(&card) = state.board.get(board.len()) else { break };
+                id = chance_child(tree, children, card)
+                    .ok_or_else(|| ReviewError::Replay(ActError::BoardMismatch(format!("node {} never deals {}", id, card))))?;
+            }
+            Node::Decision { children, actions: options, player, .. } => {
+                let Some(&action) = actions.next() else { break };
+                let a = match_action(options, action, OffTree::Nearest).ok_or(ReviewError::Replay(ActError::OffTree { node: id, action }))?;
+                if let Some(hole) = hole_cards[*player as usize] {
+                    let grade = grade_action(solver, id, a, hole).map_err(ReviewError::Grade)?;
+                    decisions.push(ReviewedDecision { hand_number, player: *player, line: line_pattern(tree, id), grade });
+                }
+                id = children[a];
+            }
+            Node::Terminal { .. } => break,
+        }
+    }
+    Ok(decisions)
+}
+
+/// Decisions added up per player and line, largest total loss first
+pub fn leak_report(decisions: &[ReviewedDecision]) -> Vec<LineLeak> {
+    let mut lines: BTreeMap<(usize, &str), LineLeak> = BTreeMap::new();
+    for d in decisions {
+        let leak = lines.entry((d.player as usize, d.line.as_str())).or_insert_with(|| LineLeak {
+            player: d.player,
+            line: d.line.clone(),
+            decisions: 0,
+            total_loss: 0.0,
+            grades: [0; 4],
+        });
+        leak.decisions += 1;
+        leak.total_loss += d.grade.loss_vs_best;
+        leak.grades[d.grade.grade as usize] += 1;
+    }
+    let mut leaks: Vec<LineLeak> = lines.into_values().collect();
+    leaks.sort_by(|a, b| b.total_loss.total_cmp(&a.total_loss));
+    leaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::coach::Grade;
+    use crate::game::GameConfig;
+    use crate::node::{parse_cards, Action};
+    use crate::range::Range;
+
+    fn hand(s: &str) -> [Card; 2] {
+        parse_cards(s).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_review_grades_each_decision_and_ranks_leaks() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c9s").unwrap());
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 1;
+        let ranges = [Range::parse("AK,77,QJs").unwrap(), Range::parse("AQ,KQ,99").unwrap()];
+        let mut solver = VectorCfrSolver::new(build_tree(&config), &ranges);
+        for _ in 0..300 {
+            solver.run_iteration();
+        }
+        let board = parse_cards("AsKh7d2c9s").unwrap();
+        let Some(Node::Decision { actions, .. }) = solver.tree.get(solver.tree.get(0).unwrap().children()[0]) else { panic!("IP acts after a check") };
+        let bet = actions[1];
+        // OOP checks with a set of nines and folds to a bet (a blunder); IP bets top two
+        let folded = GameState::new(board.clone()).with_action(Action::Check).with_action(bet).with_action(Action::Fold);
+        let played = review_hand(&solver, 1, &folded, [Some(hand("AhKd")), Some(hand("9h9d"))]).unwrap();
+        let lines: Vec<(Player, &str)> = played.iter().map(|d| (d.player, d.line.as_str())).collect();
+        assert_eq!(lines, vec![(Player::OOP, "root"), (Player::IP, "R:x"), (Player::OOP, "R:x-b75")]);
+        assert_eq!(played[2].grade.grade, Grade::Blunder);
+
+        // Only OOP's cards known; calling the same bet is fine
+        let called = GameState::new(board.clone()).with_action(Action::Check).with_action(bet).with_action(Action::Call);
+        let mut decisions = review_hand(&solver, 2, &called, [None, Some(hand("9c9d"))]).unwrap();
+        assert_eq!(decisions.len(), 2);
+        decisions.extend(played);
+        let report = leak_report(&decisions);
+        assert_eq!((report[0].player, report[0].line.as_str(), report[0].decisions), (Player::OOP, "R:x-b75", 2));
+        assert_eq!(report[0].grades[Grade::Blunder as usize], 1);
+        assert!((report[0].mean_loss() - report[0].total_loss / 2.0).abs() < 1e-12);
+        assert_eq!(report.iter().map(|l| l.decisions).sum::<usize>(), 5);
+
+        let wrong = GameState::new(parse_cards("QsKh7d2c9s").unwrap());
+        assert!(matches!(review_hand(&solver, 3, &wrong, [None, None]), Err(ReviewError::Replay(_))));
+        let off_range = GameState::new(board).with_action(Action::Check);
+        assert!(matches!(review_hand(&solver, 4, &off_range, [None, Some(hand("3c3d"))]), Err(ReviewError::Grade(_))));
+    }
+}
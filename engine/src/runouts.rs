@@ -0,0 +1,277 @@
+//! Runout breakdown: how EV and strategy move with the next card
+//!
+//! Pick a decision and an action that closes the street (a check behind or
+//! a call). `runout_breakdown` follows it to the Chance node and, for every
+//! card dealt there, reads the acting player's EV once the card is out and
+//! the range-weighted strategy at the first decision of the new street.
+//! Each card is also put in a class relative to the board before it:
+//!
+//! - pairing: matches the rank of a board card
+//! - flush-completing: its suit is already on the board at least twice
+//! - overcard: higher than every board card
+//! - blank: none of the above
+//!
+//! The first class that applies wins, in that order. Rows record each
+//! card's shift from the plain mean over all cards, and `classes` sums the
+//! rows up per class. The report prints as an aligned table and exports as
+//! CSV.
+
+use crate::node::{Action, Card, Node, NodeId, Player};
+use crate::vector_cfr::VectorCfrSolver;
+
+/// How a dealt card relates to the board before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CardClass {
+    Pairing,
+    FlushCompleting,
+    Overcard,
+    Blank,
+}
+
+impl CardClass {
+    /// Class of `card` dealt onto `board` (see module docs)
+    pub fn of(board: &[Card], card: Card) -> Self {
+        if board.iter().any(|c| c.rank() == card.rank()) {
+            CardClass::Pairing
+        } else if board.iter().filter(|c| c.suit() == card.suit()).count() >= 2 {
+            CardClass::FlushCompleting
+        } else if board.iter().all(|c| c.rank() < card.rank()) {
+            CardClass::Overcard
+        } else {
+            CardClass::Blank
+        }
+    }
+}
+
+impl std::fmt::Display for CardClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CardClass::Pairing => "pairing",
+            CardClass::FlushCompleting => "flush",
+            CardClass::Overcard => "overcard",
+            CardClass::Blank => "blank",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Error returned when a breakdown can't be built
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunoutError {
+    NotDecision(NodeId),
+    NoSuchAction { node: NodeId, action: usize },
+    /// The action doesn't lead straight to a card being dealt
+    StreetContinues { node: NodeId, action: usize },
+}
+
+impl std::fmt::Display for RunoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunoutError::NotDecision(node) => write!(f, "node {} is not a decision", node),
+            RunoutError::NoSuchAction { node, action } => write!(f, "node {} has no action {}", node, action),
+            RunoutError::StreetContinues { node, action } => {
+                write!(f, "action {} at node {} does not close the street", action, node)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RunoutError {}
+
+/// One dealt card
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunoutRow {
+    pub card: Card,
+    pub class: CardClass,
+    /// Node right after the card
+    pub node: NodeId,
+    /// Acting player's EV (bb) once the card is dealt
+    pub ev: f64,
+    /// `ev` minus the mean over all cards
+    pub ev_shift: f64,
+    /// Range strategy at the first decision after the card (`None` when
+    /// there is none, e.g. after an all-in)
+    pub strategy: Option<Vec<f64>>,
+}
+
+/// Per-class means of the rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassSummary {
+    pub class: CardClass,
+    pub cards: usize,
+    pub ev: f64,
+    pub ev_shift: f64,
+    pub strategy: Option<Vec<f64>>,
+}
+
+/// Result of `runout_breakdown`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunoutReport {
+    pub node: NodeId,
+    pub action: usize,
+    /// Player whose EV the rows report (the one acting at `node`)
+    pub player: Player,
+    /// Actions at the first decision after the card, labelling `strategy`
+    pub actions: Vec<Action>,
+    /// Mean EV over all cards
+    pub mean_ev: f64,
+    /// Mean strategy over all cards
+    pub mean_strategy: Option<Vec<f64>>,
+    /// One row per card, in deal order
+    pub rows: Vec<RunoutRow>,
+    /// Classes that occur, in `CardClass` order
+    pub classes: Vec<ClassSummary>,
+}
+
+/// Break down action `action` at decision `node` by the card dealt next
+pub fn runout_breakdown(solver: &VectorCfrSolver, node: NodeId, action: usize) -> Result<RunoutReport, RunoutError> {
+    let tree = &solver.tree;
+    let Some(Node::Decision { player, children, board, .. }) = tree.get(node) else {
+        return Err(RunoutError::NotDecision(node));
+    };
+    let &chance = children.get(action).ok_or(RunoutError::NoSuchAction { node, action })?;
+    let Some(Node::Chance { children: dealt, .. }) = tree.get(chance) else {
+        return Err(RunoutError::StreetContinues { node, action });
+    };
+    let sign = if *player == Player::IP { 1.0 } else { -1.0 };
+    let values = solver.node_ip_values();
+
+    let mut actions = Vec::new();
+    let mut rows = Vec::new();
+    for &child in dealt {
+        let after = tree.get(child).expect("invalid node id");
+        let card = *after.board().last().expect("chance child has a board card");
+        let Some(ip_ev) = values[child as usize] else { continue };
+        let strategy = match after {
+            Node::Decision { actions: next, .. } => {
+                actions.clone_from(next);
+                Some(solver.range_strategy(child))
+            }
+            _ => None,
+        };
+        rows.push(RunoutRow { card, class: CardClass::of(board, card), node: child, ev: sign * ip_ev, ev_shift: 0.0, strategy });
+    }
+
+    let (mean_ev, mean_strategy) = means(&rows);
+    for row in &mut rows {
+        row.ev_shift = row.ev - mean_ev;
+    }
+    let mut classes = Vec::new();
+    for class in [CardClass::Pairing, CardClass::FlushCompleting, CardClass::Overcard, CardClass::Blank] {
+        let members: Vec<RunoutRow> = rows.iter().filter(|r| r.class == class).cloned().collect();
+        if members.is_empty() {
+            continue;
+        }
+        let (ev, strategy) = means(&members);
+        classes.push(ClassSummary { class, cards: members.len(), ev, ev_shift: ev - mean_ev, strategy });
+    }
+    Ok(RunoutReport { node, action, player: *player, actions, mean_ev, mean_strategy, rows, classes })
+}
+
+/// Mean EV and mean strategy of `rows` (strategy only if every row has one)
+fn means(rows: &[RunoutRow]) -> (f64, Option<Vec<f64>>) {
+    let n = rows.len().max(1) as f64;
+    let ev = rows.iter().map(|r| r.ev).sum::<f64>() / n;
+    let strategies: Option<Vec<&Vec<f64>>> = rows.iter().map(|r| r.strategy.as_ref()).collect();
+    let strategy = strategies.filter(|s| !s.is_empty()).map(|s| {
+        (0..s[0].len()).map(|a| s.iter().map(|f| f[a]).sum::<f64>() / n).collect()
+    });
+    (ev, strategy)
+}
+
+impl RunoutReport {
+    /// One line per card: `card,class,ev,ev_shift`, then a frequency and a
+    /// shift column per action
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("card,class,ev,ev_shift");
+        for a in &self.actions {
+            out.push_str(&format!(",{},{} shift", a, a));
+        }
+        out.push('\n');
+        for row in &self.rows {
+            out.push_str(&format!("{},{},{:.6},{:.6}", row.card, row.class, row.ev, row.ev_shift));
+            for (shift, freq) in self.frequencies(row.strategy.as_deref()) {
+                out.push_str(&format!(",{:.6},{:.6}", freq, shift));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// `(shift, frequency)` per action against the mean strategy
+    fn frequencies(&self, strategy: Option<&[f64]>) -> Vec<(f64, f64)> {
+        match (strategy, &self.mean_strategy) {
+            (Some(s), Some(mean)) => s.iter().zip(mean).map(|(f, m)| (f - m, *f)).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for RunoutReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header: Vec<String> = self.actions.iter().map(|a| format!("{:>12}", a.to_string())).collect();
+        writeln!(f, "{:<6} {:<9} {:>9} {:>9} {}", "Card", "Class", "EV", "Shift", header.join(""))?;
+        let cells = |strategy: Option<&[f64]>| -> String {
+            self.frequencies(strategy).iter().map(|(shift, freq)| format!("{:>5.1}% {:>+4.0}", freq * 100.0, shift * 100.0)).collect()
+        };
+        for row in &self.rows {
+            writeln!(f, "{:<6} {:<9} {:>9.3} {:>+9.3} {}", row.card.to_string(), row.class.to_string(), row.ev, row.ev_shift, cells(row.strategy.as_deref()))?;
+        }
+        for class in &self.classes {
+            let label = format!("{} ({})", class.class, class.cards);
+            writeln!(f, "{:<16} {:>9.3} {:>+9.3} {}", label, class.ev, class.ev_shift, cells(class.strategy.as_deref()))?;
+        }
+        write!(f, "{:<16} {:>9.3}", "all", self.mean_ev)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+    use crate::range::Range;
+
+    fn card(s: &str) -> Card {
+        parse_cards(s).unwrap()[0]
+    }
+
+    #[test]
+    fn test_card_classes() {
+        let flop = parse_cards("Ks8h7h").unwrap();
+        assert_eq!(CardClass::of(&flop, card("8c")), CardClass::Pairing);
+        assert_eq!(CardClass::of(&flop, card("2h")), CardClass::FlushCompleting);
+        assert_eq!(CardClass::of(&flop, card("Ad")), CardClass::Overcard);
+        assert_eq!(CardClass::of(&flop, card("Ah")), CardClass::FlushCompleting);
+        assert_eq!(CardClass::of(&flop, card("9c")), CardClass::Blank);
+    }
+
+    #[test]
+    fn test_breakdown_of_turn_check_through() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 1;
+        let mut solver = VectorCfrSolver::new(build_tree(&config), &[Range::parse("AK,77,QJs").unwrap(), Range::parse("AQ,KQ,T9s").unwrap()]);
+        for _ in 0..50 {
+            solver.run_iteration();
+        }
+        // OOP checks, IP checks behind: the river comes
+        let check = solver.tree.get(0).unwrap().children()[0];
+        let report = runout_breakdown(&solver, check, 0).unwrap();
+        assert_eq!(report.player, Player::IP);
+        assert_eq!(report.rows.len(), 48);
+        assert_eq!(report.classes.iter().map(|c| c.cards).sum::<usize>(), 48);
+        assert!(report.rows.iter().all(|r| r.strategy.as_ref().is_some_and(|s| (s.iter().sum::<f64>() - 1.0).abs() < 1e-9)));
+        assert!(report.rows.iter().map(|r| r.ev_shift).sum::<f64>().abs() < 1e-9);
+        let values = solver.node_ip_values();
+        assert!(report.rows.iter().all(|r| r.ev == values[r.node as usize].unwrap()));
+
+        let csv = report.to_csv();
+        assert_eq!(csv.lines().count(), 49);
+        assert!(csv.starts_with("card,class,ev,ev_shift,Check,Check shift,Bet"));
+        assert_eq!(report.to_string().lines().count(), 1 + 48 + report.classes.len() + 1);
+        assert_eq!(runout_breakdown(&solver, 0, 1), Err(RunoutError::StreetContinues { node: 0, action: 1 }));
+    }
+}
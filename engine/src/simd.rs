@@ -0,0 +1,288 @@
+//! SIMD kernels for the per-combo CFR+ inner loops
+//!
+//! The vector solver stores each node's regrets and strategy sums
+//! action-major (`[a * hands + h]`), so every action row is a contiguous run
+//! of combos and the kernels vectorize across combos:
+//!
+//! - `regret_match`: regret-matching+ (and average-strategy) normalization
+//! - `update_regrets`: `r = max(r + cfv[a] - v, 0)`
+//! - `accumulate_strategy`: `s += weight * reach * σ`
+//!
+//! AVX2 is used on x86_64 when the CPU reports it at runtime, NEON on
+//! aarch64 (always present); everything else takes the scalar path. All
+//! paths perform the same operations in the same order per element, so
+//! results are bit-identical across them.
+
+/// Normalize positive values per combo over `actions` rows of `hands`
+/// entries into `out`; combos with no positive value get a uniform strategy
+pub fn regret_match(values: &[f64], actions: usize, hands: usize, out: &mut [f64]) {
+    assert_eq!(values.len(), actions * hands, "values length mismatch");
+    assert_eq!(out.len(), actions * hands, "output length mismatch");
+    #[cfg(target_arch = "x86_64")]
+    if std::is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { avx2::regret_match(values, actions, hands, out) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    return neon::regret_match(values, actions, hands, out);
+    #[allow(unreachable_code)]
+    scalar::regret_match(values, actions, hands, out)
+}
+
+/// CFR+ regret update for one node: for each row `a`, combo `h`,
+/// `regrets[a * H + h] = max(regrets[a * H + h] + action_values[a * H + h] - node_values[h], 0)`
+pub fn update_regrets(regrets: &mut [f64], action_values: &[f64], node_values: &[f64]) {
+    assert_eq!(regrets.len(), action_values.len(), "action values length mismatch");
+    assert!(!node_values.is_empty() && regrets.len().is_multiple_of(node_values.len()), "node values length mismatch");
+    #[cfg(target_arch = "x86_64")]
+    if std::is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { avx2::update_regrets(regrets, action_values, node_values) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    return neon::update_regrets(regrets, action_values, node_values);
+    #[allow(unreachable_code)]
+    scalar::update_regrets(regrets, action_values, node_values)
+}
+
+/// Strategy-sum accumulation for one node:
+/// `sums[a * H + h] += weight * reach[h] * strategy[a * H + h]`
+pub fn accumulate_strategy(sums: &mut [f64], strategy: &[f64], reach: &[f64], weight: f64) {
+    assert_eq!(sums.len(), strategy.len(), "strategy length mismatch");
+    assert!(!reach.is_empty() && sums.len().is_multiple_of(reach.len()), "reach length mismatch");
+    #[cfg(target_arch = "x86_64")]
+    if std::is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 support was just checked
+        return unsafe { avx2::accumulate_strategy(sums, strategy, reach, weight) };
+    }
+    #[cfg(target_arch = "aarch64")]
+    return neon::accumulate_strategy(sums, strategy, reach, weight);
+    #[allow(unreachable_code)]
+    scalar::accumulate_strategy(sums, strategy, reach, weight)
+}
+
+/// Reference implementations; the SIMD paths use them for tail elements
+mod scalar {
+    pub fn regret_match(values: &[f64], actions: usize, hands: usize, out: &mut [f64]) {
+        regret_match_range(values, actions, hands, out, 0);
+    }
+
+    /// `regret_match` for combos `start..hands`
+    pub fn regret_match_range(values: &[f64], actions: usize, hands: usize, out: &mut [f64], start: usize) {
+        for h in start..hands {
+            let mut total = 0.0;
+            for a in 0..actions {
+                total += values[a * hands + h].max(0.0);
+            }
+            for a in 0..actions {
+                out[a * hands + h] = if total > 0.0 { values[a * hands + h].max(0.0) / total } else { 1.0 / actions as f64 };
+            }
+        }
+    }
+
+    pub fn update_regrets(regrets: &mut [f64], action_values: &[f64], node_values: &[f64]) {
+        for (row, values) in regrets.chunks_mut(node_values.len()).zip(action_values.chunks(node_values.len())) {
+            for ((r, &q), &v) in row.iter_mut().zip(values).zip(node_values) {
+                *r = (*r + q - v).max(0.0);
+            }
+        }
+    }
+
+    pub fn accumulate_strategy(sums: &mut [f64], strategy: &[f64], reach: &[f64], weight: f64) {
+        for (row, sigma) in sums.chunks_mut(reach.len()).zip(strategy.chunks(reach.len())) {
+            for ((s, &p), &r) in row.iter_mut().zip(sigma).zip(reach) {
+                *s += weight * r * p;
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+    use super::scalar;
+
+    const LANES: usize = 4;
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn regret_match(values: &[f64], actions: usize, hands: usize, out: &mut [f64]) {
+        let zero = _mm256_setzero_pd();
+        let uniform = _mm256_set1_pd(1.0 / actions as f64);
+        let full = hands - hands % LANES;
+        for h in (0..full).step_by(LANES) {
+            let mut total = zero;
+            for a in 0..actions {
+                let v = _mm256_loadu_pd(values.as_ptr().add(a * hands + h));
+                total = _mm256_add_pd(total, _mm256_max_pd(v, zero));
+            }
+            let positive = _mm256_cmp_pd::<_CMP_GT_OQ>(total, zero);
+            for a in 0..actions {
+                let v = _mm256_max_pd(_mm256_loadu_pd(values.as_ptr().add(a * hands + h)), zero);
+                let p = _mm256_blendv_pd(uniform, _mm256_div_pd(v, total), positive);
+                _mm256_storeu_pd(out.as_mut_ptr().add(a * hands + h), p);
+            }
+        }
+        scalar::regret_match_range(values, actions, hands, out, full);
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn update_regrets(regrets: &mut [f64], action_values: &[f64], node_values: &[f64]) {
+        let zero = _mm256_setzero_pd();
+        let hands = node_values.len();
+        let full = hands - hands % LANES;
+        for (row, values) in regrets.chunks_mut(hands).zip(action_values.chunks(hands)) {
+            for h in (0..full).step_by(LANES) {
+                let r = _mm256_loadu_pd(row.as_ptr().add(h));
+                let q = _mm256_loadu_pd(values.as_ptr().add(h));
+                let v = _mm256_loadu_pd(node_values.as_ptr().add(h));
+                let updated = _mm256_max_pd(_mm256_sub_pd(_mm256_add_pd(r, q), v), zero);
+                _mm256_storeu_pd(row.as_mut_ptr().add(h), updated);
+            }
+            for h in full..hands {
+                row[h] = (row[h] + values[h] - node_values[h]).max(0.0);
+            }
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn accumulate_strategy(sums: &mut [f64], strategy: &[f64], reach: &[f64], weight: f64) {
+        let w = _mm256_set1_pd(weight);
+        let hands = reach.len();
+        let full = hands - hands % LANES;
+        for (row, sigma) in sums.chunks_mut(hands).zip(strategy.chunks(hands)) {
+            for h in (0..full).step_by(LANES) {
+                let s = _mm256_loadu_pd(row.as_ptr().add(h));
+                let p = _mm256_loadu_pd(sigma.as_ptr().add(h));
+                let r = _mm256_loadu_pd(reach.as_ptr().add(h));
+                // (weight * reach) * σ, no FMA, to match the scalar rounding
+                let updated = _mm256_add_pd(s, _mm256_mul_pd(_mm256_mul_pd(w, r), p));
+                _mm256_storeu_pd(row.as_mut_ptr().add(h), updated);
+            }
+            for h in full..hands {
+                row[h] += weight * reach[h] * sigma[h];
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use std::arch::aarch64::*;
+    use super::scalar;
+
+    const LANES: usize = 2;
+
+    pub fn regret_match(values: &[f64], actions: usize, hands: usize, out: &mut [f64]) {
+        let full = hands - hands % LANES;
+        // SAFETY: NEON is baseline on aarch64; every access is at most
+        // `(actions - 1) * hands + full - 1`, inside both slices
+        unsafe {
+            let zero = vdupq_n_f64(0.0);
+            let uniform = vdupq_n_f64(1.0 / actions as f64);
+            for h in (0..full).step_by(LANES) {
+                let mut total = zero;
+                for a in 0..actions {
+                    total = vaddq_f64(total, vmaxq_f64(vld1q_f64(values.as_ptr().add(a * hands + h)), zero));
+                }
+                let positive = vcgtq_f64(total, zero);
+                for a in 0..actions {
+                    let v = vmaxq_f64(vld1q_f64(values.as_ptr().add(a * hands + h)), zero);
+                    let p = vbslq_f64(positive, vdivq_f64(v, total), uniform);
+                    vst1q_f64(out.as_mut_ptr().add(a * hands + h), p);
+                }
+            }
+        }
+        scalar::regret_match_range(values, actions, hands, out, full);
+    }
+
+    pub fn update_regrets(regrets: &mut [f64], action_values: &[f64], node_values: &[f64]) {
+        let hands = node_values.len();
+        let full = hands - hands % LANES;
+        for (row, values) in regrets.chunks_mut(hands).zip(action_values.chunks(hands)) {
+            // SAFETY: NEON is baseline on aarch64; `h + 1 < full <= hands`
+            unsafe {
+                let zero = vdupq_n_f64(0.0);
+                for h in (0..full).step_by(LANES) {
+                    let r = vld1q_f64(row.as_ptr().add(h));
+                    let q = vld1q_f64(values.as_ptr().add(h));
+                    let v = vld1q_f64(node_values.as_ptr().add(h));
+                    vst1q_f64(row.as_mut_ptr().add(h), vmaxq_f64(vsubq_f64(vaddq_f64(r, q), v), zero));
+                }
+            }
+            for h in full..hands {
+                row[h] = (row[h] + values[h] - node_values[h]).max(0.0);
+            }
+        }
+    }
+
+    pub fn accumulate_strategy(sums: &mut [f64], strategy: &[f64], reach: &[f64], weight: f64) {
+        let hands = reach.len();
+        let full = hands - hands % LANES;
+        for (row, sigma) in sums.chunks_mut(hands).zip(strategy.chunks(hands)) {
+            // SAFETY: NEON is baseline on aarch64; `h + 1 < full <= hands`
+            unsafe {
+                let w = vdupq_n_f64(weight);
+                for h in (0..full).step_by(LANES) {
+                    let s = vld1q_f64(row.as_ptr().add(h));
+                    let p = vld1q_f64(sigma.as_ptr().add(h));
+                    let r = vld1q_f64(reach.as_ptr().add(h));
+                    // (weight * reach) * σ, no FMA, to match the scalar rounding
+                    vst1q_f64(row.as_mut_ptr().add(h), vaddq_f64(s, vmulq_f64(vmulq_f64(w, r), p)));
+                }
+            }
+            for h in full..hands {
+                row[h] += weight * reach[h] * sigma[h];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    /// Signed values with exact zeros and all-negative combos mixed in
+    fn values(rng: &mut Rng, n: usize) -> Vec<f64> {
+        (0..n).map(|i| if i % 7 == 3 { 0.0 } else { rng.next_f64() * 2.0 - 1.2 }).collect()
+    }
+
+    #[test]
+    fn test_kernels_match_scalar_reference() {
+        let mut rng = Rng::new(11);
+        // Odd combo counts exercise the scalar tail after the SIMD lanes
+        for (actions, hands) in [(1, 1), (2, 3), (3, 17), (4, 64), (5, 1081)] {
+            let n = actions * hands;
+            let regrets = values(&mut rng, n);
+
+            let (mut fast, mut slow) = (vec![0.0; n], vec![0.0; n]);
+            regret_match(&regrets, actions, hands, &mut fast);
+            scalar::regret_match(&regrets, actions, hands, &mut slow);
+            assert_eq!(fast, slow, "regret_match {}x{}", actions, hands);
+
+            let cfv = values(&mut rng, n);
+            let node: Vec<f64> = values(&mut rng, hands);
+            let (mut fast, mut slow) = (regrets.clone(), regrets.clone());
+            update_regrets(&mut fast, &cfv, &node);
+            scalar::update_regrets(&mut slow, &cfv, &node);
+            assert_eq!(fast, slow, "update_regrets {}x{}", actions, hands);
+            assert!(fast.iter().all(|&r| r >= 0.0));
+
+            let reach: Vec<f64> = (0..hands).map(|_| rng.next_f64()).collect();
+            let sums: Vec<f64> = (0..n).map(|_| rng.next_f64()).collect();
+            let (mut fast, mut slow) = (sums.clone(), sums);
+            accumulate_strategy(&mut fast, &cfv, &reach, 7.0);
+            scalar::accumulate_strategy(&mut slow, &cfv, &reach, 7.0);
+            assert_eq!(fast, slow, "accumulate_strategy {}x{}", actions, hands);
+        }
+    }
+
+    #[test]
+    fn test_regret_match_is_a_distribution_per_combo() {
+        // Combo 0: positive regrets; combo 1: none positive → uniform
+        let regrets = [3.0, -1.0, 1.0, -2.0, 0.0, -5.0];
+        let mut out = [0.0; 6];
+        regret_match(&regrets, 3, 2, &mut out);
+        assert_eq!(out, [0.75, 1.0 / 3.0, 0.25, 1.0 / 3.0, 0.0, 1.0 / 3.0]);
+    }
+}
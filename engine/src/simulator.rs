@@ -0,0 +1,350 @@
+//! Head-to-head play between strategies over sampled deals
+//!
+//! `simulate` plays strategy A against strategy B on seeded deals. Each deal
+//! is played twice with the seats swapped, A as IP and then A as OOP, on the
+//! same hole cards and runout (a duplicate match), so most card luck cancels.
+//! Hole cards are drawn from the ranges by weight, actions are sampled from
+//! each strategy, and chance nodes deal the next card of the deal's runout.
+//! Showdowns use the hand evaluator; a depth-limit leaf short of the river is
+//! run out and checked down. The result is A's EV per deal, in big blinds,
+//! with a normal-approximation 95% confidence interval.
+//!
+//! `simulate_with` can score all-in hands by `AllInScoring::Equity` instead:
+//! once a player is all-in before the river, the hand pays each side its
+//! pot share over every runout (`equity::hand_vs_hand`, "all-in EV") rather
+//! than the runout the deal holds. The expected payoff is the same; the
+//! variance from all-in runouts goes, so the interval narrows.
+//!
+//! Strategies implement `Strategy`: `SolvedStrategy` reads a vector solver's
+//! per-combo average strategy, `Uniform` mixes evenly, and `Purified` plays
+//! the most frequent action of another strategy.
+
+use crate::deck::Deck;
+use crate::equity::hand_vs_hand;
+use crate::evaluator::CactusKevEvaluator;
+use crate::game::terminal_ip_ev;
+use crate::node::{Card, GameTree, Node, NodeId, Player};
+use crate::range::Range;
+use crate::rng::Rng;
+use crate::vector_cfr::VectorCfrSolver;
+
+/// Per-hand action probabilities at decision nodes
+pub trait Strategy: Sync {
+    /// Probability of each action at decision `node` for its acting player
+    /// holding `hand`
+    fn action_probabilities(&self, tree: &GameTree, node: NodeId, hand: [Card; 2]) -> Vec<f64>;
+}
+
+/// Every action equally often
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uniform;
+
+impl Strategy for Uniform {
+    fn action_probabilities(&self, tree: &GameTree, node: NodeId, _hand: [Card; 2]) -> Vec<f64> {
+        let actions = tree.get(node).map_or(0, |n| n.children().len());
+        vec![1.0 / actions as f64; actions]
+    }
+}
+
+/// A vector solver's average strategy; hands outside its ranges play uniformly
+pub struct SolvedStrategy<'a> {
+    solver: &'a VectorCfrSolver,
+}
+
+impl<'a> SolvedStrategy<'a> {
+    pub fn new(solver: &'a VectorCfrSolver) -> Self {
+        SolvedStrategy { solver }
+    }
+}
+
+impl Strategy for SolvedStrategy<'_> {
+    fn action_probabilities(&self, tree: &GameTree, node: NodeId, hand: [Card; 2]) -> Vec<f64> {
+        self.solver.hand_strategy(node, hand).unwrap_or_else(|| Uniform.action_probabilities(tree, node, hand))
+    }
+}
+
+/// Always the most frequent action of the wrapped strategy (first on ties)
+#[derive(Debug, Clone, Copy)]
+pub struct Purified<S>(pub S);
+
+impl<S: Strategy> Strategy for Purified<S> {
+    fn action_probabilities(&self, tree: &GameTree, node: NodeId, hand: [Card; 2]) -> Vec<f64> {
+        let probs = self.0.action_probabilities(tree, node, hand);
+        let best = probs.iter().enumerate().fold(0, |best, (a, &p)| if p > probs[best] { a } else { best });
+        (0..probs.len()).map(|a| if a == best { 1.0 } else { 0.0 }).collect()
+    }
+}
+
+/// How a hand that's all-in before the river is paid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AllInScoring {
+    /// The showdown on the deal's runout
+    #[default]
+    Realized,
+    /// Each player's pot share over every runout (all-in EV)
+    Equity,
+}
+
+/// Mean of sampled payoffs with its standard error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationResult {
+    /// Deals played
+    pub hands: usize,
+    /// Mean payoff per deal (bb)
+    pub ev: f64,
+    /// Standard error of `ev`
+    pub std_error: f64,
+}
+
+impl SimulationResult {
+    /// Mean and standard error of `samples`
+    pub fn from_samples(samples: &[f64]) -> Self {
+        let n = samples.len();
+        if n == 0 {
+            return SimulationResult { hands: 0, ev: 0.0, std_error: 0.0 };
+        }
+        let ev = samples.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 { samples.iter().map(|x| (x - ev).powi(2)).sum::<f64>() / (n - 1) as f64 } else { 0.0 };
+        SimulationResult { hands: n, ev, std_error: (variance / n as f64).sqrt() }
+    }
+
+    /// 95% confidence interval for the EV
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        (self.ev - 1.96 * self.std_error, self.ev + 1.96 * self.std_error)
+    }
+}
+
+/// Hole cards `[IP, OOP]` and the cards still to come, in deal order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deal {
+    pub hands: [[Card; 2]; 2],
+    /// Enough cards to complete the root board to five
+    pub runout: Vec<Card>,
+}
+
+/// Draws deals for one board: a pair of hole cards with probability
+/// proportional to the product of range weights over non-conflicting pairs
+/// (what the solver's values average over), then a uniform runout
+pub struct DealSampler {
+    board: Vec<Card>,
+    combos: [Vec<([Card; 2], f64)>; 2],
+    totals: [f64; 2],
+}
+
+/// Rejected draws before `DealSampler::sample` gives up on conflicting ranges
+const MAX_REDRAWS: usize = 1_000;
+
+impl DealSampler {
+    pub fn new(board: &[Card], ranges: &[Range; 2]) -> Self {
+        let combos = [0, 1].map(|p| ranges[p].live_combos(board).collect::<Vec<_>>());
+        let totals = [0, 1].map(|p| combos[p].iter().map(|(_, w)| w).sum());
+        DealSampler { board: board.to_vec(), combos, totals }
+    }
+
+    /// Next deal, or `None` if the ranges hold no non-conflicting pair
+    pub fn sample(&self, rng: &mut Rng) -> Option<Deal> {
+        if self.totals.iter().any(|&t| t <= 0.0) {
+            return None;
+        }
+        // Independent draws, redrawn on a shared card, give the pair distribution exactly
+        for _ in 0..MAX_REDRAWS {
+            let ip = self.pick(0, rng);
+            let oop = self.pick(1, rng);
+            if oop.iter().any(|c| ip.contains(c)) {
+                continue;
+            }
+            let mut deck = Deck::without(&self.board);
+            deck.remove_all(&ip);
+            deck.remove_all(&oop);
+            let runout = (self.board.len()..5).map(|_| deck.deal(rng)).collect::<Option<Vec<Card>>>()?;
+            return Some(Deal { hands: [ip, oop], runout });
+        }
+        None
+    }
+
+    fn pick(&self, p: usize, rng: &mut Rng) -> [Card; 2] {
+        let combos = &self.combos[p];
+        let mut target = rng.next_f64() * self.totals[p];
+        for &(hand, w) in combos {
+            if target < w {
+                return hand;
+            }
+            target -= w;
+        }
+        combos[combos.len() - 1].0
+    }
+}
+
+/// Play one deal from the root with `strategies` indexed `[IP, OOP]`;
+/// returns IP's payoff (bb).
+///
+/// Panics if the tree groups isomorphic runouts: a class's representative
+/// card isn't the card that was dealt.
+pub fn play_hand(tree: &GameTree, strategies: [&dyn Strategy; 2], deal: &Deal, rng: &mut Rng) -> f64 {
+    play(tree, strategies, deal, rng, AllInScoring::Realized, &mut Vec::new())
+}
+
+/// `play_hand` scoring all-ins by `scoring`, also recording every node the
+/// hand passes through (up to the all-in, when scored by equity)
+pub(crate) fn play(tree: &GameTree, strategies: [&dyn Strategy; 2], deal: &Deal, rng: &mut Rng, scoring: AllInScoring, path: &mut Vec<NodeId>) -> f64 {
+    let root_stacks = match tree.get(0) {
+        Some(Node::Decision { stacks, .. }) | Some(Node::Chance { stacks, .. }) | Some(Node::Terminal { stacks, .. }) => *stacks,
+        None => return 0.0,
+    };
+    let root_board = tree.get(0).map_or(0, |n| n.board().len());
+    let mut id: NodeId = 0;
+    loop {
+        path.push(id);
+        match tree.get(id).expect("invalid node id") {
+            Node::Decision { player, children, .. } => {
+                let seat = if *player == Player::IP { 0 } else { 1 };
+                let probs = strategies[seat].action_probabilities(tree, id, deal.hands[seat]);
+                let mut target = rng.next_f64() * probs.iter().sum::<f64>();
+                let mut choice = children.len() - 1;
+                for (a, &p) in probs.iter().enumerate() {
+                    if target < p {
+                        choice = a;
+                        break;
+                    }
+                    target -= p;
+                }
+                id = children[choice];
+            }
+            Node::Chance { children, weights, board, stacks, .. } => {
+                if scoring == AllInScoring::Equity && stacks.iter().any(|&s| s <= 0.0) {
+                    // Nobody acts again, so every runout ends at a showdown of the same pot
+                    let mut showdown = id;
+                    while let Some(&child) = tree.get(showdown).and_then(|n| n.children().first()) {
+                        showdown = child;
+                    }
+                    let equity = hand_vs_hand(&CactusKevEvaluator::new(), deal.hands[0], deal.hands[1], board);
+                    return terminal_ip_ev(tree.get(showdown).expect("invalid node id"), root_stacks, equity);
+                }
+                assert!(weights.is_empty(), "simulation needs every runout dealt");
+                let card = deal.runout[board.len() - root_board];
+                id = *children.iter()
+                    .find(|&&c| tree.get(c).and_then(|n| n.board().last()) == Some(&card))
+                    .expect("chance node deals every live card");
+            }
+            node @ Node::Terminal { folder, board, .. } => {
+                if folder.is_some() {
+                    return terminal_ip_ev(node, root_stacks, 0.0);
+                }
+                let mut full = [Card::new(0); 5];
+                full[..board.len()].copy_from_slice(board);
+                full[board.len()..].copy_from_slice(&deal.runout[board.len() - root_board..]);
+                let evaluator = CactusKevEvaluator::new();
+                let (ip, oop) = (evaluator.evaluate_7cards(full, deal.hands[0]), evaluator.evaluate_7cards(full, deal.hands[1]));
+                let equity = if ip.value() < oop.value() { 1.0 } else if ip == oop { 0.5 } else { 0.0 };
+                return terminal_ip_ev(node, root_stacks, equity);
+            }
+        }
+    }
+}
+
+/// Duplicate match of `a` against `b` over `hands` seeded deals (see module
+/// docs); the result is `a`'s EV per deal, averaged over both seats
+pub fn simulate<A: Strategy, B: Strategy>(tree: &GameTree, ranges: &[Range; 2], a: &A, b: &B, hands: usize, seed: u64) -> SimulationResult {
+    simulate_with(tree, ranges, a, b, hands, seed, AllInScoring::Realized)
+}
+
+/// `simulate`, paying all-in hands by `scoring`
+pub fn simulate_with<A: Strategy, B: Strategy>(
+    tree: &GameTree,
+    ranges: &[Range; 2],
+    a: &A,
+    b: &B,
+    hands: usize,
+    seed: u64,
+    scoring: AllInScoring,
+) -> SimulationResult {
+    let sampler = DealSampler::new(tree.get(0).map_or(&[], |n| n.board()), ranges);
+    let mut rng = Rng::new(seed);
+    let mut samples = Vec::with_capacity(hands);
+    for _ in 0..hands {
+        let Some(deal) = sampler.sample(&mut rng) else { break };
+        let a_as_ip = play(tree, [a, b], &deal, &mut rng.split(), scoring, &mut Vec::new());
+        let a_as_oop = -play(tree, [b, a], &deal, &mut rng.split(), scoring, &mut Vec::new());
+        samples.push((a_as_ip + a_as_oop) / 2.0);
+    }
+    SimulationResult::from_samples(&samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+
+    fn solved_river() -> (GameTree, [Range; 2], VectorCfrSolver) {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c9s").unwrap());
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 2;
+        let tree = build_tree(&config);
+        let ranges = [Range::parse("AK,77,QJs,T8s").unwrap(), Range::parse("AQ,KQ,99,65s").unwrap()];
+        let mut solver = VectorCfrSolver::new(tree.clone(), &ranges);
+        for _ in 0..200 {
+            solver.run_iteration();
+        }
+        (tree, ranges, solver)
+    }
+
+    #[test]
+    fn test_solution_beats_uniform_and_ties_itself() {
+        let (tree, ranges, solver) = solved_river();
+        let solved = SolvedStrategy::new(&solver);
+        let vs_uniform = simulate(&tree, &ranges, &solved, &Uniform, 2_000, 1);
+        assert_eq!(vs_uniform.hands, 2_000);
+        assert!(vs_uniform.confidence_interval().0 > 0.0, "{:?}", vs_uniform);
+        assert_eq!(vs_uniform, simulate(&tree, &ranges, &solved, &Uniform, 2_000, 1));
+
+        let mirror = simulate(&tree, &ranges, &solved, &solved, 2_000, 2);
+        let (lo, hi) = mirror.confidence_interval();
+        assert!(lo < 0.0 && hi > 0.0, "{:?}", mirror);
+    }
+
+    #[test]
+    fn test_purified_plays_one_action() {
+        let (tree, ranges, solver) = solved_river();
+        let pure = Purified(SolvedStrategy::new(&solver));
+        let hand = ranges[1].live_combos(tree.get(0).unwrap().board()).next().unwrap().0;
+        let probs = pure.action_probabilities(&tree, 0, hand);
+        assert_eq!(probs.iter().filter(|&&p| p == 1.0).count(), 1);
+        assert_eq!(probs.iter().sum::<f64>(), 1.0);
+        let stats = SimulationResult::from_samples(&[1.0, 3.0]);
+        assert_eq!((stats.ev, stats.std_error), (2.0, 1.0));
+    }
+
+    /// Always the last action: the all-in, then the call
+    struct Jam;
+
+    impl Strategy for Jam {
+        fn action_probabilities(&self, tree: &GameTree, node: NodeId, _hand: [Card; 2]) -> Vec<f64> {
+            let actions = tree.get(node).map_or(0, |n| n.children().len());
+            (0..actions).map(|a| if a + 1 == actions { 1.0 } else { 0.0 }).collect()
+        }
+    }
+
+    #[test]
+    fn test_all_in_equity_pays_the_pot_share_over_every_runout() {
+        let mut config = TreeConfig::new(GameConfig::from_pot(10.0, [5.0, 5.0]), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        let tree = build_tree(&config);
+        let hand = |s: &str| -> [Card; 2] { parse_cards(s).unwrap().try_into().unwrap() };
+        let deal = |river: &str| Deal { hands: [hand("AhAd"), hand("KcKd")], runout: parse_cards(river).unwrap() };
+        let play_with = |deal: &Deal, scoring| play(&tree, [&Jam, &Jam], deal, &mut Rng::new(1), scoring, &mut Vec::new());
+        let (win, lose) = (play_with(&deal("3h"), AllInScoring::Realized), play_with(&deal("Ks"), AllInScoring::Realized));
+        assert!(win > 0.0 && lose < 0.0);
+        // Kings need the last king, 1 of 44 rivers
+        let all_in = play_with(&deal("Ks"), AllInScoring::Equity);
+        assert!((all_in - (43.0 * win + lose) / 44.0).abs() < 1e-9, "{}", all_in);
+        assert_eq!(play_with(&deal("3h"), AllInScoring::Equity), all_in);
+
+        let ranges = [Range::parse("AA,KK,AK,QJs").unwrap(), Range::parse("QQ+,AQ,T9s").unwrap()];
+        let realized = simulate_with(&tree, &ranges, &Jam, &Uniform, 500, 3, AllInScoring::Realized);
+        let equity = simulate_with(&tree, &ranges, &Jam, &Uniform, 500, 3, AllInScoring::Equity);
+        assert!(equity.std_error < realized.std_error, "{:?} {:?}", equity, realized);
+        assert!((equity.ev - realized.ev).abs() < 1.96 * (realized.std_error + equity.std_error));
+    }
+}
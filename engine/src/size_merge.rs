@@ -0,0 +1,240 @@
+//! Size merging: dropping bet sizes a solution plays alike
+//!
+//! Every bet size multiplies the tree, and a solve often shows neighbouring
+//! sizes doing the same job. `find_size_merges` compares each pair of
+//! neighbouring bets (or raises) at a decision: the acting player's EV
+//! after each (`analysis::node_values`) and the opponent's average strategy
+//! facing each. A pair within `MergeTolerance` on both is proposed as a
+//! merge: the less played size goes and its frequency moves to the other.
+//! Sizes are walked smallest first and a kept size is compared with the
+//! next one, so a run of alike sizes collapses into one.
+//!
+//! `merge_ev_loss` bounds what the merges give up. Each player's opponent
+//! best-responds to the merged strategy, and the gain over best-responding
+//! to the original one, summed for both players, is the EV lost. Only the
+//! merged nodes change and nobody re-optimizes around them, so a re-solve
+//! of the smaller tree loses no more (as for
+//! `analysis::action_removal_ev_loss`).
+//!
+//! `merge_sizes` performs the merges on a solution: the dropped sizes and
+//! their subtrees leave the tree, and each kept size's regrets and strategy
+//! sums absorb the dropped one's, so the smaller solution plays the merged
+//! strategy and can be resumed.
+
+use std::collections::HashMap;
+use crate::par::join;
+use crate::analysis::node_values;
+use crate::cfr::RegretStorage;
+use crate::ev_table::TerminalEvs;
+use crate::exploitability::br_traverse_with_strategy;
+use crate::node::{Action, GameTree, Node, NodeId, Player};
+use crate::prune::{remove_actions, PrunedSolution};
+use crate::solution::Solution;
+use crate::summation::Summation;
+
+/// How alike two sizes must be to merge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MergeTolerance {
+    /// Largest gap between the acting player's EVs, as a fraction of the pot
+    pub ev: f64,
+    /// Largest gap between any of the opponent's response frequencies
+    pub strategy: f64,
+}
+
+impl Default for MergeTolerance {
+    fn default() -> Self {
+        MergeTolerance { ev: 0.01, strategy: 0.05 }
+    }
+}
+
+/// One proposed merge of two sizes at an infoset
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeMerge {
+    /// First decision node in the infoset
+    pub node: NodeId,
+    pub infoset: usize,
+    pub player: Player,
+    /// Index of the size kept
+    pub keep: usize,
+    /// Index of the size dropped
+    pub remove: usize,
+    pub kept: Action,
+    pub removed: Action,
+    /// Frequency moved from the dropped size to the kept one
+    pub frequency: f64,
+    /// Acting player's EV after the kept size minus after the dropped one, bb
+    pub ev_gap: f64,
+    /// Largest gap between the opponent's response frequencies
+    pub strategy_gap: f64,
+}
+
+/// Merges of alike neighbouring sizes across the tree (see module docs)
+pub fn find_size_merges(tree: &GameTree, storage: &RegretStorage, terminal_evs: &impl TerminalEvs, tolerance: MergeTolerance) -> Vec<SizeMerge> {
+    let values = node_values(tree, storage, terminal_evs);
+    let mut seen = vec![false; storage.num_infosets()];
+    let mut merges = Vec::new();
+    for node in &tree.nodes {
+        let Node::Decision { id, infoset_id, player, actions, children, pot, .. } = node else { continue };
+        let infoset = *infoset_id as usize;
+        if std::mem::replace(&mut seen[infoset], true) {
+            continue;
+        }
+        let mut probs = storage.average_strategy(infoset);
+        let ev = |a: usize| if *player == Player::IP { values[children[a] as usize] } else { -values[children[a] as usize] };
+        for kind in [is_bet as fn(&Action) -> bool, is_raise] {
+            let mut sizes: Vec<usize> = (0..actions.len()).filter(|&a| kind(&actions[a])).collect();
+            sizes.sort_by(|&a, &b| size(&actions[a]).total_cmp(&size(&actions[b])));
+            let Some((&first, rest)) = sizes.split_first() else { continue };
+            let mut current = first;
+            for &next in rest {
+                let strategy_gap = response_gap(tree, storage, children[current], children[next]);
+                let ev_gap = (ev(current) - ev(next)).abs();
+                if strategy_gap.is_none_or(|gap| gap > tolerance.strategy) || ev_gap > tolerance.ev * pot {
+                    current = next;
+                    continue;
+                }
+                let (keep, remove) = if probs[next] > probs[current] { (next, current) } else { (current, next) };
+                merges.push(SizeMerge {
+                    node: *id,
+                    infoset,
+                    player: *player,
+                    keep,
+                    remove,
+                    kept: actions[keep],
+                    removed: actions[remove],
+                    frequency: probs[remove],
+                    ev_gap: ev(keep) - ev(remove),
+                    strategy_gap: strategy_gap.unwrap_or(0.0),
+                });
+                probs[keep] += probs[remove];
+                probs[remove] = 0.0;
+                current = keep;
+            }
+        }
+    }
+    merges
+}
+
+/// Largest gap between the opponent's average strategies after two sizes,
+/// `None` unless both face them with the same number of actions
+fn response_gap(tree: &GameTree, storage: &RegretStorage, a: NodeId, b: NodeId) -> Option<f64> {
+    match (tree.get(a)?, tree.get(b)?) {
+        (Node::Decision { infoset_id: x, actions: xs, .. }, Node::Decision { infoset_id: y, actions: ys, .. }) if xs.len() == ys.len() => {
+            let (x, y) = (storage.average_strategy(*x as usize), storage.average_strategy(*y as usize));
+            Some(x.iter().zip(&y).map(|(p, q)| (p - q).abs()).fold(0.0, f64::max))
+        }
+        _ => None,
+    }
+}
+
+fn is_bet(action: &Action) -> bool {
+    matches!(action, Action::Bet { .. })
+}
+
+fn is_raise(action: &Action) -> bool {
+    matches!(action, Action::Raise { .. })
+}
+
+fn size(action: &Action) -> f64 {
+    match action {
+        Action::Bet { size } | Action::Raise { size } => *size,
+        _ => 0.0,
+    }
+}
+
+/// EV the merges give up, summed over both players (see module docs)
+pub fn merge_ev_loss(tree: &GameTree, storage: &RegretStorage, terminal_evs: &impl TerminalEvs, merges: &[SizeMerge]) -> f64 {
+    let mut by_infoset: HashMap<usize, Vec<&SizeMerge>> = HashMap::new();
+    for merge in merges {
+        by_infoset.entry(merge.infoset).or_default().push(merge);
+    }
+    let average = |infoset: usize| storage.average_strategy(infoset);
+    let merged = |infoset: usize| {
+        let mut probs = storage.average_strategy(infoset);
+        for merge in by_infoset.get(&infoset).into_iter().flatten() {
+            probs[merge.keep] += probs[merge.remove];
+            probs[merge.remove] = 0.0;
+        }
+        probs
+    };
+    let gain = |p: Player| {
+        let before = br_traverse_with_strategy(tree, &average, terminal_evs, 0, p, Summation::Naive);
+        br_traverse_with_strategy(tree, &merged, terminal_evs, 0, p, Summation::Naive) - before
+    };
+    let (ip, oop) = join(|| gain(Player::IP), || gain(Player::OOP));
+    ip + oop
+}
+
+/// `solution` with the merges performed (see module docs)
+pub fn merge_sizes(solution: &Solution, merges: &[SizeMerge]) -> PrunedSolution {
+    let mut storage = solution.storage.clone();
+    let mut keep: HashMap<usize, Vec<bool>> = storage.regrets.iter().enumerate().map(|(i, row)| (i, vec![true; row.len()])).collect();
+    for merge in merges {
+        for rows in [&mut storage.regrets, &mut storage.strategy_sums] {
+            let row = &mut rows[merge.infoset];
+            row[merge.keep] += row[merge.remove];
+            row[merge.remove] = 0.0;
+        }
+        keep.get_mut(&merge.infoset).expect("merges name stored infosets")[merge.remove] = false;
+    }
+    remove_actions(solution, &storage, &keep)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::StreetSizes;
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+    use crate::range::Range;
+    use crate::solve::{solve, SolveSpec};
+    use crate::StopCondition;
+
+    #[test]
+    fn test_merges_alike_sizes_with_a_small_loss() {
+        let ranges = [Range::parse("AA,KK,AK,QJs").unwrap(), Range::parse("QQ-99,AQ,KQ").unwrap()];
+        let mut spec = SolveSpec::new(parse_cards("AsKh7d2c9s").unwrap(), ranges, GameConfig::new());
+        spec.tree_config.river = StreetSizes::new(vec![0.5, 0.52, 1.5]);
+        spec.tree_config.max_raises = 1;
+        spec.stop = StopCondition::iterations(2_000);
+        let solution = Solution::from_solver(&solve(&spec).unwrap().solver);
+        let storage = RegretStorage::from_snapshot(solution.storage.clone());
+        let evs = solution.terminal_ev_map();
+
+        // IP overbets after a check and OOP folds to every size, so the
+        // unplayed sizes fold into the overbet
+        let merges = find_size_merges(&solution.tree, &storage, &evs, MergeTolerance::default());
+        let after_check: Vec<&SizeMerge> = merges.iter().filter(|m| m.node == 1).collect();
+        assert_eq!(after_check.len(), 2);
+        assert_eq!(after_check.iter().map(|m| m.remove).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(after_check[1].kept, Action::Bet { size: 8.25 });
+        assert!(after_check.iter().all(|m| m.frequency < 1e-3));
+        let none = MergeTolerance { ev: 0.0, strategy: 0.0 };
+        assert!(find_size_merges(&solution.tree, &storage, &evs, none).len() < merges.len());
+
+        // A looser EV tolerance also merges OOP's half-pot sizes, not the overbet
+        let loose = find_size_merges(&solution.tree, &storage, &evs, MergeTolerance { ev: 0.05, strategy: 0.05 });
+        let root: Vec<&SizeMerge> = loose.iter().filter(|m| m.node == 0).collect();
+        assert_eq!(root.len(), 1);
+        assert_eq!((root[0].keep, root[0].remove), (1, 2));
+        for m in &loose {
+            let pot = solution.tree.get(m.node).unwrap().pot();
+            assert!(m.ev_gap.abs() <= 0.05 * pot && m.strategy_gap <= 0.05, "{:?}", m);
+        }
+
+        let loss = merge_ev_loss(&solution.tree, &storage, &evs, &loose);
+        assert!(loss.abs() < 0.01, "{}", loss);
+        assert_eq!(merge_ev_loss(&solution.tree, &storage, &evs, &[]), 0.0);
+
+        let merged = merge_sizes(&solution, &loose);
+        assert!(merged.solution.validate().is_ok());
+        assert_eq!(merged.removed_actions, loose.len());
+        assert!(merged.solution.tree.len() < solution.tree.len());
+        let Some(Node::Decision { actions, .. }) = merged.solution.tree.get(0) else { panic!("root decision") };
+        assert_eq!(actions.len(), 3);
+        // The kept half-pot bet plays both sizes' frequency
+        let before = storage.average_strategy(0);
+        let after = RegretStorage::from_snapshot(merged.solution.storage.clone()).average_strategy(0);
+        assert!((after[1] - before[1] - before[2]).abs() < 1e-9);
+    }
+}
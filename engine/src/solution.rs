@@ -0,0 +1,647 @@
+//! Portable solution type: tree + solver arrays + terminal EVs (+ ranges)
+//!
+//! `Solution` is the one shape every save/load, export, and server feature
+//! shares. With the `serde` feature enabled it (and the tree, node, range,
+//! and config types) implement `Serialize`/`Deserialize`.
+//!
+//! Schema stability: the field set is versioned by `SOLUTION_SCHEMA_VERSION`,
+//! bumped whenever a field is added, removed, or changes meaning. Terminal
+//! EVs are stored sorted by node ID so equal solutions serialize to equal
+//! bytes.
+//!
+//! `fingerprint` hashes the tree, the solver options the solution was
+//! captured with, the storage arrays, terminal EVs, and iteration count (see
+//! `fingerprint`). Only the config part is stored, since the config itself
+//! isn't; the rest is recomputed from the saved content, so the fingerprint
+//! can't go stale when a solution is edited.
+//!
+//! `blend` mixes two solutions of the same tree infoset by infoset, e.g. a
+//! GTO solve with a node-locked exploitative one, to get a strategy that
+//! exploits partway while giving up less than the full exploit would.
+//!
+//! `resume` rebuilds a solver that carries on from a solution, with any
+//! `SolverConfig`. The solution records the algorithm that produced it, and
+//! `config.resume` (`ResumeWeighting`) decides how the new solver counts
+//! and weights the inherited iterations, including after a switch to
+//! another variant. Continuing with the same config finishes bit for bit
+//! where an uninterrupted solve would; for that, PCFR+ and pruned solves
+//! keep the last iteration's regrets in `storage`, and others drop them.
+//!
+//! `infosets` labels every infoset with who acts, the street, the line to
+//! it (`line::format_line`), and the board, so exporters and viewers can walk
+//! the strategy rows without working out which nodes share an infoset.
+//!
+//! `partial` keeps only the infosets some node of which is reached at least
+//! `min_reach` often (`prune::node_reach`), for exploration files that never
+//! visit improbable lines. The tree stays whole; the rows of the infosets
+//! left out are zeroed (so they read as uniform) and listed in `omitted`,
+//! and `save_solution` writes them as empty rows. A partial solution can be
+//! explored and exported but not resumed or blended, and `act` refuses to
+//! play from an omitted node.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::bot::{check_hole_cards, locate, ActError, GameState, OffTree};
+use crate::cfr::{CfrSolver, RegretStorage, StorageSnapshot};
+use crate::config::{Algorithm, SolverConfig};
+use crate::exploitability::compute_exploitability_with_evs;
+use crate::fingerprint::{config_fingerprint, tree_fingerprint, Fingerprint, Fingerprinter};
+use crate::line::format_line;
+use crate::node::{Action, Card, GameTree, Node, NodeId, Player, Street};
+use crate::prune::node_reach;
+use crate::range::Range;
+use crate::rng::Rng;
+
+/// Current `Solution` schema version
+pub const SOLUTION_SCHEMA_VERSION: u32 = 7;
+
+/// Error returned when a loaded `Solution` can't be used
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolutionError {
+    /// Written by a different schema version
+    SchemaVersion { found: u32, expected: u32 },
+    /// Storage arrays don't line up with the tree
+    Mismatch(String),
+    /// A partial solution (see `partial`) used where every infoset is needed
+    Partial { omitted: usize },
+}
+
+impl std::fmt::Display for SolutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolutionError::SchemaVersion { found, expected } => {
+                write!(f, "solution schema version {} (expected {})", found, expected)
+            }
+            SolutionError::Mismatch(m) => write!(f, "solution does not match its tree: {}", m),
+            SolutionError::Partial { omitted } => write!(f, "partial solution: {} infosets were left out", omitted),
+        }
+    }
+}
+
+impl std::error::Error for SolutionError {}
+
+/// A blended solution and how exploitable its strategy is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blend {
+    pub solution: Solution,
+    /// Total exploitability of the blend (in bb)
+    pub exploitability: f64,
+    /// IP's and OOP's best-response gains against the blend
+    pub ip_br_value: f64,
+    pub oop_br_value: f64,
+}
+
+/// Who acts at an infoset and where it sits in the tree (see `Solution::infosets`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfosetLabel {
+    /// Index into the storage rows
+    pub infoset: usize,
+    /// First decision node in the infoset
+    pub node: NodeId,
+    pub player: Player,
+    pub street: Street,
+    /// Line from the root to `node`, e.g. `F:x-b33|T:7h`
+    pub line: String,
+    pub board: Vec<Card>,
+}
+
+/// A solved (or partially solved) game
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Solution {
+    pub schema_version: u32,
+    pub tree: GameTree,
+    /// Regrets and strategy sums, indexed by infoset ID
+    pub storage: StorageSnapshot,
+    /// Terminal EVs (IP perspective), sorted by node ID
+    pub terminal_evs: Vec<(NodeId, f64)>,
+    /// CFR iterations run
+    pub iterations: u64,
+    /// Variant that ran them, which weighted the strategy sums
+    pub algorithm: Algorithm,
+    /// Ranges `[IP, OOP]` the solve started from, when known
+    pub ranges: Option<[Range; 2]>,
+    /// Fingerprint of the result-affecting solver options
+    pub config_fingerprint: Fingerprint,
+    /// Infosets left out by `partial`, ascending; empty for a full solution
+    pub omitted: Vec<usize>,
+}
+
+impl Solution {
+    /// Capture a solver's current state
+    pub fn from_solver(solver: &CfrSolver) -> Self {
+        let terminal_evs: Vec<(NodeId, f64)> = solver.terminal_evs().iter().collect();
+        let config = solver.config();
+        let mut storage = solver.storage.snapshot();
+        if config.algorithm != Algorithm::PredictiveCfrPlus && config.prune.is_none() {
+            storage.last_regrets = Vec::new();
+        }
+        Solution {
+            schema_version: SOLUTION_SCHEMA_VERSION,
+            tree: solver.tree.clone(),
+            storage,
+            terminal_evs,
+            iterations: solver.iteration,
+            algorithm: config.algorithm,
+            ranges: None,
+            config_fingerprint: config_fingerprint(config),
+            omitted: Vec::new(),
+        }
+    }
+
+    /// Stable hash of everything that determines this result (see module docs)
+    pub fn fingerprint(&self) -> Fingerprint {
+        let mut h = Fingerprinter::new();
+        h.tree(&self.tree);
+        h.u64(self.config_fingerprint.0);
+        h.storage(&self.storage);
+        h.usize(self.terminal_evs.len());
+        for &(id, ev) in &self.terminal_evs {
+            h.u64(id as u64);
+            h.f64(ev);
+        }
+        h.u64(self.iterations);
+        if !self.omitted.is_empty() {
+            h.usize(self.omitted.len());
+            self.omitted.iter().for_each(|&i| h.usize(i));
+        }
+        h.finish()
+    }
+
+    /// Attach the starting ranges (needed for per-hand views like strategy grids)
+    pub fn with_ranges(mut self, ranges: [Range; 2]) -> Self {
+        self.ranges = Some(ranges);
+        self
+    }
+
+    /// Check the schema version and that storage matches the tree
+    pub fn validate(&self) -> Result<(), SolutionError> {
+        if self.schema_version != SOLUTION_SCHEMA_VERSION {
+            return Err(SolutionError::SchemaVersion { found: self.schema_version, expected: SOLUTION_SCHEMA_VERSION });
+        }
+        let infosets = self.tree.num_infosets();
+        if self.storage.regrets.len() != infosets || self.storage.strategy_sums.len() != infosets {
+            return Err(SolutionError::Mismatch(format!(
+                "{} infosets but {} regret slots", infosets, self.storage.regrets.len()
+            )));
+        }
+        for node in &self.tree.nodes {
+            let Some(id) = node.infoset_id() else { continue };
+            let (id, actions) = (id as usize, node.children().len());
+            if self.storage.regrets[id].len() != actions || self.storage.strategy_sums[id].len() != actions {
+                return Err(SolutionError::Mismatch(format!("infoset {} action count", id)));
+            }
+        }
+        let last = &self.storage.last_regrets;
+        if !last.is_empty() && (last.len() != infosets || last.iter().zip(&self.storage.regrets).any(|(l, r)| l.len() != r.len())) {
+            return Err(SolutionError::Mismatch("last regrets don't match the regret arrays".to_string()));
+        }
+        if !self.omitted.windows(2).all(|w| w[0] < w[1]) || self.omitted.last().is_some_and(|&i| i >= infosets) {
+            return Err(SolutionError::Mismatch("omitted infosets out of order or range".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Every infoset in ID order, labelled from its first decision node
+    pub fn infosets(&self) -> impl Iterator<Item = InfosetLabel> + '_ {
+        let mut first: Vec<Option<NodeId>> = vec![None; self.tree.num_infosets()];
+        for node in &self.tree.nodes {
+            if let Some(id) = node.infoset_id() {
+                first[id as usize].get_or_insert(node.id());
+            }
+        }
+        first.into_iter().enumerate().filter_map(move |(infoset, node)| match self.tree.get(node?)? {
+            Node::Decision { id, player, street, board, .. } => Some(InfosetLabel {
+                infoset,
+                node: *id,
+                player: *player,
+                street: *street,
+                line: format_line(&self.tree, *id),
+                board: board.clone(),
+            }),
+            _ => None,
+        })
+    }
+
+    /// Whether `partial` left some infosets out
+    pub fn is_partial(&self) -> bool {
+        !self.omitted.is_empty()
+    }
+
+    /// Whether `partial` left `infoset` out
+    pub fn is_omitted(&self, infoset: usize) -> bool {
+        self.omitted.binary_search(&infoset).is_ok()
+    }
+
+    /// This solution without the infosets no node of which is reached at
+    /// least `min_reach` often under the average strategy (see module docs).
+    /// Infosets already left out stay out; 0.0 keeps everything.
+    pub fn partial(&self, min_reach: f64) -> Solution {
+        let reach = node_reach(&self.tree, &RegretStorage::from_snapshot(self.storage.clone()));
+        let mut infoset_reach = vec![0.0f64; self.tree.num_infosets()];
+        for node in &self.tree.nodes {
+            let Some(id) = node.infoset_id() else { continue };
+            infoset_reach[id as usize] = infoset_reach[id as usize].max(reach[node.id() as usize]);
+        }
+        let mut partial = self.clone();
+        partial.storage.last_regrets = Vec::new();
+        for (infoset, &r) in infoset_reach.iter().enumerate() {
+            if r < min_reach && !self.is_omitted(infoset) {
+                partial.storage.regrets[infoset].fill(0.0);
+                partial.storage.strategy_sums[infoset].fill(0.0);
+                partial.omitted.push(infoset);
+            }
+        }
+        partial.omitted.sort_unstable();
+        partial
+    }
+
+    /// Copy with the omitted rows emptied, as `save_solution` writes it
+    #[cfg(feature = "serde")]
+    pub(crate) fn compacted(&self) -> Solution {
+        let mut compact = self.clone();
+        for &i in &self.omitted {
+            compact.storage.regrets[i] = Vec::new();
+            compact.storage.strategy_sums[i] = Vec::new();
+        }
+        compact
+    }
+
+    /// Undo `compacted`: zero rows sized to each omitted infoset's actions
+    #[cfg(feature = "serde")]
+    pub(crate) fn expand_omitted(&mut self) {
+        if self.omitted.is_empty() {
+            return;
+        }
+        for node in &self.tree.nodes {
+            let Some(id) = node.infoset_id() else { continue };
+            let (id, actions) = (id as usize, node.children().len());
+            if self.is_omitted(id) && id < self.storage.regrets.len() && id < self.storage.strategy_sums.len() {
+                self.storage.regrets[id].resize(actions, 0.0);
+                self.storage.strategy_sums[id].resize(actions, 0.0);
+            }
+        }
+    }
+
+    /// `validate`, and that no infoset was left out
+    fn validate_complete(&self) -> Result<(), SolutionError> {
+        self.validate()?;
+        if self.is_partial() {
+            return Err(SolutionError::Partial { omitted: self.omitted.len() });
+        }
+        Ok(())
+    }
+
+    /// Terminal EVs as the map the solver uses
+    pub fn terminal_ev_map(&self) -> HashMap<NodeId, f64> {
+        self.terminal_evs.iter().copied().collect()
+    }
+
+    /// Sample the stored average strategy at the node `state` reaches (see
+    /// `bot`), mapping off-tree bet sizes to the nearest one
+    pub fn act(&self, state: &GameState, hole_cards: [Card; 2], rng: &mut Rng) -> Result<Action, ActError> {
+        self.act_with(state, hole_cards, rng, OffTree::Nearest)
+    }
+
+    /// `act` with explicit off-tree handling
+    pub fn act_with(&self, state: &GameState, hole_cards: [Card; 2], rng: &mut Rng, off_tree: OffTree) -> Result<Action, ActError> {
+        check_hole_cards(&state.board, hole_cards)?;
+        let node = locate(&self.tree, state, off_tree)?;
+        let Some(Node::Decision { infoset_id, actions, .. }) = self.tree.get(node) else {
+            unreachable!("locate returns decision nodes");
+        };
+        if self.is_omitted(*infoset_id as usize) {
+            return Err(ActError::Omitted(node));
+        }
+        let sums = &self.storage.strategy_sums[*infoset_id as usize];
+        let total: f64 = sums.iter().sum();
+        // Nothing accumulated yet plays uniformly, like `RegretStorage::average_strategy`
+        let weight = |a: usize| if total > 0.0 { sums[a] } else { 1.0 };
+        let mut target = rng.next_f64() * (0..sums.len()).map(weight).sum::<f64>();
+        for (a, &action) in actions.iter().enumerate() {
+            if target < weight(a) {
+                return Ok(action);
+            }
+            target -= weight(a);
+        }
+        Ok(actions[actions.len() - 1])
+    }
+
+    /// Mix this solution's average strategy with `other`'s: at every
+    /// infoset, `(1 - alpha) * self + alpha * other`, renormalized to sum to
+    /// 1. Both must share a tree and terminal EVs.
+    ///
+    /// The blend's strategy sums hold the mixed strategy itself and its
+    /// regrets are mixed the same way; its iteration count is 0, since no
+    /// solver produced it. Panics unless `alpha` is in `[0, 1]`.
+    pub fn blend(&self, other: &Solution, alpha: f64) -> Result<Blend, SolutionError> {
+        assert!((0.0..=1.0).contains(&alpha), "blend alpha must be in [0, 1], got {}", alpha);
+        self.validate_complete()?;
+        other.validate_complete()?;
+        if tree_fingerprint(&self.tree) != tree_fingerprint(&other.tree) {
+            return Err(SolutionError::Mismatch("blended solutions have different trees".to_string()));
+        }
+        if self.terminal_evs != other.terminal_evs {
+            return Err(SolutionError::Mismatch("blended solutions have different terminal EVs".to_string()));
+        }
+
+        let mine = RegretStorage::from_snapshot(self.storage.clone());
+        let theirs = RegretStorage::from_snapshot(other.storage.clone());
+        let mix = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(&x, &y)| (1.0 - alpha) * x + alpha * y).collect::<Vec<f64>>();
+        let mut storage = StorageSnapshot { regrets: Vec::new(), strategy_sums: Vec::new(), last_regrets: Vec::new() };
+        for infoset in 0..mine.num_infosets() {
+            let mut probs = mix(&mine.average_strategy(infoset), &theirs.average_strategy(infoset));
+            let total: f64 = probs.iter().sum();
+            probs.iter_mut().for_each(|p| *p /= total);
+            storage.strategy_sums.push(probs);
+            storage.regrets.push(mix(&self.storage.regrets[infoset], &other.storage.regrets[infoset]));
+        }
+        let solution = Solution { storage, iterations: 0, ..self.clone() };
+
+        let blended = RegretStorage::from_snapshot(solution.storage.clone());
+        let metrics = compute_exploitability_with_evs(&solution.tree, &blended, &solution.terminal_ev_map(), 0, Duration::ZERO);
+        Ok(Blend {
+            solution,
+            exploitability: metrics.exploitability,
+            ip_br_value: metrics.ip_br_value,
+            oop_br_value: metrics.oop_br_value,
+        })
+    }
+
+    /// Rebuild a solver that continues from this state with the algorithm
+    /// that produced it and otherwise default options
+    pub fn into_solver(self) -> Result<CfrSolver, SolutionError> {
+        let config = SolverConfig { algorithm: self.algorithm, ..SolverConfig::new() };
+        self.resume(config)
+    }
+
+    /// Rebuild a solver that continues from this state under `config`,
+    /// counting on from `iterations` as `config.resume` says (see module
+    /// docs). `config` should come from `SolverConfig::builder().build()`.
+    pub fn resume(self, config: SolverConfig) -> Result<CfrSolver, SolutionError> {
+        self.validate_complete()?;
+        let evs = self.terminal_ev_map();
+        let mut solver = CfrSolver::with_config(self.tree, evs, config);
+        solver.storage = RegretStorage::from_snapshot(self.storage);
+        solver.iteration = self.iterations;
+        solver.apply_resume(self.algorithm);
+        Ok(solver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ResumeWeighting;
+    use crate::test_tree::{build_test_tree, terminal_ev_table};
+
+    fn solved(iterations: usize) -> CfrSolver {
+        let mut solver = CfrSolver::new(build_test_tree());
+        for _ in 0..iterations {
+            solver.run_iteration();
+        }
+        solver
+    }
+
+    #[test]
+    fn test_solution_round_trip_through_solver() {
+        let solver = solved(200);
+        let solution = Solution::from_solver(&solver);
+        assert!(solution.validate().is_ok());
+        assert!(solution.terminal_evs.windows(2).all(|w| w[0].0 < w[1].0));
+
+        let mut resumed = solution.clone().into_solver().unwrap();
+        assert_eq!(resumed.iteration, 200);
+        assert_eq!(resumed.storage.average_strategy(0), solver.storage.average_strategy(0));
+        resumed.run_iteration();
+        assert_eq!(resumed.iteration, 201);
+    }
+
+    #[test]
+    fn test_resume_matches_an_uninterrupted_solve() {
+        let run = |solver: &mut CfrSolver, iterations: usize| (0..iterations).for_each(|_| solver.run_iteration());
+        for algorithm in Algorithm::ALL {
+            let config = SolverConfig::builder().algorithm(algorithm).prune(0.0, 30).build().unwrap();
+            let mut full = CfrSolver::with_config(build_test_tree(), terminal_ev_table(), config.clone());
+            run(&mut full, 200);
+            let mut half = CfrSolver::with_config(build_test_tree(), terminal_ev_table(), config.clone());
+            run(&mut half, 100);
+
+            let mut resumed = Solution::from_solver(&half).resume(config).unwrap();
+            assert_eq!(resumed.iteration, 100);
+            run(&mut resumed, 100);
+            assert_eq!(resumed.storage.snapshot(), full.storage.snapshot(), "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_resume_reweights_or_restarts_the_count() {
+        let saved = Solution::from_solver(&solved(100));
+        assert_eq!(saved.algorithm, Algorithm::CfrPlus);
+        assert!(saved.storage.last_regrets.is_empty());
+
+        let config = |resume| SolverConfig::builder().algorithm(Algorithm::DiscountedCfr).resume(resume).build().unwrap();
+        let kept = saved.clone().resume(config(ResumeWeighting::Continue)).unwrap();
+        let reweighted = saved.clone().resume(config(ResumeWeighting::Reweight)).unwrap();
+        assert_eq!(reweighted.iteration, 100);
+        let factor = Algorithm::DiscountedCfr.total_weight(100) / Algorithm::CfrPlus.total_weight(100);
+        for infoset in 0..saved.tree.num_infosets() {
+            let scaled: Vec<f64> = kept.storage.strategy_sum(infoset).iter().map(|x| x * factor).collect();
+            assert_eq!(reweighted.storage.strategy_sum(infoset), &scaled[..]);
+            assert_eq!(reweighted.storage.regrets(infoset), kept.storage.regrets(infoset));
+            let (a, b) = (reweighted.storage.average_strategy(infoset), kept.storage.average_strategy(infoset));
+            assert!(a.iter().zip(&b).all(|(x, y)| (x - y).abs() < 1e-12));
+        }
+
+        let restarted = saved.clone().resume(config(ResumeWeighting::Restart)).unwrap();
+        assert_eq!(restarted.iteration, 0);
+        assert_eq!(restarted.storage.snapshot(), kept.storage.snapshot());
+    }
+
+    #[test]
+    fn test_infosets_label_every_storage_row() {
+        use crate::builder::{build_tree, StreetSizes, TreeConfig};
+        use crate::game::GameConfig;
+        use crate::node::parse_cards;
+
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.5]);
+        let tree = build_tree(&config);
+        let solver = CfrSolver::new_with_evs(tree.clone(), tree.nodes.iter().filter(|n| n.is_terminal()).map(|n| (n.id(), 0.0)).collect::<HashMap<_, _>>());
+        let solution = Solution::from_solver(&solver);
+
+        let labels: Vec<InfosetLabel> = solution.infosets().collect();
+        assert_eq!(labels.len(), tree.num_infosets());
+        assert!(labels.iter().enumerate().all(|(i, l)| l.infoset == i));
+        assert_eq!((labels[0].node, labels[0].player, labels[0].street, labels[0].line.as_str()), (0, Player::OOP, Street::Turn, "root"));
+        for label in &labels {
+            let node = tree.get(label.node).unwrap();
+            assert_eq!(node.infoset_id(), Some(label.infoset as _));
+            assert_eq!(node.board(), &label.board[..]);
+            assert_eq!(label.line, format_line(&tree, label.node));
+        }
+        let river = labels.iter().find(|l| l.street == Street::River).unwrap();
+        assert_eq!(river.board.len(), 5);
+        assert!(river.line.contains("|R:"), "{}", river.line);
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_result() {
+        let solution = Solution::from_solver(&solved(50));
+        // Same run, same fingerprint; ranges aren't part of the result
+        assert_eq!(Solution::from_solver(&solved(50)).fingerprint(), solution.fingerprint());
+        let ranges = [Range::parse("AA").unwrap(), Range::parse("KK").unwrap()];
+        assert_eq!(solution.clone().with_ranges(ranges).fingerprint(), solution.fingerprint());
+        assert_ne!(Solution::from_solver(&solved(51)).fingerprint(), solution.fingerprint());
+
+        let mut nudged = solution.clone();
+        nudged.storage.strategy_sums[0][0] += 1e-9;
+        assert_ne!(nudged.fingerprint(), solution.fingerprint());
+        let mut reconfigured = solution.clone();
+        reconfigured.config_fingerprint = Fingerprint(0);
+        assert_ne!(reconfigured.fingerprint(), solution.fingerprint());
+    }
+
+    #[test]
+    fn test_blend_mixes_strategies() {
+        let gto = Solution::from_solver(&solved(5_000));
+        let rough = Solution::from_solver(&solved(3));
+        let strategy = |s: &Solution, i: usize| RegretStorage::from_snapshot(s.storage.clone()).average_strategy(i);
+
+        for (alpha, expected) in [(0.0, &gto), (1.0, &rough)] {
+            let blend = gto.blend(&rough, alpha).unwrap();
+            for i in 0..4 {
+                let (b, e) = (strategy(&blend.solution, i), strategy(expected, i));
+                assert!(b.iter().zip(&e).all(|(x, y)| (x - y).abs() < 1e-12));
+            }
+        }
+        let half = gto.blend(&rough, 0.5).unwrap();
+        assert!(half.solution.validate().is_ok());
+        assert_eq!(half.solution.iterations, 0);
+        for i in 0..4 {
+            let (b, g, r) = (strategy(&half.solution, i), strategy(&gto, i), strategy(&rough, i));
+            assert!((b.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            assert!(b.iter().zip(g.iter().zip(&r)).all(|(x, (y, z))| (x - (y + z) / 2.0).abs() < 1e-12));
+        }
+        // The report measures the blend itself, which sits between its inputs here
+        let storage = RegretStorage::from_snapshot(half.solution.storage.clone());
+        let measured = compute_exploitability_with_evs(&half.solution.tree, &storage, &half.solution.terminal_ev_map(), 0, Duration::ZERO);
+        assert_eq!(half.exploitability, measured.exploitability);
+        assert_eq!(half.exploitability, half.ip_br_value + half.oop_br_value);
+        let (low, high) = (gto.blend(&rough, 0.0).unwrap().exploitability, gto.blend(&rough, 1.0).unwrap().exploitability);
+        assert!(low < half.exploitability && half.exploitability < high);
+
+        let mut other_tree = rough.clone();
+        if let Node::Decision { pot, .. } = &mut other_tree.tree.nodes[0] {
+            *pot += 1.0;
+        }
+        assert!(matches!(gto.blend(&other_tree, 0.5), Err(SolutionError::Mismatch(_))));
+        let mut other_evs = rough.clone();
+        other_evs.terminal_evs[0].1 += 1.0;
+        assert!(matches!(gto.blend(&other_evs, 0.5), Err(SolutionError::Mismatch(_))));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_solutions() {
+        let mut solution = Solution::from_solver(&solved(1));
+        solution.schema_version = 0;
+        assert!(matches!(solution.validate(), Err(SolutionError::SchemaVersion { found: 0, .. })));
+
+        let mut solution = Solution::from_solver(&solved(1));
+        solution.storage.regrets[0].push(0.0);
+        assert!(matches!(solution.validate(), Err(SolutionError::Mismatch(_))));
+    }
+
+    #[test]
+    fn test_partial_leaves_out_rarely_reached_infosets() {
+        let solution = Solution::from_solver(&solved(2_000));
+        assert_eq!(solution.partial(0.0), solution);
+        let reach = node_reach(&solution.tree, &RegretStorage::from_snapshot(solution.storage.clone()));
+
+        let partial = solution.partial(0.2);
+        assert!(partial.is_partial() && partial.validate().is_ok());
+        for node in &solution.tree.nodes {
+            let Some(id) = node.infoset_id() else { continue };
+            if reach[node.id() as usize] >= 0.2 {
+                assert!(!partial.is_omitted(id as usize));
+                assert_eq!(partial.storage.strategy_sums[id as usize], solution.storage.strategy_sums[id as usize]);
+            }
+        }
+        for &i in &partial.omitted {
+            assert!(partial.storage.strategy_sums[i].iter().all(|&x| x == 0.0));
+        }
+        assert_ne!(partial.fingerprint(), solution.fingerprint());
+        // Cutting again at a lower reach keeps what was already left out
+        assert_eq!(partial.partial(0.0).omitted, partial.omitted);
+
+        assert!(matches!(partial.clone().into_solver(), Err(SolutionError::Partial { .. })));
+        assert!(matches!(solution.blend(&partial, 0.5), Err(SolutionError::Partial { .. })));
+        let mut unsorted = partial.clone();
+        unsorted.omitted.reverse();
+        unsorted.omitted.push(solution.tree.num_infosets());
+        assert!(matches!(unsorted.validate(), Err(SolutionError::Mismatch(_))));
+    }
+
+    #[test]
+    fn test_act_samples_stored_strategy() {
+        use crate::builder::{build_tree, StreetSizes, TreeConfig};
+        use crate::game::GameConfig;
+        use crate::node::parse_cards;
+
+        let board = parse_cards("AsKh7d2c9s").unwrap();
+        let mut config = TreeConfig::new(GameConfig::new(), board.clone());
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 1;
+        let mut solution = Solution::from_solver(&CfrSolver::new_with_evs(build_tree(&config), HashMap::new()));
+        let hand: [Card; 2] = parse_cards("QcJc").unwrap().try_into().unwrap();
+        let state = GameState::new(board.clone());
+        let mut rng = Rng::new(3);
+
+        // No strategy sums yet: both root actions show up
+        let picks: Vec<Action> = (0..64).map(|_| solution.act(&state, hand, &mut rng).unwrap()).collect();
+        assert!(picks.contains(&Action::Check) && picks.iter().any(|a| matches!(a, Action::Bet { .. })));
+        // A pure check strategy is always followed
+        solution.storage.strategy_sums[0] = vec![5.0, 0.0];
+        assert!((0..64).all(|_| solution.act(&state, hand, &mut rng).unwrap() == Action::Check));
+
+        let blocked: [Card; 2] = [board[0], hand[0]];
+        assert_eq!(solution.act(&state, blocked, &mut rng), Err(ActError::CardConflict(board[0])));
+        let off = state.with_action(Action::Bet { size: 4.0 });
+        assert!(solution.act(&off, hand, &mut rng).is_ok());
+        assert!(matches!(solution.act_with(&off, hand, &mut rng, OffTree::Reject), Err(ActError::OffTree { .. })));
+
+        // The bet is never played, so a partial solution leaves the node facing it out
+        let partial = solution.partial(0.5);
+        assert!(matches!(partial.act(&off, hand, &mut rng), Err(ActError::Omitted(_))));
+        assert_eq!(partial.act(&GameState::new(board.clone()), hand, &mut rng), Ok(Action::Check));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json_round_trip() {
+        use crate::builder::{build_tree, TreeConfig};
+        use crate::game::GameConfig;
+        use crate::node::parse_cards;
+        use crate::range::Range;
+
+        let ranges = [Range::parse("AK,77").unwrap(), Range::parse("QQ+").unwrap()];
+        let solution = Solution::from_solver(&solved(100)).with_ranges(ranges);
+        let json = serde_json::to_string(&solution).unwrap();
+        let back: Solution = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, solution);
+        // Stable: serializing again gives identical output
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+
+        let config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c9s").unwrap());
+        let tree = build_tree(&config);
+        let back: GameTree = serde_json::from_str(&serde_json::to_string(&tree).unwrap()).unwrap();
+        assert_eq!(back, tree);
+        let back: TreeConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(back, config);
+
+        let range = Range::parse("AA,AKs:0.5").unwrap();
+        let back: Range = serde_json::from_str(&serde_json::to_string(&range).unwrap()).unwrap();
+        assert_eq!(back, range);
+    }
+}
@@ -0,0 +1,603 @@
+//! One-call solve API: tree building, terminal evaluation, CFR, and
+//! exploitability wired together
+//!
+//! ```no_run
+//! use oracle_engine::{parse_cards, solve, GameConfig, Range, SolveSpec};
+//!
+//! let board = parse_cards("AsKh7d2c9s").unwrap();
+//! let ranges = [Range::parse("AK,77,QQ").unwrap(), Range::parse("AQ,KQ,99").unwrap()];
+//! let spec = SolveSpec::new(board, ranges, GameConfig::new());
+//! let result = solve(&spec).unwrap();
+//! println!("root strategy {:?}, exploitability {}", result.strategy(0), result.metrics.exploitability);
+//! ```
+//!
+//! `solve` runs the scalar solver (one strategy per node for the whole
+//! range), so showdown terminals are valued with range-vs-range equity on
+//! that terminal's board: each player's range is treated as a single hand.
+//! `solve_vector` runs `VectorCfrSolver` on the same tree and returns
+//! per-combo strategies. Both add `GameConfig::bounty` to showdown payoffs;
+//! with one range-wide equity, `solve` counts IP's equity as its chance of
+//! winning the bounty and the rest as OOP's, ignoring ties.
+//!
+//! `SolveSpec::bunching` accounts for cards held by players who folded
+//! earlier: both solvers scale the starting ranges and deal turn and river
+//! cards by how likely each is to be live (weighted chance nodes for `solve`,
+//! `VectorCfrSolver::with_bunching` for `solve_vector`).
+//!
+//! `TreeConfig::isomorphic_runouts` is refused unless both ranges and any
+//! bunching are suit-symmetric on the board (`isomorphism::range_is_symmetric`),
+//! since grouped runouts are valued on one representative board each.
+//!
+//! `SolveSpec::max_memory` holds `solve` to a memory budget before the tree
+//! is built (see `memory`): a tree over it is built with isomorphic runouts
+//! when that fits and the ranges allow it, and refused otherwise.
+//! `SolveResult::memory` says which happened. The vector solver's per-combo
+//! arrays aren't estimated, so `solve_vector` rejects a budget.
+//!
+//! The root street follows the board: a 4- or 5-card board builds only the
+//! turn and river (or just the river). `SolveSpec::from_state` sets up such
+//! a re-solve from the pot and stacks the earlier streets left.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use crate::bunching::Bunching;
+use crate::builder::{build_tree, TreeConfig, TreeConfigError};
+use crate::cfr::CfrSolver;
+use crate::config::{Algorithm, SolverConfig, SolverConfigError, StopCondition, StopReason};
+use crate::equity::range_vs_range;
+use crate::evaluator::CactusKevEvaluator;
+use crate::exploitability::ConvergenceMetrics;
+use crate::game::{build_terminal_evs, BountyModel, GameConfig};
+use crate::isomorphism::range_is_symmetric;
+use crate::memory::{fit_memory, MemoryDecision, MemoryError};
+use crate::node::{Card, GameTree, Node, NodeId, Player};
+use crate::nodelock::{NodeLockError, NodeLocks};
+use crate::par::ThreadPool;
+use crate::range::Range;
+use crate::summation::Summation;
+use crate::vector_cfr::VectorCfrSolver;
+
+/// Everything `solve` needs
+#[derive(Debug, Clone)]
+pub struct SolveSpec {
+    /// Board at the root; overrides `tree_config.board`
+    pub board: Vec<Card>,
+    /// Ranges indexed `[IP, OOP]`
+    pub ranges: [Range; 2],
+    /// Game structure and bet sizes
+    pub tree_config: TreeConfig,
+    pub algo: Algorithm,
+    pub stop: StopCondition,
+    /// How traversals add up EV contributions (see `summation`)
+    pub summation: Summation,
+    /// Collect a `ProfileReport` (see `CfrSolver::profile_report`)
+    pub profile: bool,
+    /// Card removal by folded players; `None` deals every card uniformly
+    pub bunching: Option<Bunching>,
+    /// Rayon pool for all of the solve's parallel work (see
+    /// `CfrSolver::with_pool`); `None` uses the global pool
+    pub pool: Option<Arc<ThreadPool>>,
+    /// Node-lock file contents (see `nodelock`), resolved against the
+    /// built tree; `solve_vector` rejects them
+    pub locks: Option<String>,
+    /// Memory budget in bytes (see `memory::fit_memory`); `None` builds
+    /// the tree whatever its size
+    pub max_memory: Option<u64>,
+}
+
+impl SolveSpec {
+    /// Spec with the default tree (see `TreeConfig::new`), CFR+, and default stop condition
+    pub fn new(board: Vec<Card>, ranges: [Range; 2], game: GameConfig) -> Self {
+        SolveSpec {
+            tree_config: TreeConfig::new(game, board.clone()),
+            board,
+            ranges,
+            algo: Algorithm::CfrPlus,
+            stop: StopCondition::new(),
+            summation: Summation::Naive,
+            profile: false,
+            bunching: None,
+            pool: None,
+            locks: None,
+            max_memory: None,
+        }
+    }
+
+    /// Spec for a hand already on the turn (4-card board) or river (5-card
+    /// board), with `pot` in the middle and `stacks` (`[IP, OOP]`) behind
+    /// after the earlier action. `ranges` are what each player holds at that
+    /// point. Set `tree_config.initial_aggressor` to the last street's bettor
+    /// (IP by default) so donk bets are labelled correctly.
+    pub fn from_state(board: Vec<Card>, ranges: [Range; 2], pot: f64, stacks: [f64; 2]) -> Self {
+        SolveSpec::new(board, ranges, GameConfig::from_pot(pot, stacks))
+    }
+}
+
+/// Error returned by `solve`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// Tree config failed validation
+    Tree(TreeConfigError),
+    /// A player's range has no combos that are live on the board
+    EmptyRange(Player),
+    /// Algorithm or stop condition failed validation
+    Config(SolverConfigError),
+    /// The vector solver tracks every combo through every runout, so it
+    /// can't use a tree that groups isomorphic runouts
+    IsomorphicRunouts,
+    /// Isomorphic runouts are valued on one board per class, which is only
+    /// right when the ranges and bunching are suit-symmetric on the board
+    AsymmetricRanges,
+    /// The node-lock file doesn't fit the tree
+    Locks(NodeLockError),
+    /// Node locks are only played by the scalar solver
+    VectorLocks,
+    /// The tree doesn't fit `SolveSpec::max_memory`
+    Memory(MemoryError),
+    /// Memory budgets only cover the scalar solver
+    VectorMemory,
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::Tree(e) => write!(f, "{}", e),
+            SolveError::EmptyRange(p) => write!(f, "{:?} range has no live combos on this board", p),
+            SolveError::Config(e) => write!(f, "{}", e),
+            SolveError::IsomorphicRunouts => write!(f, "the vector solver needs every runout; disable isomorphic_runouts"),
+            SolveError::AsymmetricRanges => {
+                write!(f, "isomorphic runouts need ranges and bunching that are symmetric under the board's suit permutations")
+            }
+            SolveError::Locks(e) => write!(f, "node locks: {}", e),
+            SolveError::VectorLocks => write!(f, "node locks need the scalar solver"),
+            SolveError::Memory(e) => write!(f, "{}", e),
+            SolveError::VectorMemory => write!(f, "memory budgets need the scalar solver"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<SolverConfigError> for SolveError {
+    fn from(e: SolverConfigError) -> Self {
+        SolveError::Config(e)
+    }
+}
+
+impl From<TreeConfigError> for SolveError {
+    fn from(e: TreeConfigError) -> Self {
+        SolveError::Tree(e)
+    }
+}
+
+/// Output of `solve`
+pub struct SolveResult {
+    /// Solver state (tree, regrets, strategy sums)
+    pub solver: CfrSolver,
+    /// Terminal EV table (IP perspective) the solve used
+    pub terminal_evs: HashMap<NodeId, f64>,
+    /// Exploitability at the last check
+    pub metrics: ConvergenceMetrics,
+    pub stop_reason: StopReason,
+    /// How the tree fit `SolveSpec::max_memory`; `None` without a budget
+    pub memory: Option<MemoryDecision>,
+}
+
+impl SolveResult {
+    /// Average strategy at a decision node (action order matches the node's actions)
+    pub fn strategy(&self, node: NodeId) -> Vec<f64> {
+        let infoset = self.solver.tree.get(node).and_then(Node::infoset_id).expect("not a decision node");
+        self.solver.storage.average_strategy(infoset as usize)
+    }
+}
+
+/// Output of `solve_vector`
+pub struct VectorSolveResult {
+    /// Solver state (tree, per-combo regrets and strategy sums)
+    pub solver: VectorCfrSolver,
+    /// Exploitability at the last check
+    pub metrics: ConvergenceMetrics,
+    pub stop_reason: StopReason,
+}
+
+/// Build the tree, value its terminals, and run CFR until `spec.stop` is met.
+pub fn solve(spec: &SolveSpec) -> Result<SolveResult, SolveError> {
+    solve_with_progress(spec, |_| {})
+}
+
+/// `solve`, calling `on_check` with every exploitability check
+pub fn solve_with_progress(spec: &SolveSpec, on_check: impl FnMut(&ConvergenceMetrics)) -> Result<SolveResult, SolveError> {
+    let (mut tree, solver_config, memory) = prepare(spec)?;
+    let ranges = match &spec.bunching {
+        Some(bunching) => {
+            bunch_chance_nodes(&mut tree, bunching);
+            spec.ranges.clone().map(|r| bunching.apply(&r))
+        }
+        None => spec.ranges.clone(),
+    };
+    let start = Instant::now();
+    let terminal_evs = showdown_evs(&tree, &ranges, spec.tree_config.game.bounty.as_ref());
+    let evaluated = start.elapsed();
+    let mut solver = CfrSolver::with_config(tree, terminal_evs.clone(), solver_config);
+    if let Some(pool) = &spec.pool {
+        solver = solver.with_pool(Arc::clone(pool));
+    }
+    if let Some(text) = &spec.locks {
+        let locks = NodeLocks::parse(&solver.tree, text).map_err(SolveError::Locks)?;
+        solver = solver.with_locks(locks);
+    }
+    solver.record_terminal_eval(evaluated);
+    let (metrics, stop_reason) = solver.run(on_check);
+
+    Ok(SolveResult { solver, terminal_evs, metrics, stop_reason, memory })
+}
+
+/// Build the tree and run vector CFR until `spec.stop` is met.
+pub fn solve_vector(spec: &SolveSpec) -> Result<VectorSolveResult, SolveError> {
+    if spec.tree_config.isomorphic_runouts {
+        return Err(SolveError::IsomorphicRunouts);
+    }
+    if spec.locks.is_some() {
+        return Err(SolveError::VectorLocks);
+    }
+    if spec.max_memory.is_some() {
+        return Err(SolveError::VectorMemory);
+    }
+    let (tree, solver_config, _) = prepare(spec)?;
+    let mut solver = VectorCfrSolver::with_config(tree, &spec.ranges, solver_config);
+    if let Some(bounty) = &spec.tree_config.game.bounty {
+        solver = solver.with_bounty(bounty);
+    }
+    if let Some(bunching) = &spec.bunching {
+        solver = solver.with_bunching(bunching);
+    }
+    if let Some(pool) = &spec.pool {
+        solver = solver.with_pool(Arc::clone(pool));
+    }
+    let (metrics, stop_reason) = solver.run(|_| {});
+    Ok(VectorSolveResult { solver, metrics, stop_reason })
+}
+
+/// Validate the spec and build its tree and solver config, fitting the tree
+/// to any memory budget
+fn prepare(spec: &SolveSpec) -> Result<(GameTree, SolverConfig, Option<MemoryDecision>), SolveError> {
+    let solver_config = SolverConfig::builder()
+        .algorithm(spec.algo)
+        .stop(spec.stop)
+        .summation(spec.summation)
+        .deterministic(spec.stop.time_limit.is_none())
+        .profile(spec.profile)
+        .build()?;
+    let mut config = spec.tree_config.clone();
+    config.board = spec.board.clone();
+    config.validate()?;
+    for (i, player) in [Player::IP, Player::OOP].into_iter().enumerate() {
+        if spec.ranges[i].live_combos(&spec.board).next().is_none() {
+            return Err(SolveError::EmptyRange(player));
+        }
+    }
+    let symmetric = is_symmetric(spec);
+    if config.isomorphic_runouts && !symmetric {
+        return Err(SolveError::AsymmetricRanges);
+    }
+    let memory = match spec.max_memory {
+        Some(budget) => Some(fit_memory(&mut config, budget, symmetric).map_err(SolveError::Memory)?),
+        None => None,
+    };
+    Ok((build_tree(&config), solver_config, memory))
+}
+
+/// Whether both ranges and any bunching are unchanged by the board's suit
+/// symmetries, so grouped runouts value every runout right (see `isomorphism`)
+fn is_symmetric(spec: &SolveSpec) -> bool {
+    spec.ranges.iter().all(|r| range_is_symmetric(r, &spec.board)) && spec.bunching.as_ref().is_none_or(|b| b.is_symmetric(&spec.board))
+}
+
+/// Weight every chance child by how likely its card is to be live; runout
+/// class weights are scaled by their representative card
+fn bunch_chance_nodes(tree: &mut GameTree, bunching: &Bunching) {
+    let dealt: Vec<Option<Card>> = tree.nodes.iter().map(|n| n.board().last().copied()).collect();
+    for node in &mut tree.nodes {
+        let Node::Chance { children, weights, .. } = node else { continue };
+        let cards: Vec<Card> = children.iter().map(|&c| dealt[c as usize].expect("chance child has a board card")).collect();
+        let deal = bunching.deal_weights(&cards);
+        if weights.is_empty() {
+            *weights = deal;
+            continue;
+        }
+        let scaled: Vec<f64> = weights.iter().zip(&deal).map(|(w, d)| w * d).collect();
+        let total: f64 = scaled.iter().sum();
+        *weights = scaled.iter().map(|w| w / total).collect();
+    }
+}
+
+/// Terminal EVs with showdowns valued by range-vs-range equity, cached per
+/// board, plus any knockout bounty
+fn showdown_evs(tree: &GameTree, ranges: &[Range; 2], bounty: Option<&BountyModel>) -> HashMap<NodeId, f64> {
+    let evaluator = CactusKevEvaluator::new();
+    let mut cache: HashMap<u64, f64> = HashMap::new();
+    let mut equity = |node: &Node| {
+        let board = node.board();
+        let key = board.iter().fold(0u64, |m, c| m | c.mask());
+        *cache.entry(key).or_insert_with(|| range_vs_range(&evaluator, &ranges[0], &ranges[1], board).hero_equity)
+    };
+    let mut evs = build_terminal_evs(tree, &mut equity);
+    let (Some(bounty), Some(Node::Decision { stacks: root_stacks, .. })) = (bounty, tree.get(0)) else {
+        return evs;
+    };
+    for node in &tree.nodes {
+        if let Node::Terminal { id, folder: None, .. } = node {
+            let ip_equity = equity(node);
+            *evs.get_mut(id).expect("every terminal is valued") += bounty.ip_ev(node, *root_stacks, ip_equity, 1.0 - ip_equity);
+        }
+    }
+    evs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::StreetSizes;
+    use crate::config::Stagnation;
+    use crate::memory::MemoryPlan;
+    use crate::node::{parse_cards, Street};
+
+    fn river_spec(ip: &str, oop: &str) -> SolveSpec {
+        let board = parse_cards("AsKh7d2c9s").unwrap();
+        let ranges = [Range::parse(ip).unwrap(), Range::parse(oop).unwrap()];
+        let mut spec = SolveSpec::new(board, ranges, GameConfig::new());
+        spec.tree_config.river = StreetSizes::new(vec![0.75]);
+        spec.tree_config.max_raises = 2;
+        spec
+    }
+
+    #[test]
+    fn test_solve_converges_on_river() {
+        let mut spec = river_spec("AK,77", "AQ,KQ");
+        spec.stop = StopCondition { target_exploitability: Some(0.01), ..StopCondition::new() };
+        let result = solve(&spec).unwrap();
+        assert_eq!(result.stop_reason, StopReason::ExploitabilityTarget);
+        assert!(result.metrics.exploitability < 0.01);
+        let root = result.strategy(0);
+        assert!((root.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_spec_pool_runs_both_solvers() {
+        let mut spec = river_spec("AK,77", "AQ,KQ");
+        spec.stop = StopCondition::iterations(50);
+        let global = solve(&spec).unwrap();
+        let vector = solve_vector(&spec).unwrap();
+        spec.pool = Some(Arc::new(crate::par::build_pool(2)));
+        assert_eq!(solve(&spec).unwrap().strategy(0), global.strategy(0));
+        assert_eq!(solve_vector(&spec).unwrap().solver.range_strategy(0), vector.solver.range_strategy(0));
+    }
+
+    #[test]
+    fn test_spec_summation_reaches_the_solver() {
+        let mut spec = river_spec("AK,77", "AQ,KQ");
+        spec.stop = StopCondition::iterations(50);
+        let naive = solve(&spec).unwrap();
+        assert_eq!(naive.solver.config().summation, Summation::Naive);
+        spec.summation = Summation::Compensated;
+        let compensated = solve(&spec).unwrap();
+        assert_eq!(compensated.solver.config().summation, Summation::Compensated);
+        for (a, b) in compensated.strategy(0).iter().zip(naive.strategy(0)) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_drawing_dead_range_folds_to_a_bet() {
+        // IP's top two always beats OOP's queen-high
+        let mut spec = river_spec("AK", "QJ");
+        spec.stop = StopCondition::iterations(2_000);
+        let result = solve(&spec).unwrap();
+        assert_eq!(result.stop_reason, StopReason::IterationCap);
+        let tree = &result.solver.tree;
+        let ip_after_check = tree.get(0).unwrap().children()[0];
+        let oop_facing_bet = tree.get(ip_after_check).unwrap().children()[1];
+        match tree.get(oop_facing_bet).unwrap() {
+            Node::Decision { player: Player::OOP, actions, .. } => {
+                assert_eq!(actions[0], crate::node::Action::Fold);
+                assert!(result.strategy(oop_facing_bet)[0] > 0.95);
+            }
+            _ => panic!("expected OOP decision"),
+        }
+    }
+
+    #[test]
+    fn test_locks_hold_while_the_opponent_exploits_them() {
+        // OOP locked into calling every bet: IP bets its nuts for value
+        let mut spec = river_spec("AK", "QJ");
+        spec.stop = StopCondition::iterations(2_000);
+        spec.locks = Some("x b75 = 0 1 0   # fold, call, raise".to_string());
+        let result = solve(&spec).unwrap();
+        let tree = &result.solver.tree;
+        let ip_after_check = tree.get(0).unwrap().children()[0];
+        let oop_facing_bet = tree.get(ip_after_check).unwrap().children()[1];
+        assert_eq!(result.strategy(oop_facing_bet), vec![0.0, 1.0, 0.0]);
+        assert!(result.strategy(ip_after_check)[1] > 0.95);
+
+        spec.locks = Some("x b75 = 1".to_string());
+        assert!(matches!(solve(&spec).err(), Some(SolveError::Locks(e)) if e.line == 1));
+        assert_eq!(solve_vector(&spec).err().map(|e| e.to_string()), Some("node locks need the scalar solver".to_string()));
+    }
+
+    #[test]
+    fn test_solve_rejects_dead_range_and_bad_stop() {
+        // Both cards of IP's only combo are on the board
+        let mut spec = river_spec("AsKh", "QQ");
+        assert_eq!(solve(&spec).err(), Some(SolveError::EmptyRange(Player::IP)));
+
+        spec = river_spec("AK", "QQ");
+        spec.stop.check_every = 0;
+        assert!(matches!(solve(&spec).err(), Some(SolveError::Config(SolverConfigError::ZeroInterval(_)))));
+
+        spec = river_spec("AK", "QQ");
+        spec.board.truncate(2);
+        assert!(matches!(solve(&spec).err(), Some(SolveError::Tree(_))));
+    }
+
+    #[test]
+    fn test_isomorphic_runouts_need_symmetric_ranges() {
+        let ranges = [Range::parse("AhKh,QcJc").unwrap(), Range::parse("QhQd,JhJd").unwrap()];
+        let mut spec = SolveSpec::from_state(parse_cards("AsKs7d2s").unwrap(), ranges, 20.0, [40.0, 40.0]);
+        spec.tree_config.river = StreetSizes::new(vec![0.5]);
+        spec.tree_config.isomorphic_runouts = true;
+        spec.stop = StopCondition::iterations(10);
+        assert_eq!(solve(&spec).err(), Some(SolveError::AsymmetricRanges));
+
+        spec.ranges = [Range::parse("AK,QJs").unwrap(), Range::parse("QQ,JJ").unwrap()];
+        assert!(solve(&spec).is_ok());
+        spec.bunching = Some(Bunching::from_folded_ranges(&[Range::parse("5h5d").unwrap()], &spec.board));
+        assert_eq!(solve(&spec).err(), Some(SolveError::AsymmetricRanges));
+    }
+
+    #[test]
+    fn test_plateau_stops_as_stagnated() {
+        let mut spec = river_spec("AK,77", "AQ,KQ");
+        // Asking for 90% gains per window plateaus almost at once
+        let rule = Stagnation { checks: 2, min_improvement: 0.9 };
+        spec.stop = StopCondition { max_iterations: 5_000, target_exploitability: None, check_every: 10, stagnation: Some(rule), ..StopCondition::new() };
+        let result = solve(&spec).unwrap();
+        assert_eq!(result.stop_reason, StopReason::Stagnated);
+        assert!(result.metrics.iterations < 5_000);
+        assert_eq!(solve_vector(&spec).unwrap().stop_reason, StopReason::Stagnated);
+    }
+
+    #[test]
+    fn test_bounty_pays_at_all_in_showdowns() {
+        let mut spec = river_spec("AK,77,QJs", "AQ,KQ,99");
+        spec.tree_config.game = GameConfig::from_pot(10.0, [10.0, 10.0]);
+        spec.tree_config.river = StreetSizes::new(vec![1.0]);
+        spec.stop = StopCondition::iterations(200);
+        let plain = solve(&spec).unwrap();
+        let plain_value = solve_vector(&spec).unwrap().solver.ip_value();
+
+        // A 4bb bounty on OOP only: every payoff is at least as good for IP
+        spec.tree_config.game.bounty = Some(BountyModel::new([0.0, 8.0], 0.5));
+        let knockout = solve(&spec).unwrap();
+        assert!(solve_vector(&spec).unwrap().solver.ip_value() > plain_value);
+        for (id, ev) in &knockout.terminal_evs {
+            let Some(Node::Terminal { folder, stacks, .. }) = knockout.solver.tree.get(*id) else { panic!("terminal") };
+            if folder.is_none() && *stacks == [0.0, 0.0] {
+                assert!(*ev > plain.terminal_evs[id]);
+            } else {
+                assert_eq!(*ev, plain.terminal_evs[id]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_turn_state_builds_only_remaining_streets() {
+        let ranges = [Range::parse("AK,77,QJs").unwrap(), Range::parse("AQ,KQ,T9s").unwrap()];
+        let mut spec = SolveSpec::from_state(parse_cards("AsKh7d2c").unwrap(), ranges, 24.0, [60.0, 52.0]);
+        spec.tree_config.turn = StreetSizes::new(vec![0.5]);
+        spec.tree_config.river = StreetSizes::new(vec![0.75]);
+        spec.tree_config.max_raises = 1;
+        spec.stop = StopCondition::iterations(100);
+        let result = solve(&spec).unwrap();
+        let tree = &result.solver.tree;
+        let Some(Node::Decision { pot, stacks, street, .. }) = tree.get(0) else { panic!("root is a decision") };
+        assert_eq!((*pot, *stacks, *street), (24.0, [60.0, 52.0], Street::Turn));
+        assert!(tree.nodes.iter().all(|n| n.street() != Some(Street::Flop) && n.board().len() >= 4));
+        assert!(tree.nodes.iter().any(|n| n.street() == Some(Street::River)));
+        assert!(result.metrics.exploitability.is_finite());
+
+        // A river board is a single street with no chance nodes
+        let mut river = spec.clone();
+        river.board = parse_cards("AsKh7d2c9s").unwrap();
+        let tree = &solve(&river).unwrap().solver.tree;
+        assert!(tree.nodes.iter().all(|n| !n.is_chance() && n.street().is_none_or(|s| s == Street::River)));
+    }
+
+    #[test]
+    fn test_memory_budget_groups_runouts_or_refuses() {
+        let ranges = [Range::parse("AK,77").unwrap(), Range::parse("AQ,KQ").unwrap()];
+        let mut spec = SolveSpec::from_state(parse_cards("AsKs7d2s").unwrap(), ranges, 24.0, [60.0, 52.0]);
+        spec.tree_config.turn = StreetSizes::new(vec![0.5]);
+        spec.tree_config.river = StreetSizes::new(vec![0.75]);
+        spec.stop = StopCondition::iterations(20);
+        let full = crate::memory::estimate_memory(&spec.tree_config);
+        spec.max_memory = Some(full.total());
+        let as_configured = solve(&spec).unwrap();
+        assert_eq!(as_configured.solver.tree.len() as u64, full.nodes);
+        assert_eq!(as_configured.memory.map(|m| m.plan), Some(MemoryPlan::AsConfigured));
+        let as_configured = as_configured.solver.tree.len();
+
+        // Just under the full tree: river cards are grouped into weighted classes
+        spec.max_memory = Some(full.total() - 1);
+        let grouped = solve(&spec).unwrap();
+        assert_eq!(grouped.memory.map(|m| m.plan), Some(MemoryPlan::IsomorphicRunouts { full }));
+        assert!(grouped.solver.tree.len() < as_configured);
+        assert!(grouped.solver.tree.nodes.iter().any(|n| matches!(n, Node::Chance { weights, .. } if !weights.is_empty())));
+
+        spec.max_memory = Some(1 << 10);
+        assert!(matches!(solve(&spec).err(), Some(SolveError::Memory(_))));
+        assert_eq!(solve_vector(&spec).err(), Some(SolveError::VectorMemory));
+        spec.max_memory = None;
+        assert_eq!(solve(&spec).unwrap().memory, None);
+    }
+
+    #[test]
+    fn test_memory_budget_keeps_asymmetric_ranges_whole() {
+        // Grouping hearts with clubs would value AhKh's flush draws as QcJc's
+        let ranges = [Range::parse("AhKh,QcJc").unwrap(), Range::parse("QhQd,JhJd").unwrap()];
+        let mut spec = SolveSpec::new(parse_cards("AsKs7d").unwrap(), ranges, GameConfig::new());
+        for street in [&mut spec.tree_config.flop, &mut spec.tree_config.turn, &mut spec.tree_config.river] {
+            *street = StreetSizes::new(vec![0.5]);
+        }
+        spec.tree_config.max_raises = 1;
+        spec.stop = StopCondition::iterations(5);
+        spec.max_memory = Some(crate::memory::estimate_memory(&spec.tree_config).total() - 1);
+        let Some(SolveError::Memory(e)) = solve(&spec).err() else { panic!("expected a memory error") };
+        assert!(!e.isomorphic_runouts);
+    }
+
+    #[test]
+    fn test_bunching_reweights_turn_deals_and_ranges() {
+        let ranges = [Range::parse("AK,77,QJs").unwrap(), Range::parse("AQ,KQ,T9s").unwrap()];
+        let board = parse_cards("AsKh7d2c").unwrap();
+        let mut spec = SolveSpec::from_state(board.clone(), ranges, 20.0, [40.0, 40.0]);
+        spec.tree_config.turn = StreetSizes::new(vec![0.5]);
+        spec.tree_config.river = StreetSizes::new(vec![0.75]);
+        spec.tree_config.max_raises = 1;
+        spec.stop = StopCondition::iterations(50);
+        let plain = solve(&spec).unwrap();
+        let plain_value = solve_vector(&spec).unwrap().solver.ip_value();
+
+        // Folders held lots of queens: queen rivers come less often
+        spec.bunching = Some(Bunching::from_folded_ranges(&[Range::parse("QQ,AQ,KQ,QJ").unwrap()], &board));
+        let bunched = solve(&spec).unwrap();
+        let tree = &bunched.solver.tree;
+        let chance = tree.nodes.iter().find(|n| n.is_chance()).unwrap();
+        let probability = |rank: u8| {
+            let children = chance.children();
+            let i = children.iter().position(|&c| tree.get(c).unwrap().board().last().unwrap().rank() == rank).unwrap();
+            chance.chance_probability(i)
+        };
+        assert!(probability(10) < probability(3), "queen {} vs five {}", probability(10), probability(3));
+        assert!(((0..chance.children().len()).map(|i| chance.chance_probability(i)).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(plain.solver.tree.nodes.iter().all(|n| !matches!(n, Node::Chance { weights, .. } if !weights.is_empty())));
+        assert_ne!(bunched.terminal_evs, plain.terminal_evs);
+
+        let vector = solve_vector(&spec).unwrap();
+        assert!(vector.metrics.exploitability.is_finite());
+        assert_ne!(vector.solver.ip_value(), plain_value);
+    }
+
+    #[test]
+    fn test_solve_vector_converges_on_river() {
+        let mut spec = river_spec("AK,77,QJs", "AQ,KQ,99");
+        spec.stop = StopCondition { target_exploitability: Some(0.01), ..StopCondition::new() };
+        let result = solve_vector(&spec).unwrap();
+        assert_eq!(result.stop_reason, StopReason::ExploitabilityTarget);
+        let n = result.solver.hands(Player::OOP).len();
+        let root = result.solver.average_strategy(0);
+        assert_eq!(root.len(), 2 * n);
+        assert!((0..n).all(|h| (root[h] + root[n + h] - 1.0).abs() < 1e-9));
+        assert!(matches!(solve_vector(&river_spec("AsKh", "QQ")).err(), Some(SolveError::EmptyRange(Player::IP))));
+    }
+}
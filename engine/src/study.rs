@@ -0,0 +1,313 @@
+//! Multi-spot study reports: frequencies and EVs across many solutions
+//!
+//! A study solves many boards (`oracle batch`) and then asks how the
+//! strategy moves with the board. `SpotSummary::from_solution` reads the
+//! headline numbers off one solution, for each position:
+//!
+//! - `cbet`: how often the player bets when first to bet on the root street
+//!   (OOP at the root, IP once OOP checks), the c-bet when that player was
+//!   the preflop aggressor
+//! - `check_raise`: how often the player raises after checking and facing
+//!   a bet, weighted by how often the opponent picks each size (OOP only;
+//!   IP checking behind ends the street)
+//! - `ev`: what the player expects to collect from the root on (pot share
+//!   minus chips put in after the root), in bb, with both players on the
+//!   average strategy; the two positions' EVs add up to the starting pot
+//!
+//! `aggregate` groups the spots by `BoardTexture` and position and averages
+//! each number over the spots where it's defined; `to_csv` and `to_json`
+//! write the groups (and, for JSON, the spots) as one summary.
+
+use std::collections::BTreeMap;
+use crate::analysis::node_values;
+use crate::cfr::RegretStorage;
+use crate::grid::{csv_field, json_string};
+use crate::node::{format_cards, Action, Card, GameTree, Node, NodeId, Player, RANK_CHARS};
+use crate::solution::Solution;
+
+/// Suits on a board, by its most common suit: three or more cards of one
+/// suit (a flush draw or better) is monotone, two is two-tone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SuitPattern {
+    Monotone,
+    TwoTone,
+    Rainbow,
+}
+
+/// Board class a study groups spots by: high card (ten and below count as
+/// "low"), pairing, and suits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BoardTexture {
+    /// Highest rank, capped at 8 (ten): every low board groups together
+    pub high: u8,
+    pub paired: bool,
+    pub suits: SuitPattern,
+}
+
+impl BoardTexture {
+    pub fn of(board: &[Card]) -> Self {
+        let most_of_a_suit = (0..4).map(|s| board.iter().filter(|c| c.suit() == s).count()).max().unwrap_or(0);
+        let ranks = board.iter().fold(0u16, |m, c| m | 1 << c.rank()).count_ones() as usize;
+        BoardTexture {
+            high: board.iter().map(|c| c.rank()).max().unwrap_or(0).max(8),
+            paired: ranks < board.len(),
+            suits: match most_of_a_suit {
+                0 | 1 => SuitPattern::Rainbow,
+                2 => SuitPattern::TwoTone,
+                _ => SuitPattern::Monotone,
+            },
+        }
+    }
+
+    /// "A-high rainbow", "low paired two-tone"
+    pub fn label(&self) -> String {
+        let high = match self.high {
+            8 => "low".to_string(),
+            r => format!("{}-high", RANK_CHARS[r as usize]),
+        };
+        let suits = match self.suits {
+            SuitPattern::Monotone => "monotone",
+            SuitPattern::TwoTone => "two-tone",
+            SuitPattern::Rainbow => "rainbow",
+        };
+        format!("{}{} {}", high, if self.paired { " paired" } else { "" }, suits)
+    }
+}
+
+/// One position's headline numbers in one spot (see module docs)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionSummary {
+    pub cbet: Option<f64>,
+    pub check_raise: Option<f64>,
+    /// bb
+    pub ev: f64,
+}
+
+/// Headline numbers for one solution
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpotSummary {
+    pub name: String,
+    pub board: Vec<Card>,
+    pub texture: BoardTexture,
+    pub pot: f64,
+    /// Indexed `[IP, OOP]`
+    pub positions: [PositionSummary; 2],
+}
+
+impl SpotSummary {
+    pub fn from_solution(name: &str, solution: &Solution) -> Self {
+        let tree = &solution.tree;
+        let storage = RegretStorage::from_snapshot(solution.storage.clone());
+        let strategy = |id: NodeId| match tree.get(id) {
+            Some(Node::Decision { infoset_id, .. }) => storage.average_strategy(*infoset_id as usize),
+            _ => Vec::new(),
+        };
+        let root = tree.get(0).expect("empty tree");
+        let pot = root.pot();
+        let ip_ev = node_values(tree, &storage, &solution.terminal_ev_map())[0];
+
+        let summary = |player: Player, ev: f64| {
+            let first = first_decision(tree, player);
+            PositionSummary {
+                cbet: first.map(|id| bet_share(tree, id, &strategy(id), |a| matches!(a, Action::Bet { .. }))),
+                check_raise: first.and_then(|id| check_raise(tree, id, &strategy)),
+                ev,
+            }
+        };
+        SpotSummary {
+            name: name.to_string(),
+            board: root.board().to_vec(),
+            texture: BoardTexture::of(root.board()),
+            pot,
+            positions: [summary(Player::IP, ip_ev), summary(Player::OOP, pot - ip_ev)],
+        }
+    }
+}
+
+/// The player's first decision on the root street with no bet in: the root,
+/// or the node after the other player checks it
+fn first_decision(tree: &GameTree, player: Player) -> Option<NodeId> {
+    let mut id = 0;
+    loop {
+        let Some(Node::Decision { player: p, actions, children, .. }) = tree.get(id) else { return None };
+        if *p == player {
+            return Some(id);
+        }
+        let check = actions.iter().position(|&a| a == Action::Check)?;
+        id = children[check];
+    }
+}
+
+/// Share of `probs` on the actions at `id` that `pick` selects
+fn bet_share(tree: &GameTree, id: NodeId, probs: &[f64], pick: impl Fn(&Action) -> bool) -> f64 {
+    match tree.get(id) {
+        Some(Node::Decision { actions, .. }) => actions.iter().zip(probs).filter(|(a, _)| pick(a)).map(|(_, &p)| p).sum(),
+        _ => 0.0,
+    }
+}
+
+/// Raise frequency after checking at `id` and facing each of the
+/// opponent's bets, weighted by how often each bet is made
+fn check_raise(tree: &GameTree, id: NodeId, strategy: &impl Fn(NodeId) -> Vec<f64>) -> Option<f64> {
+    let Some(Node::Decision { player, street, actions, children, .. }) = tree.get(id) else { return None };
+    let check = actions.iter().position(|&a| a == Action::Check)?;
+    let after = children[check];
+    let Some(Node::Decision { player: opponent, street: s, actions: bets, children: facing, .. }) = tree.get(after) else { return None };
+    if opponent == player || s != street {
+        return None;
+    }
+    let bet_probs = strategy(after);
+    let (mut weight, mut raised) = (0.0, 0.0);
+    for ((action, &child), &p) in bets.iter().zip(facing).zip(&bet_probs) {
+        if matches!(action, Action::Bet { .. }) {
+            weight += p;
+            raised += p * bet_share(tree, child, &strategy(child), |a| matches!(a, Action::Raise { .. }));
+        }
+    }
+    (weight > 0.0).then(|| raised / weight)
+}
+
+/// Spots of one texture, one position (see `aggregate`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupSummary {
+    pub texture: BoardTexture,
+    pub player: Player,
+    pub spots: usize,
+    /// Mean over spots where it's defined
+    pub cbet: Option<f64>,
+    pub check_raise: Option<f64>,
+    /// Mean EV, bb
+    pub ev: f64,
+    /// Mean EV as a share of the starting pot
+    pub ev_pot_share: f64,
+}
+
+/// Average `spots` by texture and position, textures in `BoardTexture`
+/// order (low to high cards) and OOP before IP
+pub fn aggregate(spots: &[SpotSummary]) -> Vec<GroupSummary> {
+    let mut by_texture: BTreeMap<BoardTexture, Vec<&SpotSummary>> = BTreeMap::new();
+    for spot in spots {
+        by_texture.entry(spot.texture).or_default().push(spot);
+    }
+    let mean = |xs: Vec<f64>| (!xs.is_empty()).then(|| xs.iter().sum::<f64>() / xs.len() as f64);
+    let mut groups = Vec::new();
+    for (texture, group) in by_texture {
+        for (player, i) in [(Player::OOP, 1), (Player::IP, 0)] {
+            let n = group.len() as f64;
+            groups.push(GroupSummary {
+                texture,
+                player,
+                spots: group.len(),
+                cbet: mean(group.iter().filter_map(|s| s.positions[i].cbet).collect()),
+                check_raise: mean(group.iter().filter_map(|s| s.positions[i].check_raise).collect()),
+                ev: group.iter().map(|s| s.positions[i].ev).sum::<f64>() / n,
+                ev_pot_share: group.iter().map(|s| s.positions[i].ev / s.pot).sum::<f64>() / n,
+            });
+        }
+    }
+    groups
+}
+
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::IP => "IP",
+        Player::OOP => "OOP",
+    }
+}
+
+fn optional(x: Option<f64>, none: &str) -> String {
+    x.map_or(none.to_string(), |x| x.to_string())
+}
+
+/// One row per group; empty cells where a number isn't defined
+pub fn to_csv(groups: &[GroupSummary]) -> String {
+    let mut out = String::from("texture,position,spots,cbet,check_raise,ev,ev_pot_share\n");
+    for g in groups {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            csv_field(&g.texture.label()), player_name(g.player), g.spots, optional(g.cbet, ""), optional(g.check_raise, ""), g.ev, g.ev_pot_share,
+        ));
+    }
+    out
+}
+
+/// `{"groups":[...],"spots":[...]}`; `null` where a number isn't defined
+pub fn to_json(groups: &[GroupSummary], spots: &[SpotSummary]) -> String {
+    let mut out = String::from("{\"groups\":[");
+    for (i, g) in groups.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!(
+            "{{\"texture\":{},\"position\":\"{}\",\"spots\":{},\"cbet\":{},\"check_raise\":{},\"ev\":{},\"ev_pot_share\":{}}}",
+            json_string(&g.texture.label()), player_name(g.player), g.spots, optional(g.cbet, "null"), optional(g.check_raise, "null"), g.ev, g.ev_pot_share,
+        ));
+    }
+    out.push_str("],\"spots\":[");
+    for (i, s) in spots.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!("{{\"name\":{},\"board\":\"{}\",\"texture\":{},\"pot\":{}", json_string(&s.name), format_cards(&s.board), json_string(&s.texture.label()), s.pot));
+        for (player, p) in [(Player::OOP, &s.positions[1]), (Player::IP, &s.positions[0])] {
+            out.push_str(&format!(
+                ",\"{}\":{{\"cbet\":{},\"check_raise\":{},\"ev\":{}}}",
+                player_name(player), optional(p.cbet, "null"), optional(p.check_raise, "null"), p.ev,
+            ));
+        }
+        out.push('}');
+    }
+    out.push_str("]}");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::parse_cards;
+    use crate::range::Range;
+    use crate::solve::{solve, SolveSpec};
+    use crate::config::StopCondition;
+    use crate::game::GameConfig;
+
+    #[test]
+    fn test_texture_classes() {
+        let texture = |b: &str| BoardTexture::of(&parse_cards(b).unwrap()).label();
+        assert_eq!(texture("AsKh7d"), "A-high rainbow");
+        assert_eq!(texture("9s9h2s"), "low paired two-tone");
+        assert_eq!(texture("AsKh7d2c"), "A-high rainbow");
+        assert_eq!(texture("JdTd4d"), "J-high monotone");
+        assert_eq!(texture("Ts8h5c"), texture("9s6h2c"));
+    }
+
+    #[test]
+    fn test_spot_summaries_aggregate_by_texture() {
+        let spot = |board: &str| {
+            let ranges = [Range::parse("AA,KK,AK").unwrap(), Range::parse("QQ-99,AQ").unwrap()];
+            let mut spec = SolveSpec::new(parse_cards(board).unwrap(), ranges, GameConfig::from_pot(10.0, [50.0, 50.0]));
+            spec.stop = StopCondition::iterations(300);
+            let result = solve(&spec).unwrap();
+            SpotSummary::from_solution(board, &Solution::from_solver(&result.solver))
+        };
+        let spots = [spot("AsKh7d2c9s"), spot("Ks8d3c2h4d"), spot("9s9h2s5c3d")];
+        for s in &spots {
+            let [ip, oop] = s.positions;
+            assert!((ip.ev + oop.ev - s.pot).abs() < 1e-9);
+            assert!(oop.cbet.is_some_and(|f| (0.0..=1.0).contains(&f)));
+            assert!(ip.check_raise.is_none());
+        }
+
+        let groups = aggregate(&spots);
+        // A-high and K-high boards are separate groups; each has OOP then IP
+        assert_eq!(groups.len(), 6);
+        assert_eq!(groups.iter().map(|g| g.spots).sum::<usize>(), 6);
+        assert_eq!((groups[0].texture.label(), groups[0].player), ("low paired two-tone".to_string(), Player::OOP));
+
+        let csv = to_csv(&groups);
+        assert_eq!(csv.lines().count(), 7);
+        assert!(csv.lines().nth(2).unwrap().starts_with("low paired two-tone,IP,1,"));
+        let json = to_json(&groups, &spots);
+        assert!(json.starts_with("{\"groups\":[{\"texture\":\"low paired two-tone\",\"position\":\"OOP\""));
+        assert!(json.contains("\"board\":\"AsKh7d2c9s\""));
+    }
+}
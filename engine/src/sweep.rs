@@ -0,0 +1,233 @@
+//! Sweeps: the same spot re-solved across bet sizes or stack depths
+//!
+//! Simplified trees keep one bet size per street, and the question is which
+//! one. `bet_size_sweep` swaps the swept street's bet sizes for each size
+//! in the grid in turn (raises and the rest of the tree stay as specified),
+//! solves each variant with the vector solver, and records the game value
+//! and how often the solution bets at the root. EV as a function of size is
+//! usually flat near the top, so picking `SizeSweep::best_for` and its
+//! neighbours is the practical reading.
+//!
+//! `stack_sweep` keeps the tree and changes the postflop stacks instead
+//! (`GameConfig::with_postflop_stacks`, so they may differ per player). Its
+//! report compares every depth with the first: EV, root aggression, and
+//! the root strategy. Variants solve in parallel in both sweeps, inside
+//! `spec.pool` when one is set.
+
+use crate::par::prelude::*;
+use crate::node::{Action, Node, Player, Street};
+use crate::solve::{solve_vector, SolveError, SolveSpec};
+use crate::vector_cfr::VectorCfrSolver;
+
+/// One solved size
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizePoint {
+    /// Bet size as a pot fraction
+    pub size: f64,
+    /// IP's game value (bb per hand); OOP's is the negation
+    pub ip_ev: f64,
+    /// Exploitability (bb) at the end of the solve
+    pub exploitability: f64,
+    pub iterations: u64,
+    /// Share of the root player's range that bets or raises at the root
+    pub root_aggression: f64,
+}
+
+impl SizePoint {
+    /// `player`'s game value
+    pub fn ev(&self, player: Player) -> f64 {
+        if player == Player::IP { self.ip_ev } else { -self.ip_ev }
+    }
+}
+
+/// Result of `bet_size_sweep`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeSweep {
+    pub street: Street,
+    /// One point per size, in grid order
+    pub points: Vec<SizePoint>,
+}
+
+impl SizeSweep {
+    /// The size that is best for `player`
+    pub fn best_for(&self, player: Player) -> Option<&SizePoint> {
+        self.points.iter().max_by(|a, b| a.ev(player).total_cmp(&b.ev(player)))
+    }
+}
+
+/// Run `op` inside `spec.pool`, or on the current pool without one
+fn in_pool<R: Send>(spec: &SolveSpec, op: impl FnOnce() -> R + Send) -> R {
+    match &spec.pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// Solve `spec` once per size in `sizes` (pot fractions) with `street`'s
+/// bet sizes set to just that size
+pub fn bet_size_sweep(spec: &SolveSpec, street: Street, sizes: &[f64]) -> Result<SizeSweep, SolveError> {
+    let points = in_pool(spec, || sizes.par_iter().map(|&size| {
+        let mut variant = spec.clone();
+        variant.tree_config.sizes_mut(street).bet_sizes = vec![size];
+        let result = solve_vector(&variant)?;
+        let (actions, strategy) = root_strategy(&result.solver);
+        Ok(SizePoint {
+            size,
+            ip_ev: result.solver.ip_value(),
+            exploitability: result.metrics.exploitability,
+            iterations: result.metrics.iterations,
+            root_aggression: aggression(&actions, &strategy),
+        })
+    }).collect::<Result<Vec<_>, SolveError>>())?;
+    Ok(SizeSweep { street, points })
+}
+
+/// Root actions and range strategy (empty if the root isn't a decision)
+fn root_strategy(solver: &VectorCfrSolver) -> (Vec<Action>, Vec<f64>) {
+    match solver.tree.get(0) {
+        Some(Node::Decision { actions, .. }) => (actions.clone(), solver.range_strategy(0)),
+        _ => (Vec::new(), Vec::new()),
+    }
+}
+
+/// Total frequency of bets and raises
+fn aggression(actions: &[Action], strategy: &[f64]) -> f64 {
+    strategy.iter().zip(actions)
+        .filter(|(_, a)| matches!(a, Action::Bet { .. } | Action::Raise { .. }))
+        .map(|(f, _)| f)
+        .sum()
+}
+
+/// One solved stack depth
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackPoint {
+    /// Postflop stacks (bb), `[IP, OOP]`
+    pub stacks: [f64; 2],
+    /// Effective stack over the starting pot
+    pub spr: f64,
+    /// IP's game value (bb per hand); OOP's is the negation
+    pub ip_ev: f64,
+    pub exploitability: f64,
+    pub iterations: u64,
+    /// Root actions; the all-in and the raise caps depend on the stacks
+    pub root_actions: Vec<Action>,
+    /// Root player's range strategy over `root_actions`
+    pub root_strategy: Vec<f64>,
+    pub root_aggression: f64,
+}
+
+impl StackPoint {
+    /// `player`'s game value
+    pub fn ev(&self, player: Player) -> f64 {
+        if player == Player::IP { self.ip_ev } else { -self.ip_ev }
+    }
+}
+
+/// Result of `stack_sweep`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackSweep {
+    /// One point per stack depth, in input order; the first is the reference
+    pub points: Vec<StackPoint>,
+}
+
+impl StackSweep {
+    /// IP EV of point `i` minus the reference point's
+    pub fn ev_shift(&self, i: usize) -> f64 {
+        self.points[i].ip_ev - self.points[0].ip_ev
+    }
+
+    /// Root aggression of point `i` minus the reference point's
+    pub fn aggression_shift(&self, i: usize) -> f64 {
+        self.points[i].root_aggression - self.points[0].root_aggression
+    }
+}
+
+impl std::fmt::Display for StackSweep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<13} {:>6} {:>9} {:>9} {:>7} {:>6}  Root strategy", "Stacks", "SPR", "IP EV", "Shift", "Aggr", "Shift")?;
+        for (i, p) in self.points.iter().enumerate() {
+            let stacks = format!("{}/{}", p.stacks[0], p.stacks[1]);
+            let mix: Vec<String> = p.root_actions.iter().zip(&p.root_strategy).map(|(a, s)| format!("{} {:.1}%", a, s * 100.0)).collect();
+            write!(f, "\n{:<13} {:>6.2} {:>9.3} {:>+9.3} {:>6.1}% {:>+6.1}  {}",
+                stacks, p.spr, p.ip_ev, self.ev_shift(i), p.root_aggression * 100.0, self.aggression_shift(i) * 100.0, mix.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Solve `spec` once per entry of `stacks` (postflop stacks, `[IP, OOP]`)
+pub fn stack_sweep(spec: &SolveSpec, stacks: &[[f64; 2]]) -> Result<StackSweep, SolveError> {
+    let points = in_pool(spec, || stacks.par_iter().map(|&stacks| {
+        let mut variant = spec.clone();
+        variant.tree_config.game = variant.tree_config.game.clone().with_postflop_stacks(stacks);
+        let game = &variant.tree_config.game;
+        let spr = game.effective_stack() / game.initial_pot();
+        let result = solve_vector(&variant)?;
+        let (root_actions, root_strategy) = root_strategy(&result.solver);
+        Ok(StackPoint {
+            stacks,
+            spr,
+            ip_ev: result.solver.ip_value(),
+            exploitability: result.metrics.exploitability,
+            iterations: result.metrics.iterations,
+            root_aggression: aggression(&root_actions, &root_strategy),
+            root_actions,
+            root_strategy,
+        })
+    }).collect::<Result<Vec<_>, SolveError>>())?;
+    Ok(StackSweep { points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::StreetSizes;
+    use crate::config::StopCondition;
+    use crate::game::GameConfig;
+    use crate::node::parse_cards;
+    use crate::range::Range;
+
+    #[test]
+    fn test_sweep_solves_each_size() {
+        let board = parse_cards("AsKh7d2c9s").unwrap();
+        let ranges = [Range::parse("AK,77,QJs,65s").unwrap(), Range::parse("AQ,KQ,99").unwrap()];
+        let mut spec = SolveSpec::new(board, ranges, GameConfig::new());
+        spec.tree_config.river = StreetSizes::new(vec![0.75]);
+        spec.tree_config.max_raises = 1;
+        spec.stop = StopCondition::iterations(200);
+
+        let sweep = bet_size_sweep(&spec, Street::River, &[0.33, 0.75, 1.5]).unwrap();
+        assert_eq!(sweep.points.iter().map(|p| p.size).collect::<Vec<_>>(), vec![0.33, 0.75, 1.5]);
+        assert!(sweep.points.iter().all(|p| p.iterations == 200 && (0.0..=1.0 + 1e-9).contains(&p.root_aggression)));
+        // The 0.75 point is the spec as given
+        let direct = solve_vector(&spec).unwrap();
+        assert!((sweep.points[1].ip_ev - direct.solver.ip_value()).abs() < 1e-12);
+        let best = sweep.best_for(Player::OOP).unwrap();
+        assert!(sweep.points.iter().all(|p| p.ev(Player::OOP) <= best.ev(Player::OOP)));
+
+        spec.ranges[0] = Range::parse("AsKs").unwrap();
+        spec.board = parse_cards("AsKh7d2c9s").unwrap();
+        assert!(matches!(bet_size_sweep(&spec, Street::River, &[0.5]), Err(SolveError::EmptyRange(Player::IP))));
+    }
+
+    #[test]
+    fn test_stack_sweep_compares_depths() {
+        let board = parse_cards("AsKh7d2c9s").unwrap();
+        let ranges = [Range::parse("AK,77,QJs,65s").unwrap(), Range::parse("AQ,KQ,99").unwrap()];
+        let mut spec = SolveSpec::new(board, ranges, GameConfig::new());
+        spec.tree_config.river = StreetSizes { allow_all_in: true, ..StreetSizes::new(vec![0.75]) };
+        spec.tree_config.max_raises = 1;
+        spec.stop = StopCondition::iterations(100);
+
+        let sweep = stack_sweep(&spec, &[[97.5, 97.5], [3.0, 3.0], [40.0, 150.0]]).unwrap();
+        assert_eq!(sweep.points.len(), 3);
+        assert_eq!(sweep.ev_shift(0), 0.0);
+        // 3bb behind: the 0.75-pot bet (4.125bb) caps at all-in, so the root has one bet
+        assert!(sweep.points[1].root_actions.len() < sweep.points[0].root_actions.len());
+        assert!((sweep.points[1].spr - 3.0 / 5.5).abs() < 1e-12);
+        assert!((sweep.points[2].spr - 40.0 / 5.5).abs() < 1e-12);
+        assert!(sweep.points.iter().all(|p| (p.root_strategy.iter().sum::<f64>() - 1.0).abs() < 1e-9));
+        assert_eq!(sweep.to_string().lines().count(), 4);
+        assert!(sweep.to_string().lines().nth(3).unwrap().starts_with("40/150"));
+    }
+}
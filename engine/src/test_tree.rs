@@ -26,6 +26,7 @@ fn card(suit: u8, rank: u8) -> Card {
 
 /// Build the 9-node test tree.
 /// Nodes are pushed in ID order so that `tree.nodes[id] == node with id`.
+#[allow(clippy::vec_init_then_push)]
 pub fn build_test_tree() -> GameTree {
     let board = vec![
         card(0, 12), // As
@@ -81,14 +82,14 @@ pub fn build_test_tree() -> GameTree {
     // Node 3: Decision OOP — OOP chk / IP bet 5
     nodes.push(Node::Decision {
         id: 3,
-        infoset_id: 3,
+        infoset_id: 2,
         player: Player::OOP,
         street: Street::Flop,
         parent: Some(1),
         children: vec![4, 5],
         actions: vec![Action::Fold, Action::Call],
         pot: pot + 5.0,
-        stacks: [stacks[0], stacks[1] - 5.0], // IP bet 5
+        stacks: [stacks[0] - 5.0, stacks[1]], // IP bet 5
         board: board.clone(),
         bet_sequence: vec![Action::Check, Action::Bet { size: 5.0 }],
     });
@@ -99,7 +100,7 @@ pub fn build_test_tree() -> GameTree {
         parent: Some(3),
         folder: Some(Player::OOP),
         pot: pot + 5.0,
-        stacks: [stacks[0], stacks[1] - 5.0],
+        stacks: [stacks[0] - 5.0, stacks[1]],
         board: board.clone(),
         hole_cards: [None, None],
     });
@@ -118,7 +119,7 @@ pub fn build_test_tree() -> GameTree {
     // Node 6: Decision IP — OOP bet 5
     nodes.push(Node::Decision {
         id: 6,
-        infoset_id: 6,
+        infoset_id: 3,
         player: Player::IP,
         street: Street::Flop,
         parent: Some(0),
@@ -184,6 +185,7 @@ pub fn terminal_ev_table() -> HashMap<NodeId, f64> {
 ///   8:  Decision IP   [Fold → 9, Call → 10]
 ///   9:  Terminal      EV = -5.0
 ///   10: Terminal      EV = -1.0
+#[allow(clippy::vec_init_then_push)]
 pub fn build_test_tree_chance() -> GameTree {
     let board = vec![
         card(0, 12), // As
@@ -215,6 +217,7 @@ pub fn build_test_tree_chance() -> GameTree {
         id: 1,
         parent: Some(0),
         children: vec![2, 5],
+        weights: Vec::new(),
         street: Street::Flop,
         pot,
         stacks,
@@ -224,7 +227,7 @@ pub fn build_test_tree_chance() -> GameTree {
     // Node 2: Decision IP — after CardA
     nodes.push(Node::Decision {
         id: 2,
-        infoset_id: 2,
+        infoset_id: 1,
         player: Player::IP,
         street: Street::Flop,
         parent: Some(1),
@@ -261,7 +264,7 @@ pub fn build_test_tree_chance() -> GameTree {
     // Node 5: Decision IP — after CardB
     nodes.push(Node::Decision {
         id: 5,
-        infoset_id: 5,
+        infoset_id: 2,
         player: Player::IP,
         street: Street::Flop,
         parent: Some(1),
@@ -298,7 +301,7 @@ pub fn build_test_tree_chance() -> GameTree {
     // Node 8: Decision IP — OOP bet 5
     nodes.push(Node::Decision {
         id: 8,
-        infoset_id: 8,
+        infoset_id: 3,
         player: Player::IP,
         street: Street::Flop,
         parent: Some(0),
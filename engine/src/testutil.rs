@@ -0,0 +1,138 @@
+//! Evaluator correctness harness (behind the `testutil` feature)
+//!
+//! `ReferenceEvaluator` ranks a 7-card hand the slow, obviously-correct way:
+//! every 5-card subset through a plain 5-card ranker, keeping the best. It
+//! uses the same rank scale as `CactusKevEvaluator`, so any evaluator on that
+//! scale (NEON, AVX2, table variants) can be checked against it with
+//! `differential_test`, which compares both over N seeded random hands and
+//! reports the first disagreement. Evaluators on another scale (Omaha,
+//! short deck) bring their own reference to `differential_test_against`.
+//!
+//! ```toml
+//! [dev-dependencies]
+//! oracle-engine = { path = "../engine", features = ["testutil"] }
+//! ```
+
+use crate::evaluator::tables;
+use crate::node::{format_cards, Card, HandEvaluator, HandRank};
+use crate::rng::Rng;
+
+/// Brute-force 7-card evaluator (21 five-card subsets)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReferenceEvaluator;
+
+impl HandEvaluator for ReferenceEvaluator {
+    fn evaluate(&self, board: [Card; 5], hand: [Card; 2]) -> HandRank {
+        let all = [board[0], board[1], board[2], board[3], board[4], hand[0], hand[1]];
+        let mut best = u16::MAX;
+        for i in 0..7 {
+            for j in (i + 1)..7 {
+                for k in (j + 1)..7 {
+                    for l in (k + 1)..7 {
+                        for m in (l + 1)..7 {
+                            best = best.min(reference_rank_5([all[i], all[j], all[k], all[l], all[m]]).value());
+                        }
+                    }
+                }
+            }
+        }
+        HandRank::new(best)
+    }
+}
+
+/// Rank of exactly five cards: flush table for one-suit hands, prime-product
+/// classification otherwise
+pub fn reference_rank_5(cards: [Card; 5]) -> HandRank {
+    let mut suit_masks = [0u16; 4];
+    for card in cards.iter() {
+        suit_masks[(card.value() / 13) as usize] |= 1u16 << (card.value() % 13);
+    }
+    if let Some(mask) = suit_masks.iter().find(|m| m.count_ones() == 5) {
+        return HandRank::new(tables::get_flush_table()[*mask as usize]);
+    }
+    let product = cards.iter().map(|c| tables::RANK_PRIMES_REF[(c.value() % 13) as usize]).product();
+    HandRank::new(tables::lookup_nonflush_rank(product))
+}
+
+/// `n` seeded random (board, hand) pairs with all seven cards distinct
+pub fn random_hands(n: usize, seed: u64) -> Vec<([Card; 5], [Card; 2])> {
+    let mut rng = Rng::new(seed);
+    (0..n).map(|_| rng.board_and_hand()).collect()
+}
+
+/// First hand two evaluators disagree on
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// Position in the seeded hand sequence
+    pub index: usize,
+    pub board: [Card; 5],
+    pub hand: [Card; 2],
+    /// Reference rank
+    pub expected: HandRank,
+    /// Rank from the evaluator under test
+    pub actual: HandRank,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "hand {}: board {} hole {}: expected rank {}, got {}",
+            self.index,
+            format_cards(&self.board),
+            format_cards(&self.hand),
+            self.expected.value(),
+            self.actual.value(),
+        )
+    }
+}
+
+/// Compare `evaluator` with `ReferenceEvaluator` over `hands` seeded hands
+pub fn differential_test<E: HandEvaluator + ?Sized>(evaluator: &E, hands: usize, seed: u64) -> Result<(), Mismatch> {
+    differential_test_against(evaluator, &ReferenceEvaluator, hands, seed)
+}
+
+/// Compare `evaluator` with `reference` over `hands` seeded hands
+pub fn differential_test_against<E: HandEvaluator + ?Sized, R: HandEvaluator + ?Sized>(
+    evaluator: &E,
+    reference: &R,
+    hands: usize,
+    seed: u64,
+) -> Result<(), Mismatch> {
+    let mut rng = Rng::new(seed);
+    for index in 0..hands {
+        let (board, hand) = rng.board_and_hand();
+        let (expected, actual) = (reference.evaluate(board, hand), evaluator.evaluate(board, hand));
+        if expected != actual {
+            return Err(Mismatch { index, board, hand, expected, actual });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluator::CactusKevEvaluator;
+
+    /// Misranks every hand with an ace on the board
+    struct AceBlind;
+
+    impl HandEvaluator for AceBlind {
+        fn evaluate(&self, board: [Card; 5], hand: [Card; 2]) -> HandRank {
+            let rank = CactusKevEvaluator::new().evaluate(board, hand);
+            if board.iter().any(|c| c.rank() == 12) { HandRank::new(rank.value() + 1) } else { rank }
+        }
+    }
+
+    #[test]
+    fn test_harness_reports_first_mismatch() {
+        assert_eq!(differential_test(&CactusKevEvaluator::new(), 2_000, 1), Ok(()));
+        let mismatch = differential_test(&AceBlind, 2_000, 1).unwrap_err();
+        assert!(mismatch.board.iter().any(|c| c.rank() == 12));
+        assert_eq!(mismatch.actual.value(), mismatch.expected.value() + 1);
+        let (board, hand) = random_hands(mismatch.index + 1, 1)[mismatch.index];
+        assert_eq!((board, hand), (mismatch.board, mismatch.hand));
+        assert!(mismatch.to_string().starts_with(&format!("hand {}: board ", mismatch.index)));
+    }
+}
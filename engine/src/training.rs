@@ -0,0 +1,323 @@
+//! Training-data export: one record per decision node of a solution
+//!
+//! Each record pairs the public state at a decision (street, player to act,
+//! board, pot, stacks, betting history) with the solver's answer there: the
+//! average strategy over the node's actions and the EV of the node and of
+//! every action. EVs are from the acting player's side, in big blinds, with
+//! both players on the average strategy and chance cards dealt with their
+//! tree probabilities (see `analysis::node_values`).
+//!
+//! The file is little-endian, all floats `f32`:
+//!
+//! | field | type | meaning |
+//! |-------|------|---------|
+//! | magic | 4 bytes | `ORTD` |
+//! | version | u8 | `TRAINING_FORMAT_VERSION` |
+//! | reserved | 3 bytes | 0 |
+//! | count | u32 | number of records |
+//!
+//! then `count` records:
+//!
+//! | field | type | meaning |
+//! |-------|------|---------|
+//! | node | u32 | node ID in the solution's tree |
+//! | street | u8 | 0 flop, 1 turn, 2 river |
+//! | player | u8 | 0 IP, 1 OOP |
+//! | board | 5 × u8 | card values (`rank + 13 * suit`), 255 past the board |
+//! | pot, stack IP, stack OOP | 3 × f32 | chips, in big blinds |
+//! | ev | f32 | node EV for the acting player |
+//! | history | u8, then per action: u8 kind, f32 size | actions that led here |
+//! | actions | u8, then per action: u8 kind, f32 size, f32 frequency, f32 ev | the node's choices |
+//!
+//! Action kinds are 0 fold, 1 check, 2 call, 3 bet, 4 raise; sizes are the
+//! `Action` amounts (0 for fold, check, and call).
+
+use std::io::{Read, Write};
+use std::path::Path;
+use crate::analysis::node_values;
+use crate::cfr::RegretStorage;
+use crate::node::{Action, Card, Node, NodeId, Player, Street};
+use crate::solution::Solution;
+
+/// File magic
+pub const TRAINING_MAGIC: [u8; 4] = *b"ORTD";
+/// Current training-data format version
+pub const TRAINING_FORMAT_VERSION: u8 = 1;
+
+/// Board byte past the end of the board
+const NO_CARD: u8 = 255;
+
+/// One decision node's state and solved answer
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingRecord {
+    pub node: NodeId,
+    pub street: Street,
+    pub player: Player,
+    pub board: Vec<Card>,
+    pub pot: f32,
+    /// Chips behind, `[IP, OOP]`
+    pub stacks: [f32; 2],
+    /// Node EV for the acting player
+    pub ev: f32,
+    /// Actions that led to this node
+    pub history: Vec<Action>,
+    pub actions: Vec<Action>,
+    /// Average-strategy frequency per action
+    pub strategy: Vec<f32>,
+    /// EV of each action for the acting player
+    pub action_evs: Vec<f32>,
+}
+
+/// Error reading or writing training data
+#[derive(Debug)]
+pub enum TrainingDataError {
+    Io(std::io::Error),
+    /// Input does not start with `ORTD`
+    BadMagic,
+    /// Written by a newer (or unknown) format version
+    UnsupportedVersion(u8),
+    /// A field holds a value the format does not allow
+    Corrupt(String),
+}
+
+impl std::fmt::Display for TrainingDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrainingDataError::Io(e) => write!(f, "I/O error: {}", e),
+            TrainingDataError::BadMagic => write!(f, "not an oracle training-data file"),
+            TrainingDataError::UnsupportedVersion(v) => write!(f, "unsupported training-data version {}", v),
+            TrainingDataError::Corrupt(m) => write!(f, "corrupt training data: {}", m),
+        }
+    }
+}
+
+impl std::error::Error for TrainingDataError {}
+
+impl From<std::io::Error> for TrainingDataError {
+    fn from(e: std::io::Error) -> Self {
+        TrainingDataError::Io(e)
+    }
+}
+
+/// One record per decision node, in node order
+pub fn training_records(solution: &Solution) -> Vec<TrainingRecord> {
+    let storage = RegretStorage::from_snapshot(solution.storage.clone());
+    let values = node_values(&solution.tree, &storage, &solution.terminal_ev_map());
+    solution.tree.nodes.iter().filter_map(|node| {
+        let Node::Decision { id, infoset_id, player, street, children, actions, pot, stacks, board, bet_sequence, .. } = node else {
+            return None;
+        };
+        let sign = if *player == Player::IP { 1.0 } else { -1.0 };
+        Some(TrainingRecord {
+            node: *id,
+            street: *street,
+            player: *player,
+            board: board.clone(),
+            pot: *pot as f32,
+            stacks: stacks.map(|s| s as f32),
+            ev: (sign * values[*id as usize]) as f32,
+            history: bet_sequence.clone(),
+            actions: actions.clone(),
+            strategy: storage.average_strategy(*infoset_id as usize).iter().map(|&p| p as f32).collect(),
+            action_evs: children.iter().map(|&c| (sign * values[c as usize]) as f32).collect(),
+        })
+    }).collect()
+}
+
+/// Write `records` in the format above
+pub fn write_training_data(mut out: impl Write, records: &[TrainingRecord]) -> Result<(), TrainingDataError> {
+    let count = u32::try_from(records.len()).map_err(|_| TrainingDataError::Corrupt("more than u32::MAX records".into()))?;
+    out.write_all(&TRAINING_MAGIC)?;
+    out.write_all(&[TRAINING_FORMAT_VERSION, 0, 0, 0])?;
+    out.write_all(&count.to_le_bytes())?;
+    for record in records {
+        let mut buf = Vec::with_capacity(64);
+        buf.extend(record.node.to_le_bytes());
+        buf.push(match record.street {
+            Street::Flop => 0,
+            Street::Turn => 1,
+            Street::River => 2,
+        });
+        buf.push(if record.player == Player::IP { 0 } else { 1 });
+        if record.board.len() > 5 {
+            return Err(TrainingDataError::Corrupt(format!("node {} has a {}-card board", record.node, record.board.len())));
+        }
+        buf.extend((0..5).map(|i| record.board.get(i).map_or(NO_CARD, |c| c.value())));
+        for x in [record.pot, record.stacks[0], record.stacks[1], record.ev] {
+            buf.extend(x.to_le_bytes());
+        }
+        push_len(&mut buf, record.history.len(), record.node)?;
+        for action in &record.history {
+            push_action(&mut buf, action);
+        }
+        push_len(&mut buf, record.actions.len(), record.node)?;
+        for ((action, p), ev) in record.actions.iter().zip(&record.strategy).zip(&record.action_evs) {
+            push_action(&mut buf, action);
+            buf.extend(p.to_le_bytes());
+            buf.extend(ev.to_le_bytes());
+        }
+        out.write_all(&buf)?;
+    }
+    Ok(())
+}
+
+/// Read a file written by `write_training_data`
+pub fn read_training_data(mut input: impl Read) -> Result<Vec<TrainingRecord>, TrainingDataError> {
+    let mut header = [0u8; 12];
+    input.read_exact(&mut header)?;
+    if header[0..4] != TRAINING_MAGIC {
+        return Err(TrainingDataError::BadMagic);
+    }
+    if header[4] != TRAINING_FORMAT_VERSION {
+        return Err(TrainingDataError::UnsupportedVersion(header[4]));
+    }
+    let count = u32::from_le_bytes(header[8..12].try_into().unwrap());
+    let mut records = Vec::new();
+    for _ in 0..count {
+        let node = u32::from_le_bytes(read_array(&mut input)?);
+        let [street, player] = read_array(&mut input)?;
+        let street = match street {
+            0 => Street::Flop,
+            1 => Street::Turn,
+            2 => Street::River,
+            s => return Err(TrainingDataError::Corrupt(format!("street {}", s))),
+        };
+        let player = match player {
+            0 => Player::IP,
+            1 => Player::OOP,
+            p => return Err(TrainingDataError::Corrupt(format!("player {}", p))),
+        };
+        let board_bytes: [u8; 5] = read_array(&mut input)?;
+        let mut board = Vec::new();
+        for &b in board_bytes.iter().take_while(|&&b| b != NO_CARD) {
+            if b >= 52 {
+                return Err(TrainingDataError::Corrupt(format!("card value {}", b)));
+            }
+            board.push(Card::new(b));
+        }
+        let mut floats = [0.0f32; 4];
+        for x in floats.iter_mut() {
+            *x = read_f32(&mut input)?;
+        }
+        let [history_len] = read_array(&mut input)?;
+        let history = (0..history_len).map(|_| read_action(&mut input)).collect::<Result<Vec<_>, _>>()?;
+        let [action_len] = read_array(&mut input)?;
+        let (mut actions, mut strategy, mut action_evs) = (Vec::new(), Vec::new(), Vec::new());
+        for _ in 0..action_len {
+            actions.push(read_action(&mut input)?);
+            strategy.push(read_f32(&mut input)?);
+            action_evs.push(read_f32(&mut input)?);
+        }
+        records.push(TrainingRecord {
+            node,
+            street,
+            player,
+            board,
+            pot: floats[0],
+            stacks: [floats[1], floats[2]],
+            ev: floats[3],
+            history,
+            actions,
+            strategy,
+            action_evs,
+        });
+    }
+    Ok(records)
+}
+
+/// Write one record per decision node of `solution` to `path`; returns the
+/// number of records
+pub fn export_training_data(path: impl AsRef<Path>, solution: &Solution) -> Result<usize, TrainingDataError> {
+    let records = training_records(solution);
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    write_training_data(file, &records)?;
+    Ok(records.len())
+}
+
+fn push_len(buf: &mut Vec<u8>, len: usize, node: NodeId) -> Result<(), TrainingDataError> {
+    let len = u8::try_from(len).map_err(|_| TrainingDataError::Corrupt(format!("node {} has more than 255 actions", node)))?;
+    buf.push(len);
+    Ok(())
+}
+
+fn push_action(buf: &mut Vec<u8>, action: &Action) {
+    let (kind, size) = match *action {
+        Action::Fold => (0, 0.0),
+        Action::Check => (1, 0.0),
+        Action::Call => (2, 0.0),
+        Action::Bet { size } => (3, size),
+        Action::Raise { size } => (4, size),
+    };
+    buf.push(kind);
+    buf.extend((size as f32).to_le_bytes());
+}
+
+fn read_array<const N: usize>(input: &mut impl Read) -> Result<[u8; N], TrainingDataError> {
+    let mut bytes = [0u8; N];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_f32(input: &mut impl Read) -> Result<f32, TrainingDataError> {
+    Ok(f32::from_le_bytes(read_array(input)?))
+}
+
+fn read_action(input: &mut impl Read) -> Result<Action, TrainingDataError> {
+    let [kind] = read_array(input)?;
+    let size = read_f32(input)? as f64;
+    Ok(match kind {
+        0 => Action::Fold,
+        1 => Action::Check,
+        2 => Action::Call,
+        3 => Action::Bet { size },
+        4 => Action::Raise { size },
+        k => return Err(TrainingDataError::Corrupt(format!("action kind {}", k))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfr::CfrSolver;
+    use crate::test_tree::build_test_tree;
+
+    fn solution() -> Solution {
+        let mut solver = CfrSolver::new(build_test_tree());
+        for _ in 0..200 {
+            solver.run_iteration();
+        }
+        Solution::from_solver(&solver)
+    }
+
+    #[test]
+    fn test_records_cover_every_decision() {
+        let solution = solution();
+        let records = training_records(&solution);
+        assert_eq!(records.len(), solution.tree.nodes.iter().filter(|n| n.is_decision()).count());
+        for record in &records {
+            assert_eq!(record.actions.len(), record.strategy.len());
+            assert!((record.strategy.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+            // Node EV is the strategy-weighted action EV
+            let mixed: f32 = record.strategy.iter().zip(&record.action_evs).map(|(p, ev)| p * ev).sum();
+            assert!((mixed - record.ev).abs() < 1e-4, "node {}: {} vs {}", record.node, mixed, record.ev);
+        }
+        assert!(records[0].history.is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_and_bad_input() {
+        let records = training_records(&solution());
+        let mut bytes = Vec::new();
+        write_training_data(&mut bytes, &records).unwrap();
+        assert_eq!(&bytes[0..4], b"ORTD");
+        let back = read_training_data(bytes.as_slice()).unwrap();
+        assert_eq!(back, records);
+
+        assert!(matches!(read_training_data(&b"ORCL\x01\0\0\0\0\0\0\0"[..]), Err(TrainingDataError::BadMagic)));
+        bytes[4] = 9;
+        assert!(matches!(read_training_data(bytes.as_slice()), Err(TrainingDataError::UnsupportedVersion(9))));
+        bytes[4] = TRAINING_FORMAT_VERSION;
+        bytes.truncate(bytes.len() - 1);
+        assert!(matches!(read_training_data(bytes.as_slice()), Err(TrainingDataError::Io(_))));
+    }
+}
@@ -0,0 +1,144 @@
+//! Converting exploitability and EVs between display units
+//!
+//! Solvers and metrics work in big blinds. People compare spots in other
+//! units: chips for a real table, percent of the starting pot so a 5bb and a
+//! 50bb pot read alike, or bb/100 (per 100 hands) for a win rate. `Units`
+//! holds what a conversion needs for one spot — the starting pot and the
+//! chip value of a big blind — and converts both ways, so a threshold typed
+//! in % pot becomes the bb the stop condition wants and a result in bb
+//! prints in the unit it was asked in.
+
+use crate::game::GameConfig;
+
+/// Chips per big blind when none is given (matches `acpc::DEFAULT_BIG_BLIND_CHIPS`)
+pub const DEFAULT_CHIPS_PER_BB: f64 = 100.0;
+
+/// A unit for exploitability and EVs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Unit {
+    /// Big blinds per hand
+    #[default]
+    Bb,
+    /// Chips per hand, at `Units::chips_per_bb`
+    Chips,
+    /// Percent of the starting pot
+    PotPercent,
+    /// Big blinds per 100 hands
+    BbPer100,
+}
+
+impl Unit {
+    /// Label printed after a value
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Unit::Bb => "bb",
+            Unit::Chips => "chips",
+            Unit::PotPercent => "% pot",
+            Unit::BbPer100 => "bb/100",
+        }
+    }
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+/// Error returned for an unknown unit name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitParseError(pub String);
+
+impl std::fmt::Display for UnitParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown unit '{}' (expected bb, chips, pot, or bb100)", self.0)
+    }
+}
+
+impl std::error::Error for UnitParseError {}
+
+impl std::str::FromStr for Unit {
+    type Err = UnitParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bb" => Ok(Unit::Bb),
+            "chips" => Ok(Unit::Chips),
+            "pot" | "%pot" | "pct-pot" => Ok(Unit::PotPercent),
+            "bb100" | "bb/100" => Ok(Unit::BbPer100),
+            _ => Err(UnitParseError(s.to_string())),
+        }
+    }
+}
+
+/// Conversions for one spot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Units {
+    /// Starting pot (bb) that `Unit::PotPercent` is relative to
+    pub pot: f64,
+    pub chips_per_bb: f64,
+}
+
+impl Units {
+    /// Conversions for a spot starting with `pot` bb in the middle
+    pub fn new(pot: f64) -> Self {
+        Units { pot, chips_per_bb: DEFAULT_CHIPS_PER_BB }
+    }
+
+    /// Conversions for the pot `game` reaches the flop with
+    pub fn for_game(game: &GameConfig) -> Self {
+        Units::new(game.initial_pot())
+    }
+
+    pub fn with_chips_per_bb(self, chips_per_bb: f64) -> Self {
+        Units { chips_per_bb, ..self }
+    }
+
+    /// `bb` expressed in `unit`
+    pub fn from_bb(&self, bb: f64, unit: Unit) -> f64 {
+        match unit {
+            Unit::Bb => bb,
+            Unit::Chips => bb * self.chips_per_bb,
+            Unit::PotPercent => bb / self.pot * 100.0,
+            Unit::BbPer100 => bb * 100.0,
+        }
+    }
+
+    /// `value` in `unit` expressed in bb
+    pub fn to_bb(&self, value: f64, unit: Unit) -> f64 {
+        match unit {
+            Unit::Bb => value,
+            Unit::Chips => value / self.chips_per_bb,
+            Unit::PotPercent => value * self.pot / 100.0,
+            Unit::BbPer100 => value / 100.0,
+        }
+    }
+
+    /// `bb` in `unit` with its suffix, e.g. `0.182 % pot`
+    pub fn format(&self, bb: f64, unit: Unit) -> String {
+        format!("{:.6} {}", self.from_bb(bb, unit), unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions_round_trip() {
+        let units = Units::for_game(&GameConfig::new());
+        assert_eq!(units.pot, 5.5);
+        assert_eq!(units.from_bb(0.055, Unit::PotPercent), 1.0);
+        assert_eq!(units.from_bb(0.5, Unit::Chips), 50.0);
+        assert_eq!(units.with_chips_per_bb(2.0).from_bb(0.5, Unit::Chips), 1.0);
+        assert_eq!(units.from_bb(0.02, Unit::BbPer100), 2.0);
+        for unit in [Unit::Bb, Unit::Chips, Unit::PotPercent, Unit::BbPer100] {
+            assert!((units.to_bb(units.from_bb(0.37, unit), unit) - 0.37).abs() < 1e-12, "{}", unit);
+        }
+        assert_eq!(units.format(0.055, Unit::PotPercent), "1.000000 % pot");
+
+        assert_eq!("pot".parse(), Ok(Unit::PotPercent));
+        assert_eq!("BB100".parse(), Ok(Unit::BbPer100));
+        assert_eq!("mbb".parse::<Unit>(), Err(UnitParseError("mbb".to_string())));
+    }
+}
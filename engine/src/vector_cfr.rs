@@ -0,0 +1,1273 @@
+//! Vector (range-based) CFR+: every traversal carries per-combo reach vectors
+//!
+//! Instead of one scalar EV per node, each traversal for player `p` passes
+//! down `p`'s own reach and the opponent's reach for every hole-card combo
+//! and returns `p`'s counterfactual value for each of `p`'s combos. Every
+//! decision node holds one regret and strategy-sum row per action, each
+//! with one entry per combo of the acting player, so strategies are
+//! per-hand rather than per-node.
+//!
+//! Terminals are valued without enumerating hand pairs:
+//!
+//! - Folds: a combo's value is the payoff times the opponent reach of combos
+//!   that don't share a card with it (total, minus the two per-card sums,
+//!   plus the identical combo that was subtracted twice).
+//! - Showdowns: both players' combos are pre-ranked per river board
+//!   (`showdown::BoardRanks`); one sweep in strength order accumulates the
+//!   opponent reach that a combo beats, loses to, and ties, with the same
+//!   card-removal correction.
+//!
+//! Chance nodes zero the reach of combos that contain the dealt card and
+//! weight each card by `1 / (cards left - 4)`, the probability of a card
+//! given both players' hole cards. `with_bunching` reweights both the
+//! starting ranges and each dealt card by folded players' card removal. Updates alternate (IP then OOP each
+//! iteration), regrets are floored at zero (CFR+), and strategy sums are
+//! weighted by the iteration number and the acting player's own reach.
+//! Regret matching and both storage updates run through the `simd` kernels.
+//! With `SolverConfig::prune`, an action that no combo plays and whose best
+//! combo's last regret is below `-threshold` is skipped (see `PruneConfig`).
+//!
+//! Showdown terminals on a shorter board are depth-limit leaves (see
+//! `TreeConfig::depth_limit`) and take their values from the solver's
+//! `LeafEvaluator`, check-down equity unless one is supplied. A board's rank table settles every pairwise showdown on it, so
+//! it is built once and reused by every iteration. Flop trees reach over a
+//! thousand river boards; `SolverConfig::showdown_cache` caps how many
+//! tables stay in memory, ranking the rest again when they're next reached.
+//!
+//! `SolverConfig::sampled_br` adds cheap exploitability estimates between
+//! the exact checks: the same best response, dealing only a few cards per
+//! chance node (`sampled_exploitability`, reported by `run_with_estimates`).
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use crate::par::{build_pool, join, ThreadPool};
+use crate::par::prelude::*;
+use crate::config::{SolverConfig, StagnationTracker, StopReason};
+use crate::evaluator::CactusKevEvaluator;
+use crate::exploitability::ConvergenceMetrics;
+use crate::game::{terminal_ip_ev, BountyModel};
+use crate::bunching::Bunching;
+use crate::leaf::{EquityLeaf, Leaf, LeafEvaluator};
+use crate::node::{Card, GameTree, Node, NodeId, Player};
+use crate::combo::{combo_index, NUM_COMBOS};
+use crate::range::Range;
+use crate::rng::Rng;
+use crate::showdown::{showdown_weights, BoardRanks};
+use crate::simd;
+
+const IP: usize = 0;
+const OOP: usize = 1;
+
+/// Per-combo regrets and strategy sums in flat arrays.
+///
+/// An infoset with `A` actions for a player with `H` combos owns the range
+/// `offset .. offset + A * H`, laid out action-major (`[a * H + h]`).
+pub struct VectorStorage {
+    offsets: Vec<usize>,
+    regrets: Vec<f64>,
+    strategy_sums: Vec<f64>,
+    /// Per infoset, the start of its actions in `last_regrets`
+    action_offsets: Vec<usize>,
+    /// Largest per-combo counterfactual regret of each action's most recent
+    /// update, for pruning
+    last_regrets: Vec<f64>,
+}
+
+impl VectorStorage {
+    fn new(tree: &GameTree, hands: [usize; 2]) -> Self {
+        let mut offsets = vec![usize::MAX; tree.num_infosets()];
+        let mut action_offsets = vec![0; tree.num_infosets()];
+        let (mut len, mut num_actions) = (0, 0);
+        for node in &tree.nodes {
+            if let Node::Decision { infoset_id, player, actions, .. } = node {
+                if offsets[*infoset_id as usize] == usize::MAX {
+                    offsets[*infoset_id as usize] = len;
+                    action_offsets[*infoset_id as usize] = num_actions;
+                    len += actions.len() * hands[index(*player)];
+                    num_actions += actions.len();
+                }
+            }
+        }
+        VectorStorage {
+            offsets,
+            regrets: vec![0.0; len],
+            strategy_sums: vec![0.0; len],
+            action_offsets,
+            last_regrets: vec![0.0; num_actions],
+        }
+    }
+
+    /// Total regret slots (actions × combos summed over decision nodes)
+    pub fn len(&self) -> usize {
+        self.regrets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regrets.is_empty()
+    }
+
+    fn range(&self, infoset: usize, size: usize) -> std::ops::Range<usize> {
+        let start = self.offsets[infoset];
+        debug_assert!(start != usize::MAX, "infoset {} has no decision node", infoset);
+        start..start + size
+    }
+
+    /// Regret-matching+ strategy, action-major, uniform where no regret is positive
+    fn current_strategy(&self, infoset: usize, actions: usize, hands: usize) -> Vec<f64> {
+        normalize(&self.regrets[self.range(infoset, actions * hands)], actions, hands)
+    }
+
+    /// Average strategy, action-major, uniform where nothing has accumulated
+    fn average_strategy(&self, infoset: usize, actions: usize, hands: usize) -> Vec<f64> {
+        normalize(&self.strategy_sums[self.range(infoset, actions * hands)], actions, hands)
+    }
+
+    /// Average strategy of combo `hand` alone, uniform if nothing has accumulated
+    fn hand_average_strategy(&self, infoset: usize, actions: usize, hands: usize, hand: usize) -> Vec<f64> {
+        let sums = &self.strategy_sums[self.range(infoset, actions * hands)];
+        let positive: Vec<f64> = (0..actions).map(|a| sums[a * hands + hand].max(0.0)).collect();
+        let total: f64 = positive.iter().sum();
+        if total > 0.0 {
+            positive.iter().map(|p| p / total).collect()
+        } else {
+            vec![1.0 / actions as f64; actions]
+        }
+    }
+
+    fn last_regrets(&self, infoset: usize, actions: usize) -> &[f64] {
+        let start = self.action_offsets[infoset];
+        &self.last_regrets[start..start + actions]
+    }
+
+    fn apply(&mut self, update: &VectorUpdate) {
+        let hands = update.node_values.len();
+        let start = self.action_offsets[update.infoset];
+        for (a, q) in update.action_values.chunks(hands.max(1)).enumerate() {
+            self.last_regrets[start + a] = q.iter().zip(&update.node_values).map(|(q, v)| q - v).fold(f64::NEG_INFINITY, f64::max);
+        }
+        let range = self.range(update.infoset, update.action_values.len());
+        simd::update_regrets(&mut self.regrets[range.clone()], &update.action_values, &update.node_values);
+        simd::accumulate_strategy(&mut self.strategy_sums[range], &update.strategy, &update.reach, update.weight);
+    }
+}
+
+/// Per-combo normalization of positive values (regret matching / averaging)
+fn normalize(values: &[f64], actions: usize, hands: usize) -> Vec<f64> {
+    let mut out = vec![0.0; actions * hands];
+    simd::regret_match(values, actions, hands, &mut out);
+    out
+}
+
+/// One node's traversal results, applied to storage after the traversal
+struct VectorUpdate {
+    infoset: usize,
+    /// Counterfactual value per action and combo, action-major
+    action_values: Vec<f64>,
+    /// Counterfactual value per combo under the current strategy
+    node_values: Vec<f64>,
+    /// Current strategy, action-major
+    strategy: Vec<f64>,
+    /// Acting player's reach per combo
+    reach: Vec<f64>,
+    /// Iteration number (linear strategy averaging)
+    weight: f64,
+}
+
+/// How a terminal pays IP
+enum Terminal {
+    Fold { ip_ev: f64 },
+    Showdown { win: f64, lose: f64, tie: f64, table: usize },
+    /// Depth-limit leaf: showdown payoffs, valued by the leaf evaluator
+    Leaf { win: f64, lose: f64, tie: f64 },
+}
+
+/// Both players' combos ranked on one river board, indexed `[IP, OOP]`
+type RankTable = [BoardRanks; 2];
+
+/// Rank tables for every showdown board
+enum Showdowns {
+    /// Every board ranked at construction
+    All(Vec<RankTable>),
+    /// Boards ranked on first use, at most `limit` kept
+    Cached(ShowdownCache),
+}
+
+/// Bounded cache of rank tables; the oldest table is evicted first
+struct ShowdownCache {
+    boards: Vec<[Card; 5]>,
+    limit: usize,
+    state: RwLock<CacheState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+struct CacheState {
+    tables: Vec<Option<Arc<RankTable>>>,
+    /// Resident boards, oldest first
+    order: VecDeque<usize>,
+}
+
+impl ShowdownCache {
+    fn get(&self, table: usize, hands: &[Hands; 2]) -> Arc<RankTable> {
+        if let Some(cached) = &self.state.read().unwrap().tables[table] {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Arc::clone(cached);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let built = Arc::new(rank_table(&CactusKevEvaluator::new(), self.boards[table], hands));
+        let mut state = self.state.write().unwrap();
+        if let Some(raced) = &state.tables[table] {
+            return Arc::clone(raced);
+        }
+        while state.order.len() >= self.limit {
+            let oldest = state.order.pop_front().unwrap();
+            state.tables[oldest] = None;
+        }
+        state.tables[table] = Some(Arc::clone(&built));
+        state.order.push_back(table);
+        built
+    }
+}
+
+/// Showdown cache counters (see `VectorCfrSolver::showdown_cache_stats`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShowdownCacheStats {
+    /// Showdown visits served from a resident table
+    pub hits: u64,
+    /// Visits that had to rank the board (first visit or after eviction)
+    pub misses: u64,
+    /// Tables currently in memory
+    pub resident: usize,
+    /// Distinct showdown boards in the tree
+    pub boards: usize,
+}
+
+/// One player's combos
+struct Hands {
+    cards: Vec<[Card; 2]>,
+    masks: Vec<u64>,
+    /// Starting reach (range weight)
+    weights: Vec<f64>,
+    /// Index of the identical combo in the opponent's list
+    same: Vec<Option<usize>>,
+}
+
+/// Range-based CFR+ solver (see module docs)
+pub struct VectorCfrSolver {
+    pub tree: GameTree,
+    pub iteration: u64,
+    storage: VectorStorage,
+    hands: [Hands; 2],
+    terminals: Vec<Option<Terminal>>,
+    showdowns: Showdowns,
+    /// Sum of weight products over non-conflicting combo pairs
+    pair_weight: f64,
+    config: SolverConfig,
+    /// See `CfrSolver::with_pool`
+    pool: Option<Arc<ThreadPool>>,
+    leaf: Box<dyn LeafEvaluator>,
+    /// Card removal by folded players, if any (see `with_bunching`)
+    bunching: Option<Bunching>,
+}
+
+impl VectorCfrSolver {
+    /// Solver for `tree` with ranges indexed `[IP, OOP]` and the default config
+    pub fn new(tree: GameTree, ranges: &[Range; 2]) -> Self {
+        Self::with_config(tree, ranges, SolverConfig::new())
+    }
+
+    /// Solver with an explicit (validated) configuration.
+    ///
+    /// Panics if the tree groups isomorphic runouts (weighted Chance nodes):
+    /// combos would have to be permuted along with the suits.
+    pub fn with_config(tree: GameTree, ranges: &[Range; 2], config: SolverConfig) -> Self {
+        assert!(
+            tree.nodes.iter().all(|n| !matches!(n, Node::Chance { weights, .. } if !weights.is_empty())),
+            "vector CFR needs every runout dealt"
+        );
+        let root_board = tree.get(0).map(|n| n.board().to_vec()).unwrap_or_default();
+        let live: [Vec<([Card; 2], f64)>; 2] = [
+            ranges[IP].live_combos(&root_board).collect(),
+            ranges[OOP].live_combos(&root_board).collect(),
+        ];
+        let mut lookup = [vec![None; NUM_COMBOS], vec![None; NUM_COMBOS]];
+        for (p, combos) in live.iter().enumerate() {
+            for (i, (cards, _)) in combos.iter().enumerate() {
+                lookup[p][combo_index(cards[0], cards[1])] = Some(i);
+            }
+        }
+        let hands = [IP, OOP].map(|p| {
+            let combos = &live[p];
+            Hands {
+                cards: combos.iter().map(|(c, _)| *c).collect(),
+                masks: combos.iter().map(|(c, _)| c[0].mask() | c[1].mask()).collect(),
+                weights: combos.iter().map(|(_, w)| *w).collect(),
+                same: combos.iter().map(|(c, _)| lookup[1 - p][combo_index(c[0], c[1])]).collect(),
+            }
+        });
+
+        let pair_weight = pair_weight(&hands);
+        let (terminals, boards) = value_terminals(&tree);
+        let showdowns = match config.showdown_cache {
+            None => {
+                let evaluator = CactusKevEvaluator::new();
+                Showdowns::All(boards.iter().map(|&board| rank_table(&evaluator, board, &hands)).collect())
+            }
+            Some(limit) => Showdowns::Cached(ShowdownCache {
+                state: RwLock::new(CacheState { tables: vec![None; boards.len()], order: VecDeque::new() }),
+                boards,
+                limit,
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            }),
+        };
+        let storage = VectorStorage::new(&tree, [hands[IP].cards.len(), hands[OOP].cards.len()]);
+        let pool = config.threads.map(|n| Arc::new(build_pool(n)));
+        VectorCfrSolver { tree, iteration: 0, storage, hands, terminals, showdowns, pair_weight, config, pool, leaf: Box::new(EquityLeaf::new()), bunching: None }
+    }
+
+    /// Run all parallel work inside `pool` (see `CfrSolver::with_pool`)
+    pub fn with_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Account for cards held by folded players: each combo's starting
+    /// weight is scaled by the chance its cards are live, and chance nodes
+    /// deal cards in proportion to the same (see `bunching`)
+    pub fn with_bunching(mut self, bunching: &Bunching) -> Self {
+        for hands in &mut self.hands {
+            for (w, cards) in hands.weights.iter_mut().zip(&hands.cards) {
+                *w *= bunching.live(cards[0]) * bunching.live(cards[1]);
+            }
+        }
+        self.pair_weight = pair_weight(&self.hands);
+        self.bunching = Some(bunching.clone());
+        self
+    }
+
+    /// Value depth-limit leaves with `leaf` instead of check-down equity
+    pub fn with_leaf_evaluator(mut self, leaf: impl LeafEvaluator + 'static) -> Self {
+        self.leaf = Box::new(leaf);
+        self
+    }
+
+    /// Add knockout bounties to every showdown and leaf payoff
+    /// (`BountyModel::ip_ev`: a win pays the covered loser's bounty, a tie
+    /// pays nothing)
+    pub fn with_bounty(mut self, bounty: &BountyModel) -> Self {
+        let root_stacks = match self.tree.get(0) {
+            Some(Node::Decision { stacks, .. }) | Some(Node::Chance { stacks, .. }) | Some(Node::Terminal { stacks, .. }) => *stacks,
+            None => return self,
+        };
+        for node in &self.tree.nodes {
+            let id = node.id() as usize;
+            if let Some(Terminal::Showdown { win, lose, .. } | Terminal::Leaf { win, lose, .. }) = &mut self.terminals[id] {
+                *win += bounty.ip_ev(node, root_stacks, 1.0, 0.0);
+                *lose += bounty.ip_ev(node, root_stacks, 0.0, 1.0);
+            }
+        }
+        self
+    }
+
+    /// Hits and misses of the bounded showdown cache; `None` when every
+    /// board was ranked up front
+    pub fn showdown_cache_stats(&self) -> Option<ShowdownCacheStats> {
+        let Showdowns::Cached(cache) = &self.showdowns else { return None };
+        Some(ShowdownCacheStats {
+            hits: cache.hits.load(Ordering::Relaxed),
+            misses: cache.misses.load(Ordering::Relaxed),
+            resident: cache.state.read().unwrap().order.len(),
+            boards: cache.boards.len(),
+        })
+    }
+
+    pub fn config(&self) -> &SolverConfig {
+        &self.config
+    }
+
+    pub fn storage(&self) -> &VectorStorage {
+        &self.storage
+    }
+
+    /// A player's combos (live on the root board), in storage order
+    pub fn hands(&self, player: Player) -> &[[Card; 2]] {
+        &self.hands[index(player)].cards
+    }
+
+    /// Average strategy at a decision node, action-major: entry `a * H + h`
+    /// is the probability that combo `h` of the acting player takes action `a`
+    pub fn average_strategy(&self, node: NodeId) -> Vec<f64> {
+        let (player, actions, infoset) = self.decision(node);
+        self.storage.average_strategy(infoset, actions, self.hands[player].cards.len())
+    }
+
+    /// Average strategy of one combo at a decision node, or `None` if the
+    /// acting player's range doesn't hold it
+    pub fn hand_strategy(&self, node: NodeId, hand: [Card; 2]) -> Option<Vec<f64>> {
+        let (player, actions, infoset) = self.decision(node);
+        let h = self.hand_position(player, hand)?;
+        Some(self.storage.hand_average_strategy(infoset, actions, self.hands[player].cards.len(), h))
+    }
+
+    /// EV (bb) of each action at decision `node` for one combo of the acting
+    /// player, with the average strategy played from there on and the
+    /// opponent's range narrowed by the line to the node. `None` if the
+    /// player's range doesn't hold the combo or no opponent combo gets there
+    /// alongside it.
+    pub fn hand_action_values(&self, node: NodeId, hand: [Card; 2]) -> Option<Vec<f64>> {
+        let (q, _, _) = self.decision(node);
+        let h = self.hand_position(q, hand)?;
+        let reach = self.reach_at(node);
+        let mask = self.hands[q].masks[h];
+        let compatible: f64 = reach[1 - q].iter().zip(&self.hands[1 - q].masks)
+            .filter(|(_, &m)| m & mask == 0)
+            .map(|(r, _)| r)
+            .sum();
+        if compatible <= 0.0 {
+            return None;
+        }
+        let mut scratch = vec![None; self.tree.len()];
+        let children = self.tree.get(node).map(|n| n.children().to_vec()).unwrap_or_default();
+        Some(children.iter().map(|&child| self.expected(child, q, &reach[1 - q], &reach[q], &mut scratch)[h] / compatible).collect())
+    }
+
+    /// Action frequencies at decision `node` over the acting player's range
+    /// as it arrives there (reach-weighted mean of the combo strategies);
+    /// uniform if no combo gets there
+    pub fn range_strategy(&self, node: NodeId) -> Vec<f64> {
+        let (q, actions, infoset) = self.decision(node);
+        let reach = &self.reach_at(node)[q];
+        let n = reach.len();
+        let strategy = self.storage.average_strategy(infoset, actions, n);
+        let total: f64 = reach.iter().sum();
+        if total <= 0.0 {
+            return vec![1.0 / actions as f64; actions];
+        }
+        (0..actions).map(|a| reach.iter().zip(&strategy[a * n..(a + 1) * n]).map(|(r, s)| r * s).sum::<f64>() / total).collect()
+    }
+
+    /// Each player's range `[IP, OOP]` on arriving at `node` under the
+    /// average strategy: every combo's starting weight times its own action
+    /// frequencies on the line there, without combos dealt cards block
+    pub fn continuing_ranges(&self, node: NodeId) -> [Range; 2] {
+        let reach = self.reach_at(node);
+        [IP, OOP].map(|p| {
+            let mut range = Range::new();
+            for (&hand, &r) in self.hands[p].cards.iter().zip(&reach[p]) {
+                range.set_weight(hand, r);
+            }
+            range
+        })
+    }
+
+    /// Index of `hand` in `player`'s combo list
+    fn hand_position(&self, player: usize, hand: [Card; 2]) -> Option<usize> {
+        let key = combo_index(hand[0], hand[1]);
+        self.hands[player].cards.iter().position(|c| combo_index(c[0], c[1]) == key)
+    }
+
+    /// Both players' reach `[IP, OOP]` on arriving at `node` under the average
+    /// strategy: range weights narrowed by each action and dealt card on the way
+    fn reach_at(&self, node: NodeId) -> [Vec<f64>; 2] {
+        let mut path = vec![node];
+        while let Some(parent) = self.tree.get(path[path.len() - 1]).and_then(|n| n.parent()) {
+            path.push(parent);
+        }
+        path.reverse();
+        let mut reach = [self.hands[IP].weights.clone(), self.hands[OOP].weights.clone()];
+        for step in path.windows(2) {
+            match self.tree.get(step[0]).expect("invalid node id") {
+                Node::Chance { .. } => {
+                    let card = self.dealt_card(step[1]);
+                    reach = [IP, OOP].map(|p| self.block(p, &reach[p], card));
+                }
+                Node::Decision { player, children, infoset_id, .. } => {
+                    let q = index(*player);
+                    let n = self.hands[q].cards.len();
+                    let a = children.iter().position(|&c| c == step[1]).expect("child of its parent");
+                    let strategy = self.storage.average_strategy(*infoset_id as usize, children.len(), n);
+                    for (r, s) in reach[q].iter_mut().zip(&strategy[a * n..(a + 1) * n]) {
+                        *r *= s;
+                    }
+                }
+                Node::Terminal { .. } => unreachable!("terminals have no children"),
+            }
+        }
+        reach
+    }
+
+    /// Average strategy as `frequencies[action][combo_index]` over all 1326
+    /// combos (zero for combos outside the range), e.g. for
+    /// `RangeGrid::from_action_frequencies`
+    pub fn combo_frequencies(&self, node: NodeId) -> Vec<Vec<f64>> {
+        let (player, actions, _) = self.decision(node);
+        let hands = &self.hands[player];
+        let strategy = self.average_strategy(node);
+        (0..actions).map(|a| {
+            let mut row = vec![0.0; NUM_COMBOS];
+            for (h, cards) in hands.cards.iter().enumerate() {
+                row[combo_index(cards[0], cards[1])] = strategy[a * hands.cards.len() + h];
+            }
+            row
+        }).collect()
+    }
+
+    /// Acting player index, action count, and infoset of a decision node
+    fn decision(&self, node: NodeId) -> (usize, usize, usize) {
+        match self.tree.get(node) {
+            Some(Node::Decision { player, actions, infoset_id, .. }) => (index(*player), actions.len(), *infoset_id as usize),
+            _ => panic!("node {} is not a decision node", node),
+        }
+    }
+
+    /// Run one iteration: an IP traversal, then an OOP traversal
+    pub fn run_iteration(&mut self) {
+        self.iteration += 1;
+        for p in [IP, OOP] {
+            let traverse = || {
+                let mut updates = Vec::new();
+                self.cfr(0, p, &self.hands[p].weights, &self.hands[1 - p].weights, &mut updates);
+                updates
+            };
+            let updates = match &self.pool {
+                Some(pool) => pool.install(traverse),
+                None => traverse(),
+            };
+            for u in &updates {
+                self.storage.apply(u);
+            }
+        }
+    }
+
+    /// Best-response values and exploitability of the average strategy
+    /// (bb per hand dealt, averaged over non-conflicting combo pairs)
+    pub fn exploitability(&self, elapsed: std::time::Duration) -> ConvergenceMetrics {
+        self.br_metrics(None, elapsed)
+    }
+
+    /// `exploitability` estimated from `cards` cards per chance node, chosen
+    /// reproducibly from `seed` (see `SampledBr`)
+    pub fn sampled_exploitability(&self, cards: usize, seed: u64, elapsed: std::time::Duration) -> ConvergenceMetrics {
+        self.br_metrics(Some(ChanceSample { cards, seed }), elapsed)
+    }
+
+    fn br_metrics(&self, sample: Option<ChanceSample>, elapsed: std::time::Duration) -> ConvergenceMetrics {
+        let value = |p: usize| {
+            let cfv = self.best_response(0, p, &self.hands[1 - p].weights, sample);
+            let total: f64 = cfv.iter().zip(&self.hands[p].weights).map(|(v, w)| v * w).sum();
+            total / self.pair_weight
+        };
+        let compute = || join(|| value(IP), || value(OOP));
+        let (ip_br_value, oop_br_value) = match &self.pool {
+            Some(pool) => pool.install(compute),
+            None => compute(),
+        };
+        ConvergenceMetrics {
+            exploitability: ip_br_value + oop_br_value,
+            ip_br_value,
+            oop_br_value,
+            iterations: self.iteration,
+            elapsed_time: elapsed,
+        }
+    }
+
+    /// Game value for IP (bb per hand) when both players play the average strategy
+    pub fn ip_value(&self) -> f64 {
+        self.node_ip_values()[0].unwrap_or(0.0)
+    }
+
+    /// IP's EV (bb per hand) at every node under the average strategy,
+    /// conditional on reaching it: the reach-weighted mean over the
+    /// non-conflicting combo pairs that get there. `None` where no pair does.
+    pub fn node_ip_values(&self) -> Vec<Option<f64>> {
+        let mut values = vec![None; self.tree.len()];
+        self.expected(0, IP, &self.hands[OOP].weights, &self.hands[IP].weights, &mut values);
+        values
+    }
+
+    /// Iterate until the configured stop condition is met (same schedule as
+    /// `CfrSolver::run`)
+    pub fn run(&mut self, on_check: impl FnMut(&ConvergenceMetrics)) -> (ConvergenceMetrics, StopReason) {
+        self.run_with_estimates(on_check, |_| {})
+    }
+
+    /// `run`, also passing each `SolverConfig::sampled_br` estimate taken
+    /// between exact checks to `on_estimate`
+    pub fn run_with_estimates(
+        &mut self,
+        mut on_check: impl FnMut(&ConvergenceMetrics),
+        mut on_estimate: impl FnMut(&ConvergenceMetrics),
+    ) -> (ConvergenceMetrics, StopReason) {
+        let stop = self.config.stop;
+        let sampled = self.config.sampled_br;
+        let start = Instant::now();
+        let mut last = None;
+        let mut plateau = StagnationTracker::new(stop.stagnation);
+        for iter in 1..=stop.max_iterations {
+            self.run_iteration();
+            let elapsed = start.elapsed();
+            let timed_out = stop.time_limit.is_some_and(|limit| elapsed >= limit);
+            if iter.is_multiple_of(stop.check_every) || timed_out || iter == stop.max_iterations {
+                let m = self.exploitability(elapsed);
+                on_check(&m);
+                if stop.target_exploitability.is_some_and(|t| m.exploitability < t) {
+                    return (m, StopReason::ExploitabilityTarget);
+                }
+                if timed_out {
+                    return (m, StopReason::TimeLimit);
+                }
+                if plateau.observe(m.exploitability) {
+                    return (m, StopReason::Stagnated);
+                }
+                last = Some(m);
+            } else if let Some(s) = sampled.filter(|s| iter.is_multiple_of(s.every)) {
+                on_estimate(&self.sampled_exploitability(s.cards, self.config.seed ^ iter, elapsed));
+            }
+        }
+        let m = last.unwrap_or_else(|| self.exploitability(start.elapsed()));
+        (m, StopReason::IterationCap)
+    }
+
+    /// CFR+ traversal for player `p`: returns `p`'s counterfactual value per combo
+    fn cfr(&self, node_id: NodeId, p: usize, own: &[f64], opp: &[f64], updates: &mut Vec<VectorUpdate>) -> Vec<f64> {
+        match self.tree.get(node_id).expect("invalid node id") {
+            Node::Terminal { .. } => self.terminal_values(node_id, p, opp),
+            Node::Chance { children, .. } => {
+                let results: Vec<(Vec<f64>, Vec<VectorUpdate>)> = children.par_iter().map(|&child| {
+                    let card = self.dealt_card(child);
+                    let child_own = self.block(p, own, card);
+                    let child_opp = self.block(1 - p, opp, card);
+                    let mut child_updates = Vec::new();
+                    let cfv = self.cfr(child, p, &child_own, &child_opp, &mut child_updates);
+                    (cfv, child_updates)
+                }).collect();
+                let scales = self.deal_scales(children);
+                let mut values = vec![0.0; own.len()];
+                for ((cfv, child_updates), scale) in results.into_iter().zip(scales) {
+                    add_scaled(&mut values, &cfv, scale);
+                    updates.extend(child_updates);
+                }
+                values
+            }
+            Node::Decision { player, children, infoset_id, .. } => {
+                let q = index(*player);
+                let actions = children.len();
+                let n = self.hands[q].cards.len();
+                let strategy = self.storage.current_strategy(*infoset_id as usize, actions, n);
+                if q == p {
+                    let prune = self.config.prune_threshold(self.iteration);
+                    let last = self.storage.last_regrets(*infoset_id as usize, actions);
+                    let mut child_values = Vec::with_capacity(actions);
+                    let mut values = vec![0.0; n];
+                    for (a, &child) in children.iter().enumerate() {
+                        let sigma = &strategy[a * n..(a + 1) * n];
+                        if prune.is_some_and(|threshold| last[a] < -threshold && sigma.iter().all(|&s| s == 0.0)) {
+                            child_values.push(None);
+                            continue;
+                        }
+                        let child_own: Vec<f64> = own.iter().zip(sigma).map(|(r, s)| r * s).collect();
+                        let cfv = self.cfr(child, p, &child_own, opp, updates);
+                        for h in 0..n {
+                            values[h] += sigma[h] * cfv[h];
+                        }
+                        child_values.push(Some(cfv));
+                    }
+                    // A pruned action replays its last regret, which keeps its regrets at zero
+                    let mut action_values = Vec::with_capacity(actions * n);
+                    for (a, cfv) in child_values.into_iter().enumerate() {
+                        match cfv {
+                            Some(cfv) => action_values.extend(cfv),
+                            None => action_values.extend(values.iter().map(|v| v + last[a])),
+                        }
+                    }
+                    updates.push(VectorUpdate {
+                        infoset: *infoset_id as usize,
+                        action_values,
+                        node_values: values.clone(),
+                        strategy,
+                        reach: own.to_vec(),
+                        weight: self.iteration as f64,
+                    });
+                    values
+                } else {
+                    let mut values = vec![0.0; own.len()];
+                    for (a, &child) in children.iter().enumerate() {
+                        let sigma = &strategy[a * n..(a + 1) * n];
+                        let child_opp: Vec<f64> = opp.iter().zip(sigma).map(|(r, s)| r * s).collect();
+                        let cfv = self.cfr(child, p, own, &child_opp, updates);
+                        add_scaled(&mut values, &cfv, 1.0);
+                    }
+                    values
+                }
+            }
+        }
+    }
+
+    /// Best response of `p` to the opponent's average strategy, dealing every
+    /// card at chance nodes or only a `sample` of them
+    fn best_response(&self, node_id: NodeId, p: usize, opp: &[f64], sample: Option<ChanceSample>) -> Vec<f64> {
+        let n_own = self.hands[p].cards.len();
+        match self.tree.get(node_id).expect("invalid node id") {
+            Node::Terminal { .. } => self.terminal_values(node_id, p, opp),
+            Node::Chance { children, .. } => {
+                let sampled;
+                let dealt: &[NodeId] = match sample {
+                    Some(s) => {
+                        sampled = s.children(node_id, children);
+                        &sampled
+                    }
+                    None => children,
+                };
+                let results: Vec<Vec<f64>> = dealt.par_iter().map(|&child| {
+                    let card = self.dealt_card(child);
+                    let mut cfv = self.best_response(child, p, &self.block(1 - p, opp, card), sample);
+                    self.zero_blocked(p, &mut cfv, card);
+                    cfv
+                }).collect();
+                // Each sampled card stands in for `children / dealt` cards
+                let scales = self.deal_scales(children);
+                let stand_in = children.len() as f64 / dealt.len() as f64;
+                let mut values = vec![0.0; n_own];
+                for (cfv, child) in results.iter().zip(dealt) {
+                    let i = children.iter().position(|c| c == child).expect("dealt from children");
+                    add_scaled(&mut values, cfv, scales[i] * stand_in);
+                }
+                values
+            }
+            Node::Decision { player, children, infoset_id, .. } => {
+                let q = index(*player);
+                if q == p {
+                    let mut values = vec![f64::NEG_INFINITY; n_own];
+                    for &child in children {
+                        let cfv = self.best_response(child, p, opp, sample);
+                        for (v, c) in values.iter_mut().zip(&cfv) {
+                            *v = v.max(*c);
+                        }
+                    }
+                    values
+                } else {
+                    let n = self.hands[q].cards.len();
+                    let strategy = self.storage.average_strategy(*infoset_id as usize, children.len(), n);
+                    let mut values = vec![0.0; n_own];
+                    for (a, &child) in children.iter().enumerate() {
+                        let sigma = &strategy[a * n..(a + 1) * n];
+                        let child_opp: Vec<f64> = opp.iter().zip(sigma).map(|(r, s)| r * s).collect();
+                        add_scaled(&mut values, &self.best_response(child, p, &child_opp, sample), 1.0);
+                    }
+                    values
+                }
+            }
+        }
+    }
+
+    /// `p`'s value per combo when both players follow the average strategy
+    /// Counterfactual values of `p`'s combos under the average strategy; each
+    /// node's reach-normalized value for `p` goes into `record`
+    fn expected(&self, node_id: NodeId, p: usize, opp: &[f64], own: &[f64], record: &mut [Option<f64>]) -> Vec<f64> {
+        let cfv = match self.tree.get(node_id).expect("invalid node id") {
+            Node::Terminal { .. } => self.terminal_values(node_id, p, opp),
+            Node::Chance { children, .. } => {
+                let scales = self.deal_scales(children);
+                let mut values = vec![0.0; own.len()];
+                for (&child, scale) in children.iter().zip(scales) {
+                    let card = self.dealt_card(child);
+                    let mut cfv = self.expected(child, p, &self.block(1 - p, opp, card), &self.block(p, own, card), record);
+                    self.zero_blocked(p, &mut cfv, card);
+                    add_scaled(&mut values, &cfv, scale);
+                }
+                values
+            }
+            Node::Decision { player, children, infoset_id, .. } => {
+                let q = index(*player);
+                let n = self.hands[q].cards.len();
+                let strategy = self.storage.average_strategy(*infoset_id as usize, children.len(), n);
+                let mut values = vec![0.0; own.len()];
+                for (a, &child) in children.iter().enumerate() {
+                    let sigma = &strategy[a * n..(a + 1) * n];
+                    if q == p {
+                        let child_own: Vec<f64> = own.iter().zip(sigma).map(|(r, s)| r * s).collect();
+                        let cfv = self.expected(child, p, opp, &child_own, record);
+                        for h in 0..n {
+                            values[h] += sigma[h] * cfv[h];
+                        }
+                    } else {
+                        let child_opp: Vec<f64> = opp.iter().zip(sigma).map(|(r, s)| r * s).collect();
+                        add_scaled(&mut values, &self.expected(child, p, &child_opp, own, record), 1.0);
+                    }
+                }
+                values
+            }
+        };
+        let pairs = self.pair_reach(p, own, opp);
+        if pairs > 0.0 {
+            record[node_id as usize] = Some(cfv.iter().zip(own).map(|(v, r)| v * r).sum::<f64>() / pairs);
+        }
+        cfv
+    }
+
+    /// Reach product summed over non-conflicting (`p`, opponent) combo pairs
+    fn pair_reach(&self, p: usize, own: &[f64], opp: &[f64]) -> f64 {
+        let other = &self.hands[1 - p];
+        let mut total = 0.0;
+        let mut per_card = [0.0f64; 52];
+        for (o, &r) in opp.iter().enumerate() {
+            total += r;
+            per_card[other.cards[o][0].value() as usize] += r;
+            per_card[other.cards[o][1].value() as usize] += r;
+        }
+        let hands = &self.hands[p];
+        own.iter().enumerate().filter(|(_, &r)| r != 0.0).map(|(h, &r)| {
+            let [a, b] = hands.cards[h];
+            let compatible = total - per_card[a.value() as usize] - per_card[b.value() as usize] + hands.same[h].map_or(0.0, |o| opp[o]);
+            r * compatible
+        }).sum()
+    }
+
+    /// Reach weight of each chance child: `1 / (cards left - 4)`, or with
+    /// bunching the card's deal probability rescaled to the same total
+    fn deal_scales(&self, children: &[NodeId]) -> Vec<f64> {
+        let uniform = chance_weight(children.len());
+        match &self.bunching {
+            None => vec![uniform; children.len()],
+            Some(bunching) => {
+                let cards: Vec<Card> = children.iter().map(|&c| self.dealt_card(c)).collect();
+                bunching.deal_weights(&cards).iter().map(|w| w * children.len() as f64 * uniform).collect()
+            }
+        }
+    }
+
+    fn dealt_card(&self, child: NodeId) -> Card {
+        *self.tree.get(child).and_then(|n| n.board().last()).expect("chance child has a board card")
+    }
+
+    /// `reach` with player `p`'s combos containing `card` zeroed
+    fn block(&self, p: usize, reach: &[f64], card: Card) -> Vec<f64> {
+        reach.iter().zip(&self.hands[p].masks).map(|(&r, &m)| if m & card.mask() != 0 { 0.0 } else { r }).collect()
+    }
+
+    fn zero_blocked(&self, p: usize, values: &mut [f64], card: Card) {
+        for (v, &m) in values.iter_mut().zip(&self.hands[p].masks) {
+            if m & card.mask() != 0 {
+                *v = 0.0;
+            }
+        }
+    }
+
+    /// Counterfactual value of each of `p`'s combos at a terminal
+    fn terminal_values(&self, node_id: NodeId, p: usize, opp: &[f64]) -> Vec<f64> {
+        let own = &self.hands[p];
+        let other = &self.hands[1 - p];
+        let mut total = 0.0;
+        let mut per_card = [0.0f64; 52];
+        for (o, &r) in opp.iter().enumerate() {
+            if r != 0.0 {
+                total += r;
+                per_card[other.cards[o][0].value() as usize] += r;
+                per_card[other.cards[o][1].value() as usize] += r;
+            }
+        }
+        let compatible = |h: usize| {
+            let [a, b] = own.cards[h];
+            total - per_card[a.value() as usize] - per_card[b.value() as usize] + own.same[h].map_or(0.0, |o| opp[o])
+        };
+        let sign = if p == IP { 1.0 } else { -1.0 };
+
+        match self.terminals[node_id as usize].as_ref().expect("terminal was valued") {
+            Terminal::Fold { ip_ev } => (0..own.cards.len()).map(|h| sign * ip_ev * compatible(h)).collect(),
+            Terminal::Showdown { win, lose, tie, table } => {
+                let (p_win, p_lose, p_tie) = payoffs_for(p, *win, *lose, *tie);
+                let cached;
+                let table = match &self.showdowns {
+                    Showdowns::All(tables) => &tables[*table],
+                    Showdowns::Cached(cache) => {
+                        cached = cache.get(*table, &self.hands);
+                        &*cached
+                    }
+                };
+                let sums = showdown_weights(&table[p], &table[1 - p], opp);
+                (0..own.cards.len()).map(|h| p_win * sums.win[h] + p_lose * sums.lose[h] + p_tie * sums.tie[h]).collect()
+            }
+            Terminal::Leaf { win, lose, tie } => {
+                let (p_win, p_lose, p_tie) = payoffs_for(p, *win, *lose, *tie);
+                let Some(Node::Terminal { pot, stacks, board, .. }) = self.tree.get(node_id) else {
+                    unreachable!("leaves are terminals")
+                };
+                let leaf = Leaf {
+                    board,
+                    pot: *pot,
+                    stacks: *stacks,
+                    player: p,
+                    win: p_win,
+                    lose: p_lose,
+                    tie: p_tie,
+                    combos: &own.cards,
+                    opp_combos: &other.cards,
+                    opp_reach: opp,
+                };
+                let values = self.leaf.evaluate(&leaf);
+                assert_eq!(values.len(), own.cards.len(), "leaf evaluator must return one value per combo");
+                values
+            }
+        }
+    }
+}
+
+/// Showdown payoffs from `p`'s side: `p` winning is IP winning only when `p` is IP
+fn payoffs_for(p: usize, win: f64, lose: f64, tie: f64) -> (f64, f64, f64) {
+    if p == IP { (win, lose, tie) } else { (-lose, -win, -tie) }
+}
+
+/// Fold, showdown, and leaf payoffs for every terminal, plus the distinct
+/// river showdown boards (`Terminal::Showdown::table` indexes them)
+fn value_terminals(tree: &GameTree) -> (Vec<Option<Terminal>>, Vec<[Card; 5]>) {
+    let root_stacks = match tree.get(0) {
+        Some(Node::Decision { stacks, .. }) | Some(Node::Chance { stacks, .. }) | Some(Node::Terminal { stacks, .. }) => *stacks,
+        None => return (Vec::new(), Vec::new()),
+    };
+    let mut boards: HashMap<u64, usize> = HashMap::new();
+    let mut tables = Vec::new();
+    let mut terminals: Vec<Option<Terminal>> = (0..tree.len()).map(|_| None).collect();
+
+    for node in &tree.nodes {
+        let Node::Terminal { id, folder, board, .. } = node else { continue };
+        let terminal = match folder {
+            Some(_) => Terminal::Fold { ip_ev: terminal_ip_ev(node, root_stacks, 0.0) },
+            None if board.len() < 5 => Terminal::Leaf {
+                win: terminal_ip_ev(node, root_stacks, 1.0),
+                lose: terminal_ip_ev(node, root_stacks, 0.0),
+                tie: terminal_ip_ev(node, root_stacks, 0.5),
+            },
+            None => {
+                let board: [Card; 5] = board.as_slice().try_into().expect("boards have at most 5 cards");
+                let key = board.iter().fold(0u64, |m, c| m | c.mask());
+                let table = *boards.entry(key).or_insert_with(|| {
+                    tables.push(board);
+                    tables.len() - 1
+                });
+                Terminal::Showdown {
+                    win: terminal_ip_ev(node, root_stacks, 1.0),
+                    lose: terminal_ip_ev(node, root_stacks, 0.0),
+                    tie: terminal_ip_ev(node, root_stacks, 0.5),
+                    table,
+                }
+            }
+        };
+        terminals[*id as usize] = Some(terminal);
+    }
+    (terminals, tables)
+}
+
+/// Sum of weight products over non-conflicting combo pairs
+fn pair_weight(hands: &[Hands; 2]) -> f64 {
+    let mut total = 0.0;
+    for (i, &mi) in hands[IP].masks.iter().enumerate() {
+        for (o, &mo) in hands[OOP].masks.iter().enumerate() {
+            if mi & mo == 0 {
+                total += hands[IP].weights[i] * hands[OOP].weights[o];
+            }
+        }
+    }
+    total
+}
+
+fn rank_table(evaluator: &CactusKevEvaluator, board: [Card; 5], hands: &[Hands; 2]) -> RankTable {
+    [IP, OOP].map(|p| BoardRanks::new(evaluator, board, &hands[p].cards))
+}
+
+/// Chance cards a sampled best response deals (see `SampledBr`)
+#[derive(Debug, Clone, Copy)]
+struct ChanceSample {
+    cards: usize,
+    seed: u64,
+}
+
+impl ChanceSample {
+    /// `cards` of `children`, drawn from a stream seeded by the node so the
+    /// pick doesn't depend on traversal order
+    fn children(&self, node_id: NodeId, children: &[NodeId]) -> Vec<NodeId> {
+        if self.cards >= children.len() {
+            return children.to_vec();
+        }
+        let mut picked = children.to_vec();
+        Rng::new(self.seed ^ (node_id as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)).shuffle(&mut picked);
+        picked.truncate(self.cards);
+        picked
+    }
+}
+
+/// Probability of one chance card given both players' hole cards
+fn chance_weight(children: usize) -> f64 {
+    1.0 / (children as f64 - 4.0)
+}
+
+fn add_scaled(acc: &mut [f64], values: &[f64], scale: f64) {
+    for (a, v) in acc.iter_mut().zip(values) {
+        *a += scale * v;
+    }
+}
+
+fn index(player: Player) -> usize {
+    match player {
+        Player::IP => IP,
+        Player::OOP => OOP,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{build_tree, StreetSizes, TreeConfig};
+    use crate::game::GameConfig;
+    use crate::node::{parse_cards, Action, Street};
+
+    fn river_tree(board: &str) -> GameTree {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards(board).unwrap());
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 2;
+        build_tree(&config)
+    }
+
+    fn ranges(ip: &str, oop: &str) -> [Range; 2] {
+        [Range::parse(ip).unwrap(), Range::parse(oop).unwrap()]
+    }
+
+    /// Pair-by-pair terminal values for comparison with the sweep
+    fn brute_force(solver: &VectorCfrSolver, node: NodeId, p: usize, opp: &[f64]) -> Vec<f64> {
+        let evaluator = CactusKevEvaluator::new();
+        let tree_node = solver.tree.get(node).unwrap();
+        let root_stacks = match solver.tree.get(0).unwrap() {
+            Node::Decision { stacks, .. } => *stacks,
+            _ => unreachable!(),
+        };
+        let board: [Card; 5] = tree_node.board().try_into().unwrap();
+        let own = &solver.hands[p];
+        let other = &solver.hands[1 - p];
+        (0..own.cards.len()).map(|h| {
+            let mut v = 0.0;
+            for (o, &r) in opp.iter().enumerate() {
+                if own.masks[h] & other.masks[o] != 0 {
+                    continue;
+                }
+                let (ip_hand, oop_hand) = if p == IP { (own.cards[h], other.cards[o]) } else { (other.cards[o], own.cards[h]) };
+                let (ri, ro) = (evaluator.evaluate_7cards(board, ip_hand), evaluator.evaluate_7cards(board, oop_hand));
+                let eq = if ri.value() < ro.value() { 1.0 } else if ri == ro { 0.5 } else { 0.0 };
+                let ip_ev = match tree_node {
+                    Node::Terminal { folder: Some(_), .. } => terminal_ip_ev(tree_node, root_stacks, 0.0),
+                    _ => terminal_ip_ev(tree_node, root_stacks, eq),
+                };
+                v += r * if p == IP { ip_ev } else { -ip_ev };
+            }
+            v
+        }).collect()
+    }
+
+    #[test]
+    fn test_terminal_values_match_pairwise_enumeration() {
+        let tree = river_tree("AsKh7d2c9s");
+        let solver = VectorCfrSolver::new(tree, &ranges("AA,KK,77,AK,KQs,T8s,22", "AQ,KQ,99,QJs,AK:0.5,22"));
+        let mut rng = crate::rng::Rng::new(7);
+        for node in solver.tree.nodes.iter().filter(|n| n.is_terminal()) {
+            for p in [IP, OOP] {
+                let opp: Vec<f64> = (0..solver.hands[1 - p].cards.len()).map(|_| rng.next_f64()).collect();
+                let fast = solver.terminal_values(node.id(), p, &opp);
+                let slow = brute_force(&solver, node.id(), p, &opp);
+                for (f, s) in fast.iter().zip(&slow) {
+                    assert!((f - s).abs() < 1e-9, "node {} player {}: {} vs {}", node.id(), p, f, s);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_river_solve_converges_with_per_hand_strategies() {
+        // IP has the nuts (AK) or air (QJ) against OOP's bluff-catcher (KQ)
+        let tree = river_tree("AsKh7d2c9s");
+        let config = SolverConfig::builder().max_iterations(2_000).target_exploitability(0.005).build().unwrap();
+        let mut solver = VectorCfrSolver::with_config(tree, &ranges("AK,QJ", "KQ"), config);
+        let first = solver.exploitability(std::time::Duration::ZERO);
+        let (m, reason) = solver.run(|_| {});
+        assert_eq!(reason, StopReason::ExploitabilityTarget, "exploitability {}", m.exploitability);
+        assert!(m.exploitability < first.exploitability);
+
+        // After OOP checks, IP bets all nuts and only some air
+        let ip_node = solver.tree.get(0).unwrap().children()[0];
+        let Node::Decision { player: Player::IP, actions, .. } = solver.tree.get(ip_node).unwrap() else { panic!() };
+        assert!(matches!(actions[1], Action::Bet { .. }));
+        let strategy = solver.average_strategy(ip_node);
+        let hands = solver.hands(Player::IP);
+        let n = hands.len();
+        let bet_freq = |rank: u8| {
+            let idx: Vec<usize> = (0..n).filter(|&h| hands[h].iter().any(|c| c.rank() == rank)).collect();
+            idx.iter().map(|&h| strategy[n + h]).sum::<f64>() / idx.len() as f64
+        };
+        assert!(bet_freq(12) > 0.95, "AK bets {}", bet_freq(12));
+        let air = bet_freq(10);
+        assert!(air > 0.05 && air < 0.9, "QJ bluffs {}", air);
+
+        // IP's range after betting: every AK combo, the bluffed share of QJ
+        let bet = solver.tree.get(ip_node).unwrap().children()[1];
+        let [betting, oop] = solver.continuing_ranges(bet);
+        assert!((betting.combo_count() - (0..n).map(|h| strategy[n + h]).sum::<f64>()).abs() < 1e-9);
+        let board = solver.tree.get(bet).unwrap().board().to_vec();
+        let value = betting.to_notation(&board, 0.9);
+        assert!(!value.is_empty() && value.split(',').all(|t| t.starts_with('A') && t.contains('K')), "{}", value);
+        assert!((oop.combo_count() - solver.range_strategy(0)[0] * 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pruning_skips_unplayed_actions() {
+        let tree = river_tree("AsKh7d2c9s");
+        let r = ranges("AK,AQ", "QJ,T8s");
+        // Iteration 1,000 is not a revisit
+        let config = SolverConfig::builder().prune(0.0, 128).build().unwrap();
+        let mut pruned = VectorCfrSolver::with_config(tree.clone(), &r, config);
+        let mut full = VectorCfrSolver::new(tree, &r);
+        for _ in 0..1_000 {
+            pruned.run_iteration();
+            full.run_iteration();
+        }
+        let updates = |solver: &VectorCfrSolver| {
+            let mut updates = Vec::new();
+            solver.cfr(0, OOP, &solver.hands[OOP].weights, &solver.hands[IP].weights, &mut updates);
+            updates.len()
+        };
+        assert!(updates(&pruned) < updates(&full), "nothing was pruned");
+        let (p, f) = (pruned.exploitability(std::time::Duration::ZERO), full.exploitability(std::time::Duration::ZERO));
+        assert!((p.exploitability - f.exploitability).abs() < 0.01, "{} vs {}", p.exploitability, f.exploitability);
+    }
+
+    #[test]
+    fn test_single_combo_value_matches_scalar_solver() {
+        // One combo each: vector CFR reduces to the scalar solver with 0/1 equity
+        let tree = river_tree("AsKh7d2c9s");
+        let r = ranges("QhQd", "JhJd");
+        let mut vector = VectorCfrSolver::new(tree.clone(), &r);
+        let evs = crate::game::build_terminal_evs(&tree, |_| 1.0);
+        let mut scalar = crate::cfr::CfrSolver::new_with_evs(tree.clone(), evs.clone());
+        for _ in 0..3_000 {
+            vector.run_iteration();
+            scalar.run_iteration();
+        }
+        let scalar_value = crate::analysis::node_values(&tree, &scalar.storage, &evs)[0];
+        assert!((vector.ip_value() - scalar_value).abs() < 0.05, "{} vs {}", vector.ip_value(), scalar_value);
+        assert!(vector.exploitability(std::time::Duration::ZERO).exploitability < 0.05);
+    }
+
+    #[test]
+    fn test_turn_solve_deals_river_cards() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 1;
+        let tree = build_tree(&config);
+        let mut solver = VectorCfrSolver::new(tree, &ranges("AK,77,QJs", "AQ,KQ,T9s"));
+        let before = solver.exploitability(std::time::Duration::ZERO).exploitability;
+        for _ in 0..200 {
+            solver.run_iteration();
+        }
+        let after = solver.exploitability(std::time::Duration::ZERO);
+        assert!(after.exploitability.is_finite() && after.exploitability < before * 0.2, "{} -> {}", before, after.exploitability);
+        let freqs = solver.combo_frequencies(0);
+        let combo = |s: &str| {
+            let c = parse_cards(s).unwrap();
+            combo_index(c[0], c[1])
+        };
+        assert_eq!(freqs.len(), 2);
+        // OOP acts first: its AQ combos have a full strategy, AK (IP's hand) none
+        assert!((freqs[0][combo("AdQd")] + freqs[1][combo("AdQd")] - 1.0).abs() < 1e-9);
+        assert_eq!(freqs[0][combo("AdKd")] + freqs[1][combo("AdKd")], 0.0);
+    }
+
+    #[test]
+    fn test_sampled_best_response_estimates_exploitability() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 1;
+        let solver_config = SolverConfig::builder().max_iterations(60).check_every(30).no_target().sampled_br(10, 8).build().unwrap();
+        let mut solver = VectorCfrSolver::with_config(build_tree(&config), &ranges("AK,77,QJs", "AQ,KQ,T9s"), solver_config);
+        let mut estimates = Vec::new();
+        let (m, _) = solver.run_with_estimates(|_| {}, |e| estimates.push((e.iterations, e.exploitability)));
+        // Every 10th iteration except the exact checks at 30 and 60
+        assert_eq!(estimates.iter().map(|e| e.0).collect::<Vec<_>>(), [10, 20, 40, 50]);
+
+        let zero = std::time::Duration::ZERO;
+        // Dealing every card is the exact best response
+        let full = solver.sampled_exploitability(48, 1, zero);
+        assert!((full.exploitability - m.exploitability).abs() < 1e-9);
+        let sampled = solver.sampled_exploitability(8, 1, zero);
+        assert_eq!(sampled.exploitability, solver.sampled_exploitability(8, 1, zero).exploitability);
+        assert_ne!(sampled.exploitability, m.exploitability);
+        assert!((sampled.exploitability - m.exploitability).abs() < m.exploitability.abs().max(0.5), "{} vs {}", sampled.exploitability, m.exploitability);
+    }
+
+    #[test]
+    fn test_depth_limited_leaves_match_checked_down_river() {
+        use std::sync::atomic::AtomicUsize;
+
+        // No river bets: the river is always checked down, which is what the equity leaf assumes
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(Vec::new());
+        config.max_raises = 1;
+        let full_tree = build_tree(&config);
+        config.depth_limit = Some(Street::Turn);
+        let limited_tree = build_tree(&config);
+        assert!(limited_tree.len() < full_tree.len());
+
+        let ranges = ranges("AK,77,QJs", "AQ,KQ,T9s");
+        let mut full = VectorCfrSolver::new(full_tree, &ranges);
+        let mut limited = VectorCfrSolver::new(limited_tree.clone(), &ranges);
+        for _ in 0..10 {
+            full.run_iteration();
+            limited.run_iteration();
+        }
+        for (a, b) in full.average_strategy(0).iter().zip(limited.average_strategy(0)) {
+            assert!((a - b).abs() < 1e-9, "{} vs {}", a, b);
+        }
+
+        /// Counts calls and values every leaf at zero
+        struct Counting(AtomicUsize);
+        impl LeafEvaluator for Counting {
+            fn evaluate(&self, leaf: &Leaf<'_>) -> Vec<f64> {
+                assert_eq!(leaf.board.len(), 4);
+                self.0.fetch_add(1, Ordering::Relaxed);
+                vec![0.0; leaf.combos.len()]
+            }
+        }
+        let counting = Arc::new(Counting(AtomicUsize::new(0)));
+        let mut custom = VectorCfrSolver::new(limited_tree, &ranges).with_leaf_evaluator(Arc::clone(&counting));
+        custom.run_iteration();
+        assert!(counting.0.load(Ordering::Relaxed) > 0);
+    }
+
+    #[test]
+    fn test_bounded_showdown_cache_matches_precomputed_tables() {
+        let mut config = TreeConfig::new(GameConfig::new(), parse_cards("AsKh7d2c").unwrap());
+        config.turn = StreetSizes::new(vec![0.5]);
+        config.river = StreetSizes::new(vec![0.75]);
+        config.max_raises = 1;
+        let tree = build_tree(&config);
+        let ranges = ranges("AK,77,QJs", "AQ,KQ,T9s");
+        let mut full = VectorCfrSolver::new(tree.clone(), &ranges);
+        let bounded_config = SolverConfig::builder().showdown_cache(3).build().unwrap();
+        let mut bounded = VectorCfrSolver::with_config(tree, &ranges, bounded_config);
+        assert_eq!(full.showdown_cache_stats(), None);
+        for _ in 0..5 {
+            full.run_iteration();
+            bounded.run_iteration();
+        }
+        let expl = |s: &VectorCfrSolver| s.exploitability(std::time::Duration::ZERO).exploitability;
+        assert_eq!(expl(&full), expl(&bounded));
+        assert_eq!(full.average_strategy(0), bounded.average_strategy(0));
+
+        let stats = bounded.showdown_cache_stats().unwrap();
+        assert_eq!(stats.boards, 48);
+        assert_eq!(stats.resident, 3);
+        // Every iteration revisits all 48 boards, so evicted ones were ranked again
+        assert!(stats.misses > 48 && stats.hits > 0, "{:?}", stats);
+    }
+}
@@ -0,0 +1,660 @@
+//! Golden-solution checks for validating a build
+//!
+//! `verify` solves a small built-in game to a fixed iteration count and
+//! compares the result against values embedded here: the exploitability and
+//! average strategies this engine produced when the values were recorded,
+//! plus, where the game has a known solution, the theoretical game value and
+//! equilibrium frequencies. Run `verify_all` after building on a new machine,
+//! compiler, or target; every check passing means the solver computes what
+//! it did when the goldens were recorded, within each check's tolerance.
+//!
+//! The games:
+//!
+//! - `TestTree`: the 9-node tree from `test_tree`.
+//! - `Kuhn`: Kuhn poker. Three cards (J, Q, K), ante 1, one betting round
+//!   with a bet of 1 and no raises. IP's equilibrium value is +1/18.
+//! - `Leduc`: Leduc hold'em. Six cards (J, Q, K in two suits), ante 1, two
+//!   betting rounds with bets of 2 then 4 and at most one raise per round,
+//!   and one public card dealt between them. A pair with the public card
+//!   beats any unpaired hand, then the higher card wins.
+//!
+//! Kuhn and Leduc are imperfect-information games: their trees deal private
+//! cards at a root chance node and intern one infoset per (player, card,
+//! public history), so `compute_exploitability` — whose best responder
+//! picks the best action node by node — would overstate how exploitable
+//! they are. Their checks use `infoset_exploitability`, a best response that
+//! must play one strategy across each infoset. OOP acts first in every round
+//! of both, and terminal EVs are IP's net winnings, antes included.
+
+use std::time::{Duration, Instant};
+use crate::cfr::{CfrSolver, RegretStorage};
+use crate::ev_table::{EvTable, TerminalEvs};
+use crate::infoset::{InfosetKey, InfosetTable};
+use crate::node::{format_cards, Action, Card, GameTree, Node, NodeId, Player, Street};
+use crate::test_tree::{build_test_tree, terminal_ev_table};
+
+/// A built-in game with golden values (see module docs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldenGame {
+    TestTree,
+    Kuhn,
+    Leduc,
+}
+
+impl GoldenGame {
+    pub const ALL: [GoldenGame; 3] = [GoldenGame::TestTree, GoldenGame::Kuhn, GoldenGame::Leduc];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GoldenGame::TestTree => "test-tree",
+            GoldenGame::Kuhn => "kuhn",
+            GoldenGame::Leduc => "leduc",
+        }
+    }
+
+    /// The game's tree and terminal EVs
+    pub fn build(self) -> (GameTree, EvTable) {
+        let (tree, evs, _) = self.build_with_infosets();
+        (tree, evs)
+    }
+
+    fn build_with_infosets(self) -> (GameTree, EvTable, InfosetTable) {
+        match self {
+            GoldenGame::TestTree => {
+                let tree = build_test_tree();
+                let infosets = InfosetTable::from_tree(&tree);
+                (tree, EvTable::from(terminal_ev_table()), infosets)
+            }
+            GoldenGame::Kuhn => KUHN.build(),
+            GoldenGame::Leduc => LEDUC.build(),
+        }
+    }
+
+    /// Iterations the goldens were recorded at
+    pub fn iterations(self) -> u64 {
+        match self {
+            GoldenGame::TestTree => 5_000,
+            GoldenGame::Kuhn => 5_000,
+            GoldenGame::Leduc => 1_000,
+        }
+    }
+}
+
+/// One quantity compared against its expected value
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenCheck {
+    pub name: String,
+    pub expected: f64,
+    pub actual: f64,
+    pub tolerance: f64,
+}
+
+impl GoldenCheck {
+    fn new(name: impl Into<String>, expected: f64, actual: f64, tolerance: f64) -> Self {
+        GoldenCheck { name: name.into(), expected, actual, tolerance }
+    }
+
+    /// Within tolerance (NaN never is)
+    pub fn passed(&self) -> bool {
+        (self.actual - self.expected).abs() <= self.tolerance
+    }
+}
+
+impl std::fmt::Display for GoldenCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}: expected {:.9}, got {:.9} (tolerance {:e})",
+            if self.passed() { "ok  " } else { "FAIL" },
+            self.name,
+            self.expected,
+            self.actual,
+            self.tolerance,
+        )
+    }
+}
+
+/// The result of verifying one game
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationReport {
+    pub game: GoldenGame,
+    pub iterations: u64,
+    pub exploitability: f64,
+    pub checks: Vec<GoldenCheck>,
+    pub elapsed: Duration,
+}
+
+impl VerificationReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(GoldenCheck::passed)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &GoldenCheck> {
+        self.checks.iter().filter(|c| !c.passed())
+    }
+}
+
+/// Values recorded from this engine: exploitability, and the average
+/// strategy at a few infosets
+struct Golden {
+    exploitability: f64,
+    strategies: &'static [(usize, &'static [f64])],
+}
+
+/// Recorded values are deterministic, so any drift beyond rounding is a fault
+const RECORDED_TOLERANCE: f64 = 1e-9;
+
+const TEST_TREE_GOLDEN: Golden = Golden {
+    exploitability: 2.799440159684252e-7,
+    strategies: &[
+        (0, &[3.9992001599680065e-8, 0.9999999600079984]),
+        (1, &[3.9992001599680065e-8, 0.9999999600079984]),
+        (2, &[3.9992001599680065e-8, 0.9999999600079984]),
+        (3, &[3.9992001599680065e-8, 0.9999999600079984]),
+    ],
+};
+
+const KUHN_GOLDEN: Golden = Golden {
+    exploitability: 0.0021515117774557058,
+    strategies: &[
+        (0, &[0.9999986310380159, 1.3689619841830477e-6]),
+        (1, &[0.6649177189788215, 0.3350822810211786]),
+        (2, &[0.41903714756422944, 0.5809628524357705]),
+        (3, &[0.9999999600079984, 3.9992001599680065e-8]),
+        (4, &[0.25726025887161325, 0.7427397411283868]),
+        (5, &[3.9992001599680065e-8, 0.9999999600079984]),
+        (6, &[0.7526831518058851, 0.24731684819411495]),
+        (7, &[0.9999994702183158, 5.297816841126157e-7]),
+        (8, &[0.9999999600079984, 3.9992001599680065e-8]),
+        (9, &[0.6558198983293344, 0.34418010167066565]),
+        (10, &[3.9992001599680065e-8, 0.9999999600079984]),
+        (11, &[3.9992001599680065e-8, 0.9999999600079984]),
+    ],
+};
+
+const LEDUC_GOLDEN: Golden = Golden {
+    exploitability: 0.020749333149887556,
+    strategies: &[
+        (0, &[0.23386717308231225, 0.7661328269176877]),
+        (1, &[0.6962260687871188, 0.3037739312128813]),
+        (2, &[0.6551450387275574, 0.3448549612724427]),
+        (4, &[0.0008882420356997766, 0.7079385067600081, 0.29117325120429205]),
+        (7, &[0.5340161700955753, 0.46598382990442466]),
+        (8, &[0.9930906676740215, 0.006909332325978469]),
+    ],
+};
+
+/// Solve `game` with a default `CfrSolver` for `game.iterations()`
+/// iterations and compare the result against its goldens
+pub fn verify(game: GoldenGame) -> VerificationReport {
+    let start = Instant::now();
+    let (tree, evs, infosets) = game.build_with_infosets();
+    let mut solver = CfrSolver::new_with_evs(tree, evs);
+    for _ in 0..game.iterations() {
+        solver.run_iteration();
+    }
+    let (tree, storage, evs) = (&solver.tree, &solver.storage, solver.terminal_evs());
+    let exploitability = infoset_exploitability(tree, storage, evs);
+
+    let golden = match game {
+        GoldenGame::TestTree => &TEST_TREE_GOLDEN,
+        GoldenGame::Kuhn => &KUHN_GOLDEN,
+        GoldenGame::Leduc => &LEDUC_GOLDEN,
+    };
+    let mut checks = vec![GoldenCheck::new("exploitability", golden.exploitability, exploitability, RECORDED_TOLERANCE)];
+    for &(infoset, expected) in golden.strategies {
+        let actual = storage.average_strategy(infoset);
+        for (a, (&e, &x)) in expected.iter().zip(&actual).enumerate() {
+            let name = format!("strategy {} / {}", describe(infosets.key(infoset as _)), tree_action(tree, infoset, a));
+            checks.push(GoldenCheck::new(name, e, x, RECORDED_TOLERANCE));
+        }
+    }
+
+    // Known solutions, to a tolerance the iteration count reaches
+    let value = crate::analysis::node_values(tree, storage, evs)[0];
+    let mut theory = |name: &str, history: Vec<Action>, rank: u8, action: usize, expected: f64, tolerance: f64| {
+        let infoset = infosets.get(&InfosetKey::new(Player::IP, history, Vec::new(), u32::from(rank)))
+            .expect("golden infoset exists");
+        let actual = storage.average_strategy(infoset as usize)[action];
+        checks.push(GoldenCheck::new(format!("equilibrium {}", name), expected, actual, tolerance));
+    };
+    match game {
+        GoldenGame::TestTree => {}
+        GoldenGame::Kuhn => {
+            let bet = Action::Bet { size: 1.0 };
+            theory("IP K bets after a check", vec![Action::Check], RANKS[2], 1, 1.0, 0.01);
+            theory("IP Q checks behind", vec![Action::Check], RANKS[1], 0, 1.0, 0.01);
+            theory("IP J bluffs after a check", vec![Action::Check], RANKS[0], 1, 1.0 / 3.0, 0.02);
+            theory("IP K calls a bet", vec![bet], RANKS[2], 1, 1.0, 0.01);
+            theory("IP Q calls a bet", vec![bet], RANKS[1], 1, 1.0 / 3.0, 0.02);
+            theory("IP J folds to a bet", vec![bet], RANKS[0], 0, 1.0, 0.01);
+            checks.push(GoldenCheck::new("game value", 1.0 / 18.0, value, 1e-3));
+        }
+        GoldenGame::Leduc => checks.push(GoldenCheck::new("game value", LEDUC_VALUE, value, 5e-3)),
+    }
+
+    VerificationReport { game, iterations: solver.iteration, exploitability, checks, elapsed: start.elapsed() }
+}
+
+/// `verify` every built-in game
+pub fn verify_all() -> Vec<VerificationReport> {
+    GoldenGame::ALL.iter().map(|&game| verify(game)).collect()
+}
+
+/// IP's equilibrium value in Leduc hold'em, to four places
+const LEDUC_VALUE: f64 = 0.0856;
+
+fn describe(key: &InfosetKey) -> String {
+    let mut out = format!("{:?}", key.player);
+    if let Some(i) = RANKS.iter().position(|&r| u32::from(r) == key.bucket) {
+        out.push(' ');
+        out.push(['J', 'Q', 'K'][i]);
+    }
+    if !key.board.is_empty() {
+        out.push_str(&format!(" [{}]", format_cards(&key.board)));
+    }
+    for action in &key.history {
+        out.push_str(&format!(" {}", action));
+    }
+    out
+}
+
+fn tree_action(tree: &GameTree, infoset: usize, action: usize) -> Action {
+    tree.nodes.iter()
+        .find_map(|n| match n {
+            Node::Decision { infoset_id, actions, .. } if *infoset_id as usize == infoset => Some(actions[action]),
+            _ => None,
+        })
+        .expect("golden infoset is in the tree")
+}
+
+/// Kuhn poker (see module docs)
+pub fn kuhn_tree() -> (GameTree, EvTable) {
+    GoldenGame::Kuhn.build()
+}
+
+/// Leduc hold'em (see module docs)
+pub fn leduc_tree() -> (GameTree, EvTable) {
+    GoldenGame::Leduc.build()
+}
+
+/// Jack, queen, king
+const RANKS: [u8; 3] = [9, 10, 11];
+const ANTE: f64 = 1.0;
+/// Nominal starting stack; never binding
+const STACK: f64 = 100.0;
+
+/// A one-card poker game over `RANKS` in `suits` suits, with a public card
+/// dealt between consecutive betting rounds
+struct ToyPoker {
+    suits: u8,
+    /// (bet size, max bets and raises) per round
+    rounds: &'static [(f64, u32)],
+}
+
+const KUHN: ToyPoker = ToyPoker { suits: 1, rounds: &[(1.0, 1)] };
+const LEDUC: ToyPoker = ToyPoker { suits: 2, rounds: &[(2.0, 2), (4.0, 2)] };
+
+/// Where a hand stands while its tree is built
+#[derive(Clone)]
+struct Hand {
+    /// [IP, OOP] private cards
+    cards: [Card; 2],
+    board: Vec<Card>,
+    round: usize,
+    history: Vec<Action>,
+    /// Actions this round
+    round_actions: usize,
+    /// Bets and raises this round
+    bets: u32,
+    /// [IP, OOP] chips in the pot, antes included
+    invested: [f64; 2],
+    to_act: Player,
+}
+
+impl Hand {
+    fn pot(&self) -> f64 {
+        self.invested[0] + self.invested[1]
+    }
+
+    fn stacks(&self) -> [f64; 2] {
+        [STACK - self.invested[0], STACK - self.invested[1]]
+    }
+
+    fn street(&self, rounds: usize) -> Street {
+        if self.round + 1 == rounds { Street::River } else { Street::Turn }
+    }
+}
+
+struct Builder<'a> {
+    game: &'a ToyPoker,
+    nodes: Vec<Node>,
+    evs: EvTable,
+    infosets: InfosetTable,
+}
+
+impl ToyPoker {
+    fn deck(&self) -> Vec<Card> {
+        (0..self.suits).flat_map(|s| RANKS.iter().map(move |&r| Card::from_rank_suit(r, s))).collect()
+    }
+
+    fn build(&self) -> (GameTree, EvTable, InfosetTable) {
+        let mut b = Builder { game: self, nodes: Vec::new(), evs: EvTable::new(), infosets: InfosetTable::new() };
+        let deck = self.deck();
+        let root = b.push(Node::Chance {
+            id: 0,
+            parent: None,
+            children: Vec::new(),
+            weights: Vec::new(),
+            street: Street::Turn,
+            pot: 2.0 * ANTE,
+            stacks: [STACK - ANTE; 2],
+            board: Vec::new(),
+        });
+        let mut children = Vec::new();
+        for &ip in &deck {
+            for &oop in deck.iter().filter(|&&c| c != ip) {
+                let hand = Hand {
+                    cards: [ip, oop],
+                    board: Vec::new(),
+                    round: 0,
+                    history: Vec::new(),
+                    round_actions: 0,
+                    bets: 0,
+                    invested: [ANTE; 2],
+                    to_act: Player::OOP,
+                };
+                children.push(b.decision(hand, root));
+            }
+        }
+        b.set_children(root, children);
+        (GameTree { nodes: b.nodes }, b.evs, b.infosets)
+    }
+}
+
+impl Builder<'_> {
+    fn push(&mut self, node: Node) -> NodeId {
+        self.nodes.push(node);
+        (self.nodes.len() - 1) as NodeId
+    }
+
+    fn set_children(&mut self, id: NodeId, new: Vec<NodeId>) {
+        match &mut self.nodes[id as usize] {
+            Node::Decision { children, .. } | Node::Chance { children, .. } => *children = new,
+            Node::Terminal { .. } => unreachable!("terminals have no children"),
+        }
+    }
+
+    fn decision(&mut self, hand: Hand, parent: NodeId) -> NodeId {
+        let (bet, max_bets) = self.game.rounds[hand.round];
+        let me = hand.to_act as usize;
+        let facing = hand.invested[1 - me] > hand.invested[me];
+        let actions = if !facing {
+            vec![Action::Check, Action::Bet { size: bet }]
+        } else if hand.bets < max_bets {
+            vec![Action::Fold, Action::Call, Action::Raise { size: bet * f64::from(hand.bets + 1) }]
+        } else {
+            vec![Action::Fold, Action::Call]
+        };
+        let key = InfosetKey::new(hand.to_act, hand.history.clone(), hand.board.clone(), u32::from(hand.cards[me].rank()));
+        let infoset_id = self.infosets.intern(key, actions.len());
+        let id = self.push(Node::Decision {
+            id: self.nodes.len() as NodeId,
+            infoset_id,
+            player: hand.to_act,
+            street: hand.street(self.game.rounds.len()),
+            parent: Some(parent),
+            children: Vec::new(),
+            actions: actions.clone(),
+            pot: hand.pot(),
+            stacks: hand.stacks(),
+            board: hand.board.clone(),
+            bet_sequence: hand.history.clone(),
+        });
+
+        let mut children = Vec::with_capacity(actions.len());
+        for &action in &actions {
+            let mut next = hand.clone();
+            next.history.push(action);
+            next.round_actions += 1;
+            next.to_act = hand.to_act.opponent();
+            let child = match action {
+                Action::Fold => self.terminal(&next, Some(hand.to_act), id),
+                Action::Check if hand.round_actions == 0 => self.decision(next, id),
+                Action::Check => self.end_round(next, id),
+                Action::Call => {
+                    next.invested[me] = hand.invested[1 - me];
+                    self.end_round(next, id)
+                }
+                Action::Bet { .. } | Action::Raise { .. } => {
+                    next.invested[me] = hand.invested[1 - me] + bet;
+                    next.bets += 1;
+                    self.decision(next, id)
+                }
+            };
+            children.push(child);
+        }
+        self.set_children(id, children);
+        id
+    }
+
+    /// Deal the next public card, or show down after the last round
+    fn end_round(&mut self, hand: Hand, parent: NodeId) -> NodeId {
+        if hand.round + 1 == self.game.rounds.len() {
+            return self.terminal(&hand, None, parent);
+        }
+        let id = self.push(Node::Chance {
+            id: self.nodes.len() as NodeId,
+            parent: Some(parent),
+            children: Vec::new(),
+            weights: Vec::new(),
+            street: hand.street(self.game.rounds.len()),
+            pot: hand.pot(),
+            stacks: hand.stacks(),
+            board: hand.board.clone(),
+        });
+        let remaining: Vec<Card> = self.game.deck().into_iter()
+            .filter(|c| !hand.cards.contains(c) && !hand.board.contains(c))
+            .collect();
+        let children = remaining.into_iter().map(|card| {
+            let mut next = hand.clone();
+            next.board.push(card);
+            next.round += 1;
+            next.round_actions = 0;
+            next.bets = 0;
+            next.to_act = Player::OOP;
+            self.decision(next, id)
+        }).collect();
+        self.set_children(id, children);
+        id
+    }
+
+    fn terminal(&mut self, hand: &Hand, folder: Option<Player>, parent: NodeId) -> NodeId {
+        let id = self.nodes.len() as NodeId;
+        let ev = match folder {
+            Some(Player::IP) => -hand.invested[0],
+            Some(Player::OOP) => hand.invested[1],
+            None => match showdown_strength(hand.cards[0], &hand.board).cmp(&showdown_strength(hand.cards[1], &hand.board)) {
+                std::cmp::Ordering::Greater => hand.invested[1],
+                std::cmp::Ordering::Less => -hand.invested[0],
+                std::cmp::Ordering::Equal => 0.0,
+            },
+        };
+        self.evs.insert(id, ev);
+        self.push(Node::Terminal {
+            id,
+            parent: Some(parent),
+            folder,
+            pot: hand.pot(),
+            stacks: hand.stacks(),
+            board: hand.board.clone(),
+            hole_cards: [None, None],
+        })
+    }
+}
+
+/// Pairing the board beats any high card
+fn showdown_strength(card: Card, board: &[Card]) -> (bool, u8) {
+    (board.iter().any(|b| b.rank() == card.rank()), card.rank())
+}
+
+/// Exploitability of the average strategy in `storage` against best
+/// responses that play one action per infoset (see module docs)
+///
+/// Returns the sum of both players' best-response values, as
+/// `ConvergenceMetrics::exploitability` does; 0 at a Nash equilibrium.
+pub fn infoset_exploitability(tree: &GameTree, storage: &RegretStorage, terminal_evs: &impl TerminalEvs) -> f64 {
+    infoset_best_response(tree, storage, terminal_evs, Player::IP)
+        + infoset_best_response(tree, storage, terminal_evs, Player::OOP)
+}
+
+/// `player`'s value (from their side) when best-responding infoset by
+/// infoset to the opponent's average strategy
+pub fn infoset_best_response(
+    tree: &GameTree,
+    storage: &RegretStorage,
+    terminal_evs: &impl TerminalEvs,
+    player: Player,
+) -> f64 {
+    if tree.is_empty() {
+        return 0.0;
+    }
+    // Probability the opponent and chance reach each node, and its depth
+    let mut reach = vec![0.0; tree.len()];
+    let mut depth = vec![0usize; tree.len()];
+    let mut stack = vec![(0 as NodeId, 1.0, 0)];
+    while let Some((id, r, d)) = stack.pop() {
+        reach[id as usize] = r;
+        depth[id as usize] = d;
+        match tree.get(id).expect("invalid node id") {
+            Node::Decision { children, infoset_id, player: actor, .. } if *actor != player => {
+                let probs = storage.average_strategy(*infoset_id as usize);
+                stack.extend(children.iter().zip(&probs).map(|(&c, &p)| (c, r * p, d + 1)));
+            }
+            Node::Decision { children, .. } => stack.extend(children.iter().map(|&c| (c, r, d + 1))),
+            node @ Node::Chance { children, .. } => {
+                stack.extend(children.iter().enumerate().map(|(i, &c)| (c, r * node.chance_probability(i), d + 1)));
+            }
+            Node::Terminal { .. } => {}
+        }
+    }
+
+    // The responder's infosets, deepest first: every infoset below one is
+    // decided before it is
+    let mut members: Vec<Vec<NodeId>> = vec![Vec::new(); tree.num_infosets()];
+    for node in &tree.nodes {
+        if let Node::Decision { id, infoset_id, player: actor, .. } = node {
+            if *actor == player {
+                members[*infoset_id as usize].push(*id);
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..members.len()).filter(|&i| !members[i].is_empty()).collect();
+    let deepest = |i: usize| members[i].iter().map(|&n| depth[n as usize]).max().unwrap_or(0);
+    order.sort_by_key(|&i| std::cmp::Reverse(deepest(i)));
+
+    let mut choice: Vec<Option<usize>> = vec![None; members.len()];
+    let sign = if player == Player::IP { 1.0 } else { -1.0 };
+    for infoset in order {
+        let mut totals: Vec<f64> = Vec::new();
+        for &id in &members[infoset] {
+            let node = tree.get(id).expect("invalid node id");
+            totals.resize(node.children().len(), 0.0);
+            for (total, &child) in totals.iter_mut().zip(node.children()) {
+                *total += reach[id as usize] * response_value(tree, storage, terminal_evs, player, &choice, child);
+            }
+        }
+        let best = (0..totals.len()).fold(0, |best, a| if sign * totals[a] > sign * totals[best] { a } else { best });
+        choice[infoset] = Some(best);
+    }
+    sign * response_value(tree, storage, terminal_evs, player, &choice, 0)
+}
+
+/// IP's EV at `node_id` with the responder playing `choice` and the
+/// opponent their average strategy
+fn response_value(
+    tree: &GameTree,
+    storage: &RegretStorage,
+    terminal_evs: &impl TerminalEvs,
+    player: Player,
+    choice: &[Option<usize>],
+    node_id: NodeId,
+) -> f64 {
+    match tree.get(node_id).expect("invalid node id") {
+        Node::Terminal { .. } => terminal_evs.terminal_ev(node_id),
+        Node::Decision { children, infoset_id, player: actor, .. } if *actor == player => {
+            let action = choice[*infoset_id as usize].expect("responder infosets are decided deepest first");
+            response_value(tree, storage, terminal_evs, player, choice, children[action])
+        }
+        Node::Decision { children, infoset_id, .. } => {
+            let probs = storage.average_strategy(*infoset_id as usize);
+            children.iter().zip(&probs)
+                .map(|(&child, &p)| p * response_value(tree, storage, terminal_evs, player, choice, child))
+                .sum()
+        }
+        node @ Node::Chance { children, .. } => children.iter().enumerate()
+            .map(|(i, &child)| node.chance_probability(i) * response_value(tree, storage, terminal_evs, player, choice, child))
+            .sum(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exploitability::compute_exploitability_with_evs;
+
+    #[test]
+    fn test_toy_games_are_well_formed() {
+        let (kuhn, evs) = kuhn_tree();
+        // 6 deals x (check-check, check-bet-fold, check-bet-call, bet-fold, bet-call)
+        assert_eq!(kuhn.nodes.iter().filter(|n| n.is_terminal()).count(), 30);
+        assert_eq!(kuhn.num_infosets(), 12);
+        assert_eq!(evs.len(), 30);
+        // Zero-sum and symmetric: IP's EV over all deals and lines is 0 when
+        // every line is weighted equally
+        assert_eq!(evs.iter().map(|(_, ev)| ev).sum::<f64>(), 0.0);
+
+        let (leduc, evs) = leduc_tree();
+        assert_eq!(leduc.num_infosets(), 558);
+        assert!(evs.iter().all(|(id, ev)| leduc.get(id).unwrap().is_terminal() && ev.abs() <= 13.0));
+        assert!(leduc.nodes.iter().enumerate().all(|(i, n)| n.id() as usize == i));
+    }
+
+    #[test]
+    fn test_infoset_best_response_matches_per_node_on_perfect_information() {
+        let mut solver = CfrSolver::new(build_test_tree());
+        for _ in 0..50 {
+            solver.run_iteration();
+        }
+        let evs = terminal_ev_table();
+        let per_node = compute_exploitability_with_evs(&solver.tree, &solver.storage, &evs, 50, Duration::ZERO);
+        assert!((infoset_best_response(&solver.tree, &solver.storage, &evs, Player::IP) - per_node.ip_br_value).abs() < 1e-12);
+        assert!((infoset_best_response(&solver.tree, &solver.storage, &evs, Player::OOP) - per_node.oop_br_value).abs() < 1e-12);
+
+        // With shared infosets the per-node responder sees through them.
+        // Uniform play in Kuhn poker is exploitable for 11/12 in total
+        let (kuhn, evs) = kuhn_tree();
+        let uniform = RegretStorage::for_tree(&kuhn);
+        let per_node = compute_exploitability_with_evs(&kuhn, &uniform, &evs, 0, Duration::ZERO);
+        let exact = infoset_exploitability(&kuhn, &uniform, &evs);
+        assert!((exact - 11.0 / 12.0).abs() < 1e-12);
+        assert!(per_node.exploitability > exact + 0.05);
+    }
+
+    #[test]
+    fn test_verify_all_passes() {
+        for report in verify_all() {
+            let failures: Vec<String> = report.failures().map(|c| c.to_string()).collect();
+            assert!(report.passed(), "{}: {:?}", report.game.name(), failures);
+            assert_eq!(report.iterations, report.game.iterations());
+        }
+    }
+
+    #[test]
+    fn test_check_tolerance() {
+        assert!(GoldenCheck::new("x", 1.0, 1.0 + 1e-10, 1e-9).passed());
+        assert!(!GoldenCheck::new("x", 1.0, 1.1, 1e-9).passed());
+        assert!(!GoldenCheck::new("x", 1.0, f64::NAN, 1e-9).passed());
+    }
+}
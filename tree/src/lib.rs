@@ -1,13 +1,11 @@
 //! oracle Tree Builder - Game tree construction module
 //!
-//! This crate is responsible for building game trees from game parameters
-//! (bet sizes, stack depth, etc.) and returning them to the engine.
+//! This crate is the public entry point for building game trees from game
+//! parameters (blinds, antes, straddles, bet sizes, stack depth).
 //!
-//! In Phase 0, this is a placeholder that will be expanded in Phase 3.
+//! The builder itself lives in `oracle_engine::builder` so the engine's
+//! high-level solve API can construct trees without a dependency cycle;
+//! this crate re-exports it.
 
-use oracle_engine::node;
-
-/// Placeholder for tree building functionality
-pub fn build_tree() -> Vec<node::Node> {
-    Vec::new()
-}
+pub use oracle_engine::builder::{build_tree, BettingStructure, StreetSizes, TreeConfig, TreeConfigError};
+pub use oracle_engine::game::{GameConfig, GameConfigError, PostedBlind};